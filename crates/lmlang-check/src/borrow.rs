@@ -0,0 +1,820 @@
+//! Ownership and borrow checking for pointers and closure captures.
+//!
+//! Pointers are traced back to the node they originate from: an `Alloc`, a
+//! pointer-typed `Parameter`, or a `CaptureAccess`, followed through
+//! `GetElementPtr` and `Phi`. Three kinds of violation are reported:
+//!
+//! - [`BorrowError::AliasedMutableBorrow`]: one `Call`, `IndirectCall` or
+//!   `MakeClosure` receives a mutable borrow of some memory together with
+//!   any other borrow of it, through pointer arguments, by-reference
+//!   captures, or closures that captured it.
+//! - [`BorrowError::StoreThroughImmutable`]: a `Store` writes through a
+//!   pointer typed `Pointer { mutable: false }`, a read-only pointer
+//!   parameter, or a `ByRef` capture.
+//! - [`BorrowError::DanglingCapture`]: a closure captures an `Alloc` of its
+//!   creating function by reference and can outlive it, by being returned,
+//!   stored outside that function's own allocations, or passed to a callee
+//!   parameter that escapes in turn.
+//!
+//! The analysis is conservative in what it follows, not in what it reports:
+//! pointers whose type does not say they are pointers, pointers loaded from
+//! memory and closures called indirectly are not tracked, so every reported
+//! violation is backed by a data path in the graph.
+
+use std::collections::{HashSet, VecDeque};
+
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+
+use lmlang_core::edge::FlowEdge;
+use lmlang_core::function::{Capture, CaptureMode};
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::ops::{ComputeNodeOp, ComputeOp, StructuredOp};
+use lmlang_core::type_id::TypeId;
+use lmlang_core::types::LmType;
+
+/// A borrow violation found by [`check_borrows`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum BorrowError {
+    /// Memory is borrowed mutably and borrowed again by the same node.
+    #[error(
+        "node {node} in function '{function_name}' borrows the memory of node {origin} mutably on port {mutable_port} while also borrowing it on port {other_port}"
+    )]
+    AliasedMutableBorrow {
+        function_id: FunctionId,
+        function_name: String,
+        /// The call or closure receiving both borrows.
+        node: NodeId,
+        /// Where the borrowed memory comes from.
+        origin: NodeId,
+        /// Input port carrying the mutable borrow.
+        mutable_port: u16,
+        /// Input port carrying the other borrow.
+        other_port: u16,
+    },
+
+    /// A `Store` writes through a read-only pointer.
+    #[error(
+        "store at node {node} in function '{function_name}' writes through a read-only pointer from node {pointer}"
+    )]
+    StoreThroughImmutable {
+        function_id: FunctionId,
+        function_name: String,
+        /// The `Store`.
+        node: NodeId,
+        /// The node producing the pointer.
+        pointer: NodeId,
+    },
+
+    /// A closure borrowing a local allocation escapes its creating function.
+    #[error(
+        "closure created at node {node} in function '{function_name}' captures allocation {alloc} by reference (capture {capture}) and escapes through node {escape}"
+    )]
+    DanglingCapture {
+        function_id: FunctionId,
+        function_name: String,
+        /// The `MakeClosure`.
+        node: NodeId,
+        /// Index of the by-reference capture.
+        capture: u32,
+        /// The captured `Alloc`.
+        alloc: NodeId,
+        /// Where the closure leaves the function: a `Return`, `Store` or `Call`.
+        escape: NodeId,
+    },
+}
+
+/// Checks every function for aliased mutable borrows, stores through
+/// read-only pointers and escaping by-reference captures, sorted by function
+/// and node.
+pub fn check_borrows(graph: &ProgramGraph) -> Vec<BorrowError> {
+    let mut functions: Vec<FunctionId> = graph.functions().keys().copied().collect();
+    functions.sort_by_key(|f| f.0);
+
+    let mut errors = Vec::new();
+    for func_id in functions {
+        let view = BorrowView::new(graph, func_id);
+        let function_name = graph
+            .get_function(func_id)
+            .map(|f| f.name.clone())
+            .unwrap_or_default();
+        for node in graph.function_nodes_sorted(func_id) {
+            for found in view.check_node(node) {
+                errors.push(found.into_error(func_id, function_name.clone(), node));
+            }
+        }
+    }
+    errors
+}
+
+/// A violation at one node, before the function is attached.
+enum Found {
+    Aliased {
+        origin: NodeId,
+        mutable_port: u16,
+        other_port: u16,
+    },
+    ReadOnlyStore {
+        pointer: NodeId,
+    },
+    Dangling {
+        capture: u32,
+        alloc: NodeId,
+        escape: NodeId,
+    },
+}
+
+impl Found {
+    fn into_error(
+        self,
+        function_id: FunctionId,
+        function_name: String,
+        node: NodeId,
+    ) -> BorrowError {
+        match self {
+            Found::Aliased {
+                origin,
+                mutable_port,
+                other_port,
+            } => BorrowError::AliasedMutableBorrow {
+                function_id,
+                function_name,
+                node,
+                origin,
+                mutable_port,
+                other_port,
+            },
+            Found::ReadOnlyStore { pointer } => BorrowError::StoreThroughImmutable {
+                function_id,
+                function_name,
+                node,
+                pointer,
+            },
+            Found::Dangling {
+                capture,
+                alloc,
+                escape,
+            } => BorrowError::DanglingCapture {
+                function_id,
+                function_name,
+                node,
+                capture,
+                alloc,
+                escape,
+            },
+        }
+    }
+}
+
+/// One borrow of the memory at `origin`, received on `port`.
+#[derive(Debug, Clone, Copy)]
+struct Borrow {
+    port: u16,
+    origin: NodeId,
+    mutable: bool,
+}
+
+struct BorrowView<'g> {
+    graph: &'g ProgramGraph,
+    func_id: FunctionId,
+}
+
+impl<'g> BorrowView<'g> {
+    fn new(graph: &'g ProgramGraph, func_id: FunctionId) -> Self {
+        BorrowView { graph, func_id }
+    }
+
+    fn op(&self, node: NodeId) -> Option<&'g ComputeNodeOp> {
+        self.graph.get_compute_node(node).map(|n| &n.op)
+    }
+
+    /// Incoming data edges of `node`: `(target_port, source, value_type)`,
+    /// sorted by port.
+    fn inputs(&self, node: NodeId) -> Vec<(u16, NodeId, TypeId)> {
+        let mut inputs: Vec<(u16, NodeId, TypeId)> = self
+            .graph
+            .compute()
+            .edges_directed(node.into(), Direction::Incoming)
+            .filter_map(|edge| match edge.weight() {
+                FlowEdge::Data {
+                    target_port,
+                    value_type,
+                    ..
+                } => Some((*target_port, NodeId::from(edge.source()), *value_type)),
+                FlowEdge::Control { .. } => None,
+            })
+            .collect();
+        inputs.sort_by_key(|(port, _, _)| *port);
+        inputs
+    }
+
+    fn input(&self, node: NodeId, port: u16) -> Option<(NodeId, TypeId)> {
+        self.inputs(node)
+            .into_iter()
+            .find(|(p, _, _)| *p == port)
+            .map(|(_, source, ty)| (source, ty))
+    }
+
+    /// Data successors of `node` in this function: `(target, target_port)`.
+    fn uses(&self, node: NodeId) -> Vec<(NodeId, u16)> {
+        self.graph
+            .compute()
+            .edges_directed(node.into(), Direction::Outgoing)
+            .filter_map(|edge| match edge.weight() {
+                FlowEdge::Data { target_port, .. } => {
+                    Some((NodeId::from(edge.target()), *target_port))
+                }
+                FlowEdge::Control { .. } => None,
+            })
+            .filter(|(target, _)| {
+                self.graph
+                    .get_compute_node(*target)
+                    .is_some_and(|n| n.owner == self.func_id)
+            })
+            .collect()
+    }
+
+    /// `Some(mutable)` if `ty` is a pointer type.
+    fn pointer(&self, ty: TypeId) -> Option<bool> {
+        match self.graph.types.get(ty) {
+            Some(LmType::Pointer { mutable, .. }) => Some(*mutable),
+            _ => None,
+        }
+    }
+
+    fn param_type(&self, func_id: FunctionId, index: usize) -> Option<TypeId> {
+        self.graph
+            .get_function(func_id)?
+            .params
+            .get(index)
+            .map(|(_, ty)| *ty)
+    }
+
+    fn capture(&self, index: u32) -> Option<&'g Capture> {
+        self.graph
+            .get_function(self.func_id)?
+            .captures
+            .get(index as usize)
+    }
+
+    /// Nodes the pointer produced by `node` may originate from, sorted by ID.
+    fn origins(&self, node: NodeId) -> Vec<NodeId> {
+        let mut origins = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([node]);
+        while let Some(node) = queue.pop_front() {
+            if !seen.insert(node) {
+                continue;
+            }
+            match self.op(node) {
+                Some(ComputeNodeOp::Core(ComputeOp::Alloc)) => origins.push(node),
+                Some(ComputeNodeOp::Core(ComputeOp::Parameter { index }))
+                    if self
+                        .param_type(self.func_id, *index as usize)
+                        .and_then(|ty| self.pointer(ty))
+                        .is_some() =>
+                {
+                    origins.push(node)
+                }
+                Some(ComputeNodeOp::Core(ComputeOp::CaptureAccess { index }))
+                    if self.capture(*index).is_some_and(|c| {
+                        c.mode != CaptureMode::ByValue || self.pointer(c.captured_type).is_some()
+                    }) =>
+                {
+                    origins.push(node)
+                }
+                Some(ComputeNodeOp::Core(ComputeOp::GetElementPtr)) => {
+                    queue.extend(self.input(node, 0).map(|(source, _)| source));
+                }
+                Some(ComputeNodeOp::Core(ComputeOp::Phi)) => {
+                    queue.extend(self.inputs(node).into_iter().map(|(_, source, _)| source));
+                }
+                _ => {}
+            }
+        }
+        origins.sort_by_key(|n| n.0);
+        origins
+    }
+
+    /// Whether the pointer produced by `node` may not be written through.
+    fn read_only(&self, node: NodeId) -> bool {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([node]);
+        while let Some(node) = queue.pop_front() {
+            if !seen.insert(node) {
+                continue;
+            }
+            let forwarded = match self.op(node) {
+                Some(ComputeNodeOp::Core(ComputeOp::Parameter { index })) => {
+                    if self
+                        .param_type(self.func_id, *index as usize)
+                        .and_then(|ty| self.pointer(ty))
+                        == Some(false)
+                    {
+                        return true;
+                    }
+                    continue;
+                }
+                Some(ComputeNodeOp::Core(ComputeOp::CaptureAccess { index })) => {
+                    if self.capture(*index).is_some_and(|c| {
+                        c.mode == CaptureMode::ByRef || self.pointer(c.captured_type) == Some(false)
+                    }) {
+                        return true;
+                    }
+                    continue;
+                }
+                Some(ComputeNodeOp::Core(ComputeOp::GetElementPtr)) => {
+                    self.input(node, 0).into_iter().collect::<Vec<_>>()
+                }
+                Some(ComputeNodeOp::Core(ComputeOp::Phi)) => self
+                    .inputs(node)
+                    .into_iter()
+                    .map(|(_, source, ty)| (source, ty))
+                    .collect(),
+                _ => continue,
+            };
+            for (source, ty) in forwarded {
+                if self.pointer(ty) == Some(false) {
+                    return true;
+                }
+                queue.push_back(source);
+            }
+        }
+        false
+    }
+
+    /// Borrows held by the closure value produced by `node`.
+    fn closure_borrows(&self, node: NodeId, port: u16) -> Vec<Borrow> {
+        let mut borrows = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([node]);
+        while let Some(node) = queue.pop_front() {
+            if !seen.insert(node) {
+                continue;
+            }
+            match self.op(node) {
+                Some(ComputeNodeOp::Core(ComputeOp::MakeClosure { function })) => {
+                    for (_, origin, mutable) in self.capture_borrows(node, *function) {
+                        borrows.push(Borrow {
+                            port,
+                            origin,
+                            mutable,
+                        });
+                    }
+                }
+                Some(ComputeNodeOp::Core(ComputeOp::Phi)) => {
+                    queue.extend(self.inputs(node).into_iter().map(|(_, source, _)| source));
+                }
+                _ => {}
+            }
+        }
+        borrows
+    }
+
+    /// Borrows taken by the `MakeClosure` at `node`: `(capture port, origin,
+    /// mutable)`, including those held by captured closures.
+    fn capture_borrows(&self, node: NodeId, function: FunctionId) -> Vec<(u16, NodeId, bool)> {
+        let captures = self
+            .graph
+            .get_function(function)
+            .map(|f| f.captures.as_slice())
+            .unwrap_or_default();
+        let mut borrows = Vec::new();
+        for (port, source, ty) in self.inputs(node) {
+            let Some(capture) = captures.get(port as usize) else {
+                continue;
+            };
+            let mutable = match capture.mode {
+                CaptureMode::ByRef => Some(false),
+                CaptureMode::ByMutRef => Some(true),
+                CaptureMode::ByValue => self.pointer(capture.captured_type).or(self.pointer(ty)),
+            };
+            if let Some(mutable) = mutable {
+                for origin in self.origins(source) {
+                    borrows.push((port, origin, mutable));
+                }
+            }
+            for borrow in self.closure_borrows(source, port) {
+                borrows.push((port, borrow.origin, borrow.mutable));
+            }
+        }
+        borrows
+    }
+
+    /// Borrows received by a call or closure creation at `node`.
+    fn borrows(&self, node: NodeId) -> Vec<Borrow> {
+        // Parameter types of a direct call's target; edge types otherwise
+        let callee = match self.op(node) {
+            Some(ComputeNodeOp::Core(ComputeOp::Call { target })) => Some(*target),
+            Some(ComputeNodeOp::Core(ComputeOp::IndirectCall)) => None,
+            Some(ComputeNodeOp::Core(ComputeOp::MakeClosure { function })) => {
+                return self
+                    .capture_borrows(node, *function)
+                    .into_iter()
+                    .map(|(port, origin, mutable)| Borrow {
+                        port,
+                        origin,
+                        mutable,
+                    })
+                    .collect();
+            }
+            _ => return Vec::new(),
+        };
+
+        let mut borrows = Vec::new();
+        for (port, source, ty) in self.inputs(node) {
+            let declared = callee
+                .and_then(|target| self.param_type(target, port as usize))
+                .unwrap_or(ty);
+            if let Some(mutable) = self.pointer(declared) {
+                for origin in self.origins(source) {
+                    borrows.push(Borrow {
+                        port,
+                        origin,
+                        mutable,
+                    });
+                }
+            }
+            borrows.extend(self.closure_borrows(source, port));
+        }
+        borrows
+    }
+
+    fn check_node(&self, node: NodeId) -> Vec<Found> {
+        let mut found = Vec::new();
+
+        // Aliasing: a mutable borrow next to any other borrow of the same memory
+        let borrows = self.borrows(node);
+        let mut reported = HashSet::new();
+        for a in borrows.iter().filter(|b| b.mutable) {
+            if let Some(b) = borrows
+                .iter()
+                .find(|b| b.origin == a.origin && b.port != a.port)
+            {
+                if reported.insert(a.origin) {
+                    found.push(Found::Aliased {
+                        origin: a.origin,
+                        mutable_port: a.port,
+                        other_port: b.port,
+                    });
+                }
+            }
+        }
+
+        match self.op(node) {
+            Some(ComputeNodeOp::Core(ComputeOp::Store)) => {
+                if let Some((pointer, ty)) = self.input(node, 0) {
+                    if self.pointer(ty) == Some(false) || self.read_only(pointer) {
+                        found.push(Found::ReadOnlyStore { pointer });
+                    }
+                }
+            }
+            Some(ComputeNodeOp::Core(ComputeOp::MakeClosure { function })) => {
+                let captures = self
+                    .graph
+                    .get_function(*function)
+                    .map(|f| f.captures.as_slice())
+                    .unwrap_or_default();
+                let mut escape = None;
+                for (port, source, _) in self.inputs(node) {
+                    if !captures
+                        .get(port as usize)
+                        .is_some_and(|c| c.mode != CaptureMode::ByValue)
+                    {
+                        continue;
+                    }
+                    for alloc in self.origins(source) {
+                        if !matches!(self.op(alloc), Some(ComputeNodeOp::Core(ComputeOp::Alloc))) {
+                            continue;
+                        }
+                        let escape =
+                            *escape.get_or_insert_with(|| self.escape(node, &mut HashSet::new()));
+                        if let Some(escape) = escape {
+                            found.push(Found::Dangling {
+                                capture: port as u32,
+                                alloc,
+                                escape,
+                            });
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        found
+    }
+
+    /// Where the value produced by `node` may leave this function's frame:
+    /// a `Return`, a `Store` into memory it does not own, or a `Call` whose
+    /// parameter escapes. `visiting` guards against recursive callees.
+    fn escape(&self, node: NodeId, visiting: &mut HashSet<(FunctionId, u16)>) -> Option<NodeId> {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([node]);
+        while let Some(value) = queue.pop_front() {
+            if !seen.insert(value) {
+                continue;
+            }
+            for (target, port) in self.uses(value) {
+                match self.op(target) {
+                    Some(ComputeNodeOp::Core(ComputeOp::Return)) => return Some(target),
+                    Some(ComputeNodeOp::Core(ComputeOp::Store)) if port == 1 => {
+                        let cells = self
+                            .input(target, 0)
+                            .map(|(pointer, _)| self.origins(pointer))
+                            .unwrap_or_default();
+                        let local = !cells.is_empty()
+                            && cells.iter().all(|cell| {
+                                matches!(
+                                    self.op(*cell),
+                                    Some(ComputeNodeOp::Core(ComputeOp::Alloc))
+                                )
+                            });
+                        if !local {
+                            return Some(target);
+                        }
+                        // Follow the value through loads of the local cells
+                        queue.extend(self.graph.function_nodes(self.func_id).into_iter().filter(
+                            |n| {
+                                matches!(self.op(*n), Some(ComputeNodeOp::Core(ComputeOp::Load)))
+                                    && self.input(*n, 0).is_some_and(|(pointer, _)| {
+                                        self.origins(pointer).iter().any(|o| cells.contains(o))
+                                    })
+                            },
+                        ));
+                    }
+                    Some(ComputeNodeOp::Core(ComputeOp::Call { target: callee }))
+                        if self.param_escapes(*callee, port, visiting) =>
+                    {
+                        return Some(target)
+                    }
+                    Some(
+                        ComputeNodeOp::Core(ComputeOp::Phi | ComputeOp::MakeClosure { .. })
+                        | ComputeNodeOp::Structured(
+                            StructuredOp::StructCreate { .. }
+                            | StructuredOp::StructSet { .. }
+                            | StructuredOp::StructGet { .. }
+                            | StructuredOp::ArrayCreate { .. }
+                            | StructuredOp::ArraySet
+                            | StructuredOp::ArrayGet
+                            | StructuredOp::EnumCreate { .. }
+                            | StructuredOp::EnumPayload { .. }
+                            | StructuredOp::Cast { .. },
+                        ),
+                    ) => queue.push_back(target),
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// Whether parameter `port` of `callee` may escape `callee`'s frame.
+    fn param_escapes(
+        &self,
+        callee: FunctionId,
+        port: u16,
+        visiting: &mut HashSet<(FunctionId, u16)>,
+    ) -> bool {
+        if !visiting.insert((callee, port)) {
+            return false;
+        }
+        let view = BorrowView::new(self.graph, callee);
+        self.graph
+            .function_nodes_sorted(callee)
+            .into_iter()
+            .filter(|n| {
+                matches!(
+                    view.op(*n),
+                    Some(ComputeNodeOp::Core(ComputeOp::Parameter { index }))
+                        if *index == port as u32
+                )
+            })
+            .any(|param| view.escape(param, visiting).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lmlang_core::types::{ConstValue, Visibility};
+
+    fn function(graph: &mut ProgramGraph, name: &str, params: Vec<TypeId>) -> FunctionId {
+        let root = graph.modules.root_id();
+        let params = params
+            .into_iter()
+            .enumerate()
+            .map(|(i, ty)| (format!("p{i}"), ty))
+            .collect();
+        graph
+            .add_function(name.into(), root, params, TypeId::I32, Visibility::Public)
+            .unwrap()
+    }
+
+    fn op(graph: &mut ProgramGraph, func_id: FunctionId, op: ComputeOp) -> NodeId {
+        graph.add_core_op(op, func_id).unwrap()
+    }
+
+    fn pointer(graph: &mut ProgramGraph, mutable: bool) -> TypeId {
+        graph.types.register(LmType::Pointer {
+            pointee: TypeId::I32,
+            mutable,
+        })
+    }
+
+    /// Helper: `f(p)` storing 1 through a pointer parameter of the given
+    /// mutability. Returns the graph and the `Store`.
+    fn store_through_param(mutable: bool) -> (ProgramGraph, NodeId) {
+        let mut graph = ProgramGraph::new("test");
+        let ptr = pointer(&mut graph, mutable);
+        let f = function(&mut graph, "f", vec![ptr]);
+        let p = op(&mut graph, f, ComputeOp::Parameter { index: 0 });
+        let one = op(
+            &mut graph,
+            f,
+            ComputeOp::Const {
+                value: ConstValue::I32(1),
+            },
+        );
+        let store = op(&mut graph, f, ComputeOp::Store);
+        graph.add_data_edge(p, store, 0, 0, ptr).unwrap();
+        graph.add_data_edge(one, store, 0, 1, TypeId::I32).unwrap();
+        (graph, store)
+    }
+
+    #[test]
+    fn store_through_read_only_parameter_is_rejected() {
+        let (graph, store) = store_through_param(false);
+        let errors = check_borrows(&graph);
+        assert!(
+            matches!(
+                errors.as_slice(),
+                [BorrowError::StoreThroughImmutable { node, .. }] if *node == store
+            ),
+            "{errors:?}"
+        );
+
+        let (graph, _) = store_through_param(true);
+        assert!(check_borrows(&graph).is_empty());
+    }
+
+    /// Helper: `main` allocating a cell and passing it to
+    /// `g(a: *mut i32, b: *i32)` twice, with `b` typed per `second_mutable`.
+    /// Returns the graph, the call and the cell.
+    fn call_with_two_borrows(second_mutable: bool) -> (ProgramGraph, NodeId, NodeId) {
+        let mut graph = ProgramGraph::new("test");
+        let mut_ptr = pointer(&mut graph, true);
+        let second = pointer(&mut graph, second_mutable);
+        let g = function(&mut graph, "g", vec![mut_ptr, second]);
+        let main = function(&mut graph, "main", vec![]);
+        let cell = op(&mut graph, main, ComputeOp::Alloc);
+        let call = op(&mut graph, main, ComputeOp::Call { target: g });
+        graph.add_data_edge(cell, call, 0, 0, mut_ptr).unwrap();
+        graph.add_data_edge(cell, call, 0, 1, second).unwrap();
+        (graph, call, cell)
+    }
+
+    #[test]
+    fn mutable_and_shared_borrow_of_one_cell_alias() {
+        for second_mutable in [false, true] {
+            let (graph, call, cell) = call_with_two_borrows(second_mutable);
+            let errors = check_borrows(&graph);
+            assert_eq!(errors.len(), 1, "{errors:?}");
+            assert!(
+                matches!(
+                    &errors[0],
+                    BorrowError::AliasedMutableBorrow {
+                        node, origin, mutable_port: 0, other_port: 1, ..
+                    } if *node == call && *origin == cell
+                ),
+                "{errors:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn closure_capturing_a_cell_aliases_a_mutable_argument() {
+        let mut graph = ProgramGraph::new("test");
+        let mut_ptr = pointer(&mut graph, true);
+        let g = function(&mut graph, "g", vec![mut_ptr, TypeId::UNIT]);
+        let main = function(&mut graph, "main", vec![]);
+        let root = graph.modules.root_id();
+        let reader = graph
+            .add_closure(
+                "reader".into(),
+                root,
+                main,
+                vec![],
+                TypeId::I32,
+                vec![Capture {
+                    name: "x".into(),
+                    captured_type: TypeId::I32,
+                    mode: CaptureMode::ByRef,
+                }],
+            )
+            .unwrap();
+
+        let cell = op(&mut graph, main, ComputeOp::Alloc);
+        let closure = op(
+            &mut graph,
+            main,
+            ComputeOp::MakeClosure { function: reader },
+        );
+        graph
+            .add_data_edge(cell, closure, 0, 0, TypeId::I32)
+            .unwrap();
+        let call = op(&mut graph, main, ComputeOp::Call { target: g });
+        graph.add_data_edge(cell, call, 0, 0, mut_ptr).unwrap();
+        graph
+            .add_data_edge(closure, call, 0, 1, TypeId::UNIT)
+            .unwrap();
+
+        let errors = check_borrows(&graph);
+        assert!(
+            matches!(
+                errors.as_slice(),
+                [BorrowError::AliasedMutableBorrow { node, origin, .. }]
+                    if *node == call && *origin == cell
+            ),
+            "{errors:?}"
+        );
+    }
+
+    /// Helper: `main` building a closure that captures a local cell with
+    /// `mode`, and passing it through `route` before it reaches a `Return`
+    /// (`true`) or an `IndirectCall` (`false`). Returns the graph, closure
+    /// and cell.
+    fn capturing_closure(mode: CaptureMode, returned: bool) -> (ProgramGraph, NodeId, NodeId) {
+        let mut graph = ProgramGraph::new("test");
+        let main = function(&mut graph, "main", vec![]);
+        let root = graph.modules.root_id();
+        let counter = graph
+            .add_closure(
+                "counter".into(),
+                root,
+                main,
+                vec![],
+                TypeId::I32,
+                vec![Capture {
+                    name: "x".into(),
+                    captured_type: TypeId::I32,
+                    mode,
+                }],
+            )
+            .unwrap();
+
+        let cell = op(&mut graph, main, ComputeOp::Alloc);
+        let closure = op(
+            &mut graph,
+            main,
+            ComputeOp::MakeClosure { function: counter },
+        );
+        graph
+            .add_data_edge(cell, closure, 0, 0, TypeId::I32)
+            .unwrap();
+
+        // Keep the closure in a second local cell before using it
+        let slot = op(&mut graph, main, ComputeOp::Alloc);
+        let store = op(&mut graph, main, ComputeOp::Store);
+        graph
+            .add_data_edge(slot, store, 0, 0, TypeId::UNIT)
+            .unwrap();
+        graph
+            .add_data_edge(closure, store, 0, 1, TypeId::UNIT)
+            .unwrap();
+        let load = op(&mut graph, main, ComputeOp::Load);
+        graph.add_data_edge(slot, load, 0, 0, TypeId::UNIT).unwrap();
+
+        let sink = if returned {
+            op(&mut graph, main, ComputeOp::Return)
+        } else {
+            op(&mut graph, main, ComputeOp::IndirectCall)
+        };
+        graph.add_data_edge(load, sink, 0, 0, TypeId::UNIT).unwrap();
+        (graph, closure, cell)
+    }
+
+    #[test]
+    fn returned_by_reference_closure_dangles() {
+        let (graph, closure, cell) = capturing_closure(CaptureMode::ByMutRef, true);
+        let errors = check_borrows(&graph);
+        assert!(
+            matches!(
+                errors.as_slice(),
+                [BorrowError::DanglingCapture { node, alloc, capture: 0, .. }]
+                    if *node == closure && *alloc == cell
+            ),
+            "{errors:?}"
+        );
+        assert!(errors[0].to_string().contains("escapes through node"));
+    }
+
+    #[test]
+    fn closures_that_stay_local_or_capture_by_value_are_accepted() {
+        let (graph, _, _) = capturing_closure(CaptureMode::ByRef, false);
+        assert!(check_borrows(&graph).is_empty());
+        let (graph, _, _) = capturing_closure(CaptureMode::ByValue, true);
+        assert!(check_borrows(&graph).is_empty());
+    }
+}
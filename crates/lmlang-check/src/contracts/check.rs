@@ -15,6 +15,9 @@ use lmlang_core::ops::{ComputeNodeOp, ComputeOp};
 
 use crate::contracts::{ContractKind, ContractViolation};
 use crate::interpreter::error::RuntimeError;
+use crate::interpreter::state::{
+    int_value, with_int_value, ExecutionState, Interpreter, InterpreterConfig,
+};
 use crate::interpreter::value::Value;
 
 /// Find all contract nodes of a given kind in a function, sorted by NodeId.
@@ -143,6 +146,136 @@ fn get_contract_message(graph: &ProgramGraph, contract_node_id: NodeId) -> Strin
     }
 }
 
+/// The value of an `Old { index }` node: argument `index` as passed to the
+/// frame, or for a pointer argument the memory cell it points to.
+///
+/// The interpreter seeds `Old` nodes alongside the parameters, so this runs
+/// before any node of the function body and sees the entry state.
+pub fn old_value(
+    arguments: &[Value],
+    index: u32,
+    memory: &[Value],
+    node_id: NodeId,
+) -> Result<Value, RuntimeError> {
+    match arguments.get(index as usize) {
+        Some(Value::Pointer { address, .. }) => {
+            memory
+                .get(*address)
+                .cloned()
+                .ok_or(RuntimeError::OutOfBoundsAccess {
+                    node: node_id,
+                    index: *address,
+                    size: memory.len(),
+                })
+        }
+        Some(value) => Ok(value.clone()),
+        None => Ok(Value::Unit),
+    }
+}
+
+/// The items a `ForAll`/`Exists` quantifier visits.
+#[derive(Debug, Clone)]
+pub enum QuantifierDomain {
+    /// Integer range `start..end`, step 1; `start` keeps the bounds' type.
+    Range { start: Value, end: i64 },
+    /// Array elements in index order.
+    Elements(Vec<Value>),
+}
+
+/// Reads a quantifier's domain from its inputs: an array on port 0, or
+/// integer bounds on ports 0 and 1.
+pub fn quantifier_domain(
+    inputs: &[(u16, Value)],
+    node_id: NodeId,
+) -> Result<QuantifierDomain, RuntimeError> {
+    let port = |p: u16| inputs.iter().find(|(q, _)| *q == p).map(|(_, v)| v);
+    let first = port(0).ok_or(RuntimeError::MissingValue {
+        node: node_id,
+        port: 0,
+    })?;
+    match (first, port(1)) {
+        (Value::Array { elements, .. }, None) => Ok(QuantifierDomain::Elements(elements.clone())),
+        (start, Some(end)) => match (int_value(start), int_value(end)) {
+            (Some(_), Some(end)) => Ok(QuantifierDomain::Range {
+                start: start.clone(),
+                end,
+            }),
+            (None, _) => Err(RuntimeError::TypeMismatchAtRuntime {
+                node: node_id,
+                expected: "integer".into(),
+                got: start.type_name().into(),
+            }),
+            (_, None) => Err(RuntimeError::TypeMismatchAtRuntime {
+                node: node_id,
+                expected: "integer".into(),
+                got: end.type_name().into(),
+            }),
+        },
+        (other, None) => Err(RuntimeError::TypeMismatchAtRuntime {
+            node: node_id,
+            expected: "Array".into(),
+            got: other.type_name().into(),
+        }),
+    }
+}
+
+/// Folds one predicate result into a quantifier. Returns the quantifier's
+/// value once it is decided: `false` for a `ForAll` counterexample, `true`
+/// for an `Exists` witness; `None` while every item so far agrees.
+pub fn quantifier_verdict(
+    universal: bool,
+    result: &Value,
+    node_id: NodeId,
+) -> Result<Option<bool>, RuntimeError> {
+    match result {
+        Value::Bool(holds) if *holds != universal => Ok(Some(*holds)),
+        Value::Bool(_) => Ok(None),
+        other => Err(RuntimeError::TypeMismatchAtRuntime {
+            node: node_id,
+            expected: "Bool".into(),
+            got: other.type_name().into(),
+        }),
+    }
+}
+
+/// Evaluates a `ForAll`/`Exists` outside a running interpreter: calls the
+/// predicate on each item in a fresh interpreter and stops once the verdict
+/// is decided. Used by the invariant mini-evaluation.
+fn evaluate_quantifier(
+    graph: &ProgramGraph,
+    predicate: FunctionId,
+    universal: bool,
+    inputs: &[(u16, Value)],
+    node_id: NodeId,
+) -> Result<Value, RuntimeError> {
+    let items: Vec<Value> = match quantifier_domain(inputs, node_id)? {
+        QuantifierDomain::Elements(elements) => elements,
+        QuantifierDomain::Range { start, end } => {
+            let first = int_value(&start).unwrap_or(end);
+            (first..end).map(|n| with_int_value(&start, n)).collect()
+        }
+    };
+    for item in items {
+        let mut interp = Interpreter::new(graph, InterpreterConfig::default());
+        interp.start(predicate, vec![item]);
+        let result = match interp.run() {
+            ExecutionState::Completed { result } => result.clone(),
+            ExecutionState::Error { error, .. } => return Err(error.clone()),
+            other => {
+                return Err(RuntimeError::InternalError {
+                    message: format!(
+                        "quantifier predicate {predicate} did not complete: {other:?}"
+                    ),
+                })
+            }
+        };
+        if let Some(verdict) = quantifier_verdict(universal, &result, node_id)? {
+            return Ok(Value::Bool(verdict));
+        }
+    }
+    Ok(Value::Bool(universal))
+}
+
 /// Check all preconditions for a function.
 ///
 /// Called at function entry, AFTER parameter nodes are seeded but BEFORE
@@ -365,7 +498,7 @@ fn evaluate_subgraph_node(
     let op = node.op.clone();
 
     match &op {
-        ComputeNodeOp::Core(ComputeOp::Parameter { .. }) => {
+        ComputeNodeOp::Core(ComputeOp::Parameter { .. } | ComputeOp::Old { .. }) => {
             // Substitute with the argument value being checked
             local_values.insert(node_id, arg_value.clone());
         }
@@ -399,9 +532,23 @@ fn evaluate_subgraph_node(
                 .collect();
             inputs.sort_by_key(|(port, _)| *port);
 
-            // Evaluate using the existing eval_op for arithmetic/comparison/etc.
+            // Quantifiers call their predicate; everything else uses the
+            // existing eval_op for arithmetic/comparison/etc.
             use crate::interpreter::eval::eval_op;
-            if let Some(value) = eval_op(&op, &inputs, node_id, graph)? {
+            let value = match &op {
+                ComputeNodeOp::Core(
+                    quantifier
+                    @ (ComputeOp::ForAll { predicate } | ComputeOp::Exists { predicate }),
+                ) => Some(evaluate_quantifier(
+                    graph,
+                    *predicate,
+                    matches!(quantifier, ComputeOp::ForAll { .. }),
+                    &inputs,
+                    node_id,
+                )?),
+                _ => eval_op(&op, &inputs, node_id, graph)?,
+            };
+            if let Some(value) = value {
                 local_values.insert(node_id, value);
             }
         }
@@ -858,4 +1005,361 @@ mod tests {
         assert_eq!(violations[0].kind, ContractKind::Invariant);
         assert_eq!(violations[0].message, "x must be non-negative");
     }
+
+    /// Runs `func` on `engine`, requiring bytecode lowering to succeed.
+    fn run_engine(
+        graph: &ProgramGraph,
+        engine: crate::interpreter::Engine,
+        func: FunctionId,
+        args: Vec<Value>,
+    ) -> ExecutionState {
+        let config = InterpreterConfig::default();
+        match engine {
+            crate::interpreter::Engine::Reference => {
+                let mut interp = Interpreter::new(graph, config);
+                interp.start(func, args);
+                interp.run().clone()
+            }
+            crate::interpreter::Engine::Bytecode => {
+                let program = crate::interpreter::BytecodeCache::default()
+                    .lower(graph)
+                    .unwrap();
+                crate::interpreter::vm::run(graph, &program, &config, func, args).state
+            }
+        }
+    }
+
+    fn op(graph: &mut ProgramGraph, func: FunctionId, op: ComputeOp) -> NodeId {
+        graph.add_core_op(op, func).unwrap()
+    }
+
+    fn const_i32(graph: &mut ProgramGraph, func: FunctionId, n: i32) -> NodeId {
+        op(
+            graph,
+            func,
+            ComputeOp::Const {
+                value: lmlang_core::types::ConstValue::I32(n),
+            },
+        )
+    }
+
+    /// `bump(p: *I32) -> I32` adds `step` to `*p` and returns the new value,
+    /// with the postcondition `*p == old(*p) + 1`. `main` passes a cell
+    /// holding 41.
+    fn build_bump_graph(step: i32) -> (ProgramGraph, FunctionId) {
+        use lmlang_core::ops::ArithOp;
+        use lmlang_core::types::LmType;
+
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let ptr_ty = graph.types.register(LmType::Pointer {
+            pointee: TypeId::I32,
+            mutable: true,
+        });
+        let bump = graph
+            .add_function(
+                "bump".into(),
+                root,
+                vec![("p".into(), ptr_ty)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let p = op(&mut graph, bump, ComputeOp::Parameter { index: 0 });
+        let before = op(&mut graph, bump, ComputeOp::Load);
+        let step_const = const_i32(&mut graph, bump, step);
+        let sum = op(
+            &mut graph,
+            bump,
+            ComputeOp::BinaryArith { op: ArithOp::Add },
+        );
+        let store = op(&mut graph, bump, ComputeOp::Store);
+        let after = op(&mut graph, bump, ComputeOp::Load);
+        graph.add_data_edge(p, before, 0, 0, ptr_ty).unwrap();
+        graph.add_data_edge(before, sum, 0, 0, TypeId::I32).unwrap();
+        graph
+            .add_data_edge(step_const, sum, 0, 1, TypeId::I32)
+            .unwrap();
+        graph.add_data_edge(p, store, 0, 0, ptr_ty).unwrap();
+        graph.add_data_edge(sum, store, 0, 1, TypeId::I32).unwrap();
+        graph.add_data_edge(p, after, 0, 0, ptr_ty).unwrap();
+        graph.add_control_edge(store, after, None).unwrap();
+
+        // Postcondition: *p == old(*p) + 1
+        let old = op(&mut graph, bump, ComputeOp::Old { index: 0 });
+        let one = const_i32(&mut graph, bump, 1);
+        let expected = op(
+            &mut graph,
+            bump,
+            ComputeOp::BinaryArith { op: ArithOp::Add },
+        );
+        let cmp = op(&mut graph, bump, ComputeOp::Compare { op: CmpOp::Eq });
+        let post = op(
+            &mut graph,
+            bump,
+            ComputeOp::Postcondition {
+                message: "*p == old(*p) + 1".into(),
+            },
+        );
+        graph
+            .add_data_edge(old, expected, 0, 0, TypeId::I32)
+            .unwrap();
+        graph
+            .add_data_edge(one, expected, 0, 1, TypeId::I32)
+            .unwrap();
+        graph.add_data_edge(after, cmp, 0, 0, TypeId::I32).unwrap();
+        graph
+            .add_data_edge(expected, cmp, 0, 1, TypeId::I32)
+            .unwrap();
+        graph.add_data_edge(cmp, post, 0, 0, TypeId::BOOL).unwrap();
+        let ret = op(&mut graph, bump, ComputeOp::Return);
+        graph.add_data_edge(after, ret, 0, 0, TypeId::I32).unwrap();
+        graph.add_control_edge(post, ret, None).unwrap();
+
+        let main = graph
+            .add_function("main".into(), root, vec![], TypeId::I32, Visibility::Public)
+            .unwrap();
+        let cell = op(&mut graph, main, ComputeOp::Alloc);
+        let initial = const_i32(&mut graph, main, 41);
+        let init = op(&mut graph, main, ComputeOp::Store);
+        let call = op(&mut graph, main, ComputeOp::Call { target: bump });
+        let main_ret = op(&mut graph, main, ComputeOp::Return);
+        graph.add_data_edge(cell, init, 0, 0, ptr_ty).unwrap();
+        graph
+            .add_data_edge(initial, init, 0, 1, TypeId::I32)
+            .unwrap();
+        graph.add_data_edge(cell, call, 0, 0, ptr_ty).unwrap();
+        graph.add_control_edge(init, call, None).unwrap();
+        graph
+            .add_data_edge(call, main_ret, 0, 0, TypeId::I32)
+            .unwrap();
+        (graph, main)
+    }
+
+    #[test]
+    fn test_old_reads_pointer_parameter_at_entry() {
+        for engine in [
+            crate::interpreter::Engine::Reference,
+            crate::interpreter::Engine::Bytecode,
+        ] {
+            let (graph, main) = build_bump_graph(1);
+            assert!(crate::typecheck::validate_graph(&graph).is_empty());
+            let output = run_engine(&graph, engine, main, vec![]);
+            assert!(
+                matches!(
+                    output,
+                    ExecutionState::Completed {
+                        result: Value::I32(42)
+                    }
+                ),
+                "{engine:?}: {:?}",
+                output
+            );
+
+            let (graph, main) = build_bump_graph(2);
+            let output = run_engine(&graph, engine, main, vec![]);
+            match output {
+                ExecutionState::ContractViolation { violation } => {
+                    assert_eq!(violation.kind, ContractKind::Postcondition);
+                    assert_eq!(violation.message, "*p == old(*p) + 1");
+                }
+                other => panic!("{engine:?}: expected a violation, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_old_value_snapshots_pointee() {
+        let memory = vec![Value::I32(7)];
+        let pointer = |address| Value::Pointer {
+            ty: TypeId::UNIT,
+            address,
+        };
+        let args = vec![pointer(0), Value::I64(3)];
+        let node = NodeId(0);
+        assert_eq!(old_value(&args, 0, &memory, node).unwrap(), Value::I32(7));
+        assert_eq!(old_value(&args, 1, &memory, node).unwrap(), Value::I64(3));
+        assert!(matches!(
+            old_value(&[pointer(4)], 0, &memory, node),
+            Err(RuntimeError::OutOfBoundsAccess { index: 4, .. })
+        ));
+    }
+
+    /// Adds `is_nonneg(x: I32) -> Bool` to the graph.
+    fn add_is_nonneg(graph: &mut ProgramGraph) -> FunctionId {
+        let root = graph.modules.root_id();
+        let func = graph
+            .add_function(
+                "is_nonneg".into(),
+                root,
+                vec![("x".into(), TypeId::I32)],
+                TypeId::BOOL,
+                Visibility::Public,
+            )
+            .unwrap();
+        let x = op(graph, func, ComputeOp::Parameter { index: 0 });
+        let zero = const_i32(graph, func, 0);
+        let cmp = op(graph, func, ComputeOp::Compare { op: CmpOp::Ge });
+        let ret = op(graph, func, ComputeOp::Return);
+        graph.add_data_edge(x, cmp, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(zero, cmp, 0, 1, TypeId::I32).unwrap();
+        graph.add_data_edge(cmp, ret, 0, 0, TypeId::BOOL).unwrap();
+        func
+    }
+
+    #[test]
+    fn test_quantifiers_over_arrays_and_ranges() {
+        use lmlang_core::types::LmType;
+
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let is_nonneg = add_is_nonneg(&mut graph);
+        let arr_ty = graph.types.register(LmType::Array {
+            element: TypeId::I32,
+            length: 3,
+        });
+        // all_nonneg(a) = forall x in a. x >= 0; any_nonneg(lo, hi) = exists i in lo..hi. i >= 0
+        let all = graph
+            .add_function(
+                "all_nonneg".into(),
+                root,
+                vec![("a".into(), arr_ty)],
+                TypeId::BOOL,
+                Visibility::Public,
+            )
+            .unwrap();
+        let a = op(&mut graph, all, ComputeOp::Parameter { index: 0 });
+        let forall = op(
+            &mut graph,
+            all,
+            ComputeOp::ForAll {
+                predicate: is_nonneg,
+            },
+        );
+        let ret = op(&mut graph, all, ComputeOp::Return);
+        graph.add_data_edge(a, forall, 0, 0, arr_ty).unwrap();
+        graph
+            .add_data_edge(forall, ret, 0, 0, TypeId::BOOL)
+            .unwrap();
+
+        let any = graph
+            .add_function(
+                "any_nonneg".into(),
+                root,
+                vec![("lo".into(), TypeId::I32), ("hi".into(), TypeId::I32)],
+                TypeId::BOOL,
+                Visibility::Public,
+            )
+            .unwrap();
+        let lo = op(&mut graph, any, ComputeOp::Parameter { index: 0 });
+        let hi = op(&mut graph, any, ComputeOp::Parameter { index: 1 });
+        let exists = op(
+            &mut graph,
+            any,
+            ComputeOp::Exists {
+                predicate: is_nonneg,
+            },
+        );
+        let ret = op(&mut graph, any, ComputeOp::Return);
+        graph.add_data_edge(lo, exists, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(hi, exists, 0, 1, TypeId::I32).unwrap();
+        graph
+            .add_data_edge(exists, ret, 0, 0, TypeId::BOOL)
+            .unwrap();
+
+        // Outside a contract the values escape into a Return
+        let errors = crate::typecheck::validate_graph(&graph);
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors
+            .iter()
+            .all(|e| matches!(e, crate::typecheck::TypeError::ContractValueEscapes { .. })));
+
+        let cases = [
+            (
+                all,
+                vec![Value::untyped_array(vec![
+                    Value::I32(0),
+                    Value::I32(5),
+                    Value::I32(9),
+                ])],
+                true,
+            ),
+            (
+                all,
+                vec![Value::untyped_array(vec![
+                    Value::I32(1),
+                    Value::I32(-2),
+                    Value::I32(3),
+                ])],
+                false,
+            ),
+            (all, vec![Value::untyped_array(vec![])], true),
+            (any, vec![Value::I32(-3), Value::I32(1)], true),
+            (any, vec![Value::I32(-3), Value::I32(0)], false),
+            (any, vec![Value::I32(4), Value::I32(2)], false),
+        ];
+        for engine in [
+            crate::interpreter::Engine::Reference,
+            crate::interpreter::Engine::Bytecode,
+        ] {
+            for (func, args, expected) in &cases {
+                let output = run_engine(&graph, engine, *func, args.clone());
+                match output {
+                    ExecutionState::Completed {
+                        result: Value::Bool(b),
+                    } => assert_eq!(b, *expected, "{engine:?} {args:?}"),
+                    other => panic!("{engine:?} {args:?}: {other:?}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invariant_with_quantifier_uses_mini_eval() {
+        use lmlang_core::types::LmType;
+
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let is_nonneg = add_is_nonneg(&mut graph);
+        let arr_ty = graph.types.register(LmType::Array {
+            element: TypeId::I32,
+            length: 2,
+        });
+        let func = graph
+            .add_function(
+                "validate".into(),
+                root,
+                vec![("a".into(), arr_ty)],
+                TypeId::UNIT,
+                Visibility::Public,
+            )
+            .unwrap();
+        let a = op(&mut graph, func, ComputeOp::Parameter { index: 0 });
+        let forall = op(
+            &mut graph,
+            func,
+            ComputeOp::ForAll {
+                predicate: is_nonneg,
+            },
+        );
+        let inv = op(
+            &mut graph,
+            func,
+            ComputeOp::Invariant {
+                target_type: arr_ty,
+                message: "elements are non-negative".into(),
+            },
+        );
+        graph.add_data_edge(a, forall, 0, 0, arr_ty).unwrap();
+        graph
+            .add_data_edge(forall, inv, 0, 0, TypeId::BOOL)
+            .unwrap();
+        graph.add_data_edge(a, inv, 0, 1, arr_ty).unwrap();
+        assert!(crate::typecheck::validate_graph(&graph).is_empty());
+
+        let good = Value::untyped_array(vec![Value::I32(1), Value::I32(2)]);
+        let bad = Value::untyped_array(vec![Value::I32(1), Value::I32(-2)]);
+        assert!(evaluate_invariant_for_value(&graph, inv, &good).unwrap());
+        assert!(!evaluate_invariant_for_value(&graph, inv, &bad).unwrap());
+    }
 }
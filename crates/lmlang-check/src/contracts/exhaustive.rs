@@ -0,0 +1,757 @@
+//! Bounded exhaustive contract verification for small input domains.
+//!
+//! Where property testing samples inputs, this verifier enumerates every
+//! input in a finite domain and runs each through the interpreter, so a
+//! clean run is a proof that no contract fails anywhere in that domain.
+//!
+//! Each parameter's domain comes from its type: `Bool`, `Unit`, `I8`, enums
+//! (every variant with every payload), and structs and arrays of enumerable
+//! types. Wider integers are enumerable once bounded, either by a
+//! caller-supplied inclusive box or by the function's own preconditions:
+//! preconditions of the form `param <op> const` are intersected into the
+//! parameter's range, and inputs that still violate a precondition are
+//! excluded from the domain rather than reported. Floats, pointers and
+//! function values are not enumerable.
+//!
+//! An input whose run stops on a runtime error or an exhausted budget is
+//! unsettled: its contracts were never all checked, so the domain is not
+//! proved while any input is unsettled.
+//!
+//! The total domain size is computed before anything runs and must fit in
+//! the case budget. Integers are enumerated smallest magnitude first, so the
+//! first counterexample found is a small one.
+
+use std::collections::HashMap;
+
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+
+use lmlang_core::edge::FlowEdge;
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::ops::{CmpOp, ComputeNodeOp, ComputeOp};
+use lmlang_core::type_id::{TypeId, TypeRegistry};
+use lmlang_core::types::{ConstValue, LmType};
+
+use crate::contracts::check::find_contract_nodes;
+use crate::contracts::property::{
+    run_single_test, violates_own_precondition, PropertyTestFailure, SingleTestResult, UnsettledRun,
+};
+use crate::contracts::ContractKind;
+use crate::interpreter::bytecode::BytecodeCache;
+use crate::interpreter::coverage::Coverage;
+use crate::interpreter::error::RuntimeError;
+use crate::interpreter::state::ExecutionLimits;
+use crate::interpreter::value::Value;
+use crate::interpreter::vm::{Engine, Executor};
+
+/// Default cap on the number of inputs enumerated in one verification.
+pub const DEFAULT_MAX_CASES: u64 = 100_000;
+
+/// Configuration for an exhaustive verification run.
+#[derive(Debug, Clone)]
+pub struct ExhaustiveConfig {
+    /// Optional inclusive `(min, max)` box per parameter, by position.
+    /// Only integer parameters can be bounded.
+    pub bounds: Vec<Option<(i64, i64)>>,
+    /// Maximum domain size; larger domains are refused before running.
+    pub max_cases: u64,
+    /// Seed for `Random` ops inside the function under test.
+    pub random_seed: u64,
+    /// Budgets for each interpreter run.
+    pub limits: ExecutionLimits,
+    /// Engine running each input.
+    pub engine: Engine,
+    /// Lowered functions reused across verification runs.
+    pub bytecode_cache: BytecodeCache,
+}
+
+/// Result of an exhaustive verification run.
+#[derive(Debug, Clone)]
+pub struct ExhaustiveResult {
+    /// Number of inputs in the enumerated domain.
+    pub domain_size: u64,
+    /// Inputs run to completion (excluding precondition rejects).
+    pub checked: u64,
+    /// Inputs excluded for violating the function's own preconditions.
+    pub excluded: u64,
+    /// Inputs whose run stopped on a runtime error or an exhausted budget.
+    pub unsettled: u64,
+    /// The first unsettled input and the error that stopped it.
+    pub first_unsettled: Option<UnsettledRun>,
+    /// The first failing input, or `None` if every contract held for every
+    /// input in the domain.
+    pub counterexample: Option<PropertyTestFailure>,
+    /// Node and branch coverage over the checked inputs.
+    pub coverage: Coverage,
+}
+
+impl ExhaustiveResult {
+    /// Whether the contracts were proved for all inputs in the domain: no
+    /// input failed and every input ran to completion.
+    pub fn proved(&self) -> bool {
+        self.counterexample.is_none() && self.unsettled == 0
+    }
+}
+
+/// Errors that prevent an exhaustive verification from running.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ExhaustiveError {
+    #[error("function {0} not found")]
+    FunctionNotFound(u32),
+
+    #[error("parameter {param} has type {type_name}, which cannot be enumerated")]
+    Unenumerable { param: u32, type_name: String },
+
+    #[error("invalid bound for parameter {param}: {reason}")]
+    InvalidBound { param: u32, reason: String },
+
+    #[error("domain of {domain_size} inputs exceeds the budget of {max_cases}")]
+    BudgetExceeded { domain_size: u128, max_cases: u64 },
+
+    #[error(transparent)]
+    Runtime(#[from] RuntimeError),
+}
+
+/// Enumerates the input domain of `func_id` and runs every input.
+///
+/// Stops at the first contract violation, which is returned with its
+/// execution trace. Inputs stopped by a runtime error or an exhausted
+/// budget are counted as unsettled and enumeration continues.
+pub fn verify_exhaustively(
+    graph: &ProgramGraph,
+    func_id: FunctionId,
+    config: &ExhaustiveConfig,
+) -> Result<ExhaustiveResult, ExhaustiveError> {
+    let func_def = graph
+        .get_function(func_id)
+        .ok_or(ExhaustiveError::FunctionNotFound(func_id.0))?;
+    let params = func_def.params.clone();
+
+    if config.bounds.len() > params.len() {
+        return Err(ExhaustiveError::InvalidBound {
+            param: params.len() as u32,
+            reason: format!("function only has {} parameters", params.len()),
+        });
+    }
+
+    let inferred = precondition_bounds(graph, func_id);
+
+    // Size every parameter's domain before materializing any of them
+    let mut ranges = Vec::with_capacity(params.len());
+    let mut domain_size: u128 = 1;
+    for (index, (_, type_id)) in params.iter().enumerate() {
+        let index = index as u32;
+        let range = match config.bounds.get(index as usize).copied().flatten() {
+            Some((min, max)) => {
+                let Some((lo, hi)) = int_range(*type_id) else {
+                    return Err(ExhaustiveError::InvalidBound {
+                        param: index,
+                        reason: "only integer parameters can be bounded".into(),
+                    });
+                };
+                if min > max {
+                    return Err(ExhaustiveError::InvalidBound {
+                        param: index,
+                        reason: format!("min {min} is greater than max {max}"),
+                    });
+                }
+                Some((min.max(lo), max.min(hi)))
+            }
+            None => None,
+        };
+        let range = intersect(range, inferred.get(&index).copied());
+        let size = domain_len(*type_id, &graph.types, range).ok_or_else(|| {
+            ExhaustiveError::Unenumerable {
+                param: index,
+                type_name: type_name(*type_id, &graph.types),
+            }
+        })?;
+        domain_size = domain_size.saturating_mul(size);
+        ranges.push(range);
+    }
+
+    if domain_size > config.max_cases as u128 {
+        return Err(ExhaustiveError::BudgetExceeded {
+            domain_size,
+            max_cases: config.max_cases,
+        });
+    }
+
+    let mut result = ExhaustiveResult {
+        domain_size: domain_size as u64,
+        checked: 0,
+        excluded: 0,
+        unsettled: 0,
+        first_unsettled: None,
+        counterexample: None,
+        coverage: Coverage::default(),
+    };
+    // Checked before enumerating: an empty parameter leaves the others'
+    // domains unbounded by the budget
+    if domain_size == 0 {
+        return Ok(result);
+    }
+
+    let domains: Vec<Vec<Value>> = params
+        .iter()
+        .zip(&ranges)
+        .map(|((_, type_id), range)| domain(*type_id, &graph.types, *range).unwrap_or_default())
+        .collect();
+
+    // Odometer over the cartesian product, last parameter fastest
+    let executor = Executor::new(graph, config.engine, &config.bytecode_cache);
+    let mut cursor = vec![0usize; domains.len()];
+    loop {
+        let inputs: Vec<Value> = cursor
+            .iter()
+            .zip(&domains)
+            .map(|(&i, domain)| domain[i].clone())
+            .collect();
+        let mut coverage = Coverage::default();
+        let outcome = run_single_test(
+            &executor,
+            func_id,
+            inputs.clone(),
+            config.random_seed,
+            config.limits,
+            false,
+            Some(&mut coverage),
+        )?;
+        if violates_own_precondition(&outcome, func_id) {
+            result.excluded += 1;
+        } else if let SingleTestResult::Unsettled(run) = outcome {
+            result.unsettled += 1;
+            result.coverage.merge(&coverage);
+            result.first_unsettled.get_or_insert(*run);
+        } else {
+            result.checked += 1;
+            result.coverage.merge(&coverage);
+            if let SingleTestResult::Failure(_) = outcome {
+                if let SingleTestResult::Failure(failure) = run_single_test(
+                    &executor,
+                    func_id,
+                    inputs,
+                    config.random_seed,
+                    config.limits,
+                    true,
+                    None,
+                )? {
+                    result.counterexample = Some(*failure);
+                }
+                return Ok(result);
+            }
+        }
+
+        let mut position = domains.len();
+        loop {
+            if position == 0 {
+                return Ok(result);
+            }
+            position -= 1;
+            cursor[position] += 1;
+            if cursor[position] < domains[position].len() {
+                break;
+            }
+            cursor[position] = 0;
+        }
+    }
+}
+
+/// Inclusive value range of a built-in integer type.
+fn int_range(type_id: TypeId) -> Option<(i64, i64)> {
+    Some(match type_id {
+        TypeId::I8 => (i8::MIN as i64, i8::MAX as i64),
+        TypeId::I16 => (i16::MIN as i64, i16::MAX as i64),
+        TypeId::I32 => (i32::MIN as i64, i32::MAX as i64),
+        TypeId::I64 => (i64::MIN, i64::MAX),
+        _ => return None,
+    })
+}
+
+fn intersect(a: Option<(i64, i64)>, b: Option<(i64, i64)>) -> Option<(i64, i64)> {
+    match (a, b) {
+        (Some((a_lo, a_hi)), Some((b_lo, b_hi))) => Some((a_lo.max(b_lo), a_hi.min(b_hi))),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Number of values in a type's domain, or `None` if it is not enumerable.
+/// `range` narrows a top-level integer type; an empty range has size 0.
+fn domain_len(type_id: TypeId, registry: &TypeRegistry, range: Option<(i64, i64)>) -> Option<u128> {
+    if let Some((lo, hi)) = int_range(type_id) {
+        let (lo, hi) = intersect(Some((lo, hi)), range)?;
+        return Some(if lo > hi {
+            0
+        } else {
+            (hi as i128 - lo as i128 + 1) as u128
+        });
+    }
+    match registry.get(type_id)? {
+        LmType::Scalar(_) if type_id == TypeId::BOOL => Some(2),
+        LmType::Unit => Some(1),
+        LmType::Array { element, length } => {
+            let element = domain_len(*element, registry, None)?;
+            Some((0..*length).fold(1u128, |acc, _| acc.saturating_mul(element)))
+        }
+        LmType::Struct(def) => def.fields.values().try_fold(1u128, |acc, field| {
+            Some(acc.saturating_mul(domain_len(*field, registry, None)?))
+        }),
+        LmType::Enum(def) => def.variants.values().try_fold(0u128, |acc, variant| {
+            let payload = match variant.payload {
+                Some(payload) => domain_len(payload, registry, None)?,
+                None => 1,
+            };
+            Some(acc.saturating_add(payload))
+        }),
+        _ => None,
+    }
+}
+
+/// Every value of a type, integers ordered by magnitude (0, 1, -1, 2, -2, ...).
+/// Only called once [`domain_len`] has checked the size fits the budget.
+fn domain(
+    type_id: TypeId,
+    registry: &TypeRegistry,
+    range: Option<(i64, i64)>,
+) -> Option<Vec<Value>> {
+    if let Some(full) = int_range(type_id) {
+        let (lo, hi) = intersect(Some(full), range)?;
+        let mut values: Vec<i64> = (lo..=hi).collect();
+        values.sort_by_key(|n| (n.unsigned_abs(), *n < 0));
+        return Some(
+            values
+                .into_iter()
+                .map(|n| match type_id {
+                    TypeId::I8 => Value::I8(n as i8),
+                    TypeId::I16 => Value::I16(n as i16),
+                    TypeId::I32 => Value::I32(n as i32),
+                    _ => Value::I64(n),
+                })
+                .collect(),
+        );
+    }
+    match registry.get(type_id)? {
+        LmType::Scalar(_) if type_id == TypeId::BOOL => {
+            Some(vec![Value::Bool(false), Value::Bool(true)])
+        }
+        LmType::Unit => Some(vec![Value::Unit]),
+        LmType::Array { element, length } => {
+            let element = domain(*element, registry, None)?;
+            let rows = product(&vec![element; *length as usize]);
+            Some(
+                rows.into_iter()
+                    .map(|elements| Value::Array {
+                        ty: type_id,
+                        elements,
+                    })
+                    .collect(),
+            )
+        }
+        LmType::Struct(def) => {
+            let fields = def
+                .fields
+                .values()
+                .map(|field| domain(*field, registry, None))
+                .collect::<Option<Vec<_>>>()?;
+            Some(
+                product(&fields)
+                    .into_iter()
+                    .map(|fields| Value::Struct {
+                        ty: type_id,
+                        fields,
+                    })
+                    .collect(),
+            )
+        }
+        LmType::Enum(def) => {
+            let mut values = Vec::new();
+            for variant in def.variants.values() {
+                let payloads = match variant.payload {
+                    Some(payload) => domain(payload, registry, None)?,
+                    None => vec![Value::Unit],
+                };
+                values.extend(payloads.into_iter().map(|payload| Value::Enum {
+                    ty: type_id,
+                    variant: variant.index,
+                    payload: Box::new(payload),
+                }));
+            }
+            Some(values)
+        }
+        _ => None,
+    }
+}
+
+/// Cartesian product of `columns`, last column varying fastest.
+fn product(columns: &[Vec<Value>]) -> Vec<Vec<Value>> {
+    columns.iter().fold(vec![Vec::new()], |rows, column| {
+        rows.iter()
+            .flat_map(|row| {
+                column.iter().map(move |value| {
+                    let mut row = row.clone();
+                    row.push(value.clone());
+                    row
+                })
+            })
+            .collect()
+    })
+}
+
+fn type_name(type_id: TypeId, registry: &TypeRegistry) -> String {
+    match registry.get(type_id) {
+        Some(LmType::Scalar(_)) if type_id == TypeId::F32 => "F32".into(),
+        Some(LmType::Scalar(_)) if type_id == TypeId::F64 => "F64".into(),
+        Some(LmType::Struct(def)) => def.name.clone(),
+        Some(LmType::Enum(def)) => def.name.clone(),
+        Some(LmType::Array { .. }) => format!("array {}", type_id.0),
+        Some(LmType::Pointer { .. }) => "pointer".into(),
+        Some(LmType::Function { .. }) => "function".into(),
+        Some(LmType::Never) => "Never".into(),
+        _ => format!("type {}", type_id.0),
+    }
+}
+
+/// Integer ranges implied by preconditions of the form `param <op> const`
+/// (either operand order), keyed by parameter index. All preconditions must
+/// hold at entry, so intersecting them only drops inputs outside the
+/// function's domain.
+fn precondition_bounds(graph: &ProgramGraph, func_id: FunctionId) -> HashMap<u32, (i64, i64)> {
+    let mut bounds: HashMap<u32, (i64, i64)> = HashMap::new();
+    for precondition in find_contract_nodes(graph, func_id, ContractKind::Precondition) {
+        let Some(compare) = data_input(graph, precondition, 0) else {
+            continue;
+        };
+        let Some(ComputeNodeOp::Core(ComputeOp::Compare { op })) =
+            graph.get_compute_node(compare).map(|n| &n.op)
+        else {
+            continue;
+        };
+        let (Some(lhs), Some(rhs)) = (data_input(graph, compare, 0), data_input(graph, compare, 1))
+        else {
+            continue;
+        };
+        let (param, op, konst) = match (parameter_index(graph, lhs), const_int(graph, rhs)) {
+            (Some(param), Some(konst)) => (param, *op, konst),
+            _ => match (const_int(graph, lhs), parameter_index(graph, rhs)) {
+                // `c < p` is `p > c`
+                (Some(konst), Some(param)) => (param, flip(*op), konst),
+                _ => continue,
+            },
+        };
+        let Some(full) = graph
+            .get_function(func_id)
+            .and_then(|f| f.params.get(param as usize))
+            .and_then(|(_, ty)| int_range(*ty))
+        else {
+            continue;
+        };
+        let range = match op {
+            CmpOp::Eq => (konst, konst),
+            CmpOp::Lt => (full.0, konst.saturating_sub(1)),
+            CmpOp::Le => (full.0, konst),
+            CmpOp::Gt => (konst.saturating_add(1), full.1),
+            CmpOp::Ge => (konst, full.1),
+            CmpOp::Ne => continue,
+        };
+        let entry = bounds.entry(param).or_insert(full);
+        *entry = (entry.0.max(range.0), entry.1.min(range.1));
+    }
+    bounds
+}
+
+fn flip(op: CmpOp) -> CmpOp {
+    match op {
+        CmpOp::Lt => CmpOp::Gt,
+        CmpOp::Le => CmpOp::Ge,
+        CmpOp::Gt => CmpOp::Lt,
+        CmpOp::Ge => CmpOp::Le,
+        other => other,
+    }
+}
+
+/// The node feeding `port` of `node` through a data edge.
+fn data_input(graph: &ProgramGraph, node: NodeId, port: u16) -> Option<NodeId> {
+    graph
+        .compute()
+        .edges_directed(node.into(), Direction::Incoming)
+        .find(|edge| matches!(edge.weight(), FlowEdge::Data { target_port, .. } if *target_port == port))
+        .map(|edge| NodeId::from(edge.source()))
+}
+
+fn parameter_index(graph: &ProgramGraph, node: NodeId) -> Option<u32> {
+    match graph.get_compute_node(node)?.op {
+        ComputeNodeOp::Core(ComputeOp::Parameter { index }) => Some(index),
+        _ => None,
+    }
+}
+
+fn const_int(graph: &ProgramGraph, node: NodeId) -> Option<i64> {
+    match &graph.get_compute_node(node)?.op {
+        ComputeNodeOp::Core(ComputeOp::Const { value }) => match value {
+            ConstValue::I8(n) => Some(*n as i64),
+            ConstValue::I16(n) => Some(*n as i64),
+            ConstValue::I32(n) => Some(*n as i64),
+            ConstValue::I64(n) => Some(*n),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+    use lmlang_core::types::{EnumDef, EnumVariant, Visibility};
+
+    /// Helper: build `f(a: ty, flag: bool) -> ty` returning `a`, with an
+    /// optional precondition `a <pre> c` and postcondition `a <post> c`.
+    fn build_function(
+        ty: TypeId,
+        pre: Option<(CmpOp, ConstValue)>,
+        post: Option<(CmpOp, ConstValue)>,
+    ) -> (ProgramGraph, FunctionId) {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function(
+                "f".into(),
+                root,
+                vec![("a".into(), ty), ("flag".into(), TypeId::BOOL)],
+                ty,
+                Visibility::Public,
+            )
+            .unwrap();
+        let param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func_id)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+        graph.add_data_edge(param, ret, 0, 0, ty).unwrap();
+
+        for (contract, condition) in [
+            (
+                ComputeOp::Precondition {
+                    message: "pre".into(),
+                },
+                pre,
+            ),
+            (
+                ComputeOp::Postcondition {
+                    message: "post".into(),
+                },
+                post,
+            ),
+        ] {
+            let Some((op, value)) = condition else {
+                continue;
+            };
+            let konst = graph
+                .add_core_op(ComputeOp::Const { value }, func_id)
+                .unwrap();
+            let cmp = graph
+                .add_core_op(ComputeOp::Compare { op }, func_id)
+                .unwrap();
+            graph.add_data_edge(param, cmp, 0, 0, ty).unwrap();
+            graph.add_data_edge(konst, cmp, 0, 1, ty).unwrap();
+            let node = graph.add_core_op(contract, func_id).unwrap();
+            graph.add_data_edge(cmp, node, 0, 0, TypeId::BOOL).unwrap();
+            // Contracts are checked before the function completes
+            graph.add_control_edge(node, ret, None).unwrap();
+        }
+
+        (graph, func_id)
+    }
+
+    fn config(bounds: Vec<Option<(i64, i64)>>) -> ExhaustiveConfig {
+        ExhaustiveConfig {
+            bounds,
+            max_cases: DEFAULT_MAX_CASES,
+            random_seed: 0,
+            limits: ExecutionLimits::default(),
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
+        }
+    }
+
+    #[test]
+    fn small_domain_is_proved() {
+        let (graph, func_id) =
+            build_function(TypeId::I8, None, Some((CmpOp::Le, ConstValue::I8(i8::MAX))));
+        let result = verify_exhaustively(&graph, func_id, &config(vec![])).unwrap();
+        assert!(result.proved());
+        assert_eq!(result.domain_size, 256 * 2);
+        assert_eq!(result.checked, 512);
+        assert_eq!(result.excluded, 0);
+    }
+
+    #[test]
+    fn counterexample_is_smallest_failing_input() {
+        let (graph, func_id) = build_function(
+            TypeId::I8,
+            Some((CmpOp::Ge, ConstValue::I8(-20))),
+            Some((CmpOp::Lt, ConstValue::I8(10))),
+        );
+        let result = verify_exhaustively(&graph, func_id, &config(vec![])).unwrap();
+
+        // The precondition narrows `a` to -20..=127
+        assert_eq!(result.domain_size, 148 * 2);
+        let failure = result
+            .counterexample
+            .expect("a = 10 violates the postcondition");
+        assert_eq!(failure.inputs, vec![Value::I8(10), Value::Bool(false)]);
+        assert_eq!(failure.violation.kind, ContractKind::Postcondition);
+        assert!(!failure.trace.is_empty());
+    }
+
+    #[test]
+    fn wide_integers_need_bounds_within_budget() {
+        let (graph, func_id) = build_function(
+            TypeId::I32,
+            Some((CmpOp::Ge, ConstValue::I32(0))),
+            Some((CmpOp::Lt, ConstValue::I32(1000))),
+        );
+
+        let err = verify_exhaustively(&graph, func_id, &config(vec![])).unwrap_err();
+        assert!(matches!(
+            err,
+            ExhaustiveError::BudgetExceeded { domain_size, .. } if domain_size == (1u128 << 32)
+        ));
+
+        // The box is intersected with the precondition: 0..=500
+        let result =
+            verify_exhaustively(&graph, func_id, &config(vec![Some((-500, 500))])).unwrap();
+        assert!(result.proved());
+        assert_eq!(result.domain_size, 501 * 2);
+
+        let err =
+            verify_exhaustively(&graph, func_id, &config(vec![None, Some((0, 1))])).unwrap_err();
+        assert!(matches!(
+            err,
+            ExhaustiveError::InvalidBound { param: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn excluded_inputs_do_not_count_as_checked() {
+        // `a != 0` cannot narrow the range, so a = 0 is excluded at runtime
+        let (graph, func_id) =
+            build_function(TypeId::I8, Some((CmpOp::Ne, ConstValue::I8(0))), None);
+        let result = verify_exhaustively(&graph, func_id, &config(vec![])).unwrap();
+        assert!(result.proved());
+        assert_eq!(result.excluded, 2);
+        assert_eq!(result.checked, 510);
+    }
+
+    #[test]
+    fn unfinished_runs_are_unsettled_not_proved() {
+        let (graph, func_id) =
+            build_function(TypeId::I8, None, Some((CmpOp::Le, ConstValue::I8(i8::MAX))));
+        let mut config = config(vec![]);
+        config.limits.max_steps = Some(1);
+        let result = verify_exhaustively(&graph, func_id, &config).unwrap();
+
+        assert!(!result.proved());
+        assert!(result.counterexample.is_none());
+        assert_eq!(result.checked, 0);
+        assert_eq!(result.unsettled, 512);
+        let unsettled = result
+            .first_unsettled
+            .expect("every run hits the step limit");
+        assert_eq!(unsettled.inputs, vec![Value::I8(0), Value::Bool(false)]);
+        assert_eq!(unsettled.error.kind(), "step_limit_exceeded");
+    }
+
+    #[test]
+    fn empty_parameter_domain_skips_enumerating_the_others() {
+        // f(a: i8, b: i64) -> i8 { a }
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function(
+                "f".into(),
+                root,
+                vec![("a".into(), TypeId::I8), ("b".into(), TypeId::I64)],
+                TypeId::I8,
+                Visibility::Public,
+            )
+            .unwrap();
+        let param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func_id)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+        graph.add_data_edge(param, ret, 0, 0, TypeId::I8).unwrap();
+
+        // The box for `a` lies outside i8, leaving `b` unbounded
+        let result = verify_exhaustively(&graph, func_id, &config(vec![Some((200, 300))])).unwrap();
+        assert_eq!(result.domain_size, 0);
+        assert_eq!(result.checked, 0);
+        assert!(result.counterexample.is_none());
+    }
+
+    #[test]
+    fn float_parameters_are_not_enumerable() {
+        let (graph, func_id) = build_function(TypeId::F64, None, None);
+        let err = verify_exhaustively(&graph, func_id, &config(vec![])).unwrap_err();
+        assert!(matches!(
+            err,
+            ExhaustiveError::Unenumerable { param: 0, ref type_name } if type_name == "F64"
+        ));
+    }
+
+    #[test]
+    fn enum_domains_cover_every_variant_and_payload() {
+        let mut registry = TypeRegistry::new();
+        let option = registry.register(LmType::Enum(EnumDef {
+            name: "OptionBool".into(),
+            type_id: TypeId(0),
+            variants: IndexMap::from([
+                (
+                    "None".into(),
+                    EnumVariant {
+                        index: 0,
+                        payload: None,
+                    },
+                ),
+                (
+                    "Some".into(),
+                    EnumVariant {
+                        index: 1,
+                        payload: Some(TypeId::BOOL),
+                    },
+                ),
+            ]),
+            module: lmlang_core::id::ModuleId(0),
+            visibility: Visibility::Public,
+        }));
+        let pair = registry.register(LmType::Array {
+            element: option,
+            length: 2,
+        });
+
+        assert_eq!(domain_len(option, &registry, None), Some(3));
+        assert_eq!(domain_len(pair, &registry, None), Some(9));
+        let values = domain(option, &registry, None).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                Value::Enum {
+                    ty: option,
+                    variant: 0,
+                    payload: Box::new(Value::Unit)
+                },
+                Value::Enum {
+                    ty: option,
+                    variant: 1,
+                    payload: Box::new(Value::Bool(false))
+                },
+                Value::Enum {
+                    ty: option,
+                    variant: 1,
+                    payload: Box::new(Value::Bool(true))
+                },
+            ]
+        );
+        assert_eq!(domain(pair, &registry, None).unwrap().len(), 9);
+    }
+}
@@ -5,6 +5,8 @@
 //! Violations produce structured diagnostics with counterexample values.
 
 pub mod check;
+pub mod exhaustive;
+pub mod mutation;
 pub mod property;
 
 use lmlang_core::id::{FunctionId, NodeId};
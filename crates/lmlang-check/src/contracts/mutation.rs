@@ -0,0 +1,707 @@
+//! Mutation testing: scores how well contracts and tests catch bugs.
+//!
+//! A mutant is a copy of the program with one small semantic change in the
+//! tested function: an `ArithOp` swapped, a `CmpOp` shifted across its
+//! boundary or negated, or a `Const` perturbed by one. Each mutant is run
+//! against the function's property tests and the program's test node cases.
+//! A mutant is *killed* if it makes a contract or case fail that passes on the
+//! original program, and *survives* otherwise. The mutation score is the share
+//! of mutants killed; survivors point at behavior no spec pins down.
+//!
+//! Nodes that only feed contracts (conditions, contract expressions and the
+//! checks themselves) are never mutated: changing a spec is not a bug in the
+//! code it specifies.
+//!
+//! Property tests run without shrinking, and runtime errors count as passes,
+//! as in [`run_property_tests`]. A mutant that only makes the function trap
+//! is therefore killed only by a test case expecting a value. A mutant that
+//! exceeds an execution limit the original stays within (typically one that
+//! no longer terminates) is killed by that limit; its remaining property
+//! cases are skipped. Without a step limit in the config, each run gets
+//! [`DEFAULT_MUTANT_MAX_STEPS`] so a diverging mutant cannot hang the run.
+
+use std::collections::HashSet;
+
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::Serialize;
+
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::ops::{ArithOp, CmpOp, ComputeNodeOp, ComputeOp};
+use lmlang_core::types::ConstValue;
+
+use crate::contracts::property::{run_property_tests, GeneratorLimits, PropertyTestConfig};
+use crate::interpreter::bytecode::BytecodeCache;
+use crate::interpreter::error::RuntimeError;
+use crate::interpreter::state::ExecutionLimits;
+use crate::interpreter::value::Value;
+use crate::interpreter::vm::Engine;
+use crate::test_runner::{run_test_suites, TestRunConfig};
+
+/// Default number of random property test iterations per mutant.
+pub const DEFAULT_MUTATION_ITERATIONS: u32 = 100;
+
+/// Default cap on the number of mutants run.
+pub const DEFAULT_MAX_MUTANTS: u32 = 200;
+
+/// Step budget per run when the config sets no step limit.
+pub const DEFAULT_MUTANT_MAX_STEPS: u64 = 1_000_000;
+
+/// One semantic change to a node's operation.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Mutation {
+    /// A binary arithmetic operator replaced by another.
+    ArithSwap { from: ArithOp, to: ArithOp },
+    /// A comparison shifted across its boundary or negated.
+    CmpFlip { from: CmpOp, to: CmpOp },
+    /// A constant nudged to a neighboring value.
+    ConstPerturb { from: ConstValue, to: ConstValue },
+}
+
+/// A mutation applied to one node of the tested function.
+#[derive(Debug, Clone, Serialize)]
+pub struct Mutant {
+    /// Position in the generated mutant list.
+    pub id: u32,
+    /// The mutated node.
+    pub node_id: NodeId,
+    /// What was changed.
+    pub mutation: Mutation,
+}
+
+impl Mutant {
+    /// Human-readable summary, e.g. `node 4: Add -> Sub`.
+    pub fn description(&self) -> String {
+        let change = match &self.mutation {
+            Mutation::ArithSwap { from, to } => format!("{:?} -> {:?}", from, to),
+            Mutation::CmpFlip { from, to } => format!("{:?} -> {:?}", from, to),
+            Mutation::ConstPerturb { from, to } => format!("{:?} -> {:?}", from, to),
+        };
+        format!("node {}: {}", self.node_id.0, change)
+    }
+
+    /// A copy of `graph` with this mutation applied.
+    pub fn apply(&self, graph: &ProgramGraph) -> ProgramGraph {
+        let op = match &self.mutation {
+            Mutation::ArithSwap { to, .. } => ComputeOp::BinaryArith { op: *to },
+            Mutation::CmpFlip { to, .. } => ComputeOp::Compare { op: *to },
+            Mutation::ConstPerturb { to, .. } => ComputeOp::Const { value: to.clone() },
+        };
+        let mut mutated = graph.clone();
+        mutated
+            .modify_compute_node_op(self.node_id, ComputeNodeOp::Core(op))
+            .expect("mutants target existing nodes");
+        mutated
+    }
+}
+
+/// Generates every mutant of `func_id`, ordered by node ID.
+///
+/// Nodes that only feed contracts are skipped.
+pub fn generate_mutants(graph: &ProgramGraph, func_id: FunctionId) -> Vec<Mutant> {
+    let spec = spec_nodes(graph, func_id);
+    let mut nodes: Vec<NodeId> = graph
+        .function_nodes(func_id)
+        .into_iter()
+        .filter(|node_id| !spec.contains(node_id))
+        .collect();
+    nodes.sort_by_key(|node_id| node_id.0);
+
+    let mut mutants = Vec::new();
+    for node_id in nodes {
+        let Some(ComputeNodeOp::Core(op)) = graph.get_compute_node(node_id).map(|n| &n.op) else {
+            continue;
+        };
+        for mutation in mutations_of(op) {
+            mutants.push(Mutant {
+                id: mutants.len() as u32,
+                node_id,
+                mutation,
+            });
+        }
+    }
+    mutants
+}
+
+/// The mutations applicable to one operation.
+fn mutations_of(op: &ComputeOp) -> Vec<Mutation> {
+    match op {
+        ComputeOp::BinaryArith { op: from } => {
+            let to = match from {
+                ArithOp::Add => ArithOp::Sub,
+                ArithOp::Sub => ArithOp::Add,
+                ArithOp::Mul => ArithOp::Div,
+                ArithOp::Div => ArithOp::Mul,
+                ArithOp::Rem => ArithOp::Div,
+            };
+            vec![Mutation::ArithSwap { from: *from, to }]
+        }
+        ComputeOp::Compare { op: from } => {
+            // Boundary shift, then negation
+            let targets: &[CmpOp] = match from {
+                CmpOp::Eq => &[CmpOp::Ne],
+                CmpOp::Ne => &[CmpOp::Eq],
+                CmpOp::Lt => &[CmpOp::Le, CmpOp::Ge],
+                CmpOp::Le => &[CmpOp::Lt, CmpOp::Gt],
+                CmpOp::Gt => &[CmpOp::Ge, CmpOp::Le],
+                CmpOp::Ge => &[CmpOp::Gt, CmpOp::Lt],
+            };
+            targets
+                .iter()
+                .map(|to| Mutation::CmpFlip {
+                    from: *from,
+                    to: *to,
+                })
+                .collect()
+        }
+        ComputeOp::Const { value } => perturbations(value)
+            .into_iter()
+            .map(|to| Mutation::ConstPerturb {
+                from: value.clone(),
+                to,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Neighbors of a constant: `n + 1` and `n - 1` (wrapping) for integers,
+/// `x + 1.0` for floats, the negation for booleans.
+fn perturbations(value: &ConstValue) -> Vec<ConstValue> {
+    match value {
+        ConstValue::Bool(b) => vec![ConstValue::Bool(!b)],
+        ConstValue::I8(n) => vec![
+            ConstValue::I8(n.wrapping_add(1)),
+            ConstValue::I8(n.wrapping_sub(1)),
+        ],
+        ConstValue::I16(n) => vec![
+            ConstValue::I16(n.wrapping_add(1)),
+            ConstValue::I16(n.wrapping_sub(1)),
+        ],
+        ConstValue::I32(n) => vec![
+            ConstValue::I32(n.wrapping_add(1)),
+            ConstValue::I32(n.wrapping_sub(1)),
+        ],
+        ConstValue::I64(n) => vec![
+            ConstValue::I64(n.wrapping_add(1)),
+            ConstValue::I64(n.wrapping_sub(1)),
+        ],
+        ConstValue::F32(x) => vec![ConstValue::F32(x + 1.0)],
+        ConstValue::F64(x) => vec![ConstValue::F64(x + 1.0)],
+        ConstValue::Unit => Vec::new(),
+    }
+}
+
+/// Nodes of `func_id` that only feed contracts: the contract-only nodes
+/// plus every node whose successors are all such nodes.
+fn spec_nodes(graph: &ProgramGraph, func_id: FunctionId) -> HashSet<NodeId> {
+    let mut spec = graph.contract_only_nodes(func_id);
+    let nodes = graph.function_nodes(func_id);
+    loop {
+        let before = spec.len();
+        for &node_id in &nodes {
+            if spec.contains(&node_id) {
+                continue;
+            }
+            let mut targets = graph
+                .compute()
+                .edges_directed(node_id.into(), Direction::Outgoing)
+                .map(|edge| NodeId::from(edge.target()))
+                .peekable();
+            if targets.peek().is_some() && targets.all(|target| spec.contains(&target)) {
+                spec.insert(node_id);
+            }
+        }
+        if spec.len() == before {
+            return spec;
+        }
+    }
+}
+
+/// Configuration for a mutation testing run.
+#[derive(Debug, Clone)]
+pub struct MutationConfig {
+    /// Seed inputs for property tests of the mutated function.
+    pub seeds: Vec<Vec<Value>>,
+    /// Random property test iterations per mutant. With no seeds and zero
+    /// iterations only test node cases can kill a mutant.
+    pub iterations: u32,
+    /// Random seed shared by the original and every mutant, so they see
+    /// the same inputs.
+    pub random_seed: u64,
+    /// Test IDs whose cases run against each mutant; empty runs every test
+    /// node.
+    pub test_ids: Vec<String>,
+    /// Maximum number of mutants run; the rest are reported as skipped.
+    pub max_mutants: u32,
+    /// Budgets for each interpreter run; an unset step limit defaults to
+    /// [`DEFAULT_MUTANT_MAX_STEPS`].
+    pub limits: ExecutionLimits,
+    /// Engine running each case.
+    pub engine: Engine,
+    /// Lowered functions reused across runs. Mutants only re-lower the
+    /// mutated function.
+    pub bytecode_cache: BytecodeCache,
+}
+
+impl Default for MutationConfig {
+    fn default() -> Self {
+        MutationConfig {
+            seeds: Vec::new(),
+            iterations: DEFAULT_MUTATION_ITERATIONS,
+            random_seed: 0,
+            test_ids: Vec::new(),
+            max_mutants: DEFAULT_MAX_MUTANTS,
+            limits: ExecutionLimits::default(),
+            engine: Engine::default(),
+            bytecode_cache: BytecodeCache::default(),
+        }
+    }
+}
+
+/// Errors that prevent a mutation testing run.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum MutationError {
+    #[error("function {0} not found")]
+    FunctionNotFound(u32),
+
+    #[error(transparent)]
+    Runtime(#[from] RuntimeError),
+}
+
+/// What happened to one mutant.
+#[derive(Debug, Clone, Serialize)]
+pub struct MutantOutcome {
+    /// The mutant that ran.
+    #[serde(flatten)]
+    pub mutant: Mutant,
+    /// Human-readable summary of the change.
+    pub description: String,
+    /// Whether a contract or test case caught the mutant.
+    pub killed: bool,
+    /// The contracts and cases that newly failed; empty for survivors.
+    pub killed_by: Vec<String>,
+}
+
+/// Result of a mutation testing run.
+#[derive(Debug, Clone, Serialize)]
+pub struct MutationReport {
+    /// The mutated function.
+    pub function_id: FunctionId,
+    /// Mutants run.
+    pub total: u32,
+    /// Mutants caught by a contract or test case.
+    pub killed: u32,
+    /// Mutants no contract or test case caught.
+    pub survived: u32,
+    /// Mutants generated beyond `max_mutants` and not run.
+    pub skipped: u32,
+    /// `killed / total`, or `None` when no mutant ran.
+    pub score: Option<f64>,
+    /// Surviving mutants, in mutant order.
+    pub survivors: Vec<MutantOutcome>,
+    /// Killed mutants, in mutant order.
+    pub killed_mutants: Vec<MutantOutcome>,
+}
+
+/// Failures observed for one version of the program.
+struct Failures {
+    /// Violated contract nodes, with a description.
+    contracts: Vec<(NodeId, String)>,
+    /// Failed test cases as `(test_id, case name)`.
+    cases: Vec<(String, String)>,
+    /// The first property case that exceeded an execution limit.
+    limit: Option<String>,
+}
+
+/// Generates the mutants of `func_id`, runs each against the property
+/// tests and test node cases, and reports which ones survive.
+pub fn run_mutation_tests(
+    graph: &ProgramGraph,
+    func_id: FunctionId,
+    config: &MutationConfig,
+) -> Result<MutationReport, MutationError> {
+    if graph.get_function(func_id).is_none() {
+        return Err(MutationError::FunctionNotFound(func_id.0));
+    }
+
+    let baseline = collect_failures(graph, func_id, config)?;
+    let mutants = generate_mutants(graph, func_id);
+    let skipped = mutants.len().saturating_sub(config.max_mutants as usize) as u32;
+
+    let mut survivors = Vec::new();
+    let mut killed_mutants = Vec::new();
+    for mutant in mutants.into_iter().take(config.max_mutants as usize) {
+        let failures = collect_failures(&mutant.apply(graph), func_id, config)?;
+        let killed_by: Vec<String> = failures
+            .contracts
+            .into_iter()
+            .filter(|(node, _)| !baseline.contracts.iter().any(|(b, _)| b == node))
+            .map(|(_, description)| description)
+            .chain(
+                failures
+                    .cases
+                    .into_iter()
+                    .filter(|case| !baseline.cases.contains(case))
+                    .map(|(test_id, case)| format!("test '{}' {}", test_id, case)),
+            )
+            .chain(failures.limit.filter(|_| baseline.limit.is_none()))
+            .collect();
+        let outcome = MutantOutcome {
+            description: mutant.description(),
+            mutant,
+            killed: !killed_by.is_empty(),
+            killed_by,
+        };
+        if outcome.killed {
+            killed_mutants.push(outcome);
+        } else {
+            survivors.push(outcome);
+        }
+    }
+
+    let killed = killed_mutants.len() as u32;
+    let total = killed + survivors.len() as u32;
+    Ok(MutationReport {
+        function_id: func_id,
+        total,
+        killed,
+        survived: survivors.len() as u32,
+        skipped,
+        score: (total > 0).then(|| killed as f64 / total as f64),
+        survivors,
+        killed_mutants,
+    })
+}
+
+/// Runs the property tests and test cases against one version of the
+/// program and collects what failed.
+fn collect_failures(
+    graph: &ProgramGraph,
+    func_id: FunctionId,
+    config: &MutationConfig,
+) -> Result<Failures, RuntimeError> {
+    let limits = ExecutionLimits {
+        max_steps: config.limits.max_steps.or(Some(DEFAULT_MUTANT_MAX_STEPS)),
+        ..config.limits
+    };
+    let mut contracts = Vec::new();
+    let mut limit = None;
+    if !config.seeds.is_empty() || config.iterations > 0 {
+        let result = run_property_tests(
+            graph,
+            func_id,
+            PropertyTestConfig {
+                seeds: config.seeds.clone(),
+                iterations: config.iterations,
+                random_seed: config.random_seed,
+                max_shrink_runs: 0,
+                generator: GeneratorLimits::default(),
+                limits,
+                // One limit hit already decides the outcome
+                stop_on_unsettled: true,
+                engine: config.engine,
+                bytecode_cache: config.bytecode_cache.clone(),
+            },
+        )?;
+        contracts = result
+            .failures
+            .iter()
+            .map(|failure| {
+                let violation = &failure.violation;
+                let kind = format!("{:?}", violation.kind).to_lowercase();
+                (
+                    violation.contract_node,
+                    format!(
+                        "{} {} ('{}')",
+                        kind, violation.contract_node.0, violation.message
+                    ),
+                )
+            })
+            .collect();
+        limit = result
+            .unsettled_runs
+            .first()
+            .map(|run| format!("{} on inputs {:?}", run.error, run.inputs));
+    }
+
+    let report = run_test_suites(
+        graph,
+        &TestRunConfig {
+            test_ids: config.test_ids.clone(),
+            random_seed: config.random_seed,
+            limits,
+            engine: config.engine,
+            bytecode_cache: config.bytecode_cache.clone(),
+        },
+    );
+    let cases = report
+        .tests
+        .iter()
+        .flat_map(|test| {
+            test.cases
+                .iter()
+                .filter(|case| !case.passed)
+                .map(|case| (test.test_id.clone(), case.name.clone()))
+        })
+        .collect();
+
+    Ok(Failures {
+        contracts,
+        cases,
+        limit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lmlang_core::node::{TestCase, TestExpectation};
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::Visibility;
+    use serde_json::json;
+
+    /// Builds `inc(x: i32) -> i32` returning `x + 1` and ensuring the result
+    /// is greater than `x`.
+    fn build_inc() -> (ProgramGraph, FunctionId) {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function(
+                "inc".into(),
+                root,
+                vec![("x".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+
+        let x = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func_id)
+            .unwrap();
+        let one = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: ConstValue::I32(1),
+                },
+                func_id,
+            )
+            .unwrap();
+        let add = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Add }, func_id)
+            .unwrap();
+        let gt = graph
+            .add_core_op(ComputeOp::Compare { op: CmpOp::Gt }, func_id)
+            .unwrap();
+        let post = graph
+            .add_core_op(
+                ComputeOp::Postcondition {
+                    message: "result > x".into(),
+                },
+                func_id,
+            )
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+
+        graph.add_data_edge(x, add, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(one, add, 0, 1, TypeId::I32).unwrap();
+        graph.add_data_edge(add, gt, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(x, gt, 0, 1, TypeId::I32).unwrap();
+        graph.add_data_edge(gt, post, 0, 0, TypeId::BOOL).unwrap();
+        graph.add_data_edge(add, ret, 0, 0, TypeId::I32).unwrap();
+        graph.add_control_edge(post, ret, None).unwrap();
+
+        (graph, func_id)
+    }
+
+    fn config() -> MutationConfig {
+        MutationConfig {
+            iterations: 50,
+            random_seed: 7,
+            ..MutationConfig::default()
+        }
+    }
+
+    #[test]
+    fn mutants_skip_nodes_that_only_feed_contracts() {
+        let (graph, func_id) = build_inc();
+        let mutants = generate_mutants(&graph, func_id);
+        let descriptions: Vec<String> = mutants.iter().map(Mutant::description).collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "node 1: I32(1) -> I32(2)",
+                "node 1: I32(1) -> I32(0)",
+                "node 2: Add -> Sub",
+            ]
+        );
+
+        let mutated = mutants[2].apply(&graph);
+        assert!(matches!(
+            mutated.get_compute_node(NodeId(2)).map(|n| &n.op),
+            Some(ComputeNodeOp::Core(ComputeOp::BinaryArith {
+                op: ArithOp::Sub
+            }))
+        ));
+        // The original is untouched
+        assert!(matches!(
+            graph.get_compute_node(NodeId(2)).map(|n| &n.op),
+            Some(ComputeNodeOp::Core(ComputeOp::BinaryArith {
+                op: ArithOp::Add
+            }))
+        ));
+    }
+
+    #[test]
+    fn contracts_kill_mutants_that_break_them() {
+        let (graph, func_id) = build_inc();
+        let report = run_mutation_tests(&graph, func_id, &config()).unwrap();
+
+        assert_eq!(report.total, 3);
+        assert_eq!(report.killed, 2);
+        assert_eq!(report.score, Some(2.0 / 3.0));
+        // x + 2 still satisfies `result > x`
+        assert_eq!(report.survivors.len(), 1);
+        assert_eq!(report.survivors[0].description, "node 1: I32(1) -> I32(2)");
+        assert!(report.survivors[0].killed_by.is_empty());
+        for killed in &report.killed_mutants {
+            assert_eq!(killed.killed_by, vec!["postcondition 4 ('result > x')"]);
+        }
+    }
+
+    #[test]
+    fn test_cases_kill_survivors_and_baseline_failures_are_ignored() {
+        let (mut graph, func_id) = build_inc();
+        let root = graph.modules.root_id();
+        let case = |name: &str, expected: i32| TestCase {
+            name: name.into(),
+            inputs: vec![json!({ "I32": 5 })],
+            expect: TestExpectation::Returns {
+                value: json!({ "I32": expected }),
+            },
+            expected_io: None,
+        };
+        graph
+            .add_test_suite(
+                root,
+                "T-INC".into(),
+                "increment".into(),
+                Some(func_id),
+                vec![case("five", 6), case("wrong", 100)],
+            )
+            .unwrap();
+
+        let report = run_mutation_tests(&graph, func_id, &config()).unwrap();
+        assert_eq!(report.killed, 3);
+        assert_eq!(report.score, Some(1.0));
+        let plus_two = &report.killed_mutants[0];
+        assert_eq!(plus_two.killed_by, vec!["test 'T-INC' five"]);
+
+        // Without property tests only the cases can kill
+        let cases_only = MutationConfig {
+            iterations: 0,
+            ..config()
+        };
+        let report = run_mutation_tests(&graph, func_id, &cases_only).unwrap();
+        assert_eq!(report.killed, 3);
+    }
+
+    #[test]
+    fn mutants_exceeding_a_limit_are_killed() {
+        // down(n) = if n <= 0 { 0 } else { down(n - 1) }
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function(
+                "down".into(),
+                root,
+                vec![("n".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let n = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func_id)
+            .unwrap();
+        let konst = |graph: &mut ProgramGraph, value: i32| {
+            graph
+                .add_core_op(
+                    ComputeOp::Const {
+                        value: ConstValue::I32(value),
+                    },
+                    func_id,
+                )
+                .unwrap()
+        };
+        let zero = konst(&mut graph, 0);
+        let one = konst(&mut graph, 1);
+        let le = graph
+            .add_core_op(ComputeOp::Compare { op: CmpOp::Le }, func_id)
+            .unwrap();
+        graph.add_data_edge(n, le, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(zero, le, 0, 1, TypeId::I32).unwrap();
+        let branch = graph.add_core_op(ComputeOp::Branch, func_id).unwrap();
+        graph.add_data_edge(le, branch, 0, 0, TypeId::BOOL).unwrap();
+        let base = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+        graph.add_data_edge(zero, base, 0, 0, TypeId::I32).unwrap();
+        graph.add_control_edge(branch, base, Some(0)).unwrap();
+        let sub = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Sub }, func_id)
+            .unwrap();
+        graph.add_data_edge(n, sub, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(one, sub, 0, 1, TypeId::I32).unwrap();
+        graph.add_control_edge(branch, sub, Some(1)).unwrap();
+        let call = graph
+            .add_core_op(ComputeOp::Call { target: func_id }, func_id)
+            .unwrap();
+        graph.add_data_edge(sub, call, 0, 0, TypeId::I32).unwrap();
+        let recurse = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+        graph
+            .add_data_edge(call, recurse, 0, 0, TypeId::I32)
+            .unwrap();
+
+        let config = MutationConfig {
+            seeds: vec![vec![Value::I32(3)]],
+            iterations: 0,
+            ..config()
+        };
+        let report = run_mutation_tests(&graph, func_id, &config).unwrap();
+
+        // `n - 0` and `n + 1` never reach the base case
+        let diverging: Vec<&str> = report
+            .killed_mutants
+            .iter()
+            .map(|m| m.description.as_str())
+            .collect();
+        assert_eq!(
+            diverging,
+            vec!["node 2: I32(1) -> I32(0)", "node 6: Sub -> Add"]
+        );
+        for mutant in &report.killed_mutants {
+            assert_eq!(mutant.killed_by.len(), 1);
+            assert!(
+                mutant.killed_by[0].starts_with("recursion depth limit"),
+                "{:?}",
+                mutant.killed_by
+            );
+        }
+    }
+
+    #[test]
+    fn max_mutants_caps_the_run() {
+        let (graph, func_id) = build_inc();
+        let capped = MutationConfig {
+            max_mutants: 1,
+            ..config()
+        };
+        let report = run_mutation_tests(&graph, func_id, &capped).unwrap();
+        assert_eq!((report.total, report.skipped), (1, 2));
+
+        assert!(matches!(
+            run_mutation_tests(&graph, FunctionId(99), &capped),
+            Err(MutationError::FunctionNotFound(99))
+        ));
+    }
+}
@@ -5,22 +5,50 @@
 //! runs each through the interpreter, and collects contract violations with
 //! full execution traces.
 //!
+//! Random inputs are type-directed: structs, enums and arrays are generated by
+//! walking their definitions in the [`TypeRegistry`], bounded by
+//! [`GeneratorLimits`]. Random inputs that violate one of the tested function's
+//! own `Precondition` nodes are rejected and redrawn rather than reported, since
+//! they are outside the function's domain. Seeds always run as given.
+//!
+//! Runtime errors are not contract violations and count as passes, except
+//! when the run exceeded one of its [`ExecutionLimits`]: such a run is
+//! unsettled, since the function might still have violated a contract had
+//! it been allowed to finish.
+//!
+//! Failures are deduplicated by contract node: the first failing input for each
+//! contract is kept and shrunk toward a minimal input (toward 0, smaller
+//! magnitudes, fewer array elements, earlier enum variants) for as long as the
+//! same contract node still fails. Later failures of the same contract only
+//! bump its occurrence count.
+//!
 //! Reproducibility: given the same `random_seed`, the same inputs are generated
-//! and the same test results are produced.
+//! and the same test results are produced. `Random` ops inside each test case
+//! draw from a seed derived from `random_seed` and the case's position (see
+//! [`case_seed`]), so cases see different random streams, and `Now` reads the
+//! default virtual clock. A failure records its case's seed, and replays
+//! identically in a simulate run with that seed.
+//!
+//! Cases run on the bytecode VM by default (see [`Engine`]), which reports
+//! the same results and traces as the reference interpreter.
 
 use rand::Rng;
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
 use lmlang_core::graph::ProgramGraph;
-use lmlang_core::id::FunctionId;
-use lmlang_core::type_id::TypeId;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::type_id::{TypeId, TypeRegistry};
+use lmlang_core::types::LmType;
 
-use crate::contracts::ContractViolation;
+use crate::contracts::{ContractKind, ContractViolation};
+use crate::interpreter::bytecode::BytecodeCache;
+use crate::interpreter::coverage::Coverage;
 use crate::interpreter::error::RuntimeError;
-use crate::interpreter::state::{ExecutionState, Interpreter, InterpreterConfig};
+use crate::interpreter::state::{ExecutionLimits, ExecutionState, InterpreterConfig};
 use crate::interpreter::trace::TraceEntry;
 use crate::interpreter::value::Value;
+use crate::interpreter::vm::{Engine, Executor};
 
 /// Configuration for a property test run.
 #[derive(Debug, Clone)]
@@ -31,8 +59,61 @@ pub struct PropertyTestConfig {
     pub iterations: u32,
     /// Random seed for reproducibility (system generates if not provided).
     pub random_seed: u64,
+    /// Maximum number of extra interpreter runs spent shrinking each
+    /// distinct failure. Zero disables shrinking.
+    pub max_shrink_runs: u32,
+    /// Size bounds for generated compound values.
+    pub generator: GeneratorLimits,
+    /// Budgets for each interpreter run. A run that exceeds one is reported
+    /// as unsettled rather than passed.
+    pub limits: ExecutionLimits,
+    /// Skip the remaining cases after the first unsettled run.
+    pub stop_on_unsettled: bool,
+    /// Engine running each case.
+    pub engine: Engine,
+    /// Lowered functions reused across property test runs.
+    pub bytecode_cache: BytecodeCache,
+}
+
+/// Bounds on type-directed value generation.
+#[derive(Debug, Clone, Copy)]
+pub struct GeneratorLimits {
+    /// Maximum nesting depth of structs, enums and arrays. Deeper values are
+    /// zero-filled within a further `max_depth` levels; a type with no zero
+    /// value that shallow (e.g. a recursive definition) is not generated.
+    pub max_depth: u32,
+    /// Maximum number of scalar leaves drawn at random per value; the rest
+    /// of a large value is zero-filled.
+    pub max_leaves: u32,
+}
+
+impl Default for GeneratorLimits {
+    fn default() -> Self {
+        GeneratorLimits {
+            max_depth: 8,
+            max_leaves: 256,
+        }
+    }
+}
+
+/// Seed for the `Random` ops of the `case`-th run of a property test seeded
+/// with `random_seed` (a SplitMix64 step, so neighbouring cases diverge).
+pub fn case_seed(random_seed: u64, case: u32) -> u64 {
+    let mut z = random_seed.wrapping_add((case as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
 }
 
+/// Redraws allowed per random case before it is skipped as unsatisfiable.
+pub const MAX_REJECTIONS_PER_CASE: u32 = 100;
+
+/// Default shrink budget per distinct failure.
+pub const DEFAULT_MAX_SHRINK_RUNS: u32 = 500;
+
+/// Unsettled runs whose inputs are kept in a [`PropertyTestResult`].
+pub const MAX_UNSETTLED_REPORTED: usize = 10;
+
 /// Result of a property test run.
 #[derive(Debug, Clone)]
 pub struct PropertyTestResult {
@@ -40,29 +121,190 @@ pub struct PropertyTestResult {
     pub total_run: u32,
     /// Number of passing tests.
     pub passed: u32,
-    /// All failures with full details.
+    /// Tests stopped by an execution limit before they finished.
+    pub unsettled: u32,
+    /// The first [`MAX_UNSETTLED_REPORTED`] unsettled tests.
+    pub unsettled_runs: Vec<UnsettledRun>,
+    /// Random inputs rejected for violating the function's own preconditions.
+    pub rejected: u32,
+    /// Random cases skipped, and not run, because all
+    /// [`MAX_REJECTIONS_PER_CASE`] of their draws were rejected.
+    pub skipped: u32,
+    /// One failure per violated contract node, in order of first occurrence.
     pub failures: Vec<PropertyTestFailure>,
     /// The random seed used (for reproducibility).
     pub random_seed: u64,
+    /// Node and branch coverage over the seed and random cases run (not
+    /// rejected inputs or shrinking).
+    pub coverage: Coverage,
 }
 
-/// A single property test failure with counterexample and trace.
+/// A contract's failure with its original and shrunk counterexamples.
 #[derive(Debug, Clone)]
 pub struct PropertyTestFailure {
-    /// The inputs that caused the failure.
+    /// The first generated (or seed) inputs that caused the failure.
     pub inputs: Vec<Value>,
-    /// The contract violation that occurred.
+    /// The minimal inputs found that still violate the same contract node.
+    pub shrunk_inputs: Vec<Value>,
+    /// Number of shrink steps accepted on the way to `shrunk_inputs`.
+    pub shrink_steps: u32,
+    /// Number of test cases that violated this contract node.
+    pub occurrences: u32,
+    /// Seed of the failing case's `Random` ops, used again for shrinking.
+    pub case_seed: u64,
+    /// The contract violation produced by `shrunk_inputs`.
     pub violation: ContractViolation,
-    /// Execution trace for this test case.
+    /// Execution trace for `shrunk_inputs`.
     pub trace: Vec<TraceEntry>,
 }
 
+/// A run that stopped on a runtime error or an exhausted budget before its
+/// contracts could all be checked.
+#[derive(Debug, Clone)]
+pub struct UnsettledRun {
+    /// The inputs of the run.
+    pub inputs: Vec<Value>,
+    /// The error that stopped it.
+    pub error: RuntimeError,
+}
+
 /// Generates a random value of the given type using the provided RNG.
 ///
-/// For scalar types, boundary values (0, 1, -1, MIN, MAX) are weighted
-/// into the mix to increase edge-case coverage.
-pub fn generate_random_value(type_id: TypeId, rng: &mut ChaCha8Rng) -> Value {
-    match type_id {
+/// Scalars weight boundary values (0, 1, -1, MIN, MAX) into the mix to
+/// increase edge-case coverage. Structs, enums and arrays are built by walking
+/// their registry definitions within `limits`. Returns `None` for types that
+/// cannot be generated from nothing (pointers, functions, `Never`).
+pub fn generate_random_value(
+    type_id: TypeId,
+    registry: &TypeRegistry,
+    limits: &GeneratorLimits,
+    rng: &mut ChaCha8Rng,
+) -> Option<Value> {
+    let mut leaves = limits.max_leaves;
+    generate_at_depth(
+        type_id,
+        registry,
+        limits,
+        limits.max_depth,
+        &mut leaves,
+        rng,
+    )
+}
+
+/// Walks `type_id`, drawing scalars while `leaves` remain and zero-filling
+/// once `depth` or `leaves` run out.
+fn generate_at_depth(
+    type_id: TypeId,
+    registry: &TypeRegistry,
+    limits: &GeneratorLimits,
+    depth: u32,
+    leaves: &mut u32,
+    rng: &mut ChaCha8Rng,
+) -> Option<Value> {
+    match registry.get(type_id)? {
+        LmType::Scalar(_) | LmType::Unit => {
+            if *leaves == 0 {
+                return zero_value(type_id, registry, limits);
+            }
+            *leaves -= 1;
+            generate_scalar(type_id, rng)
+        }
+        _ if depth == 0 => zero_value(type_id, registry, limits),
+        LmType::Array { element, length } => (0..*length)
+            .map(|_| generate_at_depth(*element, registry, limits, depth - 1, leaves, rng))
+            .collect::<Option<Vec<_>>>()
+            .map(|elements| Value::Array {
+                ty: type_id,
+                elements,
+            }),
+        LmType::Struct(def) => def
+            .fields
+            .values()
+            .map(|field| generate_at_depth(*field, registry, limits, depth - 1, leaves, rng))
+            .collect::<Option<Vec<_>>>()
+            .map(|fields| Value::Struct {
+                ty: type_id,
+                fields,
+            }),
+        LmType::Enum(def) => {
+            if def.variants.is_empty() {
+                return None;
+            }
+            let index = rng.gen_range(0..def.variants.len());
+            let variant = &def.variants[index];
+            let payload = match variant.payload {
+                Some(payload) => {
+                    generate_at_depth(payload, registry, limits, depth - 1, leaves, rng)?
+                }
+                None => Value::Unit,
+            };
+            Some(Value::Enum {
+                ty: type_id,
+                variant: variant.index,
+                payload: Box::new(payload),
+            })
+        }
+        LmType::Pointer { .. } | LmType::Function { .. } | LmType::Never => None,
+    }
+}
+
+/// The zero value of `type_id`: `0`/`false` scalars, zeroed fields and
+/// elements, and the first enum variant whose payload has a zero value.
+///
+/// Compounds nest at most `limits.max_depth` levels; returns `None` if the
+/// type has no zero value within that bound (a recursive definition) or
+/// cannot be generated at all.
+fn zero_value(type_id: TypeId, registry: &TypeRegistry, limits: &GeneratorLimits) -> Option<Value> {
+    zero_at_depth(type_id, registry, limits.max_depth)
+}
+
+/// [`zero_value`] with `depth` compound levels left.
+fn zero_at_depth(type_id: TypeId, registry: &TypeRegistry, depth: u32) -> Option<Value> {
+    Some(match registry.get(type_id)? {
+        LmType::Scalar(_) => match type_id {
+            TypeId::BOOL => Value::Bool(false),
+            TypeId::I8 => Value::I8(0),
+            TypeId::I16 => Value::I16(0),
+            TypeId::I32 => Value::I32(0),
+            TypeId::I64 => Value::I64(0),
+            TypeId::F32 => Value::F32(0.0),
+            _ => Value::F64(0.0),
+        },
+        LmType::Unit => Value::Unit,
+        _ if depth == 0 => return None,
+        LmType::Array { element, length } => {
+            let zero = zero_at_depth(*element, registry, depth - 1)?;
+            Value::Array {
+                ty: type_id,
+                elements: vec![zero; *length as usize],
+            }
+        }
+        LmType::Struct(def) => Value::Struct {
+            ty: type_id,
+            fields: def
+                .fields
+                .values()
+                .map(|field| zero_at_depth(*field, registry, depth - 1))
+                .collect::<Option<Vec<_>>>()?,
+        },
+        LmType::Enum(def) => def.variants.values().find_map(|variant| {
+            let payload = match variant.payload {
+                Some(payload) => zero_at_depth(payload, registry, depth - 1)?,
+                None => Value::Unit,
+            };
+            Some(Value::Enum {
+                ty: type_id,
+                variant: variant.index,
+                payload: Box::new(payload),
+            })
+        })?,
+        LmType::Pointer { .. } | LmType::Function { .. } | LmType::Never => return None,
+    })
+}
+
+/// Draws a random built-in scalar (or `Unit`).
+fn generate_scalar(type_id: TypeId, rng: &mut ChaCha8Rng) -> Option<Value> {
+    Some(match type_id {
         TypeId::BOOL => Value::Bool(rng.gen_bool(0.5)),
 
         TypeId::I8 => {
@@ -120,21 +362,24 @@ pub fn generate_random_value(type_id: TypeId, rng: &mut ChaCha8Rng) -> Value {
             }
         }
 
-        // Other types: return a zero/default value
         TypeId::UNIT => Value::Unit,
 
-        _ => {
-            // For unknown/compound types, return a zero/default value
-            Value::I32(0)
-        }
-    }
+        _ => return None,
+    })
 }
 
 /// Generates random input values for a function's parameters.
-pub fn generate_random_inputs(params: &[(String, TypeId)], rng: &mut ChaCha8Rng) -> Vec<Value> {
+///
+/// Returns `None` if any parameter type cannot be generated.
+pub fn generate_random_inputs(
+    params: &[(String, TypeId)],
+    registry: &TypeRegistry,
+    limits: &GeneratorLimits,
+    rng: &mut ChaCha8Rng,
+) -> Option<Vec<Value>> {
     params
         .iter()
-        .map(|(_, type_id)| generate_random_value(*type_id, rng))
+        .map(|(_, type_id)| generate_random_value(*type_id, registry, limits, rng))
         .collect()
 }
 
@@ -142,10 +387,14 @@ pub fn generate_random_inputs(params: &[(String, TypeId)], rng: &mut ChaCha8Rng)
 /// and randomized variations.
 ///
 /// Seeds are run first, then random variations. For each test case:
-/// 1. Create a fresh interpreter with tracing enabled
+/// 1. Create a fresh interpreter
 /// 2. Run the function with the test inputs
-/// 3. If the interpreter halts with a ContractViolation, record a failure
-/// 4. Capture the execution trace for failures
+/// 3. If a random input violates the function's own precondition, reject it
+///    and draw another (up to [`MAX_REJECTIONS_PER_CASE`] times, after which
+///    the case is counted as skipped)
+/// 4. If the interpreter halts with a ContractViolation, record a failure,
+///    or count an occurrence if that contract node already failed
+/// 5. Shrink each new failure and capture the trace of its shrunk inputs
 ///
 /// Returns a PropertyTestResult with totals, failures, and the random seed.
 pub fn run_property_tests(
@@ -160,34 +409,106 @@ pub fn run_property_tests(
         })?;
 
     let params = func_def.params.clone();
+    let executor = Executor::new(graph, config.engine, &config.bytecode_cache);
     let mut rng = ChaCha8Rng::seed_from_u64(config.random_seed);
-    let mut failures = Vec::new();
+    let mut failures: Vec<PropertyTestFailure> = Vec::new();
     let mut total_run: u32 = 0;
     let mut passed: u32 = 0;
+    let mut unsettled: u32 = 0;
+    let mut unsettled_runs: Vec<UnsettledRun> = Vec::new();
+    let mut rejected: u32 = 0;
+    let mut skipped: u32 = 0;
+    let mut coverage = Coverage::default();
+    let mut case: u32 = 0;
+    let mut next_seed = || {
+        case += 1;
+        case_seed(config.random_seed, case - 1)
+    };
 
-    // Run seed inputs first
-    for seed in &config.seeds {
+    // Returns whether to stop running cases
+    let mut record = |outcome: SingleTestResult| -> Result<bool, RuntimeError> {
         total_run += 1;
-        match run_single_test(graph, func_id, seed.clone())? {
-            SingleTestResult::Pass => {
-                passed += 1;
+        match outcome {
+            SingleTestResult::Unsettled(run) if run.error.is_limit_exceeded() => {
+                unsettled += 1;
+                if unsettled_runs.len() < MAX_UNSETTLED_REPORTED {
+                    unsettled_runs.push(*run);
+                }
+                return Ok(config.stop_on_unsettled);
             }
+            SingleTestResult::Pass | SingleTestResult::Unsettled(_) => passed += 1,
             SingleTestResult::Failure(failure) => {
-                failures.push(failure);
+                let node = failure.violation.contract_node;
+                match failures
+                    .iter_mut()
+                    .find(|f| f.violation.contract_node == node)
+                {
+                    Some(existing) => existing.occurrences += 1,
+                    None => failures.push(shrink_failure(
+                        &executor,
+                        &graph.types,
+                        func_id,
+                        *failure,
+                        &config,
+                    )?),
+                }
             }
         }
-    }
+        Ok(false)
+    };
 
-    // Run random variations
-    for _ in 0..config.iterations {
-        total_run += 1;
-        let inputs = generate_random_inputs(&params, &mut rng);
-        match run_single_test(graph, func_id, inputs)? {
-            SingleTestResult::Pass => {
-                passed += 1;
+    'cases: {
+        // Run seed inputs first
+        for seed in &config.seeds {
+            if record(run_single_test(
+                &executor,
+                func_id,
+                seed.clone(),
+                next_seed(),
+                config.limits,
+                false,
+                Some(&mut coverage),
+            )?)? {
+                break 'cases;
             }
-            SingleTestResult::Failure(failure) => {
-                failures.push(failure);
+        }
+
+        // Run random variations, redrawing inputs outside the precondition
+        for _ in 0..config.iterations {
+            let mut accepted = false;
+            for _ in 0..MAX_REJECTIONS_PER_CASE {
+                let inputs =
+                    generate_random_inputs(&params, &graph.types, &config.generator, &mut rng)
+                        .ok_or_else(|| RuntimeError::InternalError {
+                            message: format!(
+                                "cannot generate random inputs for function {}: \
+                             a parameter type has no generator",
+                                func_id.0
+                            ),
+                        })?;
+                let mut case_coverage = Coverage::default();
+                let outcome = run_single_test(
+                    &executor,
+                    func_id,
+                    inputs,
+                    next_seed(),
+                    config.limits,
+                    false,
+                    Some(&mut case_coverage),
+                )?;
+                if violates_own_precondition(&outcome, func_id) {
+                    rejected += 1;
+                    continue;
+                }
+                coverage.merge(&case_coverage);
+                if record(outcome)? {
+                    break 'cases;
+                }
+                accepted = true;
+                break;
+            }
+            if !accepted {
+                skipped += 1;
             }
         }
     }
@@ -195,50 +516,346 @@ pub fn run_property_tests(
     Ok(PropertyTestResult {
         total_run,
         passed,
+        unsettled,
+        unsettled_runs,
+        rejected,
+        skipped,
         failures,
         random_seed: config.random_seed,
+        coverage,
     })
 }
 
+/// Whether a test failed only because its inputs are outside `func_id`'s
+/// preconditions. Precondition failures in callees are real failures.
+pub(crate) fn violates_own_precondition(outcome: &SingleTestResult, func_id: FunctionId) -> bool {
+    matches!(
+        outcome,
+        SingleTestResult::Failure(f)
+            if f.violation.kind == ContractKind::Precondition
+                && f.violation.function_id == func_id
+    )
+}
+
+/// Shrinks a failure's inputs while the same contract node keeps failing,
+/// then re-runs the shrunk inputs with tracing enabled.
+///
+/// Greedy: each round tries candidates for every input position in order and
+/// restarts from the first position after any accepted step, until no
+/// candidate fails or the run budget is spent.
+fn shrink_failure(
+    executor: &Executor<'_>,
+    registry: &TypeRegistry,
+    func_id: FunctionId,
+    failure: PropertyTestFailure,
+    config: &PropertyTestConfig,
+) -> Result<PropertyTestFailure, RuntimeError> {
+    let node = failure.violation.contract_node;
+    let mut current = failure.inputs.clone();
+    let mut steps = 0;
+    let mut budget = config.max_shrink_runs;
+
+    'rounds: while budget > 0 {
+        for position in 0..current.len() {
+            for candidate in shrink_candidates(&current[position], registry, &config.generator) {
+                if budget == 0 {
+                    break 'rounds;
+                }
+                budget -= 1;
+                let mut trial = current.clone();
+                trial[position] = candidate;
+                if fails_at(executor, func_id, &trial, node, failure.case_seed, config)? {
+                    current = trial;
+                    steps += 1;
+                    continue 'rounds;
+                }
+            }
+        }
+        break;
+    }
+
+    let (violation, trace) = match run_single_test(
+        executor,
+        func_id,
+        current.clone(),
+        failure.case_seed,
+        config.limits,
+        true,
+        None,
+    )? {
+        SingleTestResult::Failure(shrunk) => (shrunk.violation, shrunk.trace),
+        // Only possible for nondeterministic programs: report the original
+        SingleTestResult::Pass | SingleTestResult::Unsettled(_) => {
+            let original = run_single_test(
+                executor,
+                func_id,
+                failure.inputs.clone(),
+                failure.case_seed,
+                config.limits,
+                true,
+                None,
+            )?;
+            current = failure.inputs.clone();
+            match original {
+                SingleTestResult::Failure(f) => (f.violation, f.trace),
+                SingleTestResult::Pass | SingleTestResult::Unsettled(_) => {
+                    (failure.violation, failure.trace)
+                }
+            }
+        }
+    };
+
+    Ok(PropertyTestFailure {
+        inputs: failure.inputs,
+        shrunk_inputs: current,
+        shrink_steps: steps,
+        occurrences: 1,
+        case_seed: failure.case_seed,
+        violation,
+        trace,
+    })
+}
+
+/// Whether `inputs` violate the contract at `node`.
+fn fails_at(
+    executor: &Executor<'_>,
+    func_id: FunctionId,
+    inputs: &[Value],
+    node: NodeId,
+    random_seed: u64,
+    config: &PropertyTestConfig,
+) -> Result<bool, RuntimeError> {
+    Ok(
+        match run_single_test(
+            executor,
+            func_id,
+            inputs.to_vec(),
+            random_seed,
+            config.limits,
+            false,
+            None,
+        )? {
+            SingleTestResult::Failure(f) => f.violation.contract_node == node,
+            SingleTestResult::Pass | SingleTestResult::Unsettled(_) => false,
+        },
+    )
+}
+
+/// Simpler variants of `value`, most aggressive first. Every candidate has
+/// the same type as `value`.
+///
+/// Integers move toward 0 (zero, negation, then successively smaller steps
+/// from halving down to one); floats toward
+/// 0 and whole numbers; arrays (whose length is part of their type) and
+/// structs shrink element- and field-wise; enums try earlier variants with
+/// the zero value (within `limits`) of that variant's payload type from
+/// `registry`, then shrink the payload.
+pub fn shrink_candidates(
+    value: &Value,
+    registry: &TypeRegistry,
+    limits: &GeneratorLimits,
+) -> Vec<Value> {
+    match value {
+        Value::Bool(true) => vec![Value::Bool(false)],
+        Value::I8(_) | Value::I16(_) | Value::I32(_) | Value::I64(_) => {
+            let n = int_of(value);
+            let mut out: Vec<i64> = Vec::new();
+            let mut push = |c: i64| {
+                if c != n && !out.contains(&c) {
+                    out.push(c);
+                }
+            };
+            push(0);
+            if n < 0 && n != int_min(value) {
+                push(-n);
+            }
+            // n/2, then ever closer to n: a greedy pass binary-searches
+            // toward the smallest failing magnitude
+            let mut delta = n / 2;
+            while delta != 0 {
+                push(n - delta);
+                delta /= 2;
+            }
+            push(n - n.signum());
+            out.into_iter().map(|c| int_like(value, c)).collect()
+        }
+        Value::F32(x) => shrink_float(*x as f64)
+            .into_iter()
+            .map(|c| Value::F32(c as f32))
+            .collect(),
+        Value::F64(x) => shrink_float(*x).into_iter().map(Value::F64).collect(),
+        Value::Array { ty, elements } => shrink_each(elements, registry, limits)
+            .into_iter()
+            .map(|elements| Value::Array { ty: *ty, elements })
+            .collect(),
+        Value::Struct { ty, fields } => shrink_each(fields, registry, limits)
+            .into_iter()
+            .map(|fields| Value::Struct { ty: *ty, fields })
+            .collect(),
+        Value::Enum {
+            ty,
+            variant,
+            payload,
+        } => {
+            let mut out: Vec<Value> = match registry.get(*ty) {
+                Some(LmType::Enum(def)) => def
+                    .variants
+                    .values()
+                    .filter(|v| v.index < *variant)
+                    .filter_map(|v| {
+                        let payload = match v.payload {
+                            Some(payload) => zero_value(payload, registry, limits)?,
+                            None => Value::Unit,
+                        };
+                        Some(Value::Enum {
+                            ty: *ty,
+                            variant: v.index,
+                            payload: Box::new(payload),
+                        })
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            out.extend(
+                shrink_candidates(payload, registry, limits)
+                    .into_iter()
+                    .map(|p| Value::Enum {
+                        ty: *ty,
+                        variant: *variant,
+                        payload: Box::new(p),
+                    }),
+            );
+            out
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Candidates replacing one element of `values` with one of its shrinks.
+fn shrink_each(
+    values: &[Value],
+    registry: &TypeRegistry,
+    limits: &GeneratorLimits,
+) -> Vec<Vec<Value>> {
+    let mut out = Vec::new();
+    for (i, v) in values.iter().enumerate() {
+        for candidate in shrink_candidates(v, registry, limits) {
+            let mut next = values.to_vec();
+            next[i] = candidate;
+            out.push(next);
+        }
+    }
+    out
+}
+
+/// Float shrinks: 0, the positive value, the whole part, then half the whole
+/// part. Only whole numbers are halved so shrinking terminates.
+fn shrink_float(x: f64) -> Vec<f64> {
+    let mut out: Vec<f64> = Vec::new();
+    let mut push = |c: f64| {
+        if c.to_bits() != x.to_bits() && !out.iter().any(|o| o.to_bits() == c.to_bits()) {
+            out.push(c);
+        }
+    };
+    if x != 0.0 || x.is_sign_negative() {
+        push(0.0);
+    }
+    if x.is_finite() {
+        if x < 0.0 {
+            push(-x);
+        }
+        push(x.trunc());
+        if x.abs() >= 2.0 {
+            push((x / 2.0).trunc());
+        }
+    }
+    out
+}
+
+/// Reads an integer value as `i64`.
+fn int_of(v: &Value) -> i64 {
+    match v {
+        Value::I8(n) => *n as i64,
+        Value::I16(n) => *n as i64,
+        Value::I32(n) => *n as i64,
+        Value::I64(n) => *n,
+        _ => 0,
+    }
+}
+
+/// Minimum of the integer type of `v` (which has no positive counterpart).
+fn int_min(v: &Value) -> i64 {
+    match v {
+        Value::I8(_) => i8::MIN as i64,
+        Value::I16(_) => i16::MIN as i64,
+        Value::I32(_) => i32::MIN as i64,
+        _ => i64::MIN,
+    }
+}
+
+/// Builds an integer value of the same type as `template`.
+fn int_like(template: &Value, n: i64) -> Value {
+    match template {
+        Value::I8(_) => Value::I8(n as i8),
+        Value::I16(_) => Value::I16(n as i16),
+        Value::I32(_) => Value::I32(n as i32),
+        _ => Value::I64(n),
+    }
+}
+
 /// Result of a single test execution.
-enum SingleTestResult {
+pub(crate) enum SingleTestResult {
     Pass,
-    Failure(PropertyTestFailure),
+    Failure(Box<PropertyTestFailure>),
+    /// The run stopped on a runtime error or an exhausted budget.
+    Unsettled(Box<UnsettledRun>),
 }
 
-/// Runs a single test case and returns the result.
-fn run_single_test(
-    graph: &ProgramGraph,
+/// Runs a single test case and returns the result, adding the run's coverage
+/// to `coverage` if given.
+pub(crate) fn run_single_test(
+    executor: &Executor<'_>,
     func_id: FunctionId,
     inputs: Vec<Value>,
+    random_seed: u64,
+    limits: ExecutionLimits,
+    trace_enabled: bool,
+    coverage: Option<&mut Coverage>,
 ) -> Result<SingleTestResult, RuntimeError> {
     let config = InterpreterConfig {
-        trace_enabled: true,
+        trace_enabled,
+        coverage_enabled: coverage.is_some(),
         max_recursion_depth: 256,
+        random_seed,
+        limits,
+        ..Default::default()
     };
 
-    let mut interp = Interpreter::new(graph, config);
-    interp.start(func_id, inputs.clone());
-    interp.run();
+    let run = executor.run(&config, func_id, inputs.clone());
+    if let (Some(total), Some(run)) = (coverage, &run.coverage) {
+        total.merge(run);
+    }
 
-    match interp.state() {
+    match run.state {
         ExecutionState::Completed { .. } => Ok(SingleTestResult::Pass),
         ExecutionState::ContractViolation { violation } => {
-            let trace = interp.trace().map(|t| t.to_vec()).unwrap_or_default();
-            Ok(SingleTestResult::Failure(PropertyTestFailure {
+            Ok(SingleTestResult::Failure(Box::new(PropertyTestFailure {
+                shrunk_inputs: inputs.clone(),
                 inputs,
-                violation: violation.clone(),
-                trace,
-            }))
+                shrink_steps: 0,
+                occurrences: 1,
+                case_seed: random_seed,
+                violation,
+                trace: run.trace.unwrap_or_default(),
+            })))
         }
+        // Runtime errors (overflow, div-by-zero, exceeded budgets) are not
+        // contract violations; callers decide how to count them
         ExecutionState::Error { error, .. } => {
-            // Runtime errors (overflow, div-by-zero) are not contract violations.
-            // They count as a pass from the contract perspective (the function
-            // errored before contracts could be checked, or after).
-            // If needed, these could be tracked separately, but per the plan
-            // spec we only track ContractViolation failures.
-            let _ = error;
-            Ok(SingleTestResult::Pass)
+            Ok(SingleTestResult::Unsettled(Box::new(UnsettledRun {
+                inputs,
+                error,
+            })))
         }
         _ => Ok(SingleTestResult::Pass),
     }
@@ -247,7 +864,7 @@ fn run_single_test(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use lmlang_core::ops::{CmpOp, ComputeOp};
+    use lmlang_core::ops::{CmpOp, ComputeNodeOp, ComputeOp};
     use lmlang_core::types::Visibility;
 
     /// Helper: build a function `checked_fn(a: i32) -> i32` with precondition `a >= 0`.
@@ -320,72 +937,417 @@ mod tests {
     #[test]
     fn generate_random_value_produces_correct_types() {
         let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let registry = TypeRegistry::new();
+        let limits = GeneratorLimits::default();
 
         // Bool
-        let val = generate_random_value(TypeId::BOOL, &mut rng);
+        let val = generate_random_value(TypeId::BOOL, &registry, &limits, &mut rng).unwrap();
         assert!(matches!(val, Value::Bool(_)));
 
         // I8
-        let val = generate_random_value(TypeId::I8, &mut rng);
+        let val = generate_random_value(TypeId::I8, &registry, &limits, &mut rng).unwrap();
         assert!(matches!(val, Value::I8(_)));
 
         // I16
-        let val = generate_random_value(TypeId::I16, &mut rng);
+        let val = generate_random_value(TypeId::I16, &registry, &limits, &mut rng).unwrap();
         assert!(matches!(val, Value::I16(_)));
 
         // I32
-        let val = generate_random_value(TypeId::I32, &mut rng);
+        let val = generate_random_value(TypeId::I32, &registry, &limits, &mut rng).unwrap();
         assert!(matches!(val, Value::I32(_)));
 
         // I64
-        let val = generate_random_value(TypeId::I64, &mut rng);
+        let val = generate_random_value(TypeId::I64, &registry, &limits, &mut rng).unwrap();
         assert!(matches!(val, Value::I64(_)));
 
         // F32
-        let val = generate_random_value(TypeId::F32, &mut rng);
+        let val = generate_random_value(TypeId::F32, &registry, &limits, &mut rng).unwrap();
         assert!(matches!(val, Value::F32(_)));
 
         // F64
-        let val = generate_random_value(TypeId::F64, &mut rng);
+        let val = generate_random_value(TypeId::F64, &registry, &limits, &mut rng).unwrap();
         assert!(matches!(val, Value::F64(_)));
 
         // Unit
-        let val = generate_random_value(TypeId::UNIT, &mut rng);
+        let val = generate_random_value(TypeId::UNIT, &registry, &limits, &mut rng).unwrap();
         assert!(matches!(val, Value::Unit));
     }
 
     #[test]
-    fn property_test_finds_violations_with_negative_inputs() {
+    fn limit_exceeded_runs_are_unsettled() {
+        let (graph, func_id) = build_precondition_function();
+
+        let config = PropertyTestConfig {
+            seeds: vec![vec![Value::I32(5)]],
+            iterations: 20,
+            random_seed: 7,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits {
+                max_steps: Some(1),
+                ..Default::default()
+            },
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
+        };
+
+        let result = run_property_tests(&graph, func_id, config.clone()).unwrap();
+
+        // No run reaches the precondition, so nothing is rejected or passed
+        assert_eq!(result.total_run, 21);
+        assert_eq!(result.passed, 0);
+        assert_eq!(result.rejected, 0);
+        assert_eq!(result.unsettled, 21);
+        assert!(result.failures.is_empty());
+        assert_eq!(result.unsettled_runs.len(), MAX_UNSETTLED_REPORTED);
+        assert_eq!(result.unsettled_runs[0].inputs, vec![Value::I32(5)]);
+        assert_eq!(result.unsettled_runs[0].error.kind(), "step_limit_exceeded");
+
+        // Stopping early skips every case after the first budget hit
+        let config = PropertyTestConfig {
+            stop_on_unsettled: true,
+            ..config
+        };
+        let result = run_property_tests(&graph, func_id, config).unwrap();
+        assert_eq!(result.total_run, 1);
+        assert_eq!(result.unsettled, 1);
+    }
+
+    #[test]
+    fn random_inputs_outside_precondition_are_rejected() {
         let (graph, func_id) = build_precondition_function();
 
         let config = PropertyTestConfig {
             seeds: vec![],
             iterations: 100,
             random_seed: 12345,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits::default(),
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
         };
 
         let result = run_property_tests(&graph, func_id, config).unwrap();
 
         assert_eq!(result.total_run, 100);
+        assert_eq!(result.passed, 100);
         assert_eq!(result.random_seed, 12345);
+        // With 100 accepted random i32 values, some negative draws must have
+        // been rejected (statistically nearly certain given the distribution)
+        assert!(result.rejected > 0, "expected rejected negative draws");
+        assert!(result.failures.is_empty(), "{:?}", result.failures);
+
+        // Coverage counts accepted cases only
+        let param = graph
+            .function_nodes(func_id)
+            .into_iter()
+            .find(|n| {
+                matches!(
+                    graph.get_compute_node(*n).unwrap().op,
+                    ComputeNodeOp::Core(ComputeOp::Parameter { .. })
+                )
+            })
+            .unwrap();
+        assert_eq!(result.coverage.node_hits(param), 100);
+    }
+
+    #[test]
+    fn cases_with_only_rejected_draws_are_counted_as_skipped() {
+        // checked_fn with the unsatisfiable precondition `a > i32::MAX`
+        let (mut graph, func_id) = build_precondition_function();
+        for node_id in graph.function_nodes(func_id) {
+            let new_op = match graph.get_compute_node(node_id).unwrap().op {
+                ComputeNodeOp::Core(ComputeOp::Const { .. }) => ComputeOp::Const {
+                    value: lmlang_core::types::ConstValue::I32(i32::MAX),
+                },
+                ComputeNodeOp::Core(ComputeOp::Compare { .. }) => {
+                    ComputeOp::Compare { op: CmpOp::Gt }
+                }
+                _ => continue,
+            };
+            graph
+                .modify_compute_node_op(node_id, ComputeNodeOp::Core(new_op))
+                .unwrap();
+        }
+
+        let config = PropertyTestConfig {
+            seeds: vec![],
+            iterations: 3,
+            random_seed: 1,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits::default(),
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
+        };
+        let result = run_property_tests(&graph, func_id, config).unwrap();
+
+        assert_eq!(result.total_run, 0);
+        assert_eq!(result.skipped, 3);
+        assert_eq!(result.rejected, 3 * MAX_REJECTIONS_PER_CASE);
+    }
+
+    /// Helper: build `clamp_x(p: Point) -> i32` returning `p.x`, with
+    /// precondition `p.x >= 0` and postcondition `p.x < 1000`.
+    fn build_struct_contract_function() -> (ProgramGraph, FunctionId, TypeId) {
+        use indexmap::IndexMap;
+        use lmlang_core::ops::StructuredOp;
+        use lmlang_core::types::StructDef;
+
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let point = graph
+            .types
+            .register_named(
+                "Point",
+                LmType::Struct(StructDef {
+                    name: "Point".into(),
+                    type_id: TypeId(0), // placeholder
+                    fields: IndexMap::from([("x".into(), TypeId::I32), ("y".into(), TypeId::I32)]),
+                    module: root,
+                    visibility: Visibility::Public,
+                }),
+            )
+            .unwrap();
+        let func_id = graph
+            .add_function(
+                "clamp_x".into(),
+                root,
+                vec![("p".into(), point)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+
+        let param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func_id)
+            .unwrap();
+        let x = graph
+            .add_structured_op(StructuredOp::StructGet { field_index: 0 }, func_id)
+            .unwrap();
+        graph.add_data_edge(param, x, 0, 0, point).unwrap();
+
+        let konst = |graph: &mut ProgramGraph, n: i32| {
+            graph
+                .add_core_op(
+                    ComputeOp::Const {
+                        value: lmlang_core::types::ConstValue::I32(n),
+                    },
+                    func_id,
+                )
+                .unwrap()
+        };
+        let zero = konst(&mut graph, 0);
+        let limit = konst(&mut graph, 1000);
+
+        let pre_cmp = graph
+            .add_core_op(ComputeOp::Compare { op: CmpOp::Ge }, func_id)
+            .unwrap();
+        graph.add_data_edge(x, pre_cmp, 0, 0, TypeId::I32).unwrap();
+        graph
+            .add_data_edge(zero, pre_cmp, 0, 1, TypeId::I32)
+            .unwrap();
+        let precond = graph
+            .add_core_op(
+                ComputeOp::Precondition {
+                    message: "p.x must be non-negative".into(),
+                },
+                func_id,
+            )
+            .unwrap();
+        graph
+            .add_data_edge(pre_cmp, precond, 0, 0, TypeId::BOOL)
+            .unwrap();
+
+        let post_cmp = graph
+            .add_core_op(ComputeOp::Compare { op: CmpOp::Lt }, func_id)
+            .unwrap();
+        graph.add_data_edge(x, post_cmp, 0, 0, TypeId::I32).unwrap();
+        graph
+            .add_data_edge(limit, post_cmp, 0, 1, TypeId::I32)
+            .unwrap();
+        let postcond = graph
+            .add_core_op(
+                ComputeOp::Postcondition {
+                    message: "result must be below 1000".into(),
+                },
+                func_id,
+            )
+            .unwrap();
+        graph
+            .add_data_edge(post_cmp, postcond, 0, 0, TypeId::BOOL)
+            .unwrap();
+
+        let ret = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+        graph.add_data_edge(x, ret, 0, 0, TypeId::I32).unwrap();
+        graph.add_control_edge(precond, ret, None).unwrap();
+
+        (graph, func_id, point)
+    }
 
-        // With 100 random i32 values, we should find at least one negative
-        // (statistically nearly certain given the distribution)
+    #[test]
+    fn struct_parameters_are_generated_and_shrunk() {
+        let (graph, func_id, point) = build_struct_contract_function();
+
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let value =
+            generate_random_value(point, &graph.types, &GeneratorLimits::default(), &mut rng);
         assert!(
-            !result.failures.is_empty(),
-            "Expected at least one failure from random negatives; got {} passed out of {}",
-            result.passed,
-            result.total_run,
+            matches!(&value, Some(Value::Struct { ty, fields })
+                if *ty == point && matches!(fields.as_slice(), [Value::I32(_), Value::I32(_)])),
+            "{value:?}"
         );
 
-        // Verify each failure has a contract violation
-        for failure in &result.failures {
-            assert_eq!(failure.violation.message, "a must be non-negative");
-            assert!(
-                !failure.trace.is_empty(),
-                "Failure trace should not be empty"
-            );
-        }
+        let config = PropertyTestConfig {
+            seeds: vec![],
+            iterations: 50,
+            random_seed: 7,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits::default(),
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
+        };
+        let result = run_property_tests(&graph, func_id, config).unwrap();
+
+        assert!(result.rejected > 0, "negative p.x draws should be rejected");
+        assert_eq!(result.failures.len(), 1, "{:?}", result.failures);
+        let failure = &result.failures[0];
+        assert_eq!(failure.violation.kind, ContractKind::Postcondition);
+        assert_eq!(
+            failure.shrunk_inputs,
+            vec![Value::Struct {
+                ty: point,
+                fields: vec![Value::I32(1000), Value::I32(0)]
+            }]
+        );
+    }
+
+    #[test]
+    fn generator_limits_bound_compound_values() {
+        let mut registry = TypeRegistry::new();
+        let inner = registry.register(LmType::Array {
+            element: TypeId::I64,
+            length: 3,
+        });
+        let outer = registry.register(LmType::Array {
+            element: inner,
+            length: 2,
+        });
+        let pointer = registry.register(LmType::Pointer {
+            pointee: TypeId::I64,
+            mutable: false,
+        });
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+
+        // Depth 1 zero-fills the inner arrays
+        let shallow = GeneratorLimits {
+            max_depth: 1,
+            max_leaves: 256,
+        };
+        let zeros = Value::Array {
+            ty: inner,
+            elements: vec![Value::I64(0); 3],
+        };
+        assert_eq!(
+            generate_random_value(outer, &registry, &shallow, &mut rng),
+            Some(Value::Array {
+                ty: outer,
+                elements: vec![zeros.clone(), zeros.clone()]
+            })
+        );
+
+        // Two leaves: everything after the first two elements is zero
+        let small = GeneratorLimits {
+            max_depth: 8,
+            max_leaves: 2,
+        };
+        let Some(Value::Array { elements: rows, .. }) =
+            generate_random_value(outer, &registry, &small, &mut rng)
+        else {
+            panic!("expected an array");
+        };
+        assert_eq!(rows[1], zeros);
+
+        assert_eq!(
+            generate_random_value(pointer, &registry, &small, &mut rng),
+            None
+        );
+
+        // Zero-filling honours the caller's depth: [[I64; 3]; 2] needs two levels
+        assert_eq!(zero_value(outer, &registry, &shallow), None);
+        assert!(zero_value(outer, &registry, &small).is_some());
+    }
+
+    #[test]
+    fn recursive_types_have_no_ill_typed_zero() {
+        use indexmap::IndexMap;
+        use lmlang_core::types::{EnumDef, EnumVariant, StructDef};
+
+        let mut registry = TypeRegistry::new();
+        let root = lmlang_core::id::ModuleId(0);
+        let limits = GeneratorLimits {
+            max_depth: 2,
+            max_leaves: 256,
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+
+        // struct Loop { next: Loop } has no finite value
+        let looped = TypeId(registry.iter().count() as u32);
+        let registered = registry.register(LmType::Struct(StructDef {
+            name: "Loop".into(),
+            type_id: looped,
+            fields: IndexMap::from([("next".into(), looped)]),
+            module: root,
+            visibility: Visibility::Public,
+        }));
+        assert_eq!(registered, looped);
+        assert_eq!(
+            generate_random_value(looped, &registry, &limits, &mut rng),
+            None
+        );
+
+        // enum List { Cons(List), Nil } bottoms out in `Nil`: `Cons(Nil)`
+        let list = TypeId(registry.iter().count() as u32);
+        registry.register(LmType::Enum(EnumDef {
+            name: "List".into(),
+            type_id: list,
+            variants: IndexMap::from([
+                (
+                    "Cons".into(),
+                    EnumVariant {
+                        index: 0,
+                        payload: Some(list),
+                    },
+                ),
+                (
+                    "Nil".into(),
+                    EnumVariant {
+                        index: 1,
+                        payload: None,
+                    },
+                ),
+            ]),
+            module: root,
+            visibility: Visibility::Public,
+        }));
+        assert_eq!(
+            zero_value(list, &registry, &limits),
+            Some(Value::Enum {
+                ty: list,
+                variant: 0,
+                payload: Box::new(Value::Enum {
+                    ty: list,
+                    variant: 1,
+                    payload: Box::new(Value::Unit)
+                })
+            })
+        );
     }
 
     #[test]
@@ -396,12 +1358,24 @@ mod tests {
             seeds: vec![vec![Value::I32(5)]],
             iterations: 50,
             random_seed: 99999,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits::default(),
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
         };
 
         let config2 = PropertyTestConfig {
             seeds: vec![vec![Value::I32(5)]],
             iterations: 50,
             random_seed: 99999,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits::default(),
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
         };
 
         let result1 = run_property_tests(&graph, func_id, config1).unwrap();
@@ -431,6 +1405,12 @@ mod tests {
             seeds: vec![vec![Value::I32(-1)]],
             iterations: 10,
             random_seed: 42,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits::default(),
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
         };
 
         let result = run_property_tests(&graph, func_id, config).unwrap();
@@ -443,6 +1423,186 @@ mod tests {
         assert_eq!(result.failures[0].inputs, vec![Value::I32(-1)]);
     }
 
+    #[test]
+    fn failures_dedupe_by_contract_and_shrink_to_minimal_input() {
+        let (graph, func_id) = build_precondition_function();
+
+        let config = PropertyTestConfig {
+            seeds: vec![vec![Value::I32(-123_456)]],
+            iterations: 100,
+            random_seed: 12345,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits::default(),
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
+        };
+
+        let result = run_property_tests(&graph, func_id, config).unwrap();
+
+        assert_eq!(result.failures.len(), 1, "one failure per contract node");
+        let failure = &result.failures[0];
+        assert_eq!(failure.inputs, vec![Value::I32(-123_456)]);
+        assert_eq!(failure.shrunk_inputs, vec![Value::I32(-1)]);
+        assert!(failure.shrink_steps > 0);
+        assert_eq!(failure.violation.inputs, vec![Value::I32(-1)]);
+        assert_eq!(failure.occurrences, result.total_run - result.passed);
+    }
+
+    #[test]
+    fn bytecode_and_reference_engines_report_identical_results() {
+        let (graph, func_id) = build_precondition_function();
+        let cache = BytecodeCache::new();
+        let run = |engine: Engine| {
+            let config = PropertyTestConfig {
+                seeds: vec![vec![Value::I32(-123_456)], vec![Value::I32(7)]],
+                iterations: 200,
+                random_seed: 4242,
+                max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+                generator: GeneratorLimits::default(),
+                limits: ExecutionLimits::default(),
+                stop_on_unsettled: false,
+                engine,
+                bytecode_cache: cache.clone(),
+            };
+            run_property_tests(&graph, func_id, config).unwrap()
+        };
+
+        let vm = run(Engine::Bytecode);
+        let reference = run(Engine::Reference);
+        assert!(!cache.is_empty());
+        assert_eq!(vm.total_run, reference.total_run);
+        assert_eq!(vm.passed, reference.passed);
+        assert_eq!(vm.rejected, reference.rejected);
+        assert_eq!(vm.skipped, reference.skipped);
+        assert_eq!(vm.coverage, reference.coverage);
+        assert_eq!(
+            format!("{:?}", vm.failures),
+            format!("{:?}", reference.failures)
+        );
+    }
+
+    #[test]
+    fn shrinking_disabled_keeps_original_inputs() {
+        let (graph, func_id) = build_precondition_function();
+
+        let config = PropertyTestConfig {
+            seeds: vec![vec![Value::I32(-77)]],
+            iterations: 0,
+            random_seed: 1,
+            max_shrink_runs: 0,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits::default(),
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
+        };
+
+        let result = run_property_tests(&graph, func_id, config).unwrap();
+        assert_eq!(result.failures[0].shrunk_inputs, vec![Value::I32(-77)]);
+        assert_eq!(result.failures[0].shrink_steps, 0);
+    }
+
+    #[test]
+    fn shrink_candidates_move_toward_simpler_values() {
+        use indexmap::IndexMap;
+        use lmlang_core::types::{EnumDef, EnumVariant};
+
+        let mut registry = TypeRegistry::new();
+        let root = lmlang_core::id::ModuleId(0);
+        let shape = registry.register(LmType::Enum(EnumDef {
+            name: "Shape".into(),
+            type_id: TypeId(0), // placeholder
+            variants: IndexMap::from([
+                (
+                    "Empty".into(),
+                    EnumVariant {
+                        index: 0,
+                        payload: None,
+                    },
+                ),
+                (
+                    "Flag".into(),
+                    EnumVariant {
+                        index: 1,
+                        payload: Some(TypeId::BOOL),
+                    },
+                ),
+                (
+                    "Size".into(),
+                    EnumVariant {
+                        index: 2,
+                        payload: Some(TypeId::I32),
+                    },
+                ),
+            ]),
+            module: root,
+            visibility: Visibility::Public,
+        }));
+        let limits = GeneratorLimits::default();
+        let shrink_candidates = |value: &Value| super::shrink_candidates(value, &registry, &limits);
+        assert_eq!(
+            shrink_candidates(&Value::I8(-100)),
+            [0, 100, -50, -75, -88, -94, -97, -99].map(Value::I8)
+        );
+        // MIN has no positive counterpart
+        assert_eq!(
+            shrink_candidates(&Value::I64(i64::MIN))[..2],
+            [Value::I64(0), Value::I64(i64::MIN / 2)]
+        );
+        assert!(shrink_candidates(&Value::I32(0)).is_empty());
+        assert_eq!(
+            shrink_candidates(&Value::F64(-7.5)),
+            vec![
+                Value::F64(0.0),
+                Value::F64(7.5),
+                Value::F64(-7.0),
+                Value::F64(-3.0)
+            ]
+        );
+
+        // Arrays keep their length and shrink element-wise
+        let array = Value::untyped_array(vec![Value::I32(3), Value::I32(4)]);
+        let candidates = shrink_candidates(&array);
+        assert_eq!(
+            candidates[0],
+            Value::untyped_array(vec![Value::I32(0), Value::I32(4)])
+        );
+        assert!(candidates.iter().all(|c| matches!(
+            c,
+            Value::Array { elements, .. } if elements.len() == 2
+        )));
+
+        // Earlier variants carry a zero payload of their own type
+        let variant = Value::Enum {
+            ty: shape,
+            variant: 2,
+            payload: Box::new(Value::I32(9)),
+        };
+        let candidates = shrink_candidates(&variant);
+        assert_eq!(
+            candidates[..3],
+            [
+                Value::Enum {
+                    ty: shape,
+                    variant: 0,
+                    payload: Box::new(Value::Unit)
+                },
+                Value::Enum {
+                    ty: shape,
+                    variant: 1,
+                    payload: Box::new(Value::Bool(false))
+                },
+                Value::Enum {
+                    ty: shape,
+                    variant: 2,
+                    payload: Box::new(Value::I32(0))
+                },
+            ]
+        );
+    }
+
     #[test]
     fn all_seeds_pass_when_valid() {
         let (graph, func_id) = build_precondition_function();
@@ -456,6 +1616,12 @@ mod tests {
             ],
             iterations: 0,
             random_seed: 42,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits::default(),
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
         };
 
         let result = run_property_tests(&graph, func_id, config).unwrap();
@@ -464,4 +1630,81 @@ mod tests {
         assert_eq!(result.passed, 3);
         assert!(result.failures.is_empty());
     }
+
+    #[test]
+    fn random_ops_draw_a_different_stream_per_case() {
+        // roll() -> i64 = random(), with postcondition `result >= 0`
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function("roll".into(), root, vec![], TypeId::I64, Visibility::Public)
+            .unwrap();
+        let random = graph.add_core_op(ComputeOp::Random, func_id).unwrap();
+        let zero = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: lmlang_core::types::ConstValue::I64(0),
+                },
+                func_id,
+            )
+            .unwrap();
+        let cmp = graph
+            .add_core_op(ComputeOp::Compare { op: CmpOp::Ge }, func_id)
+            .unwrap();
+        graph.add_data_edge(random, cmp, 0, 0, TypeId::I64).unwrap();
+        graph.add_data_edge(zero, cmp, 0, 1, TypeId::I64).unwrap();
+        let postcond = graph
+            .add_core_op(
+                ComputeOp::Postcondition {
+                    message: "result must be non-negative".into(),
+                },
+                func_id,
+            )
+            .unwrap();
+        graph
+            .add_data_edge(cmp, postcond, 0, 0, TypeId::BOOL)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+        graph.add_data_edge(random, ret, 0, 0, TypeId::I64).unwrap();
+        graph.add_control_edge(postcond, ret, None).unwrap();
+
+        let config = PropertyTestConfig {
+            seeds: vec![],
+            iterations: 40,
+            random_seed: 3,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits::default(),
+            stop_on_unsettled: false,
+            engine: Engine::Bytecode,
+            bytecode_cache: BytecodeCache::new(),
+        };
+        let result = run_property_tests(&graph, func_id, config.clone()).unwrap();
+
+        // About half the cases draw a negative value
+        assert_eq!(result.failures.len(), 1, "{:?}", result.failures);
+        let failure = &result.failures[0];
+        assert!(
+            failure.occurrences > 0 && failure.occurrences < 40,
+            "{} of 40 cases failed",
+            failure.occurrences
+        );
+        assert_ne!(case_seed(3, 0), case_seed(3, 1));
+
+        // The recorded case seed replays the failure
+        let executor = Executor::new(&graph, Engine::Bytecode, &config.bytecode_cache);
+        assert!(matches!(
+            run_single_test(
+                &executor,
+                func_id,
+                vec![],
+                failure.case_seed,
+                ExecutionLimits::default(),
+                false,
+                None
+            )
+            .unwrap(),
+            SingleTestResult::Failure(_)
+        ));
+    }
 }
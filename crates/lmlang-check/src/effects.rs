@@ -0,0 +1,460 @@
+//! Capability (effect) checking across the call graph.
+//!
+//! Each function's effects are the capabilities required by its own ops (see
+//! [`ComputeOp::required_capability`]) plus the effects of every function it
+//! calls or builds a closure for. Effects are computed as a fixpoint so
+//! recursive and mutually recursive functions are handled.
+//!
+//! The call graph is passed in rather than rebuilt here so callers can share
+//! the one produced by `lmlang_codegen::incremental::build_call_graph`.
+//! `MakeClosure` targets are added on top of it, since a closure may be
+//! invoked through `IndirectCall` anywhere its value flows.
+//!
+//! Two checks are provided:
+//! - [`check_effects`]: functions with declared [`FunctionDef::effects`] must
+//!   not use capabilities outside their declaration.
+//! - [`check_forbidden`]: no function may use a capability forbidden by an
+//!   operator policy, declared or not.
+//!
+//! [`ComputeOp::required_capability`]: lmlang_core::ops::ComputeOp::required_capability
+//! [`FunctionDef::effects`]: lmlang_core::function::FunctionDef::effects
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use lmlang_core::capability::{Capability, EffectSet};
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::ops::{ComputeNodeOp, ComputeOp};
+
+/// Where a function picks up a capability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EffectOrigin {
+    /// An op in the function body requires it directly.
+    Node(NodeId),
+    /// A called function (transitively) requires it.
+    Call(FunctionId),
+    /// A closure created by this function (transitively) requires it.
+    Closure(FunctionId),
+}
+
+impl fmt::Display for EffectOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EffectOrigin::Node(node) => write!(f, "node {}", node),
+            EffectOrigin::Call(func) => write!(f, "call to function {}", func),
+            EffectOrigin::Closure(func) => write!(f, "closure function {}", func),
+        }
+    }
+}
+
+/// A capability violation found by effect checking.
+#[derive(Debug, Clone, Serialize, Deserialize, thiserror::Error)]
+pub enum EffectError {
+    /// A function uses a capability it did not declare.
+    #[error(
+        "function '{function_name}' uses capability '{capability}' not in its declared effects (via {origin})"
+    )]
+    UndeclaredCapability {
+        /// The function whose declaration is violated.
+        function_id: FunctionId,
+        /// Name of that function.
+        function_name: String,
+        /// The capability that was not declared.
+        capability: Capability,
+        /// Where the capability comes from.
+        origin: EffectOrigin,
+    },
+
+    /// A function uses a capability forbidden by policy.
+    #[error("function '{function_name}' uses forbidden capability '{capability}' (via {origin})")]
+    ForbiddenCapability {
+        /// The offending function.
+        function_id: FunctionId,
+        /// Name of that function.
+        function_name: String,
+        /// The forbidden capability.
+        capability: Capability,
+        /// Where the capability comes from.
+        origin: EffectOrigin,
+    },
+}
+
+/// Returns the capabilities required directly by ops in `func_id`'s body,
+/// each paired with the first (lowest ID) node requiring it.
+///
+/// File open/close need no capability of their own when the body already
+/// reads or writes; otherwise they require `FsWrite` if that is the only fs
+/// capability the function declares, and `FsRead` if not.
+pub fn direct_effects(graph: &ProgramGraph, func_id: FunctionId) -> Vec<(Capability, NodeId)> {
+    let mut nodes = graph.function_nodes(func_id);
+    nodes.sort_by_key(|n| n.0);
+
+    let mut found: Vec<(Capability, NodeId)> = Vec::new();
+    let mut handle_op = None;
+    for node_id in nodes {
+        let Some(node) = graph.get_compute_node(node_id) else {
+            continue;
+        };
+        if node.op.accepts_any_fs_capability() {
+            handle_op.get_or_insert(node_id);
+            continue;
+        }
+        let Some(cap) = node.op.required_capability() else {
+            continue;
+        };
+        if !found.iter().any(|(c, _)| *c == cap) {
+            found.push((cap, node_id));
+        }
+    }
+    if let Some(node_id) = handle_op {
+        let uses_fs = found
+            .iter()
+            .any(|(c, _)| matches!(c, Capability::FsRead | Capability::FsWrite));
+        if !uses_fs {
+            let write_only = graph.get_function(func_id).is_some_and(|f| {
+                f.effects.as_ref().is_some_and(|declared| {
+                    declared.contains(&Capability::FsWrite)
+                        && !declared.contains(&Capability::FsRead)
+                })
+            });
+            let cap = if write_only {
+                Capability::FsWrite
+            } else {
+                Capability::FsRead
+            };
+            found.push((cap, node_id));
+        }
+    }
+    found.sort_by_key(|(cap, _)| *cap);
+    found
+}
+
+/// Computes the transitive effects of every function.
+pub fn infer_effects(
+    graph: &ProgramGraph,
+    call_graph: &HashMap<FunctionId, Vec<FunctionId>>,
+) -> HashMap<FunctionId, EffectSet> {
+    EffectAnalysis::new(graph, call_graph).effects
+}
+
+/// Checks that every function with declared effects stays within them.
+pub fn check_effects(
+    graph: &ProgramGraph,
+    call_graph: &HashMap<FunctionId, Vec<FunctionId>>,
+) -> Vec<EffectError> {
+    let analysis = EffectAnalysis::new(graph, call_graph);
+    let mut errors = Vec::new();
+
+    for func_id in analysis.sorted_functions() {
+        let func_def = &graph.functions()[&func_id];
+        let Some(declared) = &func_def.effects else {
+            continue;
+        };
+        for &capability in &analysis.effects[&func_id] {
+            if declared.contains(&capability) {
+                continue;
+            }
+            errors.push(EffectError::UndeclaredCapability {
+                function_id: func_id,
+                function_name: func_def.name.clone(),
+                capability,
+                origin: analysis.origin(func_id, capability),
+            });
+        }
+    }
+
+    errors
+}
+
+/// Checks that no function uses a capability in `forbidden`.
+pub fn check_forbidden(
+    graph: &ProgramGraph,
+    call_graph: &HashMap<FunctionId, Vec<FunctionId>>,
+    forbidden: &EffectSet,
+) -> Vec<EffectError> {
+    if forbidden.is_empty() {
+        return Vec::new();
+    }
+
+    let analysis = EffectAnalysis::new(graph, call_graph);
+    let mut errors = Vec::new();
+
+    for func_id in analysis.sorted_functions() {
+        let func_def = &graph.functions()[&func_id];
+        for &capability in analysis.effects[&func_id].intersection(forbidden) {
+            errors.push(EffectError::ForbiddenCapability {
+                function_id: func_id,
+                function_name: func_def.name.clone(),
+                capability,
+                origin: analysis.origin(func_id, capability),
+            });
+        }
+    }
+
+    errors
+}
+
+/// Fixpoint effect computation shared by the public checks.
+struct EffectAnalysis {
+    /// Direct (capability, node) pairs per function.
+    direct: HashMap<FunctionId, Vec<(Capability, NodeId)>>,
+    /// Outgoing dependencies per function, in origin-reporting order.
+    deps: HashMap<FunctionId, Vec<EffectOrigin>>,
+    /// Transitive effects per function.
+    effects: HashMap<FunctionId, EffectSet>,
+}
+
+impl EffectAnalysis {
+    fn new(graph: &ProgramGraph, call_graph: &HashMap<FunctionId, Vec<FunctionId>>) -> Self {
+        let mut direct = HashMap::new();
+        let mut deps: HashMap<FunctionId, Vec<EffectOrigin>> = HashMap::new();
+
+        for &func_id in graph.functions().keys() {
+            direct.insert(func_id, direct_effects(graph, func_id));
+
+            let mut func_deps: Vec<EffectOrigin> = call_graph
+                .get(&func_id)
+                .map(|callees| callees.iter().map(|&c| EffectOrigin::Call(c)).collect())
+                .unwrap_or_default();
+            let mut closures: Vec<FunctionId> = graph
+                .function_nodes(func_id)
+                .into_iter()
+                .filter_map(|node_id| match &graph.get_compute_node(node_id)?.op {
+                    ComputeNodeOp::Core(ComputeOp::MakeClosure { function }) => Some(*function),
+                    _ => None,
+                })
+                .collect();
+            closures.sort_by_key(|f| f.0);
+            closures.dedup();
+            func_deps.extend(closures.into_iter().map(EffectOrigin::Closure));
+            deps.insert(func_id, func_deps);
+        }
+
+        let mut effects: HashMap<FunctionId, EffectSet> = direct
+            .iter()
+            .map(|(&f, caps)| (f, caps.iter().map(|(c, _)| *c).collect()))
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (func_id, func_deps) in &deps {
+                let mut inherited = EffectSet::new();
+                for origin in func_deps {
+                    if let Some(callee_effects) =
+                        dep_function(*origin).and_then(|f| effects.get(&f))
+                    {
+                        inherited.extend(callee_effects.iter().copied());
+                    }
+                }
+                let own = effects.entry(*func_id).or_default();
+                let before = own.len();
+                own.extend(inherited);
+                changed |= own.len() != before;
+            }
+        }
+
+        EffectAnalysis {
+            direct,
+            deps,
+            effects,
+        }
+    }
+
+    fn sorted_functions(&self) -> Vec<FunctionId> {
+        let mut ids: Vec<FunctionId> = self.effects.keys().copied().collect();
+        ids.sort_by_key(|f| f.0);
+        ids
+    }
+
+    /// Picks the most direct explanation for why `func_id` has `capability`:
+    /// its own node if one requires it, otherwise the first dependency that does.
+    fn origin(&self, func_id: FunctionId, capability: Capability) -> EffectOrigin {
+        if let Some((_, node)) = self.direct[&func_id]
+            .iter()
+            .find(|(cap, _)| *cap == capability)
+        {
+            return EffectOrigin::Node(*node);
+        }
+        self.deps[&func_id]
+            .iter()
+            .copied()
+            .find(|origin| {
+                dep_function(*origin)
+                    .and_then(|f| self.effects.get(&f))
+                    .is_some_and(|e| e.contains(&capability))
+            })
+            .expect("inherited capability must come from a dependency")
+    }
+}
+
+fn dep_function(origin: EffectOrigin) -> Option<FunctionId> {
+    match origin {
+        EffectOrigin::Call(f) | EffectOrigin::Closure(f) => Some(f),
+        EffectOrigin::Node(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::Visibility;
+
+    /// Local copy of the codegen call-graph builder (lmlang-check cannot
+    /// depend on lmlang-codegen).
+    fn call_graph(graph: &ProgramGraph) -> HashMap<FunctionId, Vec<FunctionId>> {
+        graph
+            .functions()
+            .keys()
+            .map(|&f| {
+                let mut callees: Vec<FunctionId> = graph
+                    .function_nodes(f)
+                    .into_iter()
+                    .filter_map(|n| graph.get_compute_node(n)?.op.call_target())
+                    .collect();
+                callees.sort_by_key(|c| c.0);
+                callees.dedup();
+                (f, callees)
+            })
+            .collect()
+    }
+
+    fn add_fn(graph: &mut ProgramGraph, name: &str) -> FunctionId {
+        let root = graph.modules.root_id();
+        graph
+            .add_function(name.into(), root, vec![], TypeId::UNIT, Visibility::Public)
+            .unwrap()
+    }
+
+    /// Builds: logger() prints; helper() calls logger(); main() calls helper().
+    fn logging_program() -> (ProgramGraph, FunctionId, FunctionId, FunctionId) {
+        let mut graph = ProgramGraph::new("test");
+        let logger = add_fn(&mut graph, "logger");
+        let helper = add_fn(&mut graph, "helper");
+        let main = add_fn(&mut graph, "main");
+
+        let c = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: lmlang_core::types::ConstValue::I32(1),
+                },
+                logger,
+            )
+            .unwrap();
+        let print = graph.add_core_op(ComputeOp::Print, logger).unwrap();
+        graph.add_data_edge(c, print, 0, 0, TypeId::I32).unwrap();
+
+        graph
+            .add_core_op(ComputeOp::Call { target: logger }, helper)
+            .unwrap();
+        graph
+            .add_core_op(ComputeOp::Call { target: helper }, main)
+            .unwrap();
+
+        (graph, logger, helper, main)
+    }
+
+    #[test]
+    fn effects_propagate_through_calls() {
+        let (graph, logger, helper, main) = logging_program();
+        let effects = infer_effects(&graph, &call_graph(&graph));
+        let console: EffectSet = [Capability::Console].into();
+        assert_eq!(effects[&logger], console);
+        assert_eq!(effects[&helper], console);
+        assert_eq!(effects[&main], console);
+    }
+
+    #[test]
+    fn undeclared_functions_are_not_checked() {
+        let (graph, ..) = logging_program();
+        assert!(check_effects(&graph, &call_graph(&graph)).is_empty());
+    }
+
+    #[test]
+    fn pure_declaration_rejects_transitive_io() {
+        let (mut graph, _logger, helper, main) = logging_program();
+        graph.get_function_mut(main).unwrap().effects = Some(EffectSet::new());
+
+        let errors = check_effects(&graph, &call_graph(&graph));
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            EffectError::UndeclaredCapability {
+                function_id,
+                capability,
+                origin,
+                ..
+            } => {
+                assert_eq!(*function_id, main);
+                assert_eq!(*capability, Capability::Console);
+                assert_eq!(*origin, EffectOrigin::Call(helper));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn declared_capability_is_accepted() {
+        let (mut graph, logger, ..) = logging_program();
+        graph.get_function_mut(logger).unwrap().effects = Some([Capability::Console].into());
+        assert!(check_effects(&graph, &call_graph(&graph)).is_empty());
+    }
+
+    #[test]
+    fn file_handle_ops_accept_either_fs_capability() {
+        let mut graph = ProgramGraph::new("test");
+        let writer = add_fn(&mut graph, "writer");
+        let opener = add_fn(&mut graph, "opener");
+        for op in [
+            ComputeOp::FileOpen,
+            ComputeOp::FileWrite,
+            ComputeOp::FileClose,
+        ] {
+            graph.add_core_op(op, writer).unwrap();
+        }
+        graph.add_core_op(ComputeOp::FileOpen, opener).unwrap();
+        graph.get_function_mut(writer).unwrap().effects = Some([Capability::FsWrite].into());
+        graph.get_function_mut(opener).unwrap().effects = Some([Capability::FsWrite].into());
+
+        assert!(check_effects(&graph, &call_graph(&graph)).is_empty());
+        let effects = infer_effects(&graph, &call_graph(&graph));
+        assert_eq!(effects[&writer], [Capability::FsWrite].into());
+        assert_eq!(effects[&opener], [Capability::FsWrite].into());
+
+        graph.get_function_mut(opener).unwrap().effects = None;
+        let effects = infer_effects(&graph, &call_graph(&graph));
+        assert_eq!(effects[&opener], [Capability::FsRead].into());
+    }
+
+    #[test]
+    fn recursion_reaches_fixpoint() {
+        let mut graph = ProgramGraph::new("test");
+        let a = add_fn(&mut graph, "a");
+        let b = add_fn(&mut graph, "b");
+        graph.add_core_op(ComputeOp::Call { target: b }, a).unwrap();
+        graph.add_core_op(ComputeOp::Call { target: a }, b).unwrap();
+        graph.add_core_op(ComputeOp::Now, b).unwrap();
+
+        let effects = infer_effects(&graph, &call_graph(&graph));
+        assert!(effects[&a].contains(&Capability::Clock));
+        assert!(effects[&b].contains(&Capability::Clock));
+    }
+
+    #[test]
+    fn forbidden_capabilities_are_reported_for_every_function() {
+        let (graph, logger, ..) = logging_program();
+        let forbidden: EffectSet = [Capability::Console, Capability::FsWrite].into();
+
+        let errors = check_forbidden(&graph, &call_graph(&graph), &forbidden);
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            EffectError::ForbiddenCapability { function_id, origin: EffectOrigin::Node(_), .. }
+                if *function_id == logger
+        )));
+        assert!(check_forbidden(&graph, &call_graph(&graph), &EffectSet::new()).is_empty());
+    }
+}
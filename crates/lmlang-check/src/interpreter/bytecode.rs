@@ -0,0 +1,526 @@
+//! Lowering of function graphs into register bytecode for the [`vm`](super::vm).
+//!
+//! Each function is lowered once into a [`FunctionCode`]: one instruction per
+//! compute node, addressed by a dense slot that doubles as the node's
+//! register. An instruction carries everything the work-list [`Interpreter`]
+//! otherwise looks up in the graph on every step: its input registers sorted
+//! by port, its successors in edge order, whether it is control-gated, and
+//! for `Loop` nodes the body that is reset on each iteration.
+//!
+//! Functions without value-dependent control flow (no Branch, IfElse, Loop,
+//! Match or structured loops) evaluate their nodes in the same order on every
+//! call, so that order is simulated once at lowering time and the VM runs
+//! them as straight-line code. Other functions keep a work list over slots.
+//!
+//! Lowered code is cached in a [`BytecodeCache`] keyed by the function's
+//! [`hash_function`] combined with its edge layout: the content hash ignores
+//! which of two identical nodes an edge targets and the order edges were
+//! added in, both of which decide evaluation order.
+//!
+//! [`Interpreter`]: super::Interpreter
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+
+use lmlang_core::edge::FlowEdge;
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::ops::{ComputeNodeOp, ComputeOp, StructuredOp};
+use lmlang_core::type_id::TypeId;
+use lmlang_storage::hash::hash_function;
+
+/// Index of an instruction within its function, and of the register holding
+/// its node's value.
+pub(super) type Slot = u32;
+
+/// Lowered functions kept by a [`BytecodeCache`] before it starts over.
+const MAX_CACHED_FUNCTIONS: usize = 4096;
+
+/// Why a function cannot be lowered to bytecode.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LowerError {
+    /// Frames only hold values of their own function's nodes, so an edge
+    /// leaving the function cannot be given a register.
+    #[error("node {node} of function {function} has an edge to node {other} of another function")]
+    CrossFunctionEdge {
+        function: FunctionId,
+        node: NodeId,
+        other: NodeId,
+    },
+}
+
+/// An outgoing edge of an instruction, in graph edge order.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Successor {
+    /// Data edge: the target gains one ready input.
+    Data(Slot),
+    /// Control edge: the target becomes control-ready if its arm is taken.
+    Control(Slot, Option<u16>),
+}
+
+/// One compute node lowered to an instruction.
+#[derive(Debug)]
+pub(super) struct Instr {
+    pub(super) node_id: NodeId,
+    pub(super) op: ComputeNodeOp,
+    /// `format!("{:?}", op)`, as recorded in trace entries.
+    pub(super) description: String,
+    /// Data inputs as `(port, register)`, sorted by port.
+    pub(super) inputs: Vec<(u16, Slot)>,
+    /// Whether the node waits for an incoming control edge.
+    pub(super) gated: bool,
+    pub(super) successors: Vec<Successor>,
+    /// Type on the first outgoing data edge, for tagging untyped compound
+    /// results (see [`super::state::output_type`]).
+    pub(super) output_type: Option<TypeId>,
+    /// `Phi`: registers of the branch nodes behind its branch-indexed
+    /// control edges.
+    pub(super) decisions: Vec<Slot>,
+    /// `Loop`: body nodes reset when the loop continues, each with the
+    /// registers feeding it from outside the body.
+    pub(super) loop_body: Vec<(Slot, Vec<Slot>)>,
+}
+
+/// A function lowered to register bytecode.
+#[derive(Debug)]
+pub struct FunctionCode {
+    pub(super) function_id: FunctionId,
+    pub(super) instrs: Vec<Instr>,
+    /// `Parameter` instructions and the argument index each is seeded with.
+    pub(super) parameters: Vec<(Slot, usize)>,
+    /// Initial work list.
+    pub(super) seeds: Vec<Slot>,
+    /// Evaluation order for functions without value-dependent control flow.
+    pub(super) straight_line: Option<Vec<Slot>>,
+}
+
+impl FunctionCode {
+    /// Lowers `function_id` of `graph`.
+    pub fn lower(graph: &ProgramGraph, function_id: FunctionId) -> Result<Self, LowerError> {
+        let nodes = graph.function_nodes(function_id);
+        let slots: HashMap<NodeId, Slot> = nodes
+            .iter()
+            .enumerate()
+            .map(|(slot, &node_id)| (node_id, slot as Slot))
+            .collect();
+        let slot_of = |node: NodeId, other: NodeId| {
+            slots
+                .get(&other)
+                .copied()
+                .ok_or(LowerError::CrossFunctionEdge {
+                    function: function_id,
+                    node,
+                    other,
+                })
+        };
+
+        let mut instrs = Vec::with_capacity(nodes.len());
+        for &node_id in &nodes {
+            let op = graph
+                .get_compute_node(node_id)
+                .map(|node| node.op.clone())
+                .expect("function_nodes returns existing nodes");
+            let mut inputs = Vec::new();
+            let mut gated = false;
+            let mut decisions = Vec::new();
+            for edge in graph
+                .compute()
+                .edges_directed(node_id.into(), Direction::Incoming)
+            {
+                let source = slot_of(node_id, NodeId::from(edge.source()))?;
+                match edge.weight() {
+                    FlowEdge::Data { target_port, .. } => inputs.push((*target_port, source)),
+                    FlowEdge::Control { branch_index } => {
+                        gated = true;
+                        if branch_index.is_some() {
+                            decisions.push(source);
+                        }
+                    }
+                }
+            }
+            inputs.sort_by_key(|(port, _)| *port);
+            let successors = graph
+                .compute()
+                .edges_directed(node_id.into(), Direction::Outgoing)
+                .map(|edge| {
+                    let target = slot_of(node_id, NodeId::from(edge.target()))?;
+                    Ok(match edge.weight() {
+                        FlowEdge::Data { .. } => Successor::Data(target),
+                        FlowEdge::Control { branch_index } => {
+                            Successor::Control(target, *branch_index)
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            instrs.push(Instr {
+                node_id,
+                description: format!("{:?}", op),
+                op,
+                inputs,
+                gated,
+                successors,
+                output_type: super::state::output_type(graph, node_id),
+                decisions,
+                loop_body: Vec::new(),
+            });
+        }
+
+        for slot in 0..instrs.len() {
+            if matches!(instrs[slot].op, ComputeNodeOp::Core(ComputeOp::Loop)) {
+                instrs[slot].loop_body = loop_body(&instrs, slot as Slot);
+            }
+        }
+
+        let mut parameters = Vec::new();
+        let mut seeds = Vec::new();
+        for (slot, instr) in instrs.iter().enumerate() {
+            let slot = slot as Slot;
+            match &instr.op {
+                ComputeNodeOp::Core(ComputeOp::Parameter { index }) => {
+                    parameters.push((slot, *index as usize));
+                    seeds.push(slot);
+                }
+                ComputeNodeOp::Core(ComputeOp::Old { .. }) => seeds.push(slot),
+                ComputeNodeOp::Core(ComputeOp::MakeClosure { function })
+                    if !graph
+                        .get_function(*function)
+                        .is_some_and(|def| def.captures.is_empty()) => {}
+                ComputeNodeOp::Core(
+                    ComputeOp::Const { .. }
+                    | ComputeOp::CaptureAccess { .. }
+                    | ComputeOp::MakeClosure { .. }
+                    | ComputeOp::Alloc
+                    | ComputeOp::ReadLine
+                    | ComputeOp::Now
+                    | ComputeOp::Random,
+                ) if !instr.gated => seeds.push(slot),
+                _ => {}
+            }
+        }
+
+        let mut code = FunctionCode {
+            function_id,
+            instrs,
+            parameters,
+            seeds,
+            straight_line: None,
+        };
+        code.straight_line = code.simulate_schedule();
+        Ok(code)
+    }
+
+    /// The function this code was lowered from.
+    pub fn function_id(&self) -> FunctionId {
+        self.function_id
+    }
+
+    /// Number of instructions (one per compute node).
+    pub fn len(&self) -> usize {
+        self.instrs.len()
+    }
+
+    /// Whether the function has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.instrs.is_empty()
+    }
+
+    /// Whether the VM runs this function without a work list.
+    pub fn is_straight_line(&self) -> bool {
+        self.straight_line.is_some()
+    }
+
+    /// The node evaluation order of a function whose scheduling does not
+    /// depend on values, found by running the work-list algorithm without
+    /// evaluating anything. Ends at the first `Return`; a schedule that runs
+    /// out without one deadlocks.
+    fn simulate_schedule(&self) -> Option<Vec<Slot>> {
+        if self
+            .instrs
+            .iter()
+            .any(|instr| has_dynamic_schedule(&instr.op))
+        {
+            return None;
+        }
+        // Each node runs once plus once per late control edge re-enabling a
+        // call; anything longer is a call re-enabling itself forever.
+        let bound = self.instrs.len()
+            + self
+                .instrs
+                .iter()
+                .map(|instr| instr.successors.len())
+                .sum::<usize>();
+        let mut scheduler = Scheduler::new(self);
+        let mut order = Vec::new();
+        while let Some(slot) = scheduler.next_ready(self) {
+            if order.len() == bound {
+                return None;
+            }
+            order.push(slot);
+            match &self.instrs[slot as usize].op {
+                ComputeNodeOp::Core(ComputeOp::Return) => break,
+                // A call is not marked evaluated; its successors are
+                // released when the callee returns, before the caller's
+                // next step.
+                ComputeNodeOp::Core(ComputeOp::Call { .. } | ComputeOp::IndirectCall) => {}
+                _ => scheduler.evaluated[slot as usize] = true,
+            }
+            scheduler.propagate(self, slot);
+        }
+        Some(order)
+    }
+}
+
+/// Whether an op's effect on scheduling depends on the values it sees.
+fn has_dynamic_schedule(op: &ComputeNodeOp) -> bool {
+    matches!(
+        op,
+        ComputeNodeOp::Core(
+            ComputeOp::Branch
+                | ComputeOp::IfElse
+                | ComputeOp::Loop
+                | ComputeOp::Match
+                | ComputeOp::ForRange { .. }
+                | ComputeOp::ForEach { .. }
+                | ComputeOp::ForAll { .. }
+                | ComputeOp::Exists { .. }
+        ) | ComputeNodeOp::Structured(
+            StructuredOp::ArrayMap | StructuredOp::ArrayFold | StructuredOp::ArrayFilter
+        )
+    )
+}
+
+/// The nodes a `Loop` at `slot` resets when it continues: everything reached
+/// from its arm-0 control successors, without passing through the loop
+/// itself, each with its data sources outside that set.
+fn loop_body(instrs: &[Instr], slot: Slot) -> Vec<(Slot, Vec<Slot>)> {
+    let mut body = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    for successor in &instrs[slot as usize].successors {
+        if let Successor::Control(target, None | Some(0)) = *successor {
+            if visited.insert(target) {
+                queue.push_back(target);
+                body.push(target);
+            }
+        }
+    }
+    while let Some(current) = queue.pop_front() {
+        for successor in &instrs[current as usize].successors {
+            let (Successor::Data(target) | Successor::Control(target, _)) = *successor;
+            if target != slot && visited.insert(target) {
+                queue.push_back(target);
+                body.push(target);
+            }
+        }
+    }
+    body.into_iter()
+        .map(|node| {
+            let external = instrs[node as usize]
+                .inputs
+                .iter()
+                .map(|&(_, source)| source)
+                .filter(|source| *source != slot && !visited.contains(source))
+                .collect();
+            (node, external)
+        })
+        .collect()
+}
+
+/// Work-list scheduling state of one frame, indexed by slot.
+///
+/// Mirrors the [`Interpreter`](super::Interpreter)'s work list exactly,
+/// including the order deferred nodes are re-queued in, so both engines
+/// evaluate nodes in the same order.
+#[derive(Debug, Clone)]
+pub(super) struct Scheduler {
+    work_list: VecDeque<Slot>,
+    /// Whether a slot is in `work_list`.
+    queued: Vec<bool>,
+    pub(super) readiness: Vec<usize>,
+    pub(super) control_ready: Vec<bool>,
+    pub(super) evaluated: Vec<bool>,
+}
+
+impl Scheduler {
+    /// A fresh frame's state: only the seeds are queued.
+    pub(super) fn new(code: &FunctionCode) -> Self {
+        let len = code.instrs.len();
+        let mut queued = vec![false; len];
+        for &slot in &code.seeds {
+            queued[slot as usize] = true;
+        }
+        Scheduler {
+            work_list: code.seeds.iter().copied().collect(),
+            queued,
+            readiness: vec![0; len],
+            control_ready: vec![false; len],
+            evaluated: vec![false; len],
+        }
+    }
+
+    /// Whether `slot` has its control and data inputs.
+    fn is_ready(&self, code: &FunctionCode, slot: Slot) -> bool {
+        let instr = &code.instrs[slot as usize];
+        (!instr.gated || self.control_ready[slot as usize])
+            && self.readiness[slot as usize] >= instr.inputs.len()
+    }
+
+    /// Pops the first ready node, moving the unready nodes before it to the
+    /// back of the work list.
+    pub(super) fn next_ready(&mut self, code: &FunctionCode) -> Option<Slot> {
+        let mut deferred = Vec::new();
+        let mut found = None;
+        while let Some(slot) = self.work_list.pop_front() {
+            if self.evaluated[slot as usize] {
+                self.queued[slot as usize] = false;
+                continue;
+            }
+            if self.is_ready(code, slot) {
+                self.queued[slot as usize] = false;
+                found = Some(slot);
+                break;
+            }
+            deferred.push(slot);
+        }
+        self.work_list.extend(deferred);
+        found
+    }
+
+    /// Queues `slot` if it is unevaluated, ready and not already queued.
+    pub(super) fn try_schedule(&mut self, code: &FunctionCode, slot: Slot) {
+        if self.evaluated[slot as usize] || !self.is_ready(code, slot) {
+            return;
+        }
+        if !self.queued[slot as usize] {
+            self.queued[slot as usize] = true;
+            self.work_list.push_back(slot);
+        }
+    }
+
+    /// Gives every data successor of `slot` one more ready input and makes
+    /// every control successor control-ready, then schedules them.
+    pub(super) fn propagate(&mut self, code: &FunctionCode, slot: Slot) {
+        let successors = &code.instrs[slot as usize].successors;
+        for successor in successors {
+            match *successor {
+                Successor::Data(target) => self.readiness[target as usize] += 1,
+                Successor::Control(target, _) => self.control_ready[target as usize] = true,
+            }
+        }
+        for successor in successors {
+            let (Successor::Data(target) | Successor::Control(target, _)) = *successor;
+            self.try_schedule(code, target);
+        }
+    }
+}
+
+/// Every function of a graph lowered to bytecode.
+#[derive(Debug, Default)]
+pub struct BytecodeProgram {
+    functions: HashMap<FunctionId, Arc<FunctionCode>>,
+}
+
+impl BytecodeProgram {
+    /// The code of `function_id`, if the graph defines it.
+    pub fn function(&self, function_id: FunctionId) -> Option<&Arc<FunctionCode>> {
+        self.functions.get(&function_id)
+    }
+}
+
+/// Lowered functions shared across runs, keyed by function hash and edge
+/// layout. Cloning shares the cache.
+#[derive(Debug, Clone, Default)]
+pub struct BytecodeCache {
+    functions: Arc<Mutex<HashMap<blake3::Hash, Arc<FunctionCode>>>>,
+}
+
+impl BytecodeCache {
+    /// An empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of lowered functions held.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether no function has been lowered yet.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Lowers every function of `graph`, reusing cached code for functions
+    /// whose hash and layout are unchanged.
+    pub fn lower(&self, graph: &ProgramGraph) -> Result<BytecodeProgram, LowerError> {
+        let mut functions = HashMap::new();
+        for &function_id in graph.functions().keys() {
+            let key = layout_hash(graph, function_id);
+            let cached = self.lock().get(&key).cloned();
+            let code = match cached {
+                Some(code) => code,
+                None => {
+                    let code = Arc::new(FunctionCode::lower(graph, function_id)?);
+                    let mut cache = self.lock();
+                    if cache.len() >= MAX_CACHED_FUNCTIONS {
+                        cache.clear();
+                    }
+                    cache.insert(key, code.clone());
+                    code
+                }
+            };
+            functions.insert(function_id, code);
+        }
+        Ok(BytecodeProgram { functions })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<blake3::Hash, Arc<FunctionCode>>> {
+        self.functions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// [`hash_function`] extended with everything else lowering depends on:
+/// node order, the exact endpoints and order of every edge, and whether each
+/// `MakeClosure` target has captures.
+pub(crate) fn layout_hash(graph: &ProgramGraph, function_id: FunctionId) -> blake3::Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(hash_function(graph, function_id).as_bytes());
+    hasher.update(&function_id.0.to_le_bytes());
+    for node_id in graph.function_nodes(function_id) {
+        hasher.update(&node_id.0.to_le_bytes());
+        for direction in [Direction::Incoming, Direction::Outgoing] {
+            for edge in graph.compute().edges_directed(node_id.into(), direction) {
+                let other = match direction {
+                    Direction::Incoming => edge.source(),
+                    Direction::Outgoing => edge.target(),
+                };
+                hasher.update(&(other.index() as u32).to_le_bytes());
+                match edge.weight() {
+                    FlowEdge::Data { target_port, .. } => {
+                        hasher.update(&[0]);
+                        hasher.update(&target_port.to_le_bytes());
+                    }
+                    FlowEdge::Control { branch_index } => {
+                        hasher.update(&[1]);
+                        hasher.update(&branch_index.map_or(u32::MAX, u32::from).to_le_bytes());
+                    }
+                }
+            }
+            hasher.update(&[0xff]);
+        }
+        if let Some(ComputeNodeOp::Core(ComputeOp::MakeClosure { function })) =
+            graph.get_compute_node(node_id).map(|node| &node.op)
+        {
+            let captures = graph
+                .get_function(*function)
+                .map_or(u64::MAX, |def| def.captures.len() as u64);
+            hasher.update(&captures.to_le_bytes());
+        }
+    }
+    hasher.finalize()
+}
@@ -0,0 +1,287 @@
+//! Node and branch coverage recording for the graph interpreter.
+//!
+//! When coverage is enabled via [`InterpreterConfig::coverage_enabled`], the
+//! interpreter counts every node evaluation and every arm taken out of a
+//! `Branch`, `IfElse` or `Match`. Coverage from several runs is combined with
+//! [`Coverage::merge`], and [`Coverage::summarize`] reports what a set of
+//! functions left unexercised.
+//!
+//! [`InterpreterConfig::coverage_enabled`]: super::InterpreterConfig::coverage_enabled
+
+use std::collections::{HashMap, HashSet};
+
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+
+use lmlang_core::edge::FlowEdge;
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::ops::{ComputeNodeOp, ComputeOp};
+
+/// Evaluation counts per node and per branch arm.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Coverage {
+    node_hits: HashMap<NodeId, u64>,
+    arm_hits: HashMap<(NodeId, u16), u64>,
+}
+
+impl Coverage {
+    /// Counts one evaluation of `node`.
+    pub fn record_node(&mut self, node: NodeId) {
+        *self.node_hits.entry(node).or_default() += 1;
+    }
+
+    /// Counts one transfer of control out of `node` through `arm`.
+    pub fn record_arm(&mut self, node: NodeId, arm: u16) {
+        *self.arm_hits.entry((node, arm)).or_default() += 1;
+    }
+
+    /// Number of times `node` was evaluated.
+    pub fn node_hits(&self, node: NodeId) -> u64 {
+        self.node_hits.get(&node).copied().unwrap_or(0)
+    }
+
+    /// Number of times `arm` of `node` was taken.
+    pub fn arm_hits(&self, node: NodeId, arm: u16) -> u64 {
+        self.arm_hits.get(&(node, arm)).copied().unwrap_or(0)
+    }
+
+    /// Whether nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.node_hits.is_empty() && self.arm_hits.is_empty()
+    }
+
+    /// Adds the counts of `other` to these.
+    pub fn merge(&mut self, other: &Coverage) {
+        for (node, hits) in &other.node_hits {
+            *self.node_hits.entry(*node).or_default() += hits;
+        }
+        for (arm, hits) in &other.arm_hits {
+            *self.arm_hits.entry(*arm).or_default() += hits;
+        }
+    }
+
+    /// Coverage of every node and branch arm in `functions`.
+    pub fn summarize(&self, graph: &ProgramGraph, functions: &[FunctionId]) -> CoverageSummary {
+        let mut nodes = Vec::new();
+        let mut arms = Vec::new();
+        for &function_id in functions {
+            let mut function_nodes = graph.function_nodes(function_id);
+            function_nodes.sort_by_key(|n| n.0);
+            for node_id in function_nodes {
+                nodes.push(NodeCoverage {
+                    node_id,
+                    function_id,
+                    hits: self.node_hits(node_id),
+                });
+                arms.extend(
+                    branch_arms(graph, node_id)
+                        .into_iter()
+                        .map(|arm| ArmCoverage {
+                            node_id,
+                            arm,
+                            hits: self.arm_hits(node_id, arm),
+                        }),
+                );
+            }
+        }
+
+        CoverageSummary {
+            functions: functions.to_vec(),
+            nodes_total: nodes.len(),
+            nodes_covered: nodes.iter().filter(|n| n.hits > 0).count(),
+            arms_total: arms.len(),
+            arms_covered: arms.iter().filter(|a| a.hits > 0).count(),
+            uncovered_nodes: nodes
+                .iter()
+                .filter(|n| n.hits == 0)
+                .map(|n| n.node_id)
+                .collect(),
+            nodes,
+            arms,
+        }
+    }
+}
+
+/// Coverage of one node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeCoverage {
+    pub node_id: NodeId,
+    pub function_id: FunctionId,
+    /// Number of evaluations.
+    pub hits: u64,
+}
+
+/// Coverage of one arm of a `Branch`, `IfElse` or `Match`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArmCoverage {
+    pub node_id: NodeId,
+    /// Branch index: 0 = true and 1 = false for `Branch`/`IfElse`, the
+    /// matched value for `Match`.
+    pub arm: u16,
+    /// Number of times the arm was taken.
+    pub hits: u64,
+}
+
+/// Node and arm coverage over a set of functions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageSummary {
+    /// Functions whose nodes are counted.
+    pub functions: Vec<FunctionId>,
+    pub nodes_total: usize,
+    pub nodes_covered: usize,
+    pub arms_total: usize,
+    pub arms_covered: usize,
+    /// Nodes never evaluated.
+    pub uncovered_nodes: Vec<NodeId>,
+    pub nodes: Vec<NodeCoverage>,
+    pub arms: Vec<ArmCoverage>,
+}
+
+/// The arms of `node` if it is a `Branch`, `IfElse` or `Match`: both
+/// outcomes of a two-way branch, and the arms with outgoing control edges
+/// of a match.
+pub fn branch_arms(graph: &ProgramGraph, node: NodeId) -> Vec<u16> {
+    match graph.get_compute_node(node).map(|n| &n.op) {
+        Some(ComputeNodeOp::Core(ComputeOp::Branch | ComputeOp::IfElse)) => vec![0, 1],
+        Some(ComputeNodeOp::Core(ComputeOp::Match)) => {
+            let mut arms: Vec<u16> = graph
+                .compute()
+                .edges_directed(node.into(), Direction::Outgoing)
+                .filter_map(|edge| match edge.weight() {
+                    FlowEdge::Control {
+                        branch_index: Some(arm),
+                    } => Some(*arm),
+                    _ => None,
+                })
+                .collect();
+            arms.sort_unstable();
+            arms.dedup();
+            arms
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// `root` and every function it can transitively invoke, through calls,
+/// loop bodies and closures, sorted by ID.
+pub fn reachable_functions(graph: &ProgramGraph, root: FunctionId) -> Vec<FunctionId> {
+    let mut seen: HashSet<FunctionId> = HashSet::from([root]);
+    let mut stack = vec![root];
+    while let Some(function_id) = stack.pop() {
+        for node_id in graph.function_nodes(function_id) {
+            let target = match graph.get_compute_node(node_id).map(|n| &n.op) {
+                Some(ComputeNodeOp::Core(ComputeOp::MakeClosure { function })) => Some(*function),
+                Some(ComputeNodeOp::Core(op)) => op.call_target(),
+                _ => None,
+            };
+            if let Some(target) = target {
+                if graph.get_function(target).is_some() && seen.insert(target) {
+                    stack.push(target);
+                }
+            }
+        }
+    }
+    let mut functions: Vec<FunctionId> = seen.into_iter().collect();
+    functions.sort_by_key(|f| f.0);
+    functions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::{ExecutionState, Interpreter, InterpreterConfig, Value};
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::{ConstValue, Visibility};
+
+    /// Helper: `pick(flag: bool) -> i32` returning 1 on the true arm and 2 on
+    /// the false arm. Returns the graph, function and `(then, else)` returns.
+    fn build_pick() -> (ProgramGraph, FunctionId, NodeId, NodeId) {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function(
+                "pick".into(),
+                root,
+                vec![("flag".into(), TypeId::BOOL)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let flag = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func_id)
+            .unwrap();
+        let branch = graph.add_core_op(ComputeOp::Branch, func_id).unwrap();
+        graph
+            .add_data_edge(flag, branch, 0, 0, TypeId::BOOL)
+            .unwrap();
+
+        let mut returns = Vec::new();
+        for (arm, value) in [(0, 1), (1, 2)] {
+            let konst = graph
+                .add_core_op(
+                    ComputeOp::Const {
+                        value: ConstValue::I32(value),
+                    },
+                    func_id,
+                )
+                .unwrap();
+            graph.add_control_edge(branch, konst, Some(arm)).unwrap();
+            let ret = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+            graph.add_data_edge(konst, ret, 0, 0, TypeId::I32).unwrap();
+            returns.push(ret);
+        }
+        (graph, func_id, returns[0], returns[1])
+    }
+
+    fn run(graph: &ProgramGraph, func_id: FunctionId, flag: bool) -> Coverage {
+        let config = InterpreterConfig {
+            coverage_enabled: true,
+            ..Default::default()
+        };
+        let mut interp = Interpreter::new(graph, config);
+        interp.start(func_id, vec![Value::Bool(flag)]);
+        interp.run();
+        assert!(matches!(interp.state(), ExecutionState::Completed { .. }));
+        interp.coverage().cloned().expect("coverage enabled")
+    }
+
+    #[test]
+    fn single_run_leaves_other_arm_uncovered() {
+        let (graph, func_id, then_ret, else_ret) = build_pick();
+        let summary = run(&graph, func_id, true).summarize(&graph, &[func_id]);
+
+        assert_eq!(summary.nodes_total, 6);
+        assert_eq!(summary.nodes_covered, 4);
+        assert!(!summary.uncovered_nodes.contains(&then_ret));
+        assert!(summary.uncovered_nodes.contains(&else_ret));
+        assert_eq!(summary.arms_total, 2);
+        assert_eq!(summary.arms_covered, 1);
+        let hits: Vec<(u16, u64)> = summary.arms.iter().map(|a| (a.arm, a.hits)).collect();
+        assert_eq!(hits, vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn merged_runs_cover_both_arms() {
+        let (graph, func_id, _, _) = build_pick();
+        let mut coverage = run(&graph, func_id, true);
+        coverage.merge(&run(&graph, func_id, false));
+        coverage.merge(&run(&graph, func_id, false));
+
+        let summary = coverage.summarize(&graph, &[func_id]);
+        assert_eq!(summary.nodes_covered, summary.nodes_total);
+        assert!(summary.uncovered_nodes.is_empty());
+        assert_eq!(summary.arms_covered, 2);
+        let hits: Vec<u64> = summary.arms.iter().map(|a| a.hits).collect();
+        assert_eq!(hits, vec![1, 2]);
+    }
+
+    #[test]
+    fn coverage_is_off_by_default() {
+        let (graph, func_id, _, _) = build_pick();
+        let mut interp = Interpreter::new(&graph, InterpreterConfig::default());
+        interp.start(func_id, vec![Value::Bool(true)]);
+        interp.run();
+        assert!(interp.coverage().is_none());
+    }
+}
@@ -0,0 +1,605 @@
+//! Interactive debugging on top of the step-wise interpreter.
+//!
+//! [`Debugger`] drives an [`Interpreter`] in source-level increments: step
+//! into, over or out of `Call`s (and structured loop bodies, which also run
+//! in their own frames), or continue until a breakpoint. Breakpoints stop
+//! *before* the node is evaluated. With a checkpoint interval configured,
+//! execution can also step backward or rewind to the last write of a node or
+//! memory cell. [`WatchExpr`] reads values out of a call
+//! frame or interpreter memory, projecting through struct fields, array
+//! elements, enum payloads and pointers.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+
+use super::history::{StepRecord, WriteTarget};
+use super::state::{ExecutionState, Interpreter};
+use super::value::Value;
+
+/// How far a [`Debugger::step`] advances execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepMode {
+    /// Evaluate one node, entering the callee of a `Call`.
+    Into,
+    /// Evaluate one node, running any call it makes to completion.
+    Over,
+    /// Run until the current frame returns to its caller.
+    Out,
+    /// Run until a breakpoint is reached or execution finishes.
+    Continue,
+    /// Undo the last node evaluation (requires a checkpoint interval).
+    Back,
+}
+
+/// Why a [`Debugger::step`] stopped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum StopReason {
+    /// The requested step completed.
+    Step,
+    /// The next node to evaluate has a breakpoint.
+    Breakpoint { node_id: NodeId },
+    /// Stepping back reached the start of execution, or history is disabled.
+    HistoryStart,
+    /// The debugger's step limit ran out before the step completed.
+    StepLimit { steps: u64 },
+    /// Execution completed, errored or hit a contract violation.
+    Finished,
+}
+
+/// Breakpoints and stepping over an interpreter run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Debugger {
+    breakpoints: HashSet<NodeId>,
+    /// Node a breakpoint or reverse step stopped before; resuming evaluates
+    /// it instead of stopping there again.
+    stopped_at: Option<NodeId>,
+    /// Maximum nodes evaluated by one [`step`](Self::step).
+    step_limit: Option<u64>,
+}
+
+impl Debugger {
+    /// Creates a debugger without breakpoints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bounds the nodes a single [`step`](Self::step) may evaluate, so
+    /// stepping over or continuing through a non-terminating loop returns.
+    pub fn with_step_limit(mut self, limit: u64) -> Self {
+        self.step_limit = Some(limit);
+        self
+    }
+
+    /// Replaces all breakpoints.
+    pub fn set_breakpoints(&mut self, nodes: impl IntoIterator<Item = NodeId>) {
+        self.breakpoints = nodes.into_iter().collect();
+    }
+
+    /// Adds a breakpoint before `node`.
+    pub fn add_breakpoint(&mut self, node: NodeId) {
+        self.breakpoints.insert(node);
+    }
+
+    /// Removes the breakpoint before `node`, returning whether it was set.
+    pub fn remove_breakpoint(&mut self, node: NodeId) -> bool {
+        self.breakpoints.remove(&node)
+    }
+
+    /// The breakpoints, sorted by node ID.
+    pub fn breakpoints(&self) -> Vec<NodeId> {
+        let mut nodes: Vec<NodeId> = self.breakpoints.iter().copied().collect();
+        nodes.sort_by_key(|n| n.0);
+        nodes
+    }
+
+    /// Advances `interp` according to `mode`.
+    ///
+    /// Stops before any node with a breakpoint, except the one execution is
+    /// already stopped at. Without a step limit, continuing through a
+    /// non-terminating loop never returns.
+    pub fn step(&mut self, interp: &mut Interpreter<'_>, mode: StepMode) -> StopReason {
+        if mode == StepMode::Back {
+            if !interp.step_back() {
+                return StopReason::HistoryStart;
+            }
+            self.stopped_at = interp.next_node();
+            return StopReason::Step;
+        }
+
+        let depth = interp.call_depth();
+        let mut first = true;
+        let mut steps = 0;
+        loop {
+            if !matches!(
+                interp.state(),
+                ExecutionState::Running | ExecutionState::Paused { .. }
+            ) {
+                self.stopped_at = None;
+                return StopReason::Finished;
+            }
+            if !first {
+                let done = match mode {
+                    StepMode::Into => true,
+                    StepMode::Over => interp.call_depth() <= depth,
+                    StepMode::Out => interp.call_depth() < depth,
+                    StepMode::Continue | StepMode::Back => false,
+                };
+                if done {
+                    self.stopped_at = None;
+                    return StopReason::Step;
+                }
+            }
+            if let Some(node_id) = interp.next_node() {
+                let resuming = first && self.stopped_at == Some(node_id);
+                if !resuming && self.breakpoints.contains(&node_id) {
+                    self.stopped_at = Some(node_id);
+                    return StopReason::Breakpoint { node_id };
+                }
+            }
+            if self.step_limit.is_some_and(|limit| steps >= limit) {
+                self.stopped_at = None;
+                return StopReason::StepLimit { steps };
+            }
+            first = false;
+            steps += 1;
+            interp.step();
+        }
+    }
+
+    /// Rewinds `interp` to just before the last write of `target`; see
+    /// [`Interpreter::rewind_to_last_write`].
+    pub fn rewind_to_write(
+        &mut self,
+        interp: &mut Interpreter<'_>,
+        target: &WriteTarget,
+    ) -> Option<StepRecord> {
+        let record = interp.rewind_to_last_write(target)?;
+        self.stopped_at = interp.next_node();
+        Some(record)
+    }
+}
+
+/// Contract nodes (preconditions, postconditions and invariants) of
+/// `function`, or of every function if `None`, sorted by node ID.
+pub fn contract_nodes(graph: &ProgramGraph, function: Option<FunctionId>) -> Vec<NodeId> {
+    let functions: Vec<FunctionId> = match function {
+        Some(function_id) => vec![function_id],
+        None => graph.functions().keys().copied().collect(),
+    };
+    let mut nodes: Vec<NodeId> = functions
+        .into_iter()
+        .flat_map(|function_id| graph.function_nodes(function_id))
+        .filter(|node_id| {
+            graph
+                .get_compute_node(*node_id)
+                .is_some_and(|node| node.op.is_contract())
+        })
+        .collect();
+    nodes.sort_by_key(|n| n.0);
+    nodes
+}
+
+/// Where a watch expression reads its root value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WatchTarget {
+    /// The value a node produced.
+    Node { node_id: NodeId },
+    /// An argument of the frame's function.
+    Argument { index: usize },
+    /// A closure capture of the frame.
+    Capture { index: usize },
+    /// A memory cell.
+    Memory { address: usize },
+}
+
+/// A value to inspect while execution is stopped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchExpr {
+    #[serde(flatten)]
+    pub target: WatchTarget,
+    /// Field, element or payload (index 0) indices applied in order to the
+    /// root value. Pointers are dereferenced before indexing.
+    #[serde(default)]
+    pub path: Vec<usize>,
+    /// Call stack index to read from (0 = outermost). Defaults to the
+    /// innermost frame, or for node targets the innermost frame running the
+    /// node's function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame: Option<usize>,
+}
+
+/// Why a watch expression could not be evaluated.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WatchError {
+    #[error("frame {frame} out of range (call depth {depth})")]
+    FrameOutOfRange { frame: usize, depth: usize },
+
+    #[error("node {0} has not produced a value in the selected frame")]
+    NoValue(NodeId),
+
+    #[error("argument {index} out of range ({count} arguments)")]
+    ArgumentOutOfRange { index: usize, count: usize },
+
+    #[error("capture {index} out of range ({count} captures)")]
+    CaptureOutOfRange { index: usize, count: usize },
+
+    #[error("memory address {0} out of range")]
+    AddressOutOfRange(usize),
+
+    #[error("cannot index {value_type} value with {index}")]
+    InvalidPath {
+        index: usize,
+        value_type: &'static str,
+    },
+}
+
+/// Evaluates `expr` against the current state of `interp`.
+pub fn evaluate_watch(
+    graph: &ProgramGraph,
+    interp: &Interpreter<'_>,
+    expr: &WatchExpr,
+) -> Result<Value, WatchError> {
+    let stack = interp.call_stack();
+    let frame = match expr.frame {
+        Some(frame) => Some(stack.get(frame).ok_or(WatchError::FrameOutOfRange {
+            frame,
+            depth: stack.len(),
+        })?),
+        None => match &expr.target {
+            WatchTarget::Node { node_id } => {
+                let owner = graph.get_compute_node(*node_id).map(|n| n.owner);
+                stack
+                    .iter()
+                    .rev()
+                    .find(|frame| Some(frame.function_id) == owner)
+            }
+            _ => stack.last(),
+        },
+    };
+
+    let root = match &expr.target {
+        WatchTarget::Node { node_id } => frame
+            .and_then(|frame| frame.node_values.get(node_id))
+            .ok_or(WatchError::NoValue(*node_id))?,
+        WatchTarget::Argument { index } => {
+            let arguments = frame.map_or(&[][..], |frame| &frame.arguments);
+            arguments
+                .get(*index)
+                .ok_or(WatchError::ArgumentOutOfRange {
+                    index: *index,
+                    count: arguments.len(),
+                })?
+        }
+        WatchTarget::Capture { index } => {
+            let captures = frame.map_or(&[][..], |frame| &frame.captures);
+            captures.get(*index).ok_or(WatchError::CaptureOutOfRange {
+                index: *index,
+                count: captures.len(),
+            })?
+        }
+        WatchTarget::Memory { address } => interp
+            .memory()
+            .get(*address)
+            .ok_or(WatchError::AddressOutOfRange(*address))?,
+    };
+
+    let mut value = root;
+    for &index in &expr.path {
+        if let Value::Pointer { address, .. } = value {
+            value = interp
+                .memory()
+                .get(*address)
+                .ok_or(WatchError::AddressOutOfRange(*address))?;
+        }
+        value = match value {
+            Value::Struct { fields: items, .. }
+            | Value::Array {
+                elements: items, ..
+            } => items.get(index),
+            Value::Enum { payload, .. } if index == 0 => Some(payload.as_ref()),
+            _ => None,
+        }
+        .ok_or(WatchError::InvalidPath {
+            index,
+            value_type: value.type_name(),
+        })?;
+    }
+    Ok(value.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::InterpreterConfig;
+    use lmlang_core::ops::{ArithOp, ComputeOp};
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::{ConstValue, Visibility};
+
+    struct Program {
+        graph: ProgramGraph,
+        main: FunctionId,
+        call: NodeId,
+        main_ret: NodeId,
+        inc_add: NodeId,
+    }
+
+    /// Helper: `main(x) = inc(x)` with `inc(x) = x + 1`, both i32.
+    fn build_program() -> Program {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let add_fn = |graph: &mut ProgramGraph, name: &str| {
+            graph
+                .add_function(
+                    name.into(),
+                    root,
+                    vec![("x".into(), TypeId::I32)],
+                    TypeId::I32,
+                    Visibility::Public,
+                )
+                .unwrap()
+        };
+
+        let inc = add_fn(&mut graph, "inc");
+        let param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, inc)
+            .unwrap();
+        let one = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: ConstValue::I32(1),
+                },
+                inc,
+            )
+            .unwrap();
+        let inc_add = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Add }, inc)
+            .unwrap();
+        let inc_ret = graph.add_core_op(ComputeOp::Return, inc).unwrap();
+        graph
+            .add_data_edge(param, inc_add, 0, 0, TypeId::I32)
+            .unwrap();
+        graph
+            .add_data_edge(one, inc_add, 0, 1, TypeId::I32)
+            .unwrap();
+        graph
+            .add_data_edge(inc_add, inc_ret, 0, 0, TypeId::I32)
+            .unwrap();
+
+        let main = add_fn(&mut graph, "main");
+        let param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, main)
+            .unwrap();
+        let call = graph
+            .add_core_op(ComputeOp::Call { target: inc }, main)
+            .unwrap();
+        let main_ret = graph.add_core_op(ComputeOp::Return, main).unwrap();
+        graph.add_data_edge(param, call, 0, 0, TypeId::I32).unwrap();
+        graph
+            .add_data_edge(call, main_ret, 0, 0, TypeId::I32)
+            .unwrap();
+
+        Program {
+            graph,
+            main,
+            call,
+            main_ret,
+            inc_add,
+        }
+    }
+
+    fn start(program: &Program) -> Interpreter<'_> {
+        let mut interp = Interpreter::new(&program.graph, InterpreterConfig::default());
+        interp.start(program.main, vec![Value::I32(41)]);
+        interp
+    }
+
+    fn result(interp: &Interpreter<'_>) -> Option<Value> {
+        match interp.state() {
+            ExecutionState::Completed { result } => Some(result.clone()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn step_over_runs_call_without_entering_it() {
+        let program = build_program();
+        let mut interp = start(&program);
+        let mut debugger = Debugger::new();
+
+        assert_eq!(debugger.step(&mut interp, StepMode::Over), StopReason::Step);
+        assert_eq!(interp.next_node(), Some(program.call));
+        assert_eq!(debugger.step(&mut interp, StepMode::Over), StopReason::Step);
+        assert_eq!(interp.call_depth(), 1);
+        assert_eq!(interp.next_node(), Some(program.main_ret));
+        assert_eq!(
+            debugger.step(&mut interp, StepMode::Over),
+            StopReason::Finished
+        );
+        assert_eq!(result(&interp), Some(Value::I32(42)));
+    }
+
+    #[test]
+    fn step_into_enters_callee_and_step_out_returns() {
+        let program = build_program();
+        let mut interp = start(&program);
+        let mut debugger = Debugger::new();
+
+        debugger.step(&mut interp, StepMode::Into);
+        assert_eq!(interp.next_node(), Some(program.call));
+        debugger.step(&mut interp, StepMode::Into);
+        assert_eq!(interp.call_depth(), 2);
+
+        assert_eq!(debugger.step(&mut interp, StepMode::Out), StopReason::Step);
+        assert_eq!(interp.call_depth(), 1);
+        assert_eq!(interp.next_node(), Some(program.main_ret));
+        let watch = WatchExpr {
+            target: WatchTarget::Node {
+                node_id: program.call,
+            },
+            path: vec![],
+            frame: None,
+        };
+        assert_eq!(
+            evaluate_watch(&program.graph, &interp, &watch),
+            Ok(Value::I32(42))
+        );
+    }
+
+    #[test]
+    fn continue_stops_at_breakpoints_and_resumes_past_them() {
+        let program = build_program();
+        let mut interp = start(&program);
+        let mut debugger = Debugger::new();
+        debugger.set_breakpoints([program.inc_add, program.main_ret]);
+
+        assert_eq!(
+            debugger.step(&mut interp, StepMode::Continue),
+            StopReason::Breakpoint {
+                node_id: program.inc_add
+            }
+        );
+        assert_eq!(interp.call_depth(), 2);
+        let argument = WatchExpr {
+            target: WatchTarget::Argument { index: 0 },
+            path: vec![],
+            frame: None,
+        };
+        assert_eq!(
+            evaluate_watch(&program.graph, &interp, &argument),
+            Ok(Value::I32(41))
+        );
+        let pending = WatchExpr {
+            target: WatchTarget::Node {
+                node_id: program.inc_add,
+            },
+            path: vec![],
+            frame: None,
+        };
+        assert_eq!(
+            evaluate_watch(&program.graph, &interp, &pending),
+            Err(WatchError::NoValue(program.inc_add))
+        );
+
+        assert_eq!(
+            debugger.step(&mut interp, StepMode::Continue),
+            StopReason::Breakpoint {
+                node_id: program.main_ret
+            }
+        );
+        assert!(debugger.remove_breakpoint(program.main_ret));
+        assert_eq!(
+            debugger.step(&mut interp, StepMode::Continue),
+            StopReason::Finished
+        );
+        assert_eq!(result(&interp), Some(Value::I32(42)));
+    }
+
+    #[test]
+    fn step_limit_bounds_continue() {
+        let program = build_program();
+        let mut interp = start(&program);
+        let mut debugger = Debugger::new().with_step_limit(2);
+
+        assert_eq!(
+            debugger.step(&mut interp, StepMode::Continue),
+            StopReason::StepLimit { steps: 2 }
+        );
+        // Parameter and Call evaluated: now inside `inc`.
+        assert_eq!(interp.call_depth(), 2);
+        while debugger.step(&mut interp, StepMode::Continue) != StopReason::Finished {}
+        assert_eq!(result(&interp), Some(Value::I32(42)));
+    }
+
+    #[test]
+    fn detached_run_resumes_after_attach() {
+        let program = build_program();
+        let mut interp = start(&program);
+        let mut debugger = Debugger::new();
+        debugger.add_breakpoint(program.inc_add);
+        debugger.step(&mut interp, StepMode::Continue);
+
+        let detached = interp.detach();
+        let mut interp = detached.attach(&program.graph);
+        assert_eq!(interp.next_node(), Some(program.inc_add));
+        debugger.step(&mut interp, StepMode::Continue);
+        assert_eq!(result(&interp), Some(Value::I32(42)));
+    }
+
+    #[test]
+    fn step_back_and_rewind_to_write_stop_before_earlier_nodes() {
+        let program = build_program();
+        let config = InterpreterConfig {
+            checkpoint_interval: Some(2),
+            ..Default::default()
+        };
+        let mut interp = Interpreter::new(&program.graph, config);
+        interp.start(program.main, vec![Value::I32(41)]);
+        let mut debugger = Debugger::new();
+        debugger.add_breakpoint(program.main_ret);
+
+        assert_eq!(
+            debugger.step(&mut interp, StepMode::Back),
+            StopReason::HistoryStart
+        );
+        debugger.step(&mut interp, StepMode::Continue);
+        assert_eq!(debugger.step(&mut interp, StepMode::Back), StopReason::Step);
+        assert_eq!(interp.call_depth(), 2);
+
+        let record = debugger
+            .rewind_to_write(
+                &mut interp,
+                &WriteTarget::Node {
+                    node_id: program.inc_add,
+                },
+            )
+            .unwrap();
+        assert_eq!(record.node_id, Some(program.inc_add));
+        assert_eq!(interp.next_node(), Some(program.inc_add));
+
+        // Resuming from the rewound position runs to the same result.
+        assert_eq!(
+            debugger.step(&mut interp, StepMode::Continue),
+            StopReason::Breakpoint {
+                node_id: program.main_ret
+            }
+        );
+        debugger.step(&mut interp, StepMode::Continue);
+        assert_eq!(result(&interp), Some(Value::I32(42)));
+    }
+
+    #[test]
+    fn watch_paths_index_aggregates_and_reject_scalars() {
+        let program = build_program();
+        let interp = start(&program);
+        let expr = WatchExpr {
+            target: WatchTarget::Argument { index: 0 },
+            path: vec![1],
+            frame: None,
+        };
+        assert_eq!(
+            evaluate_watch(&program.graph, &interp, &expr),
+            Err(WatchError::InvalidPath {
+                index: 1,
+                value_type: "I32"
+            })
+        );
+
+        let parsed: WatchExpr = serde_json::from_value(
+            serde_json::json!({"kind": "memory", "address": 3, "path": [0]}),
+        )
+        .unwrap();
+        assert_eq!(parsed.target, WatchTarget::Memory { address: 3 });
+        assert_eq!(parsed.path, vec![0]);
+        assert_eq!(
+            evaluate_watch(&program.graph, &interp, &parsed),
+            Err(WatchError::AddressOutOfRange(3))
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! Program entry-point convention shared by the interpreter and codegen.
+//!
+//! An entry function takes either no parameters or a single argument array
+//! parameter of type `[T; N]`, where `T` is an integer scalar (I8-I64).
+//! Command-line arguments after the program name fill the array in order:
+//! each is parsed as a base-10 integer with C `strtoll` semantics (leading
+//! whitespace and sign accepted, parsing stops at the first non-digit,
+//! out-of-range values saturate) and then truncated to `T`. Missing slots are
+//! zero and extra arguments are ignored.
+//!
+//! An integer return value becomes the process exit status (sign-extended or
+//! truncated to 32 bits). Any other return type exits with status 0.
+//!
+//! The compiled `main` wrapper in `lmlang-codegen` and [`EntryPoint::arguments`]
+//! implement the same rules, so a program behaves identically when run
+//! natively or through the [`Interpreter`](super::Interpreter).
+
+use lmlang_core::function::FunctionDef;
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::FunctionId;
+use lmlang_core::type_id::TypeId;
+use lmlang_core::types::{LmType, Visibility};
+use serde::{Deserialize, Serialize};
+
+use super::value::Value;
+
+/// Errors raised when a function does not satisfy the entry-point convention.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EntryError {
+    #[error("program has no functions")]
+    NoFunctions,
+
+    #[error("entry function '{name}' not found")]
+    FunctionNotFound { name: String },
+
+    #[error(
+        "entry function '{name}' must take zero parameters or one argument array, but has {count}"
+    )]
+    TooManyParameters { name: String, count: usize },
+
+    #[error("entry function '{name}' parameter must be an integer array [I8..I64; N], got type {type_id:?}")]
+    InvalidArgumentParameter { name: String, type_id: TypeId },
+}
+
+/// Shape of the optional argument array parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryArgs {
+    /// Integer element type (one of `TypeId::I8`..`TypeId::I64`).
+    pub element: TypeId,
+    /// Number of argument slots.
+    pub length: u32,
+    /// TypeId of the `[element; length]` array parameter itself.
+    pub array_type: TypeId,
+}
+
+/// A function validated against the entry-point convention.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryPoint {
+    /// The entry function.
+    pub function_id: FunctionId,
+    /// Argument array parameter, if the entry function takes one.
+    pub args: Option<EntryArgs>,
+    /// Declared return type of the entry function.
+    pub return_type: TypeId,
+}
+
+/// Selects the entry function for a program.
+///
+/// 1. If `name` is given, the function with that name.
+/// 2. Otherwise, the first function named "main".
+/// 3. Otherwise, the first public function.
+/// 4. Otherwise, the first function.
+pub fn select_entry_function<'g>(
+    graph: &'g ProgramGraph,
+    name: Option<&str>,
+) -> Result<&'g FunctionDef, EntryError> {
+    let functions = graph.functions();
+    if functions.is_empty() {
+        return Err(EntryError::NoFunctions);
+    }
+
+    if let Some(name) = name {
+        return functions.values().find(|f| f.name == name).ok_or_else(|| {
+            EntryError::FunctionNotFound {
+                name: name.to_string(),
+            }
+        });
+    }
+
+    functions
+        .values()
+        .find(|f| f.name == "main")
+        .or_else(|| {
+            functions
+                .values()
+                .find(|f| f.visibility == Visibility::Public)
+        })
+        .or_else(|| functions.values().next())
+        .ok_or(EntryError::NoFunctions)
+}
+
+/// Validates `func_def` against the entry-point convention.
+pub fn resolve_entry(
+    graph: &ProgramGraph,
+    func_def: &FunctionDef,
+) -> Result<EntryPoint, EntryError> {
+    let args = match func_def.params.as_slice() {
+        [] => None,
+        [(_, type_id)] => {
+            let array = match graph.types.get(*type_id) {
+                Some(LmType::Array { element, length }) if is_integer_type(*element) => EntryArgs {
+                    element: *element,
+                    length: *length,
+                    array_type: *type_id,
+                },
+                _ => {
+                    return Err(EntryError::InvalidArgumentParameter {
+                        name: func_def.name.clone(),
+                        type_id: *type_id,
+                    })
+                }
+            };
+            Some(array)
+        }
+        params => {
+            return Err(EntryError::TooManyParameters {
+                name: func_def.name.clone(),
+                count: params.len(),
+            })
+        }
+    };
+
+    Ok(EntryPoint {
+        function_id: func_def.id,
+        args,
+        return_type: func_def.return_type,
+    })
+}
+
+impl EntryPoint {
+    /// Builds the interpreter arguments for this entry point from
+    /// command-line arguments (excluding the program name).
+    pub fn arguments(&self, argv: &[String]) -> Vec<Value> {
+        let Some(args) = self.args else {
+            return Vec::new();
+        };
+
+        let elements = (0..args.length as usize)
+            .map(|i| {
+                let parsed = argv.get(i).map(|s| parse_c_integer(s)).unwrap_or(0);
+                truncate_to(parsed, args.element)
+            })
+            .collect();
+        vec![Value::Array {
+            ty: args.array_type,
+            elements,
+        }]
+    }
+}
+
+/// Maps an entry function's result to a process exit status.
+pub fn exit_status(result: &Value) -> i32 {
+    match result {
+        Value::I8(v) => *v as i32,
+        Value::I16(v) => *v as i32,
+        Value::I32(v) => *v,
+        Value::I64(v) => *v as i32,
+        _ => 0,
+    }
+}
+
+/// Parses a base-10 integer prefix with C `strtoll` semantics.
+///
+/// Leading whitespace and an optional sign are accepted, parsing stops at the
+/// first non-digit, out-of-range values saturate to `i64::MIN`/`i64::MAX`,
+/// and input with no digits yields 0.
+pub fn parse_c_integer(s: &str) -> i64 {
+    let trimmed = s.trim_start_matches([' ', '\t', '\n', '\r', '\x0b', '\x0c']);
+    let (negative, digits) = match trimmed.as_bytes().first() {
+        Some(b'-') => (true, &trimmed[1..]),
+        Some(b'+') => (false, &trimmed[1..]),
+        _ => (false, trimmed),
+    };
+
+    let mut acc: i64 = 0;
+    for byte in digits.bytes() {
+        if !byte.is_ascii_digit() {
+            break;
+        }
+        let digit = (byte - b'0') as i64;
+        acc = match acc.checked_mul(10).and_then(|v| {
+            if negative {
+                v.checked_sub(digit)
+            } else {
+                v.checked_add(digit)
+            }
+        }) {
+            Some(v) => v,
+            None => return if negative { i64::MIN } else { i64::MAX },
+        };
+    }
+    acc
+}
+
+fn is_integer_type(type_id: TypeId) -> bool {
+    matches!(
+        type_id,
+        TypeId::I8 | TypeId::I16 | TypeId::I32 | TypeId::I64
+    )
+}
+
+fn truncate_to(value: i64, element: TypeId) -> Value {
+    match element {
+        TypeId::I8 => Value::I8(value as i8),
+        TypeId::I16 => Value::I16(value as i16),
+        TypeId::I32 => Value::I32(value as i32),
+        _ => Value::I64(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph_with_entry(params: Vec<(String, TypeId)>) -> (ProgramGraph, FunctionId) {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function("main".into(), root, params, TypeId::I32, Visibility::Public)
+            .unwrap();
+        (graph, func_id)
+    }
+
+    #[test]
+    fn parse_c_integer_follows_strtoll() {
+        assert_eq!(parse_c_integer("42"), 42);
+        assert_eq!(parse_c_integer("  -17"), -17);
+        assert_eq!(parse_c_integer("+8"), 8);
+        assert_eq!(parse_c_integer("12abc"), 12);
+        assert_eq!(parse_c_integer("abc"), 0);
+        assert_eq!(parse_c_integer(""), 0);
+        assert_eq!(parse_c_integer("99999999999999999999"), i64::MAX);
+        assert_eq!(parse_c_integer("-99999999999999999999"), i64::MIN);
+    }
+
+    #[test]
+    fn zero_parameter_entry_takes_no_arguments() {
+        let (graph, func_id) = graph_with_entry(vec![]);
+        let entry = resolve_entry(&graph, graph.get_function(func_id).unwrap()).unwrap();
+        assert!(entry.args.is_none());
+        assert!(entry.arguments(&["1".into()]).is_empty());
+    }
+
+    #[test]
+    fn argument_array_pads_and_truncates() {
+        let mut graph = ProgramGraph::new("test");
+        let array = graph.types.register(LmType::Array {
+            element: TypeId::I8,
+            length: 3,
+        });
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function(
+                "main".into(),
+                root,
+                vec![("args".into(), array)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+
+        let entry = resolve_entry(&graph, graph.get_function(func_id).unwrap()).unwrap();
+        let values = entry.arguments(&["300".into(), "-2".into()]);
+        assert_eq!(
+            values,
+            vec![Value::Array {
+                ty: array,
+                elements: vec![Value::I8(300i64 as i8), Value::I8(-2), Value::I8(0)]
+            }]
+        );
+    }
+
+    #[test]
+    fn non_array_parameter_is_rejected() {
+        let (graph, func_id) = graph_with_entry(vec![("x".into(), TypeId::I32)]);
+        let err = resolve_entry(&graph, graph.get_function(func_id).unwrap()).unwrap_err();
+        assert!(matches!(err, EntryError::InvalidArgumentParameter { .. }));
+    }
+
+    #[test]
+    fn exit_status_truncates_integers() {
+        assert_eq!(exit_status(&Value::I32(7)), 7);
+        assert_eq!(exit_status(&Value::I8(-1)), -1);
+        assert_eq!(exit_status(&Value::I64(1 << 32 | 3)), 3);
+        assert_eq!(exit_status(&Value::Unit), 0);
+        assert_eq!(exit_status(&Value::Bool(true)), 0);
+    }
+}
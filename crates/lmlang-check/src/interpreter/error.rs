@@ -29,6 +29,15 @@ pub enum RuntimeError {
     #[error("recursion depth limit ({limit}) exceeded at node {node}")]
     RecursionLimitExceeded { node: NodeId, limit: usize },
 
+    #[error("step limit ({limit}) exceeded at node {node}")]
+    StepLimitExceeded { node: NodeId, limit: u64 },
+
+    #[error("time limit ({limit_ms} ms) exceeded at node {node}")]
+    TimeoutExceeded { node: NodeId, limit_ms: u64 },
+
+    #[error("memory limit ({limit} cells) exceeded at node {node}")]
+    MemoryLimitExceeded { node: NodeId, limit: usize },
+
     #[error("type mismatch at runtime: node {node}, expected {expected}, got {got}")]
     TypeMismatchAtRuntime {
         node: NodeId,
@@ -48,3 +57,35 @@ pub enum RuntimeError {
     #[error("internal error: {message}")]
     InternalError { message: String },
 }
+
+impl RuntimeError {
+    /// Stable snake_case name of the trap, as used in test expectations.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RuntimeError::IntegerOverflow { .. } => "integer_overflow",
+            RuntimeError::DivideByZero { .. } => "divide_by_zero",
+            RuntimeError::OutOfBoundsAccess { .. } => "out_of_bounds",
+            RuntimeError::RecursionLimitExceeded { .. } => "recursion_limit_exceeded",
+            RuntimeError::StepLimitExceeded { .. } => "step_limit_exceeded",
+            RuntimeError::TimeoutExceeded { .. } => "timeout_exceeded",
+            RuntimeError::MemoryLimitExceeded { .. } => "memory_limit_exceeded",
+            RuntimeError::TypeMismatchAtRuntime { .. } => "type_mismatch",
+            RuntimeError::MissingValue { .. } => "missing_value",
+            RuntimeError::FunctionNotFound { .. } => "function_not_found",
+            RuntimeError::NoReturnNode { .. } => "no_return_node",
+            RuntimeError::InternalError { .. } => "internal_error",
+        }
+    }
+
+    /// Whether the run was stopped by an execution budget rather than by
+    /// the program itself.
+    pub fn is_limit_exceeded(&self) -> bool {
+        matches!(
+            self,
+            RuntimeError::RecursionLimitExceeded { .. }
+                | RuntimeError::StepLimitExceeded { .. }
+                | RuntimeError::TimeoutExceeded { .. }
+                | RuntimeError::MemoryLimitExceeded { .. }
+        )
+    }
+}
@@ -89,6 +89,8 @@ fn eval_core_op(
         // by the Interpreter in state.rs. If they reach here, it's an internal error.
         ComputeOp::IfElse
         | ComputeOp::Loop
+        | ComputeOp::ForRange { .. }
+        | ComputeOp::ForEach { .. }
         | ComputeOp::Match
         | ComputeOp::Branch
         | ComputeOp::Jump
@@ -107,8 +109,13 @@ fn eval_core_op(
         | ComputeOp::FileRead
         | ComputeOp::FileWrite
         | ComputeOp::FileClose
+        | ComputeOp::Now
+        | ComputeOp::Random
         | ComputeOp::MakeClosure { .. }
-        | ComputeOp::CaptureAccess { .. } => Err(RuntimeError::InternalError {
+        | ComputeOp::CaptureAccess { .. }
+        | ComputeOp::Old { .. }
+        | ComputeOp::ForAll { .. }
+        | ComputeOp::Exists { .. } => Err(RuntimeError::InternalError {
             message: format!("op {:?} should be handled by Interpreter, not eval_op", op),
         }),
 
@@ -127,17 +134,20 @@ fn eval_structured_op(
     node_id: NodeId,
 ) -> Result<Option<Value>, RuntimeError> {
     match op {
-        StructuredOp::StructCreate { .. } => {
+        StructuredOp::StructCreate { type_id } => {
             let mut sorted: Vec<(u16, Value)> = inputs.to_vec();
             sorted.sort_by_key(|(p, _)| *p);
             let fields: Vec<Value> = sorted.into_iter().map(|(_, v)| v).collect();
-            Ok(Some(Value::Struct(fields)))
+            Ok(Some(Value::Struct {
+                ty: *type_id,
+                fields,
+            }))
         }
 
         StructuredOp::StructGet { field_index } => {
             let s = get_input(inputs, 0, node_id)?;
             match s {
-                Value::Struct(fields) => {
+                Value::Struct { fields, .. } => {
                     let idx = *field_index as usize;
                     if idx >= fields.len() {
                         Err(RuntimeError::OutOfBoundsAccess {
@@ -161,7 +171,7 @@ fn eval_structured_op(
             let s = get_input(inputs, 0, node_id)?;
             let new_val = get_input(inputs, 1, node_id)?;
             match s {
-                Value::Struct(fields) => {
+                Value::Struct { ty, fields } => {
                     let idx = *field_index as usize;
                     if idx >= fields.len() {
                         Err(RuntimeError::OutOfBoundsAccess {
@@ -172,7 +182,10 @@ fn eval_structured_op(
                     } else {
                         let mut new_fields = fields.clone();
                         new_fields[idx] = new_val.clone();
-                        Ok(Some(Value::Struct(new_fields)))
+                        Ok(Some(Value::Struct {
+                            ty: *ty,
+                            fields: new_fields,
+                        }))
                     }
                 }
                 _ => Err(RuntimeError::TypeMismatchAtRuntime {
@@ -197,14 +210,16 @@ fn eval_structured_op(
                     ),
                 });
             }
-            Ok(Some(Value::Array(elements)))
+            // The array type is not on the op; the interpreter tags it from
+            // the node's outgoing edges.
+            Ok(Some(Value::untyped_array(elements)))
         }
 
         StructuredOp::ArrayGet => {
             let arr = get_input(inputs, 0, node_id)?;
             let idx_val = get_input(inputs, 1, node_id)?;
             match arr {
-                Value::Array(elements) => {
+                Value::Array { elements, .. } => {
                     let idx = value_to_usize(idx_val, node_id)?;
                     if idx >= elements.len() {
                         Err(RuntimeError::OutOfBoundsAccess {
@@ -229,7 +244,7 @@ fn eval_structured_op(
             let idx_val = get_input(inputs, 1, node_id)?;
             let new_val = get_input(inputs, 2, node_id)?;
             match arr {
-                Value::Array(elements) => {
+                Value::Array { ty, elements } => {
                     let idx = value_to_usize(idx_val, node_id)?;
                     if idx >= elements.len() {
                         Err(RuntimeError::OutOfBoundsAccess {
@@ -240,7 +255,10 @@ fn eval_structured_op(
                     } else {
                         let mut new_arr = elements.clone();
                         new_arr[idx] = new_val.clone();
-                        Ok(Some(Value::Array(new_arr)))
+                        Ok(Some(Value::Array {
+                            ty: *ty,
+                            elements: new_arr,
+                        }))
                     }
                 }
                 _ => Err(RuntimeError::TypeMismatchAtRuntime {
@@ -256,13 +274,17 @@ fn eval_structured_op(
             Ok(Some(eval_cast(val, *target_type, node_id)?))
         }
 
-        StructuredOp::EnumCreate { variant_index, .. } => {
+        StructuredOp::EnumCreate {
+            type_id,
+            variant_index,
+        } => {
             let payload = if inputs.is_empty() {
                 Value::Unit
             } else {
                 get_input(inputs, 0, node_id)?.clone()
             };
             Ok(Some(Value::Enum {
+                ty: *type_id,
                 variant: *variant_index,
                 payload: Box::new(payload),
             }))
@@ -291,6 +313,13 @@ fn eval_structured_op(
                 }),
             }
         }
+
+        // Combinators call back into the graph, so the Interpreter runs them
+        StructuredOp::ArrayMap | StructuredOp::ArrayFold | StructuredOp::ArrayFilter => {
+            Err(RuntimeError::InternalError {
+                message: format!("op {:?} should be handled by Interpreter, not eval_op", op),
+            })
+        }
     }
 }
 
@@ -0,0 +1,231 @@
+//! Execution history for reverse (time-travel) stepping.
+//!
+//! When [`InterpreterConfig::checkpoint_interval`] is set, the interpreter
+//! records a [`StepRecord`] per step and periodically snapshots its call
+//! stack and memory. [`Interpreter::rewind_to`] restores the nearest earlier
+//! snapshot and replays forward, which is exact because execution is
+//! deterministic (seeded `Random`, virtual clock).
+//!
+//! [`InterpreterConfig::checkpoint_interval`]: super::InterpreterConfig::checkpoint_interval
+//! [`Interpreter::rewind_to`]: super::Interpreter::rewind_to
+
+use lmlang_core::id::NodeId;
+use serde::{Deserialize, Serialize};
+
+/// What one interpreter step did.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepRecord {
+    /// Step number (0 = the first step after `start`).
+    pub step: u64,
+    /// The node evaluated, or `None` for a step that failed to find one.
+    pub node_id: Option<NodeId>,
+    /// Call stack depth before the step.
+    pub depth: usize,
+    /// Memory cell allocated or stored to.
+    pub memory_write: Option<usize>,
+    /// Caller node (e.g. a `Call`) that received a return value.
+    pub returned_to: Option<NodeId>,
+}
+
+/// A location whose last write [`Interpreter::rewind_to_last_write`]
+/// searches for.
+///
+/// [`Interpreter::rewind_to_last_write`]: super::Interpreter::rewind_to_last_write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WriteTarget {
+    /// A node's value: written when the node is evaluated, or for calls and
+    /// structured loops when the callee returns into it.
+    Node { node_id: NodeId },
+    /// A memory cell, written by `Alloc` and `Store`.
+    Memory { address: usize },
+}
+
+impl WriteTarget {
+    /// Whether the step described by `record` wrote this target.
+    pub fn written_by(&self, record: &StepRecord) -> bool {
+        match *self {
+            WriteTarget::Node { node_id } => {
+                record.node_id == Some(node_id) || record.returned_to == Some(node_id)
+            }
+            WriteTarget::Memory { address } => record.memory_write == Some(address),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::{ExecutionState, Interpreter, InterpreterConfig, Value};
+    use lmlang_core::graph::ProgramGraph;
+    use lmlang_core::id::FunctionId;
+    use lmlang_core::ops::{ArithOp, ComputeOp};
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::{ConstValue, Visibility};
+
+    fn function(graph: &mut ProgramGraph, name: &str) -> FunctionId {
+        let root = graph.modules.root_id();
+        graph
+            .add_function(
+                name.into(),
+                root,
+                vec![("x".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap()
+    }
+
+    /// Helper: `main(x) = inc(x)` with `inc(x) = x + 1`. Returns the graph,
+    /// `main` and its `Call` node.
+    fn build_calls() -> (ProgramGraph, FunctionId, NodeId) {
+        let mut graph = ProgramGraph::new("test");
+        let inc = function(&mut graph, "inc");
+        let param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, inc)
+            .unwrap();
+        let one = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: ConstValue::I32(1),
+                },
+                inc,
+            )
+            .unwrap();
+        let add = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Add }, inc)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, inc).unwrap();
+        graph.add_data_edge(param, add, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(one, add, 0, 1, TypeId::I32).unwrap();
+        graph.add_data_edge(add, ret, 0, 0, TypeId::I32).unwrap();
+
+        let main = function(&mut graph, "main");
+        let param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, main)
+            .unwrap();
+        let call = graph
+            .add_core_op(ComputeOp::Call { target: inc }, main)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, main).unwrap();
+        graph.add_data_edge(param, call, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(call, ret, 0, 0, TypeId::I32).unwrap();
+        (graph, main, call)
+    }
+
+    fn config(interval: u64) -> InterpreterConfig {
+        InterpreterConfig {
+            trace_enabled: true,
+            checkpoint_interval: Some(interval),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn step_back_retraces_every_forward_step() {
+        let (graph, main, _) = build_calls();
+        let mut interp = Interpreter::new(&graph, config(3));
+        interp.start(main, vec![Value::I32(1)]);
+
+        let mut forward = Vec::new();
+        while matches!(interp.state(), ExecutionState::Running) {
+            forward.push((interp.next_node(), interp.call_depth()));
+            interp.step();
+        }
+        assert!(matches!(
+            interp.state(),
+            ExecutionState::Completed {
+                result: Value::I32(2)
+            }
+        ));
+        let total = interp.steps();
+        assert_eq!(total as usize, forward.len());
+
+        while let Some(expected) = forward.pop() {
+            assert!(interp.step_back());
+            assert_eq!((interp.next_node(), interp.call_depth()), expected);
+            assert_eq!(interp.trace().unwrap().len(), forward.len());
+        }
+        assert_eq!(interp.steps(), 0);
+        assert!(!interp.step_back());
+
+        interp.run();
+        assert!(matches!(
+            interp.state(),
+            ExecutionState::Completed {
+                result: Value::I32(2)
+            }
+        ));
+        assert_eq!(interp.steps(), total);
+    }
+
+    #[test]
+    fn rewinds_to_the_return_into_a_call() {
+        let (graph, main, call) = build_calls();
+        let mut interp = Interpreter::new(&graph, config(4));
+        interp.start(main, vec![Value::I32(1)]);
+        interp.run();
+
+        let record = interp
+            .rewind_to_last_write(&WriteTarget::Node { node_id: call })
+            .expect("call received a value");
+        assert_eq!(record.returned_to, Some(call));
+        assert_eq!(interp.steps(), record.step);
+        // Stopped before the callee's Return, still inside `inc`.
+        assert_eq!(interp.call_depth(), 2);
+        assert_eq!(interp.next_node(), record.node_id);
+    }
+
+    #[test]
+    fn rewinds_to_the_last_store_to_a_cell() {
+        // cell(x) { p = alloc; store(p, x); return load(p) }
+        let mut graph = ProgramGraph::new("test");
+        let func = function(&mut graph, "cell");
+        let param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func)
+            .unwrap();
+        let alloc = graph.add_core_op(ComputeOp::Alloc, func).unwrap();
+        let store = graph.add_core_op(ComputeOp::Store, func).unwrap();
+        let load = graph.add_core_op(ComputeOp::Load, func).unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, func).unwrap();
+        graph
+            .add_data_edge(alloc, store, 0, 0, TypeId::I32)
+            .unwrap();
+        graph
+            .add_data_edge(param, store, 0, 1, TypeId::I32)
+            .unwrap();
+        graph.add_data_edge(alloc, load, 0, 0, TypeId::I32).unwrap();
+        graph.add_control_edge(store, load, None).unwrap();
+        graph.add_data_edge(load, ret, 0, 0, TypeId::I32).unwrap();
+
+        let mut interp = Interpreter::new(&graph, config(8));
+        interp.start(func, vec![Value::I32(9)]);
+        interp.run();
+        assert_eq!(interp.memory(), &[Value::I32(9)]);
+
+        let record = interp
+            .rewind_to_last_write(&WriteTarget::Memory { address: 0 })
+            .unwrap();
+        assert_eq!(record.node_id, Some(store));
+        assert_eq!(interp.next_node(), Some(store));
+        assert_eq!(interp.memory(), &[Value::Unit]);
+
+        interp.rewind_to_last_write(&WriteTarget::Memory { address: 0 });
+        assert_eq!(interp.next_node(), Some(alloc));
+        assert!(interp.memory().is_empty());
+    }
+
+    #[test]
+    fn reverse_execution_requires_history() {
+        let (graph, main, call) = build_calls();
+        let mut interp = Interpreter::new(&graph, InterpreterConfig::default());
+        interp.start(main, vec![Value::I32(1)]);
+        interp.run();
+        assert!(!interp.step_back());
+        assert!(interp.history().is_empty());
+        assert_eq!(
+            interp.rewind_to_last_write(&WriteTarget::Node { node_id: call }),
+            None
+        );
+    }
+}
@@ -0,0 +1,581 @@
+//! Typed JSON marshalling of runtime values.
+//!
+//! [`Value::from_typed_json`] builds a value of a given [`TypeId`] from JSON,
+//! validating it against the type's definition in the [`TypeRegistry`].
+//! [`Value::to_typed_json`] renders values back in the same schema:
+//! - Scalars are JSON numbers and booleans, and unit is `null`.
+//! - Arrays are JSON arrays of exactly the array's length.
+//! - Structs are objects with every field keyed by name (`{"x": 1, "y": 2}`).
+//! - Enums are single-key objects naming the variant (`{"Some": 3}`), with a
+//!   `null` payload for unit variants (`{"None": null}`).
+//!
+//! Marshalling is strict: integers must fit their width, integer types reject
+//! fractional numbers, and missing or unknown fields are errors. Each
+//! [`MarshalError`] names the path to the offending value, e.g.
+//! `$.points[2].x`.
+
+use lmlang_core::type_id::{TypeId, TypeRegistry};
+use lmlang_core::types::{LmType, ScalarType};
+
+use super::value::Value;
+
+/// Why JSON does not describe a value of the expected type.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MarshalError {
+    #[error("{path}: expected {expected}, found {found}")]
+    TypeMismatch {
+        path: String,
+        expected: String,
+        found: String,
+    },
+    #[error("{path}: {value} is out of range for {ty} ({min} to {max})")]
+    OutOfRange {
+        path: String,
+        value: String,
+        ty: String,
+        min: String,
+        max: String,
+    },
+    #[error("{path}: expected {expected} elements, found {found}")]
+    LengthMismatch {
+        path: String,
+        expected: u32,
+        found: usize,
+    },
+    #[error("{path}: missing field `{field}` of struct {ty}")]
+    MissingField {
+        path: String,
+        field: String,
+        ty: String,
+    },
+    #[error("{path}: unknown field `{field}` of struct {ty}")]
+    UnknownField {
+        path: String,
+        field: String,
+        ty: String,
+    },
+    #[error("{path}: unknown variant `{variant}` of enum {ty}")]
+    UnknownVariant {
+        path: String,
+        variant: String,
+        ty: String,
+    },
+    #[error("{path}: {ty} values cannot be built from JSON")]
+    Unsupported { path: String, ty: String },
+    #[error("{path}: type {type_id} is not defined")]
+    UnknownType { path: String, type_id: u32 },
+}
+
+impl Value {
+    /// Builds a value of type `ty` from JSON in the typed schema.
+    pub fn from_typed_json(
+        json: &serde_json::Value,
+        ty: TypeId,
+        registry: &TypeRegistry,
+    ) -> Result<Value, MarshalError> {
+        from_json(json, ty, registry, "$")
+    }
+
+    /// Renders the value as JSON named by its type in `registry`.
+    ///
+    /// Scalars become JSON numbers and booleans, unit `null`, arrays JSON
+    /// arrays, structs objects keyed by field name and enums single-key
+    /// objects keyed by variant name (`{"Some": 3}`, `{"None": null}`).
+    /// Compounds whose type is not in `registry` fall back to positional
+    /// forms: structs render as arrays and enums as
+    /// `{"variant": index, "payload": ...}`.
+    pub fn to_typed_json(&self, registry: &TypeRegistry) -> serde_json::Value {
+        use serde_json::json;
+
+        match self {
+            Value::Bool(b) => json!(b),
+            Value::I8(v) => json!(v),
+            Value::I16(v) => json!(v),
+            Value::I32(v) => json!(v),
+            Value::I64(v) => json!(v),
+            Value::F32(v) => json!(v),
+            Value::F64(v) => json!(v),
+            Value::Unit => serde_json::Value::Null,
+            Value::Array { elements, .. } => serde_json::Value::Array(
+                elements.iter().map(|e| e.to_typed_json(registry)).collect(),
+            ),
+            Value::Struct { ty, fields } => match registry.get(*ty) {
+                Some(LmType::Struct(def)) if def.fields.len() == fields.len() => {
+                    serde_json::Value::Object(
+                        def.fields
+                            .keys()
+                            .zip(fields)
+                            .map(|(name, field)| (name.clone(), field.to_typed_json(registry)))
+                            .collect(),
+                    )
+                }
+                _ => serde_json::Value::Array(
+                    fields.iter().map(|f| f.to_typed_json(registry)).collect(),
+                ),
+            },
+            Value::Enum {
+                ty,
+                variant,
+                payload,
+            } => {
+                let name = match registry.get(*ty) {
+                    Some(LmType::Enum(def)) => def
+                        .variants
+                        .iter()
+                        .find(|(_, v)| v.index == *variant)
+                        .map(|(name, _)| name.clone()),
+                    _ => None,
+                };
+                match name {
+                    Some(name) => json!({ name: payload.to_typed_json(registry) }),
+                    None => json!({
+                        "variant": variant,
+                        "payload": payload.to_typed_json(registry),
+                    }),
+                }
+            }
+            Value::Pointer { address, .. } => json!({ "pointer": address }),
+            Value::FunctionRef(function) => json!({ "function": function.0 }),
+            Value::Closure {
+                function, captures, ..
+            } => json!({
+                "closure": function.0,
+                "captures": captures
+                    .iter()
+                    .map(|c| c.to_typed_json(registry))
+                    .collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+fn from_json(
+    json: &serde_json::Value,
+    ty: TypeId,
+    registry: &TypeRegistry,
+    path: &str,
+) -> Result<Value, MarshalError> {
+    let Some(lm_type) = registry.get(ty) else {
+        return Err(MarshalError::UnknownType {
+            path: path.to_string(),
+            type_id: ty.0,
+        });
+    };
+    match lm_type {
+        LmType::Scalar(scalar) => scalar_from_json(json, *scalar, path),
+        LmType::Unit => match json {
+            serde_json::Value::Null => Ok(Value::Unit),
+            other => Err(mismatch(path, "null", other)),
+        },
+        LmType::Array { element, length } => {
+            let Some(items) = json.as_array() else {
+                return Err(mismatch(path, &format!("array of {length}"), json));
+            };
+            if items.len() != *length as usize {
+                return Err(MarshalError::LengthMismatch {
+                    path: path.to_string(),
+                    expected: *length,
+                    found: items.len(),
+                });
+            }
+            let elements = items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| from_json(item, *element, registry, &format!("{path}[{i}]")))
+                .collect::<Result<_, _>>()?;
+            Ok(Value::Array { ty, elements })
+        }
+        LmType::Struct(def) => {
+            let Some(object) = json.as_object() else {
+                return Err(mismatch(
+                    path,
+                    &format!("object for struct {}", def.name),
+                    json,
+                ));
+            };
+            if let Some(field) = object.keys().find(|k| !def.fields.contains_key(*k)) {
+                return Err(MarshalError::UnknownField {
+                    path: path.to_string(),
+                    field: field.clone(),
+                    ty: def.name.clone(),
+                });
+            }
+            let fields = def
+                .fields
+                .iter()
+                .map(|(name, field_ty)| match object.get(name) {
+                    Some(field) => from_json(field, *field_ty, registry, &format!("{path}.{name}")),
+                    None => Err(MarshalError::MissingField {
+                        path: path.to_string(),
+                        field: name.clone(),
+                        ty: def.name.clone(),
+                    }),
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Value::Struct { ty, fields })
+        }
+        LmType::Enum(def) => {
+            let tagged = json
+                .as_object()
+                .filter(|object| object.len() == 1)
+                .and_then(|object| object.iter().next());
+            let Some((name, payload)) = tagged else {
+                return Err(mismatch(
+                    path,
+                    &format!("single-key object naming a variant of enum {}", def.name),
+                    json,
+                ));
+            };
+            let Some(variant) = def.variants.get(name) else {
+                return Err(MarshalError::UnknownVariant {
+                    path: path.to_string(),
+                    variant: name.clone(),
+                    ty: def.name.clone(),
+                });
+            };
+            let payload_path = format!("{path}.{name}");
+            let payload = match (variant.payload, payload) {
+                (Some(payload_ty), payload) => {
+                    from_json(payload, payload_ty, registry, &payload_path)?
+                }
+                (None, serde_json::Value::Null) => Value::Unit,
+                (None, other) => return Err(mismatch(&payload_path, "null", other)),
+            };
+            Ok(Value::Enum {
+                ty,
+                variant: variant.index,
+                payload: Box::new(payload),
+            })
+        }
+        LmType::Pointer { .. } => Err(unsupported(path, "pointer")),
+        LmType::Function { .. } => Err(unsupported(path, "function")),
+        LmType::Never => Err(unsupported(path, "never")),
+    }
+}
+
+fn scalar_from_json(
+    json: &serde_json::Value,
+    scalar: ScalarType,
+    path: &str,
+) -> Result<Value, MarshalError> {
+    match scalar {
+        ScalarType::Bool => json
+            .as_bool()
+            .map(Value::Bool)
+            .ok_or_else(|| mismatch(path, "bool", json)),
+        ScalarType::I8 => int_from_json(json, path, "i8", i8::MIN.into(), i8::MAX.into())
+            .map(|v| Value::I8(v as i8)),
+        ScalarType::I16 => int_from_json(json, path, "i16", i16::MIN.into(), i16::MAX.into())
+            .map(|v| Value::I16(v as i16)),
+        ScalarType::I32 => int_from_json(json, path, "i32", i32::MIN.into(), i32::MAX.into())
+            .map(|v| Value::I32(v as i32)),
+        ScalarType::I64 => int_from_json(json, path, "i64", i64::MIN, i64::MAX).map(Value::I64),
+        ScalarType::F32 => {
+            let v = json.as_f64().ok_or_else(|| mismatch(path, "f32", json))?;
+            if v.abs() > f32::MAX as f64 {
+                return Err(MarshalError::OutOfRange {
+                    path: path.to_string(),
+                    value: v.to_string(),
+                    ty: "f32".into(),
+                    min: f32::MIN.to_string(),
+                    max: f32::MAX.to_string(),
+                });
+            }
+            Ok(Value::F32(v as f32))
+        }
+        ScalarType::F64 => json
+            .as_f64()
+            .map(Value::F64)
+            .ok_or_else(|| mismatch(path, "f64", json)),
+    }
+}
+
+/// An integer of type `ty` within `min..=max`.
+fn int_from_json(
+    json: &serde_json::Value,
+    path: &str,
+    ty: &str,
+    min: i64,
+    max: i64,
+) -> Result<i64, MarshalError> {
+    let serde_json::Value::Number(n) = json else {
+        return Err(mismatch(path, ty, json));
+    };
+    match n.as_i64() {
+        Some(v) if (min..=max).contains(&v) => return Ok(v),
+        // Fractional numbers are the wrong kind, not merely out of range
+        None if n.as_u64().is_none() => return Err(mismatch(path, ty, json)),
+        _ => {}
+    }
+    Err(MarshalError::OutOfRange {
+        path: path.to_string(),
+        value: n.to_string(),
+        ty: ty.to_string(),
+        min: min.to_string(),
+        max: max.to_string(),
+    })
+}
+
+fn mismatch(path: &str, expected: &str, found: &serde_json::Value) -> MarshalError {
+    let found = match found {
+        serde_json::Value::Null => "null".to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => format!("string {s:?}"),
+        serde_json::Value::Array(items) => format!("array of {}", items.len()),
+        serde_json::Value::Object(_) => "object".to_string(),
+    };
+    MarshalError::TypeMismatch {
+        path: path.to_string(),
+        expected: expected.to_string(),
+        found,
+    }
+}
+
+fn unsupported(path: &str, ty: &str) -> MarshalError {
+    MarshalError::Unsupported {
+        path: path.to_string(),
+        ty: ty.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+    use lmlang_core::id::ModuleId;
+    use lmlang_core::types::{EnumDef, EnumVariant, StructDef, Visibility};
+    use serde_json::json;
+
+    /// Helper: registers `Point { x: i8, y: i8 }`, `[Point; 2]` and
+    /// `Shape { Empty, Line([Point; 2]) }`, returning their IDs.
+    fn shapes() -> (TypeRegistry, TypeId, TypeId, TypeId) {
+        let mut registry = TypeRegistry::new();
+        let point = registry
+            .register_named(
+                "Point",
+                LmType::Struct(StructDef {
+                    name: "Point".into(),
+                    type_id: TypeId(0),
+                    fields: IndexMap::from([("x".into(), TypeId::I8), ("y".into(), TypeId::I8)]),
+                    module: ModuleId(0),
+                    visibility: Visibility::Public,
+                }),
+            )
+            .unwrap();
+        let segment = registry.register(LmType::Array {
+            element: point,
+            length: 2,
+        });
+        let shape = registry
+            .register_named(
+                "Shape",
+                LmType::Enum(EnumDef {
+                    name: "Shape".into(),
+                    type_id: TypeId(0),
+                    variants: IndexMap::from([
+                        (
+                            "Empty".into(),
+                            EnumVariant {
+                                index: 0,
+                                payload: None,
+                            },
+                        ),
+                        (
+                            "Line".into(),
+                            EnumVariant {
+                                index: 1,
+                                payload: Some(segment),
+                            },
+                        ),
+                    ]),
+                    module: ModuleId(0),
+                    visibility: Visibility::Public,
+                }),
+            )
+            .unwrap();
+        (registry, point, segment, shape)
+    }
+
+    #[test]
+    fn compound_values_round_trip_through_typed_json() {
+        let (registry, point, segment, shape) = shapes();
+        let line = json!({ "Line": [{ "x": 1, "y": -2 }, { "y": 4, "x": 3 }] });
+
+        let value = Value::from_typed_json(&line, shape, &registry).unwrap();
+        let expected = Value::Enum {
+            ty: shape,
+            variant: 1,
+            payload: Box::new(Value::Array {
+                ty: segment,
+                elements: vec![
+                    Value::Struct {
+                        ty: point,
+                        fields: vec![Value::I8(1), Value::I8(-2)],
+                    },
+                    Value::Struct {
+                        ty: point,
+                        fields: vec![Value::I8(3), Value::I8(4)],
+                    },
+                ],
+            }),
+        };
+        assert_eq!(value, expected);
+        assert_eq!(
+            value.to_typed_json(&registry),
+            json!({ "Line": [{ "x": 1, "y": -2 }, { "x": 3, "y": 4 }] })
+        );
+
+        let empty = json!({ "Empty": null });
+        let value = Value::from_typed_json(&empty, shape, &registry).unwrap();
+        assert_eq!(value.to_typed_json(&registry), empty);
+    }
+
+    #[test]
+    fn nested_untyped_values_are_tagged_from_the_registry() {
+        let (mut registry, point, segment, shape) = shapes();
+        let path = registry
+            .register_named(
+                "Path",
+                LmType::Struct(StructDef {
+                    name: "Path".into(),
+                    type_id: TypeId(0),
+                    fields: IndexMap::from([
+                        ("len".into(), TypeId::I32),
+                        ("points".into(), segment),
+                        ("shape".into(), shape),
+                    ]),
+                    module: ModuleId(0),
+                    visibility: Visibility::Public,
+                }),
+            )
+            .unwrap();
+        let untyped_point = |x, y| Value::Struct {
+            ty: TypeId::UNIT,
+            fields: vec![Value::I8(x), Value::I8(y)],
+        };
+        let value = Value::Struct {
+            ty: TypeId::UNIT,
+            fields: vec![
+                Value::I32(2),
+                Value::untyped_array(vec![untyped_point(1, 2), untyped_point(3, 4)]),
+                Value::Enum {
+                    ty: TypeId::UNIT,
+                    variant: 1,
+                    payload: Box::new(Value::untyped_array(vec![
+                        untyped_point(5, 6),
+                        untyped_point(7, 8),
+                    ])),
+                },
+            ],
+        };
+
+        let value = value.with_type(path, &registry);
+        let Value::Struct { ty, fields } = &value else {
+            panic!("expected a struct");
+        };
+        assert_eq!(*ty, path);
+        let Value::Array { ty, elements } = &fields[1] else {
+            panic!("expected an array");
+        };
+        assert_eq!(*ty, segment);
+        assert_eq!(elements[0].type_id(), point);
+        assert_eq!(
+            value.to_typed_json(&registry),
+            json!({
+                "len": 2,
+                "points": [{ "x": 1, "y": 2 }, { "x": 3, "y": 4 }],
+                "shape": { "Line": [{ "x": 5, "y": 6 }, { "x": 7, "y": 8 }] },
+            })
+        );
+    }
+
+    #[test]
+    fn scalars_are_checked_against_their_width() {
+        let registry = TypeRegistry::new();
+        let parse = |json: serde_json::Value, ty| Value::from_typed_json(&json, ty, &registry);
+
+        assert_eq!(parse(json!(-128), TypeId::I8), Ok(Value::I8(-128)));
+        assert_eq!(
+            parse(json!(i64::MAX), TypeId::I64),
+            Ok(Value::I64(i64::MAX))
+        );
+        assert_eq!(parse(json!(2), TypeId::F64), Ok(Value::F64(2.0)));
+        assert_eq!(
+            parse(json!(128), TypeId::I8).unwrap_err().to_string(),
+            "$: 128 is out of range for i8 (-128 to 127)"
+        );
+        assert_eq!(
+            parse(json!(u64::MAX), TypeId::I64).unwrap_err().to_string(),
+            format!(
+                "$: {} is out of range for i64 ({} to {})",
+                u64::MAX,
+                i64::MIN,
+                i64::MAX
+            )
+        );
+        assert!(matches!(
+            parse(json!(1e39), TypeId::F32),
+            Err(MarshalError::OutOfRange { .. })
+        ));
+        assert_eq!(
+            parse(json!(1.5), TypeId::I32).unwrap_err().to_string(),
+            "$: expected i32, found 1.5"
+        );
+        assert_eq!(
+            parse(json!("7"), TypeId::I32).unwrap_err().to_string(),
+            "$: expected i32, found string \"7\""
+        );
+        assert_eq!(
+            parse(json!(0), TypeId::BOOL).unwrap_err().to_string(),
+            "$: expected bool, found 0"
+        );
+        assert_eq!(
+            parse(json!([]), TypeId::UNIT).unwrap_err().to_string(),
+            "$: expected null, found array of 0"
+        );
+    }
+
+    #[test]
+    fn errors_name_the_offending_path() {
+        let (registry, point, segment, shape) = shapes();
+        let parse = |json: serde_json::Value, ty| {
+            Value::from_typed_json(&json, ty, &registry)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            parse(
+                json!({ "Line": [{ "x": 1, "y": 2 }, { "x": 3, "y": 300 }] }),
+                shape
+            ),
+            "$.Line[1].y: 300 is out of range for i8 (-128 to 127)"
+        );
+        assert_eq!(
+            parse(json!({ "x": 1 }), point),
+            "$: missing field `y` of struct Point"
+        );
+        assert_eq!(
+            parse(json!({ "x": 1, "y": 2, "z": 3 }), point),
+            "$: unknown field `z` of struct Point"
+        );
+        assert_eq!(
+            parse(json!([{ "x": 1, "y": 2 }]), segment),
+            "$: expected 2 elements, found 1"
+        );
+        assert_eq!(
+            parse(json!({ "Circle": 1 }), shape),
+            "$: unknown variant `Circle` of enum Shape"
+        );
+        assert_eq!(
+            parse(json!({ "Empty": 0 }), shape),
+            "$.Empty: expected null, found 0"
+        );
+        assert_eq!(
+            parse(json!("Empty"), shape),
+            "$: expected single-key object naming a variant of enum Shape, found string \"Empty\""
+        );
+        assert_eq!(parse(json!(1), TypeId(999)), "$: type 999 is not defined");
+    }
+}
@@ -14,10 +14,34 @@
 //! - [`ExecutionState`] tracks the interpreter's lifecycle:
 //!   `Ready -> Running -> (Paused | Completed | Error)`.
 //! - [`CallFrame`] represents a function invocation on the call stack.
-//! - [`Value`] is the runtime representation of all values.
+//! - [`Value`] is the runtime representation of all values; [`marshal`]
+//!   converts values to and from JSON validated against their types.
 //! - [`RuntimeError`] captures trap conditions (overflow, div-by-zero, etc.)
 //!   with the node ID that caused the error.
 //! - [`TraceEntry`] records each node evaluation when tracing is enabled.
+//! - [`Coverage`] counts node evaluations and branch arms taken when coverage
+//!   is enabled, and merges across runs.
+//! - [`Profile`] records evaluation counts and wall time per node, calls and
+//!   inclusive/exclusive steps per function, and steps per call stack when
+//!   profiling is enabled; it renders ranked reports and folded stacks.
+//! - [`Debugger`] steps into, over and out of calls, stops at breakpoints and
+//!   evaluates [`WatchExpr`]s; [`DetachedInterpreter`] keeps a run alive
+//!   between debugger commands without borrowing the graph.
+//! - [`InterpreterSnapshot`] serializes a run so it can be suspended and
+//!   resumed later, refusing to resume on a graph whose functions changed.
+//! - [`StepRecord`]s and periodic checkpoints (see
+//!   [`InterpreterConfig::checkpoint_interval`]) let execution step backward
+//!   and rewind to the last write of a node or memory cell; [`lineage`] lists
+//!   the trace entries a value was derived from.
+//! - [`ExecutionLimits`] bound the steps, wall-clock time and memory cells a
+//!   run may use, so non-terminating graphs halt with a runtime error.
+//! - [`VirtualClock`] and the seed in [`InterpreterConfig`] make `Now` and
+//!   `Random` ops deterministic, so repeated runs produce identical results.
+//! - [`BytecodeCache`] lowers functions into register bytecode, cached by
+//!   function hash, which the [`vm`] runs to completion with the same states
+//!   and traces as the [`Interpreter`]; [`Executor`] picks an [`Engine`].
+//! - [`EntryPoint`] describes the program entry convention (argument array
+//!   parameter and exit status) shared with the compiled `main` wrapper.
 //!
 //! # Usage
 //!
@@ -32,16 +56,37 @@
 //! }
 //! ```
 
+pub mod bytecode;
+pub mod coverage;
+pub mod debugger;
+pub mod entry;
 pub mod error;
 pub mod eval;
+pub mod history;
+pub mod marshal;
+pub mod profile;
+pub mod snapshot;
 pub mod state;
 pub mod trace;
 pub mod value;
+pub mod vm;
 
+pub use bytecode::{BytecodeCache, BytecodeProgram, FunctionCode, LowerError};
+pub use coverage::{Coverage, CoverageSummary};
+pub use debugger::{Debugger, StepMode, StopReason, WatchExpr, WatchTarget};
+pub use entry::{EntryError, EntryPoint};
 pub use error::RuntimeError;
-pub use state::{CallFrame, ExecutionState, Interpreter, InterpreterConfig};
-pub use trace::TraceEntry;
+pub use history::{StepRecord, WriteTarget};
+pub use marshal::MarshalError;
+pub use profile::{Profile, ProfileReport};
+pub use snapshot::{InterpreterSnapshot, SnapshotError};
+pub use state::{
+    CallFrame, DetachedInterpreter, ExecutionLimits, ExecutionState, Interpreter,
+    InterpreterConfig, VirtualClock,
+};
+pub use trace::{lineage, TraceEntry};
 pub use value::Value;
+pub use vm::{Engine, Executor, RunOutput};
 
 #[cfg(test)]
 mod tests {
@@ -67,6 +112,7 @@ mod tests {
         args: Vec<Value>,
         config: InterpreterConfig,
     ) -> Result<Value, RuntimeError> {
+        vm::assert_engines_agree(graph, func_id, args.clone(), &config);
         let mut interp = Interpreter::new(graph, config);
         interp.start(func_id, args);
         interp.run();
@@ -714,6 +760,7 @@ mod tests {
         let config = InterpreterConfig {
             trace_enabled: false,
             max_recursion_depth: 10, // Low limit for quick test
+            ..Default::default()
         };
         let mut interp = Interpreter::new(&graph, config);
         interp.start(func_id, vec![Value::I32(1)]);
@@ -811,6 +858,7 @@ mod tests {
         let config = InterpreterConfig {
             trace_enabled: true,
             max_recursion_depth: 256,
+            ..Default::default()
         };
         let mut interp = Interpreter::new(&graph, config);
         interp.start(func_id, vec![Value::I32(3), Value::I32(5)]);
@@ -1097,6 +1145,130 @@ mod tests {
         }
     }
 
+    #[test]
+    fn integration_compound_values_carry_type_ids() {
+        use indexmap::IndexMap;
+        use lmlang_core::id::ModuleId;
+        use lmlang_core::types::{EnumDef, EnumVariant, LmType, StructDef};
+
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+
+        let pair_type = graph.types.register(LmType::Array {
+            element: TypeId::I32,
+            length: 2,
+        });
+        let option_type = graph
+            .types
+            .register_named(
+                "Option",
+                LmType::Enum(EnumDef {
+                    name: "Option".into(),
+                    type_id: TypeId(0),
+                    variants: IndexMap::from([
+                        (
+                            "None".into(),
+                            EnumVariant {
+                                index: 0,
+                                payload: None,
+                            },
+                        ),
+                        (
+                            "Some".into(),
+                            EnumVariant {
+                                index: 1,
+                                payload: Some(TypeId::I32),
+                            },
+                        ),
+                    ]),
+                    module: ModuleId(0),
+                    visibility: Visibility::Public,
+                }),
+            )
+            .unwrap();
+        let labeled_type = graph
+            .types
+            .register_named(
+                "Labeled",
+                LmType::Struct(StructDef {
+                    name: "Labeled".into(),
+                    type_id: TypeId(0),
+                    fields: IndexMap::from([
+                        ("label".into(), option_type),
+                        ("values".into(), pair_type),
+                    ]),
+                    module: ModuleId(0),
+                    visibility: Visibility::Public,
+                }),
+            )
+            .unwrap();
+
+        // labeled(values) = Labeled { label: Some(7), values }
+        let func_id = graph
+            .add_function(
+                "labeled".into(),
+                root,
+                vec![("values".into(), pair_type)],
+                labeled_type,
+                Visibility::Public,
+            )
+            .unwrap();
+        let values = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func_id)
+            .unwrap();
+        let c7 = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: lmlang_core::ConstValue::I32(7),
+                },
+                func_id,
+            )
+            .unwrap();
+        let some = graph
+            .add_structured_op(
+                StructuredOp::EnumCreate {
+                    type_id: option_type,
+                    variant_index: 1,
+                },
+                func_id,
+            )
+            .unwrap();
+        let create = graph
+            .add_structured_op(
+                StructuredOp::StructCreate {
+                    type_id: labeled_type,
+                },
+                func_id,
+            )
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+        graph.add_data_edge(c7, some, 0, 0, TypeId::I32).unwrap();
+        graph
+            .add_data_edge(some, create, 0, 0, option_type)
+            .unwrap();
+        graph
+            .add_data_edge(values, create, 0, 1, pair_type)
+            .unwrap();
+        graph
+            .add_data_edge(create, ret, 0, 0, labeled_type)
+            .unwrap();
+
+        let args = vec![Value::untyped_array(vec![Value::I32(1), Value::I32(2)])];
+        let result = run_function(&graph, func_id, args).unwrap();
+        assert_eq!(result.type_id(), labeled_type);
+        match &result {
+            Value::Struct { fields, .. } => {
+                assert_eq!(fields[0].type_id(), option_type);
+                assert_eq!(fields[1].type_id(), pair_type);
+            }
+            other => panic!("Expected Struct, got {:?}", other),
+        }
+        assert_eq!(
+            result.to_typed_json(&graph.types),
+            serde_json::json!({ "label": { "Some": 7 }, "values": [1, 2] })
+        );
+    }
+
     // -----------------------------------------------------------------------
     // 14. Partial results on error
     // -----------------------------------------------------------------------
@@ -1905,4 +2077,106 @@ mod tests {
             _ => panic!("Expected I32(0), got {:?}", result),
         }
     }
+
+    // -----------------------------------------------------------------------
+    // Execution limits
+    // -----------------------------------------------------------------------
+
+    fn run_with_limits(limits: ExecutionLimits, n: i32) -> (ExecutionState, u64) {
+        let (graph, func_id) = build_loop_sum_graph();
+        let config = InterpreterConfig {
+            limits,
+            ..Default::default()
+        };
+        vm::assert_engines_agree(&graph, func_id, vec![Value::I32(n)], &config);
+        let mut interp = Interpreter::new(&graph, config);
+        interp.start(func_id, vec![Value::I32(n)]);
+        interp.run();
+        (interp.state().clone(), interp.steps())
+    }
+
+    #[test]
+    fn step_limit_halts_long_loops_with_partial_results() {
+        let limits = ExecutionLimits {
+            max_steps: Some(50),
+            ..Default::default()
+        };
+        let (state, steps) = run_with_limits(limits, 1_000);
+        assert_eq!(steps, 50);
+        match state {
+            ExecutionState::Error {
+                error: RuntimeError::StepLimitExceeded { limit, .. },
+                partial_results,
+            } => {
+                assert_eq!(limit, 50);
+                assert!(!partial_results.is_empty());
+            }
+            other => panic!("expected StepLimitExceeded, got {:?}", other),
+        }
+
+        // The same budget is enough for a short run
+        let (state, _) = run_with_limits(limits, 1);
+        assert!(matches!(
+            state,
+            ExecutionState::Completed {
+                result: Value::I32(1)
+            }
+        ));
+    }
+
+    #[test]
+    fn timeout_halts_execution() {
+        let limits = ExecutionLimits {
+            timeout_ms: Some(0),
+            ..Default::default()
+        };
+        let (state, steps) = run_with_limits(limits, 5);
+        assert_eq!(steps, 0);
+        assert!(matches!(
+            state,
+            ExecutionState::Error {
+                error: RuntimeError::TimeoutExceeded { limit_ms: 0, .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn memory_limit_halts_at_alloc() {
+        let limits = ExecutionLimits {
+            max_memory_cells: Some(1),
+            ..Default::default()
+        };
+        let (state, _) = run_with_limits(limits, 5);
+        assert!(matches!(
+            state,
+            ExecutionState::Error {
+                error: RuntimeError::MemoryLimitExceeded { limit: 1, .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn request_limits_fall_back_to_defaults() {
+        let defaults = ExecutionLimits {
+            max_steps: Some(100),
+            timeout_ms: Some(1_000),
+            max_memory_cells: None,
+        };
+        let request = ExecutionLimits {
+            max_steps: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(
+            request.or(defaults),
+            ExecutionLimits {
+                max_steps: Some(5),
+                timeout_ms: Some(1_000),
+                max_memory_cells: None,
+            }
+        );
+        assert!(ExecutionLimits::default().is_unlimited());
+        assert!(!defaults.is_unlimited());
+    }
 }
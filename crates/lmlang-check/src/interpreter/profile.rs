@@ -0,0 +1,444 @@
+//! Cost profiling for the graph interpreter.
+//!
+//! When profiling is enabled via [`InterpreterConfig::profile_enabled`], the
+//! interpreter counts evaluations and wall time per node, and calls plus
+//! inclusive and exclusive steps per function, and counts the steps taken
+//! under each call stack. Profiles from several runs are combined with
+//! [`Profile::merge`]. [`Profile::report`] ranks functions, op kinds and hot
+//! nodes, and [`Profile::folded_stacks`] renders the stacks in the folded
+//! format read by flamegraph tools.
+//!
+//! Steps are deterministic; wall times are not and vary between runs.
+//!
+//! [`InterpreterConfig::profile_enabled`]: super::InterpreterConfig::profile_enabled
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::ops::ComputeNodeOp;
+
+/// Hot nodes listed in a [`ProfileReport`] unless a caller asks otherwise.
+pub const DEFAULT_HOT_NODES: usize = 20;
+
+/// Evaluations and time spent on one node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct NodeCost {
+    evaluations: u64,
+    nanos: u64,
+}
+
+/// Calls and steps attributed to one function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct FunctionCost {
+    calls: u64,
+    inclusive_steps: u64,
+    exclusive_steps: u64,
+}
+
+/// Costs recorded while interpreting, per node, function and call stack.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    nodes: HashMap<NodeId, NodeCost>,
+    functions: HashMap<FunctionId, FunctionCost>,
+    /// Steps taken with each call stack, outermost function first.
+    stacks: HashMap<Vec<FunctionId>, u64>,
+}
+
+impl Profile {
+    /// Counts one invocation of `function`.
+    pub fn record_call(&mut self, function: FunctionId) {
+        self.functions.entry(function).or_default().calls += 1;
+    }
+
+    /// Counts one evaluation of `node`, taking `elapsed`, with `stack` the
+    /// functions on the call stack (outermost first).
+    ///
+    /// The step is exclusive to the innermost function and inclusive to
+    /// every function on the stack, once per function even under recursion.
+    pub fn record_step(&mut self, stack: &[FunctionId], node: NodeId, elapsed: Duration) {
+        let Some(&current) = stack.last() else {
+            return;
+        };
+        let cost = self.nodes.entry(node).or_default();
+        cost.evaluations += 1;
+        cost.nanos += elapsed.as_nanos() as u64;
+
+        self.functions.entry(current).or_default().exclusive_steps += 1;
+        for (i, function) in stack.iter().enumerate() {
+            if !stack[..i].contains(function) {
+                self.functions.entry(*function).or_default().inclusive_steps += 1;
+            }
+        }
+
+        match self.stacks.get_mut(stack) {
+            Some(steps) => *steps += 1,
+            None => {
+                self.stacks.insert(stack.to_vec(), 1);
+            }
+        }
+    }
+
+    /// Number of times `node` was evaluated.
+    pub fn node_evaluations(&self, node: NodeId) -> u64 {
+        self.nodes.get(&node).map_or(0, |cost| cost.evaluations)
+    }
+
+    /// Total steps recorded.
+    pub fn total_steps(&self) -> u64 {
+        self.nodes.values().map(|cost| cost.evaluations).sum()
+    }
+
+    /// Whether nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.functions.is_empty()
+    }
+
+    /// Adds the costs of `other` to these.
+    pub fn merge(&mut self, other: &Profile) {
+        for (node, cost) in &other.nodes {
+            let total = self.nodes.entry(*node).or_default();
+            total.evaluations += cost.evaluations;
+            total.nanos += cost.nanos;
+        }
+        for (function, cost) in &other.functions {
+            let total = self.functions.entry(*function).or_default();
+            total.calls += cost.calls;
+            total.inclusive_steps += cost.inclusive_steps;
+            total.exclusive_steps += cost.exclusive_steps;
+        }
+        for (stack, steps) in &other.stacks {
+            *self.stacks.entry(stack.clone()).or_default() += steps;
+        }
+    }
+
+    /// Steps per call stack in the folded format: one line per stack, with
+    /// function names separated by `;` followed by a space and the step
+    /// count. Lines are sorted, so equal profiles render identically.
+    pub fn folded_stacks(&self, graph: &ProgramGraph) -> String {
+        let mut lines: Vec<String> = self
+            .stacks
+            .iter()
+            .map(|(stack, steps)| {
+                let names: Vec<String> = stack
+                    .iter()
+                    .map(|function| function_name(graph, *function))
+                    .collect();
+                format!("{} {}", names.join(";"), steps)
+            })
+            .collect();
+        lines.sort();
+        lines.iter().map(|line| format!("{line}\n")).collect()
+    }
+
+    /// Ranked costs with at most `max_hot_nodes` hot nodes.
+    ///
+    /// Functions are ranked by inclusive steps, op kinds by time, and nodes
+    /// by evaluations, then time; ties fall back to ascending IDs and names.
+    pub fn report(&self, graph: &ProgramGraph, max_hot_nodes: usize) -> ProfileReport {
+        let mut functions: Vec<FunctionProfile> = self
+            .functions
+            .iter()
+            .map(|(function_id, cost)| FunctionProfile {
+                function_id: *function_id,
+                name: function_name(graph, *function_id),
+                calls: cost.calls,
+                inclusive_steps: cost.inclusive_steps,
+                exclusive_steps: cost.exclusive_steps,
+            })
+            .collect();
+        functions.sort_by(|a, b| {
+            b.inclusive_steps
+                .cmp(&a.inclusive_steps)
+                .then(a.function_id.0.cmp(&b.function_id.0))
+        });
+
+        let mut nodes: Vec<NodeProfile> = self
+            .nodes
+            .iter()
+            .map(|(node_id, cost)| {
+                let node = graph.get_compute_node(*node_id);
+                NodeProfile {
+                    node_id: *node_id,
+                    function_id: node.map(|n| n.owner),
+                    op: node.map_or_else(|| "Unknown".to_string(), |n| op_kind(&n.op)),
+                    evaluations: cost.evaluations,
+                    time_ns: cost.nanos,
+                }
+            })
+            .collect();
+        nodes.sort_by(|a, b| {
+            b.evaluations
+                .cmp(&a.evaluations)
+                .then(b.time_ns.cmp(&a.time_ns))
+                .then(a.node_id.0.cmp(&b.node_id.0))
+        });
+
+        let mut by_kind: HashMap<&str, OpKindProfile> = HashMap::new();
+        for node in &nodes {
+            let kind = by_kind
+                .entry(node.op.as_str())
+                .or_insert_with(|| OpKindProfile {
+                    op: node.op.clone(),
+                    evaluations: 0,
+                    time_ns: 0,
+                });
+            kind.evaluations += node.evaluations;
+            kind.time_ns += node.time_ns;
+        }
+        let mut op_kinds: Vec<OpKindProfile> = by_kind.into_values().collect();
+        op_kinds.sort_by(|a, b| b.time_ns.cmp(&a.time_ns).then(a.op.cmp(&b.op)));
+
+        ProfileReport {
+            total_steps: self.total_steps(),
+            total_time_ns: nodes.iter().map(|n| n.time_ns).sum(),
+            functions,
+            op_kinds,
+            hot_nodes: nodes.into_iter().take(max_hot_nodes).collect(),
+        }
+    }
+}
+
+/// Ranked costs of one or more profiled runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileReport {
+    /// Nodes evaluated.
+    pub total_steps: u64,
+    /// Wall time spent evaluating nodes, in nanoseconds.
+    pub total_time_ns: u64,
+    /// Functions by descending inclusive steps.
+    pub functions: Vec<FunctionProfile>,
+    /// Op kinds by descending time.
+    pub op_kinds: Vec<OpKindProfile>,
+    /// The most evaluated nodes, hottest first.
+    pub hot_nodes: Vec<NodeProfile>,
+}
+
+/// Calls and steps of one function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionProfile {
+    pub function_id: FunctionId,
+    pub name: String,
+    /// Number of invocations, including loop body and closure calls.
+    pub calls: u64,
+    /// Steps taken while the function was on the call stack.
+    pub inclusive_steps: u64,
+    /// Steps taken in the function's own nodes.
+    pub exclusive_steps: u64,
+}
+
+/// Evaluations and time of all nodes of one op kind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpKindProfile {
+    /// Op name without operands, e.g. `BinaryArith`.
+    pub op: String,
+    pub evaluations: u64,
+    pub time_ns: u64,
+}
+
+/// Evaluations and time of one node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeProfile {
+    pub node_id: NodeId,
+    /// Owning function; `None` if the node has since been removed.
+    pub function_id: Option<FunctionId>,
+    pub op: String,
+    pub evaluations: u64,
+    pub time_ns: u64,
+}
+
+/// Name of `function`, or `fn<id>` if it no longer exists.
+fn function_name(graph: &ProgramGraph, function: FunctionId) -> String {
+    graph
+        .get_function(function)
+        .map_or_else(|| format!("fn{}", function.0), |f| f.name.clone())
+}
+
+/// Name of the op variant, without operands.
+fn op_kind(op: &ComputeNodeOp) -> String {
+    let description = match op {
+        ComputeNodeOp::Core(op) => format!("{op:?}"),
+        ComputeNodeOp::Structured(op) => format!("{op:?}"),
+    };
+    description
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::{ExecutionState, Interpreter, InterpreterConfig, Value};
+    use lmlang_core::ops::{ArithOp, ComputeOp};
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::{ConstValue, Visibility};
+
+    /// Helper: `double(x) = x + x` and `quad(x) = double(double(x))`.
+    /// Returns the graph, `quad`, `double` and the `Add` node.
+    fn build_quad() -> (ProgramGraph, FunctionId, FunctionId, NodeId) {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let params = vec![("x".into(), TypeId::I32)];
+        let double = graph
+            .add_function(
+                "double".into(),
+                root,
+                params.clone(),
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let x = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, double)
+            .unwrap();
+        let add = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Add }, double)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, double).unwrap();
+        graph.add_data_edge(x, add, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(x, add, 0, 1, TypeId::I32).unwrap();
+        graph.add_data_edge(add, ret, 0, 0, TypeId::I32).unwrap();
+
+        let quad = graph
+            .add_function("quad".into(), root, params, TypeId::I32, Visibility::Public)
+            .unwrap();
+        let x = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, quad)
+            .unwrap();
+        let first = graph
+            .add_core_op(ComputeOp::Call { target: double }, quad)
+            .unwrap();
+        let second = graph
+            .add_core_op(ComputeOp::Call { target: double }, quad)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, quad).unwrap();
+        graph.add_data_edge(x, first, 0, 0, TypeId::I32).unwrap();
+        graph
+            .add_data_edge(first, second, 0, 0, TypeId::I32)
+            .unwrap();
+        graph.add_data_edge(second, ret, 0, 0, TypeId::I32).unwrap();
+
+        (graph, quad, double, add)
+    }
+
+    fn run(graph: &ProgramGraph, func_id: FunctionId) -> Profile {
+        let config = InterpreterConfig {
+            profile_enabled: true,
+            ..Default::default()
+        };
+        let mut interp = Interpreter::new(graph, config);
+        interp.start(func_id, vec![Value::I32(3)]);
+        interp.run();
+        assert!(matches!(
+            interp.state(),
+            ExecutionState::Completed {
+                result: Value::I32(12)
+            }
+        ));
+        assert_eq!(interp.profile().unwrap().total_steps(), interp.steps());
+        interp.profile().cloned().expect("profiling enabled")
+    }
+
+    #[test]
+    fn steps_split_into_inclusive_and_exclusive() {
+        let (graph, quad, double, add) = build_quad();
+        let profile = run(&graph, quad);
+        let report = profile.report(&graph, DEFAULT_HOT_NODES);
+
+        // quad: Parameter, 2 Calls, Return; double: 3 nodes per call
+        assert_eq!(report.total_steps, 10);
+        let costs: Vec<(&str, u64, u64, u64)> = report
+            .functions
+            .iter()
+            .map(|f| {
+                (
+                    f.name.as_str(),
+                    f.calls,
+                    f.inclusive_steps,
+                    f.exclusive_steps,
+                )
+            })
+            .collect();
+        assert_eq!(costs, vec![("quad", 1, 10, 4), ("double", 2, 6, 6)]);
+        assert_eq!(report.functions[1].function_id, double);
+
+        let hottest = &report.hot_nodes[0];
+        assert_eq!(hottest.evaluations, 2);
+        assert!(report.hot_nodes.iter().any(|n| n.node_id == add));
+        assert_eq!(profile.node_evaluations(add), 2);
+
+        let kinds: Vec<(&str, u64)> = {
+            let mut kinds: Vec<_> = report
+                .op_kinds
+                .iter()
+                .map(|k| (k.op.as_str(), k.evaluations))
+                .collect();
+            kinds.sort();
+            kinds
+        };
+        assert_eq!(
+            kinds,
+            vec![
+                ("BinaryArith", 2),
+                ("Call", 2),
+                ("Parameter", 3),
+                ("Return", 3)
+            ]
+        );
+
+        assert_eq!(profile.report(&graph, 1).hot_nodes.len(), 1);
+    }
+
+    #[test]
+    fn folded_stacks_name_each_call_path() {
+        let (graph, quad, _, _) = build_quad();
+        let mut profile = run(&graph, quad);
+        assert_eq!(profile.folded_stacks(&graph), "quad 4\nquad;double 6\n");
+
+        profile.merge(&run(&graph, quad));
+        assert_eq!(profile.folded_stacks(&graph), "quad 8\nquad;double 12\n");
+        assert_eq!(profile.report(&graph, 0).functions[1].calls, 4);
+    }
+
+    #[test]
+    fn recursion_counts_inclusive_steps_once() {
+        let mut profile = Profile::default();
+        let (f, g) = (FunctionId(0), FunctionId(1));
+        profile.record_step(&[f, g, f], NodeId(0), Duration::from_nanos(5));
+        profile.record_step(&[f], NodeId(1), Duration::from_nanos(7));
+
+        let graph = ProgramGraph::new("test");
+        let report = profile.report(&graph, DEFAULT_HOT_NODES);
+        let steps: Vec<(&str, u64, u64)> = report
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.inclusive_steps, f.exclusive_steps))
+            .collect();
+        assert_eq!(steps, vec![("fn0", 2, 2), ("fn1", 1, 0)]);
+        assert_eq!(report.total_time_ns, 12);
+        assert_eq!(report.hot_nodes[0].op, "Unknown");
+    }
+
+    #[test]
+    fn profiling_is_off_by_default() {
+        let (graph, quad, _, _) = build_quad();
+        let mut interp = Interpreter::new(&graph, InterpreterConfig::default());
+        interp.start(quad, vec![Value::I32(3)]);
+        interp.run();
+        assert!(interp.profile().is_none());
+    }
+
+    #[test]
+    fn op_kind_drops_operands() {
+        let op = ComputeNodeOp::Core(ComputeOp::Const {
+            value: ConstValue::I32(1),
+        });
+        assert_eq!(op_kind(&op), "Const");
+        assert_eq!(op_kind(&ComputeNodeOp::Core(ComputeOp::Return)), "Return");
+    }
+}
@@ -0,0 +1,362 @@
+//! Serializable interpreter snapshots for suspending and resuming runs.
+//!
+//! [`Interpreter::snapshot`] captures a run's execution state, call stack
+//! (values, work lists and in-flight loops), memory, trace, I/O log, random
+//! generator and clock, together with the layout hash of every function of
+//! the graph it runs against. [`InterpreterSnapshot::resume`] continues the
+//! run on a graph whose functions still hash the same, and rejects it with
+//! [`SnapshotError::GraphChanged`] otherwise, since node IDs and work lists
+//! are only meaningful for the graph they were computed from. The layout
+//! hash covers the exact endpoints of every edge, so moving an edge between
+//! two nodes with identical content also counts as a change.
+//!
+//! Rewind history, coverage and profiles are not captured: a resumed run
+//! records them afresh if enabled, and cannot step back past the point it
+//! was suspended. The timeout budget keeps counting the time already spent.
+//!
+//! [`Interpreter::snapshot`]: super::Interpreter::snapshot
+
+use std::collections::HashMap;
+
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::FunctionId;
+
+use super::bytecode::layout_hash;
+use super::state::{CallFrame, ExecutionState, Interpreter, InterpreterConfig};
+use super::trace::TraceEntry;
+use super::value::Value;
+
+/// Snapshot format written by this version; others are rejected on resume.
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// Layout hash of one function, as recorded in a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionHash {
+    pub function_id: FunctionId,
+    /// Hex-encoded BLAKE3 hash of the function's signature, body, node IDs
+    /// and edge layout.
+    pub hash: String,
+}
+
+/// A suspended interpreter run, bound to the functions of its graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterpreterSnapshot {
+    /// Snapshot format ([`SNAPSHOT_VERSION`] when written).
+    pub version: u32,
+    /// Every function of the graph the run executes against, sorted by ID.
+    pub function_hashes: Vec<FunctionHash>,
+    pub(super) state: ExecutionState,
+    pub(super) call_stack: Vec<CallFrame>,
+    pub(super) memory: Vec<Value>,
+    pub(super) trace: Option<Vec<TraceEntry>>,
+    pub(super) io_log: Vec<Value>,
+    pub(super) config: InterpreterConfig,
+    pub(super) pause_requested: bool,
+    pub(super) rng: ChaCha8Rng,
+    pub(super) clock_ns: i64,
+    pub(super) steps: u64,
+    /// Milliseconds the run had spent since `start`, if started.
+    pub(super) elapsed_ms: Option<u64>,
+}
+
+/// Why a snapshot cannot be resumed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("snapshot format {found} is not supported (expected {expected})")]
+    UnsupportedVersion { found: u32, expected: u32 },
+    #[error("graph changed since the snapshot was taken: functions {}", format_ids(.functions))]
+    GraphChanged { functions: Vec<FunctionId> },
+}
+
+fn format_ids(functions: &[FunctionId]) -> String {
+    functions
+        .iter()
+        .map(|f| f.0.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl InterpreterSnapshot {
+    /// Execution state when the snapshot was taken.
+    pub fn state(&self) -> &ExecutionState {
+        &self.state
+    }
+
+    /// Steps the run had taken.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Functions added, removed or edited in `graph` since the snapshot,
+    /// sorted by ID; empty if the run can resume on `graph`.
+    pub fn changed_functions(&self, graph: &ProgramGraph) -> Vec<FunctionId> {
+        let mut current: HashMap<FunctionId, String> = function_hashes(graph)
+            .into_iter()
+            .map(|f| (f.function_id, f.hash))
+            .collect();
+        let mut changed: Vec<FunctionId> = self
+            .function_hashes
+            .iter()
+            .filter(|f| current.remove(&f.function_id).as_ref() != Some(&f.hash))
+            .map(|f| f.function_id)
+            .collect();
+        changed.extend(current.into_keys());
+        changed.sort_by_key(|f| f.0);
+        changed
+    }
+
+    /// Continues the run against `graph`, in the state it was suspended in.
+    pub fn resume(self, graph: &ProgramGraph) -> Result<Interpreter<'_>, SnapshotError> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion {
+                found: self.version,
+                expected: SNAPSHOT_VERSION,
+            });
+        }
+        let functions = self.changed_functions(graph);
+        if !functions.is_empty() {
+            return Err(SnapshotError::GraphChanged { functions });
+        }
+        Ok(Interpreter::from_snapshot(graph, self))
+    }
+}
+
+/// Layout hash of every function in `graph`, sorted by ID.
+pub fn function_hashes(graph: &ProgramGraph) -> Vec<FunctionHash> {
+    let mut hashes: Vec<FunctionHash> = graph
+        .functions()
+        .keys()
+        .map(|&function_id| FunctionHash {
+            function_id,
+            hash: layout_hash(graph, function_id).to_hex().to_string(),
+        })
+        .collect();
+    hashes.sort_by_key(|f| f.function_id.0);
+    hashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lmlang_core::ops::{ArithOp, ComputeNodeOp, ComputeOp};
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::Visibility;
+    use petgraph::visit::EdgeRef;
+    use petgraph::Direction;
+
+    use crate::interpreter::state::ExecutionState;
+
+    /// Helper: `sum(xs, bias) = fold(tick, bias, xs)` with
+    /// `tick(item, acc) = acc + item`, run through a `ForEach` loop.
+    /// Returns the graph, `sum`, and the `Add` node of `tick`.
+    fn sum_graph() -> (ProgramGraph, FunctionId, lmlang_core::id::NodeId) {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+
+        let tick = graph
+            .add_function(
+                "tick".into(),
+                root,
+                vec![("item".into(), TypeId::I64), ("acc".into(), TypeId::I64)],
+                TypeId::I64,
+                Visibility::Public,
+            )
+            .unwrap();
+        let item = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, tick)
+            .unwrap();
+        let acc = graph
+            .add_core_op(ComputeOp::Parameter { index: 1 }, tick)
+            .unwrap();
+        let add = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Add }, tick)
+            .unwrap();
+        let tick_ret = graph.add_core_op(ComputeOp::Return, tick).unwrap();
+        graph.add_data_edge(acc, add, 0, 0, TypeId::I64).unwrap();
+        graph.add_data_edge(item, add, 0, 1, TypeId::I64).unwrap();
+        graph
+            .add_data_edge(add, tick_ret, 0, 0, TypeId::I64)
+            .unwrap();
+
+        let sum = graph
+            .add_function(
+                "sum".into(),
+                root,
+                vec![("xs".into(), TypeId::UNIT), ("bias".into(), TypeId::I64)],
+                TypeId::I64,
+                Visibility::Public,
+            )
+            .unwrap();
+        let xs = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, sum)
+            .unwrap();
+        let bias = graph
+            .add_core_op(ComputeOp::Parameter { index: 1 }, sum)
+            .unwrap();
+        let for_each = graph
+            .add_core_op(ComputeOp::ForEach { body: tick }, sum)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, sum).unwrap();
+        graph
+            .add_data_edge(xs, for_each, 0, 0, TypeId::UNIT)
+            .unwrap();
+        graph
+            .add_data_edge(bias, for_each, 0, 1, TypeId::I64)
+            .unwrap();
+        graph
+            .add_data_edge(for_each, ret, 0, 0, TypeId::I64)
+            .unwrap();
+
+        (graph, sum, add)
+    }
+
+    fn args() -> Vec<Value> {
+        vec![
+            Value::untyped_array(vec![Value::I64(1), Value::I64(2), Value::I64(3)]),
+            Value::I64(10),
+        ]
+    }
+
+    fn traced() -> InterpreterConfig {
+        InterpreterConfig {
+            trace_enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn round_trip(snapshot: &InterpreterSnapshot) -> InterpreterSnapshot {
+        serde_json::from_str(&serde_json::to_string(snapshot).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn resumed_run_matches_uninterrupted_run_at_every_step() {
+        let (graph, sum, _) = sum_graph();
+        let mut baseline = Interpreter::new(&graph, traced());
+        baseline.start(sum, args());
+        baseline.run();
+        assert!(matches!(
+            baseline.state(),
+            ExecutionState::Completed {
+                result: Value::I64(16)
+            }
+        ));
+        let expected_trace = format!("{:?}", baseline.trace());
+
+        for suspend_at in 0..baseline.steps() {
+            let mut interp = Interpreter::new(&graph, traced());
+            interp.start(sum, args());
+            for _ in 0..suspend_at {
+                interp.step();
+            }
+            let snapshot = round_trip(&interp.snapshot());
+            assert_eq!(snapshot.steps(), suspend_at);
+
+            let mut resumed = snapshot.resume(&graph).unwrap();
+            resumed.run();
+            assert!(
+                matches!(
+                    resumed.state(),
+                    ExecutionState::Completed {
+                        result: Value::I64(16)
+                    }
+                ),
+                "suspended at step {suspend_at}: {:?}",
+                resumed.state()
+            );
+            assert_eq!(resumed.steps(), baseline.steps());
+            assert_eq!(format!("{:?}", resumed.trace()), expected_trace);
+        }
+    }
+
+    #[test]
+    fn resume_rejects_edited_graph() {
+        let (mut graph, sum, add) = sum_graph();
+        let mut interp = Interpreter::new(&graph, InterpreterConfig::default());
+        interp.start(sum, args());
+        interp.step();
+        let snapshot = round_trip(&interp.snapshot());
+        assert!(snapshot.changed_functions(&graph).is_empty());
+
+        graph
+            .modify_compute_node_op(
+                add,
+                ComputeNodeOp::Core(ComputeOp::BinaryArith { op: ArithOp::Sub }),
+            )
+            .unwrap();
+        let tick = graph.get_compute_node(add).unwrap().owner;
+        match snapshot.resume(&graph) {
+            Err(SnapshotError::GraphChanged { functions }) => assert_eq!(functions, vec![tick]),
+            Err(other) => panic!("Expected GraphChanged, got {other:?}"),
+            Ok(_) => panic!("Expected GraphChanged, resumed instead"),
+        }
+    }
+
+    #[test]
+    fn resume_rejects_edge_moved_between_identical_nodes() {
+        let (mut graph, sum, add) = sum_graph();
+        let tick = graph.get_compute_node(add).unwrap().owner;
+        let spare_ret = graph.add_core_op(ComputeOp::Return, tick).unwrap();
+        let mut interp = Interpreter::new(&graph, InterpreterConfig::default());
+        interp.start(sum, args());
+        interp.step();
+        let snapshot = round_trip(&interp.snapshot());
+
+        // Feed the spare return instead: the content hash only sees that
+        // `add` feeds *a* return, so it cannot tell the two graphs apart.
+        let before = lmlang_storage::hash::hash_function(&graph, tick);
+        let edge = graph
+            .compute()
+            .edges_directed(add.into(), Direction::Outgoing)
+            .next()
+            .unwrap()
+            .id();
+        graph
+            .remove_edge(lmlang_core::id::EdgeId(edge.index() as u32))
+            .unwrap();
+        graph
+            .add_data_edge(add, spare_ret, 0, 0, TypeId::I64)
+            .unwrap();
+        assert_eq!(lmlang_storage::hash::hash_function(&graph, tick), before);
+
+        assert_eq!(snapshot.changed_functions(&graph), vec![tick]);
+        assert!(matches!(
+            snapshot.resume(&graph),
+            Err(SnapshotError::GraphChanged { functions }) if functions == vec![tick]
+        ));
+    }
+
+    #[test]
+    fn resume_rejects_added_function_and_unknown_version() {
+        let (mut graph, sum, _) = sum_graph();
+        let mut interp = Interpreter::new(&graph, InterpreterConfig::default());
+        interp.start(sum, args());
+        let snapshot = interp.snapshot();
+
+        let mut future = snapshot.clone();
+        future.version = SNAPSHOT_VERSION + 1;
+        assert!(matches!(
+            future.resume(&graph),
+            Err(SnapshotError::UnsupportedVersion { found, expected: SNAPSHOT_VERSION })
+                if found == SNAPSHOT_VERSION + 1
+        ));
+
+        let root = graph.modules.root_id();
+        let extra = graph
+            .add_function(
+                "extra".into(),
+                root,
+                Vec::new(),
+                TypeId::UNIT,
+                Visibility::Public,
+            )
+            .unwrap();
+        assert_eq!(snapshot.changed_functions(&graph), vec![extra]);
+        assert!(matches!(
+            snapshot.resume(&graph),
+            Err(SnapshotError::GraphChanged { functions }) if functions == vec![extra]
+        ));
+    }
+}
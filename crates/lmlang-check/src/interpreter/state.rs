@@ -9,21 +9,30 @@
 //! which successor nodes enter the work list.
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 
 use lmlang_core::edge::FlowEdge;
 use lmlang_core::graph::ProgramGraph;
 use lmlang_core::id::{FunctionId, NodeId};
-use lmlang_core::ops::{ComputeNodeOp, ComputeOp};
+use lmlang_core::ops::{ComputeNodeOp, ComputeOp, StructuredOp};
+use lmlang_core::type_id::TypeId;
 
+use super::coverage::Coverage;
 use super::error::RuntimeError;
+use super::history::{StepRecord, WriteTarget};
+use super::profile::Profile;
+use super::snapshot::{function_hashes, InterpreterSnapshot, SNAPSHOT_VERSION};
 use super::trace::TraceEntry;
 use super::value::Value;
 
 /// Execution state of the interpreter state machine.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ExecutionState {
     /// Ready to start execution (initial state).
     Ready,
@@ -50,7 +59,7 @@ pub enum ExecutionState {
 }
 
 /// A single call frame on the interpreter's call stack.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CallFrame {
     /// Which function this frame is executing.
     pub function_id: FunctionId,
@@ -76,22 +85,291 @@ pub struct CallFrame {
     pub control_gated: HashSet<NodeId>,
     /// Tracks which nodes have been evaluated (to avoid double-evaluation).
     pub evaluated: HashSet<NodeId>,
+    /// In-flight `ForRange`/`ForEach` loops and array combinators, keyed by
+    /// node. Each return from the body advances the loop until it completes.
+    pub loops: HashMap<NodeId, StructuredLoop>,
+}
+
+/// Progress of an in-flight `ForRange`/`ForEach` loop or array combinator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredLoop {
+    /// Function called once per iteration.
+    pub(super) body: FunctionId,
+    /// Captured environment passed to `body` (for closure callbacks).
+    pub(super) captures: Vec<Value>,
+    /// Remaining indices or elements.
+    items: LoopItems,
+    /// How body results are combined into the node's output.
+    kind: LoopKind,
+    /// Item passed to the in-flight body call (kept for `ArrayFilter`).
+    current: Option<Value>,
+}
+
+/// Items a structured loop has yet to visit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum LoopItems {
+    /// `ForRange`: the next index, kept in the bounds' integer type.
+    Range { next: Value, end: i64, step: i64 },
+    /// `ForEach`, combinators and array quantifiers: the remaining array
+    /// elements.
+    Elements(#[serde(with = "remaining_elements")] std::vec::IntoIter<Value>),
+}
+
+/// Serializes the elements a loop has yet to visit as an array.
+mod remaining_elements {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Value;
+
+    pub(super) fn serialize<S: Serializer>(
+        elements: &std::vec::IntoIter<Value>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        elements.as_slice().serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<std::vec::IntoIter<Value>, D::Error> {
+        Vec::<Value>::deserialize(deserializer).map(Vec::into_iter)
+    }
+}
+
+/// How a structured loop folds body results into its output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum LoopKind {
+    /// `ForRange`/`ForEach`: body takes `(item, acc?)` and returns the next
+    /// accumulator; `None` if the body takes no accumulator.
+    Accumulate(Option<Value>),
+    /// `ArrayFold`: callback takes `(acc, item)` and returns the next accumulator.
+    Fold(Value),
+    /// `ArrayMap`: callback results collected in order.
+    Map(Vec<Value>),
+    /// `ArrayFilter`: elements whose predicate returned true; the output
+    /// pads them back to the input's length with the input's elements at the
+    /// remaining positions, keeping the input array's type.
+    Filter {
+        kept: Vec<Value>,
+        input: Vec<Value>,
+        array_ty: TypeId,
+    },
+    /// `ForAll`/`Exists`: predicate results until one decides the verdict.
+    Quantify {
+        universal: bool,
+        verdict: Option<bool>,
+    },
+}
+
+impl StructuredLoop {
+    /// A loop calling `body` for each of `items` and folding results by `kind`.
+    fn new(body: FunctionId, captures: Vec<Value>, items: LoopItems, kind: LoopKind) -> Self {
+        StructuredLoop {
+            body,
+            captures,
+            items,
+            kind,
+            current: None,
+        }
+    }
+
+    /// Arguments for the next body call, or `None` once the loop is done.
+    pub(super) fn next_args(&mut self) -> Option<Vec<Value>> {
+        if let LoopKind::Quantify {
+            verdict: Some(_), ..
+        } = self.kind
+        {
+            return None;
+        }
+        let item = match &mut self.items {
+            LoopItems::Range { next, end, step } => {
+                let index = int_value(next)?;
+                let more = (*step > 0 && index < *end) || (*step < 0 && index > *end);
+                if !more {
+                    return None;
+                }
+                // Stop after this index if advancing would overflow the
+                // bounds' integer type rather than wrap around.
+                match index
+                    .checked_add(*step)
+                    .filter(|n| int_value(&with_int_value(next, *n)) == Some(*n))
+                {
+                    Some(advanced) => std::mem::replace(next, with_int_value(next, advanced)),
+                    None => {
+                        let last = next.clone();
+                        self.items = LoopItems::Elements(Vec::new().into_iter());
+                        last
+                    }
+                }
+            }
+            LoopItems::Elements(elements) => elements.next()?,
+        };
+        Some(match &self.kind {
+            LoopKind::Accumulate(acc) => {
+                let mut args = vec![item];
+                args.extend(acc.clone());
+                args
+            }
+            LoopKind::Fold(acc) => vec![acc.clone(), item],
+            LoopKind::Map(_) | LoopKind::Quantify { .. } => vec![item],
+            LoopKind::Filter { .. } => {
+                self.current = Some(item.clone());
+                vec![item]
+            }
+        })
+    }
+
+    /// Folds one body result into the loop state.
+    pub(super) fn absorb(&mut self, value: Value, node_id: NodeId) -> Result<(), RuntimeError> {
+        match &mut self.kind {
+            LoopKind::Accumulate(acc) => {
+                if acc.is_some() {
+                    *acc = Some(value);
+                }
+            }
+            LoopKind::Fold(acc) => *acc = value,
+            LoopKind::Map(results) => results.push(value),
+            LoopKind::Filter { kept, .. } => match value {
+                Value::Bool(keep) => {
+                    let item = self.current.take();
+                    if keep {
+                        kept.extend(item);
+                    }
+                }
+                other => {
+                    return Err(RuntimeError::TypeMismatchAtRuntime {
+                        node: node_id,
+                        expected: "Bool".into(),
+                        got: other.type_name().into(),
+                    })
+                }
+            },
+            LoopKind::Quantify { universal, verdict } => {
+                *verdict =
+                    crate::contracts::check::quantifier_verdict(*universal, &value, node_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The node's output once every item has been visited.
+    pub(super) fn finish(self) -> Value {
+        match self.kind {
+            LoopKind::Accumulate(acc) => acc.unwrap_or(Value::Unit),
+            LoopKind::Fold(acc) => acc,
+            LoopKind::Map(results) => Value::untyped_array(results),
+            LoopKind::Filter {
+                mut kept,
+                input,
+                array_ty,
+            } => {
+                let count = kept.len();
+                kept.extend(input.into_iter().skip(count));
+                Value::Struct {
+                    ty: TypeId::UNIT,
+                    fields: vec![
+                        Value::I32(count as i32),
+                        Value::Array {
+                            ty: array_ty,
+                            elements: kept,
+                        },
+                    ],
+                }
+            }
+            LoopKind::Quantify { universal, verdict } => Value::Bool(verdict.unwrap_or(universal)),
+        }
+    }
 }
 
 /// Configuration for the interpreter.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InterpreterConfig {
     /// Whether to record execution traces.
     pub trace_enabled: bool,
+    /// Whether to count node evaluations and branch arms taken.
+    pub coverage_enabled: bool,
+    /// Whether to record per-node, per-function and per-stack costs. Only
+    /// the reference interpreter profiles; the bytecode VM ignores this.
+    pub profile_enabled: bool,
     /// Maximum recursion depth (call stack frames). Default: 256.
     pub max_recursion_depth: usize,
+    /// Seed for the generator behind `Random` ops. Default: 0.
+    pub random_seed: u64,
+    /// Virtual clock read by `Now` ops.
+    pub clock: VirtualClock,
+    /// Steps between rewind checkpoints; `None` disables reverse execution.
+    /// Smaller intervals rewind faster at the cost of more snapshots.
+    pub checkpoint_interval: Option<u64>,
+    /// Step, wall-clock and memory budgets. Default: unlimited.
+    pub limits: ExecutionLimits,
 }
 
 impl Default for InterpreterConfig {
     fn default() -> Self {
         InterpreterConfig {
             trace_enabled: false,
+            coverage_enabled: false,
+            profile_enabled: false,
             max_recursion_depth: 256,
+            random_seed: 0,
+            clock: VirtualClock::default(),
+            checkpoint_interval: None,
+            limits: ExecutionLimits::default(),
+        }
+    }
+}
+
+/// Resource budgets for one interpreter run; `None` leaves a resource
+/// unlimited.
+///
+/// Exceeding a budget halts execution with [`RuntimeError::StepLimitExceeded`],
+/// [`RuntimeError::TimeoutExceeded`] or [`RuntimeError::MemoryLimitExceeded`]
+/// and the values computed so far as partial results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionLimits {
+    /// Maximum nodes evaluated.
+    #[serde(default)]
+    pub max_steps: Option<u64>,
+    /// Wall-clock budget in milliseconds, measured from `start`.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Maximum memory cells allocated by `Alloc`.
+    #[serde(default)]
+    pub max_memory_cells: Option<usize>,
+}
+
+impl ExecutionLimits {
+    /// Whether no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        *self == ExecutionLimits::default()
+    }
+
+    /// Takes each limit from `self` where set, otherwise from `defaults`.
+    pub fn or(self, defaults: ExecutionLimits) -> Self {
+        ExecutionLimits {
+            max_steps: self.max_steps.or(defaults.max_steps),
+            timeout_ms: self.timeout_ms.or(defaults.timeout_ms),
+            max_memory_cells: self.max_memory_cells.or(defaults.max_memory_cells),
+        }
+    }
+}
+
+/// Deterministic clock used by the interpreter in place of the system clock.
+///
+/// The first `Now` returns `start_ns`; each subsequent read advances by
+/// `step_ns`, so repeated runs observe the same timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VirtualClock {
+    /// Nanoseconds since the Unix epoch returned by the first `Now`.
+    pub start_ns: i64,
+    /// Nanoseconds added after each `Now`. Default: 1ms.
+    pub step_ns: i64,
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        VirtualClock {
+            start_ns: 0,
+            step_ns: 1_000_000,
         }
     }
 }
@@ -112,12 +390,105 @@ pub struct Interpreter<'g> {
     memory: Vec<Value>,
     /// Execution trace (when enabled).
     trace: Option<Vec<TraceEntry>>,
+    /// Coverage counts (when enabled).
+    coverage: Option<Coverage>,
+    /// Cost profile (when enabled).
+    profile: Option<Profile>,
     /// Configuration.
     config: InterpreterConfig,
     /// Whether a pause has been requested (for pause-after-step).
     pause_requested: bool,
     /// I/O log for capturing Print output.
     pub(crate) io_log: Vec<Value>,
+    /// Seeded generator for `Random` ops.
+    rng: ChaCha8Rng,
+    /// Current virtual clock reading for `Now` ops.
+    clock_ns: i64,
+    /// Steps taken since `start`.
+    steps: u64,
+    /// Rewind checkpoints and per-step records (when enabled).
+    history: Option<History>,
+    /// Memory cell written by the node being evaluated.
+    memory_write: Option<usize>,
+    /// Caller node that received a return value in the current step.
+    returned_to: Option<NodeId>,
+    /// When `start` was called, for [`ExecutionLimits::timeout_ms`].
+    started_at: Option<Instant>,
+}
+
+/// Everything needed to resume execution from a past step.
+#[derive(Debug, Clone)]
+struct Checkpoint {
+    step: u64,
+    state: ExecutionState,
+    call_stack: Vec<CallFrame>,
+    memory: Vec<Value>,
+    trace_len: usize,
+    coverage: Option<Coverage>,
+    profile: Option<Profile>,
+    io_log_len: usize,
+    rng: ChaCha8Rng,
+    clock_ns: i64,
+    pause_requested: bool,
+}
+
+/// Recorded execution history for reverse stepping.
+///
+/// Execution is deterministic, so any past step is reached by restoring the
+/// nearest earlier checkpoint and replaying forward.
+#[derive(Debug)]
+struct History {
+    interval: u64,
+    checkpoints: Vec<Checkpoint>,
+    records: Vec<StepRecord>,
+}
+
+/// Execution state of an [`Interpreter`] without its graph borrow.
+///
+/// Lets a run outlive the borrow it started with, e.g. across requests of an
+/// interactive debug session. Must be re-attached to the same graph.
+#[derive(Debug)]
+pub struct DetachedInterpreter {
+    state: ExecutionState,
+    call_stack: Vec<CallFrame>,
+    memory: Vec<Value>,
+    trace: Option<Vec<TraceEntry>>,
+    coverage: Option<Coverage>,
+    profile: Option<Profile>,
+    config: InterpreterConfig,
+    pause_requested: bool,
+    io_log: Vec<Value>,
+    rng: ChaCha8Rng,
+    clock_ns: i64,
+    steps: u64,
+    history: Option<History>,
+    started_at: Option<Instant>,
+}
+
+impl DetachedInterpreter {
+    /// Resumes interpreting against `graph`, which must be the graph the run
+    /// was started on.
+    pub fn attach(self, graph: &ProgramGraph) -> Interpreter<'_> {
+        Interpreter {
+            graph,
+            state: self.state,
+            call_stack: self.call_stack,
+            memory: self.memory,
+            trace: self.trace,
+            coverage: self.coverage,
+            profile: self.profile,
+            config: self.config,
+            pause_requested: self.pause_requested,
+            io_log: self.io_log,
+            rng: self.rng,
+            clock_ns: self.clock_ns,
+            steps: self.steps,
+            history: self.history,
+            memory_write: None,
+            returned_to: None,
+            started_at: self.started_at,
+        }
+    }
 }
 
 impl<'g> Interpreter<'g> {
@@ -135,9 +506,31 @@ impl<'g> Interpreter<'g> {
             call_stack: Vec::new(),
             memory: Vec::new(),
             trace,
+            coverage: config.coverage_enabled.then(Coverage::default),
+            profile: config.profile_enabled.then(Profile::default),
+            rng: ChaCha8Rng::seed_from_u64(config.random_seed),
+            clock_ns: config.clock.start_ns,
+            history: config.checkpoint_interval.map(|interval| History {
+                interval: interval.max(1),
+                checkpoints: Vec::new(),
+                records: Vec::new(),
+            }),
             config,
             pause_requested: false,
             io_log: Vec::new(),
+            steps: 0,
+            memory_write: None,
+            returned_to: None,
+            started_at: None,
+        }
+    }
+
+    /// Tags an untyped compound result of `node_id`, and its untyped
+    /// contents; see [`output_type`].
+    fn tag_output(&self, node_id: NodeId, value: Value) -> Value {
+        match output_type(self.graph, node_id) {
+            Some(ty) => value.with_type(ty, &self.graph.types),
+            None => value,
         }
     }
 
@@ -147,9 +540,19 @@ impl<'g> Interpreter<'g> {
     /// and seeds the work list with Parameter nodes and Const nodes.
     pub fn start(&mut self, function_id: FunctionId, args: Vec<Value>) {
         self.state = ExecutionState::Running;
+        self.started_at = Some(Instant::now());
 
         let frame = self.create_call_frame(function_id, args, None, Vec::new());
-        self.call_stack.push(frame);
+        self.push_frame(frame);
+    }
+
+    /// Starts execution of a program entry point with command-line arguments.
+    ///
+    /// Arguments are marshalled with the same rules as the compiled `main`
+    /// wrapper (see [`super::entry`]). Use [`super::entry::exit_status`] on
+    /// the completed result to obtain the process exit status.
+    pub fn start_entry(&mut self, entry: &super::entry::EntryPoint, argv: &[String]) {
+        self.start(entry.function_id, entry.arguments(argv));
     }
 
     /// Advances execution by one node.
@@ -157,6 +560,169 @@ impl<'g> Interpreter<'g> {
     /// Pops a ready node from the work list, evaluates it, stores the result,
     /// and updates readiness of successor nodes. Returns the new state.
     pub fn step(&mut self) -> &ExecutionState {
+        if !matches!(
+            self.state,
+            ExecutionState::Running | ExecutionState::Paused { .. }
+        ) {
+            return &self.state;
+        }
+        if let Some(error) = self.exceeded_limit() {
+            self.state = ExecutionState::Error {
+                error,
+                partial_results: self.collect_partial_results(),
+            };
+            return &self.state;
+        }
+
+        let mut record = None;
+        if let Some(history) = &self.history {
+            let due = self.steps.is_multiple_of(history.interval)
+                && history
+                    .checkpoints
+                    .last()
+                    .is_none_or(|checkpoint| checkpoint.step < self.steps);
+            if due {
+                let checkpoint = self.checkpoint();
+                if let Some(history) = &mut self.history {
+                    history.checkpoints.push(checkpoint);
+                }
+            }
+            record = Some(StepRecord {
+                step: self.steps,
+                node_id: self.next_node(),
+                depth: self.call_stack.len(),
+                memory_write: None,
+                returned_to: None,
+            });
+        }
+
+        self.memory_write = None;
+        self.returned_to = None;
+        self.step_node();
+        self.steps += 1;
+        if let (Some(history), Some(mut record)) = (&mut self.history, record) {
+            record.memory_write = self.memory_write;
+            record.returned_to = self.returned_to;
+            history.records.push(record);
+        }
+        &self.state
+    }
+
+    /// The step or time budget the next step would exceed, if any.
+    fn exceeded_limit(&self) -> Option<RuntimeError> {
+        let limits = &self.config.limits;
+        let node = self.next_node()?;
+        if let Some(limit) = limits.max_steps.filter(|&limit| self.steps >= limit) {
+            return Some(RuntimeError::StepLimitExceeded { node, limit });
+        }
+        let timeout = limits.timeout_ms.map(Duration::from_millis);
+        if let (Some(timeout), Some(started_at)) = (timeout, self.started_at) {
+            if started_at.elapsed() >= timeout {
+                return Some(RuntimeError::TimeoutExceeded {
+                    node,
+                    limit_ms: timeout.as_millis() as u64,
+                });
+            }
+        }
+        None
+    }
+
+    /// Number of steps taken since [`start`](Self::start).
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Per-step records, oldest first; empty unless
+    /// [`InterpreterConfig::checkpoint_interval`] is set.
+    pub fn history(&self) -> &[StepRecord] {
+        self.history
+            .as_ref()
+            .map_or(&[][..], |history| &history.records)
+    }
+
+    /// Restores the state as it was before step `step` was taken.
+    ///
+    /// Returns `false` (leaving the state untouched) if history is disabled
+    /// or `step` lies in the future.
+    pub fn rewind_to(&mut self, step: u64) -> bool {
+        let Some(history) = &mut self.history else {
+            return false;
+        };
+        if step > self.steps {
+            return false;
+        }
+        if step == self.steps {
+            return true;
+        }
+        let Some(index) = history
+            .checkpoints
+            .iter()
+            .rposition(|checkpoint| checkpoint.step <= step)
+        else {
+            return false;
+        };
+        history.checkpoints.truncate(index + 1);
+        let checkpoint = history.checkpoints[index].clone();
+        history.records.truncate(checkpoint.step as usize);
+
+        self.steps = checkpoint.step;
+        self.state = checkpoint.state;
+        self.call_stack = checkpoint.call_stack;
+        self.memory = checkpoint.memory;
+        if let Some(trace) = &mut self.trace {
+            trace.truncate(checkpoint.trace_len);
+        }
+        self.coverage = checkpoint.coverage;
+        self.profile = checkpoint.profile;
+        self.io_log.truncate(checkpoint.io_log_len);
+        self.rng = checkpoint.rng;
+        self.clock_ns = checkpoint.clock_ns;
+        self.pause_requested = checkpoint.pause_requested;
+
+        while self.steps < step {
+            self.step();
+        }
+        true
+    }
+
+    /// Undoes the last step. Returns `false` at the start of execution or
+    /// if history is disabled.
+    pub fn step_back(&mut self) -> bool {
+        self.steps > 0 && self.rewind_to(self.steps - 1)
+    }
+
+    /// Rewinds to just before the most recent step that wrote `target`, so
+    /// the writing node is next to evaluate with its inputs available.
+    /// Returns that step's record, or `None` if no recorded step wrote it.
+    pub fn rewind_to_last_write(&mut self, target: &WriteTarget) -> Option<StepRecord> {
+        let record = self
+            .history()
+            .iter()
+            .rev()
+            .find(|record| target.written_by(record))
+            .cloned()?;
+        self.rewind_to(record.step).then_some(record)
+    }
+
+    /// Snapshot of the current state for rewinding.
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            step: self.steps,
+            state: self.state.clone(),
+            call_stack: self.call_stack.clone(),
+            memory: self.memory.clone(),
+            trace_len: self.trace.as_ref().map_or(0, Vec::len),
+            coverage: self.coverage.clone(),
+            profile: self.profile.clone(),
+            io_log_len: self.io_log.len(),
+            rng: self.rng.clone(),
+            clock_ns: self.clock_ns,
+            pause_requested: self.pause_requested,
+        }
+    }
+
+    /// Evaluates the next ready node; the body of [`step`](Self::step).
+    fn step_node(&mut self) -> &ExecutionState {
         // Only step if Running
         match &self.state {
             ExecutionState::Running => {}
@@ -217,8 +783,19 @@ impl<'g> Interpreter<'g> {
         let inputs = self.gather_inputs(node_id);
 
         // Evaluate the op
-        match self.eval_node(&op, &inputs, node_id) {
+        let depth = self.call_stack.len();
+        let started = self.profile.is_some().then(Instant::now);
+        let result = self.eval_node(&op, &inputs, node_id);
+        if let (Some(profile), Some(started)) = (&mut self.profile, started) {
+            let stack: Vec<FunctionId> = self.call_stack.iter().map(|f| f.function_id).collect();
+            profile.record_step(&stack, node_id, started.elapsed());
+        }
+        if let Some(coverage) = &mut self.coverage {
+            coverage.record_node(node_id);
+        }
+        match result {
             Ok(EvalResult::Value(value)) => {
+                let value = self.tag_output(node_id, value);
                 // Store result in current frame and mark evaluated
                 if let Some(frame) = self.call_stack.last_mut() {
                     frame.node_values.insert(node_id, value.clone());
@@ -229,6 +806,7 @@ impl<'g> Interpreter<'g> {
                 if let Some(trace) = &mut self.trace {
                     trace.push(TraceEntry {
                         node_id,
+                        depth,
                         op_description: format!("{:?}", op),
                         inputs: inputs.clone(),
                         output: Some(value.clone()),
@@ -258,6 +836,7 @@ impl<'g> Interpreter<'g> {
                 if let Some(trace) = &mut self.trace {
                     trace.push(TraceEntry {
                         node_id,
+                        depth,
                         op_description: format!("{:?}", op),
                         inputs: inputs.clone(),
                         output: None,
@@ -281,6 +860,7 @@ impl<'g> Interpreter<'g> {
                 if let Some(trace) = &mut self.trace {
                     trace.push(TraceEntry {
                         node_id,
+                        depth,
                         op_description: format!("{:?}", op),
                         inputs: inputs.clone(),
                         output: Some(value.clone()),
@@ -294,8 +874,25 @@ impl<'g> Interpreter<'g> {
                     // Top-level function returned -- execution complete
                     self.state = ExecutionState::Completed { result: value };
                 } else {
-                    // Store return value in caller's frame at return_target
+                    // Store return value in caller's frame at return_target,
+                    // unless a structured loop calls its body again
                     if let Some((target_node, _target_port)) = frame.return_target {
+                        let value = match self.advance_structured_loop(target_node, value) {
+                            Ok(Some(value)) => value,
+                            Ok(None) => {
+                                self.state = ExecutionState::Running;
+                                return &self.state;
+                            }
+                            Err(error) => {
+                                self.state = ExecutionState::Error {
+                                    error,
+                                    partial_results: self.collect_partial_results(),
+                                };
+                                return &self.state;
+                            }
+                        };
+                        self.returned_to = Some(target_node);
+                        let value = self.tag_output(target_node, value);
                         if let Some(caller_frame) = self.call_stack.last_mut() {
                             caller_frame.node_values.insert(target_node, value);
                             // The Call node now has its value; propagate readiness
@@ -316,6 +913,7 @@ impl<'g> Interpreter<'g> {
                 if let Some(trace) = &mut self.trace {
                     trace.push(TraceEntry {
                         node_id,
+                        depth,
                         op_description: format!("{:?}", op),
                         inputs: inputs.clone(),
                         output: None,
@@ -334,50 +932,29 @@ impl<'g> Interpreter<'g> {
                     return &self.state;
                 }
 
-                // --- Module-boundary invariant checking ---
-                // Check invariants for arguments crossing module boundaries.
-                // Must happen BEFORE pushing the callee frame because invariant
-                // subgraphs are evaluated on-the-fly (the callee frame doesn't
-                // exist yet).
-                if let Some(caller_func_id) = self.call_stack.last().map(|f| f.function_id) {
-                    if let (Some(caller_func), Some(target_func)) = (
-                        self.graph.get_function(caller_func_id),
-                        self.graph.get_function(target),
-                    ) {
-                        if caller_func.module != target_func.module {
-                            // Cross-module call: check invariants for each typed argument
-                            for (idx, arg_value) in args.iter().enumerate() {
-                                if let Some((_, type_id)) = target_func.params.get(idx) {
-                                    let violations =
-                                        crate::contracts::check::check_invariants_for_value(
-                                            self.graph, *type_id, arg_value, target,
-                                        );
-                                    match violations {
-                                        Ok(v) => {
-                                            if let Some(violation) = v.into_iter().next() {
-                                                self.state =
-                                                    ExecutionState::ContractViolation { violation };
-                                                return &self.state;
-                                            }
-                                        }
-                                        Err(error) => {
-                                            self.state = ExecutionState::Error {
-                                                error,
-                                                partial_results: self.collect_partial_results(),
-                                            };
-                                            return &self.state;
-                                        }
-                                    }
-                                }
-                            }
+                // Module-boundary invariants are checked BEFORE pushing the
+                // callee frame because invariant subgraphs are evaluated
+                // on-the-fly (the callee frame doesn't exist yet).
+                if let Some(caller) = self.call_stack.last().map(|f| f.function_id) {
+                    match check_call_invariants(self.graph, caller, target, &args) {
+                        Ok(None) => {}
+                        Ok(Some(violation)) => {
+                            self.state = ExecutionState::ContractViolation { violation };
+                            return &self.state;
+                        }
+                        Err(error) => {
+                            self.state = ExecutionState::Error {
+                                error,
+                                partial_results: self.collect_partial_results(),
+                            };
+                            return &self.state;
                         }
                     }
                 }
-                // --- End module-boundary invariant checking ---
 
                 // Push new frame
                 let frame = self.create_call_frame(target, args, Some(return_target), captures);
-                self.call_stack.push(frame);
+                self.push_frame(frame);
                 self.state = ExecutionState::Running;
             }
             Ok(EvalResult::ContractViolated { violation }) => {
@@ -385,6 +962,7 @@ impl<'g> Interpreter<'g> {
                 if let Some(trace) = &mut self.trace {
                     trace.push(TraceEntry {
                         node_id,
+                        depth,
                         op_description: format!("{:?}", op),
                         inputs: inputs.clone(),
                         output: None,
@@ -437,6 +1015,16 @@ impl<'g> Interpreter<'g> {
         self.trace.as_deref()
     }
 
+    /// Returns the coverage counts (if coverage was enabled).
+    pub fn coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_ref()
+    }
+
+    /// Returns the cost profile (if profiling was enabled).
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+
     /// Returns the I/O log (values printed via Print ops).
     pub fn io_log(&self) -> &[Value] {
         &self.io_log
@@ -457,10 +1045,114 @@ impl<'g> Interpreter<'g> {
         &self.config
     }
 
+    /// Returns the call stack, outermost frame first.
+    pub fn call_stack(&self) -> &[CallFrame] {
+        &self.call_stack
+    }
+
+    /// The node the next [`step`](Self::step) will evaluate, if execution is
+    /// running or paused and a node in the current frame is ready.
+    pub fn next_node(&self) -> Option<NodeId> {
+        if !matches!(
+            self.state,
+            ExecutionState::Running | ExecutionState::Paused { .. }
+        ) {
+            return None;
+        }
+        let frame = self.call_stack.last()?;
+        frame.work_list.iter().copied().find(|node_id| {
+            if frame.evaluated.contains(node_id) {
+                return false;
+            }
+            if frame.control_gated.contains(node_id) && !frame.control_ready.contains(node_id) {
+                return false;
+            }
+            let expected_inputs = self
+                .graph
+                .compute()
+                .edges_directed((*node_id).into(), Direction::Incoming)
+                .filter(|e| e.weight().is_data())
+                .count();
+            expected_inputs == 0
+                || frame.readiness.get(node_id).copied().unwrap_or(0) >= expected_inputs
+        })
+    }
+
+    /// Releases the graph borrow, keeping all execution state so the run can
+    /// continue later with [`DetachedInterpreter::attach`].
+    pub fn detach(self) -> DetachedInterpreter {
+        DetachedInterpreter {
+            state: self.state,
+            call_stack: self.call_stack,
+            memory: self.memory,
+            trace: self.trace,
+            coverage: self.coverage,
+            profile: self.profile,
+            config: self.config,
+            pause_requested: self.pause_requested,
+            io_log: self.io_log,
+            rng: self.rng,
+            clock_ns: self.clock_ns,
+            steps: self.steps,
+            history: self.history,
+            started_at: self.started_at,
+        }
+    }
+
+    /// Captures the run so it can be serialized and resumed later with
+    /// [`InterpreterSnapshot::resume`], bound to the current graph's
+    /// function hashes.
+    pub fn snapshot(&self) -> InterpreterSnapshot {
+        InterpreterSnapshot {
+            version: SNAPSHOT_VERSION,
+            function_hashes: function_hashes(self.graph),
+            state: self.state.clone(),
+            call_stack: self.call_stack.clone(),
+            memory: self.memory.clone(),
+            trace: self.trace.clone(),
+            io_log: self.io_log.clone(),
+            config: self.config.clone(),
+            pause_requested: self.pause_requested,
+            rng: self.rng.clone(),
+            clock_ns: self.clock_ns,
+            steps: self.steps,
+            elapsed_ms: self
+                .started_at
+                .map(|started_at| started_at.elapsed().as_millis() as u64),
+        }
+    }
+
+    /// Rebuilds a run from `snapshot`, whose graph binding the caller has
+    /// checked. History, coverage and profile restart empty.
+    pub(super) fn from_snapshot(graph: &'g ProgramGraph, snapshot: InterpreterSnapshot) -> Self {
+        let mut interp = Interpreter::new(graph, snapshot.config);
+        interp.state = snapshot.state;
+        interp.call_stack = snapshot.call_stack;
+        interp.memory = snapshot.memory;
+        interp.trace = snapshot.trace.or(interp.trace);
+        interp.io_log = snapshot.io_log;
+        interp.pause_requested = snapshot.pause_requested;
+        interp.rng = snapshot.rng;
+        interp.clock_ns = snapshot.clock_ns;
+        interp.steps = snapshot.steps;
+        interp.started_at = snapshot
+            .elapsed_ms
+            .and_then(|ms| Instant::now().checked_sub(Duration::from_millis(ms)));
+        interp
+    }
+
     // -----------------------------------------------------------------------
     // Internal methods
     // -----------------------------------------------------------------------
 
+    /// Pushes `frame` onto the call stack, counting the call when profiling.
+    fn push_frame(&mut self, frame: CallFrame) {
+        if let Some(profile) = &mut self.profile {
+            profile.record_call(frame.function_id);
+        }
+        self.call_stack.push(frame);
+    }
+
     /// Creates a new call frame for a function, seeding the work list.
     fn create_call_frame(
         &self,
@@ -469,6 +1161,7 @@ impl<'g> Interpreter<'g> {
         return_target: Option<(NodeId, u16)>,
         captures: Vec<Value>,
     ) -> CallFrame {
+        let args = tag_arguments(self.graph, function_id, args);
         let mut frame = CallFrame {
             function_id,
             node_values: HashMap::new(),
@@ -480,6 +1173,7 @@ impl<'g> Interpreter<'g> {
             control_ready: HashSet::new(),
             control_gated: HashSet::new(),
             evaluated: HashSet::new(),
+            loops: HashMap::new(),
         };
 
         // Find all nodes owned by this function
@@ -509,6 +1203,11 @@ impl<'g> Interpreter<'g> {
                         }
                         frame.work_list.push_back(node_id);
                     }
+                    ComputeNodeOp::Core(ComputeOp::Old { .. }) => {
+                        // Seeded with the parameters so the snapshot is taken
+                        // before any body node runs
+                        frame.work_list.push_back(node_id);
+                    }
                     ComputeNodeOp::Core(ComputeOp::Const { .. }) => {
                         // Const nodes are always ready -- but if control-gated,
                         // they wait for control
@@ -522,8 +1221,22 @@ impl<'g> Interpreter<'g> {
                         }
                     }
                     // Nodes that are seedable with no data inputs
+                    // A closure without captures has no data inputs either
+                    ComputeNodeOp::Core(ComputeOp::MakeClosure { function })
+                        if self
+                            .graph
+                            .get_function(*function)
+                            .is_some_and(|def| def.captures.is_empty()) =>
+                    {
+                        if !frame.control_gated.contains(&node_id) {
+                            frame.work_list.push_back(node_id);
+                        }
+                        frame.readiness.insert(node_id, 0);
+                    }
                     ComputeNodeOp::Core(ComputeOp::Alloc)
-                    | ComputeNodeOp::Core(ComputeOp::ReadLine) => {
+                    | ComputeNodeOp::Core(ComputeOp::ReadLine)
+                    | ComputeNodeOp::Core(ComputeOp::Now)
+                    | ComputeNodeOp::Core(ComputeOp::Random) => {
                         if !frame.control_gated.contains(&node_id) {
                             frame.work_list.push_back(node_id);
                         }
@@ -637,62 +1350,11 @@ impl<'g> Interpreter<'g> {
         use super::eval::eval_op;
 
         match op {
-            ComputeNodeOp::Core(ComputeOp::Return) => {
-                // Return takes port 0 as the return value
-                let value = inputs
-                    .iter()
-                    .find(|(port, _)| *port == 0)
-                    .map(|(_, v)| v.clone())
-                    .unwrap_or(Value::Unit);
-                Ok(EvalResult::Return(value))
-            }
+            ComputeNodeOp::Core(ComputeOp::Return) => Ok(EvalResult::Return(return_value(inputs))),
             ComputeNodeOp::Core(ComputeOp::Call { target }) => {
-                // Gather arguments from data inputs (sorted by port)
-                let args: Vec<Value> = inputs.iter().map(|(_, v)| v.clone()).collect();
-                Ok(EvalResult::Call {
-                    target: *target,
-                    args,
-                    return_target: (node_id, 0),
-                    captures: Vec::new(),
-                })
-            }
-            ComputeNodeOp::Core(ComputeOp::IndirectCall) => {
-                // Port 0 is the function reference, remaining ports are args
-                if inputs.is_empty() {
-                    return Err(RuntimeError::MissingValue {
-                        node: node_id,
-                        port: 0,
-                    });
-                }
-                let func_val = &inputs[0].1;
-                match func_val {
-                    Value::FunctionRef(fid) => {
-                        let args: Vec<Value> =
-                            inputs.iter().skip(1).map(|(_, v)| v.clone()).collect();
-                        Ok(EvalResult::Call {
-                            target: *fid,
-                            args,
-                            return_target: (node_id, 0),
-                            captures: Vec::new(),
-                        })
-                    }
-                    Value::Closure { function, captures } => {
-                        let args: Vec<Value> =
-                            inputs.iter().skip(1).map(|(_, v)| v.clone()).collect();
-                        Ok(EvalResult::Call {
-                            target: *function,
-                            args,
-                            return_target: (node_id, 0),
-                            captures: captures.clone(),
-                        })
-                    }
-                    _ => Err(RuntimeError::TypeMismatchAtRuntime {
-                        node: node_id,
-                        expected: "FunctionRef or Closure".into(),
-                        got: func_val.type_name().into(),
-                    }),
-                }
+                Ok(direct_call(*target, inputs, node_id))
             }
+            ComputeNodeOp::Core(ComputeOp::IndirectCall) => indirect_call(inputs, node_id),
             ComputeNodeOp::Core(ComputeOp::Parameter { index }) => {
                 // Parameter values are pre-seeded in the frame
                 let frame = self
@@ -708,6 +1370,20 @@ impl<'g> Interpreter<'g> {
                     .unwrap_or(Value::Unit);
                 Ok(EvalResult::Value(value))
             }
+            ComputeNodeOp::Core(ComputeOp::Old { index }) => {
+                let frame = self
+                    .call_stack
+                    .last()
+                    .ok_or_else(|| RuntimeError::InternalError {
+                        message: "no call frame for Old".into(),
+                    })?;
+                Ok(EvalResult::Value(crate::contracts::check::old_value(
+                    &frame.arguments,
+                    *index,
+                    &self.memory,
+                    node_id,
+                )?))
+            }
             ComputeNodeOp::Core(ComputeOp::CaptureAccess { index }) => {
                 let frame = self
                     .call_stack
@@ -733,6 +1409,14 @@ impl<'g> Interpreter<'g> {
                 // Placeholder: return I64(0) as per plan
                 Ok(EvalResult::Value(Value::I64(0)))
             }
+            ComputeNodeOp::Core(ComputeOp::Now) => {
+                let now = self.clock_ns;
+                self.clock_ns = self.clock_ns.wrapping_add(self.config.clock.step_ns);
+                Ok(EvalResult::Value(Value::I64(now)))
+            }
+            ComputeNodeOp::Core(ComputeOp::Random) => {
+                Ok(EvalResult::Value(Value::I64(self.rng.next_u64() as i64)))
+            }
             ComputeNodeOp::Core(
                 ComputeOp::FileOpen
                 | ComputeOp::FileRead
@@ -743,123 +1427,35 @@ impl<'g> Interpreter<'g> {
                 Ok(EvalResult::Value(Value::I64(0)))
             }
             ComputeNodeOp::Core(ComputeOp::Alloc) => {
-                let addr = self.memory.len();
-                self.memory.push(Value::Unit);
-                Ok(EvalResult::Value(Value::Pointer(addr)))
+                let addr = alloc_cell(&mut self.memory, &self.config.limits, node_id)?;
+                self.memory_write = Some(addr);
+                Ok(EvalResult::Value(Value::Pointer {
+                    ty: TypeId::UNIT,
+                    address: addr,
+                }))
             }
             ComputeNodeOp::Core(ComputeOp::Load) => {
-                // Port 0 is the pointer
-                let ptr = inputs.iter().find(|(p, _)| *p == 0).map(|(_, v)| v).ok_or(
-                    RuntimeError::MissingValue {
-                        node: node_id,
-                        port: 0,
-                    },
-                )?;
-                match ptr {
-                    Value::Pointer(addr) => {
-                        if *addr >= self.memory.len() {
-                            Err(RuntimeError::OutOfBoundsAccess {
-                                node: node_id,
-                                index: *addr,
-                                size: self.memory.len(),
-                            })
-                        } else {
-                            Ok(EvalResult::Value(self.memory[*addr].clone()))
-                        }
-                    }
-                    _ => Err(RuntimeError::TypeMismatchAtRuntime {
-                        node: node_id,
-                        expected: "Pointer".into(),
-                        got: ptr.type_name().into(),
-                    }),
-                }
+                Ok(EvalResult::Value(load_cell(&self.memory, inputs, node_id)?))
             }
             ComputeNodeOp::Core(ComputeOp::Store) => {
-                // Port 0: pointer, Port 1: value
-                let ptr = inputs.iter().find(|(p, _)| *p == 0).map(|(_, v)| v).ok_or(
-                    RuntimeError::MissingValue {
-                        node: node_id,
-                        port: 0,
-                    },
-                )?;
-                let val = inputs.iter().find(|(p, _)| *p == 1).map(|(_, v)| v).ok_or(
-                    RuntimeError::MissingValue {
-                        node: node_id,
-                        port: 1,
-                    },
-                )?;
-                match ptr {
-                    Value::Pointer(addr) => {
-                        if *addr >= self.memory.len() {
-                            Err(RuntimeError::OutOfBoundsAccess {
-                                node: node_id,
-                                index: *addr,
-                                size: self.memory.len(),
-                            })
-                        } else {
-                            self.memory[*addr] = val.clone();
-                            Ok(EvalResult::NoValue)
-                        }
-                    }
-                    _ => Err(RuntimeError::TypeMismatchAtRuntime {
-                        node: node_id,
-                        expected: "Pointer".into(),
-                        got: ptr.type_name().into(),
-                    }),
-                }
+                let addr = store_cell(&mut self.memory, inputs, node_id)?;
+                self.memory_write = Some(addr);
+                Ok(EvalResult::NoValue)
             }
             ComputeNodeOp::Core(ComputeOp::GetElementPtr) => {
-                // Port 0: base pointer, Port 1: index
-                let base = inputs.iter().find(|(p, _)| *p == 0).map(|(_, v)| v).ok_or(
-                    RuntimeError::MissingValue {
-                        node: node_id,
-                        port: 0,
-                    },
-                )?;
-                let index = inputs.iter().find(|(p, _)| *p == 1).map(|(_, v)| v).ok_or(
-                    RuntimeError::MissingValue {
-                        node: node_id,
-                        port: 1,
-                    },
-                )?;
-                match (base, index) {
-                    (Value::Pointer(addr), idx) => {
-                        let offset = value_to_usize(idx, node_id)?;
-                        Ok(EvalResult::Value(Value::Pointer(addr + offset)))
-                    }
-                    _ => Err(RuntimeError::TypeMismatchAtRuntime {
-                        node: node_id,
-                        expected: "Pointer".into(),
-                        got: base.type_name().into(),
-                    }),
-                }
+                Ok(EvalResult::Value(element_ptr(inputs, node_id)?))
             }
             ComputeNodeOp::Core(ComputeOp::MakeClosure { function }) => {
                 let captures: Vec<Value> = inputs.iter().map(|(_, v)| v.clone()).collect();
                 Ok(EvalResult::Value(Value::Closure {
+                    ty: TypeId::UNIT,
                     function: *function,
                     captures,
                 }))
             }
             // Control flow ops -- Branch/IfElse/Loop/Match/Jump/Phi handled here
             ComputeNodeOp::Core(ComputeOp::Branch) | ComputeNodeOp::Core(ComputeOp::IfElse) => {
-                // Condition at port 0
-                let cond = inputs.iter().find(|(p, _)| *p == 0).map(|(_, v)| v).ok_or(
-                    RuntimeError::MissingValue {
-                        node: node_id,
-                        port: 0,
-                    },
-                )?;
-                let taken = match cond {
-                    Value::Bool(b) => *b,
-                    _ => {
-                        return Err(RuntimeError::TypeMismatchAtRuntime {
-                            node: node_id,
-                            expected: "Bool".into(),
-                            got: cond.type_name().into(),
-                        })
-                    }
-                };
+                let taken = branch_condition(inputs, node_id)?;
                 // Store the branch decision so propagate_readiness can use it
                 if let Some(frame) = self.call_stack.last_mut() {
                     // Store a value indicating which branch was taken
@@ -878,14 +1474,21 @@ impl<'g> Interpreter<'g> {
                 }
                 Ok(EvalResult::NoValue)
             }
+            ComputeNodeOp::Core(
+                ComputeOp::ForRange { .. }
+                | ComputeOp::ForEach { .. }
+                | ComputeOp::ForAll { .. }
+                | ComputeOp::Exists { .. },
+            )
+            | ComputeNodeOp::Structured(
+                StructuredOp::ArrayMap | StructuredOp::ArrayFold | StructuredOp::ArrayFilter,
+            ) => {
+                let structured = structured_loop(op, inputs, node_id)?;
+                self.start_structured_loop(node_id, structured)
+            }
             ComputeNodeOp::Core(ComputeOp::Match) => {
                 // Discriminant at port 0
-                let disc = inputs.iter().find(|(p, _)| *p == 0).map(|(_, v)| v).ok_or(
-                    RuntimeError::MissingValue {
-                        node: node_id,
-                        port: 0,
-                    },
-                )?;
+                let disc = input_at(inputs, 0, node_id)?;
                 if let Some(frame) = self.call_stack.last_mut() {
                     frame.node_values.insert(node_id, disc.clone());
                 }
@@ -897,11 +1500,6 @@ impl<'g> Interpreter<'g> {
             }
             ComputeNodeOp::Core(ComputeOp::Phi) => {
                 // Phi: select value from the control flow path that was actually taken.
-                //
-                // Convention: incoming control edges from a Branch node carry
-                // branch_index. The branch node stores its Bool decision as a
-                // node_value. We map: true -> branch_index=0 -> data port 0,
-                // false -> branch_index=1 -> data port 1.
                 let node_idx: petgraph::graph::NodeIndex<u32> = node_id.into();
 
                 let frame = self
@@ -911,134 +1509,54 @@ impl<'g> Interpreter<'g> {
                         message: "no call frame for Phi".into(),
                     })?;
 
-                let mut selected_port: Option<u16> = None;
-
                 // Look at incoming control edges from branch nodes
-                for edge_ref in self
+                let decisions = self
                     .graph
                     .compute()
                     .edges_directed(node_idx, Direction::Incoming)
-                {
-                    if let FlowEdge::Control {
-                        branch_index: Some(_),
-                    } = edge_ref.weight()
-                    {
-                        let source_id = NodeId::from(edge_ref.source());
-                        if let Some(branch_val) = frame.node_values.get(&source_id) {
-                            match branch_val {
-                                Value::Bool(true) => {
-                                    selected_port = Some(0);
-                                }
-                                Value::Bool(false) => {
-                                    selected_port = Some(1);
-                                }
-                                _ => {}
+                    .filter(|edge_ref| {
+                        matches!(
+                            edge_ref.weight(),
+                            FlowEdge::Control {
+                                branch_index: Some(_)
                             }
-                        }
-                    }
-                }
-
-                let value = if let Some(port) = selected_port {
-                    inputs
-                        .iter()
-                        .find(|(p, _)| *p == port)
-                        .map(|(_, v)| v.clone())
-                        .unwrap_or(Value::Unit)
-                } else {
-                    // No control info available -- take first input
-                    inputs
-                        .iter()
-                        .find(|(_, _)| true)
-                        .map(|(_, v)| v.clone())
-                        .unwrap_or(Value::Unit)
-                };
-                Ok(EvalResult::Value(value))
+                        )
+                    })
+                    .map(|edge_ref| frame.node_values.get(&NodeId::from(edge_ref.source())));
+                Ok(EvalResult::Value(phi_value(inputs, decisions)))
             }
             // Contract ops: check condition and halt with ContractViolation if false
             ComputeNodeOp::Core(
-                ComputeOp::Precondition { message } | ComputeOp::Postcondition { message },
-            ) => {
-                // Port 0 is the condition (Bool)
-                let condition = inputs.iter().find(|(p, _)| *p == 0).map(|(_, v)| v);
-                match condition {
-                    Some(Value::Bool(true)) => Ok(EvalResult::NoValue),
-                    Some(Value::Bool(false)) => {
-                        let kind = match op {
-                            ComputeNodeOp::Core(ComputeOp::Precondition { .. }) => {
-                                crate::contracts::ContractKind::Precondition
-                            }
-                            _ => crate::contracts::ContractKind::Postcondition,
-                        };
-                        let frame =
-                            self.call_stack
-                                .last()
-                                .ok_or_else(|| RuntimeError::InternalError {
-                                    message: "no call frame for contract check".into(),
-                                })?;
-                        let counterexample = crate::contracts::check::collect_counterexample(
-                            self.graph,
-                            node_id,
-                            &frame.node_values,
-                        );
-                        Ok(EvalResult::ContractViolated {
-                            violation: crate::contracts::ContractViolation {
-                                kind,
-                                contract_node: node_id,
-                                function_id: frame.function_id,
-                                message: message.clone(),
-                                inputs: frame.arguments.clone(),
-                                actual_return: None,
-                                counterexample,
-                            },
-                        })
-                    }
-                    Some(other) => Err(RuntimeError::TypeMismatchAtRuntime {
-                        node: node_id,
-                        expected: "Bool".into(),
-                        got: other.type_name().into(),
-                    }),
-                    None => {
-                        // No condition input -- treat as passed
-                        Ok(EvalResult::NoValue)
-                    }
-                }
-            }
-            ComputeNodeOp::Core(ComputeOp::Invariant { message, .. }) => {
-                let condition = inputs.iter().find(|(p, _)| *p == 0).map(|(_, v)| v);
-                match condition {
-                    Some(Value::Bool(true)) => Ok(EvalResult::NoValue),
-                    Some(Value::Bool(false)) => {
-                        let frame =
-                            self.call_stack
-                                .last()
-                                .ok_or_else(|| RuntimeError::InternalError {
-                                    message: "no call frame for contract check".into(),
-                                })?;
-                        let counterexample = crate::contracts::check::collect_counterexample(
-                            self.graph,
-                            node_id,
-                            &frame.node_values,
-                        );
-                        Ok(EvalResult::ContractViolated {
-                            violation: crate::contracts::ContractViolation {
-                                kind: crate::contracts::ContractKind::Invariant,
-                                contract_node: node_id,
-                                function_id: frame.function_id,
-                                message: message.clone(),
-                                inputs: frame.arguments.clone(),
-                                actual_return: None,
-                                counterexample,
-                            },
-                        })
-                    }
-                    Some(other) => Err(RuntimeError::TypeMismatchAtRuntime {
-                        node: node_id,
-                        expected: "Bool".into(),
-                        got: other.type_name().into(),
-                    }),
-                    None => Ok(EvalResult::NoValue),
+                ComputeOp::Precondition { .. }
+                | ComputeOp::Postcondition { .. }
+                | ComputeOp::Invariant { .. },
+            ) => match failed_contract(op, inputs, node_id)? {
+                None => Ok(EvalResult::NoValue),
+                Some((kind, message)) => {
+                    let frame =
+                        self.call_stack
+                            .last()
+                            .ok_or_else(|| RuntimeError::InternalError {
+                                message: "no call frame for contract check".into(),
+                            })?;
+                    let counterexample = crate::contracts::check::collect_counterexample(
+                        self.graph,
+                        node_id,
+                        &frame.node_values,
+                    );
+                    Ok(EvalResult::ContractViolated {
+                        violation: crate::contracts::ContractViolation {
+                            kind,
+                            contract_node: node_id,
+                            function_id: frame.function_id,
+                            message,
+                            inputs: frame.arguments.clone(),
+                            actual_return: None,
+                            counterexample,
+                        },
+                    })
                 }
-            }
+            },
 
             // Delegate all other ops (arithmetic, logic, comparison, structured) to eval_op
             _ => match eval_op(op, inputs, node_id, self.graph)? {
@@ -1050,8 +1568,6 @@ impl<'g> Interpreter<'g> {
 
     /// Propagates readiness to successor nodes after a node evaluation.
     fn propagate_readiness(&mut self, node_id: NodeId, op: &ComputeNodeOp) {
-        let node_idx: petgraph::graph::NodeIndex<u32> = node_id.into();
-
         // Check if this is a control flow node that selects branches
         let is_branch = matches!(
             op,
@@ -1063,33 +1579,41 @@ impl<'g> Interpreter<'g> {
         if is_branch {
             self.propagate_control_flow(node_id, op);
         } else {
-            // Collect successors first (no mutable borrow)
-            let successors: Vec<(NodeId, bool)> = self
-                .graph
-                .compute()
-                .edges_directed(node_idx, Direction::Outgoing)
-                .map(|edge_ref| match edge_ref.weight() {
-                    FlowEdge::Data { .. } => (NodeId::from(edge_ref.target()), true),
-                    FlowEdge::Control { .. } => (NodeId::from(edge_ref.target()), false),
-                })
-                .collect();
+            self.propagate_to_successors(node_id);
+        }
+    }
 
-            // Update readiness counters and control flags
-            if let Some(frame) = self.call_stack.last_mut() {
-                for &(succ_id, is_data) in &successors {
-                    if is_data {
-                        let count = frame.readiness.entry(succ_id).or_insert(0);
-                        *count += 1;
-                    } else {
-                        frame.control_ready.insert(succ_id);
-                    }
+    /// Marks every data successor of `node_id` as having one more input and
+    /// every control successor as control-ready, then schedules them.
+    fn propagate_to_successors(&mut self, node_id: NodeId) {
+        let node_idx: petgraph::graph::NodeIndex<u32> = node_id.into();
+
+        // Collect successors first (no mutable borrow)
+        let successors: Vec<(NodeId, bool)> = self
+            .graph
+            .compute()
+            .edges_directed(node_idx, Direction::Outgoing)
+            .map(|edge_ref| match edge_ref.weight() {
+                FlowEdge::Data { .. } => (NodeId::from(edge_ref.target()), true),
+                FlowEdge::Control { .. } => (NodeId::from(edge_ref.target()), false),
+            })
+            .collect();
+
+        // Update readiness counters and control flags
+        if let Some(frame) = self.call_stack.last_mut() {
+            for &(succ_id, is_data) in &successors {
+                if is_data {
+                    let count = frame.readiness.entry(succ_id).or_insert(0);
+                    *count += 1;
+                } else {
+                    frame.control_ready.insert(succ_id);
                 }
             }
+        }
 
-            // Now schedule (separate borrow scope)
-            for (succ_id, _) in successors {
-                self.try_schedule_node(succ_id);
-            }
+        // Now schedule (separate borrow scope)
+        for (succ_id, _) in successors {
+            self.try_schedule_node(succ_id);
         }
     }
 
@@ -1144,31 +1668,12 @@ impl<'g> Interpreter<'g> {
             .cloned();
 
         // Determine which branch index is taken
-        let taken_branch: Option<u16> = match op {
-            ComputeNodeOp::Core(ComputeOp::Branch | ComputeOp::IfElse) => {
-                match &branch_value {
-                    Some(Value::Bool(true)) => Some(0),  // then branch
-                    Some(Value::Bool(false)) => Some(1), // else branch
-                    _ => None,
-                }
-            }
-            ComputeNodeOp::Core(ComputeOp::Loop) => {
-                match &branch_value {
-                    Some(Value::Bool(true)) => Some(0),  // continue loop (body)
-                    Some(Value::Bool(false)) => Some(1), // exit loop
-                    _ => Some(1),                        // default: exit
-                }
+        let taken_branch = taken_branch(op, branch_value.as_ref());
+        if let (Some(coverage), Some(arm)) = (&mut self.coverage, taken_branch) {
+            if !matches!(op, ComputeNodeOp::Core(ComputeOp::Loop)) {
+                coverage.record_arm(node_id, arm);
             }
-            ComputeNodeOp::Core(ComputeOp::Match) => match &branch_value {
-                Some(Value::I32(v)) => Some(*v as u16),
-                Some(Value::I8(v)) => Some(*v as u16),
-                Some(Value::I16(v)) => Some(*v as u16),
-                Some(Value::I64(v)) => Some(*v as u16),
-                Some(Value::Enum { variant, .. }) => Some(*variant as u16),
-                _ => Some(0),
-            },
-            _ => None,
-        };
+        }
 
         // Collect outgoing control edges
         let control_successors: Vec<(NodeId, Option<u16>)> = self
@@ -1331,29 +1836,68 @@ impl<'g> Interpreter<'g> {
         }
     }
 
-    /// Propagates readiness from a Call node after it receives its return value.
-    fn propagate_readiness_for_call_return(&mut self, call_node_id: NodeId) {
-        let node_idx: petgraph::graph::NodeIndex<u32> = call_node_id.into();
-
-        let successors: Vec<NodeId> = self
-            .graph
-            .compute()
-            .edges_directed(node_idx, Direction::Outgoing)
-            .filter_map(|edge_ref| match edge_ref.weight() {
-                FlowEdge::Data { .. } => Some(NodeId::from(edge_ref.target())),
-                _ => None,
-            })
-            .collect();
+    /// Begins a `ForRange`/`ForEach` loop or array combinator: calls the body
+    /// for the first item, or completes immediately if there are no items.
+    fn start_structured_loop(
+        &mut self,
+        node_id: NodeId,
+        mut structured: StructuredLoop,
+    ) -> Result<EvalResult, RuntimeError> {
+        let Some(args) = structured.next_args() else {
+            return Ok(EvalResult::Value(structured.finish()));
+        };
+        let body = structured.body;
+        let captures = structured.captures.clone();
+        let frame = self
+            .call_stack
+            .last_mut()
+            .ok_or_else(|| RuntimeError::InternalError {
+                message: "no call frame for structured loop".into(),
+            })?;
+        frame.loops.insert(node_id, structured);
+        Ok(EvalResult::Call {
+            target: body,
+            args,
+            return_target: (node_id, 0),
+            captures,
+        })
+    }
 
-        for succ_id in successors {
-            if let Some(frame) = self.call_stack.last_mut() {
-                let count = frame.readiness.entry(succ_id).or_insert(0);
-                *count += 1;
+    /// Feeds a body's return value back into the loop at `node_id`, if one is
+    /// in flight in the current frame.
+    ///
+    /// Pushes the next body call and returns `Ok(None)` while iterations
+    /// remain; otherwise returns the value the node produces (the loop's
+    /// result, or `value` itself when `node_id` is a plain call).
+    fn advance_structured_loop(
+        &mut self,
+        node_id: NodeId,
+        value: Value,
+    ) -> Result<Option<Value>, RuntimeError> {
+        let Some(frame) = self.call_stack.last_mut() else {
+            return Ok(Some(value));
+        };
+        let Some(structured) = frame.loops.get_mut(&node_id) else {
+            return Ok(Some(value));
+        };
+        structured.absorb(value, node_id)?;
+        match structured.next_args() {
+            Some(args) => {
+                let body = structured.body;
+                let captures = structured.captures.clone();
+                let next = self.create_call_frame(body, args, Some((node_id, 0)), captures);
+                self.push_frame(next);
+                Ok(None)
             }
-            self.try_schedule_node(succ_id);
+            None => Ok(frame.loops.remove(&node_id).map(StructuredLoop::finish)),
         }
     }
 
+    /// Propagates readiness from a Call node after it receives its return value.
+    fn propagate_readiness_for_call_return(&mut self, call_node_id: NodeId) {
+        self.propagate_to_successors(call_node_id);
+    }
+
     /// Collects all partial results from all call frames.
     fn collect_partial_results(&self) -> HashMap<NodeId, Value> {
         let mut results = HashMap::new();
@@ -1385,6 +1929,504 @@ pub(crate) enum EvalResult {
     },
 }
 
+// ---------------------------------------------------------------------------
+// Op semantics shared with the bytecode VM
+// ---------------------------------------------------------------------------
+
+/// The value on `port`, or [`RuntimeError::MissingValue`].
+pub(super) fn input_at(
+    inputs: &[(u16, Value)],
+    port: u16,
+    node_id: NodeId,
+) -> Result<&Value, RuntimeError> {
+    inputs
+        .iter()
+        .find(|(p, _)| *p == port)
+        .map(|(_, v)| v)
+        .ok_or(RuntimeError::MissingValue {
+            node: node_id,
+            port,
+        })
+}
+
+/// A `Return` node's result: port 0, or `Unit` without one.
+pub(super) fn return_value(inputs: &[(u16, Value)]) -> Value {
+    inputs
+        .iter()
+        .find(|(port, _)| *port == 0)
+        .map(|(_, v)| v.clone())
+        .unwrap_or(Value::Unit)
+}
+
+/// A `Call` of `target` with the node's inputs (sorted by port) as arguments.
+pub(super) fn direct_call(
+    target: FunctionId,
+    inputs: &[(u16, Value)],
+    node_id: NodeId,
+) -> EvalResult {
+    EvalResult::Call {
+        target,
+        args: inputs.iter().map(|(_, v)| v.clone()).collect(),
+        return_target: (node_id, 0),
+        captures: Vec::new(),
+    }
+}
+
+/// An `IndirectCall`: port 0 is the function reference or closure, the
+/// remaining ports are arguments.
+pub(super) fn indirect_call(
+    inputs: &[(u16, Value)],
+    node_id: NodeId,
+) -> Result<EvalResult, RuntimeError> {
+    let Some((_, func_val)) = inputs.first() else {
+        return Err(RuntimeError::MissingValue {
+            node: node_id,
+            port: 0,
+        });
+    };
+    let (target, captures) = match func_val {
+        Value::FunctionRef(fid) => (*fid, Vec::new()),
+        Value::Closure {
+            function, captures, ..
+        } => (*function, captures.clone()),
+        _ => {
+            return Err(RuntimeError::TypeMismatchAtRuntime {
+                node: node_id,
+                expected: "FunctionRef or Closure".into(),
+                got: func_val.type_name().into(),
+            })
+        }
+    };
+    Ok(EvalResult::Call {
+        target,
+        args: inputs.iter().skip(1).map(|(_, v)| v.clone()).collect(),
+        return_target: (node_id, 0),
+        captures,
+    })
+}
+
+/// Checks the invariants of every typed argument of a call from `caller`
+/// into a function of another module. Returns the first violation.
+pub(super) fn check_call_invariants(
+    graph: &ProgramGraph,
+    caller: FunctionId,
+    target: FunctionId,
+    args: &[Value],
+) -> Result<Option<crate::contracts::ContractViolation>, RuntimeError> {
+    let (Some(caller_func), Some(target_func)) =
+        (graph.get_function(caller), graph.get_function(target))
+    else {
+        return Ok(None);
+    };
+    if caller_func.module == target_func.module {
+        return Ok(None);
+    }
+    for (arg_value, (_, type_id)) in args.iter().zip(&target_func.params) {
+        let violations = crate::contracts::check::check_invariants_for_value(
+            graph, *type_id, arg_value, target,
+        )?;
+        if let Some(violation) = violations.into_iter().next() {
+            return Ok(Some(violation));
+        }
+    }
+    Ok(None)
+}
+
+/// Allocates a `Unit` memory cell within `limits` and returns its address.
+pub(super) fn alloc_cell(
+    memory: &mut Vec<Value>,
+    limits: &ExecutionLimits,
+    node_id: NodeId,
+) -> Result<usize, RuntimeError> {
+    let addr = memory.len();
+    if let Some(limit) = limits.max_memory_cells {
+        if addr >= limit {
+            return Err(RuntimeError::MemoryLimitExceeded {
+                node: node_id,
+                limit,
+            });
+        }
+    }
+    memory.push(Value::Unit);
+    Ok(addr)
+}
+
+/// The type `node_id` produces: for array combinators, the result type the
+/// type checker derives from their inputs; otherwise the type on the first
+/// outgoing data edge, which the type checker keeps consistent with what the
+/// node produces, or the derived type if the result is not consumed. Used to
+/// tag compound results whose op does not name their type, such as
+/// `ArrayCreate`, `Alloc` and `MakeClosure`.
+pub(super) fn output_type(graph: &ProgramGraph, node_id: NodeId) -> Option<TypeId> {
+    let derived = match graph.get_compute_node(node_id).map(|node| &node.op) {
+        Some(ComputeNodeOp::Structured(StructuredOp::ArrayMap | StructuredOp::ArrayFilter)) => {
+            crate::typecheck::derived_output_type(graph, node_id)
+        }
+        _ => None,
+    };
+    if derived.is_some() {
+        return derived;
+    }
+    graph
+        .compute()
+        .edges_directed(node_id.into(), Direction::Outgoing)
+        .find_map(|edge_ref| match edge_ref.weight() {
+            FlowEdge::Data { value_type, .. } => Some(*value_type),
+            FlowEdge::Control { .. } => None,
+        })
+        .or_else(|| crate::typecheck::derived_output_type(graph, node_id))
+}
+
+/// Tags untyped compound arguments, e.g. built from JSON, with the
+/// parameter types of `function_id`.
+pub(super) fn tag_arguments(
+    graph: &ProgramGraph,
+    function_id: FunctionId,
+    args: Vec<Value>,
+) -> Vec<Value> {
+    let Some(func) = graph.get_function(function_id) else {
+        return args;
+    };
+    args.into_iter()
+        .enumerate()
+        .map(|(i, arg)| match func.params.get(i) {
+            Some((_, ty)) => arg.with_type(*ty, &graph.types),
+            None => arg,
+        })
+        .collect()
+}
+
+/// The memory cell at the pointer on port 0, or an out-of-bounds error.
+fn memory_cell(
+    memory_len: usize,
+    inputs: &[(u16, Value)],
+    node_id: NodeId,
+) -> Result<usize, RuntimeError> {
+    match input_at(inputs, 0, node_id)? {
+        Value::Pointer { address, .. } if *address >= memory_len => {
+            Err(RuntimeError::OutOfBoundsAccess {
+                node: node_id,
+                index: *address,
+                size: memory_len,
+            })
+        }
+        Value::Pointer { address, .. } => Ok(*address),
+        ptr => Err(RuntimeError::TypeMismatchAtRuntime {
+            node: node_id,
+            expected: "Pointer".into(),
+            got: ptr.type_name().into(),
+        }),
+    }
+}
+
+/// A `Load`: reads the cell at the pointer on port 0.
+pub(super) fn load_cell(
+    memory: &[Value],
+    inputs: &[(u16, Value)],
+    node_id: NodeId,
+) -> Result<Value, RuntimeError> {
+    let addr = memory_cell(memory.len(), inputs, node_id)?;
+    Ok(memory[addr].clone())
+}
+
+/// A `Store`: writes port 1 to the cell at the pointer on port 0 and returns
+/// the address written.
+pub(super) fn store_cell(
+    memory: &mut [Value],
+    inputs: &[(u16, Value)],
+    node_id: NodeId,
+) -> Result<usize, RuntimeError> {
+    input_at(inputs, 0, node_id)?;
+    let val = input_at(inputs, 1, node_id)?;
+    let addr = memory_cell(memory.len(), inputs, node_id)?;
+    memory[addr] = val.clone();
+    Ok(addr)
+}
+
+/// A `GetElementPtr`: offsets the base pointer on port 0 by the index on port 1.
+pub(super) fn element_ptr(inputs: &[(u16, Value)], node_id: NodeId) -> Result<Value, RuntimeError> {
+    let base = input_at(inputs, 0, node_id)?;
+    let index = input_at(inputs, 1, node_id)?;
+    match base {
+        Value::Pointer { address, .. } => {
+            // The element pointer's type is tagged from the node's edges
+            let offset = value_to_usize(index, node_id)?;
+            Ok(Value::Pointer {
+                ty: TypeId::UNIT,
+                address: address + offset,
+            })
+        }
+        _ => Err(RuntimeError::TypeMismatchAtRuntime {
+            node: node_id,
+            expected: "Pointer".into(),
+            got: base.type_name().into(),
+        }),
+    }
+}
+
+/// The `Bool` condition of a `Branch`/`IfElse` on port 0.
+pub(super) fn branch_condition(
+    inputs: &[(u16, Value)],
+    node_id: NodeId,
+) -> Result<bool, RuntimeError> {
+    match input_at(inputs, 0, node_id)? {
+        Value::Bool(b) => Ok(*b),
+        cond => Err(RuntimeError::TypeMismatchAtRuntime {
+            node: node_id,
+            expected: "Bool".into(),
+            got: cond.type_name().into(),
+        }),
+    }
+}
+
+/// The branch index a Branch/IfElse/Loop/Match takes given the decision it
+/// stored as its node value.
+pub(super) fn taken_branch(op: &ComputeNodeOp, decision: Option<&Value>) -> Option<u16> {
+    match op {
+        ComputeNodeOp::Core(ComputeOp::Branch | ComputeOp::IfElse) => match decision {
+            Some(Value::Bool(true)) => Some(0),  // then branch
+            Some(Value::Bool(false)) => Some(1), // else branch
+            _ => None,
+        },
+        ComputeNodeOp::Core(ComputeOp::Loop) => match decision {
+            Some(Value::Bool(true)) => Some(0),  // continue loop (body)
+            Some(Value::Bool(false)) => Some(1), // exit loop
+            _ => Some(1),                        // default: exit
+        },
+        ComputeNodeOp::Core(ComputeOp::Match) => match decision {
+            Some(Value::I32(v)) => Some(*v as u16),
+            Some(Value::I8(v)) => Some(*v as u16),
+            Some(Value::I16(v)) => Some(*v as u16),
+            Some(Value::I64(v)) => Some(*v as u16),
+            Some(Value::Enum { variant, .. }) => Some(*variant as u16),
+            _ => Some(0),
+        },
+        _ => None,
+    }
+}
+
+/// A `Phi`'s value, selected by the decisions stored by the branch nodes
+/// behind its incoming branch-indexed control edges.
+///
+/// Convention: the branch node stores its Bool decision as a node value. We
+/// map: true -> branch_index=0 -> data port 0, false -> branch_index=1 ->
+/// data port 1. The last decision wins; without one, the first input is taken.
+pub(super) fn phi_value<'v>(
+    inputs: &[(u16, Value)],
+    decisions: impl IntoIterator<Item = Option<&'v Value>>,
+) -> Value {
+    let mut selected_port: Option<u16> = None;
+    for decision in decisions {
+        match decision {
+            Some(Value::Bool(true)) => selected_port = Some(0),
+            Some(Value::Bool(false)) => selected_port = Some(1),
+            _ => {}
+        }
+    }
+    match selected_port {
+        Some(port) => inputs
+            .iter()
+            .find(|(p, _)| *p == port)
+            .map(|(_, v)| v.clone())
+            .unwrap_or(Value::Unit),
+        None => inputs
+            .first()
+            .map(|(_, v)| v.clone())
+            .unwrap_or(Value::Unit),
+    }
+}
+
+/// Checks a Precondition/Postcondition/Invariant's condition on port 0.
+/// Returns the contract kind and message if it is false; a missing condition
+/// passes.
+pub(super) fn failed_contract(
+    op: &ComputeNodeOp,
+    inputs: &[(u16, Value)],
+    node_id: NodeId,
+) -> Result<Option<(crate::contracts::ContractKind, String)>, RuntimeError> {
+    use crate::contracts::ContractKind;
+
+    let (kind, message) = match op {
+        ComputeNodeOp::Core(ComputeOp::Precondition { message }) => {
+            (ContractKind::Precondition, message)
+        }
+        ComputeNodeOp::Core(ComputeOp::Postcondition { message }) => {
+            (ContractKind::Postcondition, message)
+        }
+        ComputeNodeOp::Core(ComputeOp::Invariant { message, .. }) => {
+            (ContractKind::Invariant, message)
+        }
+        _ => return Ok(None),
+    };
+    match inputs.iter().find(|(p, _)| *p == 0).map(|(_, v)| v) {
+        Some(Value::Bool(false)) => Ok(Some((kind, message.clone()))),
+        Some(Value::Bool(true)) | None => Ok(None),
+        Some(other) => Err(RuntimeError::TypeMismatchAtRuntime {
+            node: node_id,
+            expected: "Bool".into(),
+            got: other.type_name().into(),
+        }),
+    }
+}
+
+/// Builds the in-flight state of a `ForRange`/`ForEach` loop, array
+/// combinator or `ForAll`/`Exists` quantifier from its inputs.
+pub(super) fn structured_loop(
+    op: &ComputeNodeOp,
+    inputs: &[(u16, Value)],
+    node_id: NodeId,
+) -> Result<StructuredLoop, RuntimeError> {
+    match op {
+        ComputeNodeOp::Core(ComputeOp::ForRange { body }) => {
+            // Ports 0..=2: start, end, step; port 3: optional accumulator
+            let bound = |port: u16| {
+                let value = input_at(inputs, port, node_id)?;
+                int_value(value).map(|n| (value.clone(), n)).ok_or_else(|| {
+                    RuntimeError::TypeMismatchAtRuntime {
+                        node: node_id,
+                        expected: "integer".into(),
+                        got: value.type_name().into(),
+                    }
+                })
+            };
+            let (start, _) = bound(0)?;
+            let (_, end) = bound(1)?;
+            let (_, step) = bound(2)?;
+            let acc = inputs.iter().find(|(p, _)| *p == 3).map(|(_, v)| v.clone());
+            Ok(StructuredLoop::new(
+                *body,
+                Vec::new(),
+                LoopItems::Range {
+                    next: start,
+                    end,
+                    step,
+                },
+                LoopKind::Accumulate(acc),
+            ))
+        }
+        ComputeNodeOp::Core(ComputeOp::ForEach { body }) => {
+            // Port 0: array; port 1: optional accumulator
+            let array = input_at(inputs, 0, node_id)?;
+            let Value::Array { elements, .. } = array else {
+                return Err(RuntimeError::TypeMismatchAtRuntime {
+                    node: node_id,
+                    expected: "Array".into(),
+                    got: array.type_name().into(),
+                });
+            };
+            let acc = inputs.iter().find(|(p, _)| *p == 1).map(|(_, v)| v.clone());
+            Ok(StructuredLoop::new(
+                *body,
+                Vec::new(),
+                LoopItems::Elements(elements.clone().into_iter()),
+                LoopKind::Accumulate(acc),
+            ))
+        }
+        ComputeNodeOp::Core(
+            quantifier @ (ComputeOp::ForAll { predicate } | ComputeOp::Exists { predicate }),
+        ) => {
+            // Port 0: array, or ports 0 and 1: integer range start..end
+            use crate::contracts::check::{quantifier_domain, QuantifierDomain};
+
+            let items = match quantifier_domain(inputs, node_id)? {
+                QuantifierDomain::Range { start, end } => LoopItems::Range {
+                    next: start,
+                    end,
+                    step: 1,
+                },
+                QuantifierDomain::Elements(elements) => LoopItems::Elements(elements.into_iter()),
+            };
+            Ok(StructuredLoop::new(
+                *predicate,
+                Vec::new(),
+                items,
+                LoopKind::Quantify {
+                    universal: matches!(quantifier, ComputeOp::ForAll { .. }),
+                    verdict: None,
+                },
+            ))
+        }
+        ComputeNodeOp::Structured(
+            combinator @ (StructuredOp::ArrayMap
+            | StructuredOp::ArrayFold
+            | StructuredOp::ArrayFilter),
+        ) => {
+            // Port 0: array; last port: closure or function reference;
+            // ArrayFold takes its initial accumulator on port 1
+            let array = input_at(inputs, 0, node_id)?;
+            let Value::Array {
+                ty: array_ty,
+                elements,
+            } = array
+            else {
+                return Err(RuntimeError::TypeMismatchAtRuntime {
+                    node: node_id,
+                    expected: "Array".into(),
+                    got: array.type_name().into(),
+                });
+            };
+            let callback_port = if matches!(combinator, StructuredOp::ArrayFold) {
+                2
+            } else {
+                1
+            };
+            let (body, captures) = match input_at(inputs, callback_port, node_id)? {
+                Value::FunctionRef(fid) => (*fid, Vec::new()),
+                Value::Closure {
+                    function, captures, ..
+                } => (*function, captures.clone()),
+                other => {
+                    return Err(RuntimeError::TypeMismatchAtRuntime {
+                        node: node_id,
+                        expected: "FunctionRef or Closure".into(),
+                        got: other.type_name().into(),
+                    })
+                }
+            };
+            let kind = match combinator {
+                StructuredOp::ArrayMap => LoopKind::Map(Vec::with_capacity(elements.len())),
+                StructuredOp::ArrayFold => LoopKind::Fold(input_at(inputs, 1, node_id)?.clone()),
+                _ => LoopKind::Filter {
+                    kept: Vec::new(),
+                    input: elements.clone(),
+                    array_ty: *array_ty,
+                },
+            };
+            Ok(StructuredLoop::new(
+                body,
+                captures,
+                LoopItems::Elements(elements.clone().into_iter()),
+                kind,
+            ))
+        }
+        _ => Err(RuntimeError::InternalError {
+            message: format!("node {node_id} is not a structured loop"),
+        }),
+    }
+}
+
+/// Reads an integer value as `i64`.
+pub(crate) fn int_value(v: &Value) -> Option<i64> {
+    match v {
+        Value::I8(n) => Some(*n as i64),
+        Value::I16(n) => Some(*n as i64),
+        Value::I32(n) => Some(*n as i64),
+        Value::I64(n) => Some(*n),
+        _ => None,
+    }
+}
+
+/// Builds an integer value of the same type as `template`, wrapping `n` to
+/// that width.
+pub(crate) fn with_int_value(template: &Value, n: i64) -> Value {
+    match template {
+        Value::I8(_) => Value::I8(n as i8),
+        Value::I16(_) => Value::I16(n as i16),
+        Value::I32(_) => Value::I32(n as i32),
+        _ => Value::I64(n),
+    }
+}
+
 /// Helper to convert a Value to usize (for index/pointer arithmetic).
 fn value_to_usize(v: &Value, node_id: NodeId) -> Result<usize, RuntimeError> {
     match v {
@@ -1580,6 +2622,7 @@ mod tests {
         let config = InterpreterConfig {
             trace_enabled: true,
             max_recursion_depth: 256,
+            ..Default::default()
         };
         let mut interp = Interpreter::new(&graph, config);
         interp.start(func_id, vec![]);
@@ -1595,6 +2638,7 @@ mod tests {
         let config = InterpreterConfig {
             trace_enabled: false,
             max_recursion_depth: 256,
+            ..Default::default()
         };
         let mut interp = Interpreter::new(&graph, config);
         interp.start(func_id, vec![]);
@@ -1606,6 +2650,59 @@ mod tests {
         );
     }
 
+    /// Helper: build a function that returns the result of a single zero-input op.
+    fn environment_op_graph(op: ComputeOp) -> (ProgramGraph, FunctionId) {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function("env".into(), root, vec![], TypeId::I64, Visibility::Public)
+            .unwrap();
+        let node = graph.add_core_op(op, func_id).unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+        graph.add_data_edge(node, ret, 0, 0, TypeId::I64).unwrap();
+        (graph, func_id)
+    }
+
+    fn run_with_config(
+        graph: &ProgramGraph,
+        func_id: FunctionId,
+        config: InterpreterConfig,
+    ) -> Value {
+        let mut interp = Interpreter::new(graph, config);
+        interp.start(func_id, vec![]);
+        interp.run();
+        match interp.state() {
+            ExecutionState::Completed { result } => result.clone(),
+            other => panic!("Expected Completed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn random_is_reproducible_per_seed() {
+        let (graph, func_id) = environment_op_graph(ComputeOp::Random);
+        let seeded = |random_seed| InterpreterConfig {
+            random_seed,
+            ..Default::default()
+        };
+
+        let first = run_with_config(&graph, func_id, seeded(42));
+        assert_eq!(first, run_with_config(&graph, func_id, seeded(42)));
+        assert_ne!(first, run_with_config(&graph, func_id, seeded(43)));
+    }
+
+    #[test]
+    fn now_reads_virtual_clock() {
+        let (graph, func_id) = environment_op_graph(ComputeOp::Now);
+        let config = InterpreterConfig {
+            clock: VirtualClock {
+                start_ns: 1_000,
+                step_ns: 10,
+            },
+            ..Default::default()
+        };
+        assert_eq!(run_with_config(&graph, func_id, config), Value::I64(1_000));
+    }
+
     #[test]
     fn value_from_const_all_variants() {
         use lmlang_core::types::ConstValue;
@@ -1764,4 +2861,424 @@ mod tests {
             ),
         }
     }
+
+    /// Helper: `count(start, end, step) -> I64` counting `ForRange` iterations
+    /// with the body `tick(i, acc) -> acc + 1`.
+    fn for_range_count_graph() -> (ProgramGraph, FunctionId) {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+
+        let tick = graph
+            .add_function(
+                "tick".into(),
+                root,
+                vec![("i".into(), TypeId::I64), ("acc".into(), TypeId::I64)],
+                TypeId::I64,
+                Visibility::Public,
+            )
+            .unwrap();
+        let acc = graph
+            .add_core_op(ComputeOp::Parameter { index: 1 }, tick)
+            .unwrap();
+        let one = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: lmlang_core::types::ConstValue::I64(1),
+                },
+                tick,
+            )
+            .unwrap();
+        let add = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Add }, tick)
+            .unwrap();
+        let tick_ret = graph.add_core_op(ComputeOp::Return, tick).unwrap();
+        graph.add_data_edge(acc, add, 0, 0, TypeId::I64).unwrap();
+        graph.add_data_edge(one, add, 0, 1, TypeId::I64).unwrap();
+        graph
+            .add_data_edge(add, tick_ret, 0, 0, TypeId::I64)
+            .unwrap();
+
+        let count = graph
+            .add_function(
+                "count".into(),
+                root,
+                vec![
+                    ("start".into(), TypeId::I64),
+                    ("end".into(), TypeId::I64),
+                    ("step".into(), TypeId::I64),
+                ],
+                TypeId::I64,
+                Visibility::Public,
+            )
+            .unwrap();
+        let for_range = graph
+            .add_core_op(ComputeOp::ForRange { body: tick }, count)
+            .unwrap();
+        for index in 0..3 {
+            let param = graph
+                .add_core_op(ComputeOp::Parameter { index }, count)
+                .unwrap();
+            graph
+                .add_data_edge(param, for_range, 0, index as u16, TypeId::I64)
+                .unwrap();
+        }
+        let zero = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: lmlang_core::types::ConstValue::I64(0),
+                },
+                count,
+            )
+            .unwrap();
+        graph
+            .add_data_edge(zero, for_range, 0, 3, TypeId::I64)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, count).unwrap();
+        graph
+            .add_data_edge(for_range, ret, 0, 0, TypeId::I64)
+            .unwrap();
+
+        (graph, count)
+    }
+
+    #[test]
+    fn for_range_iterates_in_step_direction() {
+        let (graph, count) = for_range_count_graph();
+        let run = |start: i64, end: i64, step: i64| {
+            let mut interp = Interpreter::new(&graph, InterpreterConfig::default());
+            interp.start(
+                count,
+                vec![Value::I64(start), Value::I64(end), Value::I64(step)],
+            );
+            interp.run();
+            match interp.state() {
+                ExecutionState::Completed { result } => result.clone(),
+                other => panic!("Expected Completed, got {:?}", other),
+            }
+        };
+
+        assert_eq!(run(0, 10, 3), Value::I64(4));
+        assert_eq!(run(5, 0, -2), Value::I64(3));
+        assert_eq!(run(3, 3, 1), Value::I64(0));
+        assert_eq!(run(0, 10, 0), Value::I64(0));
+        // The index stops at the type's bound instead of wrapping around.
+        assert_eq!(run(i64::MAX - 5, i64::MAX, 4), Value::I64(2));
+        assert_eq!(run(i64::MIN + 5, i64::MIN, -4), Value::I64(2));
+    }
+
+    #[test]
+    fn for_range_iterations_do_not_grow_call_stack() {
+        let (graph, count) = for_range_count_graph();
+        let config = InterpreterConfig {
+            max_recursion_depth: 2,
+            ..Default::default()
+        };
+        let mut interp = Interpreter::new(&graph, config);
+        interp.start(count, vec![Value::I64(0), Value::I64(500), Value::I64(1)]);
+        interp.run();
+        match interp.state() {
+            ExecutionState::Completed { result } => assert_eq!(*result, Value::I64(500)),
+            other => panic!("Expected Completed, got {:?}", other),
+        }
+    }
+
+    /// Helper: `pipeline(arr: [I64; 4], k: I64) -> I64` computing
+    /// `fold(+, 0, filter(even, map(|x| x + k, arr)).items)` with closure
+    /// callbacks.
+    fn array_combinator_graph() -> (ProgramGraph, FunctionId) {
+        use indexmap::IndexMap;
+        use lmlang_core::function::{Capture, CaptureMode};
+        use lmlang_core::ops::CmpOp;
+        use lmlang_core::types::{ConstValue, LmType, StructDef};
+
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let array = graph.types.register(LmType::Array {
+            element: TypeId::I64,
+            length: 4,
+        });
+        let unary = graph.types.register(LmType::Function {
+            params: vec![TypeId::I64],
+            return_type: TypeId::I64,
+        });
+        let predicate = graph.types.register(LmType::Function {
+            params: vec![TypeId::I64],
+            return_type: TypeId::BOOL,
+        });
+        let binary = graph.types.register(LmType::Function {
+            params: vec![TypeId::I64, TypeId::I64],
+            return_type: TypeId::I64,
+        });
+        let filtered = graph.types.register(LmType::Struct(StructDef {
+            name: "Filtered".into(),
+            type_id: TypeId(0), // placeholder
+            fields: IndexMap::from([("count".into(), TypeId::I32), ("items".into(), array)]),
+            module: root,
+            visibility: Visibility::Public,
+        }));
+
+        let pipeline = graph
+            .add_function(
+                "pipeline".into(),
+                root,
+                vec![("arr".into(), array), ("k".into(), TypeId::I64)],
+                TypeId::I64,
+                Visibility::Public,
+            )
+            .unwrap();
+
+        // add_k(x) = x + k, capturing k
+        let add_k = graph
+            .add_closure(
+                "add_k".into(),
+                root,
+                pipeline,
+                vec![("x".into(), TypeId::I64)],
+                TypeId::I64,
+                vec![Capture {
+                    name: "k".into(),
+                    captured_type: TypeId::I64,
+                    mode: CaptureMode::ByValue,
+                }],
+            )
+            .unwrap();
+        let x = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, add_k)
+            .unwrap();
+        let k = graph
+            .add_core_op(ComputeOp::CaptureAccess { index: 0 }, add_k)
+            .unwrap();
+        let sum = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Add }, add_k)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, add_k).unwrap();
+        graph.add_data_edge(x, sum, 0, 0, TypeId::I64).unwrap();
+        graph.add_data_edge(k, sum, 0, 1, TypeId::I64).unwrap();
+        graph.add_data_edge(sum, ret, 0, 0, TypeId::I64).unwrap();
+
+        // is_even(x) = x % 2 == 0
+        let is_even = graph
+            .add_closure(
+                "is_even".into(),
+                root,
+                pipeline,
+                vec![("x".into(), TypeId::I64)],
+                TypeId::BOOL,
+                vec![],
+            )
+            .unwrap();
+        let x = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, is_even)
+            .unwrap();
+        let two = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: ConstValue::I64(2),
+                },
+                is_even,
+            )
+            .unwrap();
+        let zero = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: ConstValue::I64(0),
+                },
+                is_even,
+            )
+            .unwrap();
+        let rem = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Rem }, is_even)
+            .unwrap();
+        let eq = graph
+            .add_core_op(ComputeOp::Compare { op: CmpOp::Eq }, is_even)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, is_even).unwrap();
+        graph.add_data_edge(x, rem, 0, 0, TypeId::I64).unwrap();
+        graph.add_data_edge(two, rem, 0, 1, TypeId::I64).unwrap();
+        graph.add_data_edge(rem, eq, 0, 0, TypeId::I64).unwrap();
+        graph.add_data_edge(zero, eq, 0, 1, TypeId::I64).unwrap();
+        graph.add_data_edge(eq, ret, 0, 0, TypeId::BOOL).unwrap();
+
+        // add(a, b) = a + b
+        let add = graph
+            .add_closure(
+                "add".into(),
+                root,
+                pipeline,
+                vec![("a".into(), TypeId::I64), ("b".into(), TypeId::I64)],
+                TypeId::I64,
+                vec![],
+            )
+            .unwrap();
+        let a = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, add)
+            .unwrap();
+        let b = graph
+            .add_core_op(ComputeOp::Parameter { index: 1 }, add)
+            .unwrap();
+        let sum = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Add }, add)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, add).unwrap();
+        graph.add_data_edge(a, sum, 0, 0, TypeId::I64).unwrap();
+        graph.add_data_edge(b, sum, 0, 1, TypeId::I64).unwrap();
+        graph.add_data_edge(sum, ret, 0, 0, TypeId::I64).unwrap();
+
+        let arr = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, pipeline)
+            .unwrap();
+        let k = graph
+            .add_core_op(ComputeOp::Parameter { index: 1 }, pipeline)
+            .unwrap();
+        let add_k_closure = graph
+            .add_core_op(ComputeOp::MakeClosure { function: add_k }, pipeline)
+            .unwrap();
+        let is_even_closure = graph
+            .add_core_op(ComputeOp::MakeClosure { function: is_even }, pipeline)
+            .unwrap();
+        let add_closure = graph
+            .add_core_op(ComputeOp::MakeClosure { function: add }, pipeline)
+            .unwrap();
+        let init = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: ConstValue::I64(0),
+                },
+                pipeline,
+            )
+            .unwrap();
+        let map = graph
+            .add_structured_op(StructuredOp::ArrayMap, pipeline)
+            .unwrap();
+        let filter = graph
+            .add_structured_op(StructuredOp::ArrayFilter, pipeline)
+            .unwrap();
+        let items = graph
+            .add_structured_op(StructuredOp::StructGet { field_index: 1 }, pipeline)
+            .unwrap();
+        let fold = graph
+            .add_structured_op(StructuredOp::ArrayFold, pipeline)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, pipeline).unwrap();
+        graph
+            .add_data_edge(k, add_k_closure, 0, 0, TypeId::I64)
+            .unwrap();
+        graph.add_data_edge(arr, map, 0, 0, array).unwrap();
+        graph
+            .add_data_edge(add_k_closure, map, 0, 1, unary)
+            .unwrap();
+        graph.add_data_edge(map, filter, 0, 0, array).unwrap();
+        graph
+            .add_data_edge(is_even_closure, filter, 0, 1, predicate)
+            .unwrap();
+        graph.add_data_edge(filter, items, 0, 0, filtered).unwrap();
+        graph.add_data_edge(items, fold, 0, 0, array).unwrap();
+        graph.add_data_edge(init, fold, 0, 1, TypeId::I64).unwrap();
+        graph
+            .add_data_edge(add_closure, fold, 0, 2, binary)
+            .unwrap();
+        graph.add_data_edge(fold, ret, 0, 0, TypeId::I64).unwrap();
+
+        (graph, pipeline)
+    }
+
+    #[test]
+    fn array_combinators_apply_closures() {
+        let (graph, pipeline) = array_combinator_graph();
+        let errors = crate::typecheck::validate_graph(&graph);
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let run = |k: i64| {
+            let mut interp = Interpreter::new(&graph, InterpreterConfig::default());
+            let arr = Value::untyped_array((1..=4).map(Value::I64).collect());
+            interp.start(pipeline, vec![arr, Value::I64(k)]);
+            interp.run();
+            match interp.state() {
+                ExecutionState::Completed { result } => result.clone(),
+                other => panic!("Expected Completed, got {:?}", other),
+            }
+        };
+
+        // [4, 5, 6, 7] -> [4, 6 | 6, 7] -> 23 (slots past the count keep
+        // the input's elements)
+        assert_eq!(run(3), Value::I64(23));
+        // [11, 12, 13, 14] -> [12, 14 | 13, 14] -> 53
+        assert_eq!(run(10), Value::I64(53));
+    }
+
+    #[test]
+    fn array_combinator_results_carry_derived_types() {
+        use lmlang_core::types::LmType;
+
+        let (graph, pipeline) = array_combinator_graph();
+        let registered = |shape: fn(&LmType) -> bool| {
+            graph
+                .types
+                .iter()
+                .find(|(_, ty)| shape(ty))
+                .map(|(id, _)| id)
+                .unwrap()
+        };
+        let array = registered(|ty| matches!(ty, LmType::Array { .. }));
+        let filtered = registered(|ty| matches!(ty, LmType::Struct(_)));
+
+        let config = InterpreterConfig {
+            trace_enabled: true,
+            ..Default::default()
+        };
+        let mut interp = Interpreter::new(&graph, config);
+        let arr = Value::untyped_array((1..=4).map(Value::I64).collect());
+        interp.start(pipeline, vec![arr, Value::I64(3)]);
+        interp.run();
+        // The array on port 0 of the consuming node: the map's output feeds
+        // the filter, the filter's output feeds `StructGet`
+        let input = |op: fn(&StructuredOp) -> bool| {
+            interp
+                .trace()
+                .unwrap()
+                .iter()
+                .find(|entry| {
+                    matches!(&graph.get_compute_node(entry.node_id).unwrap().op,
+                        ComputeNodeOp::Structured(s) if op(s))
+                })
+                .map(|entry| entry.inputs[0].1.clone())
+                .unwrap()
+        };
+
+        assert_eq!(
+            input(|op| matches!(op, StructuredOp::ArrayFilter)).type_id(),
+            array
+        );
+        match input(|op| matches!(op, StructuredOp::StructGet { .. })) {
+            Value::Struct { ty, fields } => {
+                assert_eq!(ty, filtered);
+                assert_eq!(fields[0], Value::I32(2));
+                assert_eq!(fields[1].type_id(), array);
+            }
+            other => panic!("Expected Struct, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn structured_loops_agree_with_the_bytecode_vm() {
+        use crate::interpreter::vm::assert_engines_agree;
+
+        let config = InterpreterConfig::default();
+        let (graph, count) = for_range_count_graph();
+        for (start, end, step) in [(0, 10, 3), (5, 0, -2), (3, 3, 1)] {
+            let args = vec![Value::I64(start), Value::I64(end), Value::I64(step)];
+            assert_engines_agree(&graph, count, args, &config);
+        }
+
+        let (graph, pipeline) = array_combinator_graph();
+        let arr = Value::untyped_array((1..=4).map(Value::I64).collect());
+        assert_engines_agree(&graph, pipeline, vec![arr, Value::I64(3)], &config);
+        // Not an array: both engines fail at the first combinator
+        assert_engines_agree(
+            &graph,
+            pipeline,
+            vec![Value::I64(1), Value::I64(3)],
+            &config,
+        );
+    }
 }
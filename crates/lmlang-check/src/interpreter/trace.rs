@@ -3,8 +3,18 @@
 //! When tracing is enabled via [`InterpreterConfig::trace_enabled`], the
 //! interpreter records a [`TraceEntry`] for every node evaluation, capturing
 //! the node ID, operation description, input values, and output value.
+//! [`lineage`] walks a trace backward to the entries a value was derived from.
+//!
+//! [`InterpreterConfig::trace_enabled`]: super::InterpreterConfig::trace_enabled
+
+use std::collections::{BTreeSet, HashSet};
 
+use lmlang_core::edge::FlowEdge;
+use lmlang_core::graph::ProgramGraph;
 use lmlang_core::id::NodeId;
+use lmlang_core::ops::{ComputeNodeOp, ComputeOp};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
 use serde::{Deserialize, Serialize};
 
 use super::value::Value;
@@ -14,6 +24,9 @@ use super::value::Value;
 pub struct TraceEntry {
     /// The node that was evaluated.
     pub node_id: NodeId,
+    /// Call stack depth of the evaluating frame (1 = the entry function).
+    #[serde(default)]
+    pub depth: usize,
     /// Human-readable description of the operation.
     pub op_description: String,
     /// Input values gathered for this node, keyed by port number.
@@ -21,3 +34,183 @@ pub struct TraceEntry {
     /// Output value produced (None for ops like Store, Branch that produce no value).
     pub output: Option<Value>,
 }
+
+/// Indices of the trace entries that contributed to the value of
+/// `trace[entry]`, including `entry` itself, in trace order.
+///
+/// Follows data edges backward within a frame (matching producers by node
+/// and depth), from a `Parameter` to the argument its caller passed, and from
+/// a call's result into the callee's final `Return`.
+pub fn lineage(graph: &ProgramGraph, trace: &[TraceEntry], entry: usize) -> Vec<usize> {
+    let mut visited: HashSet<(usize, Option<u16>)> = HashSet::new();
+    let mut contributors = BTreeSet::new();
+    // (entry index, only follow this input port)
+    let mut stack: Vec<(usize, Option<u16>)> = vec![(entry, None)];
+
+    while let Some((index, port)) = stack.pop() {
+        let Some(current) = trace.get(index) else {
+            continue;
+        };
+        if !visited.insert((index, port)) {
+            continue;
+        }
+        contributors.insert(index);
+        let op_of = |i: usize| graph.get_compute_node(trace[i].node_id).map(|n| &n.op);
+
+        if let Some(ComputeNodeOp::Core(ComputeOp::Parameter { index: param })) = op_of(index) {
+            // The argument came from the entry that pushed this frame.
+            let caller = (0..index).rev().find(|&i| trace[i].depth < current.depth);
+            if let Some(caller) = caller {
+                let arg = match op_of(caller) {
+                    Some(ComputeNodeOp::Core(ComputeOp::Call { .. })) => Some(*param as usize),
+                    Some(ComputeNodeOp::Core(ComputeOp::IndirectCall)) => Some(*param as usize + 1),
+                    _ => None,
+                };
+                let arg_port = arg.and_then(|arg| trace[caller].inputs.get(arg).map(|(p, _)| *p));
+                stack.push((caller, arg_port));
+            }
+            continue;
+        }
+
+        for edge in graph
+            .compute()
+            .edges_directed(current.node_id.into(), Direction::Incoming)
+        {
+            let FlowEdge::Data { target_port, .. } = edge.weight() else {
+                continue;
+            };
+            if port.is_some_and(|port| port != *target_port) {
+                continue;
+            }
+            let source = NodeId::from(edge.source());
+            let Some(producer) = (0..index)
+                .rev()
+                .find(|&i| trace[i].node_id == source && trace[i].depth == current.depth)
+            else {
+                continue;
+            };
+
+            let entered_callee = trace[producer].output.is_none()
+                && trace
+                    .get(producer + 1)
+                    .is_some_and(|next| next.depth > current.depth);
+            if entered_callee {
+                // The value was returned into the producer by its callee.
+                contributors.insert(producer);
+                let callee_return = (producer + 1..index)
+                    .take_while(|&i| trace[i].depth > current.depth)
+                    .filter(|&i| trace[i].depth == current.depth + 1)
+                    .last();
+                stack.extend(callee_return.map(|i| (i, None)));
+            } else {
+                stack.push((producer, None));
+            }
+        }
+    }
+
+    contributors.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::{Interpreter, InterpreterConfig};
+    use lmlang_core::ops::ArithOp;
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::{ConstValue, Visibility};
+
+    #[test]
+    fn lineage_follows_values_through_calls() {
+        // inc(x) = x + 1; main(x, y) { noise = y + y; return inc(x) }
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let inc = graph
+            .add_function(
+                "inc".into(),
+                root,
+                vec![("x".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let inc_param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, inc)
+            .unwrap();
+        let one = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: ConstValue::I32(1),
+                },
+                inc,
+            )
+            .unwrap();
+        let inc_add = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Add }, inc)
+            .unwrap();
+        let inc_ret = graph.add_core_op(ComputeOp::Return, inc).unwrap();
+        graph
+            .add_data_edge(inc_param, inc_add, 0, 0, TypeId::I32)
+            .unwrap();
+        graph
+            .add_data_edge(one, inc_add, 0, 1, TypeId::I32)
+            .unwrap();
+        graph
+            .add_data_edge(inc_add, inc_ret, 0, 0, TypeId::I32)
+            .unwrap();
+
+        let main = graph
+            .add_function(
+                "main".into(),
+                root,
+                vec![("x".into(), TypeId::I32), ("y".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let x = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, main)
+            .unwrap();
+        let y = graph
+            .add_core_op(ComputeOp::Parameter { index: 1 }, main)
+            .unwrap();
+        let noise = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Add }, main)
+            .unwrap();
+        let call = graph
+            .add_core_op(ComputeOp::Call { target: inc }, main)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, main).unwrap();
+        graph.add_data_edge(y, noise, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(y, noise, 0, 1, TypeId::I32).unwrap();
+        graph.add_data_edge(x, call, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(call, ret, 0, 0, TypeId::I32).unwrap();
+        graph.add_control_edge(noise, ret, None).unwrap();
+
+        let config = InterpreterConfig {
+            trace_enabled: true,
+            ..Default::default()
+        };
+        let mut interp = Interpreter::new(&graph, config);
+        interp.start(main, vec![Value::I32(4), Value::I32(7)]);
+        interp.run();
+        let trace = interp.trace().unwrap();
+        let last = trace.iter().rposition(|e| e.node_id == ret).unwrap();
+
+        let nodes: HashSet<NodeId> = lineage(&graph, trace, last)
+            .into_iter()
+            .map(|i| trace[i].node_id)
+            .collect();
+        let expected: HashSet<NodeId> = [ret, call, x, inc_ret, inc_add, inc_param, one]
+            .into_iter()
+            .collect();
+        assert_eq!(nodes, expected);
+
+        // Within the callee, the Add derives from the argument passed in.
+        let add = trace.iter().position(|e| e.node_id == inc_add).unwrap();
+        let nodes: HashSet<NodeId> = lineage(&graph, trace, add)
+            .into_iter()
+            .map(|i| trace[i].node_id)
+            .collect();
+        assert!(nodes.contains(&x) && !nodes.contains(&y) && !nodes.contains(&noise));
+    }
+}
@@ -3,10 +3,13 @@
 //! [`Value`] is the dynamic runtime counterpart to lmlang-core's static type
 //! system. Every node evaluation produces a `Value` that flows through data
 //! edges to downstream nodes.
+//!
+//! Compound values carry the [`TypeId`] they were created with, so they can
+//! be rendered as typed, named JSON (see [`super::marshal`]).
 
 use lmlang_core::id::FunctionId;
-use lmlang_core::type_id::TypeId;
-use lmlang_core::types::ConstValue;
+use lmlang_core::type_id::{TypeId, TypeRegistry};
+use lmlang_core::types::{ConstValue, LmType};
 use serde::{Deserialize, Serialize};
 
 /// A runtime value produced or consumed by interpreter node evaluation.
@@ -19,6 +22,9 @@ use serde::{Deserialize, Serialize};
 /// Note: `F32` stores an actual `f32` at runtime, unlike `ConstValue::F32`
 /// which stores f64 for derive safety. The conversion happens in
 /// [`Value::from_const`].
+///
+/// The `ty` of a compound value is its type in the program's
+/// `TypeRegistry`, or `TypeId::UNIT` if it was built without one.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Value {
     Bool(bool),
@@ -29,17 +35,28 @@ pub enum Value {
     F32(f32),
     F64(f64),
     Unit,
-    Array(Vec<Value>),
-    /// Struct fields in declaration order.
-    Struct(Vec<Value>),
+    Array {
+        ty: TypeId,
+        elements: Vec<Value>,
+    },
+    Struct {
+        ty: TypeId,
+        /// Fields in declaration order.
+        fields: Vec<Value>,
+    },
     Enum {
+        ty: TypeId,
         variant: u32,
         payload: Box<Value>,
     },
-    /// Index into interpreter memory.
-    Pointer(usize),
+    Pointer {
+        ty: TypeId,
+        /// Index into interpreter memory.
+        address: usize,
+    },
     FunctionRef(FunctionId),
     Closure {
+        ty: TypeId,
         function: FunctionId,
         captures: Vec<Value>,
     },
@@ -63,11 +80,19 @@ impl Value {
         }
     }
 
-    /// Returns the [`TypeId`] of this runtime value based on its variant.
+    /// An array of `elements` without a registry type.
+    pub fn untyped_array(elements: Vec<Value>) -> Value {
+        Value::Array {
+            ty: TypeId::UNIT,
+            elements,
+        }
+    }
+
+    /// Returns the [`TypeId`] of this runtime value.
     ///
-    /// For scalar and unit types, returns the well-known built-in TypeId.
-    /// For compound types, returns a placeholder (the actual TypeId tracking
-    /// is handled by the type checker at the graph level, not at runtime).
+    /// Scalars and unit return the well-known built-in TypeId and compounds
+    /// the type they were tagged with. Function references return
+    /// `TypeId::UNIT`, since they are not tied to a registered type.
     pub fn type_id(&self) -> TypeId {
         match self {
             Value::Bool(_) => TypeId::BOOL,
@@ -77,16 +102,96 @@ impl Value {
             Value::I64(_) => TypeId::I64,
             Value::F32(_) => TypeId::F32,
             Value::F64(_) => TypeId::F64,
-            Value::Unit => TypeId::UNIT,
-            // Compound types don't have a static TypeId available at runtime
-            // without the registry. Return UNIT as a placeholder -- the type
-            // checker ensures correctness at the graph level.
-            Value::Array(_) => TypeId::UNIT,
-            Value::Struct(_) => TypeId::UNIT,
-            Value::Enum { .. } => TypeId::UNIT,
-            Value::Pointer(_) => TypeId::UNIT,
-            Value::FunctionRef(_) => TypeId::UNIT,
-            Value::Closure { .. } => TypeId::UNIT,
+            Value::Unit | Value::FunctionRef(_) => TypeId::UNIT,
+            Value::Array { ty, .. }
+            | Value::Struct { ty, .. }
+            | Value::Enum { ty, .. }
+            | Value::Pointer { ty, .. }
+            | Value::Closure { ty, .. } => *ty,
+        }
+    }
+
+    /// Tags an untyped compound value with `ty`, then tags its untyped
+    /// fields, elements and enum payload from the definition of its type in
+    /// `registry`. Values that are already typed keep their tag but still
+    /// have their contents tagged; scalars are returned unchanged.
+    pub fn with_type(mut self, ty: TypeId, registry: &TypeRegistry) -> Value {
+        self.tag(ty, registry);
+        self
+    }
+
+    fn tag(&mut self, ty: TypeId, registry: &TypeRegistry) {
+        let tag = match self {
+            Value::Array { ty: tag, .. }
+            | Value::Struct { ty: tag, .. }
+            | Value::Enum { ty: tag, .. }
+            | Value::Pointer { ty: tag, .. }
+            | Value::Closure { ty: tag, .. } => tag,
+            _ => return,
+        };
+        if *tag == TypeId::UNIT {
+            *tag = ty;
+        }
+        let ty = *tag;
+        match (self, registry.get(ty)) {
+            (Value::Array { elements, .. }, Some(LmType::Array { element, .. })) => {
+                for value in elements {
+                    value.tag(*element, registry);
+                }
+            }
+            (Value::Struct { fields, .. }, Some(LmType::Struct(def))) => {
+                for (value, field_ty) in fields.iter_mut().zip(def.fields.values()) {
+                    value.tag(*field_ty, registry);
+                }
+            }
+            (
+                Value::Enum {
+                    variant, payload, ..
+                },
+                Some(LmType::Enum(def)),
+            ) => {
+                let payload_ty = def
+                    .variants
+                    .values()
+                    .find(|v| v.index == *variant)
+                    .and_then(|v| v.payload);
+                if let Some(payload_ty) = payload_ty {
+                    payload.tag(payload_ty, registry);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the zero value with the same shape as `self`.
+    ///
+    /// Scalars become `0`/`false`, compounds are zeroed element-wise, and
+    /// enums reset to variant 0. Pointers, function references and closures
+    /// are returned unchanged since they have no meaningful zero.
+    pub fn zeroed(&self) -> Value {
+        match self {
+            Value::Bool(_) => Value::Bool(false),
+            Value::I8(_) => Value::I8(0),
+            Value::I16(_) => Value::I16(0),
+            Value::I32(_) => Value::I32(0),
+            Value::I64(_) => Value::I64(0),
+            Value::F32(_) => Value::F32(0.0),
+            Value::F64(_) => Value::F64(0.0),
+            Value::Unit => Value::Unit,
+            Value::Array { ty, elements } => Value::Array {
+                ty: *ty,
+                elements: elements.iter().map(Value::zeroed).collect(),
+            },
+            Value::Struct { ty, fields } => Value::Struct {
+                ty: *ty,
+                fields: fields.iter().map(Value::zeroed).collect(),
+            },
+            Value::Enum { ty, payload, .. } => Value::Enum {
+                ty: *ty,
+                variant: 0,
+                payload: Box::new(payload.zeroed()),
+            },
+            Value::Pointer { .. } | Value::FunctionRef(_) | Value::Closure { .. } => self.clone(),
         }
     }
 
@@ -101,10 +206,10 @@ impl Value {
             Value::F32(_) => "F32",
             Value::F64(_) => "F64",
             Value::Unit => "Unit",
-            Value::Array(_) => "Array",
-            Value::Struct(_) => "Struct",
+            Value::Array { .. } => "Array",
+            Value::Struct { .. } => "Struct",
             Value::Enum { .. } => "Enum",
-            Value::Pointer(_) => "Pointer",
+            Value::Pointer { .. } => "Pointer",
             Value::FunctionRef(_) => "FunctionRef",
             Value::Closure { .. } => "Closure",
         }
@@ -0,0 +1,874 @@
+//! Fast execution engine for [`bytecode`](super::bytecode).
+//!
+//! The VM runs a [`BytecodeProgram`] to completion and produces the same
+//! [`ExecutionState`], [`TraceEntry`]s, coverage, I/O log and memory as the
+//! work-list [`Interpreter`], which remains the reference engine and the only
+//! one supporting pausing, stepping and reverse execution. Node values live
+//! in per-frame registers indexed by slot; straight-line functions follow
+//! their precomputed order and the rest keep a slot-indexed work list.
+//!
+//! [`Executor`] selects an [`Engine`] for a graph and falls back to the
+//! reference engine for graphs that cannot be lowered.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::ops::{ComputeNodeOp, ComputeOp, StructuredOp};
+use lmlang_core::type_id::{TypeId, TypeRegistry};
+
+use super::bytecode::{
+    BytecodeCache, BytecodeProgram, FunctionCode, Instr, Scheduler, Slot, Successor,
+};
+use super::coverage::Coverage;
+use super::error::RuntimeError;
+use super::eval::eval_op;
+use super::state::{
+    alloc_cell, branch_condition, check_call_invariants, direct_call, element_ptr, failed_contract,
+    indirect_call, input_at, load_cell, phi_value, return_value, store_cell, structured_loop,
+    tag_arguments, taken_branch, EvalResult, ExecutionState, Interpreter, InterpreterConfig,
+    StructuredLoop,
+};
+use super::trace::TraceEntry;
+use super::value::Value;
+
+/// Which engine executes a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Engine {
+    /// The work-list [`Interpreter`].
+    Reference,
+    /// The bytecode VM, falling back to the reference engine for graphs
+    /// that cannot be lowered.
+    #[default]
+    Bytecode,
+}
+
+/// Everything a completed run produced, whichever engine ran it.
+#[derive(Debug, Clone)]
+pub struct RunOutput {
+    /// Final state: `Completed`, `Error` or `ContractViolation`.
+    pub state: ExecutionState,
+    /// Execution trace, if [`InterpreterConfig::trace_enabled`].
+    pub trace: Option<Vec<TraceEntry>>,
+    /// Coverage counts, if [`InterpreterConfig::coverage_enabled`].
+    pub coverage: Option<Coverage>,
+    /// Values printed via Print ops.
+    pub io_log: Vec<Value>,
+    /// Final memory contents.
+    pub memory: Vec<Value>,
+    /// Steps taken.
+    pub steps: u64,
+}
+
+impl RunOutput {
+    /// The output of a finished reference run.
+    pub fn from_interpreter(interp: &Interpreter<'_>) -> Self {
+        RunOutput {
+            state: interp.state().clone(),
+            trace: interp.trace().map(<[TraceEntry]>::to_vec),
+            coverage: interp.coverage().cloned(),
+            io_log: interp.io_log().to_vec(),
+            memory: interp.memory().to_vec(),
+            steps: interp.steps(),
+        }
+    }
+}
+
+/// Runs functions of one graph on an [`Engine`], lowering the graph once.
+pub struct Executor<'g> {
+    graph: &'g ProgramGraph,
+    program: Option<BytecodeProgram>,
+}
+
+impl<'g> Executor<'g> {
+    /// Prepares `engine` for `graph`, taking lowered functions from `cache`.
+    pub fn new(graph: &'g ProgramGraph, engine: Engine, cache: &BytecodeCache) -> Self {
+        let program = match engine {
+            Engine::Reference => None,
+            Engine::Bytecode => cache.lower(graph).ok(),
+        };
+        Executor { graph, program }
+    }
+
+    /// The engine runs actually use.
+    pub fn engine(&self) -> Engine {
+        match self.program {
+            Some(_) => Engine::Bytecode,
+            None => Engine::Reference,
+        }
+    }
+
+    /// Runs `function_id` with `args` to completion.
+    pub fn run(
+        &self,
+        config: &InterpreterConfig,
+        function_id: FunctionId,
+        args: Vec<Value>,
+    ) -> RunOutput {
+        match &self.program {
+            Some(program) => run(self.graph, program, config, function_id, args),
+            None => {
+                let mut interp = Interpreter::new(self.graph, config.clone());
+                interp.start(function_id, args);
+                interp.run();
+                RunOutput::from_interpreter(&interp)
+            }
+        }
+    }
+}
+
+/// Runs `function_id` of a lowered `program` to completion on the VM.
+///
+/// `config.checkpoint_interval` is ignored: the VM keeps no history.
+pub fn run(
+    graph: &ProgramGraph,
+    program: &BytecodeProgram,
+    config: &InterpreterConfig,
+    function_id: FunctionId,
+    args: Vec<Value>,
+) -> RunOutput {
+    let mut vm = Vm {
+        graph,
+        program,
+        config,
+        frames: Vec::new(),
+        memory: Vec::new(),
+        trace: config.trace_enabled.then(Vec::new),
+        coverage: config.coverage_enabled.then(Coverage::default),
+        io_log: Vec::new(),
+        rng: ChaCha8Rng::seed_from_u64(config.random_seed),
+        clock_ns: config.clock.start_ns,
+        steps: 0,
+        started_at: Instant::now(),
+    };
+    let state = match vm.push_frame(function_id, args, None, Vec::new()) {
+        Ok(()) => loop {
+            if let Some(state) = vm.step() {
+                break state;
+            }
+        },
+        Err(error) => ExecutionState::Error {
+            error,
+            partial_results: HashMap::new(),
+        },
+    };
+    RunOutput {
+        state,
+        trace: vm.trace,
+        coverage: vm.coverage,
+        io_log: vm.io_log,
+        memory: vm.memory,
+        steps: vm.steps,
+    }
+}
+
+/// One function invocation: registers plus the position in its code.
+struct Frame {
+    code: Arc<FunctionCode>,
+    regs: Vec<Option<Value>>,
+    arguments: Vec<Value>,
+    captures: Vec<Value>,
+    /// Call node in the caller that receives the return value.
+    return_target: Option<Slot>,
+    cursor: Cursor,
+    /// In-flight structured loops, keyed by node.
+    loops: HashMap<Slot, StructuredLoop>,
+}
+
+/// How a frame picks its next node.
+enum Cursor {
+    /// Index into [`FunctionCode::straight_line`].
+    StraightLine(usize),
+    WorkList(Scheduler),
+}
+
+impl Frame {
+    /// The next node to evaluate, or `None` if the frame is deadlocked.
+    fn next(&mut self) -> Option<Slot> {
+        match &mut self.cursor {
+            Cursor::StraightLine(pc) => {
+                let order = self.code.straight_line.as_ref()?;
+                let slot = order.get(*pc).copied()?;
+                *pc += 1;
+                Some(slot)
+            }
+            Cursor::WorkList(scheduler) => scheduler.next_ready(&self.code),
+        }
+    }
+
+    /// Records that `slot` finished evaluating and releases its successors.
+    fn complete(&mut self, slot: Slot, coverage: Option<&mut Coverage>) {
+        let Cursor::WorkList(scheduler) = &mut self.cursor else {
+            return;
+        };
+        scheduler.evaluated[slot as usize] = true;
+        if is_branch(&self.code.instrs[slot as usize].op) {
+            self.propagate_control_flow(slot, coverage);
+        } else {
+            scheduler.propagate(&self.code, slot);
+        }
+    }
+
+    /// Releases the successors of a call node once its value arrived.
+    fn returned(&mut self, slot: Slot) {
+        if let Cursor::WorkList(scheduler) = &mut self.cursor {
+            scheduler.propagate(&self.code, slot);
+        }
+    }
+
+    /// Activates the taken arm of a Branch/IfElse/Loop/Match, resetting the
+    /// loop body when a `Loop` continues.
+    fn propagate_control_flow(&mut self, slot: Slot, coverage: Option<&mut Coverage>) {
+        let code = self.code.clone();
+        let instr = &code.instrs[slot as usize];
+        let taken = taken_branch(&instr.op, self.regs[slot as usize].as_ref());
+        if let (Some(coverage), Some(arm)) = (coverage, taken) {
+            if !matches!(instr.op, ComputeNodeOp::Core(ComputeOp::Loop)) {
+                coverage.record_arm(instr.node_id, arm);
+            }
+        }
+        let Cursor::WorkList(scheduler) = &mut self.cursor else {
+            return;
+        };
+
+        let activated: Vec<Slot> = instr
+            .successors
+            .iter()
+            .filter_map(|successor| match *successor {
+                Successor::Control(target, edge) => match (taken, edge) {
+                    (Some(taken), Some(edge)) => (taken == edge).then_some(target),
+                    _ => Some(target),
+                },
+                Successor::Data(_) => None,
+            })
+            .collect();
+        for &target in &activated {
+            scheduler.control_ready[target as usize] = true;
+        }
+
+        if matches!(instr.op, ComputeNodeOp::Core(ComputeOp::Loop)) && taken == Some(0) {
+            // Sources outside the body keep their values and won't fire
+            // again, so they are credited up front
+            let external: Vec<usize> = instr
+                .loop_body
+                .iter()
+                .map(|(_, sources)| {
+                    sources
+                        .iter()
+                        .filter(|&&source| self.regs[source as usize].is_some())
+                        .count()
+                })
+                .collect();
+            for &(node, _) in &instr.loop_body {
+                scheduler.evaluated[node as usize] = false;
+                self.regs[node as usize] = None;
+                scheduler.control_ready[node as usize] = false;
+            }
+            for (&(node, _), count) in instr.loop_body.iter().zip(external) {
+                scheduler.readiness[node as usize] = count;
+            }
+            scheduler.evaluated[slot as usize] = false;
+            self.regs[slot as usize] = None;
+            scheduler.readiness[slot as usize] = 0;
+        }
+
+        for &target in &activated {
+            scheduler.control_ready[target as usize] = true;
+        }
+        for &target in &activated {
+            scheduler.try_schedule(&code, target);
+        }
+        for successor in &instr.successors {
+            if let Successor::Data(target) = *successor {
+                scheduler.readiness[target as usize] += 1;
+                scheduler.try_schedule(&code, target);
+            }
+        }
+    }
+
+    /// Register contents keyed by node, for counterexamples and partial
+    /// results.
+    fn values(&self) -> impl Iterator<Item = (NodeId, &Value)> {
+        self.code
+            .instrs
+            .iter()
+            .zip(&self.regs)
+            .filter_map(|(instr, reg)| reg.as_ref().map(|value| (instr.node_id, value)))
+    }
+}
+
+/// Whether an op picks which successors run.
+fn is_branch(op: &ComputeNodeOp) -> bool {
+    matches!(
+        op,
+        ComputeNodeOp::Core(
+            ComputeOp::Branch | ComputeOp::IfElse | ComputeOp::Loop | ComputeOp::Match
+        )
+    )
+}
+
+/// VM state for one run.
+struct Vm<'a> {
+    graph: &'a ProgramGraph,
+    program: &'a BytecodeProgram,
+    config: &'a InterpreterConfig,
+    frames: Vec<Frame>,
+    memory: Vec<Value>,
+    trace: Option<Vec<TraceEntry>>,
+    coverage: Option<Coverage>,
+    io_log: Vec<Value>,
+    rng: ChaCha8Rng,
+    clock_ns: i64,
+    steps: u64,
+    started_at: Instant,
+}
+
+impl Vm<'_> {
+    /// Pushes a frame for `function_id`, seeding its parameter registers.
+    fn push_frame(
+        &mut self,
+        function_id: FunctionId,
+        args: Vec<Value>,
+        return_target: Option<Slot>,
+        captures: Vec<Value>,
+    ) -> Result<(), RuntimeError> {
+        let code =
+            match self.program.function(function_id) {
+                Some(code) => code.clone(),
+                None => Arc::new(FunctionCode::lower(self.graph, function_id).map_err(
+                    |error| RuntimeError::InternalError {
+                        message: error.to_string(),
+                    },
+                )?),
+            };
+        let args = tag_arguments(self.graph, function_id, args);
+        let mut regs = vec![None; code.instrs.len()];
+        for &(slot, index) in &code.parameters {
+            regs[slot as usize] = args.get(index).cloned();
+        }
+        let cursor = match code.straight_line {
+            Some(_) => Cursor::StraightLine(0),
+            None => Cursor::WorkList(Scheduler::new(&code)),
+        };
+        self.frames.push(Frame {
+            code,
+            regs,
+            arguments: args,
+            captures,
+            return_target,
+            cursor,
+            loops: HashMap::new(),
+        });
+        Ok(())
+    }
+
+    /// Evaluates the next node. Returns the final state once the run ends.
+    fn step(&mut self) -> Option<ExecutionState> {
+        let frame = self.frames.last_mut()?;
+        let Some(slot) = frame.next() else {
+            self.steps += 1;
+            return Some(self.error(RuntimeError::InternalError {
+                message: "no ready nodes in work list (possible deadlock)".into(),
+            }));
+        };
+        let code = frame.code.clone();
+        let instr = &code.instrs[slot as usize];
+        let node_id = instr.node_id;
+        if let Some(error) = self.exceeded_limit(node_id) {
+            return Some(self.error(error));
+        }
+
+        let frame = self.frames.last().expect("frame was just stepped");
+        let inputs: Vec<(u16, Value)> = instr
+            .inputs
+            .iter()
+            .filter_map(|&(port, source)| {
+                frame.regs[source as usize]
+                    .as_ref()
+                    .map(|value| (port, value.clone()))
+            })
+            .collect();
+        let depth = self.frames.len();
+        let result = self.eval(slot, &instr.op, &inputs, node_id);
+        self.steps += 1;
+        if let Some(coverage) = &mut self.coverage {
+            coverage.record_node(node_id);
+        }
+        let record = |output: Option<Value>, trace: &mut Option<Vec<TraceEntry>>| {
+            if let Some(trace) = trace {
+                trace.push(TraceEntry {
+                    node_id,
+                    depth,
+                    op_description: instr.description.clone(),
+                    inputs: inputs.clone(),
+                    output,
+                });
+            }
+        };
+
+        match result {
+            Ok(EvalResult::Value(value)) => {
+                let value = tag_output(instr, value, &self.graph.types);
+                record(Some(value.clone()), &mut self.trace);
+                let frame = self.frames.last_mut().expect("frame was just stepped");
+                frame.regs[slot as usize] = Some(value);
+                frame.complete(slot, self.coverage.as_mut());
+                None
+            }
+            Ok(EvalResult::NoValue) => {
+                record(None, &mut self.trace);
+                let frame = self.frames.last_mut().expect("frame was just stepped");
+                frame.complete(slot, self.coverage.as_mut());
+                None
+            }
+            Ok(EvalResult::Return(value)) => {
+                record(Some(value.clone()), &mut self.trace);
+                let frame = self.frames.pop().expect("frame was just stepped");
+                if self.frames.is_empty() {
+                    return Some(ExecutionState::Completed { result: value });
+                }
+                let target = frame.return_target?;
+                match self.advance_structured_loop(target, value) {
+                    Ok(Some(value)) => {
+                        let caller = self.frames.last_mut().expect("caller frame");
+                        let value = tag_output(
+                            &caller.code.instrs[target as usize],
+                            value,
+                            &self.graph.types,
+                        );
+                        caller.regs[target as usize] = Some(value);
+                        caller.returned(target);
+                        None
+                    }
+                    Ok(None) => None,
+                    Err(error) => Some(self.error(error)),
+                }
+            }
+            Ok(EvalResult::Call {
+                target,
+                args,
+                return_target: _,
+                captures,
+            }) => {
+                record(None, &mut self.trace);
+                if self.frames.len() >= self.config.max_recursion_depth {
+                    return Some(self.error(RuntimeError::RecursionLimitExceeded {
+                        node: node_id,
+                        limit: self.config.max_recursion_depth,
+                    }));
+                }
+                match check_call_invariants(self.graph, code.function_id, target, &args) {
+                    Ok(None) => {}
+                    Ok(Some(violation)) => {
+                        return Some(ExecutionState::ContractViolation { violation })
+                    }
+                    Err(error) => return Some(self.error(error)),
+                }
+                match self.push_frame(target, args, Some(slot), captures) {
+                    Ok(()) => None,
+                    Err(error) => Some(self.error(error)),
+                }
+            }
+            Ok(EvalResult::ContractViolated { violation }) => {
+                record(None, &mut self.trace);
+                Some(ExecutionState::ContractViolation { violation })
+            }
+            Err(error) => Some(self.error(error)),
+        }
+    }
+
+    /// The step or time budget the next step would exceed, if any.
+    fn exceeded_limit(&self, node: NodeId) -> Option<RuntimeError> {
+        let limits = &self.config.limits;
+        if let Some(limit) = limits.max_steps.filter(|&limit| self.steps >= limit) {
+            return Some(RuntimeError::StepLimitExceeded { node, limit });
+        }
+        let timeout = limits.timeout_ms.map(Duration::from_millis)?;
+        (self.started_at.elapsed() >= timeout).then_some(RuntimeError::TimeoutExceeded {
+            node,
+            limit_ms: timeout.as_millis() as u64,
+        })
+    }
+
+    /// Evaluates the node at `slot` of the current frame.
+    fn eval(
+        &mut self,
+        slot: Slot,
+        op: &ComputeNodeOp,
+        inputs: &[(u16, Value)],
+        node_id: NodeId,
+    ) -> Result<EvalResult, RuntimeError> {
+        let frame = self.frames.last_mut().expect("frame was just stepped");
+        match op {
+            ComputeNodeOp::Core(ComputeOp::Return) => Ok(EvalResult::Return(return_value(inputs))),
+            ComputeNodeOp::Core(ComputeOp::Call { target }) => {
+                Ok(direct_call(*target, inputs, node_id))
+            }
+            ComputeNodeOp::Core(ComputeOp::IndirectCall) => indirect_call(inputs, node_id),
+            ComputeNodeOp::Core(ComputeOp::Parameter { index }) => Ok(EvalResult::Value(
+                frame
+                    .arguments
+                    .get(*index as usize)
+                    .cloned()
+                    .unwrap_or(Value::Unit),
+            )),
+            ComputeNodeOp::Core(ComputeOp::Old { index }) => {
+                Ok(EvalResult::Value(crate::contracts::check::old_value(
+                    &frame.arguments,
+                    *index,
+                    &self.memory,
+                    node_id,
+                )?))
+            }
+            ComputeNodeOp::Core(ComputeOp::CaptureAccess { index }) => Ok(EvalResult::Value(
+                frame
+                    .captures
+                    .get(*index as usize)
+                    .cloned()
+                    .unwrap_or(Value::Unit),
+            )),
+            ComputeNodeOp::Core(ComputeOp::Print) => {
+                if let Some((_, v)) = inputs.first() {
+                    self.io_log.push(v.clone());
+                }
+                Ok(EvalResult::Value(Value::Unit))
+            }
+            ComputeNodeOp::Core(ComputeOp::ReadLine) => Ok(EvalResult::Value(Value::I64(0))),
+            ComputeNodeOp::Core(ComputeOp::Now) => {
+                let now = self.clock_ns;
+                self.clock_ns = self.clock_ns.wrapping_add(self.config.clock.step_ns);
+                Ok(EvalResult::Value(Value::I64(now)))
+            }
+            ComputeNodeOp::Core(ComputeOp::Random) => {
+                Ok(EvalResult::Value(Value::I64(self.rng.next_u64() as i64)))
+            }
+            ComputeNodeOp::Core(
+                ComputeOp::FileOpen
+                | ComputeOp::FileRead
+                | ComputeOp::FileWrite
+                | ComputeOp::FileClose,
+            ) => Ok(EvalResult::Value(Value::I64(0))),
+            ComputeNodeOp::Core(ComputeOp::Alloc) => {
+                let addr = alloc_cell(&mut self.memory, &self.config.limits, node_id)?;
+                Ok(EvalResult::Value(Value::Pointer {
+                    ty: TypeId::UNIT,
+                    address: addr,
+                }))
+            }
+            ComputeNodeOp::Core(ComputeOp::Load) => {
+                Ok(EvalResult::Value(load_cell(&self.memory, inputs, node_id)?))
+            }
+            ComputeNodeOp::Core(ComputeOp::Store) => {
+                store_cell(&mut self.memory, inputs, node_id)?;
+                Ok(EvalResult::NoValue)
+            }
+            ComputeNodeOp::Core(ComputeOp::GetElementPtr) => {
+                Ok(EvalResult::Value(element_ptr(inputs, node_id)?))
+            }
+            ComputeNodeOp::Core(ComputeOp::MakeClosure { function }) => {
+                Ok(EvalResult::Value(Value::Closure {
+                    ty: TypeId::UNIT,
+                    function: *function,
+                    captures: inputs.iter().map(|(_, v)| v.clone()).collect(),
+                }))
+            }
+            ComputeNodeOp::Core(ComputeOp::Branch | ComputeOp::IfElse) => {
+                let taken = branch_condition(inputs, node_id)?;
+                frame.regs[slot as usize] = Some(Value::Bool(taken));
+                Ok(EvalResult::NoValue)
+            }
+            ComputeNodeOp::Core(ComputeOp::Loop) => {
+                if let Some((_, cond)) = inputs.iter().find(|(p, _)| *p == 0) {
+                    frame.regs[slot as usize] = Some(cond.clone());
+                }
+                Ok(EvalResult::NoValue)
+            }
+            ComputeNodeOp::Core(
+                ComputeOp::ForRange { .. }
+                | ComputeOp::ForEach { .. }
+                | ComputeOp::ForAll { .. }
+                | ComputeOp::Exists { .. },
+            )
+            | ComputeNodeOp::Structured(
+                StructuredOp::ArrayMap | StructuredOp::ArrayFold | StructuredOp::ArrayFilter,
+            ) => {
+                let mut structured = structured_loop(op, inputs, node_id)?;
+                let Some(args) = structured.next_args() else {
+                    return Ok(EvalResult::Value(structured.finish()));
+                };
+                let call = EvalResult::Call {
+                    target: structured.body,
+                    args,
+                    return_target: (node_id, 0),
+                    captures: structured.captures.clone(),
+                };
+                frame.loops.insert(slot, structured);
+                Ok(call)
+            }
+            ComputeNodeOp::Core(ComputeOp::Match) => {
+                let disc = input_at(inputs, 0, node_id)?;
+                frame.regs[slot as usize] = Some(disc.clone());
+                Ok(EvalResult::NoValue)
+            }
+            ComputeNodeOp::Core(ComputeOp::Jump) => Ok(EvalResult::NoValue),
+            ComputeNodeOp::Core(ComputeOp::Phi) => {
+                let decisions = frame.code.instrs[slot as usize]
+                    .decisions
+                    .iter()
+                    .map(|&source| frame.regs[source as usize].as_ref());
+                Ok(EvalResult::Value(phi_value(inputs, decisions)))
+            }
+            ComputeNodeOp::Core(
+                ComputeOp::Precondition { .. }
+                | ComputeOp::Postcondition { .. }
+                | ComputeOp::Invariant { .. },
+            ) => match failed_contract(op, inputs, node_id)? {
+                None => Ok(EvalResult::NoValue),
+                Some((kind, message)) => {
+                    let values: HashMap<NodeId, Value> = frame
+                        .values()
+                        .map(|(node, value)| (node, value.clone()))
+                        .collect();
+                    Ok(EvalResult::ContractViolated {
+                        violation: crate::contracts::ContractViolation {
+                            kind,
+                            contract_node: node_id,
+                            function_id: frame.code.function_id,
+                            message,
+                            inputs: frame.arguments.clone(),
+                            actual_return: None,
+                            counterexample: crate::contracts::check::collect_counterexample(
+                                self.graph, node_id, &values,
+                            ),
+                        },
+                    })
+                }
+            },
+            _ => match eval_op(op, inputs, node_id, self.graph)? {
+                Some(value) => Ok(EvalResult::Value(value)),
+                None => Ok(EvalResult::NoValue),
+            },
+        }
+    }
+
+    /// Feeds a body's return value into the caller's loop at `slot`, if one
+    /// is in flight. Returns the value the node produces, or `None` after
+    /// pushing the next body call.
+    fn advance_structured_loop(
+        &mut self,
+        slot: Slot,
+        value: Value,
+    ) -> Result<Option<Value>, RuntimeError> {
+        let Some(caller) = self.frames.last_mut() else {
+            return Ok(Some(value));
+        };
+        let Some(structured) = caller.loops.get_mut(&slot) else {
+            return Ok(Some(value));
+        };
+        let node_id = caller.code.instrs[slot as usize].node_id;
+        structured.absorb(value, node_id)?;
+        match structured.next_args() {
+            Some(args) => {
+                let body = structured.body;
+                let captures = structured.captures.clone();
+                self.push_frame(body, args, Some(slot), captures)?;
+                Ok(None)
+            }
+            None => Ok(caller.loops.remove(&slot).map(StructuredLoop::finish)),
+        }
+    }
+
+    /// An `Error` state carrying every frame's values, inner frames last.
+    fn error(&self, error: RuntimeError) -> ExecutionState {
+        let mut partial_results = HashMap::new();
+        for frame in &self.frames {
+            partial_results.extend(frame.values().map(|(node, value)| (node, value.clone())));
+        }
+        ExecutionState::Error {
+            error,
+            partial_results,
+        }
+    }
+}
+
+/// Tags an untyped compound result of `instr`, and its untyped contents,
+/// with its output type.
+fn tag_output(instr: &Instr, value: Value, registry: &TypeRegistry) -> Value {
+    match instr.output_type {
+        Some(ty) => value.with_type(ty, registry),
+        None => value,
+    }
+}
+
+/// Runs `function_id` on both engines with tracing and coverage enabled and
+/// asserts they agree on everything observable. Returns the VM's output.
+#[cfg(test)]
+pub(crate) fn assert_engines_agree(
+    graph: &ProgramGraph,
+    function_id: FunctionId,
+    args: Vec<Value>,
+    config: &InterpreterConfig,
+) -> RunOutput {
+    /// `Debug` of a state with partial results in node order.
+    fn comparable(state: &ExecutionState) -> String {
+        match state {
+            ExecutionState::Error {
+                error,
+                partial_results,
+            } => {
+                let mut partial: Vec<_> = partial_results.iter().collect();
+                partial.sort_by_key(|(node, _)| node.0);
+                format!("Error {{ error: {error:?}, partial_results: {partial:?} }}")
+            }
+            other => format!("{other:?}"),
+        }
+    }
+
+    let config = InterpreterConfig {
+        trace_enabled: true,
+        coverage_enabled: true,
+        ..config.clone()
+    };
+    let cache = BytecodeCache::new();
+    let reference =
+        Executor::new(graph, Engine::Reference, &cache).run(&config, function_id, args.clone());
+    let program = cache.lower(graph).expect("graph lowers to bytecode");
+    let vm = run(graph, &program, &config, function_id, args);
+
+    assert_eq!(comparable(&vm.state), comparable(&reference.state));
+    assert_eq!(format!("{:?}", vm.trace), format!("{:?}", reference.trace));
+    assert_eq!(vm.coverage, reference.coverage);
+    assert_eq!(vm.io_log, reference.io_log);
+    assert_eq!(vm.memory, reference.memory);
+    assert_eq!(vm.steps, reference.steps);
+    vm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lmlang_core::ops::ArithOp;
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::Visibility;
+
+    /// Helper: `f(a, b)` returning the first of two `Sub` nodes, with the
+    /// parameters wired to the Subs' ports in the given order.
+    fn two_subs_graph(first: [u16; 2], second: [u16; 2]) -> (ProgramGraph, FunctionId) {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func = graph
+            .add_function(
+                "f".into(),
+                root,
+                vec![("a".into(), TypeId::I32), ("b".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let a = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func)
+            .unwrap();
+        let b = graph
+            .add_core_op(ComputeOp::Parameter { index: 1 }, func)
+            .unwrap();
+        let sub = ComputeOp::BinaryArith { op: ArithOp::Sub };
+        let sub1 = graph.add_core_op(sub.clone(), func).unwrap();
+        let sub2 = graph.add_core_op(sub, func).unwrap();
+        for (sub, [port_a, port_b]) in [(sub1, first), (sub2, second)] {
+            graph.add_data_edge(a, sub, 0, port_a, TypeId::I32).unwrap();
+            graph.add_data_edge(b, sub, 0, port_b, TypeId::I32).unwrap();
+        }
+        let ret = graph.add_core_op(ComputeOp::Return, func).unwrap();
+        graph.add_data_edge(sub1, ret, 0, 0, TypeId::I32).unwrap();
+        (graph, func)
+    }
+
+    fn result(output: &RunOutput) -> &Value {
+        match &output.state {
+            ExecutionState::Completed { result } => result,
+            other => panic!("Expected Completed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn straight_line_functions_skip_the_work_list() {
+        let (graph, func) = two_subs_graph([0, 1], [1, 0]);
+        let program = BytecodeCache::new().lower(&graph).unwrap();
+        let code = program.function(func).unwrap();
+        assert!(code.is_straight_line());
+        assert_eq!(code.len(), 5);
+
+        let output = assert_engines_agree(
+            &graph,
+            func,
+            vec![Value::I32(3), Value::I32(5)],
+            &InterpreterConfig::default(),
+        );
+        assert_eq!(result(&output), &Value::I32(-2));
+    }
+
+    #[test]
+    fn cache_reuses_code_until_the_layout_changes() {
+        let cache = BytecodeCache::new();
+        let (graph, func) = two_subs_graph([0, 1], [1, 0]);
+        let first = cache.lower(&graph).unwrap();
+        let again = cache.lower(&graph).unwrap();
+        assert!(Arc::ptr_eq(
+            first.function(func).unwrap(),
+            again.function(func).unwrap()
+        ));
+        assert_eq!(cache.len(), 1);
+
+        // Swapping the Subs' operands keeps the content hash but not the
+        // wiring, so it must not reuse the cached code.
+        let (swapped, func) = two_subs_graph([1, 0], [0, 1]);
+        assert_eq!(hash_of(&graph, func), hash_of(&swapped, func));
+        let program = cache.lower(&swapped).unwrap();
+        assert_eq!(cache.len(), 2);
+        let output = run(
+            &swapped,
+            &program,
+            &InterpreterConfig::default(),
+            func,
+            vec![Value::I32(3), Value::I32(5)],
+        );
+        assert_eq!(result(&output), &Value::I32(2));
+    }
+
+    fn hash_of(graph: &ProgramGraph, func: FunctionId) -> blake3::Hash {
+        lmlang_storage::hash::hash_function(graph, func)
+    }
+
+    #[test]
+    fn graphs_with_cross_function_edges_fall_back_to_the_reference_engine() {
+        let (mut graph, func) = two_subs_graph([0, 1], [1, 0]);
+        let root = graph.modules.root_id();
+        let other = graph
+            .add_function("g".into(), root, vec![], TypeId::I32, Visibility::Public)
+            .unwrap();
+        let stray = graph.add_core_op(ComputeOp::Return, other).unwrap();
+        let ret = *graph.function_nodes(func).last().unwrap();
+        graph.add_data_edge(ret, stray, 0, 0, TypeId::I32).unwrap();
+
+        let cache = BytecodeCache::new();
+        assert!(matches!(
+            cache.lower(&graph),
+            Err(super::super::bytecode::LowerError::CrossFunctionEdge { .. })
+        ));
+        let executor = Executor::new(&graph, Engine::Bytecode, &cache);
+        assert_eq!(executor.engine(), Engine::Reference);
+        let output = executor.run(
+            &InterpreterConfig::default(),
+            func,
+            vec![Value::I32(3), Value::I32(5)],
+        );
+        assert_eq!(result(&output), &Value::I32(-2));
+    }
+}
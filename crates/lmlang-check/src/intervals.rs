@@ -0,0 +1,1434 @@
+//! Interval abstract interpretation over the compute graph.
+//!
+//! Every integer-valued node gets an interval `[lo, hi]` over-approximating
+//! the values it can take. Intervals start from constants and parameter types
+//! and are narrowed by:
+//! - `Precondition`s comparing a parameter, which hold on function entry,
+//!   when the caller asks for them to be assumed;
+//! - the `Compare` conditions of the `Branch`/`IfElse`/`Loop` edges a node is
+//!   control-dependent on, including through `Not`, `And` and `Or`;
+//! - the bounds of a `ForRange` loop, for the index parameter of a body
+//!   function that is only ever used as that loop's body.
+//!
+//! Memory cells (`Alloc`s whose pointer only feeds `Load`/`Store`) hold the
+//! join of every value stored to them. Loop-carried cells make this a
+//! fixpoint, reached with widening: a cell bound still moving after a few
+//! rounds jumps to its type's limit, and narrowing passes then recover the
+//! bounds implied by loop conditions.
+//!
+//! The analysis reports operations that may trap at runtime (integer
+//! overflow, division by zero, out-of-bounds array index) as
+//! [`RangeWarning`]s, and collects the guarded nodes proven safe so codegen can
+//! omit their runtime guards. Codegen only assumes preconditions when it
+//! compiles them into checks that run first; otherwise a caller breaking a
+//! precondition would reach the unguarded operation. Reads of memory before
+//! its first store are not modeled.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+
+use lmlang_core::edge::FlowEdge;
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::ops::{
+    ArithOp, CmpOp, ComputeNodeOp, ComputeOp, LogicOp, StructuredOp, UnaryArithOp,
+};
+use lmlang_core::type_id::TypeId;
+use lmlang_core::types::{ConstValue, LmType};
+
+/// Rounds of plain joins before unstable cell bounds are widened.
+const WIDEN_AFTER: u32 = 3;
+
+/// Rounds after which the ascending phase gives up and falls back to type
+/// ranges everywhere.
+const MAX_ROUNDS: u32 = 100;
+
+/// Narrowing passes run after the ascending phase converges.
+const NARROWING_PASSES: u32 = 3;
+
+/// A non-empty inclusive range of integer values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub lo: i128,
+    pub hi: i128,
+}
+
+impl Interval {
+    /// The interval `[lo, hi]`, or `None` if it is empty.
+    pub fn new(lo: i128, hi: i128) -> Option<Self> {
+        (lo <= hi).then_some(Interval { lo, hi })
+    }
+
+    /// The single value `value`.
+    pub fn point(value: i128) -> Self {
+        Interval {
+            lo: value,
+            hi: value,
+        }
+    }
+
+    /// Every value of a built-in integer type.
+    pub fn of_type(type_id: TypeId) -> Option<Self> {
+        let (lo, hi) = match type_id {
+            TypeId::I8 => (i8::MIN as i128, i8::MAX as i128),
+            TypeId::I16 => (i16::MIN as i128, i16::MAX as i128),
+            TypeId::I32 => (i32::MIN as i128, i32::MAX as i128),
+            TypeId::I64 => (i64::MIN as i128, i64::MAX as i128),
+            _ => return None,
+        };
+        Some(Interval { lo, hi })
+    }
+
+    pub fn contains(&self, value: i128) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    /// Whether every value of `self` is in `other`.
+    pub fn is_within(&self, other: &Interval) -> bool {
+        other.lo <= self.lo && self.hi <= other.hi
+    }
+
+    /// The smallest interval containing both.
+    pub fn join(self, other: Interval) -> Interval {
+        Interval {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+
+    /// The values in both, if any.
+    pub fn meet(self, other: Interval) -> Option<Interval> {
+        Interval::new(self.lo.max(other.lo), self.hi.min(other.hi))
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+/// The kind of runtime trap a warning is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeHazard {
+    /// An integer operation may overflow its type.
+    Overflow,
+    /// A division or remainder may have a zero divisor.
+    DivisionByZero,
+    /// An array index may be outside the array.
+    OutOfBounds,
+}
+
+/// An operation that may trap at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeWarning {
+    pub hazard: RangeHazard,
+    pub function_id: FunctionId,
+    pub node: NodeId,
+    pub message: String,
+}
+
+/// Result of [`analyze_ranges`].
+#[derive(Debug, Clone, Default)]
+pub struct RangeAnalysis {
+    /// Interval of every reachable integer-valued node.
+    pub intervals: HashMap<NodeId, Interval>,
+    /// Guarded nodes (checked arithmetic, divisions, dynamic array accesses)
+    /// that can never trap.
+    pub safe_nodes: HashSet<NodeId>,
+    /// Possible traps, sorted by node.
+    pub warnings: Vec<RangeWarning>,
+    /// Index range each loop-only body was analyzed under, taken from the
+    /// bounds of the loops over it (`None` if none of them ever iterates).
+    /// A body's safe nodes depend on this as well as on its own code.
+    pub loop_index_ranges: HashMap<FunctionId, Option<Interval>>,
+}
+
+impl RangeAnalysis {
+    /// Whether `node`'s runtime guard is proven unnecessary.
+    pub fn is_safe(&self, node: NodeId) -> bool {
+        self.safe_nodes.contains(&node)
+    }
+}
+
+/// Runs the interval analysis over every function in the graph.
+///
+/// With `assume_preconditions`, parameters are narrowed by the function's
+/// `Precondition`s; warnings should assume them, but safe nodes only hold
+/// under them if the preconditions are checked at runtime.
+///
+/// Functions used only as `ForRange` bodies are analyzed after the functions
+/// containing their loops, so the index parameter starts from the loop bounds.
+pub fn analyze_ranges(graph: &ProgramGraph, assume_preconditions: bool) -> RangeAnalysis {
+    let mut result = RangeAnalysis::default();
+    let mut function_ids: Vec<FunctionId> = graph.functions().keys().copied().collect();
+    function_ids.sort_by_key(|f| f.0);
+
+    let loop_sites = exclusive_loop_bodies(graph);
+    let mut done: HashSet<FunctionId> = HashSet::new();
+    let mut index_ranges: HashMap<FunctionId, Option<Interval>> = HashMap::new();
+
+    let mut pending: VecDeque<FunctionId> = function_ids.iter().copied().collect();
+    let mut stalled = 0;
+    while let Some(func_id) = pending.pop_front() {
+        let mut overrides = HashMap::new();
+        if let Some(owners) = loop_sites.get(&func_id) {
+            let ready = owners.iter().all(|owner| done.contains(owner));
+            if !ready && stalled < pending.len() + 1 {
+                // Wait for the functions containing the loop
+                stalled += 1;
+                pending.push_back(func_id);
+                continue;
+            }
+            if ready {
+                // `None`: every loop over this body is dead or never iterates
+                let range = index_ranges.get(&func_id).copied().flatten();
+                overrides.insert(0, range);
+                result.loop_index_ranges.insert(func_id, range);
+            }
+        }
+        stalled = 0;
+
+        let mut ranges = FunctionRanges::new(graph, func_id, overrides, assume_preconditions);
+        ranges.solve();
+        ranges.report(&mut result);
+        for (body, range) in ranges.loop_index_ranges() {
+            let entry = index_ranges.entry(body).or_insert(None);
+            *entry = match (*entry, range) {
+                (Some(a), Some(b)) => Some(a.join(b)),
+                (a, b) => a.or(b),
+            };
+        }
+        done.insert(func_id);
+    }
+
+    result.warnings.sort_by_key(|w| w.node.0);
+    result
+}
+
+/// Functions only ever used as `ForRange` bodies, with the functions
+/// containing those loops.
+fn exclusive_loop_bodies(graph: &ProgramGraph) -> HashMap<FunctionId, Vec<FunctionId>> {
+    let mut sites: HashMap<FunctionId, Vec<FunctionId>> = HashMap::new();
+    let mut other_uses: HashSet<FunctionId> = HashSet::new();
+    for &owner in graph.functions().keys() {
+        for node_id in graph.function_nodes(owner) {
+            let Some(node) = graph.get_compute_node(node_id) else {
+                continue;
+            };
+            match &node.op {
+                ComputeNodeOp::Core(ComputeOp::ForRange { body }) => {
+                    let owners = sites.entry(*body).or_default();
+                    if !owners.contains(&owner) {
+                        owners.push(owner);
+                    }
+                }
+                ComputeNodeOp::Core(
+                    ComputeOp::Call { target: f }
+                    | ComputeOp::ForEach { body: f }
+                    | ComputeOp::ForAll { predicate: f }
+                    | ComputeOp::Exists { predicate: f }
+                    | ComputeOp::MakeClosure { function: f },
+                ) => {
+                    other_uses.insert(*f);
+                }
+                _ => {}
+            }
+        }
+    }
+    sites.retain(|body, owners| {
+        !other_uses.contains(body)
+            && !owners.contains(body)
+            && graph
+                .get_function(*body)
+                .is_some_and(|f| f.name != "main" && !f.params.is_empty())
+    });
+    sites
+}
+
+/// A branch condition known to have evaluated to `holds`.
+type Fact = (NodeId, bool);
+
+struct FunctionRanges<'g> {
+    graph: &'g ProgramGraph,
+    func_id: FunctionId,
+    /// Data-topological order; nodes on data cycles come last.
+    order: Vec<NodeId>,
+    /// Incoming data edges per node: `(target_port, source, value_type)`.
+    inputs: HashMap<NodeId, Vec<(u16, NodeId, TypeId)>>,
+    /// Integer type of each integer-valued node.
+    types: HashMap<NodeId, TypeId>,
+    /// Conditions that must have held for each node to run.
+    facts: HashMap<NodeId, Vec<Fact>>,
+    /// Conditions asserted by preconditions, applied to parameters.
+    entry_facts: Vec<Fact>,
+    /// Tracked memory cells: alloc node -> (cell type, stores, loads).
+    cells: HashMap<NodeId, Cell>,
+    /// Parameter intervals fixed by the caller; `None` means never called.
+    overrides: HashMap<u32, Option<Interval>>,
+    values: HashMap<NodeId, Interval>,
+}
+
+#[derive(Clone, Copy)]
+enum CellUpdate {
+    /// Ascending: join in newly stored values.
+    Join,
+    /// Ascending: send still-growing bounds to the type's limits.
+    Widen,
+    /// Descending: recompute from the stores alone.
+    Narrow,
+}
+
+struct Cell {
+    type_id: TypeId,
+    stores: Vec<NodeId>,
+    has_loads: bool,
+    value: Option<Interval>,
+}
+
+impl<'g> FunctionRanges<'g> {
+    fn new(
+        graph: &'g ProgramGraph,
+        func_id: FunctionId,
+        overrides: HashMap<u32, Option<Interval>>,
+        assume_preconditions: bool,
+    ) -> Self {
+        let mut nodes = graph.function_nodes(func_id);
+        nodes.sort_by_key(|n| n.0);
+        let node_set: HashSet<NodeId> = nodes.iter().copied().collect();
+
+        let mut inputs: HashMap<NodeId, Vec<(u16, NodeId, TypeId)>> = HashMap::new();
+        let mut controls: HashMap<NodeId, Vec<(NodeId, Option<u16>)>> = HashMap::new();
+        for &node in &nodes {
+            for edge in graph
+                .compute()
+                .edges_directed(node.into(), Direction::Incoming)
+            {
+                let source = NodeId::from(edge.source());
+                if !node_set.contains(&source) {
+                    continue;
+                }
+                match edge.weight() {
+                    FlowEdge::Data {
+                        target_port,
+                        value_type,
+                        ..
+                    } => inputs
+                        .entry(node)
+                        .or_default()
+                        .push((*target_port, source, *value_type)),
+                    FlowEdge::Control { branch_index } => controls
+                        .entry(node)
+                        .or_default()
+                        .push((source, *branch_index)),
+                }
+            }
+        }
+
+        let mut ranges = FunctionRanges {
+            graph,
+            func_id,
+            order: data_order(&nodes, &inputs),
+            inputs,
+            types: HashMap::new(),
+            facts: HashMap::new(),
+            entry_facts: Vec::new(),
+            cells: HashMap::new(),
+            overrides,
+            values: HashMap::new(),
+        };
+        for &node in &nodes {
+            if let Some(ty) = ranges.int_type(node) {
+                ranges.types.insert(node, ty);
+            }
+        }
+        ranges.compute_facts(&nodes, &controls);
+        if assume_preconditions {
+            ranges.compute_entry_facts(&nodes);
+        }
+        ranges.find_cells(&nodes);
+        ranges
+    }
+
+    fn op(&self, node: NodeId) -> Option<&'g ComputeNodeOp> {
+        self.graph.get_compute_node(node).map(|n| &n.op)
+    }
+
+    fn input(&self, node: NodeId, port: u16) -> Option<(NodeId, TypeId)> {
+        self.inputs
+            .get(&node)?
+            .iter()
+            .find(|(p, _, _)| *p == port)
+            .map(|(_, source, ty)| (*source, *ty))
+    }
+
+    /// The integer type of `node`'s output, if it produces an integer.
+    fn int_type(&self, node: NodeId) -> Option<TypeId> {
+        let ty = match self.op(node)? {
+            ComputeNodeOp::Core(ComputeOp::Const { value }) => match value {
+                ConstValue::I8(_) => TypeId::I8,
+                ConstValue::I16(_) => TypeId::I16,
+                ConstValue::I32(_) => TypeId::I32,
+                ConstValue::I64(_) => TypeId::I64,
+                _ => return None,
+            },
+            ComputeNodeOp::Core(ComputeOp::Parameter { index }) => {
+                self.graph
+                    .get_function(self.func_id)?
+                    .params
+                    .get(*index as usize)?
+                    .1
+            }
+            ComputeNodeOp::Core(ComputeOp::BinaryArith { .. } | ComputeOp::UnaryArith { .. }) => {
+                self.input(node, 0)?.1
+            }
+            ComputeNodeOp::Structured(StructuredOp::Cast { target_type }) => *target_type,
+            _ => self
+                .graph
+                .compute()
+                .edges_directed(node.into(), Direction::Outgoing)
+                .find_map(|edge| match edge.weight() {
+                    FlowEdge::Data {
+                        source_port: 0,
+                        value_type,
+                        ..
+                    } => Some(*value_type),
+                    _ => None,
+                })?,
+        };
+        Interval::of_type(ty).map(|_| ty)
+    }
+
+    /// Must-hold branch conditions per node: the union over its data inputs
+    /// (it only runs once they have) and the intersection over its control
+    /// predecessors (any one of them can enable it).
+    fn compute_facts(
+        &mut self,
+        nodes: &[NodeId],
+        controls: &HashMap<NodeId, Vec<(NodeId, Option<u16>)>>,
+    ) {
+        // `None` is "not yet known" (the top of the lattice: every fact)
+        let mut facts: HashMap<NodeId, Option<HashSet<Fact>>> =
+            nodes.iter().map(|&n| (n, None)).collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &node in nodes {
+                let mut known = true;
+                let mut set: HashSet<Fact> = HashSet::new();
+                for (_, source, _) in self.inputs.get(&node).into_iter().flatten() {
+                    match &facts[source] {
+                        Some(source_facts) => set.extend(source_facts.iter().copied()),
+                        None => known = false,
+                    }
+                }
+                let mut gate: Option<HashSet<Fact>> = None;
+                for (source, branch) in controls.get(&node).into_iter().flatten() {
+                    let Some(source_facts) = &facts[source] else {
+                        continue;
+                    };
+                    let mut edge_facts = source_facts.clone();
+                    edge_facts.extend(self.edge_facts(*source, *branch));
+                    gate = Some(match gate {
+                        Some(g) => g.intersection(&edge_facts).copied().collect(),
+                        None => edge_facts,
+                    });
+                }
+                let has_controls = controls.get(&node).is_some_and(|c| !c.is_empty());
+                if has_controls && gate.is_none() {
+                    known = false;
+                }
+                if !known {
+                    continue;
+                }
+                set.extend(gate.unwrap_or_default());
+                if facts[&node].as_ref() != Some(&set) {
+                    facts.insert(node, Some(set));
+                    changed = true;
+                }
+            }
+        }
+
+        // Nodes still unknown are unreachable; no facts are needed for them
+        self.facts = facts
+            .into_iter()
+            .map(|(n, f)| (n, f.unwrap_or_default().into_iter().collect()))
+            .collect();
+    }
+
+    /// Conditions asserted by the function's preconditions.
+    fn compute_entry_facts(&mut self, nodes: &[NodeId]) {
+        for &node in nodes {
+            if let Some(ComputeNodeOp::Core(ComputeOp::Precondition { .. })) = self.op(node) {
+                if let Some((cond, _)) = self.input(node, 0) {
+                    let mut found = Vec::new();
+                    self.condition_facts(cond, true, &mut found);
+                    self.entry_facts.extend(found);
+                }
+            }
+        }
+    }
+
+    /// Facts implied by taking control edge `branch` out of `source`.
+    fn edge_facts(&self, source: NodeId, branch: Option<u16>) -> Vec<Fact> {
+        let mut found = Vec::new();
+        if let (
+            Some(ComputeNodeOp::Core(ComputeOp::Branch | ComputeOp::IfElse | ComputeOp::Loop)),
+            Some(index @ (0 | 1)),
+        ) = (self.op(source), branch)
+        {
+            if let Some((cond, _)) = self.input(source, 0) {
+                self.condition_facts(cond, index == 0, &mut found);
+            }
+        }
+        found
+    }
+
+    fn condition_facts(&self, cond: NodeId, holds: bool, found: &mut Vec<Fact>) {
+        match self.op(cond) {
+            Some(ComputeNodeOp::Core(ComputeOp::Compare { .. })) => found.push((cond, holds)),
+            Some(ComputeNodeOp::Core(ComputeOp::Not)) => {
+                if let Some((inner, _)) = self.input(cond, 0) {
+                    self.condition_facts(inner, !holds, found);
+                }
+            }
+            Some(ComputeNodeOp::Core(ComputeOp::BinaryLogic { op })) => {
+                let both = matches!((op, holds), (LogicOp::And, true) | (LogicOp::Or, false));
+                if both {
+                    for port in [0, 1] {
+                        if let Some((inner, _)) = self.input(cond, port) {
+                            self.condition_facts(inner, holds, found);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Finds allocations whose pointer is only used as the address of loads
+    /// and integer stores.
+    fn find_cells(&mut self, nodes: &[NodeId]) {
+        for &node in nodes {
+            if !matches!(self.op(node), Some(ComputeNodeOp::Core(ComputeOp::Alloc))) {
+                continue;
+            }
+            let mut cell = Cell {
+                type_id: TypeId::UNIT,
+                stores: Vec::new(),
+                has_loads: false,
+                value: None,
+            };
+            let mut tracked = true;
+            for edge in self
+                .graph
+                .compute()
+                .edges_directed(node.into(), Direction::Outgoing)
+            {
+                let FlowEdge::Data { target_port, .. } = edge.weight() else {
+                    continue;
+                };
+                let user = NodeId::from(edge.target());
+                match (self.op(user), target_port) {
+                    (Some(ComputeNodeOp::Core(ComputeOp::Load)), 0) => {
+                        match self.types.get(&user) {
+                            Some(ty) => {
+                                cell.type_id = *ty;
+                                cell.has_loads = true;
+                            }
+                            None => tracked = false,
+                        }
+                    }
+                    (Some(ComputeNodeOp::Core(ComputeOp::Store)), 0) => {
+                        match self
+                            .input(user, 1)
+                            .filter(|(_, ty)| Interval::of_type(*ty).is_some())
+                        {
+                            Some((_, ty)) => {
+                                cell.type_id = ty;
+                                cell.stores.push(user);
+                            }
+                            None => tracked = false,
+                        }
+                    }
+                    _ => tracked = false,
+                }
+            }
+            if tracked && Interval::of_type(cell.type_id).is_some() {
+                self.cells.insert(node, cell);
+            }
+        }
+    }
+
+    /// Iterates to a fixpoint with widening on cells, then narrows.
+    fn solve(&mut self) {
+        let mut round = 0;
+        loop {
+            round += 1;
+            if round > MAX_ROUNDS {
+                self.give_up();
+                return;
+            }
+            let mode = if round > WIDEN_AFTER {
+                CellUpdate::Widen
+            } else {
+                CellUpdate::Join
+            };
+            let mut changed = self.node_pass();
+            changed |= self.cell_pass(mode);
+            if changed {
+                continue;
+            }
+            // Cells loaded but never stored to hold whatever their type allows
+            let mut seeded = false;
+            for cell in self.cells.values_mut() {
+                if cell.value.is_none() && cell.has_loads {
+                    cell.value = Interval::of_type(cell.type_id);
+                    seeded = true;
+                }
+            }
+            if !seeded {
+                break;
+            }
+        }
+
+        for _ in 0..NARROWING_PASSES {
+            self.cell_pass(CellUpdate::Narrow);
+            self.node_pass();
+        }
+    }
+
+    /// Falls back to full type ranges, which are trivially sound.
+    fn give_up(&mut self) {
+        for cell in self.cells.values_mut() {
+            cell.value = Interval::of_type(cell.type_id);
+        }
+        self.values = self
+            .types
+            .iter()
+            .filter_map(|(&n, &ty)| Interval::of_type(ty).map(|i| (n, i)))
+            .collect();
+    }
+
+    fn node_pass(&mut self) -> bool {
+        let mut changed = false;
+        for i in 0..self.order.len() {
+            let node = self.order[i];
+            let value = self.eval(node);
+            if self.values.get(&node).copied() != value {
+                changed = true;
+                match value {
+                    Some(v) => self.values.insert(node, v),
+                    None => self.values.remove(&node),
+                };
+            }
+        }
+        changed
+    }
+
+    fn cell_pass(&mut self, mode: CellUpdate) -> bool {
+        let mut changed = false;
+        let allocs: Vec<NodeId> = self.cells.keys().copied().collect();
+        for alloc in allocs {
+            let cell = &self.cells[&alloc];
+            let mut stored: Option<Interval> = None;
+            for &store in &cell.stores {
+                if let Some(v) = self.operand(store, 1) {
+                    stored = Some(stored.map_or(v, |s| s.join(v)));
+                }
+            }
+            let old = cell.value;
+            let new = match (mode, old, stored) {
+                (_, None, stored) => stored,
+                // A cell seeded for its loads keeps that value
+                (_, old, None) => old,
+                (CellUpdate::Join, Some(old), Some(stored)) => Some(old.join(stored)),
+                (CellUpdate::Widen, Some(old), Some(stored)) => {
+                    let full = Interval::of_type(cell.type_id).unwrap_or(stored);
+                    Some(Interval {
+                        lo: if stored.lo < old.lo { full.lo } else { old.lo },
+                        hi: if stored.hi > old.hi { full.hi } else { old.hi },
+                    })
+                }
+                (CellUpdate::Narrow, Some(_), Some(stored)) => Some(stored),
+            };
+            if new != old {
+                changed = true;
+                self.cells.get_mut(&alloc).expect("cell exists").value = new;
+            }
+        }
+        changed
+    }
+
+    /// The interval of `node`'s input at `port`, narrowed by the facts that
+    /// hold wherever `node` runs. `None` if the input never has a value.
+    fn operand(&self, node: NodeId, port: u16) -> Option<Interval> {
+        let (source, _) = self.input(node, port)?;
+        let value = *self.values.get(&source)?;
+        let facts = self.facts.get(&node).map(Vec::as_slice).unwrap_or(&[]);
+        self.refine(source, value, facts)
+    }
+
+    /// Narrows `value`, the interval of `subject`, by every fact comparing
+    /// `subject` directly.
+    fn refine(&self, subject: NodeId, mut value: Interval, facts: &[Fact]) -> Option<Interval> {
+        for &(cmp, holds) in facts {
+            let Some(ComputeNodeOp::Core(ComputeOp::Compare { op })) = self.op(cmp) else {
+                continue;
+            };
+            let op = if holds { *op } else { negate(*op) };
+            let (Some((lhs, _)), Some((rhs, _))) = (self.input(cmp, 0), self.input(cmp, 1)) else {
+                continue;
+            };
+            if lhs == subject && rhs != subject {
+                if let Some(other) = self.values.get(&rhs) {
+                    value = constrain(value, op, *other)?;
+                }
+            } else if rhs == subject && lhs != subject {
+                if let Some(other) = self.values.get(&lhs) {
+                    value = constrain(value, flip(op), *other)?;
+                }
+            }
+        }
+        Some(value)
+    }
+
+    /// Whether a fact at `node` says its input at `port` is not zero, which
+    /// intervals cannot express when zero is strictly inside.
+    fn known_nonzero(&self, node: NodeId, port: u16) -> bool {
+        let Some((subject, _)) = self.input(node, port) else {
+            return false;
+        };
+        let facts = self.facts.get(&node).map(Vec::as_slice).unwrap_or(&[]);
+        facts.iter().any(|&(cmp, holds)| {
+            let Some(ComputeNodeOp::Core(ComputeOp::Compare { op })) = self.op(cmp) else {
+                return false;
+            };
+            let op = if holds { *op } else { negate(*op) };
+            let (Some((lhs, _)), Some((rhs, _))) = (self.input(cmp, 0), self.input(cmp, 1)) else {
+                return false;
+            };
+            let other = if lhs == subject {
+                rhs
+            } else if rhs == subject {
+                lhs
+            } else {
+                return false;
+            };
+            op == CmpOp::Ne && self.values.get(&other) == Some(&Interval::point(0))
+        })
+    }
+
+    /// Transfer function: the interval of `node` from its inputs.
+    fn eval(&self, node: NodeId) -> Option<Interval> {
+        let ty = *self.types.get(&node)?;
+        let full = Interval::of_type(ty)?;
+        let op = self.op(node)?;
+        match op {
+            ComputeNodeOp::Core(ComputeOp::Const { value }) => Some(Interval::point(match value {
+                ConstValue::I8(v) => *v as i128,
+                ConstValue::I16(v) => *v as i128,
+                ConstValue::I32(v) => *v as i128,
+                ConstValue::I64(v) => *v as i128,
+                _ => return Some(full),
+            })),
+            ComputeNodeOp::Core(ComputeOp::Parameter { index }) => {
+                let base = match self.overrides.get(index) {
+                    Some(over) => (*over)?.meet(full)?,
+                    None => full,
+                };
+                self.refine(node, base, &self.entry_facts)
+            }
+            ComputeNodeOp::Core(ComputeOp::BinaryArith { op }) => {
+                let lhs = self.operand(node, 0)?;
+                let rhs = self.operand(node, 1)?;
+                arith(*op, lhs, rhs)?.meet(full)
+            }
+            ComputeNodeOp::Core(ComputeOp::UnaryArith { op }) => {
+                unary(*op, self.operand(node, 0)?).meet(full)
+            }
+            ComputeNodeOp::Core(ComputeOp::Load) => {
+                let (alloc, _) = self.input(node, 0)?;
+                match self.cells.get(&alloc) {
+                    Some(cell) => cell.value?.meet(full),
+                    None => Some(full),
+                }
+            }
+            ComputeNodeOp::Core(ComputeOp::Phi) => {
+                let ports: Vec<u16> = self
+                    .inputs
+                    .get(&node)
+                    .into_iter()
+                    .flatten()
+                    .map(|(p, _, _)| *p)
+                    .collect();
+                ports
+                    .into_iter()
+                    .filter_map(|p| self.operand(node, p))
+                    .reduce(Interval::join)
+            }
+            ComputeNodeOp::Structured(StructuredOp::Cast { .. }) => match self.input(node, 0) {
+                Some((_, from)) if Interval::of_type(from).is_some() => {
+                    let value = self.operand(node, 0)?;
+                    Some(if value.is_within(&full) { value } else { full })
+                }
+                _ => Some(full),
+            },
+            _ => Some(full),
+        }
+    }
+
+    /// Index intervals of this function's `ForRange` loops, per body.
+    /// `None` means the loop never reaches its body.
+    fn loop_index_ranges(&self) -> Vec<(FunctionId, Option<Interval>)> {
+        let mut ranges = Vec::new();
+        for &node in &self.order {
+            let Some(ComputeNodeOp::Core(ComputeOp::ForRange { body })) = self.op(node) else {
+                continue;
+            };
+            let ty = self.input(node, 0).map(|(_, ty)| ty);
+            let range = match (
+                ty.and_then(Interval::of_type),
+                self.operand(node, 0),
+                self.operand(node, 1),
+                self.operand(node, 2),
+            ) {
+                (Some(full), Some(start), Some(end), Some(step)) => {
+                    loop_index(full, start, end, step)
+                }
+                // A bound that never gets a value means the loop never runs
+                (Some(_), ..) => None,
+                _ => continue,
+            };
+            ranges.push((*body, range));
+        }
+        ranges
+    }
+
+    /// Records warnings and proven-safe guarded nodes.
+    fn report(&self, result: &mut RangeAnalysis) {
+        result
+            .intervals
+            .extend(self.values.iter().map(|(&n, &i)| (n, i)));
+
+        for &node in &self.order {
+            let Some(op) = self.op(node) else { continue };
+            let warn = |result: &mut RangeAnalysis, hazard, message: String| {
+                result.warnings.push(RangeWarning {
+                    hazard,
+                    function_id: self.func_id,
+                    node,
+                    message,
+                });
+            };
+            match op {
+                ComputeNodeOp::Core(ComputeOp::BinaryArith { op }) => {
+                    let Some(ty) = self.types.get(&node).copied() else {
+                        continue;
+                    };
+                    let full = Interval::of_type(ty).expect("integer type");
+                    let (Some(lhs), Some(rhs)) = (self.operand(node, 0), self.operand(node, 1))
+                    else {
+                        // Never runs
+                        result.safe_nodes.insert(node);
+                        continue;
+                    };
+                    match op {
+                        ArithOp::Add | ArithOp::Sub | ArithOp::Mul => match arith(*op, lhs, rhs) {
+                            Some(raw) if raw.is_within(&full) => {
+                                result.safe_nodes.insert(node);
+                            }
+                            _ => warn(
+                                result,
+                                RangeHazard::Overflow,
+                                format!(
+                                    "{} at node {} may overflow {}: operands {} and {}",
+                                    arith_name(*op),
+                                    node,
+                                    type_name(ty),
+                                    lhs,
+                                    rhs
+                                ),
+                            ),
+                        },
+                        ArithOp::Div | ArithOp::Rem => {
+                            if rhs.contains(0) && !self.known_nonzero(node, 1) {
+                                warn(
+                                    result,
+                                    RangeHazard::DivisionByZero,
+                                    format!(
+                                        "{} at node {} may divide by zero: divisor {}",
+                                        arith_name(*op),
+                                        node,
+                                        rhs
+                                    ),
+                                );
+                            } else {
+                                result.safe_nodes.insert(node);
+                            }
+                            if lhs.contains(full.lo) && rhs.contains(-1) {
+                                warn(
+                                    result,
+                                    RangeHazard::Overflow,
+                                    format!(
+                                        "{} at node {} may overflow {}: operands {} and {}",
+                                        arith_name(*op),
+                                        node,
+                                        type_name(ty),
+                                        lhs,
+                                        rhs
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                }
+                ComputeNodeOp::Core(ComputeOp::UnaryArith { op }) => {
+                    let Some(ty) = self.types.get(&node).copied() else {
+                        continue;
+                    };
+                    let full = Interval::of_type(ty).expect("integer type");
+                    if let Some(value) = self.operand(node, 0) {
+                        if value.contains(full.lo) {
+                            let name = match op {
+                                UnaryArithOp::Neg => "negation",
+                                UnaryArithOp::Abs => "absolute value",
+                            };
+                            warn(
+                                result,
+                                RangeHazard::Overflow,
+                                format!(
+                                    "{} at node {} may overflow {}: operand {}",
+                                    name,
+                                    node,
+                                    type_name(ty),
+                                    value
+                                ),
+                            );
+                        }
+                    }
+                }
+                ComputeNodeOp::Structured(StructuredOp::ArrayGet | StructuredOp::ArraySet) => {
+                    let length =
+                        self.input(node, 0)
+                            .and_then(|(_, ty)| match self.graph.types.get(ty) {
+                                Some(LmType::Array { length, .. }) => Some(*length as i128),
+                                _ => None,
+                            });
+                    let Some(length) = length else { continue };
+                    if self
+                        .input(node, 1)
+                        .is_none_or(|(_, ty)| Interval::of_type(ty).is_none())
+                    {
+                        continue;
+                    }
+                    match self.operand(node, 1) {
+                        None => {
+                            result.safe_nodes.insert(node);
+                        }
+                        Some(index) => match Interval::new(0, length - 1) {
+                            Some(bounds) if index.is_within(&bounds) => {
+                                result.safe_nodes.insert(node);
+                            }
+                            _ => warn(
+                                result,
+                                RangeHazard::OutOfBounds,
+                                format!(
+                                    "array access at node {} may be out of bounds: index {} for length {}",
+                                    node, index, length
+                                ),
+                            ),
+                        },
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Orders nodes so data inputs come first (Kahn's algorithm over data edges).
+fn data_order(
+    nodes: &[NodeId],
+    inputs: &HashMap<NodeId, Vec<(u16, NodeId, TypeId)>>,
+) -> Vec<NodeId> {
+    let mut in_degree: HashMap<NodeId, usize> = nodes
+        .iter()
+        .map(|n| (*n, inputs.get(n).map_or(0, Vec::len)))
+        .collect();
+    let mut users: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+    for (&node, edges) in inputs {
+        for (_, source, _) in edges {
+            users.entry(*source).or_default().push(node);
+        }
+    }
+
+    let mut queue: VecDeque<NodeId> = nodes
+        .iter()
+        .copied()
+        .filter(|n| in_degree[n] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(nodes.len());
+    let mut placed: HashSet<NodeId> = HashSet::new();
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        placed.insert(node);
+        for &user in users.get(&node).into_iter().flatten() {
+            let degree = in_degree.get_mut(&user).expect("user is a function node");
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push_back(user);
+            }
+        }
+    }
+    order.extend(nodes.iter().filter(|n| !placed.contains(n)));
+    order
+}
+
+/// Index values a `ForRange` body can see, matching the interpreter and
+/// codegen: ascending while `index < end` for a positive step, descending
+/// while `index > end` for a negative one, wrapping on overflow.
+fn loop_index(full: Interval, start: Interval, end: Interval, step: Interval) -> Option<Interval> {
+    let mut range: Option<Interval> = None;
+    let mut add = |part: Option<Interval>| {
+        if let Some(part) = part {
+            range = Some(range.map_or(part, |r| r.join(part)));
+        }
+    };
+    if step.hi > 0 {
+        if end.hi - 1 + step.hi > full.hi {
+            return Some(full);
+        }
+        add(Interval::new(start.lo, end.hi - 1));
+    }
+    if step.lo < 0 {
+        if end.lo + 1 + step.lo < full.lo {
+            return Some(full);
+        }
+        add(Interval::new(end.lo + 1, start.hi));
+    }
+    range
+}
+
+fn arith(op: ArithOp, lhs: Interval, rhs: Interval) -> Option<Interval> {
+    let corners = |f: fn(i128, i128) -> i128, a: Interval, b: Interval| {
+        let values = [f(a.lo, b.lo), f(a.lo, b.hi), f(a.hi, b.lo), f(a.hi, b.hi)];
+        Interval {
+            lo: *values.iter().min().expect("four corners"),
+            hi: *values.iter().max().expect("four corners"),
+        }
+    };
+    // Divisors split into their negative and positive parts; zero traps
+    let divisors = [
+        Interval::new(rhs.lo, rhs.hi.min(-1)),
+        Interval::new(rhs.lo.max(1), rhs.hi),
+    ];
+    match op {
+        ArithOp::Add => Some(Interval {
+            lo: lhs.lo + rhs.lo,
+            hi: lhs.hi + rhs.hi,
+        }),
+        ArithOp::Sub => Some(Interval {
+            lo: lhs.lo - rhs.hi,
+            hi: lhs.hi - rhs.lo,
+        }),
+        ArithOp::Mul => Some(corners(|a, b| a * b, lhs, rhs)),
+        ArithOp::Div => divisors
+            .into_iter()
+            .flatten()
+            .map(|d| corners(|a, b| a / b, lhs, d))
+            .reduce(Interval::join),
+        ArithOp::Rem => {
+            let magnitude = divisors
+                .into_iter()
+                .flatten()
+                .map(|d| d.lo.abs().max(d.hi.abs()))
+                .max()?;
+            let limit = magnitude - 1;
+            Some(Interval {
+                lo: if lhs.lo >= 0 { 0 } else { lhs.lo.max(-limit) },
+                hi: if lhs.hi <= 0 { 0 } else { lhs.hi.min(limit) },
+            })
+        }
+    }
+}
+
+fn unary(op: UnaryArithOp, value: Interval) -> Interval {
+    match op {
+        UnaryArithOp::Neg => Interval {
+            lo: -value.hi,
+            hi: -value.lo,
+        },
+        UnaryArithOp::Abs if value.lo >= 0 => value,
+        UnaryArithOp::Abs if value.hi <= 0 => Interval {
+            lo: -value.hi,
+            hi: -value.lo,
+        },
+        UnaryArithOp::Abs => Interval {
+            lo: 0,
+            hi: value.hi.max(-value.lo),
+        },
+    }
+}
+
+/// Narrows `value` to the values `v` with `v <op> other` for some `other`
+/// value in `other`.
+fn constrain(value: Interval, op: CmpOp, other: Interval) -> Option<Interval> {
+    match op {
+        CmpOp::Lt => Interval::new(value.lo, value.hi.min(other.hi - 1)),
+        CmpOp::Le => Interval::new(value.lo, value.hi.min(other.hi)),
+        CmpOp::Gt => Interval::new(value.lo.max(other.lo + 1), value.hi),
+        CmpOp::Ge => Interval::new(value.lo.max(other.lo), value.hi),
+        CmpOp::Eq => value.meet(other),
+        CmpOp::Ne if other.lo == other.hi && other.lo == value.lo => {
+            Interval::new(value.lo + 1, value.hi)
+        }
+        CmpOp::Ne if other.lo == other.hi && other.lo == value.hi => {
+            Interval::new(value.lo, value.hi - 1)
+        }
+        CmpOp::Ne => Some(value),
+    }
+}
+
+/// The comparison that holds when `op` does not.
+pub(crate) fn negate(op: CmpOp) -> CmpOp {
+    match op {
+        CmpOp::Eq => CmpOp::Ne,
+        CmpOp::Ne => CmpOp::Eq,
+        CmpOp::Lt => CmpOp::Ge,
+        CmpOp::Le => CmpOp::Gt,
+        CmpOp::Gt => CmpOp::Le,
+        CmpOp::Ge => CmpOp::Lt,
+    }
+}
+
+/// `a <op> b` as `b <flip(op)> a`.
+pub(crate) fn flip(op: CmpOp) -> CmpOp {
+    match op {
+        CmpOp::Lt => CmpOp::Gt,
+        CmpOp::Le => CmpOp::Ge,
+        CmpOp::Gt => CmpOp::Lt,
+        CmpOp::Ge => CmpOp::Le,
+        other => other,
+    }
+}
+
+fn arith_name(op: ArithOp) -> &'static str {
+    match op {
+        ArithOp::Add => "addition",
+        ArithOp::Sub => "subtraction",
+        ArithOp::Mul => "multiplication",
+        ArithOp::Div => "division",
+        ArithOp::Rem => "remainder",
+    }
+}
+
+fn type_name(ty: TypeId) -> &'static str {
+    match ty {
+        TypeId::I8 => "i8",
+        TypeId::I16 => "i16",
+        TypeId::I32 => "i32",
+        _ => "i64",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lmlang_core::types::Visibility;
+
+    fn function(
+        graph: &mut ProgramGraph,
+        name: &str,
+        params: Vec<TypeId>,
+        ret: TypeId,
+    ) -> FunctionId {
+        let root = graph.modules.root_id();
+        let params = params
+            .into_iter()
+            .enumerate()
+            .map(|(i, ty)| (format!("p{i}"), ty))
+            .collect();
+        graph
+            .add_function(name.into(), root, params, ret, Visibility::Public)
+            .unwrap()
+    }
+
+    fn op(graph: &mut ProgramGraph, func_id: FunctionId, op: ComputeOp) -> NodeId {
+        graph.add_core_op(op, func_id).unwrap()
+    }
+
+    fn i32_const(graph: &mut ProgramGraph, func_id: FunctionId, value: i32) -> NodeId {
+        op(
+            graph,
+            func_id,
+            ComputeOp::Const {
+                value: ConstValue::I32(value),
+            },
+        )
+    }
+
+    fn compare(
+        graph: &mut ProgramGraph,
+        func_id: FunctionId,
+        cmp: CmpOp,
+        lhs: NodeId,
+        rhs: NodeId,
+    ) -> NodeId {
+        let node = op(graph, func_id, ComputeOp::Compare { op: cmp });
+        graph.add_data_edge(lhs, node, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(rhs, node, 0, 1, TypeId::I32).unwrap();
+        node
+    }
+
+    fn binary(
+        graph: &mut ProgramGraph,
+        func_id: FunctionId,
+        arith_op: ArithOp,
+        lhs: NodeId,
+        rhs: NodeId,
+    ) -> NodeId {
+        let node = op(graph, func_id, ComputeOp::BinaryArith { op: arith_op });
+        graph.add_data_edge(lhs, node, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(rhs, node, 0, 1, TypeId::I32).unwrap();
+        node
+    }
+
+    /// Helper: `f(a: i32) -> i32` returning `a + 1`, optionally with the
+    /// precondition `a < limit`.
+    fn build_increment(limit: Option<i32>) -> (ProgramGraph, NodeId) {
+        let mut graph = ProgramGraph::new("test");
+        let f = function(&mut graph, "f", vec![TypeId::I32], TypeId::I32);
+        let a = op(&mut graph, f, ComputeOp::Parameter { index: 0 });
+        let one = i32_const(&mut graph, f, 1);
+        let sum = binary(&mut graph, f, ArithOp::Add, a, one);
+        let ret = op(&mut graph, f, ComputeOp::Return);
+        graph.add_data_edge(sum, ret, 0, 0, TypeId::I32).unwrap();
+        if let Some(limit) = limit {
+            let bound = i32_const(&mut graph, f, limit);
+            let cmp = compare(&mut graph, f, CmpOp::Lt, a, bound);
+            let pre = op(
+                &mut graph,
+                f,
+                ComputeOp::Precondition {
+                    message: "a < limit".into(),
+                },
+            );
+            graph.add_data_edge(cmp, pre, 0, 0, TypeId::BOOL).unwrap();
+            graph.add_control_edge(pre, ret, None).unwrap();
+        }
+        (graph, sum)
+    }
+
+    #[test]
+    fn precondition_proves_addition_safe() {
+        let (graph, sum) = build_increment(Some(100));
+        let ranges = analyze_ranges(&graph, true);
+        assert!(ranges.is_safe(sum));
+        assert!(ranges.warnings.is_empty());
+        assert_eq!(
+            ranges.intervals[&sum],
+            Interval::new(i32::MIN as i128 + 1, 100).unwrap()
+        );
+
+        // Unless preconditions are checked, callers may break them
+        let ranges = analyze_ranges(&graph, false);
+        assert!(!ranges.is_safe(sum));
+        assert_eq!(ranges.warnings.len(), 1);
+    }
+
+    #[test]
+    fn unbounded_addition_warns() {
+        let (graph, sum) = build_increment(None);
+        let ranges = analyze_ranges(&graph, true);
+        assert!(!ranges.is_safe(sum));
+        assert_eq!(ranges.warnings.len(), 1);
+        assert_eq!(ranges.warnings[0].hazard, RangeHazard::Overflow);
+        assert_eq!(ranges.warnings[0].node, sum);
+    }
+
+    #[test]
+    fn branch_condition_proves_divisor_nonzero() {
+        // f(a, b) = if b != 0 { a / b } else { a / b }
+        let mut graph = ProgramGraph::new("test");
+        let f = function(&mut graph, "f", vec![TypeId::I32, TypeId::I32], TypeId::I32);
+        let a = op(&mut graph, f, ComputeOp::Parameter { index: 0 });
+        let b = op(&mut graph, f, ComputeOp::Parameter { index: 1 });
+        let zero = i32_const(&mut graph, f, 0);
+        let cmp = compare(&mut graph, f, CmpOp::Ne, b, zero);
+        let branch = op(&mut graph, f, ComputeOp::Branch);
+        graph
+            .add_data_edge(cmp, branch, 0, 0, TypeId::BOOL)
+            .unwrap();
+
+        let guarded = binary(&mut graph, f, ArithOp::Div, a, b);
+        graph.add_control_edge(branch, guarded, Some(0)).unwrap();
+        let unguarded = binary(&mut graph, f, ArithOp::Rem, a, b);
+        graph.add_control_edge(branch, unguarded, Some(1)).unwrap();
+
+        let ranges = analyze_ranges(&graph, true);
+        assert!(ranges.is_safe(guarded));
+        assert!(!ranges.is_safe(unguarded));
+        // The guarded division can still overflow on MIN / -1
+        let hazards: Vec<(NodeId, RangeHazard)> =
+            ranges.warnings.iter().map(|w| (w.node, w.hazard)).collect();
+        assert_eq!(
+            hazards,
+            vec![
+                (guarded, RangeHazard::Overflow),
+                (unguarded, RangeHazard::DivisionByZero),
+            ]
+        );
+        // On the false edge b is exactly zero, so the remainder never returns
+        assert!(!ranges.intervals.contains_key(&unguarded));
+    }
+
+    #[test]
+    fn loop_counter_converges_with_widening() {
+        // f(n) with n <= 100: i = 1; sum = 0; while i <= n { sum += i; i += 1 }
+        let mut graph = ProgramGraph::new("test");
+        let f = function(&mut graph, "f", vec![TypeId::I32], TypeId::I32);
+        let n = op(&mut graph, f, ComputeOp::Parameter { index: 0 });
+        let hundred = i32_const(&mut graph, f, 100);
+        let pre_cmp = compare(&mut graph, f, CmpOp::Le, n, hundred);
+        let pre = op(
+            &mut graph,
+            f,
+            ComputeOp::Precondition {
+                message: "n <= 100".into(),
+            },
+        );
+        graph
+            .add_data_edge(pre_cmp, pre, 0, 0, TypeId::BOOL)
+            .unwrap();
+
+        let cell_i = op(&mut graph, f, ComputeOp::Alloc);
+        let cell_sum = op(&mut graph, f, ComputeOp::Alloc);
+        let store = |graph: &mut ProgramGraph, cell: NodeId, value: NodeId| {
+            let node = op(graph, f, ComputeOp::Store);
+            graph.add_data_edge(cell, node, 0, 0, TypeId::I32).unwrap();
+            graph.add_data_edge(value, node, 0, 1, TypeId::I32).unwrap();
+            node
+        };
+        let load = |graph: &mut ProgramGraph, cell: NodeId| {
+            let node = op(graph, f, ComputeOp::Load);
+            graph.add_data_edge(cell, node, 0, 0, TypeId::I32).unwrap();
+            node
+        };
+
+        let one = i32_const(&mut graph, f, 1);
+        let zero = i32_const(&mut graph, f, 0);
+        let init_i = store(&mut graph, cell_i, one);
+        store(&mut graph, cell_sum, zero);
+
+        let i = load(&mut graph, cell_i);
+        graph.add_control_edge(init_i, i, None).unwrap();
+        let cond = compare(&mut graph, f, CmpOp::Le, i, n);
+        let loop_node = op(&mut graph, f, ComputeOp::Loop);
+        graph
+            .add_data_edge(cond, loop_node, 0, 0, TypeId::BOOL)
+            .unwrap();
+
+        let sum = load(&mut graph, cell_sum);
+        graph.add_control_edge(loop_node, sum, Some(0)).unwrap();
+        let new_sum = binary(&mut graph, f, ArithOp::Add, sum, i);
+        store(&mut graph, cell_sum, new_sum);
+        let next_i = binary(&mut graph, f, ArithOp::Add, i, one);
+        graph.add_control_edge(loop_node, next_i, Some(0)).unwrap();
+        let step_i = store(&mut graph, cell_i, next_i);
+        graph.add_control_edge(step_i, i, None).unwrap();
+
+        let result = load(&mut graph, cell_sum);
+        graph.add_control_edge(loop_node, result, Some(1)).unwrap();
+        let ret = op(&mut graph, f, ComputeOp::Return);
+        graph.add_data_edge(result, ret, 0, 0, TypeId::I32).unwrap();
+
+        let ranges = analyze_ranges(&graph, true);
+        // Narrowing recovers the bound on i implied by `i <= n`
+        assert_eq!(ranges.intervals[&i], Interval::new(1, 101).unwrap());
+        assert_eq!(ranges.intervals[&next_i], Interval::new(2, 101).unwrap());
+        assert!(ranges.is_safe(next_i));
+        // The running sum is widened and cannot be bounded
+        assert!(!ranges.is_safe(new_sum));
+        assert_eq!(ranges.warnings.len(), 1);
+        assert_eq!(ranges.warnings[0].node, new_sum);
+    }
+
+    #[test]
+    fn for_range_index_proves_array_access_in_bounds() {
+        let mut graph = ProgramGraph::new("test");
+        let array_ty = graph.types.register(LmType::Array {
+            element: TypeId::I32,
+            length: 4,
+        });
+        let body = function(&mut graph, "body", vec![TypeId::I64, array_ty], array_ty);
+        let main = function(&mut graph, "main", vec![array_ty], array_ty);
+
+        // body(i, acc) = acc with acc[i] = 0
+        let index = op(&mut graph, body, ComputeOp::Parameter { index: 0 });
+        let acc = op(&mut graph, body, ComputeOp::Parameter { index: 1 });
+        let zero = i32_const(&mut graph, body, 0);
+        let set = graph
+            .add_structured_op(StructuredOp::ArraySet, body)
+            .unwrap();
+        graph.add_data_edge(acc, set, 0, 0, array_ty).unwrap();
+        graph.add_data_edge(index, set, 0, 1, TypeId::I64).unwrap();
+        graph.add_data_edge(zero, set, 0, 2, TypeId::I32).unwrap();
+        let ret = op(&mut graph, body, ComputeOp::Return);
+        graph.add_data_edge(set, ret, 0, 0, array_ty).unwrap();
+
+        // main(xs) = for i in 0..4 { body }
+        let xs = op(&mut graph, main, ComputeOp::Parameter { index: 0 });
+        let bounds: Vec<NodeId> = [0, 4, 1]
+            .into_iter()
+            .map(|v| {
+                op(
+                    &mut graph,
+                    main,
+                    ComputeOp::Const {
+                        value: ConstValue::I64(v),
+                    },
+                )
+            })
+            .collect();
+        let for_range = op(&mut graph, main, ComputeOp::ForRange { body });
+        for (port, bound) in bounds.iter().enumerate() {
+            graph
+                .add_data_edge(*bound, for_range, 0, port as u16, TypeId::I64)
+                .unwrap();
+        }
+        graph.add_data_edge(xs, for_range, 0, 3, array_ty).unwrap();
+        let main_ret = op(&mut graph, main, ComputeOp::Return);
+        graph
+            .add_data_edge(for_range, main_ret, 0, 0, array_ty)
+            .unwrap();
+
+        let ranges = analyze_ranges(&graph, true);
+        assert_eq!(ranges.intervals[&index], Interval::new(0, 3).unwrap());
+        assert!(ranges.is_safe(set));
+        assert!(ranges.warnings.is_empty());
+    }
+
+    #[test]
+    fn interval_arithmetic_handles_signs() {
+        let i = |lo, hi| Interval::new(lo, hi).unwrap();
+        assert_eq!(arith(ArithOp::Mul, i(-3, 2), i(-5, 4)), Some(i(-12, 15)));
+        assert_eq!(arith(ArithOp::Div, i(-10, 10), i(-2, 5)), Some(i(-10, 10)));
+        assert_eq!(arith(ArithOp::Div, i(1, 1), i(0, 0)), None);
+        assert_eq!(arith(ArithOp::Rem, i(-7, 20), i(3, 5)), Some(i(-4, 4)));
+        assert_eq!(unary(UnaryArithOp::Abs, i(-8, 3)), i(0, 8));
+        assert_eq!(
+            loop_index(i(-128, 127), i(0, 0), i(10, 10), i(2, 2)),
+            Some(i(0, 9))
+        );
+        // A step that can carry the index past the type's maximum wraps
+        assert_eq!(
+            loop_index(i(-128, 127), i(0, 0), i(127, 127), i(2, 2)),
+            Some(i(-128, 127))
+        );
+    }
+}
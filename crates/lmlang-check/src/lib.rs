@@ -1,3 +1,8 @@
+pub mod borrow;
 pub mod contracts;
+pub mod effects;
 pub mod interpreter;
+pub mod intervals;
+pub mod termination;
+pub mod test_runner;
 pub mod typecheck;
@@ -0,0 +1,1031 @@
+//! Termination and loop-bound analysis.
+//!
+//! Each low-level `Loop` is classified from its condition. The loop
+//! terminates if the condition has a `Compare` between an induction variable
+//! and a loop-invariant bound, with the variable moving toward the bound on
+//! every iteration (`i < n` with `i` only ever increased, `i >= 0` with `i`
+//! only ever decreased), so `n - i` is a ranking function. An induction
+//! variable is either:
+//! - a `Load` of a memory cell whose stores in the loop body are all
+//!   `load + c` (or all `load - c`), at least one of them unconditional; or
+//! - a `Phi` whose inputs from the loop body are all `phi + c` (or all
+//!   `phi - c`).
+//!
+//! When the range analysis bounds both the variable's start and the bound,
+//! the loop is bounded by the iteration count they allow. Any other loop is
+//! unknown, including one whose condition never changes in its body.
+//!
+//! Recursive cycles in the call graph are checked the same way: some
+//! parameter must be passed as `param - c` (or `param + c`) at every call
+//! back into the cycle, and the range analysis must bound that argument on
+//! the side it moves toward, as a base-case guard like `n > 0` does.
+//!
+//! Structured loops (`ForRange`, `ForEach`, array ops) always terminate and
+//! are not reported. Calls through closures are not followed.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+
+use lmlang_core::edge::FlowEdge;
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_core::ops::{ArithOp, CmpOp, ComputeNodeOp, ComputeOp, LogicOp, StructuredOp};
+use lmlang_core::types::ConstValue;
+
+use crate::intervals::{flip, negate, Interval, RangeAnalysis};
+
+/// How a loop or recursive cycle was classified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// Terminates, with no bound derived on its iterations.
+    Terminating,
+    /// Terminates after at most `max_iterations` iterations.
+    Bounded { max_iterations: u128 },
+    /// No ranking function was found.
+    Unknown,
+}
+
+/// Classification of one `Loop` node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopReport {
+    pub function_id: FunctionId,
+    pub loop_node: NodeId,
+    pub termination: Termination,
+    /// `Compare` nodes of the loop condition, sorted.
+    pub compares: Vec<NodeId>,
+    /// Induction variables (`Phi`s and cell `Load`s) those comparisons read,
+    /// sorted.
+    pub induction_variables: Vec<NodeId>,
+    pub message: String,
+}
+
+/// Classification of one recursive cycle of the call graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecursionReport {
+    /// Functions of the cycle, sorted.
+    pub functions: Vec<FunctionId>,
+    /// Calls from the cycle back into it, sorted.
+    pub calls: Vec<NodeId>,
+    pub termination: Termination,
+    /// Index of the parameter that moves toward a bound at every call.
+    pub parameter: Option<u32>,
+    pub message: String,
+}
+
+/// What a termination warning is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminationHazard {
+    /// A `Loop` may never exit.
+    Loop,
+    /// A recursive cycle may never reach a base case.
+    Recursion,
+}
+
+/// A loop or recursive cycle that may not terminate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminationWarning {
+    pub hazard: TerminationHazard,
+    pub function_id: FunctionId,
+    /// The `Loop` node, or the first recursive call.
+    pub node: NodeId,
+    /// The loop's `Compare` and induction nodes, or every recursive call.
+    pub related: Vec<NodeId>,
+    pub message: String,
+}
+
+/// Result of [`analyze_termination`].
+#[derive(Debug, Clone, Default)]
+pub struct TerminationAnalysis {
+    /// Every `Loop`, sorted by node.
+    pub loops: Vec<LoopReport>,
+    /// Every recursive cycle, sorted by its first function.
+    pub recursion: Vec<RecursionReport>,
+}
+
+impl TerminationAnalysis {
+    /// Loops and recursive cycles classified as unknown, sorted by node.
+    pub fn warnings(&self) -> Vec<TerminationWarning> {
+        let mut warnings: Vec<TerminationWarning> = self
+            .loops
+            .iter()
+            .filter(|l| l.termination == Termination::Unknown)
+            .map(|l| {
+                let mut related = l.compares.clone();
+                related.extend(&l.induction_variables);
+                related.sort_by_key(|n| n.0);
+                TerminationWarning {
+                    hazard: TerminationHazard::Loop,
+                    function_id: l.function_id,
+                    node: l.loop_node,
+                    related,
+                    message: l.message.clone(),
+                }
+            })
+            .collect();
+        warnings.extend(
+            self.recursion
+                .iter()
+                .filter(|r| r.termination == Termination::Unknown)
+                .map(|r| TerminationWarning {
+                    hazard: TerminationHazard::Recursion,
+                    function_id: r.functions[0],
+                    node: r.calls[0],
+                    related: r.calls.clone(),
+                    message: r.message.clone(),
+                }),
+        );
+        warnings.sort_by_key(|w| w.node.0);
+        warnings
+    }
+}
+
+/// Classifies every `Loop` and recursive cycle in the graph, using `ranges`
+/// to bound induction variables and recursive arguments.
+pub fn analyze_termination(graph: &ProgramGraph, ranges: &RangeAnalysis) -> TerminationAnalysis {
+    let mut function_ids: Vec<FunctionId> = graph.functions().keys().copied().collect();
+    function_ids.sort_by_key(|f| f.0);
+
+    let mut loops = Vec::new();
+    for &func_id in &function_ids {
+        let view = FunctionView::new(graph, func_id);
+        for node in graph.function_nodes_sorted(func_id) {
+            if let Some(ComputeNodeOp::Core(ComputeOp::Loop)) = view.op(node) {
+                loops.push(view.classify_loop(node, ranges));
+            }
+        }
+    }
+
+    TerminationAnalysis {
+        loops,
+        recursion: recursion_reports(graph, &function_ids, ranges),
+    }
+}
+
+/// Termination of a condition: `None` if unknown, otherwise the bound on
+/// iterations, if any.
+type Verdict = Option<Option<u128>>;
+
+/// How an induction candidate changes across iterations.
+enum Induction {
+    /// Moves by at least this much, with this sign, on every iteration.
+    Step(i128),
+    /// Has the same value on every iteration.
+    Unchanged,
+    /// Changes in some other way.
+    Irregular,
+}
+
+/// Nodes found while classifying a loop condition.
+#[derive(Default)]
+struct Found {
+    compares: Vec<NodeId>,
+    induction_variables: Vec<NodeId>,
+    /// Whether any compared value changes in the loop body.
+    changing: bool,
+}
+
+struct Loop<'a> {
+    /// Nodes evaluated on each iteration.
+    body: HashSet<NodeId>,
+    /// Body nodes that only run on some iterations.
+    conditional: HashSet<NodeId>,
+    ranges: &'a RangeAnalysis,
+}
+
+struct FunctionView<'g> {
+    graph: &'g ProgramGraph,
+    func_id: FunctionId,
+    /// Incoming data edges per node: `(target_port, source)`.
+    inputs: HashMap<NodeId, Vec<(u16, NodeId)>>,
+}
+
+impl<'g> FunctionView<'g> {
+    fn new(graph: &'g ProgramGraph, func_id: FunctionId) -> Self {
+        let mut inputs: HashMap<NodeId, Vec<(u16, NodeId)>> = HashMap::new();
+        for node in graph.function_nodes(func_id) {
+            for edge in graph
+                .compute()
+                .edges_directed(node.into(), Direction::Incoming)
+            {
+                if let FlowEdge::Data { target_port, .. } = edge.weight() {
+                    inputs
+                        .entry(node)
+                        .or_default()
+                        .push((*target_port, NodeId::from(edge.source())));
+                }
+            }
+        }
+        FunctionView {
+            graph,
+            func_id,
+            inputs,
+        }
+    }
+
+    fn op(&self, node: NodeId) -> Option<&'g ComputeNodeOp> {
+        self.graph.get_compute_node(node).map(|n| &n.op)
+    }
+
+    fn input(&self, node: NodeId, port: u16) -> Option<NodeId> {
+        self.inputs
+            .get(&node)?
+            .iter()
+            .find(|(p, _)| *p == port)
+            .map(|(_, source)| *source)
+    }
+
+    /// Targets of `node`'s outgoing edges in this function, with the branch
+    /// index of control edges.
+    fn successors(&self, node: NodeId) -> Vec<(NodeId, Option<u16>)> {
+        self.graph
+            .compute()
+            .edges_directed(node.into(), Direction::Outgoing)
+            .filter(|edge| {
+                self.graph
+                    .get_compute_node(NodeId::from(edge.target()))
+                    .is_some_and(|n| n.owner == self.func_id)
+            })
+            .map(|edge| {
+                let branch = match edge.weight() {
+                    FlowEdge::Control { branch_index } => *branch_index,
+                    FlowEdge::Data { .. } => None,
+                };
+                (NodeId::from(edge.target()), branch)
+            })
+            .collect()
+    }
+
+    /// Nodes reachable from `starts`, stopping at `stop`.
+    fn reachable(&self, starts: Vec<NodeId>, stop: NodeId) -> HashSet<NodeId> {
+        let mut seen = HashSet::new();
+        let mut queue: VecDeque<NodeId> = starts.into();
+        while let Some(node) = queue.pop_front() {
+            if node == stop || !seen.insert(node) {
+                continue;
+            }
+            queue.extend(self.successors(node).into_iter().map(|(n, _)| n));
+        }
+        seen
+    }
+
+    /// Targets of `node`'s control edges taking branch `index`.
+    fn branch_targets(&self, node: NodeId, index: u16) -> Vec<NodeId> {
+        self.successors(node)
+            .into_iter()
+            .filter(|(_, branch)| *branch == Some(index))
+            .map(|(n, _)| n)
+            .collect()
+    }
+
+    /// The memory cell a `Load` reads.
+    fn cell_of(&self, node: NodeId) -> Option<NodeId> {
+        let Some(ComputeNodeOp::Core(ComputeOp::Load)) = self.op(node) else {
+            return None;
+        };
+        let cell = self.input(node, 0)?;
+        matches!(self.op(cell), Some(ComputeNodeOp::Core(ComputeOp::Alloc))).then_some(cell)
+    }
+
+    /// `Store`s to `cell` among `nodes`.
+    fn stores_to(&self, cell: NodeId, nodes: &HashSet<NodeId>) -> Vec<NodeId> {
+        self.successors(cell)
+            .into_iter()
+            .map(|(n, _)| n)
+            .filter(|n| {
+                nodes.contains(n)
+                    && matches!(self.op(*n), Some(ComputeNodeOp::Core(ComputeOp::Store)))
+                    && self.input(*n, 0) == Some(cell)
+            })
+            .collect()
+    }
+
+    fn constant(&self, node: NodeId) -> Option<i128> {
+        match self.op(node)? {
+            ComputeNodeOp::Core(ComputeOp::Const { value }) => match value {
+                ConstValue::I8(v) => Some(*v as i128),
+                ConstValue::I16(v) => Some(*v as i128),
+                ConstValue::I32(v) => Some(*v as i128),
+                ConstValue::I64(v) => Some(*v as i128),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// `c` if `value` is `x + c`, `c + x` or `x - c` (as `-c`) for an `x`
+    /// matching `is_var` and a nonzero constant `c`.
+    fn step(&self, value: NodeId, is_var: impl Fn(NodeId) -> bool) -> Option<i128> {
+        let ComputeNodeOp::Core(ComputeOp::BinaryArith { op }) = self.op(value)? else {
+            return None;
+        };
+        let (lhs, rhs) = (self.input(value, 0)?, self.input(value, 1)?);
+        let step = match op {
+            ArithOp::Add if is_var(lhs) => self.constant(rhs)?,
+            ArithOp::Add if is_var(rhs) => self.constant(lhs)?,
+            ArithOp::Sub if is_var(lhs) => -self.constant(rhs)?,
+            _ => return None,
+        };
+        (step != 0).then_some(step)
+    }
+
+    fn classify_loop(&self, loop_node: NodeId, ranges: &RangeAnalysis) -> LoopReport {
+        let body = self.reachable(self.branch_targets(loop_node, 0), loop_node);
+        // Nodes reached from some arms of a body branch but not all of them
+        let mut conditional = HashSet::new();
+        for &node in &body {
+            if !matches!(
+                self.op(node),
+                Some(ComputeNodeOp::Core(
+                    ComputeOp::Branch | ComputeOp::IfElse | ComputeOp::Match | ComputeOp::Loop
+                ))
+            ) {
+                continue;
+            }
+            let mut indices: Vec<u16> = self
+                .successors(node)
+                .into_iter()
+                .filter_map(|(_, branch)| branch)
+                .collect();
+            indices.sort_unstable();
+            indices.dedup();
+            let arms: Vec<HashSet<NodeId>> = indices
+                .into_iter()
+                .map(|i| self.reachable(self.branch_targets(node, i), loop_node))
+                .collect();
+            let every = arms
+                .iter()
+                .skip(1)
+                .fold(arms.first().cloned().unwrap_or_default(), |acc, arm| {
+                    acc.intersection(arm).copied().collect()
+                });
+            conditional.extend(arms.iter().flatten().filter(|n| !every.contains(n)));
+        }
+        let lp = Loop {
+            body,
+            conditional,
+            ranges,
+        };
+
+        let mut found = Found::default();
+        let verdict = match self.input(loop_node, 0) {
+            Some(cond) => self.condition(&lp, cond, true, &mut found),
+            None => None,
+        };
+        found.compares.sort_by_key(|n| n.0);
+        found.compares.dedup();
+        found.induction_variables.sort_by_key(|n| n.0);
+        found.induction_variables.dedup();
+
+        let (termination, message) = match verdict {
+            Some(Some(max_iterations)) => (
+                Termination::Bounded { max_iterations },
+                format!(
+                    "loop at node {} runs at most {} iterations",
+                    loop_node, max_iterations
+                ),
+            ),
+            Some(None) => (
+                Termination::Terminating,
+                format!(
+                    "loop at node {} terminates: an induction variable moves toward a loop-invariant bound",
+                    loop_node
+                ),
+            ),
+            None if !found.changing => (
+                Termination::Unknown,
+                format!(
+                    "loop at node {} may never exit: its condition does not change in the loop body",
+                    loop_node
+                ),
+            ),
+            None => (
+                Termination::Unknown,
+                format!(
+                    "loop at node {} may never exit: no induction variable moves monotonically toward a loop-invariant bound",
+                    loop_node
+                ),
+            ),
+        };
+        LoopReport {
+            function_id: self.func_id,
+            loop_node,
+            termination,
+            compares: found.compares,
+            induction_variables: found.induction_variables,
+            message,
+        }
+    }
+
+    /// Whether the loop exits when `cond` stops evaluating to `continues`.
+    fn condition(&self, lp: &Loop, cond: NodeId, continues: bool, found: &mut Found) -> Verdict {
+        match self.op(cond)? {
+            ComputeNodeOp::Core(ComputeOp::Compare { op }) => {
+                found.compares.push(cond);
+                let op = if continues { *op } else { negate(*op) };
+                let (lhs, rhs) = (self.input(cond, 0)?, self.input(cond, 1)?);
+                let forward = self.comparison(lp, lhs, rhs, op, found);
+                let backward = self.comparison(lp, rhs, lhs, flip(op), found);
+                forward.or(backward)
+            }
+            ComputeNodeOp::Core(ComputeOp::Not) => {
+                self.condition(lp, self.input(cond, 0)?, !continues, found)
+            }
+            ComputeNodeOp::Core(ComputeOp::BinaryLogic {
+                op: op @ (LogicOp::And | LogicOp::Or),
+            }) => {
+                let (lhs, rhs) = (self.input(cond, 0)?, self.input(cond, 1)?);
+                let lhs = self.condition(lp, lhs, continues, found);
+                let rhs = self.condition(lp, rhs, continues, found);
+                // Continuing needs both sides to keep holding
+                let both = matches!((op, continues), (LogicOp::And, true) | (LogicOp::Or, false));
+                if both {
+                    match (lhs, rhs) {
+                        (Some(Some(a)), Some(Some(b))) => Some(Some(a.min(b))),
+                        (Some(a), Some(b)) => Some(a.or(b)),
+                        (a, b) => a.or(b),
+                    }
+                } else {
+                    match (lhs?, rhs?) {
+                        (Some(a), Some(b)) => Some(Some(a.max(b))),
+                        _ => Some(None),
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether `var <op> bound` eventually fails, with a bound on iterations.
+    fn comparison(
+        &self,
+        lp: &Loop,
+        var: NodeId,
+        bound: NodeId,
+        op: CmpOp,
+        found: &mut Found,
+    ) -> Verdict {
+        let step = match self.induction(lp, var) {
+            Induction::Step(step) => step,
+            Induction::Unchanged => return None,
+            Induction::Irregular => {
+                found.changing = true;
+                return None;
+            }
+        };
+        found.changing = true;
+        found.induction_variables.push(var);
+        let toward = match op {
+            CmpOp::Lt | CmpOp::Le => step > 0,
+            CmpOp::Gt | CmpOp::Ge => step < 0,
+            CmpOp::Eq | CmpOp::Ne => false,
+        };
+        if !toward || !self.invariant(lp, bound, &mut HashSet::new()) {
+            return None;
+        }
+        let (Some(start), Some(b)) = (self.start(lp, var), self.interval(lp, bound)) else {
+            return Some(None);
+        };
+        Some(Some(iterations(start, b, op, step)))
+    }
+
+    /// Values `var` can take on entry to the loop.
+    fn start(&self, lp: &Loop, var: NodeId) -> Option<Interval> {
+        match self.op(var)? {
+            // The join of the inputs from outside the body
+            ComputeNodeOp::Core(ComputeOp::Phi) => self
+                .inputs
+                .get(&var)?
+                .iter()
+                .filter(|(_, source)| !lp.body.contains(source))
+                .map(|(_, source)| self.interval(lp, *source))
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .reduce(Interval::join),
+            _ => self.interval(lp, var),
+        }
+    }
+
+    /// The range analysis' interval of `node`, exact for constants.
+    fn interval(&self, lp: &Loop, node: NodeId) -> Option<Interval> {
+        match self.constant(node) {
+            Some(value) => Some(Interval::point(value)),
+            None => lp.ranges.intervals.get(&node).copied(),
+        }
+    }
+
+    fn induction(&self, lp: &Loop, var: NodeId) -> Induction {
+        if let Some(cell) = self.cell_of(var) {
+            let stores = self.stores_to(cell, &lp.body);
+            if stores.is_empty() {
+                return Induction::Unchanged;
+            }
+            let steps: Option<Vec<(i128, bool)>> = stores
+                .iter()
+                .map(|&store| {
+                    let value = self.input(store, 1)?;
+                    let step = self.step(value, |n| self.cell_of(n) == Some(cell))?;
+                    Some((step, lp.conditional.contains(&store)))
+                })
+                .collect();
+            return monotone(steps);
+        }
+        if !lp.body.contains(&var) {
+            return Induction::Unchanged;
+        }
+        if let Some(ComputeNodeOp::Core(ComputeOp::Phi)) = self.op(var) {
+            let steps: Option<Vec<(i128, bool)>> = self
+                .inputs
+                .get(&var)
+                .into_iter()
+                .flatten()
+                .filter(|(_, source)| lp.body.contains(source))
+                .map(|(_, source)| self.step(*source, |n| n == var).map(|s| (s, false)))
+                .collect();
+            return match steps {
+                Some(steps) if steps.is_empty() => Induction::Unchanged,
+                steps => monotone(steps),
+            };
+        }
+        if self.invariant(lp, var, &mut HashSet::new()) {
+            Induction::Unchanged
+        } else {
+            Induction::Irregular
+        }
+    }
+
+    /// Whether `node` has the same value on every iteration.
+    fn invariant(&self, lp: &Loop, node: NodeId, visiting: &mut HashSet<NodeId>) -> bool {
+        if !lp.body.contains(&node) {
+            return true;
+        }
+        if !visiting.insert(node) {
+            return false;
+        }
+        match self.op(node) {
+            Some(ComputeNodeOp::Core(ComputeOp::Const { .. } | ComputeOp::Parameter { .. })) => {
+                true
+            }
+            Some(ComputeNodeOp::Core(ComputeOp::Load)) => self
+                .cell_of(node)
+                .is_some_and(|cell| self.stores_to(cell, &lp.body).is_empty()),
+            Some(
+                ComputeNodeOp::Core(
+                    ComputeOp::BinaryArith { .. }
+                    | ComputeOp::UnaryArith { .. }
+                    | ComputeOp::Compare { .. }
+                    | ComputeOp::BinaryLogic { .. }
+                    | ComputeOp::Not,
+                )
+                | ComputeNodeOp::Structured(StructuredOp::Cast { .. }),
+            ) => {
+                let sources: Vec<NodeId> = self
+                    .inputs
+                    .get(&node)
+                    .into_iter()
+                    .flatten()
+                    .map(|(_, source)| *source)
+                    .collect();
+                sources
+                    .into_iter()
+                    .all(|source| self.invariant(lp, source, visiting))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The step of a variable updated by `steps` (each with whether it only
+/// runs on some iterations), if all move the same way and one always runs.
+fn monotone(steps: Option<Vec<(i128, bool)>>) -> Induction {
+    let Some(steps) = steps else {
+        return Induction::Irregular;
+    };
+    let increasing = steps.iter().all(|(s, _)| *s > 0);
+    let decreasing = steps.iter().all(|(s, _)| *s < 0);
+    let always = steps
+        .iter()
+        .filter(|(_, conditional)| !conditional)
+        .map(|(s, _)| *s)
+        .min_by_key(|s| s.unsigned_abs());
+    match always {
+        Some(step) if increasing || decreasing => Induction::Step(step),
+        _ => Induction::Irregular,
+    }
+}
+
+/// Iterations while `var <op> bound` holds, for `var` starting in `v` and
+/// moving by `step` toward a bound in `b`.
+fn iterations(v: Interval, b: Interval, op: CmpOp, step: i128) -> u128 {
+    let (span, strict) = if step > 0 {
+        (b.hi - v.lo, op == CmpOp::Lt)
+    } else {
+        (v.hi - b.lo, op == CmpOp::Gt)
+    };
+    let step = step.unsigned_abs();
+    match (strict, span) {
+        (true, span) if span > 0 => (span as u128).div_ceil(step),
+        (false, span) if span >= 0 => span as u128 / step + 1,
+        _ => 0,
+    }
+}
+
+/// A call of `target` at `call` in `caller`.
+struct CallSite {
+    caller: FunctionId,
+    call: NodeId,
+    target: FunctionId,
+}
+
+fn recursion_reports(
+    graph: &ProgramGraph,
+    function_ids: &[FunctionId],
+    ranges: &RangeAnalysis,
+) -> Vec<RecursionReport> {
+    let mut call_graph: petgraph::Graph<FunctionId, ()> = petgraph::Graph::new();
+    let index: HashMap<FunctionId, _> = function_ids
+        .iter()
+        .map(|&f| (f, call_graph.add_node(f)))
+        .collect();
+    let mut sites = Vec::new();
+    for &caller in function_ids {
+        for call in graph.function_nodes_sorted(caller) {
+            let Some(ComputeNodeOp::Core(ComputeOp::Call { target })) =
+                graph.get_compute_node(call).map(|n| &n.op)
+            else {
+                continue;
+            };
+            if let Some(&to) = index.get(target) {
+                call_graph.add_edge(index[&caller], to, ());
+                sites.push(CallSite {
+                    caller,
+                    call,
+                    target: *target,
+                });
+            }
+        }
+    }
+
+    let mut views: HashMap<FunctionId, FunctionView> = HashMap::new();
+    let mut reports = Vec::new();
+    for component in petgraph::algo::tarjan_scc(&call_graph) {
+        let mut functions: Vec<FunctionId> = component.iter().map(|&i| call_graph[i]).collect();
+        functions.sort_by_key(|f| f.0);
+        let cycle: Vec<&CallSite> = sites
+            .iter()
+            .filter(|s| functions.contains(&s.caller) && functions.contains(&s.target))
+            .collect();
+        if cycle.is_empty() {
+            continue;
+        }
+        for &f in &functions {
+            views
+                .entry(f)
+                .or_insert_with(|| FunctionView::new(graph, f));
+        }
+
+        let arity = functions
+            .iter()
+            .filter_map(|f| graph.get_function(*f))
+            .map(|f| f.params.len())
+            .min()
+            .unwrap_or(0);
+        let parameter = (0..arity as u16).find_map(|p| {
+            let steps: Option<Vec<i128>> = cycle
+                .iter()
+                .map(|site| recursive_step(graph, &views[&site.caller], site, p, ranges))
+                .collect();
+            let steps = steps?;
+            (steps.iter().all(|s| *s > 0) || steps.iter().all(|s| *s < 0))
+                .then_some((p as u32, steps[0] > 0))
+        });
+
+        let names = functions
+            .iter()
+            .filter_map(|f| graph.get_function(*f))
+            .map(|f| format!("`{}`", f.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (termination, message) = match parameter {
+            Some((p, increasing)) => (
+                Termination::Terminating,
+                format!(
+                    "recursion through {} terminates: parameter {} {} toward a guarded bound at every recursive call",
+                    names,
+                    p,
+                    if increasing { "increases" } else { "decreases" }
+                ),
+            ),
+            None => (
+                Termination::Unknown,
+                format!(
+                    "recursion through {} may not terminate: no parameter moves toward a guarded bound at every recursive call",
+                    names
+                ),
+            ),
+        };
+        let mut calls: Vec<NodeId> = cycle.iter().map(|s| s.call).collect();
+        calls.sort_by_key(|n| n.0);
+        reports.push(RecursionReport {
+            functions,
+            calls,
+            termination,
+            parameter: parameter.map(|(p, _)| p),
+            message,
+        });
+    }
+    reports.sort_by_key(|r| r.functions[0].0);
+    reports
+}
+
+/// The step of parameter `port` at a recursive call, if the argument is
+/// `param ± c` and the range analysis bounds it on the side it moves toward.
+fn recursive_step(
+    graph: &ProgramGraph,
+    view: &FunctionView,
+    site: &CallSite,
+    port: u16,
+    ranges: &RangeAnalysis,
+) -> Option<i128> {
+    let arg = view.input(site.call, port)?;
+    let step = view.step(arg, |n| {
+        matches!(
+            view.op(n),
+            Some(ComputeNodeOp::Core(ComputeOp::Parameter { index })) if *index == port as u32
+        )
+    })?;
+    let ty = graph
+        .get_function(site.target)?
+        .params
+        .get(port as usize)?
+        .1;
+    let full = Interval::of_type(ty)?;
+    match ranges.intervals.get(&arg) {
+        // The call never runs
+        None => Some(step),
+        Some(value) if step < 0 && value.lo > full.lo => Some(step),
+        Some(value) if step > 0 && value.hi < full.hi => Some(step),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intervals::analyze_ranges;
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::Visibility;
+
+    fn function(graph: &mut ProgramGraph, name: &str, params: Vec<TypeId>) -> FunctionId {
+        let root = graph.modules.root_id();
+        let params = params
+            .into_iter()
+            .enumerate()
+            .map(|(i, ty)| (format!("p{i}"), ty))
+            .collect();
+        graph
+            .add_function(name.into(), root, params, TypeId::I32, Visibility::Public)
+            .unwrap()
+    }
+
+    fn op(graph: &mut ProgramGraph, func_id: FunctionId, op: ComputeOp) -> NodeId {
+        graph.add_core_op(op, func_id).unwrap()
+    }
+
+    fn i32_const(graph: &mut ProgramGraph, func_id: FunctionId, value: i32) -> NodeId {
+        op(
+            graph,
+            func_id,
+            ComputeOp::Const {
+                value: ConstValue::I32(value),
+            },
+        )
+    }
+
+    fn binary(
+        graph: &mut ProgramGraph,
+        func_id: FunctionId,
+        arith_op: ArithOp,
+        lhs: NodeId,
+        rhs: NodeId,
+    ) -> NodeId {
+        let node = op(graph, func_id, ComputeOp::BinaryArith { op: arith_op });
+        graph.add_data_edge(lhs, node, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(rhs, node, 0, 1, TypeId::I32).unwrap();
+        node
+    }
+
+    fn compare(
+        graph: &mut ProgramGraph,
+        func_id: FunctionId,
+        cmp: CmpOp,
+        lhs: NodeId,
+        rhs: NodeId,
+    ) -> NodeId {
+        let node = op(graph, func_id, ComputeOp::Compare { op: cmp });
+        graph.add_data_edge(lhs, node, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(rhs, node, 0, 1, TypeId::I32).unwrap();
+        node
+    }
+
+    fn analyze(graph: &ProgramGraph) -> TerminationAnalysis {
+        analyze_termination(graph, &analyze_ranges(graph, true))
+    }
+
+    /// Helper: `f(n)` with `n <= 100` running
+    /// `i = 1; while i <= n { i += step }` when `step` is given, or never
+    /// updating `i` otherwise. Returns the graph, loop, compare and `i`.
+    fn counter_loop(step: Option<i32>) -> (ProgramGraph, NodeId, NodeId, NodeId) {
+        let mut graph = ProgramGraph::new("test");
+        let f = function(&mut graph, "f", vec![TypeId::I32]);
+        let n = op(&mut graph, f, ComputeOp::Parameter { index: 0 });
+        let hundred = i32_const(&mut graph, f, 100);
+        let pre_cmp = compare(&mut graph, f, CmpOp::Le, n, hundred);
+        let pre = op(
+            &mut graph,
+            f,
+            ComputeOp::Precondition {
+                message: "n <= 100".into(),
+            },
+        );
+        graph
+            .add_data_edge(pre_cmp, pre, 0, 0, TypeId::BOOL)
+            .unwrap();
+
+        let cell = op(&mut graph, f, ComputeOp::Alloc);
+        let one = i32_const(&mut graph, f, 1);
+        let init = op(&mut graph, f, ComputeOp::Store);
+        graph.add_data_edge(cell, init, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(one, init, 0, 1, TypeId::I32).unwrap();
+
+        let i = op(&mut graph, f, ComputeOp::Load);
+        graph.add_data_edge(cell, i, 0, 0, TypeId::I32).unwrap();
+        graph.add_control_edge(init, i, None).unwrap();
+        let cond = compare(&mut graph, f, CmpOp::Le, i, n);
+        let loop_node = op(&mut graph, f, ComputeOp::Loop);
+        graph
+            .add_data_edge(cond, loop_node, 0, 0, TypeId::BOOL)
+            .unwrap();
+
+        let print = op(&mut graph, f, ComputeOp::Print);
+        graph.add_data_edge(i, print, 0, 0, TypeId::I32).unwrap();
+        graph.add_control_edge(loop_node, print, Some(0)).unwrap();
+        if let Some(step) = step {
+            let delta = i32_const(&mut graph, f, step.abs());
+            let arith = if step > 0 { ArithOp::Add } else { ArithOp::Sub };
+            let next = binary(&mut graph, f, arith, i, delta);
+            graph.add_control_edge(print, next, None).unwrap();
+            let update = op(&mut graph, f, ComputeOp::Store);
+            graph
+                .add_data_edge(cell, update, 0, 0, TypeId::I32)
+                .unwrap();
+            graph
+                .add_data_edge(next, update, 0, 1, TypeId::I32)
+                .unwrap();
+            graph.add_control_edge(update, i, None).unwrap();
+        }
+
+        let ret = op(&mut graph, f, ComputeOp::Return);
+        graph.add_data_edge(n, ret, 0, 0, TypeId::I32).unwrap();
+        graph.add_control_edge(loop_node, ret, Some(1)).unwrap();
+        (graph, loop_node, cond, i)
+    }
+
+    #[test]
+    fn counter_loop_is_bounded_by_its_precondition() {
+        let (graph, loop_node, cond, i) = counter_loop(Some(3));
+        let analysis = analyze(&graph);
+        assert_eq!(analysis.loops.len(), 1);
+        let report = &analysis.loops[0];
+        assert_eq!(report.loop_node, loop_node);
+        // i = 1, 4, ..., 100 while i <= 100
+        assert_eq!(
+            report.termination,
+            Termination::Bounded { max_iterations: 34 }
+        );
+        assert_eq!(report.compares, vec![cond]);
+        assert_eq!(report.induction_variables, vec![i]);
+        assert!(analysis.warnings().is_empty());
+    }
+
+    #[test]
+    fn loop_whose_condition_never_changes_warns() {
+        let (graph, loop_node, cond, _) = counter_loop(None);
+        let analysis = analyze(&graph);
+        assert_eq!(analysis.loops[0].termination, Termination::Unknown);
+
+        let warnings = analysis.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].hazard, TerminationHazard::Loop);
+        assert_eq!(warnings[0].node, loop_node);
+        assert_eq!(warnings[0].related, vec![cond]);
+        assert!(
+            warnings[0].message.contains("does not change"),
+            "{}",
+            warnings[0].message
+        );
+    }
+
+    #[test]
+    fn counter_moving_away_from_bound_warns() {
+        let (graph, _, cond, i) = counter_loop(Some(-1));
+        let warnings = analyze(&graph).warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].related, vec![i, cond]);
+        assert!(
+            warnings[0].message.contains("no induction variable"),
+            "{}",
+            warnings[0].message
+        );
+    }
+
+    #[test]
+    fn phi_induction_variable_counts_down() {
+        // i = phi(10, i - 2); loop while i > 0 && flag
+        let mut graph = ProgramGraph::new("test");
+        let f = function(&mut graph, "f", vec![TypeId::BOOL]);
+        let flag = op(&mut graph, f, ComputeOp::Parameter { index: 0 });
+        let ten = i32_const(&mut graph, f, 10);
+        let zero = i32_const(&mut graph, f, 0);
+        let two = i32_const(&mut graph, f, 2);
+        let i = op(&mut graph, f, ComputeOp::Phi);
+        graph.add_data_edge(ten, i, 0, 0, TypeId::I32).unwrap();
+        let cond = compare(&mut graph, f, CmpOp::Gt, i, zero);
+        let both = op(&mut graph, f, ComputeOp::BinaryLogic { op: LogicOp::And });
+        graph.add_data_edge(cond, both, 0, 0, TypeId::BOOL).unwrap();
+        graph.add_data_edge(flag, both, 0, 1, TypeId::BOOL).unwrap();
+        let loop_node = op(&mut graph, f, ComputeOp::Loop);
+        graph
+            .add_data_edge(both, loop_node, 0, 0, TypeId::BOOL)
+            .unwrap();
+        let next = binary(&mut graph, f, ArithOp::Sub, i, two);
+        graph.add_control_edge(loop_node, next, Some(0)).unwrap();
+        graph.add_data_edge(next, i, 0, 1, TypeId::I32).unwrap();
+
+        let report = &analyze(&graph).loops[0];
+        // i = 10, 8, 6, 4, 2 while i > 0
+        assert_eq!(
+            report.termination,
+            Termination::Bounded { max_iterations: 5 }
+        );
+        assert_eq!(report.compares, vec![cond]);
+        assert_eq!(report.induction_variables, vec![i]);
+    }
+
+    /// Helper: `f(n) = if n <= 0 { 0 } else { f(n - 1) }`, or an unguarded
+    /// `f(n) = f(n - 1)`. Returns the graph and the recursive call.
+    fn countdown(guarded: bool) -> (ProgramGraph, FunctionId, NodeId) {
+        let mut graph = ProgramGraph::new("test");
+        let f = function(&mut graph, "countdown", vec![TypeId::I32]);
+        let n = op(&mut graph, f, ComputeOp::Parameter { index: 0 });
+        let zero = i32_const(&mut graph, f, 0);
+        let one = i32_const(&mut graph, f, 1);
+        let pred = binary(&mut graph, f, ArithOp::Sub, n, one);
+        let call = op(&mut graph, f, ComputeOp::Call { target: f });
+        graph.add_data_edge(pred, call, 0, 0, TypeId::I32).unwrap();
+        let ret = op(&mut graph, f, ComputeOp::Return);
+        graph.add_data_edge(call, ret, 0, 0, TypeId::I32).unwrap();
+        if guarded {
+            let cmp = compare(&mut graph, f, CmpOp::Le, n, zero);
+            let branch = op(&mut graph, f, ComputeOp::Branch);
+            graph
+                .add_data_edge(cmp, branch, 0, 0, TypeId::BOOL)
+                .unwrap();
+            let base = op(&mut graph, f, ComputeOp::Return);
+            graph.add_data_edge(zero, base, 0, 0, TypeId::I32).unwrap();
+            graph.add_control_edge(branch, base, Some(0)).unwrap();
+            graph.add_control_edge(branch, pred, Some(1)).unwrap();
+        }
+        (graph, f, call)
+    }
+
+    #[test]
+    fn guarded_recursion_on_decreasing_argument_terminates() {
+        let (graph, f, call) = countdown(true);
+        let analysis = analyze(&graph);
+        assert_eq!(analysis.recursion.len(), 1);
+        let report = &analysis.recursion[0];
+        assert_eq!(report.functions, vec![f]);
+        assert_eq!(report.calls, vec![call]);
+        assert_eq!(report.termination, Termination::Terminating);
+        assert_eq!(report.parameter, Some(0));
+        assert!(analysis.warnings().is_empty());
+    }
+
+    #[test]
+    fn unguarded_recursion_warns() {
+        let (graph, f, call) = countdown(false);
+        let warnings = analyze(&graph).warnings();
+        assert_eq!(
+            warnings,
+            vec![TerminationWarning {
+                hazard: TerminationHazard::Recursion,
+                function_id: f,
+                node: call,
+                related: vec![call],
+                message: "recursion through `countdown` may not terminate: no parameter moves toward a guarded bound at every recursive call".into(),
+            }]
+        );
+    }
+}
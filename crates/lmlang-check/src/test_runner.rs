@@ -0,0 +1,451 @@
+//! Runner for the executable cases of semantic test nodes.
+//!
+//! Each [`TestNode`] with a `target_function` carries [`TestCase`]s: inputs,
+//! the expected return value or trap kind, and optionally the expected
+//! `Print` output. [`run_test_suites`] calls the target with each case's
+//! inputs and compares the run against the expectation.
+//!
+//! Values are written in the interpreter's JSON value encoding (the
+//! serialized form of [`Value`], e.g. `{"I32": 5}`), so results copied from a
+//! simulate run can be pasted into a case unchanged. Trap kinds are
+//! [`RuntimeError::kind`] names, or `precondition`, `postcondition` and
+//! `invariant` for contract violations.
+//!
+//! A case that cannot run (no target function, wrong number of inputs,
+//! undecodable values) fails with a message rather than aborting the suite.
+//! Test nodes without cases are reported with no case results.
+
+use serde::Serialize;
+
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::FunctionId;
+use lmlang_core::node::{TestCase, TestExpectation, TestNode};
+
+use crate::contracts::ContractKind;
+use crate::interpreter::bytecode::BytecodeCache;
+use crate::interpreter::error::RuntimeError;
+use crate::interpreter::state::{ExecutionLimits, ExecutionState, InterpreterConfig};
+use crate::interpreter::value::Value;
+use crate::interpreter::vm::{Engine, Executor};
+
+/// Configuration for a test suite run.
+#[derive(Debug, Clone, Default)]
+pub struct TestRunConfig {
+    /// Test IDs to run; empty runs every test node.
+    pub test_ids: Vec<String>,
+    /// Seed for `Random` ops inside each case.
+    pub random_seed: u64,
+    /// Budgets for each case. A case that exceeds one traps with the
+    /// matching limit kind.
+    pub limits: ExecutionLimits,
+    /// Engine running each case.
+    pub engine: Engine,
+    /// Lowered functions reused across runs.
+    pub bytecode_cache: BytecodeCache,
+}
+
+/// Results of every selected test node.
+#[derive(Debug, Clone, Serialize)]
+pub struct TestSuiteReport {
+    /// One entry per test node, in semantic index order.
+    pub tests: Vec<TestNodeReport>,
+    /// Cases that met their expectation.
+    pub passed: u32,
+    /// Cases that did not.
+    pub failed: u32,
+}
+
+impl TestSuiteReport {
+    /// Whether no case failed.
+    pub fn all_passed(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+/// Results of one test node.
+#[derive(Debug, Clone, Serialize)]
+pub struct TestNodeReport {
+    /// Semantic node index of the test.
+    pub semantic_node_id: u32,
+    /// Stable test identifier.
+    pub test_id: String,
+    /// Human-readable title.
+    pub title: String,
+    /// Function the cases call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_function: Option<FunctionId>,
+    /// One result per case, in declaration order.
+    pub cases: Vec<CaseReport>,
+}
+
+/// Result of one test case.
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseReport {
+    /// Case name, or its position when unnamed.
+    pub name: String,
+    /// Whether the case met its expectation.
+    pub passed: bool,
+    /// Why the case failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// How the call actually ended, if it ran.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<ActualOutcome>,
+    /// Values printed by the call.
+    pub io_log: Vec<Value>,
+}
+
+/// How a test case's call ended.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ActualOutcome {
+    /// The call returned a value.
+    Returned { value: Value },
+    /// The call stopped with a trap or contract violation.
+    Trapped { trap: String, message: String },
+}
+
+/// Runs the cases of every selected test node in `graph`.
+pub fn run_test_suites(graph: &ProgramGraph, config: &TestRunConfig) -> TestSuiteReport {
+    let executor = Executor::new(graph, config.engine, &config.bytecode_cache);
+    let mut report = TestSuiteReport {
+        tests: Vec::new(),
+        passed: 0,
+        failed: 0,
+    };
+
+    for (semantic_node_id, test) in graph.test_nodes() {
+        if !config.test_ids.is_empty() && !config.test_ids.contains(&test.test_id) {
+            continue;
+        }
+        let cases: Vec<CaseReport> = test
+            .cases
+            .iter()
+            .enumerate()
+            .map(|(index, case)| run_case(graph, &executor, config, test, index, case))
+            .collect();
+        for case in &cases {
+            if case.passed {
+                report.passed += 1;
+            } else {
+                report.failed += 1;
+            }
+        }
+        report.tests.push(TestNodeReport {
+            semantic_node_id,
+            test_id: test.test_id.clone(),
+            title: test.title.clone(),
+            target_function: test.target_function,
+            cases,
+        });
+    }
+    report
+}
+
+fn run_case(
+    graph: &ProgramGraph,
+    executor: &Executor<'_>,
+    config: &TestRunConfig,
+    test: &TestNode,
+    index: usize,
+    case: &TestCase,
+) -> CaseReport {
+    let mut report = CaseReport {
+        name: if case.name.is_empty() {
+            format!("case {}", index)
+        } else {
+            case.name.clone()
+        },
+        passed: false,
+        message: None,
+        actual: None,
+        io_log: Vec::new(),
+    };
+
+    let Some(function_id) = test.target_function else {
+        report.message = Some("test has no target function".to_string());
+        return report;
+    };
+    let Some(func_def) = graph.get_function(function_id) else {
+        report.message = Some(format!("function {} not found", function_id.0));
+        return report;
+    };
+    if case.inputs.len() != func_def.params.len() {
+        report.message = Some(format!(
+            "expected {} input(s) for '{}', got {}",
+            func_def.params.len(),
+            func_def.name,
+            case.inputs.len()
+        ));
+        return report;
+    }
+    let inputs = match decode_all(&case.inputs) {
+        Ok(inputs) => inputs,
+        Err(message) => {
+            report.message = Some(format!("invalid input: {}", message));
+            return report;
+        }
+    };
+
+    let interp_config = InterpreterConfig {
+        random_seed: config.random_seed,
+        limits: config.limits,
+        ..InterpreterConfig::default()
+    };
+    let run = executor.run(&interp_config, function_id, inputs);
+    report.io_log = run.io_log;
+    let actual = match run.state {
+        ExecutionState::Completed { result } => ActualOutcome::Returned { value: result },
+        ExecutionState::Error { error, .. } => ActualOutcome::Trapped {
+            trap: error.kind().to_string(),
+            message: error.to_string(),
+        },
+        ExecutionState::ContractViolation { violation } => ActualOutcome::Trapped {
+            trap: contract_trap(violation.kind).to_string(),
+            message: violation.message,
+        },
+        other => ActualOutcome::Trapped {
+            trap: RuntimeError::InternalError {
+                message: String::new(),
+            }
+            .kind()
+            .to_string(),
+            message: format!("run ended in state {:?}", other),
+        },
+    };
+
+    report.message = check_expectation(case, &actual, &report.io_log).err();
+    report.passed = report.message.is_none();
+    report.actual = Some(actual);
+    report
+}
+
+/// Compares a finished run against the case's expectations.
+fn check_expectation(
+    case: &TestCase,
+    actual: &ActualOutcome,
+    io_log: &[Value],
+) -> Result<(), String> {
+    match (&case.expect, actual) {
+        (TestExpectation::Returns { value }, ActualOutcome::Returned { value: got }) => {
+            let expected = decode(value).map_err(|e| format!("invalid expected value: {}", e))?;
+            if &expected != got {
+                return Err(format!("expected {:?}, got {:?}", expected, got));
+            }
+        }
+        (TestExpectation::Returns { value }, ActualOutcome::Trapped { message, .. }) => {
+            return Err(format!(
+                "expected {}, but the call trapped: {}",
+                value, message
+            ));
+        }
+        (TestExpectation::Traps { trap }, ActualOutcome::Returned { value }) => {
+            return Err(format!(
+                "expected trap '{}', but the call returned {:?}",
+                trap, value
+            ));
+        }
+        (TestExpectation::Traps { trap }, ActualOutcome::Trapped { trap: got, message }) => {
+            if trap != got {
+                return Err(format!(
+                    "expected trap '{}', got '{}': {}",
+                    trap, got, message
+                ));
+            }
+        }
+    }
+
+    if let Some(expected_io) = &case.expected_io {
+        let expected =
+            decode_all(expected_io).map_err(|e| format!("invalid expected output: {}", e))?;
+        if expected != io_log {
+            return Err(format!("expected output {:?}, got {:?}", expected, io_log));
+        }
+    }
+    Ok(())
+}
+
+fn contract_trap(kind: ContractKind) -> &'static str {
+    match kind {
+        ContractKind::Precondition => "precondition",
+        ContractKind::Postcondition => "postcondition",
+        ContractKind::Invariant => "invariant",
+    }
+}
+
+fn decode(json: &serde_json::Value) -> Result<Value, String> {
+    serde_json::from_value(json.clone()).map_err(|e| format!("{} in {}", e, json))
+}
+
+fn decode_all(json: &[serde_json::Value]) -> Result<Vec<Value>, String> {
+    json.iter().map(decode).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lmlang_core::ops::{ArithOp, CmpOp, ComputeOp};
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::{ConstValue, Visibility};
+    use serde_json::json;
+
+    /// Builds `div(a: i32, b: i32) -> i32` requiring `a >= 0`, printing `a`
+    /// and returning `a / b`.
+    fn build_div() -> (ProgramGraph, FunctionId) {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function(
+                "div".into(),
+                root,
+                vec![("a".into(), TypeId::I32), ("b".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+
+        let a = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func_id)
+            .unwrap();
+        let b = graph
+            .add_core_op(ComputeOp::Parameter { index: 1 }, func_id)
+            .unwrap();
+        let zero = graph
+            .add_core_op(
+                ComputeOp::Const {
+                    value: ConstValue::I32(0),
+                },
+                func_id,
+            )
+            .unwrap();
+        let ge = graph
+            .add_core_op(ComputeOp::Compare { op: CmpOp::Ge }, func_id)
+            .unwrap();
+        let pre = graph
+            .add_core_op(
+                ComputeOp::Precondition {
+                    message: "a must be non-negative".into(),
+                },
+                func_id,
+            )
+            .unwrap();
+        let print = graph.add_core_op(ComputeOp::Print, func_id).unwrap();
+        let div = graph
+            .add_core_op(ComputeOp::BinaryArith { op: ArithOp::Div }, func_id)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+
+        graph.add_data_edge(a, ge, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(zero, ge, 0, 1, TypeId::I32).unwrap();
+        graph.add_data_edge(ge, pre, 0, 0, TypeId::BOOL).unwrap();
+        graph.add_data_edge(a, print, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(a, div, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(b, div, 0, 1, TypeId::I32).unwrap();
+        graph.add_data_edge(div, ret, 0, 0, TypeId::I32).unwrap();
+        graph.add_control_edge(pre, print, None).unwrap();
+        graph.add_control_edge(print, ret, None).unwrap();
+
+        (graph, func_id)
+    }
+
+    fn case(name: &str, a: i32, b: i32, expect: TestExpectation, io: Option<Vec<i32>>) -> TestCase {
+        TestCase {
+            name: name.into(),
+            inputs: vec![json!({ "I32": a }), json!({ "I32": b })],
+            expect,
+            expected_io: io.map(|io| io.into_iter().map(|v| json!({ "I32": v })).collect()),
+        }
+    }
+
+    fn returns(v: i32) -> TestExpectation {
+        TestExpectation::Returns {
+            value: json!({ "I32": v }),
+        }
+    }
+
+    fn traps(trap: &str) -> TestExpectation {
+        TestExpectation::Traps { trap: trap.into() }
+    }
+
+    #[test]
+    fn passing_cases_cover_returns_traps_and_output() {
+        let (mut graph, func_id) = build_div();
+        let root = graph.modules.root_id();
+        graph
+            .add_test_suite(
+                root,
+                "T-DIV".into(),
+                "division".into(),
+                Some(func_id),
+                vec![
+                    case("exact", 6, 3, returns(2), Some(vec![6])),
+                    case("", 1, 0, traps("divide_by_zero"), None),
+                    case("negative", -1, 1, traps("precondition"), None),
+                ],
+            )
+            .unwrap();
+        // Metadata-only tests are reported with no cases.
+        graph
+            .add_test_node(root, "T-DOC".into(), "docs".into(), None)
+            .unwrap();
+
+        let report = run_test_suites(&graph, &TestRunConfig::default());
+        assert!(report.all_passed(), "{:?}", report);
+        assert_eq!(report.passed, 3);
+        assert_eq!(report.tests.len(), 2);
+        assert_eq!(report.tests[0].cases[1].name, "case 1");
+        assert_eq!(report.tests[0].cases[0].io_log, vec![Value::I32(6)]);
+        assert!(report.tests[1].cases.is_empty());
+    }
+
+    #[test]
+    fn failing_cases_explain_the_difference() {
+        let (mut graph, func_id) = build_div();
+        let root = graph.modules.root_id();
+        let mut bad_input = case("bad input", 1, 1, returns(1), None);
+        bad_input.inputs[0] = json!(1);
+        graph
+            .add_test_suite(
+                root,
+                "T-WRONG".into(),
+                "wrong expectations".into(),
+                Some(func_id),
+                vec![
+                    case("value", 6, 3, returns(3), None),
+                    case("trap", 6, 0, returns(0), None),
+                    case("output", 6, 3, returns(2), Some(vec![7])),
+                    case("kind", 6, 0, traps("integer_overflow"), None),
+                    bad_input,
+                ],
+            )
+            .unwrap();
+        graph
+            .add_test_suite(
+                root,
+                "T-SKIPPED".into(),
+                "not selected".into(),
+                Some(func_id),
+                vec![case("value", 6, 3, returns(3), None)],
+            )
+            .unwrap();
+
+        let config = TestRunConfig {
+            test_ids: vec!["T-WRONG".into()],
+            ..TestRunConfig::default()
+        };
+        let report = run_test_suites(&graph, &config);
+        assert_eq!(report.tests.len(), 1);
+        assert_eq!((report.passed, report.failed), (0, 5));
+        let messages: Vec<&str> = report.tests[0]
+            .cases
+            .iter()
+            .map(|c| c.message.as_deref().unwrap())
+            .collect();
+        assert!(messages[0].starts_with("expected I32(3), got I32(2)"));
+        assert!(messages[1].contains("trapped"));
+        assert!(messages[2].starts_with("expected output"));
+        assert!(messages[3].contains("got 'divide_by_zero'"));
+        assert!(messages[4].starts_with("invalid input"));
+        assert!(report.tests[0].cases[4].actual.is_none());
+    }
+}
@@ -90,6 +90,66 @@ pub enum TypeError {
         /// Function containing this node.
         function_id: FunctionId,
     },
+
+    /// A `ForRange`/`ForEach` body function has the wrong signature.
+    #[error("invalid loop body at node {node}: function {body} {reason}")]
+    InvalidLoopBody {
+        /// The loop node.
+        node: NodeId,
+        /// The body function named by the loop.
+        body: FunctionId,
+        /// What is wrong with the body's signature.
+        reason: String,
+        /// Function containing this node.
+        function_id: FunctionId,
+    },
+
+    /// A `ForEach` loop or array combinator was given a value that is not an array.
+    #[error("non-array iteration at node {node}: expected an array, got {actual}")]
+    NonArrayIteration {
+        /// The loop node.
+        node: NodeId,
+        /// The non-array type provided.
+        actual: TypeId,
+        /// Function containing this node.
+        function_id: FunctionId,
+    },
+
+    /// An array combinator's callback does not fit the element type.
+    #[error("invalid callback at node {node}: {reason}")]
+    InvalidCallback {
+        /// The combinator node.
+        node: NodeId,
+        /// What is wrong with the callback's type.
+        reason: String,
+        /// Function containing this node.
+        function_id: FunctionId,
+    },
+
+    /// A node's result type is derived from its inputs, but several
+    /// registered types fit it equally well (e.g. two `ArrayFilter` result
+    /// structs with the same fields).
+    #[error("result type {description} of node {node} is ambiguous: types {}", format_type_ids(.candidates))]
+    AmbiguousResultType {
+        /// The node producing the value.
+        node: NodeId,
+        /// The shape of the derived type.
+        description: String,
+        /// The registered types that fit, in ID order.
+        candidates: Vec<TypeId>,
+        /// Function containing this node.
+        function_id: FunctionId,
+    },
+
+    /// A value derived from `Old`, `ForAll` or `Exists` flows into a node
+    /// that is not part of a contract condition.
+    #[error("contract-only value reaches node {node}, which is not part of a contract condition")]
+    ContractValueEscapes {
+        /// The node outside a contract condition.
+        node: NodeId,
+        /// Function containing this node.
+        function_id: FunctionId,
+    },
 }
 
 /// A suggested fix for a type error.
@@ -107,3 +167,10 @@ pub enum FixSuggestion {
         to: TypeId,
     },
 }
+
+fn format_type_ids(ids: &[TypeId]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
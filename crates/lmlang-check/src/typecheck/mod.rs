@@ -187,7 +187,14 @@ pub fn validate_graph(graph: &ProgramGraph) -> Vec<TypeError> {
                 }
 
                 // Check input count for ops that require a specific number of inputs
-                check_input_count(&node.op, &input_types, node_id, function_id, &mut errors);
+                check_input_count(
+                    graph,
+                    &node.op,
+                    &input_types,
+                    node_id,
+                    function_id,
+                    &mut errors,
+                );
             }
             Err(type_error) => {
                 errors.push(type_error);
@@ -195,9 +202,58 @@ pub fn validate_graph(graph: &ProgramGraph) -> Vec<TypeError> {
         }
     }
 
+    for function_id in graph.sorted_function_ids() {
+        check_contract_scope(graph, function_id, &mut errors);
+    }
+
     errors
 }
 
+/// Check that values from contract expressions (`Old`, `ForAll`, `Exists`)
+/// only feed contract conditions.
+///
+/// The compiler drops everything such a value reaches, so it may flow
+/// through pure ops and calls into contract checks, but never into control
+/// flow, memory writes, I/O or a `Return`.
+fn check_contract_scope(
+    graph: &ProgramGraph,
+    function_id: lmlang_core::id::FunctionId,
+    errors: &mut Vec<TypeError>,
+) {
+    use lmlang_core::ops::{ComputeNodeOp, ComputeOp};
+
+    let mut escapes: Vec<NodeId> = graph
+        .contract_only_nodes(function_id)
+        .into_iter()
+        .filter(|node_id| {
+            graph.get_compute_node(*node_id).is_some_and(|node| {
+                node.op.is_control_flow()
+                    || node.op.is_io()
+                    || matches!(
+                        node.op,
+                        ComputeNodeOp::Core(ComputeOp::Return | ComputeOp::Store)
+                    )
+            })
+        })
+        .collect();
+    escapes.sort_by_key(|n| n.0);
+    errors.extend(
+        escapes
+            .into_iter()
+            .map(|node| TypeError::ContractValueEscapes { node, function_id }),
+    );
+}
+
+/// The output type of `node_id` derived from its incoming edges by its type
+/// rule, or `None` if the rule leaves it to context or fails.
+pub fn derived_output_type(graph: &ProgramGraph, node_id: NodeId) -> Option<TypeId> {
+    let node = graph.get_compute_node(node_id)?;
+    let input_types = incoming_data_types(graph, node_id);
+    rules::resolve_type_rule(&node.op, &input_types, graph, node_id, node.owner)
+        .ok()?
+        .output_type
+}
+
 /// Collect all incoming data edge types for a node, keyed by target_port.
 fn incoming_data_types(graph: &ProgramGraph, node_id: NodeId) -> Vec<(u16, TypeId)> {
     let node_idx: petgraph::graph::NodeIndex<u32> = node_id.into();
@@ -249,6 +305,7 @@ fn find_source_port(graph: &ProgramGraph, target_node: NodeId, target_port: u16)
 ///
 /// This catches cases like a BinaryArith node with 0 or 1 inputs.
 fn check_input_count(
+    graph: &ProgramGraph,
     op: &lmlang_core::ops::ComputeNodeOp,
     input_types: &[(u16, TypeId)],
     node_id: NodeId,
@@ -269,6 +326,10 @@ fn check_input_count(
             ComputeOp::Shift { .. } => Some(2),
             ComputeOp::IfElse => Some(1),
             ComputeOp::Branch => Some(1),
+            // Bounds (or array) plus one accumulator if the body takes one
+            ComputeOp::ForRange { body } => graph.get_function(*body).map(|f| f.params.len() + 2),
+            ComputeOp::ForEach { body } => graph.get_function(*body).map(|f| f.params.len()),
+            ComputeOp::Old { .. } => Some(0),
             // Ops with variable or zero inputs -- no count check
             ComputeOp::Const { .. }
             | ComputeOp::Loop
@@ -289,17 +350,23 @@ fn check_input_count(
             | ComputeOp::FileRead
             | ComputeOp::FileWrite
             | ComputeOp::FileClose
+            | ComputeOp::Now
+            | ComputeOp::Random
             | ComputeOp::MakeClosure { .. }
             | ComputeOp::CaptureAccess { .. }
             | ComputeOp::Precondition { .. }
             | ComputeOp::Postcondition { .. }
-            | ComputeOp::Invariant { .. } => None,
+            | ComputeOp::Invariant { .. }
+            | ComputeOp::ForAll { .. }
+            | ComputeOp::Exists { .. } => None,
         },
         lmlang_core::ops::ComputeNodeOp::Structured(struct_op) => match struct_op {
             StructuredOp::StructGet { .. } => Some(1),
             StructuredOp::StructSet { .. } => Some(2),
             StructuredOp::ArrayGet => Some(2),
             StructuredOp::ArraySet => Some(3),
+            StructuredOp::ArrayMap | StructuredOp::ArrayFilter => Some(2),
+            StructuredOp::ArrayFold => Some(3),
             StructuredOp::Cast { .. } => Some(1),
             StructuredOp::EnumDiscriminant => Some(1),
             StructuredOp::EnumPayload { .. } => Some(1),
@@ -308,6 +308,75 @@ fn resolve_core_rule(
             }
         }
 
+        ComputeOp::ForRange { body } => {
+            // Ports 0..=2 = start, end, step; port 3 = optional accumulator
+            let Some(sig) =
+                loop_body_signature(graph, *body, input_types, 3, node_id, function_id)?
+            else {
+                return Ok(OpTypeRule {
+                    expected_inputs: vec![],
+                    output_type: None,
+                });
+            };
+            if !is_integer(sig.item) {
+                return Err(TypeError::InvalidLoopBody {
+                    node: node_id,
+                    body: *body,
+                    reason: format!("must take an integer index, takes {}", sig.item),
+                    function_id,
+                });
+            }
+            let mut expected = vec![(0, sig.item), (1, sig.item), (2, sig.item)];
+            expected.extend(sig.accumulator.map(|acc| (3, acc)));
+            Ok(OpTypeRule {
+                expected_inputs: expected,
+                output_type: sig.accumulator,
+            })
+        }
+
+        ComputeOp::ForEach { body } => {
+            // Port 0 = array; port 1 = optional accumulator
+            let Some(sig) =
+                loop_body_signature(graph, *body, input_types, 1, node_id, function_id)?
+            else {
+                return Ok(OpTypeRule {
+                    expected_inputs: vec![],
+                    output_type: None,
+                });
+            };
+            let mut expected = Vec::new();
+            if let Some(array_ty) = find_port_type(input_types, 0) {
+                match registry.get(array_ty) {
+                    Some(LmType::Array { element, .. }) => {
+                        if *element != sig.item {
+                            return Err(TypeError::InvalidLoopBody {
+                                node: node_id,
+                                body: *body,
+                                reason: format!(
+                                    "must take the array element type {}, takes {}",
+                                    element, sig.item
+                                ),
+                                function_id,
+                            });
+                        }
+                        expected.push((0, array_ty));
+                    }
+                    _ => {
+                        return Err(TypeError::NonArrayIteration {
+                            node: node_id,
+                            actual: array_ty,
+                            function_id,
+                        });
+                    }
+                }
+            }
+            expected.extend(sig.accumulator.map(|acc| (1, acc)));
+            Ok(OpTypeRule {
+                expected_inputs: expected,
+                output_type: sig.accumulator,
+            })
+        }
+
         ComputeOp::Match => {
             // Port 0 = discriminant (integer or enum)
             match find_port_type(input_types, 0) {
@@ -626,6 +695,15 @@ fn resolve_core_rule(
             })
         }
 
+        // -- Environment --
+        ComputeOp::Now | ComputeOp::Random => {
+            // 0 data inputs. Output = I64 (nanoseconds since epoch / random bits).
+            Ok(OpTypeRule {
+                expected_inputs: vec![],
+                output_type: Some(TypeId::I64),
+            })
+        }
+
         // -- Closures --
         ComputeOp::MakeClosure { function } => {
             // N data inputs (captured values). Output = function type.
@@ -742,6 +820,65 @@ fn resolve_core_rule(
                 output_type: None,
             })
         }
+
+        // -- Contract expressions (contract subgraphs only) --
+        ComputeOp::Old { index } => {
+            // 0 data inputs. Output = parameter type, or the pointee type
+            // for a pointer parameter.
+            let param_type = graph
+                .get_function(function_id)
+                .and_then(|func_def| func_def.params.get(*index as usize))
+                .map(|(_, ty)| match registry.get(*ty) {
+                    Some(LmType::Pointer { pointee, .. }) => *pointee,
+                    _ => *ty,
+                });
+            Ok(OpTypeRule {
+                expected_inputs: vec![],
+                output_type: param_type,
+            })
+        }
+
+        ComputeOp::ForAll { predicate } | ComputeOp::Exists { predicate } => {
+            // Port 0 = array, or ports 0 and 1 = integer range. Output = Bool.
+            let Some(item) = quantifier_item(graph, *predicate, node_id, function_id)? else {
+                return Ok(OpTypeRule {
+                    expected_inputs: vec![],
+                    output_type: Some(TypeId::BOOL),
+                });
+            };
+            let mut expected = Vec::new();
+            if find_port_type(input_types, 1).is_some() {
+                if !is_integer(item) {
+                    return Err(TypeError::InvalidCallback {
+                        node: node_id,
+                        reason: format!(
+                            "a range quantifier's predicate must take an integer, takes {}",
+                            item
+                        ),
+                        function_id,
+                    });
+                }
+                expected.extend([(0, item), (1, item)]);
+            } else if let Some((array_ty, element, _)) =
+                combinator_array(graph, input_types, node_id, function_id)?
+            {
+                if element != item {
+                    return Err(TypeError::InvalidCallback {
+                        node: node_id,
+                        reason: format!(
+                            "predicate must take the array element type {}, takes {}",
+                            element, item
+                        ),
+                        function_id,
+                    });
+                }
+                expected.push((0, array_ty));
+            }
+            Ok(OpTypeRule {
+                expected_inputs: expected,
+                output_type: Some(TypeId::BOOL),
+            })
+        }
     }
 }
 
@@ -916,6 +1053,106 @@ fn resolve_structured_rule(
             }
         }
 
+        StructuredOp::ArrayMap => {
+            // Port 0 = array [T; N], port 1 = fn(T) -> U. Output = [U; N].
+            let Some((array_ty, element, length)) =
+                combinator_array(graph, input_types, node_id, function_id)?
+            else {
+                return Ok(OpTypeRule {
+                    expected_inputs: vec![],
+                    output_type: None,
+                });
+            };
+            let mut expected = vec![(0, array_ty)];
+            let mut output = None;
+            if let Some((callback_ty, result)) =
+                callback_signature(graph, input_types, 1, &[element], node_id, function_id)?
+            {
+                expected.push((1, callback_ty));
+                // Output is [U; N] if registered, else determined by context
+                output = registry
+                    .iter()
+                    .find(|(_, ty)| {
+                        matches!(ty, LmType::Array { element, length: len }
+                            if *element == result && *len == length)
+                    })
+                    .map(|(id, _)| id);
+            }
+            Ok(OpTypeRule {
+                expected_inputs: expected,
+                output_type: output,
+            })
+        }
+
+        StructuredOp::ArrayFold => {
+            // Port 0 = array [T; N], port 1 = init A, port 2 = fn(A, T) -> A. Output = A.
+            let Some((array_ty, element, _)) =
+                combinator_array(graph, input_types, node_id, function_id)?
+            else {
+                return Ok(OpTypeRule {
+                    expected_inputs: vec![],
+                    output_type: None,
+                });
+            };
+            let mut expected = vec![(0, array_ty)];
+            let Some(acc) = find_port_type(input_types, 1) else {
+                return Ok(OpTypeRule {
+                    expected_inputs: expected,
+                    output_type: None,
+                });
+            };
+            expected.push((1, acc));
+            if let Some((callback_ty, result)) =
+                callback_signature(graph, input_types, 2, &[acc, element], node_id, function_id)?
+            {
+                if result != acc {
+                    return Err(TypeError::InvalidCallback {
+                        node: node_id,
+                        reason: format!(
+                            "fold callback must return its accumulator type {}, returns {}",
+                            acc, result
+                        ),
+                        function_id,
+                    });
+                }
+                expected.push((2, callback_ty));
+            }
+            Ok(OpTypeRule {
+                expected_inputs: expected,
+                output_type: Some(acc),
+            })
+        }
+
+        StructuredOp::ArrayFilter => {
+            // Port 0 = array [T; N], port 1 = fn(T) -> Bool.
+            // Output = struct { count: I32, items: [T; N] }.
+            let Some((array_ty, element, _)) =
+                combinator_array(graph, input_types, node_id, function_id)?
+            else {
+                return Ok(OpTypeRule {
+                    expected_inputs: vec![],
+                    output_type: None,
+                });
+            };
+            let mut expected = vec![(0, array_ty)];
+            if let Some((callback_ty, result)) =
+                callback_signature(graph, input_types, 1, &[element], node_id, function_id)?
+            {
+                if result != TypeId::BOOL {
+                    return Err(TypeError::InvalidCallback {
+                        node: node_id,
+                        reason: format!("filter predicate must return Bool, returns {}", result),
+                        function_id,
+                    });
+                }
+                expected.push((1, callback_ty));
+            }
+            Ok(OpTypeRule {
+                expected_inputs: expected,
+                output_type: filter_result_type(registry, array_ty, node_id, function_id)?,
+            })
+        }
+
         StructuredOp::Cast { target_type } => {
             // 1 input (any numeric/pointer type). Output = target_type.
             let mut expected = Vec::new();
@@ -992,6 +1229,212 @@ fn resolve_structured_rule(
     }
 }
 
+/// Signature of a `ForRange`/`ForEach` body function.
+struct LoopBodySignature {
+    /// Type of the first parameter (index or element).
+    item: TypeId,
+    /// Accumulator type, if the body takes one.
+    accumulator: Option<TypeId>,
+}
+
+/// Checks a loop body's shape: `(item)` or `(item, acc) -> acc`.
+///
+/// `acc_port` is the loop's accumulator input port; connecting it is an
+/// error when the body takes no accumulator. Returns `None` if the body
+/// function does not exist (the caller reports that separately).
+fn loop_body_signature(
+    graph: &ProgramGraph,
+    body: FunctionId,
+    input_types: &[(u16, TypeId)],
+    acc_port: u16,
+    node_id: NodeId,
+    function_id: FunctionId,
+) -> Result<Option<LoopBodySignature>, TypeError> {
+    let Some(func_def) = graph.get_function(body) else {
+        return Ok(None);
+    };
+    let invalid = |reason: String| TypeError::InvalidLoopBody {
+        node: node_id,
+        body,
+        reason,
+        function_id,
+    };
+    match func_def.params.as_slice() {
+        [(_, item)] => {
+            if find_port_type(input_types, acc_port).is_some() {
+                return Err(invalid(format!(
+                    "takes no accumulator, but port {} is connected",
+                    acc_port
+                )));
+            }
+            Ok(Some(LoopBodySignature {
+                item: *item,
+                accumulator: None,
+            }))
+        }
+        [(_, item), (_, acc)] => {
+            if func_def.return_type != *acc {
+                return Err(invalid(format!(
+                    "must return its accumulator type {}, returns {}",
+                    acc, func_def.return_type
+                )));
+            }
+            Ok(Some(LoopBodySignature {
+                item: *item,
+                accumulator: Some(*acc),
+            }))
+        }
+        params => Err(invalid(format!(
+            "must take (item) or (item, acc), takes {} parameters",
+            params.len()
+        ))),
+    }
+}
+
+/// Checks a `ForAll`/`Exists` predicate's shape, `(item) -> Bool`, and
+/// returns its item type. Returns `None` if the predicate function does not
+/// exist (the caller reports that separately).
+fn quantifier_item(
+    graph: &ProgramGraph,
+    predicate: FunctionId,
+    node_id: NodeId,
+    function_id: FunctionId,
+) -> Result<Option<TypeId>, TypeError> {
+    let Some(func_def) = graph.get_function(predicate) else {
+        return Ok(None);
+    };
+    let invalid = |reason: String| TypeError::InvalidCallback {
+        node: node_id,
+        reason,
+        function_id,
+    };
+    match func_def.params.as_slice() {
+        [(_, item)] if func_def.return_type == TypeId::BOOL => Ok(Some(*item)),
+        [_] => Err(invalid(format!(
+            "predicate {} must return Bool, returns {}",
+            predicate, func_def.return_type
+        ))),
+        params => Err(invalid(format!(
+            "predicate {} must take (item), takes {} parameters",
+            predicate,
+            params.len()
+        ))),
+    }
+}
+
+/// Resolves port 0 of an array combinator to `(array type, element, length)`.
+///
+/// Returns `None` while the port is unconnected.
+fn combinator_array(
+    graph: &ProgramGraph,
+    input_types: &[(u16, TypeId)],
+    node_id: NodeId,
+    function_id: FunctionId,
+) -> Result<Option<(TypeId, TypeId, u32)>, TypeError> {
+    let Some(array_ty) = find_port_type(input_types, 0) else {
+        return Ok(None);
+    };
+    match graph.types.get(array_ty) {
+        Some(LmType::Array { element, length }) => Ok(Some((array_ty, *element, *length))),
+        _ => Err(TypeError::NonArrayIteration {
+            node: node_id,
+            actual: array_ty,
+            function_id,
+        }),
+    }
+}
+
+/// Checks a combinator callback on `port` against the expected parameters.
+///
+/// The callback must be an `LmType::Function` value (a closure or function
+/// reference). Returns the callback's type and its return type, or `None`
+/// while the port is unconnected.
+fn callback_signature(
+    graph: &ProgramGraph,
+    input_types: &[(u16, TypeId)],
+    port: u16,
+    expected_params: &[TypeId],
+    node_id: NodeId,
+    function_id: FunctionId,
+) -> Result<Option<(TypeId, TypeId)>, TypeError> {
+    let Some(callback_ty) = find_port_type(input_types, port) else {
+        return Ok(None);
+    };
+    let invalid = |reason: String| TypeError::InvalidCallback {
+        node: node_id,
+        reason,
+        function_id,
+    };
+    match graph.types.get(callback_ty) {
+        Some(LmType::Function {
+            params,
+            return_type,
+        }) => {
+            if params.as_slice() != expected_params {
+                let show = |tys: &[TypeId]| {
+                    tys.iter()
+                        .map(|t| t.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                return Err(invalid(format!(
+                    "callback must take ({}), takes ({})",
+                    show(expected_params),
+                    show(params)
+                )));
+            }
+            Ok(Some((callback_ty, *return_type)))
+        }
+        _ => Err(invalid(format!(
+            "port {} expects a function value, got {}",
+            port, callback_ty
+        ))),
+    }
+}
+
+/// The registered struct `{ count: I32, items: [T; N] }` an `ArrayFilter`
+/// over `array_ty` produces, or `None` if there is none (the output type is
+/// then determined by context). Among several structs with those field
+/// types, the one whose fields are named `count` and `items` is chosen; more
+/// than one such struct is an error rather than an arbitrary pick.
+fn filter_result_type(
+    registry: &lmlang_core::type_id::TypeRegistry,
+    array_ty: TypeId,
+    node_id: NodeId,
+    function_id: FunctionId,
+) -> Result<Option<TypeId>, TypeError> {
+    let shaped: Vec<(TypeId, &lmlang_core::types::StructDef)> = registry
+        .iter()
+        .filter_map(|(id, ty)| match ty {
+            LmType::Struct(def) if def.fields.values().copied().eq([TypeId::I32, array_ty]) => {
+                Some((id, def))
+            }
+            _ => None,
+        })
+        .collect();
+    if shaped.len() <= 1 {
+        return Ok(shaped.first().map(|(id, _)| *id));
+    }
+    let named: Vec<TypeId> = shaped
+        .iter()
+        .filter(|(_, def)| def.fields.keys().map(String::as_str).eq(["count", "items"]))
+        .map(|(id, _)| *id)
+        .collect();
+    if let [only] = named.as_slice() {
+        return Ok(Some(*only));
+    }
+    Err(TypeError::AmbiguousResultType {
+        node: node_id,
+        description: format!("struct {{ count: {}, items: {} }}", TypeId::I32, array_ty),
+        candidates: if named.is_empty() {
+            shaped.iter().map(|(id, _)| *id).collect()
+        } else {
+            named
+        },
+        function_id,
+    })
+}
+
 /// Helper: find the type connected to a specific port in the input list.
 fn find_port_type(input_types: &[(u16, TypeId)], port: u16) -> Option<TypeId> {
     input_types
@@ -1273,6 +1716,17 @@ mod tests {
         assert_eq!(rule.output_type, Some(TypeId::I64));
     }
 
+    #[test]
+    fn now_and_random_produce_i64() {
+        let (graph, func_id) = test_graph_with_function();
+        for op in [ComputeOp::Now, ComputeOp::Random] {
+            let op = ComputeNodeOp::Core(op);
+            let rule = resolve_type_rule(&op, &[], &graph, NodeId(0), func_id).unwrap();
+            assert!(rule.expected_inputs.is_empty());
+            assert_eq!(rule.output_type, Some(TypeId::I64));
+        }
+    }
+
     #[test]
     fn shift_requires_integer_types() {
         let (graph, func_id) = test_graph_with_function();
@@ -1403,6 +1857,225 @@ mod tests {
         assert_eq!(rule.output_type, Some(TypeId::I64));
     }
 
+    #[test]
+    fn for_range_types_follow_body_signature() {
+        // test_fn(a: I32, b: I32) -> I32 is a valid (index, acc) -> acc body
+        let (graph, func_id) = test_graph_with_function();
+        let op = ComputeNodeOp::Core(ComputeOp::ForRange { body: func_id });
+        let rule = resolve_type_rule(&op, &[], &graph, NodeId(0), func_id).unwrap();
+        assert_eq!(rule.output_type, Some(TypeId::I32));
+        assert_eq!(
+            rule.expected_inputs,
+            vec![
+                (0, TypeId::I32),
+                (1, TypeId::I32),
+                (2, TypeId::I32),
+                (3, TypeId::I32)
+            ]
+        );
+    }
+
+    #[test]
+    fn for_range_rejects_malformed_bodies() {
+        let (mut graph, func_id) = test_graph_with_function();
+        let root = graph.modules.root_id();
+        let float_index = graph
+            .add_function(
+                "float_index".into(),
+                root,
+                vec![("i".into(), TypeId::F64), ("acc".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let wrong_return = graph
+            .add_function(
+                "wrong_return".into(),
+                root,
+                vec![("i".into(), TypeId::I32), ("acc".into(), TypeId::I32)],
+                TypeId::I64,
+                Visibility::Public,
+            )
+            .unwrap();
+        let no_acc = graph
+            .add_function(
+                "no_acc".into(),
+                root,
+                vec![("i".into(), TypeId::I32)],
+                TypeId::UNIT,
+                Visibility::Public,
+            )
+            .unwrap();
+
+        for (body, inputs) in [
+            (float_index, vec![]),
+            (wrong_return, vec![]),
+            (no_acc, vec![(3, TypeId::I32)]),
+        ] {
+            let op = ComputeNodeOp::Core(ComputeOp::ForRange { body });
+            let err = resolve_type_rule(&op, &inputs, &graph, NodeId(0), func_id).unwrap_err();
+            assert!(matches!(err, TypeError::InvalidLoopBody { .. }), "{err}");
+        }
+
+        let op = ComputeNodeOp::Core(ComputeOp::ForRange { body: no_acc });
+        let rule = resolve_type_rule(&op, &[], &graph, NodeId(0), func_id).unwrap();
+        assert_eq!(rule.output_type, None);
+    }
+
+    #[test]
+    fn for_each_requires_array_of_body_element() {
+        let (mut graph, func_id) = test_graph_with_function();
+        let i32_array = graph.types.register(LmType::Array {
+            element: TypeId::I32,
+            length: 4,
+        });
+        let i64_array = graph.types.register(LmType::Array {
+            element: TypeId::I64,
+            length: 4,
+        });
+        let op = ComputeNodeOp::Core(ComputeOp::ForEach { body: func_id });
+
+        let rule = resolve_type_rule(&op, &[(0, i32_array)], &graph, NodeId(0), func_id).unwrap();
+        assert_eq!(rule.output_type, Some(TypeId::I32));
+        assert_eq!(rule.expected_inputs, vec![(0, i32_array), (1, TypeId::I32)]);
+
+        let err =
+            resolve_type_rule(&op, &[(0, i64_array)], &graph, NodeId(0), func_id).unwrap_err();
+        assert!(matches!(err, TypeError::InvalidLoopBody { .. }));
+
+        let err =
+            resolve_type_rule(&op, &[(0, TypeId::I32)], &graph, NodeId(0), func_id).unwrap_err();
+        assert!(matches!(err, TypeError::NonArrayIteration { .. }));
+    }
+
+    #[test]
+    fn array_combinators_check_callback_signatures() {
+        use indexmap::IndexMap;
+        use lmlang_core::types::StructDef;
+
+        let (mut graph, func_id) = test_graph_with_function();
+        let i32_array = graph.types.register(LmType::Array {
+            element: TypeId::I32,
+            length: 4,
+        });
+        let bool_array = graph.types.register(LmType::Array {
+            element: TypeId::BOOL,
+            length: 4,
+        });
+        let is_even = graph.types.register(LmType::Function {
+            params: vec![TypeId::I32],
+            return_type: TypeId::BOOL,
+        });
+        let add = graph.types.register(LmType::Function {
+            params: vec![TypeId::I32, TypeId::I32],
+            return_type: TypeId::I32,
+        });
+        let to_i64 = graph.types.register(LmType::Function {
+            params: vec![TypeId::I32],
+            return_type: TypeId::I64,
+        });
+        let bool_pred = graph.types.register(LmType::Function {
+            params: vec![TypeId::BOOL],
+            return_type: TypeId::BOOL,
+        });
+        let root = graph.modules.root_id();
+        let filtered = graph
+            .types
+            .register_named(
+                "Filtered",
+                LmType::Struct(StructDef {
+                    name: "Filtered".into(),
+                    type_id: TypeId(0), // placeholder
+                    fields: IndexMap::from([
+                        ("count".into(), TypeId::I32),
+                        ("items".into(), i32_array),
+                    ]),
+                    module: root,
+                    visibility: Visibility::Public,
+                }),
+            )
+            .unwrap();
+        let map = ComputeNodeOp::Structured(StructuredOp::ArrayMap);
+        let fold = ComputeNodeOp::Structured(StructuredOp::ArrayFold);
+        let filter = ComputeNodeOp::Structured(StructuredOp::ArrayFilter);
+        let resolve = |op, inputs: &[(u16, TypeId)]| {
+            resolve_type_rule(op, inputs, &graph, NodeId(0), func_id)
+        };
+
+        let rule = resolve(&map, &[(0, i32_array), (1, is_even)]).unwrap();
+        assert_eq!(rule.output_type, Some(bool_array));
+        let rule = resolve(&fold, &[(0, i32_array), (1, TypeId::I32), (2, add)]).unwrap();
+        assert_eq!(rule.output_type, Some(TypeId::I32));
+        let rule = resolve(&filter, &[(0, i32_array), (1, is_even)]).unwrap();
+        assert_eq!(rule.output_type, Some(filtered));
+        assert_eq!(rule.expected_inputs, vec![(0, i32_array), (1, is_even)]);
+
+        // [I64; 4] and struct { I32, [Bool; 4] } are not registered, so the
+        // output is left to context
+        let rule = resolve(&map, &[(0, i32_array), (1, to_i64)]).unwrap();
+        assert_eq!(rule.output_type, None);
+        let rule = resolve(&filter, &[(0, bool_array), (1, bool_pred)]).unwrap();
+        assert_eq!(rule.output_type, None);
+
+        for (op, inputs) in [
+            (&map, vec![(0, i32_array), (1, add)]),
+            (&map, vec![(0, i32_array), (1, TypeId::I32)]),
+            (&fold, vec![(0, i32_array), (1, TypeId::I64), (2, add)]),
+            (&filter, vec![(0, bool_array), (1, is_even)]),
+            (&filter, vec![(0, i32_array), (1, add)]),
+        ] {
+            let err = resolve(op, &inputs).unwrap_err();
+            assert!(matches!(err, TypeError::InvalidCallback { .. }), "{err}");
+        }
+
+        let err = resolve(&map, &[(0, TypeId::I32), (1, is_even)]).unwrap_err();
+        assert!(matches!(err, TypeError::NonArrayIteration { .. }));
+    }
+
+    #[test]
+    fn filter_result_type_prefers_named_fields_and_rejects_ties() {
+        use indexmap::IndexMap;
+        use lmlang_core::types::StructDef;
+
+        let (mut graph, func_id) = test_graph_with_function();
+        let root = graph.modules.root_id();
+        let i32_array = graph.types.register(LmType::Array {
+            element: TypeId::I32,
+            length: 4,
+        });
+        let is_even = graph.types.register(LmType::Function {
+            params: vec![TypeId::I32],
+            return_type: TypeId::BOOL,
+        });
+        let pair = |count: &str, items: &str| {
+            LmType::Struct(StructDef {
+                name: "Pair".into(),
+                type_id: TypeId(0), // placeholder
+                fields: IndexMap::from([(count.into(), TypeId::I32), (items.into(), i32_array)]),
+                module: root,
+                visibility: Visibility::Public,
+            })
+        };
+        let filter = ComputeNodeOp::Structured(StructuredOp::ArrayFilter);
+        let inputs = [(0, i32_array), (1, is_even)];
+
+        // Field names pick between structs of the same shape
+        graph.types.register(pair("len", "values"));
+        let filtered = graph.types.register(pair("count", "items"));
+        let rule = resolve_type_rule(&filter, &inputs, &graph, NodeId(0), func_id).unwrap();
+        assert_eq!(rule.output_type, Some(filtered));
+
+        // Two structs that both fit are an error, not a registration-order pick
+        let duplicate = graph.types.register(pair("count", "items"));
+        let err = resolve_type_rule(&filter, &inputs, &graph, NodeId(0), func_id).unwrap_err();
+        assert!(
+            matches!(err, TypeError::AmbiguousResultType { ref candidates, .. }
+                if *candidates == vec![filtered, duplicate]),
+            "{err}"
+        );
+        assert!(err.to_string().contains("ambiguous"), "{err}");
+    }
+
     #[test]
     fn capture_access_returns_captured_type() {
         use lmlang_core::function::{Capture, CaptureMode};
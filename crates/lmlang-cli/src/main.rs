@@ -1,18 +1,29 @@
 //! LM Language compiler CLI.
 //!
 //! Provides the `lmlang` binary with subcommands for working with lmlang
-//! programs. Currently supports `compile` which compiles a program graph
-//! stored in a SQLite database to a native executable.
+//! programs: `compile` compiles a program graph stored in a SQLite database
+//! to a native executable, `run` executes it (natively or through the
+//! interpreter, optionally profiled into folded call stacks) with
+//! command-line arguments passed to the entry function,
+//! `difftest` checks that both backends agree on one function, and `test`
+//! runs the cases of the program's test nodes through the interpreter.
 //!
 //! Uses the same `lmlang_codegen::compile()` pipeline as the HTTP server
 //! endpoint, ensuring identical compilation behavior from both entry points.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 
 use clap::{Parser, Subcommand};
 
-use lmlang_codegen::{CompileOptions, OptLevel};
+use lmlang_check::interpreter::{entry, ExecutionState, Interpreter, InterpreterConfig};
+use lmlang_check::test_runner::{run_test_suites, TestRunConfig};
+use lmlang_codegen::differential::{
+    format_printed_value, run_differential_tests, DifferentialConfig, DifferentialError,
+};
+use lmlang_codegen::error::CodegenError;
+use lmlang_codegen::{CompileOptions, ContractMode, OptLevel};
+use lmlang_core::graph::ProgramGraph;
 use lmlang_storage::traits::GraphStore;
 use lmlang_storage::types::ProgramId;
 use lmlang_storage::SqliteStore;
@@ -57,6 +68,94 @@ enum Commands {
         /// Output directory (default: ./build/).
         #[arg(short = 'O', long, default_value = "./build")]
         output_dir: PathBuf,
+
+        /// Contracts compiled as runtime checks: off, pre-only, all.
+        #[arg(long, default_value = "off")]
+        contracts: String,
+    },
+    /// Run a program's entry function, exiting with its exit status.
+    ///
+    /// Arguments after `--` fill the entry function's argument array.
+    Run {
+        /// Path to the program database file.
+        #[arg(short, long)]
+        db: String,
+
+        /// Program ID to run.
+        #[arg(short, long)]
+        program: i64,
+
+        /// Optimization level: O0, O1, O2, O3.
+        #[arg(short, long, default_value = "O0")]
+        opt_level: String,
+
+        /// Entry function name (default: auto-detect).
+        #[arg(long)]
+        entry: Option<String>,
+
+        /// Output directory for the compiled binary (default: ./build/).
+        #[arg(short = 'O', long, default_value = "./build")]
+        output_dir: PathBuf,
+
+        /// Execute with the graph interpreter instead of compiling.
+        #[arg(long)]
+        interpret: bool,
+
+        /// Write the interpreter's folded call stacks to this file, for
+        /// flamegraph tools (requires --interpret).
+        #[arg(long, requires = "interpret")]
+        profile: Option<PathBuf>,
+
+        /// Arguments passed to the entry function.
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Compare a function's interpreted and compiled behavior on generated inputs.
+    ///
+    /// Prints the differential report as JSON, exiting with 4 if the
+    /// backends disagree.
+    Difftest {
+        /// Path to the program database file.
+        #[arg(short, long)]
+        db: String,
+
+        /// Program ID to test.
+        #[arg(short, long)]
+        program: i64,
+
+        /// Name of the function to test.
+        #[arg(short, long)]
+        function: String,
+
+        /// Number of random inputs to compare.
+        #[arg(short, long, default_value_t = 100)]
+        iterations: u32,
+
+        /// Seed for input generation (default: 0).
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+
+        /// Optimization level of the compiled harness: O0, O1, O2, O3.
+        #[arg(short, long, default_value = "O0")]
+        opt_level: String,
+    },
+    /// Run the cases of the program's test nodes through the interpreter.
+    Test {
+        /// Path to the program database file.
+        #[arg(short, long)]
+        db: String,
+
+        /// Program ID to test.
+        #[arg(short, long)]
+        program: i64,
+
+        /// Test ID to run; repeat to run several (default: all).
+        #[arg(short, long = "test")]
+        tests: Vec<String>,
+
+        /// Seed for `Random` ops (default: 0).
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
     },
 }
 
@@ -72,6 +171,7 @@ fn main() {
             debug_symbols,
             entry,
             output_dir,
+            contracts,
         } => {
             let exit_code = run_compile(
                 &db,
@@ -81,9 +181,45 @@ fn main() {
                 debug_symbols,
                 entry,
                 output_dir,
+                &contracts,
+            );
+            process::exit(exit_code);
+        }
+        Commands::Run {
+            db,
+            program,
+            opt_level,
+            entry,
+            output_dir,
+            interpret,
+            profile,
+            args,
+        } => {
+            let exit_code = run_program(
+                &db, program, &opt_level, entry, output_dir, interpret, profile, &args,
             );
             process::exit(exit_code);
         }
+        Commands::Difftest {
+            db,
+            program,
+            function,
+            iterations,
+            seed,
+            opt_level,
+        } => {
+            let exit_code = run_difftest(&db, program, &function, iterations, seed, &opt_level);
+            process::exit(exit_code);
+        }
+        Commands::Test {
+            db,
+            program,
+            tests,
+            seed,
+        } => {
+            let exit_code = run_test(&db, program, tests, seed);
+            process::exit(exit_code);
+        }
     }
 }
 
@@ -91,6 +227,7 @@ fn main() {
 ///
 /// Returns exit code: 0 = success, 1 = compilation error,
 /// 2 = type check failure, 3 = I/O error.
+#[allow(clippy::too_many_arguments)]
 fn run_compile(
     db_path: &str,
     program_id: i64,
@@ -99,8 +236,9 @@ fn run_compile(
     debug_symbols: bool,
     entry: Option<String>,
     output_dir: PathBuf,
+    contracts_str: &str,
 ) -> i32 {
-    // Parse optimization level
+    // Parse optimization level and contract mode
     let opt_level = match parse_opt_level(opt_level_str) {
         Ok(level) => level,
         Err(msg) => {
@@ -108,23 +246,17 @@ fn run_compile(
             return 1;
         }
     };
-
-    // Open the database and load the program
-    let store = match SqliteStore::new(db_path) {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!("Error: failed to open database '{}': {}", db_path, e);
-            return 3;
+    let contracts = match parse_contract_mode(contracts_str) {
+        Ok(mode) => mode,
+        Err(msg) => {
+            eprintln!("Error: {}", msg);
+            return 1;
         }
     };
 
-    let pid = ProgramId(program_id);
-    let graph = match store.load_program(pid) {
+    let graph = match load_graph(db_path, program_id) {
         Ok(g) => g,
-        Err(e) => {
-            eprintln!("Error: failed to load program {}: {}", program_id, e);
-            return 3;
-        }
+        Err(code) => return code,
     };
 
     // Build compile options
@@ -134,6 +266,7 @@ fn run_compile(
         target_triple: target,
         debug_symbols,
         entry_function: entry,
+        contracts,
     };
 
     // Compile -- same function the HTTP handler uses
@@ -164,6 +297,263 @@ fn run_compile(
     }
 }
 
+/// Execute the run subcommand.
+///
+/// Returns the program's exit status. Before the program runs, failures use
+/// the compile exit codes (1 = compilation error, 2 = type check failure,
+/// 3 = I/O error); an interpreter runtime error or contract violation exits
+/// with 4.
+#[allow(clippy::too_many_arguments)]
+fn run_program(
+    db_path: &str,
+    program_id: i64,
+    opt_level_str: &str,
+    entry_name: Option<String>,
+    output_dir: PathBuf,
+    interpret: bool,
+    profile: Option<PathBuf>,
+    args: &[String],
+) -> i32 {
+    let opt_level = match parse_opt_level(opt_level_str) {
+        Ok(level) => level,
+        Err(msg) => {
+            eprintln!("Error: {}", msg);
+            return 1;
+        }
+    };
+
+    let graph = match load_graph(db_path, program_id) {
+        Ok(g) => g,
+        Err(code) => return code,
+    };
+
+    if interpret {
+        return interpret_program(&graph, entry_name.as_deref(), profile.as_deref(), args);
+    }
+
+    let options = CompileOptions {
+        output_dir,
+        opt_level,
+        entry_function: entry_name,
+        ..Default::default()
+    };
+
+    let result = match lmlang_codegen::compile(&graph, &options) {
+        Ok(result) => result,
+        Err(lmlang_codegen::error::CodegenError::TypeCheckFailed(errors)) => {
+            eprintln!("Type check failed with {} error(s):", errors.len());
+            for err in &errors {
+                eprintln!("  - {}", err);
+            }
+            return 2;
+        }
+        Err(lmlang_codegen::error::CodegenError::IoError(e)) => {
+            eprintln!("I/O error: {}", e);
+            return 3;
+        }
+        Err(e) => {
+            eprintln!("Compilation error: {}", e);
+            return 1;
+        }
+    };
+
+    match process::Command::new(&result.binary_path)
+        .args(args)
+        .status()
+    {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(e) => {
+            eprintln!(
+                "Error: failed to execute '{}': {}",
+                result.binary_path.display(),
+                e
+            );
+            3
+        }
+    }
+}
+
+/// Run the entry function through the graph interpreter.
+///
+/// Uses the same entry-point convention as the compiled `main` wrapper, and
+/// prints each `Print` value on its own line like the native runtime. With
+/// `profile_path`, writes the run's folded call stacks there (exit 3 if that
+/// fails).
+fn interpret_program(
+    graph: &ProgramGraph,
+    entry_name: Option<&str>,
+    profile_path: Option<&Path>,
+    args: &[String],
+) -> i32 {
+    let entry_point = match entry::select_entry_function(graph, entry_name)
+        .and_then(|func_def| entry::resolve_entry(graph, func_def))
+    {
+        Ok(entry_point) => entry_point,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return 1;
+        }
+    };
+
+    let config = InterpreterConfig {
+        profile_enabled: profile_path.is_some(),
+        ..Default::default()
+    };
+    let mut interp = Interpreter::new(graph, config);
+    interp.start_entry(&entry_point, args);
+    interp.run();
+
+    if let (Some(path), Some(profile)) = (profile_path, interp.profile()) {
+        if let Err(e) = std::fs::write(path, profile.folded_stacks(graph)) {
+            eprintln!("I/O error: failed to write profile: {}", e);
+            return 3;
+        }
+    }
+
+    for line in interp.io_log().iter().filter_map(format_printed_value) {
+        println!("{}", line);
+    }
+
+    match interp.state() {
+        ExecutionState::Completed { result } => entry::exit_status(result),
+        ExecutionState::Error { error, .. } => {
+            eprintln!("Runtime error: {}", error);
+            4
+        }
+        ExecutionState::ContractViolation { violation } => {
+            eprintln!(
+                "Contract violation at node {}: {}",
+                violation.contract_node, violation.message
+            );
+            4
+        }
+        other => {
+            eprintln!("Error: interpreter stopped in unexpected state {:?}", other);
+            4
+        }
+    }
+}
+
+/// Execute the difftest subcommand.
+///
+/// Returns exit code: 0 = the backends agree, 4 = mismatches found. Failures
+/// before any comparison use the compile exit codes (1 = unsupported function
+/// or compilation error, 2 = type check failure, 3 = I/O error).
+fn run_difftest(
+    db_path: &str,
+    program_id: i64,
+    function_name: &str,
+    iterations: u32,
+    seed: u64,
+    opt_level_str: &str,
+) -> i32 {
+    let opt_level = match parse_opt_level(opt_level_str) {
+        Ok(level) => level,
+        Err(msg) => {
+            eprintln!("Error: {}", msg);
+            return 1;
+        }
+    };
+
+    let graph = match load_graph(db_path, program_id) {
+        Ok(g) => g,
+        Err(code) => return code,
+    };
+
+    let Some(func_id) = graph
+        .functions()
+        .iter()
+        .find(|(_, f)| f.name == function_name)
+        .map(|(id, _)| *id)
+    else {
+        eprintln!("Error: function '{}' not found", function_name);
+        return 1;
+    };
+
+    let config = DifferentialConfig {
+        iterations,
+        random_seed: seed,
+        opt_level,
+        ..Default::default()
+    };
+    match run_differential_tests(&graph, func_id, &config) {
+        Ok(report) => {
+            let json = serde_json::to_string_pretty(&report).unwrap_or_else(|e| {
+                format!("{{\"error\": \"failed to serialize report: {}\"}}", e)
+            });
+            println!("{}", json);
+            if report.agrees() {
+                0
+            } else {
+                4
+            }
+        }
+        Err(DifferentialError::Codegen(CodegenError::TypeCheckFailed(errors))) => {
+            eprintln!("Type check failed with {} error(s):", errors.len());
+            for err in &errors {
+                eprintln!("  - {}", err);
+            }
+            2
+        }
+        Err(DifferentialError::Io(e) | DifferentialError::Codegen(CodegenError::IoError(e))) => {
+            eprintln!("I/O error: {}", e);
+            3
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+/// Execute the test subcommand, printing the report as JSON.
+///
+/// Returns exit code: 0 = every case passed, 4 = failing cases,
+/// 3 = I/O error.
+fn run_test(db_path: &str, program_id: i64, test_ids: Vec<String>, seed: u64) -> i32 {
+    let graph = match load_graph(db_path, program_id) {
+        Ok(g) => g,
+        Err(code) => return code,
+    };
+
+    let config = TestRunConfig {
+        test_ids,
+        random_seed: seed,
+        ..Default::default()
+    };
+    let report = run_test_suites(&graph, &config);
+    let json = serde_json::to_string_pretty(&report)
+        .unwrap_or_else(|e| format!("{{\"error\": \"failed to serialize report: {}\"}}", e));
+    println!("{}", json);
+    if report.all_passed() {
+        0
+    } else {
+        4
+    }
+}
+
+/// Open the database and load a program graph.
+///
+/// Returns exit code 3 (I/O error) on failure.
+fn load_graph(db_path: &str, program_id: i64) -> Result<ProgramGraph, i32> {
+    let store = match SqliteStore::new(db_path) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: failed to open database '{}': {}", db_path, e);
+            return Err(3);
+        }
+    };
+
+    let pid = ProgramId(program_id);
+    match store.load_program(pid) {
+        Ok(g) => Ok(g),
+        Err(e) => {
+            eprintln!("Error: failed to load program {}: {}", program_id, e);
+            Err(3)
+        }
+    }
+}
+
 /// Parse an optimization level string to `OptLevel`.
 fn parse_opt_level(s: &str) -> Result<OptLevel, String> {
     match s {
@@ -177,3 +567,16 @@ fn parse_opt_level(s: &str) -> Result<OptLevel, String> {
         )),
     }
 }
+
+/// Parse a contract mode string into a ContractMode enum.
+fn parse_contract_mode(s: &str) -> Result<ContractMode, String> {
+    match s {
+        "off" => Ok(ContractMode::Off),
+        "pre-only" => Ok(ContractMode::PreOnly),
+        "all" => Ok(ContractMode::All),
+        _ => Err(format!(
+            "invalid contract mode '{}', expected off/pre-only/all",
+            s
+        )),
+    }
+}
@@ -11,11 +11,12 @@ use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::module::Module;
 use inkwell::types::{BasicType, BasicTypeEnum};
-use inkwell::values::{AggregateValueEnum, BasicValueEnum, FunctionValue, IntValue};
+use inkwell::values::{AggregateValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue};
 use inkwell::{AddressSpace, FloatPredicate, IntPredicate};
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
 
+use lmlang_check::intervals::RangeAnalysis;
 use lmlang_core::edge::FlowEdge;
 use lmlang_core::function::FunctionDef;
 use lmlang_core::graph::ProgramGraph;
@@ -24,11 +25,12 @@ use lmlang_core::ops::{
     ArithOp, CmpOp, ComputeNodeOp, ComputeOp, LogicOp, ShiftOp, StructuredOp, UnaryArithOp,
 };
 use lmlang_core::type_id::{TypeId, TypeRegistry};
-use lmlang_core::types::ConstValue;
+use lmlang_core::types::{ConstValue, LmType};
 
 use crate::error::CodegenError;
 use crate::runtime;
 use crate::types::lm_type_to_llvm;
+use crate::ContractMode;
 
 // ---------------------------------------------------------------------------
 // Public entry point
@@ -42,6 +44,13 @@ use crate::types::lm_type_to_llvm;
 /// 3. Collect function nodes, topologically sort by data edges.
 /// 4. Iterate sorted nodes, dispatch to per-op emit functions.
 /// 5. Return the LLVM FunctionValue.
+///
+/// Overflow, division and bounds guards are omitted for nodes `ranges`
+/// proves safe. Contract checks enabled by `contracts` are lowered to
+/// runtime guards: preconditions ahead of the function's effects and traps,
+/// the other checks ahead of its returns. All other contract nodes are
+/// stripped.
+#[allow(clippy::too_many_arguments)]
 pub fn compile_function<'ctx>(
     context: &'ctx Context,
     module: &Module<'ctx>,
@@ -49,6 +58,8 @@ pub fn compile_function<'ctx>(
     graph: &ProgramGraph,
     func_id: FunctionId,
     func_def: &FunctionDef,
+    ranges: &RangeAnalysis,
+    contracts: ContractMode,
 ) -> Result<FunctionValue<'ctx>, CodegenError> {
     let registry = &graph.types;
 
@@ -87,19 +98,17 @@ pub fn compile_function<'ctx>(
     let entry_bb = context.append_basic_block(function, "entry");
     builder.position_at_end(entry_bb);
 
-    // 3. Collect and sort function nodes (filter out contract nodes -- dev-only)
+    // 3. Collect and sort function nodes (filter out contract nodes and the
+    //    contract expressions they read, unless `contracts` checks them)
+    let stripped = stripped_contract_nodes(graph, func_id, contracts);
     let func_nodes: Vec<NodeId> = graph
         .function_nodes(func_id)
         .into_iter()
-        .filter(|node_id| {
-            if let Some(node) = graph.get_compute_node(*node_id) {
-                !node.op.is_contract()
-            } else {
-                true
-            }
-        })
+        .filter(|node_id| !stripped.contains(node_id))
         .collect();
-    let sorted_nodes = topological_sort(&func_nodes, graph)?;
+    let mut ordering = preconditions_first(&func_nodes, graph);
+    ordering.extend(dead_ends_before_returns(&func_nodes, graph));
+    let sorted_nodes = topological_sort(&func_nodes, &ordering, graph)?;
 
     // 4. Track SSA values and basic blocks
     let mut values: HashMap<NodeId, BasicValueEnum<'ctx>> = HashMap::new();
@@ -122,6 +131,7 @@ pub fn compile_function<'ctx>(
             function,
             node_id,
             &node.op,
+            ranges,
             &mut values,
             &mut basic_blocks,
         )?;
@@ -130,6 +140,111 @@ pub fn compile_function<'ctx>(
     Ok(function)
 }
 
+/// Contract nodes of `func_id` left out of compiled code: every
+/// contract-only node except the checks `contracts` enables and the
+/// contract expressions those checks read.
+fn stripped_contract_nodes(
+    graph: &ProgramGraph,
+    func_id: FunctionId,
+    contracts: ContractMode,
+) -> HashSet<NodeId> {
+    let mut stripped = graph.contract_only_nodes(func_id);
+    let mut kept: Vec<NodeId> = stripped
+        .iter()
+        .copied()
+        .filter(|nid| {
+            matches!(
+                graph.get_compute_node(*nid).map(|n| &n.op),
+                Some(ComputeNodeOp::Core(op)) if contracts.checks(op)
+            )
+        })
+        .collect();
+    let compute = graph.compute();
+    while let Some(nid) = kept.pop() {
+        if !stripped.remove(&nid) {
+            continue;
+        }
+        let idx = petgraph::graph::NodeIndex::from(nid);
+        for edge in compute.edges_directed(idx, Direction::Incoming) {
+            if edge.weight().is_data() {
+                kept.push(NodeId::from(edge.source()));
+            }
+        }
+    }
+    stripped
+}
+
+/// Ordering pairs placing each Precondition among `func_nodes` before every
+/// node that does not feed some precondition, so no effect, trap or return
+/// runs before the preconditions hold (which range analysis relies on when
+/// it assumes them). Nodes feeding a precondition necessarily run first.
+fn preconditions_first(func_nodes: &[NodeId], graph: &ProgramGraph) -> Vec<(NodeId, NodeId)> {
+    let node_set: HashSet<NodeId> = func_nodes.iter().copied().collect();
+    let compute = graph.compute();
+    let preconditions: Vec<NodeId> = func_nodes
+        .iter()
+        .copied()
+        .filter(|&nid| is_precondition(graph, nid))
+        .collect();
+
+    // Everything a precondition depends on, through data and control edges
+    let mut feeds_precondition: HashSet<NodeId> = HashSet::new();
+    let mut stack = preconditions.clone();
+    while let Some(nid) = stack.pop() {
+        let idx = petgraph::graph::NodeIndex::from(nid);
+        for edge in compute.edges_directed(idx, Direction::Incoming) {
+            let source = NodeId::from(edge.source());
+            if node_set.contains(&source) && feeds_precondition.insert(source) {
+                stack.push(source);
+            }
+        }
+    }
+
+    let later: Vec<NodeId> = func_nodes
+        .iter()
+        .copied()
+        .filter(|nid| !feeds_precondition.contains(nid) && !preconditions.contains(nid))
+        .collect();
+    preconditions
+        .iter()
+        .flat_map(|&pre| later.iter().map(move |&after| (pre, after)))
+        .collect()
+}
+
+/// Ordering pairs placing every other dead-end node (no successors among
+/// `func_nodes`, not a terminator or precondition) before each Return, so a
+/// checked postcondition or invariant, or a condition left over from a
+/// stripped contract, is never emitted after the function's terminator.
+fn dead_ends_before_returns(func_nodes: &[NodeId], graph: &ProgramGraph) -> Vec<(NodeId, NodeId)> {
+    let node_set: HashSet<NodeId> = func_nodes.iter().copied().collect();
+    let compute = graph.compute();
+    let op = |nid: NodeId| graph.get_compute_node(nid).map(|n| &n.op);
+    let returns: Vec<NodeId> = func_nodes
+        .iter()
+        .copied()
+        .filter(|&nid| matches!(op(nid), Some(ComputeNodeOp::Core(ComputeOp::Return))))
+        .collect();
+    func_nodes
+        .iter()
+        .copied()
+        .filter(|&nid| !op(nid).is_some_and(ComputeNodeOp::is_terminator))
+        .filter(|&nid| !is_precondition(graph, nid))
+        .filter(|&nid| {
+            compute
+                .edges_directed(petgraph::graph::NodeIndex::from(nid), Direction::Outgoing)
+                .all(|edge| !node_set.contains(&NodeId::from(edge.target())))
+        })
+        .flat_map(|dead_end| returns.iter().map(move |&ret| (dead_end, ret)))
+        .collect()
+}
+
+fn is_precondition(graph: &ProgramGraph, nid: NodeId) -> bool {
+    matches!(
+        graph.get_compute_node(nid).map(|n| &n.op),
+        Some(ComputeNodeOp::Core(ComputeOp::Precondition { .. }))
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Topological sort
 // ---------------------------------------------------------------------------
@@ -138,10 +253,12 @@ pub fn compile_function<'ctx>(
 /// using Kahn's algorithm.
 ///
 /// Considers both data and control edges where both source and target are
-/// in the function node set. Nodes with no dependencies (Const, Parameter,
-/// Alloc, CaptureAccess, ReadLine) naturally sort first.
+/// in the function node set, plus the `(before, after)` pairs in
+/// `extra_edges`. Nodes with no dependencies (Const, Parameter, Alloc,
+/// CaptureAccess, ReadLine, Now, Random) naturally sort first.
 fn topological_sort(
     func_nodes: &[NodeId],
+    extra_edges: &[(NodeId, NodeId)],
     graph: &ProgramGraph,
 ) -> Result<Vec<NodeId>, CodegenError> {
     let node_set: HashSet<NodeId> = func_nodes.iter().copied().collect();
@@ -162,6 +279,9 @@ fn topological_sort(
             }
         }
     }
+    for (_, after) in extra_edges {
+        *in_degree.entry(*after).or_insert(0) += 1;
+    }
 
     // Kahn's algorithm
     let mut queue: VecDeque<NodeId> = VecDeque::new();
@@ -176,8 +296,15 @@ fn topological_sort(
         sorted.push(nid);
 
         let idx = petgraph::graph::NodeIndex::from(nid);
-        for edge in compute.edges_directed(idx, Direction::Outgoing) {
-            let target_nid = NodeId::from(edge.target());
+        let extra_targets = extra_edges
+            .iter()
+            .filter(|(before, _)| *before == nid)
+            .map(|(_, after)| *after);
+        let targets = compute
+            .edges_directed(idx, Direction::Outgoing)
+            .map(|edge| NodeId::from(edge.target()))
+            .chain(extra_targets);
+        for target_nid in targets {
             if node_set.contains(&target_nid) {
                 let deg = in_degree.get_mut(&target_nid).unwrap();
                 *deg -= 1;
@@ -234,6 +361,18 @@ fn get_input<'ctx>(
     )))
 }
 
+/// The node whose value flows into `node_id` at input `port`, if connected.
+fn input_source(graph: &ProgramGraph, node_id: NodeId, port: u16) -> Option<NodeId> {
+    let idx = petgraph::graph::NodeIndex::from(node_id);
+    graph
+        .compute()
+        .edges_directed(idx, Direction::Incoming)
+        .find(|edge| {
+            matches!(edge.weight(), FlowEdge::Data { target_port, .. } if *target_port == port)
+        })
+        .map(|edge| NodeId::from(edge.source()))
+}
+
 /// Get the TypeId of the value flowing into `node_id` at input `port`.
 fn get_input_type(
     graph: &ProgramGraph,
@@ -383,10 +522,12 @@ fn emit_node<'ctx>(
     function: FunctionValue<'ctx>,
     node_id: NodeId,
     op: &ComputeNodeOp,
+    ranges: &RangeAnalysis,
     values: &mut HashMap<NodeId, BasicValueEnum<'ctx>>,
     basic_blocks: &mut HashMap<NodeId, inkwell::basic_block::BasicBlock<'ctx>>,
 ) -> Result<(), CodegenError> {
     let registry = &graph.types;
+    let guarded = !ranges.is_safe(node_id);
 
     match op {
         ComputeNodeOp::Core(core_op) => match core_op {
@@ -413,7 +554,7 @@ fn emit_node<'ctx>(
                 let lhs = get_input(graph, node_id, 0, values)?;
                 let rhs = get_input(graph, node_id, 1, values)?;
                 let val = emit_binary_arith(
-                    context, module, builder, function, lhs, rhs, arith_op, node_id,
+                    context, module, builder, function, lhs, rhs, arith_op, node_id, guarded,
                 )?;
                 values.insert(node_id, val);
             }
@@ -514,6 +655,49 @@ fn emit_node<'ctx>(
                 )?;
             }
 
+            // ----- Control Flow: ForRange / ForEach -----
+            ComputeOp::ForRange { body } => {
+                let body_fn = get_loop_body(module, graph, *body)?;
+                let start = get_input(graph, node_id, 0, values)?.into_int_value();
+                let end = get_input(graph, node_id, 1, values)?.into_int_value();
+                let step = get_input(graph, node_id, 2, values)?.into_int_value();
+                let acc_init = loop_accumulator(graph, node_id, 3, values)?;
+                let result = emit_counted_loop(
+                    context,
+                    builder,
+                    function,
+                    node_id,
+                    (start, end, step),
+                    acc_init,
+                    |index, acc| emit_loop_body_call(builder, body_fn, index.into(), acc, node_id),
+                )?;
+                if let Some(val) = result {
+                    values.insert(node_id, val);
+                }
+            }
+
+            ComputeOp::ForEach { body } => {
+                let body_fn = get_loop_body(module, graph, *body)?;
+                let arr_val = get_input(graph, node_id, 0, values)?.into_array_value();
+                let acc_init = loop_accumulator(graph, node_id, 1, values)?;
+                let elements = spill_array(builder, arr_val, node_id)?;
+                let result = emit_counted_loop(
+                    context,
+                    builder,
+                    function,
+                    node_id,
+                    elements.bounds(context),
+                    acc_init,
+                    |index, acc| {
+                        let item = elements.load(context, builder, index)?;
+                        emit_loop_body_call(builder, body_fn, item, acc, node_id)
+                    },
+                )?;
+                if let Some(val) = result {
+                    values.insert(node_id, val);
+                }
+            }
+
             // ----- Control Flow: Match -----
             ComputeOp::Match => {
                 emit_match(
@@ -804,6 +988,14 @@ fn emit_node<'ctx>(
                 emit_file_io_stub(context, module, builder, node_id, "fclose", values)?;
             }
 
+            // ----- Environment: Now / Random -----
+            ComputeOp::Now => {
+                emit_now(context, module, builder, node_id, values)?;
+            }
+            ComputeOp::Random => {
+                emit_random(context, module, builder, node_id, values)?;
+            }
+
             // ----- Closures: MakeClosure -----
             ComputeOp::MakeClosure {
                 function: closure_fn_id,
@@ -854,13 +1046,125 @@ fn emit_node<'ctx>(
                 values.insert(node_id, val);
             }
 
-            // ----- Contracts (filtered before reaching emit_node) -----
-            ComputeOp::Precondition { .. }
-            | ComputeOp::Postcondition { .. }
-            | ComputeOp::Invariant { .. } => {
-                // Contract nodes are filtered out before topological sort in
-                // compile_function. This arm exists only for exhaustiveness.
-                unreachable!("contract nodes should be filtered before codegen");
+            // ----- Contracts (only those the contract mode checks) -----
+            ComputeOp::Precondition { message }
+            | ComputeOp::Postcondition { message }
+            | ComputeOp::Invariant { message, .. } => {
+                // An unconnected condition passes, as in the interpreter
+                if get_input_type(graph, node_id, 0).is_err() {
+                    return Ok(());
+                }
+                let kind = match core_op {
+                    ComputeOp::Precondition { .. } => runtime::error_kind::PRECONDITION_VIOLATED,
+                    ComputeOp::Postcondition { .. } => runtime::error_kind::POSTCONDITION_VIOLATED,
+                    _ => runtime::error_kind::INVARIANT_VIOLATED,
+                };
+                let cond = get_input(graph, node_id, 0, values)?.into_int_value();
+                runtime::emit_contract_guard(
+                    builder, context, module, function, cond, kind, node_id.0, message,
+                )?;
+            }
+
+            // Old values are read before any other node (no inputs), so a
+            // pointer parameter's pointee still holds its entry value.
+            ComputeOp::Old { index } => {
+                let param = function.get_nth_param(*index).ok_or_else(|| {
+                    CodegenError::InvalidGraph(format!(
+                        "parameter index {} out of range for function with {} params",
+                        index,
+                        function.count_params()
+                    ))
+                })?;
+                let owner = graph.get_compute_node(node_id).unwrap().owner;
+                let param_type = graph
+                    .get_function(owner)
+                    .and_then(|f| f.params.get(*index as usize))
+                    .map(|(_, tid)| *tid)
+                    .ok_or_else(|| {
+                        CodegenError::InvalidGraph(format!("Old {} has no parameter", node_id))
+                    })?;
+                let val = match registry.get(param_type) {
+                    Some(LmType::Pointer { pointee, .. }) => {
+                        let pointee_type = lm_type_to_llvm(context, *pointee, registry)?;
+                        builder
+                            .build_load(
+                                pointee_type,
+                                param.into_pointer_value(),
+                                &format!("old_{}", node_id),
+                            )
+                            .map_err(|e| CodegenError::LlvmError(e.to_string()))?
+                    }
+                    _ => param,
+                };
+                values.insert(node_id, val);
+            }
+
+            // Quantifiers fold every item's predicate result into a Bool
+            ComputeOp::ForAll { predicate } | ComputeOp::Exists { predicate } => {
+                let universal = matches!(core_op, ComputeOp::ForAll { .. });
+                let pred_fn = get_loop_body(module, graph, *predicate)?;
+                let init = context.bool_type().const_int(universal as u64, false);
+                let combine =
+                    |acc: Option<BasicValueEnum<'ctx>>,
+                     holds: Option<BasicValueEnum<'ctx>>|
+                     -> Result<Option<BasicValueEnum<'ctx>>, CodegenError> {
+                        let (acc, holds) = match (acc, holds) {
+                            (Some(acc), Some(holds)) => {
+                                (acc.into_int_value(), holds.into_int_value())
+                            }
+                            _ => {
+                                return Err(CodegenError::InvalidGraph(format!(
+                                    "quantifier predicate at node {} returns no value",
+                                    node_id
+                                )))
+                            }
+                        };
+                        let folded = if universal {
+                            builder.build_and(acc, holds, "forall_acc")
+                        } else {
+                            builder.build_or(acc, holds, "exists_acc")
+                        };
+                        folded
+                            .map(|v| Some(v.into()))
+                            .map_err(|e| CodegenError::LlvmError(e.to_string()))
+                    };
+                let result = if get_input_type(graph, node_id, 1).is_ok() {
+                    let start = get_input(graph, node_id, 0, values)?.into_int_value();
+                    let end = get_input(graph, node_id, 1, values)?.into_int_value();
+                    let step = start.get_type().const_int(1, false);
+                    emit_counted_loop(
+                        context,
+                        builder,
+                        function,
+                        node_id,
+                        (start, end, step),
+                        Some(init.into()),
+                        |index, acc| {
+                            let holds =
+                                emit_loop_body_call(builder, pred_fn, index.into(), None, node_id)?;
+                            combine(acc, holds)
+                        },
+                    )?
+                } else {
+                    let arr_val = get_input(graph, node_id, 0, values)?.into_array_value();
+                    let elements = spill_array(builder, arr_val, node_id)?;
+                    emit_counted_loop(
+                        context,
+                        builder,
+                        function,
+                        node_id,
+                        elements.bounds(context),
+                        Some(init.into()),
+                        |index, acc| {
+                            let item = elements.load(context, builder, index)?;
+                            let holds = emit_loop_body_call(builder, pred_fn, item, None, node_id)?;
+                            combine(acc, holds)
+                        },
+                    )?
+                };
+                if let Some(val) = result {
+                    values.insert(node_id, val);
+                }
             }
         },
 
@@ -945,9 +1249,11 @@ fn emit_node<'ctx>(
                     let length = context.i32_type().const_int(arr_len as u64, false);
 
                     // Bounds check
-                    runtime::emit_bounds_guard(
-                        builder, context, module, function, index_int, length, node_id.0,
-                    )?;
+                    if guarded {
+                        runtime::emit_bounds_guard(
+                            builder, context, module, function, index_int, length, node_id.0,
+                        )?;
+                    }
 
                     // Alloca the array, GEP, load
                     let alloca = builder
@@ -996,9 +1302,11 @@ fn emit_node<'ctx>(
                     let arr_len = arr_type.len();
                     let length = context.i32_type().const_int(arr_len as u64, false);
 
-                    runtime::emit_bounds_guard(
-                        builder, context, module, function, index_int, length, node_id.0,
-                    )?;
+                    if guarded {
+                        runtime::emit_bounds_guard(
+                            builder, context, module, function, index_int, length, node_id.0,
+                        )?;
+                    }
 
                     let alloca = builder
                         .build_alloca(arr_type, "arr_set_tmp")
@@ -1024,6 +1332,146 @@ fn emit_node<'ctx>(
                 }
             }
 
+            // ----- Array combinators -----
+            StructuredOp::ArrayMap => {
+                let arr_val = get_input(graph, node_id, 0, values)?.into_array_value();
+                let callback = Callback::resolve(context, builder, graph, node_id, 1, values)?;
+                let result_type = callback.return_type.ok_or_else(|| {
+                    CodegenError::InvalidGraph(format!(
+                        "ArrayMap callback at node {} returns no value",
+                        node_id
+                    ))
+                })?;
+                let elements = spill_array(builder, arr_val, node_id)?;
+                let out_type = result_type.array_type(elements.len());
+                let out = builder
+                    .build_alloca(out_type, &format!("map_out_{}", node_id))
+                    .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                emit_counted_loop(
+                    context,
+                    builder,
+                    function,
+                    node_id,
+                    elements.bounds(context),
+                    None,
+                    |index, _| {
+                        let item = elements.load(context, builder, index)?;
+                        let mapped =
+                            callback.call(builder, &[item], node_id)?.ok_or_else(|| {
+                                CodegenError::InvalidGraph(format!(
+                                    "ArrayMap callback at node {} returns no value",
+                                    node_id
+                                ))
+                            })?;
+                        let slot = array_slot(context, builder, out_type, out, index)?;
+                        builder
+                            .build_store(slot, mapped)
+                            .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                        Ok(None)
+                    },
+                )?;
+                let val = builder
+                    .build_load(out_type, out, &format!("map_{}", node_id))
+                    .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                values.insert(node_id, val);
+            }
+
+            StructuredOp::ArrayFold => {
+                let arr_val = get_input(graph, node_id, 0, values)?.into_array_value();
+                let init = get_input(graph, node_id, 1, values)?;
+                let callback = Callback::resolve(context, builder, graph, node_id, 2, values)?;
+                let elements = spill_array(builder, arr_val, node_id)?;
+                let result = emit_counted_loop(
+                    context,
+                    builder,
+                    function,
+                    node_id,
+                    elements.bounds(context),
+                    Some(init),
+                    |index, acc| {
+                        let item = elements.load(context, builder, index)?;
+                        let acc = acc.ok_or_else(|| {
+                            CodegenError::LlvmError("fold loop has no accumulator".into())
+                        })?;
+                        callback.call(builder, &[acc, item], node_id)
+                    },
+                )?;
+                if let Some(val) = result {
+                    values.insert(node_id, val);
+                }
+            }
+
+            StructuredOp::ArrayFilter => {
+                // Kept elements are compacted to the front of a copy of the
+                // input; the result pairs the kept count with that array
+                let arr_val = get_input(graph, node_id, 0, values)?.into_array_value();
+                let callback = Callback::resolve(context, builder, graph, node_id, 1, values)?;
+                let elements = spill_array(builder, arr_val, node_id)?;
+                let out_type = arr_val.get_type();
+                let out = builder
+                    .build_alloca(out_type, &format!("filter_out_{}", node_id))
+                    .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                builder
+                    .build_store(out, arr_val)
+                    .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                let i32_type = context.i32_type();
+                let count = emit_counted_loop(
+                    context,
+                    builder,
+                    function,
+                    node_id,
+                    elements.bounds(context),
+                    Some(i32_type.const_zero().into()),
+                    |index, count| {
+                        let count = count
+                            .ok_or_else(|| {
+                                CodegenError::LlvmError("filter loop has no count".into())
+                            })?
+                            .into_int_value();
+                        let item = elements.load(context, builder, index)?;
+                        let keep = callback
+                            .call(builder, &[item], node_id)?
+                            .ok_or_else(|| {
+                                CodegenError::InvalidGraph(format!(
+                                    "ArrayFilter predicate at node {} returns no value",
+                                    node_id
+                                ))
+                            })?
+                            .into_int_value();
+                        // out[count] = keep ? item : out[count]
+                        let slot = array_slot(context, builder, out_type, out, count)?;
+                        let current = builder
+                            .build_load(out_type.get_element_type(), slot, "filter_cur")
+                            .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                        let chosen = builder
+                            .build_select(keep, item, current, "filter_sel")
+                            .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                        builder
+                            .build_store(slot, chosen)
+                            .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                        let kept = builder
+                            .build_int_z_extend(keep, i32_type, "filter_kept")
+                            .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                        let next = builder
+                            .build_int_add(count, kept, "filter_count")
+                            .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                        Ok(Some(next.into()))
+                    },
+                )?
+                .ok_or_else(|| CodegenError::LlvmError("filter loop has no count".into()))?;
+                let items = builder
+                    .build_load(out_type, out, &format!("filter_items_{}", node_id))
+                    .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                let result_type = context.struct_type(&[i32_type.into(), out_type.into()], false);
+                let with_count = builder
+                    .build_insert_value(result_type.get_undef(), count, 0, "filter_with_count")
+                    .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                let val = builder
+                    .build_insert_value(with_count, items, 1, &format!("filter_{}", node_id))
+                    .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+                values.insert(node_id, aggregate_to_basic(val));
+            }
+
             // ----- Cast -----
             StructuredOp::Cast { target_type } => {
                 let src = get_input(graph, node_id, 0, values)?;
@@ -1169,12 +1617,26 @@ fn emit_binary_arith<'ctx>(
     rhs: BasicValueEnum<'ctx>,
     op: &ArithOp,
     node_id: NodeId,
+    guarded: bool,
 ) -> Result<BasicValueEnum<'ctx>, CodegenError> {
     if lhs.is_int_value() {
         let lhs_int = lhs.into_int_value();
         let rhs_int = rhs.into_int_value();
 
         match op {
+            // Proven not to overflow: plain `nsw` arithmetic
+            ArithOp::Add if !guarded => builder
+                .build_int_nsw_add(lhs_int, rhs_int, "add")
+                .map(Into::into)
+                .map_err(|e| CodegenError::LlvmError(e.to_string())),
+            ArithOp::Sub if !guarded => builder
+                .build_int_nsw_sub(lhs_int, rhs_int, "sub")
+                .map(Into::into)
+                .map_err(|e| CodegenError::LlvmError(e.to_string())),
+            ArithOp::Mul if !guarded => builder
+                .build_int_nsw_mul(lhs_int, rhs_int, "mul")
+                .map(Into::into)
+                .map_err(|e| CodegenError::LlvmError(e.to_string())),
             ArithOp::Add => emit_checked_int_arith(
                 context, module, builder, function, lhs_int, rhs_int, "sadd", node_id,
             ),
@@ -1185,14 +1647,22 @@ fn emit_binary_arith<'ctx>(
                 context, module, builder, function, lhs_int, rhs_int, "smul", node_id,
             ),
             ArithOp::Div => {
-                runtime::emit_div_guard(builder, context, module, function, rhs_int, node_id.0)?;
+                if guarded {
+                    runtime::emit_div_guard(
+                        builder, context, module, function, rhs_int, node_id.0,
+                    )?;
+                }
                 let val = builder
                     .build_int_signed_div(lhs_int, rhs_int, "sdiv")
                     .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
                 Ok(val.into())
             }
             ArithOp::Rem => {
-                runtime::emit_div_guard(builder, context, module, function, rhs_int, node_id.0)?;
+                if guarded {
+                    runtime::emit_div_guard(
+                        builder, context, module, function, rhs_int, node_id.0,
+                    )?;
+                }
                 let val = builder
                     .build_int_signed_rem(lhs_int, rhs_int, "srem")
                     .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
@@ -1495,6 +1965,380 @@ fn emit_loop<'ctx>(
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Control flow: ForRange / ForEach
+// ---------------------------------------------------------------------------
+
+/// Look up the LLVM function for a `ForRange`/`ForEach` body.
+fn get_loop_body<'ctx>(
+    module: &Module<'ctx>,
+    graph: &ProgramGraph,
+    body: FunctionId,
+) -> Result<FunctionValue<'ctx>, CodegenError> {
+    let body_def = graph.get_function(body).ok_or_else(|| {
+        CodegenError::InvalidGraph(format!("loop body function {} not found", body))
+    })?;
+    module.get_function(&body_def.name).ok_or_else(|| {
+        CodegenError::InvalidGraph(format!(
+            "LLVM function '{}' not found in module",
+            body_def.name
+        ))
+    })
+}
+
+/// The initial accumulator of a structured loop, if its port is connected.
+fn loop_accumulator<'ctx>(
+    graph: &ProgramGraph,
+    node_id: NodeId,
+    port: u16,
+    values: &HashMap<NodeId, BasicValueEnum<'ctx>>,
+) -> Result<Option<BasicValueEnum<'ctx>>, CodegenError> {
+    if get_input_type(graph, node_id, port).is_err() {
+        return Ok(None);
+    }
+    get_input(graph, node_id, port, values).map(Some)
+}
+
+/// Call a `ForRange`/`ForEach` body with `item` and the optional accumulator.
+fn emit_loop_body_call<'ctx>(
+    builder: &Builder<'ctx>,
+    body_fn: FunctionValue<'ctx>,
+    item: BasicValueEnum<'ctx>,
+    acc: Option<BasicValueEnum<'ctx>>,
+    node_id: NodeId,
+) -> Result<Option<BasicValueEnum<'ctx>>, CodegenError> {
+    let mut args: Vec<inkwell::values::BasicMetadataValueEnum<'ctx>> = vec![item.into()];
+    if let Some(acc) = acc {
+        args.push(acc.into());
+    }
+    let call_result = builder
+        .build_call(body_fn, &args, &format!("for_call_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    Ok(call_result.try_as_basic_value().basic())
+}
+
+/// An array spilled to the stack so a loop body can index it.
+struct SpilledArray<'ctx> {
+    ty: inkwell::types::ArrayType<'ctx>,
+    ptr: PointerValue<'ctx>,
+}
+
+impl<'ctx> SpilledArray<'ctx> {
+    fn len(&self) -> u32 {
+        self.ty.len()
+    }
+
+    /// `(0, len, 1)` bounds for [`emit_counted_loop`].
+    fn bounds(&self, context: &'ctx Context) -> (IntValue<'ctx>, IntValue<'ctx>, IntValue<'ctx>) {
+        let i32_type = context.i32_type();
+        (
+            i32_type.const_zero(),
+            i32_type.const_int(self.len() as u64, false),
+            i32_type.const_int(1, false),
+        )
+    }
+
+    /// Load the element at `index`.
+    fn load(
+        &self,
+        context: &'ctx Context,
+        builder: &Builder<'ctx>,
+        index: IntValue<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, CodegenError> {
+        let slot = array_slot(context, builder, self.ty, self.ptr, index)?;
+        builder
+            .build_load(self.ty.get_element_type(), slot, "for_elem")
+            .map_err(|e| CodegenError::LlvmError(e.to_string()))
+    }
+}
+
+/// Store `arr_val` in a stack slot once, before the loop header.
+fn spill_array<'ctx>(
+    builder: &Builder<'ctx>,
+    arr_val: inkwell::values::ArrayValue<'ctx>,
+    node_id: NodeId,
+) -> Result<SpilledArray<'ctx>, CodegenError> {
+    let ty = arr_val.get_type();
+    let ptr = builder
+        .build_alloca(ty, &format!("for_arr_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    builder
+        .build_store(ptr, arr_val)
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    Ok(SpilledArray { ty, ptr })
+}
+
+/// Pointer to element `index` of the array stored at `ptr`.
+fn array_slot<'ctx>(
+    context: &'ctx Context,
+    builder: &Builder<'ctx>,
+    ty: inkwell::types::ArrayType<'ctx>,
+    ptr: PointerValue<'ctx>,
+    index: IntValue<'ctx>,
+) -> Result<PointerValue<'ctx>, CodegenError> {
+    unsafe {
+        builder
+            .build_in_bounds_gep(
+                ty,
+                ptr,
+                &[context.i32_type().const_zero(), index],
+                "for_elem_gep",
+            )
+            .map_err(|e| CodegenError::LlvmError(e.to_string()))
+    }
+}
+
+/// A closure or function pointer passed to an array combinator.
+struct Callback<'ctx> {
+    fn_type: inkwell::types::FunctionType<'ctx>,
+    fn_ptr: PointerValue<'ctx>,
+    /// Environment pointer, passed as the trailing argument when present.
+    env: Option<PointerValue<'ctx>>,
+    return_type: Option<BasicTypeEnum<'ctx>>,
+}
+
+impl<'ctx> Callback<'ctx> {
+    /// Resolve the callback flowing into `node_id` at `port`.
+    ///
+    /// A closure pair `{fn_ptr, env_ptr}` passes its environment unless it
+    /// comes straight from a `MakeClosure` of a function without captures
+    /// (such functions take no environment parameter). A bare pointer is
+    /// called directly. The call signature comes from the port's
+    /// `LmType::Function`.
+    fn resolve(
+        context: &'ctx Context,
+        builder: &Builder<'ctx>,
+        graph: &ProgramGraph,
+        node_id: NodeId,
+        port: u16,
+        values: &HashMap<NodeId, BasicValueEnum<'ctx>>,
+    ) -> Result<Self, CodegenError> {
+        let registry = &graph.types;
+        let callback_ty = get_input_type(graph, node_id, port)?;
+        let Some(LmType::Function {
+            params,
+            return_type,
+        }) = registry.get(callback_ty)
+        else {
+            return Err(CodegenError::InvalidGraph(format!(
+                "callback at node {} port {} is not a function value",
+                node_id, port
+            )));
+        };
+
+        let value = get_input(graph, node_id, port, values)?;
+        let (fn_ptr, env) = match value {
+            BasicValueEnum::StructValue(pair) => {
+                let extract = |index, name| {
+                    builder
+                        .build_extract_value(pair, index, name)
+                        .map(BasicValueEnum::into_pointer_value)
+                        .map_err(|e| CodegenError::LlvmError(e.to_string()))
+                };
+                let fn_ptr = extract(0, "cb_fn_ptr")?;
+                let env = extract(1, "cb_env_ptr")?;
+                let captureless = input_source(graph, node_id, port)
+                    .and_then(|src| match graph.get_compute_node(src).map(|n| &n.op) {
+                        Some(ComputeNodeOp::Core(ComputeOp::MakeClosure { function })) => {
+                            graph.get_function(*function)
+                        }
+                        _ => None,
+                    })
+                    .is_some_and(|def| def.captures.is_empty());
+                (fn_ptr, (!captureless).then_some(env))
+            }
+            BasicValueEnum::PointerValue(ptr) => (ptr, None),
+            other => {
+                return Err(CodegenError::InvalidGraph(format!(
+                    "callback at node {} has non-function value {:?}",
+                    node_id,
+                    other.get_type()
+                )))
+            }
+        };
+
+        let mut param_types: Vec<inkwell::types::BasicMetadataTypeEnum<'ctx>> = params
+            .iter()
+            .map(|tid| lm_type_to_llvm(context, *tid, registry).map(Into::into))
+            .collect::<Result<Vec<_>, _>>()?;
+        if env.is_some() {
+            param_types.push(context.ptr_type(AddressSpace::default()).into());
+        }
+        let (fn_type, return_type) = if *return_type == TypeId::UNIT {
+            (context.void_type().fn_type(&param_types, false), None)
+        } else {
+            let ret = lm_type_to_llvm(context, *return_type, registry)?;
+            (ret.fn_type(&param_types, false), Some(ret))
+        };
+
+        Ok(Callback {
+            fn_type,
+            fn_ptr,
+            env,
+            return_type,
+        })
+    }
+
+    /// Emit an indirect call with `args` (plus the environment, if any).
+    fn call(
+        &self,
+        builder: &Builder<'ctx>,
+        args: &[BasicValueEnum<'ctx>],
+        node_id: NodeId,
+    ) -> Result<Option<BasicValueEnum<'ctx>>, CodegenError> {
+        let mut call_args: Vec<inkwell::values::BasicMetadataValueEnum<'ctx>> =
+            args.iter().map(|a| (*a).into()).collect();
+        if let Some(env) = self.env {
+            call_args.push(env.into());
+        }
+        let call_result = builder
+            .build_indirect_call(
+                self.fn_type,
+                self.fn_ptr,
+                &call_args,
+                &format!("cb_call_{}", node_id),
+            )
+            .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+        Ok(call_result.try_as_basic_value().basic())
+    }
+}
+
+/// Emit the counted-loop skeleton shared by `ForRange`, `ForEach` and the
+/// array combinators.
+///
+/// Uses the same header/body/exit layout as [`emit_loop`]: the header holds
+/// `phi`s for the index and the optional accumulator and tests the bounds,
+/// the body runs `body(index, acc)` and takes the back-edge with the
+/// accumulator it returns. The loop also stops once `index + step` would
+/// overflow the index type, so the index never wraps. Returns the final
+/// accumulator and leaves the builder at the exit block.
+fn emit_counted_loop<'ctx>(
+    context: &'ctx Context,
+    builder: &Builder<'ctx>,
+    function: FunctionValue<'ctx>,
+    node_id: NodeId,
+    (start, end, step): (IntValue<'ctx>, IntValue<'ctx>, IntValue<'ctx>),
+    acc_init: Option<BasicValueEnum<'ctx>>,
+    mut body: impl FnMut(
+        IntValue<'ctx>,
+        Option<BasicValueEnum<'ctx>>,
+    ) -> Result<Option<BasicValueEnum<'ctx>>, CodegenError>,
+) -> Result<Option<BasicValueEnum<'ctx>>, CodegenError> {
+    let preheader_bb = builder
+        .get_insert_block()
+        .ok_or_else(|| CodegenError::LlvmError("builder has no insert block".into()))?;
+    let header_bb = context.append_basic_block(function, &format!("for_hdr_{}", node_id));
+    let body_bb = context.append_basic_block(function, &format!("for_body_{}", node_id));
+    let exit_bb = context.append_basic_block(function, &format!("for_exit_{}", node_id));
+
+    builder
+        .build_unconditional_branch(header_bb)
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    builder.position_at_end(header_bb);
+
+    let index_phi = builder
+        .build_phi(start.get_type(), &format!("for_idx_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let acc_phi = match acc_init {
+        Some(init) => Some(
+            builder
+                .build_phi(init.get_type(), &format!("for_acc_{}", node_id))
+                .map_err(|e| CodegenError::LlvmError(e.to_string()))?,
+        ),
+        None => None,
+    };
+    let live_phi = builder
+        .build_phi(context.bool_type(), &format!("for_live_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let index = index_phi.as_basic_value().into_int_value();
+
+    // Continue while the index has not reached `end` in the direction of
+    // `step` and the previous advance did not overflow; a zero step never
+    // enters the body.
+    let zero = start.get_type().const_zero();
+    let cmp = |pred, lhs, rhs, name| {
+        builder
+            .build_int_compare(pred, lhs, rhs, name)
+            .map_err(|e| CodegenError::LlvmError(e.to_string()))
+    };
+    let ascending = cmp(IntPredicate::SGT, step, zero, "for_up")?;
+    let descending = cmp(IntPredicate::SLT, step, zero, "for_down")?;
+    let below = cmp(IntPredicate::SLT, index, end, "for_below")?;
+    let above = cmp(IntPredicate::SGT, index, end, "for_above")?;
+    let go_up = builder
+        .build_and(ascending, below, "for_go_up")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let go_down = builder
+        .build_and(descending, above, "for_go_down")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let in_bounds = builder
+        .build_or(go_up, go_down, "for_in_bounds")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let cond = builder
+        .build_and(
+            live_phi.as_basic_value().into_int_value(),
+            in_bounds,
+            "for_cond",
+        )
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    builder
+        .build_conditional_branch(cond, body_bb, exit_bb)
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    // Body: run the iteration and advance the index
+    builder.position_at_end(body_bb);
+    let next_acc = body(index, acc_phi.map(|phi| phi.as_basic_value()))?;
+    let next_index = builder
+        .build_int_add(index, step, "for_next")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    // `index + step` overflows iff index > MAX - step (step > 0) or
+    // index < MIN - step (step < 0); neither limit overflows itself.
+    let index_type = start.get_type();
+    let sign_bit = 1u64 << (index_type.get_bit_width() - 1);
+    let sub = |lhs, name| {
+        builder
+            .build_int_sub(lhs, step, name)
+            .map_err(|e| CodegenError::LlvmError(e.to_string()))
+    };
+    let up_limit = sub(index_type.const_int(sign_bit - 1, false), "for_up_limit")?;
+    let down_limit = sub(index_type.const_int(sign_bit, false), "for_down_limit")?;
+    let past_up = cmp(IntPredicate::SGT, index, up_limit, "for_past_up")?;
+    let past_down = cmp(IntPredicate::SLT, index, down_limit, "for_past_down")?;
+    let overflow_up = builder
+        .build_and(ascending, past_up, "for_ovf_up")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let overflow_down = builder
+        .build_and(descending, past_down, "for_ovf_down")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let overflow = builder
+        .build_or(overflow_up, overflow_down, "for_ovf")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let next_live = builder
+        .build_not(overflow, "for_next_live")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let latch_bb = builder
+        .get_insert_block()
+        .ok_or_else(|| CodegenError::LlvmError("builder has no insert block".into()))?;
+    builder
+        .build_unconditional_branch(header_bb)
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    index_phi.add_incoming(&[(&start, preheader_bb), (&next_index, latch_bb)]);
+    live_phi.add_incoming(&[
+        (&context.bool_type().const_all_ones(), preheader_bb),
+        (&next_live, latch_bb),
+    ]);
+    if let (Some(phi), Some(init)) = (acc_phi, acc_init) {
+        let next_acc = next_acc.ok_or_else(|| {
+            CodegenError::InvalidGraph(format!("loop body at node {} returns no value", node_id))
+        })?;
+        phi.add_incoming(&[(&init, preheader_bb), (&next_acc, latch_bb)]);
+    }
+
+    builder.position_at_end(exit_bb);
+    Ok(acc_phi.map(|phi| phi.as_basic_value()))
+}
+
 // ---------------------------------------------------------------------------
 // Control flow: Match
 // ---------------------------------------------------------------------------
@@ -1636,6 +2480,187 @@ fn emit_file_io_stub<'ctx>(
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Environment ops
+// ---------------------------------------------------------------------------
+
+/// Emit an `alloca` at the start of the current function's entry block, so a
+/// node inside a loop reuses one stack slot instead of growing the stack on
+/// every iteration.
+fn build_entry_alloca<'ctx>(
+    context: &'ctx Context,
+    builder: &Builder<'ctx>,
+    ty: BasicTypeEnum<'ctx>,
+    name: &str,
+) -> Result<PointerValue<'ctx>, CodegenError> {
+    let entry = builder
+        .get_insert_block()
+        .and_then(|block| block.get_parent())
+        .and_then(|function| function.get_first_basic_block())
+        .ok_or_else(|| CodegenError::LlvmError("builder is not inside a function".into()))?;
+    let entry_builder = context.create_builder();
+    match entry.get_first_instruction() {
+        Some(first) => entry_builder.position_before(&first),
+        None => entry_builder.position_at_end(entry),
+    }
+    entry_builder
+        .build_alloca(ty, name)
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))
+}
+
+/// `CLOCK_REALTIME` clock id (0 on Linux and macOS).
+const CLOCK_REALTIME: u64 = 0;
+
+/// Emit `Now` as `clock_gettime(CLOCK_REALTIME, &ts)` and combine the
+/// timespec into I64 nanoseconds since the Unix epoch.
+fn emit_now<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+    node_id: NodeId,
+    values: &mut HashMap<NodeId, BasicValueEnum<'ctx>>,
+) -> Result<(), CodegenError> {
+    let i32_type = context.i32_type();
+    let i64_type = context.i64_type();
+    let ptr_type = context.ptr_type(AddressSpace::default());
+
+    let clock_gettime = match module.get_function("clock_gettime") {
+        Some(f) => f,
+        None => {
+            let fn_type = i32_type.fn_type(&[i32_type.into(), ptr_type.into()], false);
+            module.add_function(
+                "clock_gettime",
+                fn_type,
+                Some(inkwell::module::Linkage::External),
+            )
+        }
+    };
+
+    // struct timespec { i64 tv_sec; i64 tv_nsec; }
+    let timespec_type = context.struct_type(&[i64_type.into(), i64_type.into()], false);
+    let ts = build_entry_alloca(
+        context,
+        builder,
+        timespec_type.into(),
+        &format!("timespec_{}", node_id),
+    )?;
+    builder
+        .build_call(
+            clock_gettime,
+            &[i32_type.const_int(CLOCK_REALTIME, false).into(), ts.into()],
+            &format!("clock_gettime_{}", node_id),
+        )
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    let sec_ptr = builder
+        .build_struct_gep(timespec_type, ts, 0, &format!("tv_sec_ptr_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let nsec_ptr = builder
+        .build_struct_gep(timespec_type, ts, 1, &format!("tv_nsec_ptr_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let sec = builder
+        .build_load(i64_type, sec_ptr, &format!("tv_sec_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?
+        .into_int_value();
+    let nsec = builder
+        .build_load(i64_type, nsec_ptr, &format!("tv_nsec_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?
+        .into_int_value();
+
+    let sec_ns = builder
+        .build_int_mul(
+            sec,
+            i64_type.const_int(1_000_000_000, false),
+            &format!("sec_ns_{}", node_id),
+        )
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let now = builder
+        .build_int_add(sec_ns, nsec, &format!("now_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    values.insert(node_id, now.into());
+    Ok(())
+}
+
+/// Emit `Random` as a libc call that fills an 8-byte buffer, then load the
+/// filled I64.
+///
+/// Linux and Android targets call `getrandom(&buf, 8, 0)`. Other Unix
+/// targets, such as macOS and the BSDs, lack `getrandom` and call
+/// `getentropy(&buf, 8)` instead. Windows targets have neither and are
+/// rejected.
+fn emit_random<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+    node_id: NodeId,
+    values: &mut HashMap<NodeId, BasicValueEnum<'ctx>>,
+) -> Result<(), CodegenError> {
+    let i32_type = context.i32_type();
+    let i64_type = context.i64_type();
+    let ptr_type = context.ptr_type(AddressSpace::default());
+
+    let triple = module.get_triple();
+    let triple = triple.as_str().to_string_lossy();
+    if triple.contains("windows") {
+        return Err(CodegenError::UnsupportedOp(format!(
+            "Random is not supported on target {}",
+            triple
+        )));
+    }
+    let has_getrandom = triple.contains("linux") || triple.contains("android");
+
+    let fill = if has_getrandom {
+        match module.get_function("getrandom") {
+            Some(f) => f,
+            None => {
+                let fn_type =
+                    i64_type.fn_type(&[ptr_type.into(), i64_type.into(), i32_type.into()], false);
+                module.add_function(
+                    "getrandom",
+                    fn_type,
+                    Some(inkwell::module::Linkage::External),
+                )
+            }
+        }
+    } else {
+        match module.get_function("getentropy") {
+            Some(f) => f,
+            None => {
+                let fn_type = i32_type.fn_type(&[ptr_type.into(), i64_type.into()], false);
+                module.add_function(
+                    "getentropy",
+                    fn_type,
+                    Some(inkwell::module::Linkage::External),
+                )
+            }
+        }
+    };
+
+    let buf = build_entry_alloca(
+        context,
+        builder,
+        i64_type.into(),
+        &format!("random_buf_{}", node_id),
+    )?;
+    builder
+        .build_store(buf, i64_type.const_zero())
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let mut args = vec![buf.into(), i64_type.const_int(8, false).into()];
+    if has_getrandom {
+        args.push(i32_type.const_zero().into());
+    }
+    builder
+        .build_call(fill, &args, &format!("fill_random_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let value = builder
+        .build_load(i64_type, buf, &format!("random_{}", node_id))
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    values.insert(node_id, value);
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // MakeClosure
 // ---------------------------------------------------------------------------
@@ -1886,7 +2911,16 @@ mod tests {
         crate::runtime::declare_runtime_functions(&context, &module);
 
         let func_def = graph.get_function(func_id).unwrap().clone();
-        let result = compile_function(&context, &module, &builder, &graph, func_id, &func_def);
+        let result = compile_function(
+            &context,
+            &module,
+            &builder,
+            &graph,
+            func_id,
+            &func_def,
+            &RangeAnalysis::default(),
+            ContractMode::Off,
+        );
         assert!(
             result.is_ok(),
             "compile_function failed: {:?}",
@@ -2044,7 +3078,7 @@ mod tests {
         graph.add_data_edge(add, ret, 0, 0, TypeId::I32).unwrap();
 
         let nodes = graph.function_nodes(func_id);
-        let sorted = topological_sort(&nodes, &graph).unwrap();
+        let sorted = topological_sort(&nodes, &[], &graph).unwrap();
 
         // p and c should come before add, add before ret
         let p_pos = sorted.iter().position(|n| *n == p).unwrap();
@@ -2228,10 +3262,30 @@ mod tests {
         crate::runtime::declare_runtime_functions(&context, &module);
 
         let callee_def = graph.get_function(callee_id).unwrap().clone();
-        compile_function(&context, &module, &builder, &graph, callee_id, &callee_def).unwrap();
+        compile_function(
+            &context,
+            &module,
+            &builder,
+            &graph,
+            callee_id,
+            &callee_def,
+            &RangeAnalysis::default(),
+            ContractMode::Off,
+        )
+        .unwrap();
 
         let caller_def = graph.get_function(caller_id).unwrap().clone();
-        compile_function(&context, &module, &builder, &graph, caller_id, &caller_def).unwrap();
+        compile_function(
+            &context,
+            &module,
+            &builder,
+            &graph,
+            caller_id,
+            &caller_def,
+            &RangeAnalysis::default(),
+            ContractMode::Off,
+        )
+        .unwrap();
 
         assert!(
             module.verify().is_ok(),
@@ -2322,7 +3376,17 @@ mod tests {
         crate::runtime::declare_runtime_functions(&context, &module);
 
         let func_def = graph.get_function(func_id).unwrap().clone();
-        compile_function(&context, &module, &builder, &graph, func_id, &func_def).unwrap();
+        compile_function(
+            &context,
+            &module,
+            &builder,
+            &graph,
+            func_id,
+            &func_def,
+            &RangeAnalysis::default(),
+            ContractMode::Off,
+        )
+        .unwrap();
 
         assert!(
             module.verify().is_ok(),
@@ -20,7 +20,8 @@ use inkwell::targets::{
 use inkwell::types::BasicType;
 use inkwell::OptimizationLevel;
 
-use lmlang_check::typecheck;
+use lmlang_check::interpreter::entry;
+use lmlang_check::{intervals, typecheck};
 use lmlang_core::graph::ProgramGraph;
 
 use inkwell::AddressSpace;
@@ -49,6 +50,31 @@ pub fn compile(
     graph: &ProgramGraph,
     options: &CompileOptions,
 ) -> Result<CompileResult, CodegenError> {
+    let binary_name = determine_binary_name(graph, options);
+    compile_with_main(graph, options, &binary_name, |context, module, builder| {
+        generate_main_wrapper(context, module, builder, graph, options)
+    })
+}
+
+/// Runs the [`compile`] pipeline with `emit_main` generating the `main`
+/// function in place of the entry-point wrapper, linking the result as
+/// `binary_name` in the output directory.
+///
+/// Used by [`differential`](crate::differential) to build test harnesses
+/// that call arbitrary functions with fixed arguments.
+pub(crate) fn compile_with_main<F>(
+    graph: &ProgramGraph,
+    options: &CompileOptions,
+    binary_name: &str,
+    emit_main: F,
+) -> Result<CompileResult, CodegenError>
+where
+    F: for<'ctx> FnOnce(
+        &'ctx Context,
+        &Module<'ctx>,
+        &inkwell::builder::Builder<'ctx>,
+    ) -> Result<(), CodegenError>,
+{
     let start = Instant::now();
 
     // 1. Run type checker -- invalid graphs are rejected before codegen
@@ -92,12 +118,22 @@ pub fn compile(
     forward_declare_functions(&context, &module, graph)?;
 
     // 9. Compile each function in the graph (bodies only -- declarations exist)
+    let ranges = intervals::analyze_ranges(graph, options.contracts.checks_preconditions());
     for (func_id, func_def) in graph.functions() {
-        codegen::compile_function(&context, &module, &builder, graph, *func_id, func_def)?;
+        codegen::compile_function(
+            &context,
+            &module,
+            &builder,
+            graph,
+            *func_id,
+            func_def,
+            &ranges,
+            options.contracts,
+        )?;
     }
 
     // 11. Generate main wrapper
-    generate_main_wrapper(&context, &module, &builder, graph, options)?;
+    emit_main(&context, &module, &builder)?;
 
     // 12. Verify module
     module
@@ -138,9 +174,8 @@ pub fn compile(
         .write_to_file(&module, FileType::Object, &obj_path)
         .map_err(|e| CodegenError::LlvmError(format!("failed to write object file: {}", e)))?;
 
-    // 16. Determine output binary name
-    let binary_name = determine_binary_name(graph, options);
-    let output_path = options.output_dir.join(&binary_name);
+    // 16. Determine output binary path
+    let output_path = options.output_dir.join(binary_name);
 
     // 17. Link into executable
     linker::link_executable(&obj_path, &output_path, options.debug_symbols)?;
@@ -204,8 +239,18 @@ pub fn compile_to_ir(
     forward_declare_functions(&context, &module, graph)?;
 
     // 8. Compile each function
+    let ranges = intervals::analyze_ranges(graph, options.contracts.checks_preconditions());
     for (func_id, func_def) in graph.functions() {
-        codegen::compile_function(&context, &module, &builder, graph, *func_id, func_def)?;
+        codegen::compile_function(
+            &context,
+            &module,
+            &builder,
+            graph,
+            *func_id,
+            func_def,
+            &ranges,
+            options.contracts,
+        )?;
     }
 
     // 9. Generate main wrapper
@@ -257,13 +302,8 @@ pub fn compile_incremental(
         state.update_settings_hash(options);
     }
 
-    // 4. Compute current hashes using hash_all_functions_for_compilation
-    let blake_hashes = lmlang_storage::hash::hash_all_functions_for_compilation(graph);
-    let current_hashes: std::collections::HashMap<lmlang_core::id::FunctionId, [u8; 32]> =
-        blake_hashes
-            .iter()
-            .map(|(&fid, h)| (fid, *h.as_bytes()))
-            .collect();
+    // 4. Compute current hashes; contract nodes count only when checked
+    let current_hashes = crate::incremental::compilation_hashes(graph, options.contracts);
 
     // 5. Build call graph and compute dirty plan
     let call_graph = crate::incremental::build_call_graph(graph);
@@ -294,6 +334,7 @@ pub fn compile_incremental(
         .chain(plan.dirty_dependents.iter())
         .copied()
         .collect();
+    let ranges = intervals::analyze_ranges(graph, options.contracts.checks_preconditions());
 
     // 8. Emit runtime module (contains lmlang_runtime_error body)
     {
@@ -348,7 +389,16 @@ pub fn compile_incremental(
         forward_declare_functions(&context, &module, graph)?;
 
         // Compile only this function's body
-        codegen::compile_function(&context, &module, &builder, graph, func_id, func_def)?;
+        codegen::compile_function(
+            &context,
+            &module,
+            &builder,
+            graph,
+            func_id,
+            func_def,
+            &ranges,
+            options.contracts,
+        )?;
 
         // If this is the entry function named "main", rename it to __lmlang_main
         // so it doesn't conflict with the main wrapper's @main symbol.
@@ -485,14 +535,17 @@ pub fn compile_incremental(
 
 /// Generate the `main` wrapper function that calls the program's entry function.
 ///
-/// Entry function selection:
+/// Entry function selection and the argument/exit-status convention are
+/// shared with the interpreter (see [`lmlang_check::interpreter::entry`]):
 /// 1. If `options.entry_function` is specified, use that name.
 /// 2. Otherwise, find the first function named "main".
 /// 3. Otherwise, use the first public function.
 /// 4. Otherwise, use the first function.
 ///
-/// The entry function must take zero parameters. If it returns an integer type,
-/// that value is used as the process exit code; otherwise main returns 0.
+/// The entry function takes either zero parameters or one integer array
+/// `[T; N]`, which the wrapper fills from `argv[1..=N]` via `strtoll`
+/// (missing slots stay zero). If it returns an integer type, that value is
+/// used as the process exit code; otherwise main returns 0.
 fn generate_main_wrapper<'ctx>(
     context: &'ctx Context,
     module: &Module<'ctx>,
@@ -500,42 +553,18 @@ fn generate_main_wrapper<'ctx>(
     graph: &ProgramGraph,
     options: &CompileOptions,
 ) -> Result<(), CodegenError> {
-    let functions = graph.functions();
-    if functions.is_empty() {
+    if graph.functions().is_empty() {
         return Err(CodegenError::NoEntryFunction);
     }
 
-    // Find the entry function
-    let entry_func_def = if let Some(ref entry_name) = options.entry_function {
-        // Explicit entry function name
-        functions
-            .values()
-            .find(|f| f.name == *entry_name)
-            .ok_or_else(|| {
-                CodegenError::InvalidGraph(format!("entry function '{}' not found", entry_name))
-            })?
-    } else {
-        // Auto-detect: first "main", or first public, or first
-        functions
-            .values()
-            .find(|f| f.name == "main")
-            .or_else(|| {
-                functions
-                    .values()
-                    .find(|f| f.visibility == lmlang_core::types::Visibility::Public)
-            })
-            .or_else(|| functions.values().next())
-            .ok_or(CodegenError::NoEntryFunction)?
-    };
-
-    // Entry function must take zero parameters
-    if !entry_func_def.params.is_empty() {
-        return Err(CodegenError::InvalidGraph(format!(
-            "entry function '{}' must take zero parameters, but has {}",
-            entry_func_def.name,
-            entry_func_def.params.len()
-        )));
-    }
+    // Find the entry function and validate it against the entry convention
+    let entry_func_def = entry::select_entry_function(graph, options.entry_function.as_deref())
+        .map_err(|e| match e {
+            entry::EntryError::NoFunctions => CodegenError::NoEntryFunction,
+            other => CodegenError::InvalidGraph(other.to_string()),
+        })?;
+    let entry_point = entry::resolve_entry(graph, entry_func_def)
+        .map_err(|e| CodegenError::InvalidGraph(e.to_string()))?;
 
     // Look up the compiled LLVM function
     let entry_llvm_fn = module.get_function(&entry_func_def.name).ok_or_else(|| {
@@ -546,26 +575,42 @@ fn generate_main_wrapper<'ctx>(
     })?;
 
     // If the entry function is already named "main", rename it to avoid
-    // symbol conflicts, then create a proper `i32 @main()` wrapper.
+    // symbol conflicts, then create a proper `i32 @main(i32, ptr)` wrapper.
     // This ensures `main` always returns i32 (required by C runtime).
     if entry_func_def.name == "main" {
         entry_llvm_fn.as_global_value().set_name("__lmlang_main");
     }
 
-    // Create main() wrapper: i32 @main()
+    // Create main() wrapper: i32 @main(i32 %argc, ptr %argv)
     let i32_type = context.i32_type();
-    let main_fn_type = i32_type.fn_type(&[], false);
+    let ptr_type = context.ptr_type(AddressSpace::default());
+    let main_fn_type = i32_type.fn_type(&[i32_type.into(), ptr_type.into()], false);
     let main_fn = module.add_function("main", main_fn_type, None);
     let entry_bb = context.append_basic_block(main_fn, "entry");
     builder.position_at_end(entry_bb);
 
+    // Marshal argv into the argument array, if the entry function takes one
+    let mut call_args: Vec<inkwell::values::BasicMetadataValueEnum<'ctx>> = Vec::new();
+    if let Some(args) = entry_point.args {
+        let argc = main_fn
+            .get_nth_param(0)
+            .ok_or_else(|| CodegenError::LlvmError("main has no argc parameter".into()))?
+            .into_int_value();
+        let argv = main_fn
+            .get_nth_param(1)
+            .ok_or_else(|| CodegenError::LlvmError("main has no argv parameter".into()))?
+            .into_pointer_value();
+        let array = emit_entry_args(context, module, builder, main_fn, graph, argc, argv, args)?;
+        call_args.push(array.into());
+    }
+
     // Call the entry function
     let call_result = builder
-        .build_call(entry_llvm_fn, &[], "call_entry")
+        .build_call(entry_llvm_fn, &call_args, "call_entry")
         .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
 
     // If entry function returns an integer type, use as exit code
-    let return_type = entry_func_def.return_type;
+    let return_type = entry_point.return_type;
     if return_type == lmlang_core::type_id::TypeId::I32 {
         // Direct i32 return
         let ret_val = call_result.try_as_basic_value().basic().ok_or_else(|| {
@@ -607,6 +652,134 @@ fn generate_main_wrapper<'ctx>(
     Ok(())
 }
 
+/// Emit the argv-to-array marshalling loop for the entry argument array.
+///
+/// Allocates a zeroed `[N x T]`, then for `i in 0..N` stores
+/// `trunc(strtoll(argv[i + 1], null, 10))` into slot `i` while `i + 1 < argc`.
+/// Returns the loaded array value, ready to pass to the entry function.
+#[allow(clippy::too_many_arguments)]
+fn emit_entry_args<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &inkwell::builder::Builder<'ctx>,
+    main_fn: inkwell::values::FunctionValue<'ctx>,
+    graph: &ProgramGraph,
+    argc: inkwell::values::IntValue<'ctx>,
+    argv: inkwell::values::PointerValue<'ctx>,
+    args: entry::EntryArgs,
+) -> Result<inkwell::values::BasicValueEnum<'ctx>, CodegenError> {
+    let i32_type = context.i32_type();
+    let i64_type = context.i64_type();
+    let ptr_type = context.ptr_type(AddressSpace::default());
+
+    // strtoll(const char*, char**, int) -> i64
+    let strtoll_fn = module.get_function("strtoll").unwrap_or_else(|| {
+        let fn_type = i64_type.fn_type(&[ptr_type.into(), ptr_type.into(), i32_type.into()], false);
+        module.add_function("strtoll", fn_type, Some(inkwell::module::Linkage::External))
+    });
+
+    let array_type = lm_type_to_llvm(context, args.array_type, &graph.types)?;
+    let element_type = lm_type_to_llvm(context, args.element, &graph.types)?.into_int_type();
+
+    let array_ptr = builder
+        .build_alloca(array_type, "entry_args")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    builder
+        .build_store(array_ptr, array_type.const_zero())
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    let header_bb = context.append_basic_block(main_fn, "args_header");
+    let body_bb = context.append_basic_block(main_fn, "args_body");
+    let exit_bb = context.append_basic_block(main_fn, "args_exit");
+
+    let preheader_bb = builder
+        .get_insert_block()
+        .ok_or_else(|| CodegenError::LlvmError("no insert block for entry args".into()))?;
+    builder
+        .build_unconditional_branch(header_bb)
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    // Header: i = phi [0, preheader], [i + 1, body]; continue while i < N && i + 1 < argc
+    builder.position_at_end(header_bb);
+    let index = builder
+        .build_phi(i32_type, "arg_index")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let i = index.as_basic_value().into_int_value();
+    let next = builder
+        .build_int_add(i, i32_type.const_int(1, false), "arg_next")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let in_array = builder
+        .build_int_compare(
+            inkwell::IntPredicate::SLT,
+            i,
+            i32_type.const_int(args.length as u64, false),
+            "arg_in_array",
+        )
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let in_argv = builder
+        .build_int_compare(inkwell::IntPredicate::SLT, next, argc, "arg_in_argv")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let cont = builder
+        .build_and(in_array, in_argv, "arg_cont")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    builder
+        .build_conditional_branch(cont, body_bb, exit_bb)
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    // Body: arr[i] = trunc(strtoll(argv[i + 1], null, 10))
+    builder.position_at_end(body_bb);
+    let arg_slot = unsafe { builder.build_gep(ptr_type, argv, &[next], "argv_slot") }
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let arg_str = builder
+        .build_load(ptr_type, arg_slot, "argv_str")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let parsed = builder
+        .build_call(
+            strtoll_fn,
+            &[
+                arg_str.into(),
+                ptr_type.const_null().into(),
+                i32_type.const_int(10, false).into(),
+            ],
+            "arg_parsed",
+        )
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?
+        .try_as_basic_value()
+        .basic()
+        .ok_or_else(|| CodegenError::LlvmError("strtoll returned no value".into()))?
+        .into_int_value();
+    let element = if element_type.get_bit_width() < 64 {
+        builder
+            .build_int_truncate(parsed, element_type, "arg_trunc")
+            .map_err(|e| CodegenError::LlvmError(e.to_string()))?
+    } else {
+        parsed
+    };
+    let elem_ptr = unsafe {
+        builder.build_gep(
+            array_type,
+            array_ptr,
+            &[i32_type.const_zero(), i],
+            "arg_elem_ptr",
+        )
+    }
+    .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    builder
+        .build_store(elem_ptr, element)
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    builder
+        .build_unconditional_branch(header_bb)
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    index.add_incoming(&[(&i32_type.const_zero(), preheader_bb), (&next, body_bb)]);
+
+    // Exit: load the filled array
+    builder.position_at_end(exit_bb);
+    builder
+        .build_load(array_type, array_ptr, "entry_args_val")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))
+}
+
 /// Forward-declare all function signatures in the LLVM module.
 ///
 /// This ensures that Call nodes can find their target functions regardless of
@@ -0,0 +1,1079 @@
+//! Differential testing between the interpreter and compiled binaries.
+//!
+//! The interpreter and the LLVM backend implement the same semantics
+//! independently, so they can drift apart (overflow checks, float printing,
+//! enum layout). [`run_differential_tests`] runs one function on inputs from
+//! the property-test generators ([`contracts::property`]) through both and
+//! compares what each observed:
+//!
+//! - the lines printed by `Print` ops, formatted like the native runtime's
+//!   `printf` calls,
+//! - the return value, printed one scalar leaf per line (enums print their
+//!   discriminant), and
+//! - the trap kind when a run stops on a runtime error.
+//!
+//! Native runs use a harness binary whose `main` takes a case index in
+//! `argv[1]` and calls the function with that case's inputs as constants,
+//! so one compilation covers a whole batch of cases. Each case runs in its
+//! own process, since a trap exits the process.
+//!
+//! Cases the interpreter cannot settle are skipped rather than compared:
+//! exceeded execution limits or recursion depth, internal errors, and
+//! contract violations (contracts are not compiled). Random inputs violating
+//! the function's own preconditions are redrawn, as in property tests.
+//! Functions that (transitively) read input, files, the clock or random
+//! numbers are rejected, since the two runs would not see the same values.
+//!
+//! Mismatches are deduplicated by [`MismatchKind`]; the first of each kind is
+//! shrunk with [`shrink_candidates`] for as long as the same kind of mismatch
+//! persists. Shrinking keeps inputs within the parameter types, so fixed-size
+//! arrays keep their length.
+//!
+//! [`contracts::property`]: lmlang_check::contracts::property
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use inkwell::builder::Builder;
+use inkwell::context::Context;
+use inkwell::module::{Linkage, Module};
+use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum};
+use inkwell::AddressSpace;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use lmlang_check::contracts::property::{
+    generate_random_inputs, shrink_candidates, GeneratorLimits, DEFAULT_MAX_SHRINK_RUNS,
+    MAX_REJECTIONS_PER_CASE,
+};
+use lmlang_check::contracts::ContractKind;
+use lmlang_check::interpreter::{
+    BytecodeCache, Engine, ExecutionLimits, ExecutionState, Executor, InterpreterConfig,
+    RuntimeError, Value,
+};
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::FunctionId;
+use lmlang_core::ops::{ComputeNodeOp, ComputeOp};
+use lmlang_core::type_id::{TypeId, TypeRegistry};
+use lmlang_core::types::LmType;
+
+use crate::compiler::compile_with_main;
+use crate::error::CodegenError;
+use crate::runtime::{self, error_kind};
+use crate::types::lm_type_to_llvm;
+use crate::{CompileOptions, OptLevel};
+
+/// Line the harness prints between a case's `Print` output and its return
+/// value.
+const RETURN_MARKER: &str = "\u{1e}";
+
+/// Configuration for a differential test run.
+#[derive(Debug, Clone)]
+pub struct DifferentialConfig {
+    /// Inputs always compared, before the random cases.
+    pub seeds: Vec<Vec<Value>>,
+    /// Number of random cases to compare.
+    pub iterations: u32,
+    /// Seed for input generation (reproducibility).
+    pub random_seed: u64,
+    /// Maximum number of candidate inputs tried while shrinking each
+    /// distinct mismatch. Zero disables shrinking.
+    pub max_shrink_runs: u32,
+    /// Size bounds for generated compound values.
+    pub generator: GeneratorLimits,
+    /// Budgets for each interpreter run. Cases exceeding one are skipped.
+    pub limits: ExecutionLimits,
+    /// Engine running the interpreter side.
+    pub engine: Engine,
+    /// Lowered functions reused across runs.
+    pub bytecode_cache: BytecodeCache,
+    /// Optimization level of the harness binary.
+    pub opt_level: OptLevel,
+    /// Wall-clock budget for each native case. A case that exceeds it is
+    /// killed and reported as [`Outcome::TimedOut`].
+    pub native_timeout: Duration,
+}
+
+impl Default for DifferentialConfig {
+    fn default() -> Self {
+        DifferentialConfig {
+            seeds: Vec::new(),
+            iterations: 100,
+            random_seed: 0,
+            max_shrink_runs: DEFAULT_MAX_SHRINK_RUNS,
+            generator: GeneratorLimits::default(),
+            limits: ExecutionLimits {
+                max_steps: Some(1_000_000),
+                timeout_ms: Some(5_000),
+                max_memory_cells: Some(1_000_000),
+            },
+            engine: Engine::default(),
+            bytecode_cache: BytecodeCache::new(),
+            opt_level: OptLevel::O0,
+            native_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Errors preventing a differential test run.
+#[derive(Debug, thiserror::Error)]
+pub enum DifferentialError {
+    /// The function under test does not exist.
+    #[error("function {0} not found")]
+    FunctionNotFound(FunctionId),
+
+    /// The function cannot be compared across backends.
+    #[error("function {function} cannot be differentially tested: {reason}")]
+    Unsupported {
+        function: FunctionId,
+        reason: String,
+    },
+
+    /// Building the harness binary failed.
+    #[error("harness compilation failed: {0}")]
+    Codegen(#[from] CodegenError),
+
+    /// Running the harness binary failed.
+    #[error("failed to run harness: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Runtime error kinds shared by both backends, matching the native exit
+/// codes in [`error_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrapKind {
+    DivideByZero,
+    IntegerOverflow,
+    OutOfBounds,
+    NullPointer,
+    TypeMismatch,
+}
+
+impl TrapKind {
+    /// The trap a native process reports through its exit status.
+    pub fn from_exit_code(code: i32) -> Option<Self> {
+        Some(match u64::try_from(code).ok()? {
+            error_kind::DIVIDE_BY_ZERO => TrapKind::DivideByZero,
+            error_kind::INTEGER_OVERFLOW => TrapKind::IntegerOverflow,
+            error_kind::OUT_OF_BOUNDS => TrapKind::OutOfBounds,
+            error_kind::NULL_POINTER => TrapKind::NullPointer,
+            error_kind::TYPE_MISMATCH => TrapKind::TypeMismatch,
+            _ => return None,
+        })
+    }
+
+    /// The trap matching an interpreter runtime error, if it has a native
+    /// counterpart.
+    pub fn from_runtime_error(error: &RuntimeError) -> Option<Self> {
+        Some(match error {
+            RuntimeError::DivideByZero { .. } => TrapKind::DivideByZero,
+            RuntimeError::IntegerOverflow { .. } => TrapKind::IntegerOverflow,
+            RuntimeError::OutOfBoundsAccess { .. } => TrapKind::OutOfBounds,
+            RuntimeError::TypeMismatchAtRuntime { .. } => TrapKind::TypeMismatch,
+            _ => return None,
+        })
+    }
+}
+
+/// How a run ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Outcome {
+    /// The function returned; one line per scalar leaf of the value.
+    Returned { value: Vec<String> },
+    /// A runtime error stopped the run.
+    Trapped { trap: TrapKind },
+    /// Native only: the process died from a signal (`status` is `None`) or
+    /// exited with a status that is not a trap.
+    Crashed { status: Option<i32> },
+    /// Native only: the process exceeded [`DifferentialConfig::native_timeout`].
+    TimedOut,
+}
+
+/// Everything one backend observed for one input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Observation {
+    /// Lines printed by `Print` ops before the run ended.
+    pub output: Vec<String>,
+    /// How the run ended.
+    pub outcome: Outcome,
+}
+
+/// Which part of the observations differs first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchKind {
+    /// One backend trapped, crashed or timed out where the other did not, or
+    /// they trapped differently.
+    Outcome,
+    /// Both ended the same way but printed different output.
+    Output,
+    /// Both returned with the same output but different values.
+    ReturnValue,
+}
+
+impl MismatchKind {
+    /// Classifies two differing observations.
+    fn of(interpreter: &Observation, native: &Observation) -> Self {
+        let both_returned = matches!(
+            (&interpreter.outcome, &native.outcome),
+            (Outcome::Returned { .. }, Outcome::Returned { .. })
+        );
+        if !both_returned && interpreter.outcome != native.outcome {
+            MismatchKind::Outcome
+        } else if interpreter.output != native.output {
+            MismatchKind::Output
+        } else {
+            MismatchKind::ReturnValue
+        }
+    }
+}
+
+/// A divergence between the backends, with its original and shrunk inputs.
+#[derive(Debug, Clone, Serialize)]
+pub struct Mismatch {
+    /// What differs.
+    pub kind: MismatchKind,
+    /// The first inputs that showed this kind of mismatch.
+    pub inputs: Vec<Value>,
+    /// The minimal inputs found that still show it.
+    pub shrunk_inputs: Vec<Value>,
+    /// Number of shrink steps accepted on the way to `shrunk_inputs`.
+    pub shrink_steps: u32,
+    /// Number of compared cases showing this kind of mismatch.
+    pub occurrences: u32,
+    /// What the interpreter observed for `shrunk_inputs`.
+    pub interpreter: Observation,
+    /// What the compiled binary observed for `shrunk_inputs`.
+    pub native: Observation,
+}
+
+/// Result of a differential test run.
+#[derive(Debug, Clone, Serialize)]
+pub struct DifferentialReport {
+    /// The function tested.
+    pub function_id: FunctionId,
+    /// Cases run on both backends.
+    pub compared: u32,
+    /// Compared cases where both backends agreed.
+    pub agreed: u32,
+    /// Random inputs redrawn for violating the function's own preconditions.
+    pub rejected: u32,
+    /// Cases the interpreter could not settle (limits, contract violations,
+    /// internal errors), which were not run natively.
+    pub skipped: u32,
+    /// One mismatch per kind, in order of first occurrence.
+    pub mismatches: Vec<Mismatch>,
+    /// The random seed used (for reproducibility).
+    pub random_seed: u64,
+}
+
+impl DifferentialReport {
+    /// Whether every compared case agreed.
+    pub fn agrees(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Runs `func_id` through the interpreter and a compiled harness on seed and
+/// random inputs and reports where they disagree.
+pub fn run_differential_tests(
+    graph: &ProgramGraph,
+    func_id: FunctionId,
+    config: &DifferentialConfig,
+) -> Result<DifferentialReport, DifferentialError> {
+    let func_def = graph
+        .get_function(func_id)
+        .ok_or(DifferentialError::FunctionNotFound(func_id))?;
+    check_supported(graph, func_id)?;
+
+    let tester = Tester {
+        graph,
+        func_id,
+        executor: Executor::new(graph, config.engine, &config.bytecode_cache),
+        config,
+    };
+    let mut rng = ChaCha8Rng::seed_from_u64(config.random_seed);
+    let mut cases: Vec<(Vec<Value>, Observation)> = Vec::new();
+    let mut rejected = 0;
+    let mut skipped = 0;
+
+    for seed in &config.seeds {
+        match tester.interpret(seed) {
+            Interpreted::Observed(observation) => cases.push((seed.clone(), observation)),
+            Interpreted::Rejected | Interpreted::Skipped => skipped += 1,
+        }
+    }
+
+    for _ in 0..config.iterations {
+        for _ in 0..MAX_REJECTIONS_PER_CASE {
+            let inputs =
+                generate_random_inputs(&func_def.params, &graph.types, &config.generator, &mut rng)
+                    .ok_or_else(|| DifferentialError::Unsupported {
+                        function: func_id,
+                        reason: "a parameter type has no generator".to_string(),
+                    })?;
+            match tester.interpret(&inputs) {
+                Interpreted::Observed(observation) => cases.push((inputs, observation)),
+                Interpreted::Rejected => {
+                    rejected += 1;
+                    continue;
+                }
+                Interpreted::Skipped => skipped += 1,
+            }
+            break;
+        }
+    }
+
+    let natives = tester.run_natively(&cases)?;
+    let mut mismatches: Vec<Mismatch> = Vec::new();
+    let mut agreed = 0;
+    for ((inputs, interpreter), native) in cases.iter().zip(natives) {
+        if *interpreter == native {
+            agreed += 1;
+            continue;
+        }
+        let kind = MismatchKind::of(interpreter, &native);
+        match mismatches.iter_mut().find(|m| m.kind == kind) {
+            Some(existing) => existing.occurrences += 1,
+            None => mismatches.push(Mismatch {
+                kind,
+                inputs: inputs.clone(),
+                shrunk_inputs: inputs.clone(),
+                shrink_steps: 0,
+                occurrences: 1,
+                interpreter: interpreter.clone(),
+                native,
+            }),
+        }
+    }
+
+    for mismatch in &mut mismatches {
+        tester.shrink(mismatch)?;
+    }
+
+    Ok(DifferentialReport {
+        function_id: func_id,
+        compared: cases.len() as u32,
+        agreed,
+        rejected,
+        skipped,
+        mismatches,
+        random_seed: config.random_seed,
+    })
+}
+
+/// Formats a printed value the way the native runtime's `printf` calls do.
+///
+/// Returns `None` for `Unit`, which prints nothing. Compound values print as
+/// an opaque `<value>`.
+pub fn format_printed_value(value: &Value) -> Option<String> {
+    Some(match value {
+        Value::Bool(b) => b.to_string(),
+        Value::I8(v) => v.to_string(),
+        Value::I16(v) => v.to_string(),
+        Value::I32(v) => v.to_string(),
+        Value::I64(v) => v.to_string(),
+        Value::F32(v) => format_float(*v as f64),
+        Value::F64(v) => format_float(*v),
+        Value::Unit => return None,
+        _ => "<value>".to_string(),
+    })
+}
+
+/// `printf("%f")` formatting, including glibc's spelling of infinities and NaNs.
+fn format_float(x: f64) -> String {
+    if x.is_nan() {
+        if x.is_sign_negative() { "-nan" } else { "nan" }.to_string()
+    } else if x.is_infinite() {
+        if x < 0.0 { "-inf" } else { "inf" }.to_string()
+    } else {
+        format!("{:.6}", x)
+    }
+}
+
+/// Appends the lines the harness prints for a returned value: one per
+/// scalar leaf, with enums printing their discriminant.
+fn render_result(value: &Value, lines: &mut Vec<String>) {
+    match value {
+        Value::Array {
+            elements: items, ..
+        }
+        | Value::Struct { fields: items, .. } => {
+            for item in items {
+                render_result(item, lines);
+            }
+        }
+        Value::Enum { variant, .. } => lines.push(variant.to_string()),
+        other => lines.extend(format_printed_value(other)),
+    }
+}
+
+/// Rejects functions whose behavior depends on their environment, and
+/// closures, which the harness cannot call directly.
+fn check_supported(graph: &ProgramGraph, func_id: FunctionId) -> Result<(), DifferentialError> {
+    let unsupported = |reason: String| DifferentialError::Unsupported {
+        function: func_id,
+        reason,
+    };
+    let func_def = &graph.functions()[&func_id];
+    if func_def.is_closure && !func_def.captures.is_empty() {
+        return Err(unsupported(
+            "closures with captures cannot be called directly".to_string(),
+        ));
+    }
+
+    let mut seen = HashSet::from([func_id]);
+    let mut pending = vec![func_id];
+    while let Some(function) = pending.pop() {
+        let mut nodes = graph.function_nodes(function);
+        nodes.sort_by_key(|n| n.0);
+        for node_id in nodes {
+            let Some(ComputeNodeOp::Core(op)) = graph.get_compute_node(node_id).map(|n| &n.op)
+            else {
+                continue;
+            };
+            if op.is_environment() {
+                return Err(unsupported(format!(
+                    "node {} in function {} is nondeterministic across backends",
+                    node_id, function
+                )));
+            }
+            if op.is_io() && !matches!(op, ComputeOp::Print) {
+                return Err(unsupported(format!(
+                    "node {} in function {} performs input or file I/O",
+                    node_id, function
+                )));
+            }
+            let callee = match op {
+                ComputeOp::MakeClosure { function } => Some(*function),
+                other => other.call_target(),
+            };
+            if let Some(callee) = callee {
+                if seen.insert(callee) {
+                    pending.push(callee);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether `value` is a well-formed value of `type_id`.
+fn fits_type(value: &Value, type_id: TypeId, registry: &TypeRegistry) -> bool {
+    match (value, registry.get(type_id)) {
+        (Value::Bool(_), _) => type_id == TypeId::BOOL,
+        (Value::I8(_), _) => type_id == TypeId::I8,
+        (Value::I16(_), _) => type_id == TypeId::I16,
+        (Value::I32(_), _) => type_id == TypeId::I32,
+        (Value::I64(_), _) => type_id == TypeId::I64,
+        (Value::F32(_), _) => type_id == TypeId::F32,
+        (Value::F64(_), _) => type_id == TypeId::F64,
+        (Value::Unit, _) => type_id == TypeId::UNIT,
+        (
+            Value::Array {
+                elements: items, ..
+            },
+            Some(LmType::Array { element, length }),
+        ) => {
+            items.len() == *length as usize
+                && items.iter().all(|item| fits_type(item, *element, registry))
+        }
+        (Value::Struct { fields, .. }, Some(LmType::Struct(def))) => {
+            fields.len() == def.fields.len()
+                && fields
+                    .iter()
+                    .zip(def.fields.values())
+                    .all(|(field, field_type)| fits_type(field, *field_type, registry))
+        }
+        (
+            Value::Enum {
+                variant, payload, ..
+            },
+            Some(LmType::Enum(def)),
+        ) => def
+            .variants
+            .values()
+            .find(|v| v.index == *variant)
+            .is_some_and(|v| fits_type(payload, v.payload.unwrap_or(TypeId::UNIT), registry)),
+        _ => false,
+    }
+}
+
+/// Interpreter verdict on one input.
+enum Interpreted {
+    /// Comparable with a native run.
+    Observed(Observation),
+    /// Outside the function's own preconditions.
+    Rejected,
+    /// Not comparable (limits, contract violations, internal errors).
+    Skipped,
+}
+
+/// Shared state for one function under test.
+struct Tester<'a> {
+    graph: &'a ProgramGraph,
+    func_id: FunctionId,
+    executor: Executor<'a>,
+    config: &'a DifferentialConfig,
+}
+
+impl Tester<'_> {
+    /// Runs `inputs` through the interpreter.
+    fn interpret(&self, inputs: &[Value]) -> Interpreted {
+        let config = InterpreterConfig {
+            random_seed: self.config.random_seed,
+            limits: self.config.limits,
+            ..InterpreterConfig::default()
+        };
+        let run = self.executor.run(&config, self.func_id, inputs.to_vec());
+        let output = run.io_log.iter().filter_map(format_printed_value).collect();
+        let outcome = match &run.state {
+            ExecutionState::Completed { result } => {
+                let mut value = Vec::new();
+                render_result(result, &mut value);
+                Outcome::Returned { value }
+            }
+            ExecutionState::Error { error, .. } => match TrapKind::from_runtime_error(error) {
+                Some(trap) => Outcome::Trapped { trap },
+                None => return Interpreted::Skipped,
+            },
+            ExecutionState::ContractViolation { violation }
+                if violation.kind == ContractKind::Precondition
+                    && violation.function_id == self.func_id =>
+            {
+                return Interpreted::Rejected
+            }
+            _ => return Interpreted::Skipped,
+        };
+        Interpreted::Observed(Observation { output, outcome })
+    }
+
+    /// Runs every case natively through one harness binary.
+    fn run_natively(
+        &self,
+        cases: &[(Vec<Value>, Observation)],
+    ) -> Result<Vec<Observation>, DifferentialError> {
+        if cases.is_empty() {
+            return Ok(Vec::new());
+        }
+        let inputs: Vec<&[Value]> = cases.iter().map(|(inputs, _)| inputs.as_slice()).collect();
+        let harness = Harness::build(self.graph, self.func_id, &inputs, self.config.opt_level)?;
+        (0..cases.len())
+            .map(|case| Ok(harness.run(case, self.config.native_timeout)?))
+            .collect()
+    }
+
+    /// Shrinks a mismatch's inputs while the same kind of mismatch persists.
+    ///
+    /// Greedy: each round interprets the candidates for every input position,
+    /// runs the comparable ones natively in one batch, and takes the first
+    /// that still mismatches, until none does or the budget is spent.
+    fn shrink(&self, mismatch: &mut Mismatch) -> Result<(), DifferentialError> {
+        let params = &self.graph.functions()[&self.func_id].params;
+        let mut budget = self.config.max_shrink_runs;
+
+        'rounds: while budget > 0 {
+            let mut trials: Vec<(Vec<Value>, Observation)> = Vec::new();
+            'candidates: for position in 0..params.len() {
+                for candidate in shrink_candidates(
+                    &mismatch.shrunk_inputs[position],
+                    &self.graph.types,
+                    &self.config.generator,
+                ) {
+                    if budget == 0 {
+                        break 'candidates;
+                    }
+                    budget -= 1;
+                    let mut trial = mismatch.shrunk_inputs.clone();
+                    trial[position] = candidate;
+                    if let Interpreted::Observed(observation) = self.interpret(&trial) {
+                        trials.push((trial, observation));
+                    }
+                }
+            }
+
+            let natives = self.run_natively(&trials)?;
+            for ((trial, interpreter), native) in trials.into_iter().zip(natives) {
+                if interpreter != native && MismatchKind::of(&interpreter, &native) == mismatch.kind
+                {
+                    mismatch.shrunk_inputs = trial;
+                    mismatch.interpreter = interpreter;
+                    mismatch.native = native;
+                    mismatch.shrink_steps += 1;
+                    continue 'rounds;
+                }
+            }
+            break;
+        }
+        Ok(())
+    }
+}
+
+/// A compiled harness binary, deleted with its temporary directory.
+struct Harness {
+    _dir: tempfile::TempDir,
+    binary: PathBuf,
+}
+
+impl Harness {
+    /// Compiles a harness whose `main` calls `func_id` with `cases[argv[1]]`.
+    fn build(
+        graph: &ProgramGraph,
+        func_id: FunctionId,
+        cases: &[&[Value]],
+        opt_level: OptLevel,
+    ) -> Result<Self, CodegenError> {
+        let dir = tempfile::tempdir()?;
+        let options = CompileOptions {
+            output_dir: dir.path().to_path_buf(),
+            opt_level,
+            ..Default::default()
+        };
+        let result = compile_with_main(graph, &options, "harness", |context, module, builder| {
+            emit_harness_main(context, module, builder, graph, func_id, cases)
+        })?;
+        Ok(Harness {
+            _dir: dir,
+            binary: result.binary_path,
+        })
+    }
+
+    /// Runs one case, killing it after `timeout`.
+    fn run(&self, case: usize, timeout: Duration) -> std::io::Result<Observation> {
+        let mut child = Command::new(&self.binary)
+            .arg(case.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let reader = std::thread::spawn(move || {
+            let mut bytes = Vec::new();
+            stdout.read_to_end(&mut bytes).map(|_| bytes)
+        });
+
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break Some(status);
+            }
+            if Instant::now() >= deadline {
+                child.kill()?;
+                child.wait()?;
+                break None;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        };
+        let bytes = reader
+            .join()
+            .map_err(|_| std::io::Error::other("stdout reader panicked"))??;
+        let text = String::from_utf8_lossy(&bytes);
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+
+        let outcome = match status.map(|s| s.code()) {
+            None => Outcome::TimedOut,
+            Some(Some(0)) => match lines.iter().position(|l| l == RETURN_MARKER) {
+                Some(marker) => {
+                    let value = lines.split_off(marker + 1);
+                    lines.pop();
+                    Outcome::Returned { value }
+                }
+                None => Outcome::Crashed { status: Some(0) },
+            },
+            Some(Some(code)) => match TrapKind::from_exit_code(code) {
+                Some(trap) => Outcome::Trapped { trap },
+                None => Outcome::Crashed { status: Some(code) },
+            },
+            Some(None) => Outcome::Crashed { status: None },
+        };
+        Ok(Observation {
+            output: lines,
+            outcome,
+        })
+    }
+}
+
+/// Emits `i32 main(i32, ptr)` switching on `strtoll(argv[1])`. Case `k`
+/// calls the function under test with `cases[k]`, prints [`RETURN_MARKER`]
+/// and then the result's scalar leaves, and returns 0.
+fn emit_harness_main<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+    graph: &ProgramGraph,
+    func_id: FunctionId,
+    cases: &[&[Value]],
+) -> Result<(), CodegenError> {
+    let func_def = graph
+        .get_function(func_id)
+        .ok_or_else(|| CodegenError::InvalidGraph(format!("function {} not found", func_id.0)))?;
+    let target = module.get_function(&func_def.name).ok_or_else(|| {
+        CodegenError::LlvmError(format!(
+            "compiled function '{}' not found in LLVM module",
+            func_def.name
+        ))
+    })?;
+    // A program function named "main" would clash with the harness entry
+    if let Some(program_main) = module.get_function("main") {
+        program_main.as_global_value().set_name("__lmlang_main");
+    }
+
+    let llvm_err = |e: inkwell::builder::BuilderError| CodegenError::LlvmError(e.to_string());
+    let i32_type = context.i32_type();
+    let i64_type = context.i64_type();
+    let ptr_type = context.ptr_type(AddressSpace::default());
+    let strtoll_fn = module.get_function("strtoll").unwrap_or_else(|| {
+        let fn_type = i64_type.fn_type(&[ptr_type.into(), ptr_type.into(), i32_type.into()], false);
+        module.add_function("strtoll", fn_type, Some(Linkage::External))
+    });
+    let printf_fn = module
+        .get_function("printf")
+        .ok_or_else(|| CodegenError::LlvmError("printf not found".into()))?;
+
+    let main_fn = module.add_function(
+        "main",
+        i32_type.fn_type(&[i32_type.into(), ptr_type.into()], false),
+        None,
+    );
+    let entry_bb = context.append_basic_block(main_fn, "entry");
+    let done_bb = context.append_basic_block(main_fn, "done");
+
+    builder.position_at_end(entry_bb);
+    let argv = main_fn
+        .get_nth_param(1)
+        .ok_or_else(|| CodegenError::LlvmError("main has no argv parameter".into()))?
+        .into_pointer_value();
+    let arg_slot =
+        unsafe { builder.build_gep(ptr_type, argv, &[i32_type.const_int(1, false)], "case_arg") }
+            .map_err(llvm_err)?;
+    let arg_str = builder
+        .build_load(ptr_type, arg_slot, "case_str")
+        .map_err(llvm_err)?;
+    let case_index = builder
+        .build_call(
+            strtoll_fn,
+            &[
+                arg_str.into(),
+                ptr_type.const_null().into(),
+                i32_type.const_int(10, false).into(),
+            ],
+            "case_index",
+        )
+        .map_err(llvm_err)?
+        .try_as_basic_value()
+        .basic()
+        .ok_or_else(|| CodegenError::LlvmError("strtoll returned no value".into()))?
+        .into_int_value();
+
+    let mut switch_cases = Vec::with_capacity(cases.len());
+    for (index, inputs) in cases.iter().enumerate() {
+        let case_bb = context.append_basic_block(main_fn, &format!("case_{}", index));
+        switch_cases.push((i64_type.const_int(index as u64, false), case_bb));
+        builder.position_at_end(case_bb);
+
+        if inputs.len() != func_def.params.len() {
+            return Err(CodegenError::InvalidGraph(format!(
+                "case {} has {} inputs, function {} takes {}",
+                index,
+                inputs.len(),
+                func_id.0,
+                func_def.params.len()
+            )));
+        }
+        let args = inputs
+            .iter()
+            .zip(&func_def.params)
+            .map(|(value, (_, type_id))| {
+                build_argument(context, builder, graph, value, *type_id).map(Into::into)
+            })
+            .collect::<Result<Vec<BasicMetadataValueEnum<'ctx>>, _>>()?;
+        let result = builder
+            .build_call(target, &args, "result")
+            .map_err(llvm_err)?
+            .try_as_basic_value()
+            .basic();
+
+        let marker = builder
+            .build_global_string_ptr(&format!("{}\n", RETURN_MARKER), "return_marker")
+            .map_err(llvm_err)?;
+        builder
+            .build_call(printf_fn, &[marker.as_pointer_value().into()], "")
+            .map_err(llvm_err)?;
+        if let Some(result) = result {
+            emit_print_result(
+                context,
+                module,
+                builder,
+                graph,
+                result,
+                func_def.return_type,
+            )?;
+        }
+        builder
+            .build_unconditional_branch(done_bb)
+            .map_err(llvm_err)?;
+    }
+
+    builder.position_at_end(entry_bb);
+    builder
+        .build_switch(case_index, done_bb, &switch_cases)
+        .map_err(llvm_err)?;
+    builder.position_at_end(done_bb);
+    builder
+        .build_return(Some(&i32_type.const_zero()))
+        .map_err(llvm_err)?;
+    Ok(())
+}
+
+/// Builds `value` as an LLVM value of `type_id`.
+fn build_argument<'ctx>(
+    context: &'ctx Context,
+    builder: &Builder<'ctx>,
+    graph: &ProgramGraph,
+    value: &Value,
+    type_id: TypeId,
+) -> Result<BasicValueEnum<'ctx>, CodegenError> {
+    let llvm_err = |e: inkwell::builder::BuilderError| CodegenError::LlvmError(e.to_string());
+    if !fits_type(value, type_id, &graph.types) {
+        return Err(CodegenError::TypeMapping(format!(
+            "input {:?} does not have type {}",
+            value, type_id
+        )));
+    }
+    let llvm_type = lm_type_to_llvm(context, type_id, &graph.types)?;
+
+    Ok(match value {
+        Value::Bool(b) => context.bool_type().const_int(*b as u64, false).into(),
+        Value::I8(v) => context.i8_type().const_int(*v as i64 as u64, true).into(),
+        Value::I16(v) => context.i16_type().const_int(*v as i64 as u64, true).into(),
+        Value::I32(v) => context.i32_type().const_int(*v as i64 as u64, true).into(),
+        Value::I64(v) => context.i64_type().const_int(*v as u64, true).into(),
+        Value::F32(v) => context.f32_type().const_float(*v as f64).into(),
+        Value::F64(v) => context.f64_type().const_float(*v).into(),
+        Value::Unit => context.const_struct(&[], false).into(),
+        Value::Array {
+            elements: items, ..
+        } => {
+            let Some(LmType::Array { element, .. }) = graph.types.get(type_id) else {
+                unreachable!("fits_type checked the array type");
+            };
+            let mut array = llvm_type.into_array_type().get_undef();
+            for (index, item) in items.iter().enumerate() {
+                let item = build_argument(context, builder, graph, item, *element)?;
+                array = builder
+                    .build_insert_value(array, item, index as u32, "arg_elem")
+                    .map_err(llvm_err)?
+                    .into_array_value();
+            }
+            array.into()
+        }
+        Value::Struct { fields, .. } => {
+            let Some(LmType::Struct(def)) = graph.types.get(type_id) else {
+                unreachable!("fits_type checked the struct type");
+            };
+            let mut record = llvm_type.into_struct_type().get_undef();
+            for (index, (field, field_type)) in fields.iter().zip(def.fields.values()).enumerate() {
+                let field = build_argument(context, builder, graph, field, *field_type)?;
+                record = builder
+                    .build_insert_value(record, field, index as u32, "arg_field")
+                    .map_err(llvm_err)?
+                    .into_struct_value();
+            }
+            record.into()
+        }
+        Value::Enum {
+            variant, payload, ..
+        } => {
+            let Some(LmType::Enum(def)) = graph.types.get(type_id) else {
+                unreachable!("fits_type checked the enum type");
+            };
+            let payload_type = def
+                .variants
+                .values()
+                .find(|v| v.index == *variant)
+                .and_then(|v| v.payload);
+            // Store the payload through memory, like EnumCreate, so it lands
+            // in the tagged union's byte array
+            let enum_type = llvm_type.into_struct_type();
+            let slot = builder
+                .build_alloca(enum_type, "arg_enum")
+                .map_err(llvm_err)?;
+            builder
+                .build_store(slot, enum_type.const_zero())
+                .map_err(llvm_err)?;
+            let tag = builder
+                .build_struct_gep(enum_type, slot, 0, "arg_tag")
+                .map_err(llvm_err)?;
+            builder
+                .build_store(tag, context.i32_type().const_int(*variant as u64, false))
+                .map_err(llvm_err)?;
+            if let Some(payload_type) = payload_type.filter(|_| enum_type.count_fields() > 1) {
+                let payload = build_argument(context, builder, graph, payload, payload_type)?;
+                let bytes = builder
+                    .build_struct_gep(enum_type, slot, 1, "arg_payload")
+                    .map_err(llvm_err)?;
+                builder.build_store(bytes, payload).map_err(llvm_err)?;
+            }
+            builder
+                .build_load(enum_type, slot, "arg_enum_val")
+                .map_err(llvm_err)?
+        }
+        other => {
+            return Err(CodegenError::TypeMapping(format!(
+                "cannot pass {:?} to a compiled function",
+                other
+            )))
+        }
+    })
+}
+
+/// Prints a returned value one scalar leaf per line, matching
+/// [`render_result`].
+fn emit_print_result<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+    graph: &ProgramGraph,
+    value: BasicValueEnum<'ctx>,
+    type_id: TypeId,
+) -> Result<(), CodegenError> {
+    let llvm_err = |e: inkwell::builder::BuilderError| CodegenError::LlvmError(e.to_string());
+    let leaves: Vec<TypeId> = match graph.types.get(type_id) {
+        Some(LmType::Array { element, length }) => vec![*element; *length as usize],
+        Some(LmType::Struct(def)) => def.fields.values().copied().collect(),
+        Some(LmType::Enum(_)) => {
+            let tag = builder
+                .build_extract_value(value.into_struct_value(), 0, "result_tag")
+                .map_err(llvm_err)?;
+            return runtime::emit_print_value(builder, context, module, tag, TypeId::I32);
+        }
+        _ => return runtime::emit_print_value(builder, context, module, value, type_id),
+    };
+
+    for (index, leaf_type) in leaves.into_iter().enumerate() {
+        let leaf = match value {
+            BasicValueEnum::ArrayValue(array) => {
+                builder.build_extract_value(array, index as u32, "result_elem")
+            }
+            BasicValueEnum::StructValue(record) => {
+                builder.build_extract_value(record, index as u32, "result_field")
+            }
+            other => {
+                return Err(CodegenError::TypeMapping(format!(
+                    "expected an aggregate result, got {:?}",
+                    other
+                )))
+            }
+        }
+        .map_err(llvm_err)?;
+        emit_print_result(context, module, builder, graph, leaf, leaf_type)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lmlang_core::id::NodeId;
+
+    #[test]
+    fn printed_floats_follow_printf() {
+        assert_eq!(format_printed_value(&Value::F64(1.5)).unwrap(), "1.500000");
+        assert_eq!(
+            format_printed_value(&Value::F64(-0.0)).unwrap(),
+            "-0.000000"
+        );
+        assert_eq!(format_printed_value(&Value::F32(0.1)).unwrap(), "0.100000");
+        assert_eq!(
+            format_printed_value(&Value::F64(f64::INFINITY)).unwrap(),
+            "inf"
+        );
+        assert_eq!(
+            format_printed_value(&Value::F64(-f64::NAN)).unwrap(),
+            "-nan"
+        );
+        assert_eq!(format_printed_value(&Value::Unit), None);
+    }
+
+    #[test]
+    fn results_render_one_line_per_leaf() {
+        let mut lines = Vec::new();
+        render_result(
+            &Value::Struct {
+                ty: TypeId::UNIT,
+                fields: vec![
+                    Value::untyped_array(vec![Value::I8(-1), Value::I8(2)]),
+                    Value::Bool(true),
+                    Value::Unit,
+                    Value::Enum {
+                        ty: TypeId::UNIT,
+                        variant: 3,
+                        payload: Box::new(Value::I64(9)),
+                    },
+                ],
+            },
+            &mut lines,
+        );
+        assert_eq!(lines, ["-1", "2", "true", "3"]);
+    }
+
+    #[test]
+    fn mismatch_kind_prefers_outcome_over_output() {
+        let returned = |value: &str, output: &[&str]| Observation {
+            output: output.iter().map(|s| s.to_string()).collect(),
+            outcome: Outcome::Returned {
+                value: vec![value.to_string()],
+            },
+        };
+        let trapped = Observation {
+            output: Vec::new(),
+            outcome: Outcome::Trapped {
+                trap: TrapKind::IntegerOverflow,
+            },
+        };
+        assert_eq!(
+            MismatchKind::of(&returned("1", &["x"]), &trapped),
+            MismatchKind::Outcome
+        );
+        assert_eq!(
+            MismatchKind::of(&returned("1", &["x"]), &returned("2", &["y"])),
+            MismatchKind::Output
+        );
+        assert_eq!(
+            MismatchKind::of(&returned("1", &["x"]), &returned("2", &["x"])),
+            MismatchKind::ReturnValue
+        );
+    }
+
+    #[test]
+    fn trap_kinds_round_trip_through_exit_codes() {
+        for (code, kind) in [
+            (1, TrapKind::DivideByZero),
+            (2, TrapKind::IntegerOverflow),
+            (3, TrapKind::OutOfBounds),
+            (4, TrapKind::NullPointer),
+            (5, TrapKind::TypeMismatch),
+        ] {
+            assert_eq!(TrapKind::from_exit_code(code), Some(kind));
+        }
+        assert_eq!(TrapKind::from_exit_code(0), None);
+        assert_eq!(TrapKind::from_exit_code(-1), None);
+        assert_eq!(
+            TrapKind::from_runtime_error(&RuntimeError::IntegerOverflow { node: NodeId(3) }),
+            Some(TrapKind::IntegerOverflow)
+        );
+        assert_eq!(
+            TrapKind::from_runtime_error(&RuntimeError::StepLimitExceeded {
+                node: NodeId(3),
+                limit: 10
+            }),
+            None
+        );
+    }
+}
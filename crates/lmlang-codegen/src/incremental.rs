@@ -18,12 +18,12 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use lmlang_check::intervals;
 use lmlang_core::graph::ProgramGraph;
 use lmlang_core::id::FunctionId;
-use lmlang_core::ops::ComputeOp;
 use petgraph::graph::NodeIndex;
 
-use crate::CompileOptions;
+use crate::{CompileOptions, ContractMode};
 
 /// Tracks compilation state for incremental builds.
 ///
@@ -39,6 +39,10 @@ pub struct IncrementalState {
     /// Compilation settings hash (opt level, target triple, debug flag).
     /// Used to detect settings changes that invalidate the entire cache.
     settings_hash: [u8; 32],
+    /// Contract mode of the last compilation, which decides whether
+    /// contract nodes count toward the per-function hashes.
+    #[serde(default)]
+    contracts: ContractMode,
     /// Directory containing cached per-function object files.
     cache_dir: PathBuf,
 }
@@ -68,6 +72,7 @@ impl IncrementalState {
         IncrementalState {
             last_compiled_hashes: HashMap::new(),
             settings_hash: [0u8; 32],
+            contracts: ContractMode::Off,
             cache_dir,
         }
     }
@@ -80,7 +85,9 @@ impl IncrementalState {
     /// Compute which functions need recompilation based on hash changes.
     ///
     /// Phase 1: Compare current hashes against last_compiled_hashes to find
-    ///   directly dirty functions (changed or new).
+    ///   directly dirty functions (changed or new). With hashes from
+    ///   [`compilation_hashes`], this includes `ForRange` bodies whose
+    ///   callers changed the loop bounds their guards were elided under.
     /// Phase 2: Build reverse call graph. BFS from dirty functions through
     ///   callers to find transitive dependents.
     /// Phase 3: Everything else is cached.
@@ -173,6 +180,12 @@ impl IncrementalState {
     /// Update the settings hash after a compilation with new settings.
     pub fn update_settings_hash(&mut self, options: &CompileOptions) {
         self.settings_hash = compute_settings_hash(options);
+        self.contracts = options.contracts;
+    }
+
+    /// Contract mode of the last compilation.
+    pub fn contracts(&self) -> ContractMode {
+        self.contracts
     }
 
     /// Returns a reference to last compiled hashes.
@@ -197,7 +210,7 @@ impl IncrementalState {
 
 /// Build a call graph from the program graph.
 ///
-/// Scans all functions' nodes for `Call { target }` ops and returns
+/// Scans all functions' nodes for `Call { target }` ops (and loop bodies) and returns
 /// a map of caller -> list of callees.
 pub fn build_call_graph(graph: &ProgramGraph) -> HashMap<FunctionId, Vec<FunctionId>> {
     let mut call_graph: HashMap<FunctionId, Vec<FunctionId>> = HashMap::new();
@@ -209,10 +222,9 @@ pub fn build_call_graph(graph: &ProgramGraph) -> HashMap<FunctionId, Vec<Functio
         for node_id in func_nodes {
             let node_idx: NodeIndex<u32> = node_id.into();
             if let Some(node) = graph.compute().node_weight(node_idx) {
-                if let lmlang_core::ops::ComputeNodeOp::Core(ComputeOp::Call { target }) = &node.op
-                {
-                    if !callees.contains(target) {
-                        callees.push(*target);
+                if let Some(target) = node.op.call_target() {
+                    if !callees.contains(&target) {
+                        callees.push(target);
                     }
                 }
             }
@@ -225,10 +237,52 @@ pub fn build_call_graph(graph: &ProgramGraph) -> HashMap<FunctionId, Vec<Functio
     call_graph
 }
 
+/// Per-function hashes for dirty detection under `contracts`.
+///
+/// Contract nodes are stripped from compiled code unless the mode checks
+/// them, so they only count toward the hash in a checked mode. A `ForRange`
+/// body's guards are elided using the bounds of the loops over it, so the
+/// index range it was analyzed under counts toward its hash too: changing a
+/// caller's bounds marks the body dirty.
+pub fn compilation_hashes(
+    graph: &ProgramGraph,
+    contracts: ContractMode,
+) -> HashMap<FunctionId, [u8; 32]> {
+    let hashes = match contracts {
+        ContractMode::Off => lmlang_storage::hash::hash_all_functions_for_compilation(graph),
+        ContractMode::PreOnly | ContractMode::All => {
+            lmlang_storage::hash::hash_all_functions(graph)
+        }
+    };
+    let ranges = intervals::analyze_ranges(graph, contracts.checks_preconditions());
+    hashes
+        .iter()
+        .map(|(&fid, h)| match ranges.loop_index_ranges.get(&fid) {
+            Some(range) => {
+                let mut hasher = blake3::Hasher::new();
+                hasher.update(h.as_bytes());
+                match range {
+                    Some(range) => {
+                        hasher.update(&[1]);
+                        hasher.update(&range.lo.to_le_bytes());
+                        hasher.update(&range.hi.to_le_bytes());
+                    }
+                    None => {
+                        hasher.update(&[0]);
+                    }
+                }
+                (fid, *hasher.finalize().as_bytes())
+            }
+            None => (fid, *h.as_bytes()),
+        })
+        .collect()
+}
+
 /// Compute a hash of the compilation settings.
 ///
 /// Used to detect when settings change (e.g., optimization level, target
-/// triple, debug flag), which invalidates the entire object file cache.
+/// triple, debug flag, contract mode), which invalidates the entire object
+/// file cache.
 pub fn compute_settings_hash(options: &CompileOptions) -> [u8; 32] {
     let mut hasher = blake3::Hasher::new();
     // Hash optimization level
@@ -247,6 +301,13 @@ pub fn compute_settings_hash(options: &CompileOptions) -> [u8; 32] {
     }
     // Hash debug flag
     hasher.update(&[options.debug_symbols as u8]);
+    // Hash contract mode
+    let contracts_byte = match options.contracts {
+        crate::ContractMode::Off => 0u8,
+        crate::ContractMode::PreOnly => 1u8,
+        crate::ContractMode::All => 2u8,
+    };
+    hasher.update(&[contracts_byte]);
     *hasher.finalize().as_bytes()
 }
 
@@ -563,6 +624,53 @@ mod tests {
 
         assert!(!state.is_settings_changed(&opts1));
         assert!(state.is_settings_changed(&opts2));
+
+        for contracts in [crate::ContractMode::PreOnly, crate::ContractMode::All] {
+            let checked = CompileOptions {
+                contracts,
+                ..opts1.clone()
+            };
+            assert!(state.is_settings_changed(&checked));
+        }
+    }
+
+    #[test]
+    fn test_contracts_count_toward_hashes_only_when_checked() {
+        let with_precondition = |message: &str| {
+            let (mut graph, _fn_a, fn_b, _fn_c) = build_call_chain_graph();
+            let cond = graph
+                .add_core_op(
+                    ComputeOp::Const {
+                        value: lmlang_core::types::ConstValue::Bool(true),
+                    },
+                    fn_b,
+                )
+                .unwrap();
+            let pre = graph
+                .add_core_op(
+                    ComputeOp::Precondition {
+                        message: message.into(),
+                    },
+                    fn_b,
+                )
+                .unwrap();
+            graph.add_data_edge(cond, pre, 0, 0, TypeId::BOOL).unwrap();
+            (graph, fn_b)
+        };
+        let (first, fn_b) = with_precondition("first");
+        let (second, _) = with_precondition("second");
+
+        // Editing a stripped contract leaves the compiled function clean
+        assert_eq!(
+            compilation_hashes(&first, ContractMode::Off)[&fn_b],
+            compilation_hashes(&second, ContractMode::Off)[&fn_b]
+        );
+        for mode in [ContractMode::PreOnly, ContractMode::All] {
+            assert_ne!(
+                compilation_hashes(&first, mode)[&fn_b],
+                compilation_hashes(&second, mode)[&fn_b]
+            );
+        }
     }
 
     #[test]
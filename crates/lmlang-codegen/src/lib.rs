@@ -9,9 +9,11 @@
 //! - [`types`] -- Mapping from lmlang types to LLVM IR types
 //! - [`runtime`] -- Runtime function declarations (error handling, I/O)
 //! - [`linker`] -- Object file to executable linking via system `cc`
+//! - [`differential`] -- Differential testing of compiled binaries against the interpreter
 
 pub mod codegen;
 pub mod compiler;
+pub mod differential;
 pub mod error;
 pub mod incremental;
 pub mod linker;
@@ -41,6 +43,41 @@ pub enum OptLevel {
     O3,
 }
 
+/// Which contract checks are compiled into the output binary.
+///
+/// Contracts are always stripped by default. In a checked mode the enabled
+/// contract nodes are lowered to runtime guards that print the contract
+/// message and node ID and exit through `lmlang_runtime_error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContractMode {
+    /// Strip all contract nodes (zero runtime overhead).
+    #[default]
+    Off,
+    /// Check preconditions only.
+    PreOnly,
+    /// Check preconditions, postconditions and invariants.
+    All,
+}
+
+impl ContractMode {
+    /// Whether the given contract check is compiled under this mode.
+    pub fn checks(self, op: &lmlang_core::ops::ComputeOp) -> bool {
+        use lmlang_core::ops::ComputeOp;
+        match self {
+            ContractMode::Off => false,
+            ContractMode::PreOnly => matches!(op, ComputeOp::Precondition { .. }),
+            ContractMode::All => op.is_contract(),
+        }
+    }
+
+    /// Whether preconditions are checked, so code after them may assume
+    /// they hold.
+    pub fn checks_preconditions(self) -> bool {
+        self != ContractMode::Off
+    }
+}
+
 /// Options controlling the compilation pipeline.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompileOptions {
@@ -60,6 +97,10 @@ pub struct CompileOptions {
     /// Name of the entry function to call from main().
     /// `None` means auto-detect (first public function).
     pub entry_function: Option<String>,
+
+    /// Contract checks to compile as runtime guards.
+    #[serde(default)]
+    pub contracts: ContractMode,
 }
 
 impl Default for CompileOptions {
@@ -70,6 +111,7 @@ impl Default for CompileOptions {
             target_triple: None,
             debug_symbols: false,
             entry_function: None,
+            contracts: ContractMode::Off,
         }
     }
 }
@@ -107,6 +149,7 @@ mod tests {
         assert!(opts.target_triple.is_none());
         assert!(!opts.debug_symbols);
         assert!(opts.entry_function.is_none());
+        assert_eq!(opts.contracts, ContractMode::Off);
     }
 
     #[test]
@@ -126,6 +169,7 @@ mod tests {
             target_triple: Some("aarch64-apple-darwin".to_string()),
             debug_symbols: true,
             entry_function: Some("my_main".to_string()),
+            contracts: ContractMode::PreOnly,
         };
         let json = serde_json::to_string(&opts).unwrap();
         let back: CompileOptions = serde_json::from_str(&json).unwrap();
@@ -134,6 +178,19 @@ mod tests {
         assert_eq!(back.target_triple, opts.target_triple);
         assert_eq!(back.debug_symbols, opts.debug_symbols);
         assert_eq!(back.entry_function, opts.entry_function);
+        assert_eq!(back.contracts, opts.contracts);
+    }
+
+    #[test]
+    fn contract_mode_serde_names() {
+        assert_eq!(
+            serde_json::to_string(&ContractMode::PreOnly).unwrap(),
+            "\"pre-only\""
+        );
+        let back: CompileOptions =
+            serde_json::from_str(r#"{"output_dir":"b","opt_level":"O0","target_triple":null,"debug_symbols":false,"entry_function":null}"#)
+                .unwrap();
+        assert_eq!(back.contracts, ContractMode::Off);
     }
 
     #[test]
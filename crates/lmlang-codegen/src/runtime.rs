@@ -2,8 +2,9 @@
 //!
 //! Declares external C functions (printf, exit, fprintf) and emits
 //! the `lmlang_runtime_error` function body in LLVM IR.
-//! Also provides guard helpers for division-by-zero, overflow, and
-//! bounds checking, plus Print op support via typed printf calls.
+//! Also provides guard helpers for division-by-zero, overflow, bounds
+//! checking and compiled contracts, plus Print op support via typed printf
+//! calls.
 
 use inkwell::builder::Builder;
 use inkwell::context::Context;
@@ -24,6 +25,9 @@ pub mod error_kind {
     pub const OUT_OF_BOUNDS: u64 = 3;
     pub const NULL_POINTER: u64 = 4;
     pub const TYPE_MISMATCH: u64 = 5;
+    pub const PRECONDITION_VIOLATED: u64 = 6;
+    pub const POSTCONDITION_VIOLATED: u64 = 7;
+    pub const INVARIANT_VIOLATED: u64 = 8;
 }
 
 /// Declare all runtime functions in the LLVM module.
@@ -103,6 +107,12 @@ pub fn declare_runtime_functions_extern<'ctx>(context: &'ctx Context, module: &M
     let fprintf_type = i32_type.fn_type(&[i8_ptr_type.into(), i8_ptr_type.into()], true);
     module.add_function("fprintf", fprintf_type, Some(Linkage::External));
 
+    // stderr handle, read by compiled contract guards
+    #[cfg(target_os = "macos")]
+    module.add_global(i8_ptr_type, Some(AddressSpace::default()), "__stderrp");
+    #[cfg(not(target_os = "macos"))]
+    module.add_global(i8_ptr_type, Some(AddressSpace::default()), "stderr");
+
     // lmlang_runtime_error(i32, i32) -> void (external declaration only, no body)
     let err_fn_type = void_type.fn_type(&[i32_type.into(), i32_type.into()], false);
     let err_fn = module.add_function("lmlang_runtime_error", err_fn_type, Some(Linkage::External));
@@ -128,6 +138,9 @@ pub fn declare_runtime_functions_extern<'ctx>(context: &'ctx Context, module: &M
 /// - 3 = OutOfBounds
 /// - 4 = NullPointer
 /// - 5 = TypeMismatch
+/// - 6 = PreconditionViolated
+/// - 7 = PostconditionViolated
+/// - 8 = InvariantViolated
 fn emit_runtime_error_fn<'ctx>(context: &'ctx Context, module: &Module<'ctx>) {
     let i32_type = context.i32_type();
     let void_type = context.void_type();
@@ -175,6 +188,24 @@ fn emit_runtime_error_fn<'ctx>(context: &'ctx Context, module: &Module<'ctx>) {
             "type_mismatch_msg",
         )
         .unwrap();
+    let precondition_msg = builder
+        .build_global_string_ptr(
+            "Runtime error: precondition violated at node %d\n",
+            "precondition_msg",
+        )
+        .unwrap();
+    let postcondition_msg = builder
+        .build_global_string_ptr(
+            "Runtime error: postcondition violated at node %d\n",
+            "postcondition_msg",
+        )
+        .unwrap();
+    let invariant_msg = builder
+        .build_global_string_ptr(
+            "Runtime error: invariant violated at node %d\n",
+            "invariant_msg",
+        )
+        .unwrap();
     let unknown_msg = builder
         .build_global_string_ptr(
             "Runtime error: unknown error (kind %d) at node %d\n",
@@ -188,6 +219,9 @@ fn emit_runtime_error_fn<'ctx>(context: &'ctx Context, module: &Module<'ctx>) {
     let oob_bb = context.append_basic_block(function, "oob");
     let null_ptr_bb = context.append_basic_block(function, "null_ptr");
     let type_mismatch_bb = context.append_basic_block(function, "type_mismatch");
+    let precondition_bb = context.append_basic_block(function, "precondition");
+    let postcondition_bb = context.append_basic_block(function, "postcondition");
+    let invariant_bb = context.append_basic_block(function, "invariant");
     let default_bb = context.append_basic_block(function, "default");
 
     // Switch on error_kind
@@ -213,6 +247,18 @@ fn emit_runtime_error_fn<'ctx>(context: &'ctx Context, module: &Module<'ctx>) {
                     i32_type.const_int(error_kind::TYPE_MISMATCH, false),
                     type_mismatch_bb,
                 ),
+                (
+                    i32_type.const_int(error_kind::PRECONDITION_VIOLATED, false),
+                    precondition_bb,
+                ),
+                (
+                    i32_type.const_int(error_kind::POSTCONDITION_VIOLATED, false),
+                    postcondition_bb,
+                ),
+                (
+                    i32_type.const_int(error_kind::INVARIANT_VIOLATED, false),
+                    invariant_bb,
+                ),
             ],
         )
         .unwrap();
@@ -254,6 +300,9 @@ fn emit_runtime_error_fn<'ctx>(context: &'ctx Context, module: &Module<'ctx>) {
     emit_error_block(oob_bb, oob_msg);
     emit_error_block(null_ptr_bb, null_ptr_msg);
     emit_error_block(type_mismatch_bb, type_mismatch_msg);
+    emit_error_block(precondition_bb, precondition_msg);
+    emit_error_block(postcondition_bb, postcondition_msg);
+    emit_error_block(invariant_bb, invariant_msg);
 
     // Default block: unknown error kind, print both kind and node_id
     builder.position_at_end(default_bb);
@@ -403,6 +452,77 @@ pub fn emit_bounds_guard<'ctx>(
     Ok(())
 }
 
+/// Emit a runtime check for a compiled contract.
+///
+/// If `condition` is false, prints `Contract violated: <message>` to stderr
+/// and calls `lmlang_runtime_error(kind, node_id)`, which reports the node
+/// and exits with `kind` (one of the `*_VIOLATED` error kinds).
+#[allow(clippy::too_many_arguments)]
+pub fn emit_contract_guard<'ctx>(
+    builder: &Builder<'ctx>,
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    function: FunctionValue<'ctx>,
+    condition: IntValue<'ctx>,
+    kind: u64,
+    node_id: u32,
+    message: &str,
+) -> Result<(), CodegenError> {
+    let error_bb = context.append_basic_block(function, "contract_error");
+    let continue_bb = context.append_basic_block(function, "contract_ok");
+    builder
+        .build_conditional_branch(condition, continue_bb, error_bb)
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    builder.position_at_end(error_bb);
+    let fprintf_fn = module
+        .get_function("fprintf")
+        .ok_or_else(|| CodegenError::LlvmError("fprintf not found".into()))?;
+    #[cfg(target_os = "macos")]
+    let stderr_name = "__stderrp";
+    #[cfg(not(target_os = "macos"))]
+    let stderr_name = "stderr";
+    let stderr_global = module
+        .get_global(stderr_name)
+        .ok_or_else(|| CodegenError::LlvmError("stderr not found".into()))?;
+    let ptr_type = context.ptr_type(AddressSpace::default());
+    let stderr_val = builder
+        .build_load(ptr_type, stderr_global.as_pointer_value(), "stderr")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    // The message is passed through %s so it is never read as a format
+    let fmt = builder
+        .build_global_string_ptr("Contract violated: %s\n", "contract_fmt")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let msg = builder
+        .build_global_string_ptr(message, "contract_msg")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    builder
+        .build_call(
+            fprintf_fn,
+            &[
+                stderr_val.into(),
+                fmt.as_pointer_value().into(),
+                msg.as_pointer_value().into(),
+            ],
+            "",
+        )
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    let err_fn = module
+        .get_function("lmlang_runtime_error")
+        .ok_or_else(|| CodegenError::LlvmError("lmlang_runtime_error not found".into()))?;
+    let kind = context.i32_type().const_int(kind, false);
+    let nid = context.i32_type().const_int(node_id as u64, false);
+    builder
+        .build_call(err_fn, &[kind.into(), nid.into()], "")
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+    builder
+        .build_unreachable()
+        .map_err(|e| CodegenError::LlvmError(e.to_string()))?;
+
+    builder.position_at_end(continue_bb);
+    Ok(())
+}
+
 /// Emit a printf call to print a value based on its lmlang type.
 ///
 /// Selects the appropriate format string based on the type:
@@ -649,6 +769,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn contract_guard_creates_valid_ir() {
+        for extern_only in [false, true] {
+            let context = Context::create();
+            let module = context.create_module("test_contract_guard");
+            if extern_only {
+                declare_runtime_functions_extern(&context, &module);
+            } else {
+                declare_runtime_functions(&context, &module);
+            }
+
+            let bool_type = context.bool_type();
+            let fn_type = context.void_type().fn_type(&[bool_type.into()], false);
+            let function = module.add_function("test_fn", fn_type, None);
+            let entry_bb = context.append_basic_block(function, "entry");
+
+            let builder = context.create_builder();
+            builder.position_at_end(entry_bb);
+
+            let condition = function.get_nth_param(0).unwrap().into_int_value();
+            emit_contract_guard(
+                &builder,
+                &context,
+                &module,
+                function,
+                condition,
+                error_kind::PRECONDITION_VIOLATED,
+                3,
+                "x must be 100% positive",
+            )
+            .unwrap();
+            builder.build_return(None).unwrap();
+
+            assert!(
+                module.verify().is_ok(),
+                "Module verification failed: {:?}",
+                module.verify()
+            );
+        }
+    }
+
     #[test]
     fn print_i32_creates_valid_ir() {
         let context = Context::create();
@@ -0,0 +1,89 @@
+//! Capabilities describing the side effects a function may perform.
+//!
+//! A function's declared effect set lives on
+//! [`FunctionDef::effects`](crate::function::FunctionDef::effects). An empty
+//! set means the function is pure; `None` means no declaration was made and
+//! the function is unchecked. Each effectful op requires one capability (see
+//! [`ComputeOp::required_capability`](crate::ops::ComputeOp::required_capability)),
+//! and `lmlang-check` verifies declarations transitively across calls.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A permission to perform one class of side effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    /// Console input and output (`Print`, `ReadLine`).
+    Console,
+    /// Reading files (`FileRead`); also permits `FileOpen` and `FileClose`.
+    FsRead,
+    /// Writing files (`FileWrite`); also permits `FileOpen` and `FileClose`.
+    FsWrite,
+    /// Reading the wall clock (`Now`).
+    Clock,
+    /// Drawing random numbers (`Random`).
+    Random,
+}
+
+/// A set of capabilities, ordered for stable serialization and diagnostics.
+pub type EffectSet = BTreeSet<Capability>;
+
+impl Capability {
+    /// All capabilities, in declaration order.
+    pub const ALL: [Capability; 5] = [
+        Capability::Console,
+        Capability::FsRead,
+        Capability::FsWrite,
+        Capability::Clock,
+        Capability::Random,
+    ];
+
+    /// The kebab-case name used in JSON and diagnostics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::Console => "console",
+            Capability::FsRead => "fs-read",
+            Capability::FsWrite => "fs-write",
+            Capability::Clock => "clock",
+            Capability::Random => "random",
+        }
+    }
+
+    /// Parses a kebab-case capability name.
+    pub fn parse(name: &str) -> Option<Capability> {
+        Capability::ALL
+            .into_iter()
+            .find(|cap| cap.as_str() == name.trim())
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_uses_kebab_case() {
+        let json = serde_json::to_string(&Capability::FsWrite).unwrap();
+        assert_eq!(json, "\"fs-write\"");
+        let back: Capability = serde_json::from_str("\"fs-read\"").unwrap();
+        assert_eq!(back, Capability::FsRead);
+    }
+
+    #[test]
+    fn parse_matches_as_str() {
+        for cap in Capability::ALL {
+            assert_eq!(Capability::parse(cap.as_str()), Some(cap));
+        }
+        assert_eq!(Capability::parse(" clock "), Some(Capability::Clock));
+        assert_eq!(Capability::parse("network"), None);
+    }
+}
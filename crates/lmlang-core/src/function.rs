@@ -10,6 +10,7 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::capability::EffectSet;
 use crate::id::{FunctionId, ModuleId, NodeId};
 use crate::type_id::TypeId;
 use crate::types::Visibility;
@@ -74,6 +75,12 @@ pub struct FunctionDef {
     /// Supports nesting per user decision. Inner functions can capture from
     /// enclosing scope. Nesting depth is unbounded.
     pub parent_function: Option<FunctionId>,
+    /// Declared capabilities this function (and everything it calls) may use.
+    ///
+    /// `None` means undeclared: the function is not checked. `Some` of an
+    /// empty set declares the function pure.
+    #[serde(default)]
+    pub effects: Option<EffectSet>,
 }
 
 impl FunctionDef {
@@ -97,6 +104,7 @@ impl FunctionDef {
             captures: Vec::new(),
             is_closure: false,
             parent_function: None,
+            effects: None,
         }
     }
 
@@ -121,6 +129,7 @@ impl FunctionDef {
             captures,
             is_closure: true,
             parent_function: Some(parent),
+            effects: None,
         }
     }
 
@@ -139,6 +148,11 @@ impl FunctionDef {
         self.params.len()
     }
 
+    /// Returns `true` if the function declares an empty effect set.
+    pub fn is_pure(&self) -> bool {
+        self.effects.as_ref().is_some_and(|e| e.is_empty())
+    }
+
     /// Returns the number of captured variables.
     pub fn capture_count(&self) -> usize {
         self.captures.len()
@@ -35,7 +35,7 @@ use crate::id::{EdgeId, FunctionId, ModuleId, NodeId};
 use crate::module::ModuleTree;
 use crate::node::{
     ComputeNode, DocNode, EmbeddingPayload, FunctionSignature, FunctionSummary, ModuleNode,
-    SemanticMetadata, SemanticNode, SemanticSummaryPayload, SpecNode, TestNode,
+    SemanticMetadata, SemanticNode, SemanticSummaryPayload, SpecNode, TestCase, TestNode,
 };
 use crate::ops::{ComputeNodeOp, ComputeOp, StructuredOp};
 use crate::type_id::{TypeId, TypeRegistry};
@@ -686,6 +686,39 @@ impl ProgramGraph {
         nodes
     }
 
+    /// Returns the nodes of a function that exist only for contract checking:
+    /// the contract checks, the contract expressions (`Old`, `ForAll`,
+    /// `Exists`) and every node reachable from a contract expression through
+    /// data edges. The compiler skips all of them.
+    pub fn contract_only_nodes(&self, id: FunctionId) -> HashSet<NodeId> {
+        let mut result = HashSet::new();
+        let mut stack = Vec::new();
+        for node_id in self.function_nodes(id) {
+            let Some(node) = self.get_compute_node(node_id) else {
+                continue;
+            };
+            if node.op.is_contract() {
+                result.insert(node_id);
+            } else if node.op.is_contract_expression() {
+                stack.push(node_id);
+            }
+        }
+        while let Some(node_id) = stack.pop() {
+            if !result.insert(node_id) {
+                continue;
+            }
+            for edge in self
+                .compute
+                .edges_directed(node_id.into(), Direction::Outgoing)
+            {
+                if edge.weight().is_data() {
+                    stack.push(NodeId::from(edge.target()));
+                }
+            }
+        }
+        result
+    }
+
     /// Returns all function IDs in deterministic order.
     pub fn sorted_function_ids(&self) -> Vec<FunctionId> {
         let mut ids: Vec<FunctionId> = self.functions.keys().copied().collect();
@@ -765,10 +798,27 @@ impl ProgramGraph {
         test_id: String,
         title: String,
         target_function: Option<FunctionId>,
+    ) -> Result<u32, CoreError> {
+        self.add_test_suite(module, test_id, title, target_function, Vec::new())
+    }
+
+    /// Creates a semantic test node with executable cases under a module.
+    pub fn add_test_suite(
+        &mut self,
+        module: ModuleId,
+        test_id: String,
+        title: String,
+        target_function: Option<FunctionId>,
+        cases: Vec<TestCase>,
     ) -> Result<u32, CoreError> {
         if self.modules.get_module(module).is_none() {
             return Err(CoreError::ModuleNotFound { id: module });
         }
+        if let Some(id) = target_function {
+            if !self.functions.contains_key(&id) {
+                return Err(CoreError::FunctionNotFound { id });
+            }
+        }
 
         let mut metadata =
             SemanticMetadata::with_module("test", module, &test_id, &format!("test {}", title));
@@ -778,6 +828,7 @@ impl ProgramGraph {
             test_id,
             title,
             target_function,
+            cases,
             metadata,
         }));
         if let Some(&module_idx) = self.module_semantic_nodes.get(&module) {
@@ -787,6 +838,37 @@ impl ProgramGraph {
         Ok(idx.index() as u32)
     }
 
+    /// Removes a semantic test node and its edges, returning it.
+    pub fn remove_test_node(&mut self, semantic_idx: u32) -> Result<TestNode, CoreError> {
+        let idx = NodeIndex::<u32>::new(semantic_idx as usize);
+        match self.semantic.node_weight(idx) {
+            Some(SemanticNode::Test(_)) => {}
+            _ => {
+                return Err(CoreError::GraphInconsistency {
+                    reason: format!("semantic node {} is not a test", semantic_idx),
+                })
+            }
+        }
+        match self.semantic.remove_node(idx) {
+            Some(SemanticNode::Test(test)) => Ok(test),
+            _ => unreachable!("checked above"),
+        }
+    }
+
+    /// Returns every semantic test node with its index, in index order.
+    pub fn test_nodes(&self) -> Vec<(u32, &TestNode)> {
+        let mut tests: Vec<(u32, &TestNode)> = self
+            .semantic
+            .node_indices()
+            .filter_map(|idx| match &self.semantic[idx] {
+                SemanticNode::Test(test) => Some((idx.index() as u32, test)),
+                _ => None,
+            })
+            .collect();
+        tests.sort_by_key(|(idx, _)| *idx);
+        tests
+    }
+
     /// Creates a semantic documentation node under a module.
     pub fn add_doc_node(
         &mut self,
@@ -1117,7 +1199,7 @@ impl ProgramGraph {
         let mut called_functions = BTreeSet::new();
         for node_id in &node_ids {
             if let Some(node) = self.get_compute_node(*node_id) {
-                if let ComputeNodeOp::Core(ComputeOp::Call { target }) = &node.op {
+                if let Some(target) = node.op.call_target() {
                     called_functions.insert(target.0);
                 }
             }
@@ -1496,6 +1578,51 @@ mod tests {
         assert_eq!(sorted, expected);
     }
 
+    #[test]
+    fn contract_only_nodes_follow_contract_expressions() {
+        let mut graph = ProgramGraph::new("main");
+        let root = graph.modules.root_id();
+        let f = graph
+            .add_function(
+                "f".into(),
+                root,
+                vec![("x".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, f)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, f).unwrap();
+        let old = graph.add_core_op(ComputeOp::Old { index: 0 }, f).unwrap();
+        let cmp = graph
+            .add_core_op(
+                ComputeOp::Compare {
+                    op: crate::ops::CmpOp::Ge,
+                },
+                f,
+            )
+            .unwrap();
+        let post = graph
+            .add_core_op(
+                ComputeOp::Postcondition {
+                    message: "x unchanged".into(),
+                },
+                f,
+            )
+            .unwrap();
+        graph.add_data_edge(param, ret, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(param, cmp, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(old, cmp, 0, 1, TypeId::I32).unwrap();
+        graph.add_data_edge(cmp, post, 0, 0, TypeId::BOOL).unwrap();
+
+        let contract_only = graph.contract_only_nodes(f);
+        assert_eq!(contract_only, HashSet::from([old, cmp, post]));
+        assert!(!contract_only.contains(&param));
+        assert!(!contract_only.contains(&ret));
+    }
+
     #[test]
     fn sorted_function_ids_and_semantic_lookup_work() {
         let mut graph = ProgramGraph::new("main");
@@ -1854,6 +1981,61 @@ mod tests {
         assert!(!spec_node.metadata().summary.checksum.is_empty());
     }
 
+    #[test]
+    fn test_suites_store_cases_and_can_be_removed() {
+        use crate::node::TestExpectation;
+
+        let mut graph = ProgramGraph::new("main");
+        let root = graph.modules.root_id();
+        let f = graph
+            .add_function(
+                "double".into(),
+                root,
+                vec![("x".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let case = TestCase {
+            name: "two".into(),
+            inputs: vec![serde_json::json!({ "I32": 2 })],
+            expect: TestExpectation::Returns {
+                value: serde_json::json!({ "I32": 4 }),
+            },
+            expected_io: None,
+        };
+
+        assert!(matches!(
+            graph.add_test_suite(
+                root,
+                "T".into(),
+                "t".into(),
+                Some(FunctionId(99)),
+                Vec::new()
+            ),
+            Err(CoreError::FunctionNotFound { .. })
+        ));
+        let idx = graph
+            .add_test_suite(
+                root,
+                "TEST-DOUBLE".into(),
+                "doubles".into(),
+                Some(f),
+                vec![case.clone()],
+            )
+            .unwrap();
+        let tests = graph.test_nodes();
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].0, idx);
+        assert_eq!(tests[0].1.cases, vec![case]);
+
+        let root_idx = graph.module_semantic_indices()[&root].index() as u32;
+        assert!(graph.remove_test_node(root_idx).is_err());
+        let removed = graph.remove_test_node(idx).unwrap();
+        assert_eq!(removed.test_id, "TEST-DOUBLE");
+        assert!(graph.test_nodes().is_empty());
+    }
+
     #[test]
     fn propagation_flush_is_deterministic_and_idempotent() {
         let mut graph = ProgramGraph::new("main");
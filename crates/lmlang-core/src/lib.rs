@@ -1,3 +1,4 @@
+pub mod capability;
 pub mod edge;
 pub mod error;
 pub mod function;
@@ -10,6 +11,7 @@ pub mod type_id;
 pub mod types;
 
 // Re-export commonly used types
+pub use capability::{Capability, EffectSet};
 pub use edge::{FlowEdge, SemanticEdge};
 pub use error::CoreError;
 pub use function::{Capture, CaptureMode, FunctionDef};
@@ -22,7 +24,7 @@ pub use module::{ModuleDef, ModuleTree};
 pub use node::{
     ComputeNode, DocNode, EmbeddingPayload, FunctionSignature, FunctionSummary, ModuleNode,
     OwnershipMetadata, ProvenanceMetadata, SemanticMetadata, SemanticNode, SemanticSummaryPayload,
-    SpecNode, TestNode, TypeDefNode,
+    SpecNode, TestCase, TestExpectation, TestNode, TypeDefNode,
 };
 pub use ops::{
     ArithOp, CmpOp, ComputeNodeOp, ComputeOp, LogicOp, ShiftOp, StructuredOp, UnaryArithOp,
@@ -336,11 +336,44 @@ pub struct TestNode {
     /// Optional target function.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub target_function: Option<FunctionId>,
+    /// Concrete cases run against `target_function`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cases: Vec<TestCase>,
     /// Rich metadata (ownership/provenance/summary/embeddings).
     #[serde(default)]
     pub metadata: SemanticMetadata,
 }
 
+/// One executable case of a [`TestNode`].
+///
+/// Values use the interpreter's JSON value encoding, the same shape
+/// `/simulate` reports results in (e.g. `{"I32": 5}`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TestCase {
+    /// Case name, used in reports.
+    #[serde(default)]
+    pub name: String,
+    /// Arguments passed to the target function.
+    #[serde(default)]
+    pub inputs: Vec<serde_json::Value>,
+    /// How the call must end.
+    pub expect: TestExpectation,
+    /// Values the call must print, in order. `None` leaves output unchecked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_io: Option<Vec<serde_json::Value>>,
+}
+
+/// Expected outcome of a [`TestCase`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TestExpectation {
+    /// The call returns this value.
+    Returns { value: serde_json::Value },
+    /// The call stops with this trap, e.g. `divide_by_zero`,
+    /// `integer_overflow` or `precondition`.
+    Traps { trap: String },
+}
+
 /// Documentation semantic node payload.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DocNode {
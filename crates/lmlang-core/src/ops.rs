@@ -4,8 +4,8 @@
 //! - **Tier 1 ([`ComputeOp`])**: ~24 core operations covering arithmetic, comparison,
 //!   logic, shifts, control flow (both high-level and low-level), memory, functions,
 //!   I/O (console + file), and closures.
-//! - **Tier 2 ([`StructuredOp`])**: 10 operations for aggregate access, type casts,
-//!   and enum operations.
+//! - **Tier 2 ([`StructuredOp`])**: 13 operations for aggregate access, higher-order
+//!   array combinators, type casts, and enum operations.
 //!
 //! # Design: Type Inference from Edges
 //!
@@ -26,6 +26,7 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::capability::Capability;
 use crate::id::FunctionId;
 use crate::type_id::TypeId;
 use crate::types::ConstValue;
@@ -163,6 +164,23 @@ pub enum ComputeOp {
     /// Takes a condition, loop body; produces a value on exit.
     /// Lowers to: loop_header_bb + `phi` + body_bb + `br` back-edge to header.
     Loop,
+    /// Counted loop calling `body` once per index in `start..end` by `step`.
+    /// Port 0 = start, port 1 = end, port 2 = step (one integer type), optional
+    /// port 3 = initial accumulator. The body has signature
+    /// `(index, acc) -> acc`, or `(index)` without an accumulator; the output
+    /// is the final accumulator. Iteration stops once the index reaches or
+    /// passes `end` in the direction of `step`; a zero step runs no iterations.
+    /// Several loop-carried values can be bundled in a struct accumulator.
+    /// Lowers to: the `Loop` skeleton (header `phi`s for index and accumulator,
+    /// body `call @body`, back-edge, exit).
+    ForRange { body: FunctionId },
+    /// Loop calling `body` once per element of an array, in index order.
+    /// Port 0 = array, optional port 1 = initial accumulator. The body has
+    /// signature `(element, acc) -> acc`, or `(element)` without an
+    /// accumulator; the output is the final accumulator.
+    /// Lowers to: the `Loop` skeleton over indices `0..N` with a
+    /// `getelementptr` + `load` of each element.
+    ForEach { body: FunctionId },
     /// High-level match/switch on discriminant.
     /// Takes discriminant input, N match arms.
     /// Lowers to: `switch` instruction or chain of `br` instructions.
@@ -231,6 +249,17 @@ pub enum ComputeOp {
     /// Lowers to: `call @fclose(...)`.
     FileClose,
 
+    // -- Environment (clock/randomness) --
+    /// Read the current wall-clock time as I64 nanoseconds since the Unix epoch.
+    /// Lowers to: `call @clock_gettime(CLOCK_REALTIME, ...)`.
+    /// The interpreter reads a virtual clock instead (see `InterpreterConfig`).
+    Now,
+    /// Produce 64 random bits as an I64.
+    /// Lowers to: `call @getrandom(...)` on Linux and `call @getentropy(...)`
+    /// on other Unix targets; Windows targets are unsupported.
+    /// The interpreter draws from a seeded generator instead.
+    Random,
+
     // -- Closures --
     /// Create a closure by capturing environment values.
     /// Takes captured values as data flow inputs, produces a closure value
@@ -247,7 +276,8 @@ pub enum ComputeOp {
     /// Precondition check at function entry.
     /// Port 0 (input): Bool -- the condition that must be true.
     /// No output -- check node only. Evaluated by interpreter before function body.
-    /// Skipped by compiler (zero overhead in compiled binaries).
+    /// Skipped by compiler unless the contract mode checks preconditions
+    /// (zero overhead in compiled binaries by default).
     Precondition {
         /// Human-readable contract description for diagnostics.
         message: String,
@@ -256,7 +286,7 @@ pub enum ComputeOp {
     /// Port 0 (input): Bool -- the condition that must be true.
     /// Port 1 (input): The return value being checked.
     /// No output. Evaluated by interpreter after return value computed.
-    /// Skipped by compiler.
+    /// Skipped by compiler unless the contract mode checks all contracts.
     Postcondition {
         /// Human-readable contract description for diagnostics.
         message: String,
@@ -272,6 +302,25 @@ pub enum ComputeOp {
         /// Human-readable invariant description for diagnostics.
         message: String,
     },
+
+    // -- Contract expressions (valid only inside contract subgraphs) --
+    /// Snapshot of parameter `index` as it was at function entry, for
+    /// postconditions that relate the result to the old state. For a pointer
+    /// parameter it is the value the pointer referred to at entry.
+    /// No data inputs. May only feed contract checks (directly or through
+    /// pure ops); skipped by compiler along with everything it feeds
+    /// unless that reaches a checked contract.
+    Old { index: u32 },
+    /// Universal quantifier: true if `predicate` holds for every item.
+    /// Port 0 = array, or ports 0 and 1 = integer range `start..end`
+    /// (exclusive, step 1). The predicate has signature `(item) -> Bool`.
+    /// Output: Bool; evaluation stops at the first item that fails.
+    /// Contract-only, compiled only for checked contracts.
+    ForAll { predicate: FunctionId },
+    /// Existential quantifier: true if `predicate` holds for some item.
+    /// Ports and predicate as for `ForAll`; evaluation stops at the first
+    /// witness. Contract-only, compiled only for checked contracts.
+    Exists { predicate: FunctionId },
 }
 
 impl ComputeOp {
@@ -290,6 +339,20 @@ impl ComputeOp {
         )
     }
 
+    /// Returns the function this op invokes directly, if any.
+    ///
+    /// This is the `Call` target, the body of a `ForRange`/`ForEach` loop or
+    /// the predicate of a `ForAll`/`Exists` quantifier; call-graph builders
+    /// treat all of them as call edges.
+    pub fn call_target(&self) -> Option<FunctionId> {
+        match self {
+            ComputeOp::Call { target } => Some(*target),
+            ComputeOp::ForRange { body } | ComputeOp::ForEach { body } => Some(*body),
+            ComputeOp::ForAll { predicate } | ComputeOp::Exists { predicate } => Some(*predicate),
+            _ => None,
+        }
+    }
+
     /// Returns `true` if this op is an I/O operation (console or file).
     ///
     /// I/O ops are: `Print`, `ReadLine`, `FileOpen`, `FileRead`, `FileWrite`, `FileClose`.
@@ -305,7 +368,39 @@ impl ComputeOp {
         )
     }
 
-    /// Returns `true` if this op is a contract node (dev-only, skipped by compiler).
+    /// Returns `true` if this op reads the environment nondeterministically.
+    ///
+    /// Environment ops are: `Now`, `Random`.
+    pub fn is_environment(&self) -> bool {
+        matches!(self, ComputeOp::Now | ComputeOp::Random)
+    }
+
+    /// Returns the capability a function needs to execute this op, if any.
+    ///
+    /// `FileOpen` and `FileClose` report `FsRead` here, but either fs
+    /// capability permits them (see [`Self::accepts_any_fs_capability`]).
+    pub fn required_capability(&self) -> Option<Capability> {
+        match self {
+            ComputeOp::Print | ComputeOp::ReadLine => Some(Capability::Console),
+            ComputeOp::FileOpen | ComputeOp::FileRead | ComputeOp::FileClose => {
+                Some(Capability::FsRead)
+            }
+            ComputeOp::FileWrite => Some(Capability::FsWrite),
+            ComputeOp::Now => Some(Capability::Clock),
+            ComputeOp::Random => Some(Capability::Random),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if either `FsRead` or `FsWrite` permits this op.
+    ///
+    /// Handle ops (`FileOpen`, `FileClose`) are needed to read or to write.
+    pub fn accepts_any_fs_capability(&self) -> bool {
+        matches!(self, ComputeOp::FileOpen | ComputeOp::FileClose)
+    }
+
+    /// Returns `true` if this op is a contract node (dev-only, skipped by
+    /// compiler unless a contract mode checks it).
     pub fn is_contract(&self) -> bool {
         matches!(
             self,
@@ -315,6 +410,15 @@ impl ComputeOp {
         )
     }
 
+    /// Returns `true` if this op is a contract expression (`Old`, `ForAll`,
+    /// `Exists`): a value-producing op valid only inside contract subgraphs.
+    pub fn is_contract_expression(&self) -> bool {
+        matches!(
+            self,
+            ComputeOp::Old { .. } | ComputeOp::ForAll { .. } | ComputeOp::Exists { .. }
+        )
+    }
+
     /// Returns `true` if this op is a basic block terminator.
     ///
     /// Terminators end a basic block and transfer control elsewhere.
@@ -328,7 +432,7 @@ impl ComputeOp {
 }
 
 // ---------------------------------------------------------------------------
-// Tier 2: Structured/aggregate operations (10 ops)
+// Tier 2: Structured/aggregate operations (13 ops)
 // ---------------------------------------------------------------------------
 
 /// Tier 2: Structured and aggregate operations.
@@ -357,6 +461,25 @@ pub enum StructuredOp {
     /// Produce a new array with one element replaced (functional update).
     /// Lowers to: `insertvalue` (constant index) or `getelementptr` + `store` (dynamic).
     ArraySet,
+    /// Apply a function to every element, producing a new array.
+    /// Port 0 = array `[T; N]`, port 1 = closure or function value `fn(T) -> U`.
+    /// Output = `[U; N]` (the registered array type of that shape, if any;
+    /// otherwise the type is left to the outgoing edge).
+    /// Lowers to: a counted loop with an indirect call per element.
+    ArrayMap,
+    /// Combine the elements left to right into a single value.
+    /// Port 0 = array `[T; N]`, port 1 = initial accumulator `A`,
+    /// port 2 = closure or function value `fn(A, T) -> A`. Output = `A`.
+    /// Lowers to: a counted loop with an accumulator `phi` and an indirect call.
+    ArrayFold,
+    /// Keep the elements for which a predicate returns true.
+    /// Port 0 = array `[T; N]`, port 1 = closure or function value `fn(T) -> Bool`.
+    /// Output = a registered struct `{ count: I32, items: [T; N] }` (one
+    /// with those field names wins among structs of that shape): `items`
+    /// holds the `count` kept elements first, in order, and the input's
+    /// elements at the remaining positions (arrays have a fixed length).
+    /// Lowers to: a counted loop with an indirect call and a kept-count `phi`.
+    ArrayFilter,
 
     /// Type cast / conversion between types.
     /// Lowers to: `trunc`/`zext`/`sext`/`fptrunc`/`fpext`/`fptosi`/`sitofp`/etc.
@@ -424,10 +547,36 @@ impl ComputeNodeOp {
         }
     }
 
+    /// Delegates to [`ComputeOp::call_target`]. Always `None` for structured ops.
+    pub fn call_target(&self) -> Option<FunctionId> {
+        match self {
+            ComputeNodeOp::Core(op) => op.call_target(),
+            ComputeNodeOp::Structured(_) => None,
+        }
+    }
+
+    /// Returns the capability required to execute this op, if any.
+    pub fn required_capability(&self) -> Option<Capability> {
+        match self {
+            ComputeNodeOp::Core(op) => op.required_capability(),
+            ComputeNodeOp::Structured(_) => None,
+        }
+    }
+
+    /// Returns `true` if either fs capability permits this op.
+    pub fn accepts_any_fs_capability(&self) -> bool {
+        matches!(self, ComputeNodeOp::Core(op) if op.accepts_any_fs_capability())
+    }
+
     /// Returns `true` if this is a contract node (development-time only).
     pub fn is_contract(&self) -> bool {
         matches!(self, ComputeNodeOp::Core(op) if op.is_contract())
     }
+
+    /// Returns `true` if this is a contract expression (`Old`, `ForAll`, `Exists`).
+    pub fn is_contract_expression(&self) -> bool {
+        matches!(self, ComputeNodeOp::Core(op) if op.is_contract_expression())
+    }
 }
 
 #[cfg(test)]
@@ -539,6 +688,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn is_environment_covers_clock_and_random() {
+        assert!(ComputeOp::Now.is_environment());
+        assert!(ComputeOp::Random.is_environment());
+        assert!(!ComputeOp::Now.is_io());
+        assert!(!ComputeOp::ReadLine.is_environment());
+    }
+
+    #[test]
+    fn call_target_covers_calls_and_loop_bodies() {
+        let f = FunctionId(7);
+        assert_eq!(ComputeOp::Call { target: f }.call_target(), Some(f));
+        assert_eq!(ComputeOp::ForRange { body: f }.call_target(), Some(f));
+        assert_eq!(ComputeOp::ForEach { body: f }.call_target(), Some(f));
+        assert_eq!(ComputeOp::MakeClosure { function: f }.call_target(), None);
+        assert!(!ComputeOp::ForRange { body: f }.is_control_flow());
+    }
+
+    #[test]
+    fn required_capability_maps_effectful_ops() {
+        assert_eq!(
+            ComputeOp::Print.required_capability(),
+            Some(Capability::Console)
+        );
+        assert_eq!(
+            ComputeOp::FileWrite.required_capability(),
+            Some(Capability::FsWrite)
+        );
+        assert_eq!(
+            ComputeOp::Random.required_capability(),
+            Some(Capability::Random)
+        );
+        assert_eq!(
+            ComputeOp::BinaryArith { op: ArithOp::Add }.required_capability(),
+            None
+        );
+        assert!(ComputeOp::FileOpen.accepts_any_fs_capability());
+        assert!(!ComputeOp::FileRead.accepts_any_fs_capability());
+    }
+
     #[test]
     fn serde_roundtrip_const() {
         let op = ComputeOp::Const {
@@ -670,6 +859,24 @@ mod tests {
         .is_contract());
     }
 
+    #[test]
+    fn contract_expressions_are_not_checks() {
+        let f = FunctionId(3);
+        for op in [
+            ComputeOp::Old { index: 0 },
+            ComputeOp::ForAll { predicate: f },
+            ComputeOp::Exists { predicate: f },
+        ] {
+            assert!(op.is_contract_expression());
+            assert!(!op.is_contract());
+        }
+        assert_eq!(ComputeOp::ForAll { predicate: f }.call_target(), Some(f));
+        assert!(!ComputeOp::Precondition {
+            message: "test".into()
+        }
+        .is_contract_expression());
+    }
+
     #[test]
     fn compute_node_op_is_contract() {
         let precond = ComputeNodeOp::Core(ComputeOp::Precondition {
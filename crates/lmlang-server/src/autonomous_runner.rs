@@ -8,7 +8,9 @@ use lmlang_storage::ProgramId;
 use serde::Serialize;
 use tokio::task::JoinHandle;
 
-use crate::autonomy_executor::execute_plan;
+use lmlang_check::test_runner::TestSuiteReport;
+
+use crate::autonomy_executor::{execute_plan, test_report_result};
 use crate::autonomy_planner::{plan_for_prompt, PlannerOutcome, PlannerRepairContext};
 use crate::concurrency::AgentId;
 use crate::project_agent::ProjectAgentSession;
@@ -17,6 +19,7 @@ use crate::schema::autonomy_execution::{
     AutonomyExecutionAttemptSummary, AutonomyExecutionError, AutonomyExecutionErrorCode,
     AutonomyExecutionOutcome, AutonomyExecutionStatus, StopReason, StopReasonCode,
 };
+use crate::schema::contracts::RunTestsRequest;
 use crate::schema::verify::VerifyScope;
 use crate::state::AppState;
 
@@ -344,175 +347,176 @@ impl AutonomousRunner {
                         }
                     }
                 } else {
-                    match self.run_verify_gate(state, program_id).await {
-                        Ok(verify) if verify.valid => {
-                            let verify_result = AutonomyActionExecutionResult::succeeded(
-                                attempt_summary.action_results.len(),
-                                "verify_gate",
-                                "post-execution verify passed",
-                            )
-                            .with_detail(
-                                serde_json::to_value(&verify).unwrap_or(serde_json::Value::Null),
-                            );
-                            attempt_summary.action_results.push(verify_result);
-                            attempt_summary.action_count += 1;
-                            attempt_summary.succeeded_actions += 1;
-
-                            let decision =
-                                decide_transition(AttemptEvent::Success, attempt, max_attempts);
-                            let (status, code) = match decision {
-                                TransitionDecision::Continue => (
-                                    AutonomyExecutionStatus::Succeeded,
-                                    StopReasonCode::Completed,
-                                ),
-                                TransitionDecision::Terminal { status, code } => (status, code),
-                            };
-                            let stop_reason =
-                                StopReason::new(code, "autonomous execution completed");
-                            attempt_summary.stop_reason = Some(stop_reason.clone());
+                    self.resolve_post_execution_gates(
+                        state,
+                        program_id,
+                        attempt_summary,
+                        accepted.version,
+                        attempt,
+                        max_attempts,
+                    )
+                    .await
+                }
+            }
+        }
+    }
 
-                            AttemptResolution {
-                                attempt: attempt_summary,
-                                version: Some(accepted.version),
-                                note: None,
-                                transition: AttemptTransition::Terminal {
-                                    status,
-                                    stop_reason,
-                                    run_status_note: "Autonomous loop completed successfully."
-                                        .to_string(),
-                                },
-                            }
-                        }
-                        Ok(verify) => {
-                            let diagnostic_messages = verify
-                                .errors
-                                .iter()
-                                .take(3)
-                                .map(|error| format!("[{}] {}", error.code, error.message))
-                                .collect::<Vec<_>>();
-                            let diagnostics = AutonomyDiagnostics::new(
-                                AutonomyDiagnosticsClass::VerifyFailure,
-                                true,
-                                format!(
-                                    "verify gate reported {} diagnostic(s)",
-                                    verify.errors.len()
-                                ),
-                            )
-                            .with_messages(diagnostic_messages)
-                            .with_detail(serde_json::json!({
-                                "error_count": verify.errors.len(),
-                                "warning_count": verify.warnings.len(),
-                            }));
-                            let verify_error = AutonomyExecutionError::new(
-                                AutonomyExecutionErrorCode::ValidationFailed,
-                                "post-execution verify failed",
-                                true,
-                            )
-                            .with_details(
-                                serde_json::to_value(&verify.errors)
-                                    .unwrap_or(serde_json::Value::Null),
-                            )
-                            .with_diagnostics(diagnostics.clone());
-                            let verify_result = AutonomyActionExecutionResult::failed(
-                                attempt_summary.action_results.len(),
-                                "verify_gate",
-                                format!(
-                                    "post-execution verify failed with {} diagnostic(s)",
-                                    verify.errors.len()
-                                ),
-                                verify_error,
-                            )
-                            .with_detail(
-                                serde_json::to_value(&verify).unwrap_or(serde_json::Value::Null),
-                            )
-                            .with_diagnostics(diagnostics);
-                            attempt_summary.action_results.push(verify_result);
-                            attempt_summary.action_count += 1;
+    /// Runs the verify gate and then the test gate after a successful
+    /// execution. The attempt completes only when both pass.
+    async fn resolve_post_execution_gates(
+        &self,
+        state: &AppState,
+        program_id: i64,
+        mut attempt_summary: AutonomyExecutionAttemptSummary,
+        version: String,
+        attempt: u32,
+        max_attempts: u32,
+    ) -> AttemptResolution {
+        let verify = match self.run_verify_gate(state, program_id).await {
+            Ok(verify) => verify,
+            Err(err) => return gate_error_resolution(attempt_summary, version, "verify", err),
+        };
+        let verify_detail = serde_json::to_value(&verify).unwrap_or(serde_json::Value::Null);
+        if !verify.valid {
+            let diagnostic_messages = verify
+                .errors
+                .iter()
+                .take(3)
+                .map(|error| format!("[{}] {}", error.code, error.message))
+                .collect::<Vec<_>>();
+            let diagnostics = AutonomyDiagnostics::new(
+                AutonomyDiagnosticsClass::VerifyFailure,
+                true,
+                format!("verify gate reported {} diagnostic(s)", verify.errors.len()),
+            )
+            .with_messages(diagnostic_messages)
+            .with_detail(serde_json::json!({
+                "error_count": verify.errors.len(),
+                "warning_count": verify.warnings.len(),
+            }));
+            let verify_error = AutonomyExecutionError::new(
+                AutonomyExecutionErrorCode::ValidationFailed,
+                "post-execution verify failed",
+                true,
+            )
+            .with_details(serde_json::to_value(&verify.errors).unwrap_or(serde_json::Value::Null))
+            .with_diagnostics(diagnostics.clone());
+            let verify_result = AutonomyActionExecutionResult::failed(
+                attempt_summary.action_results.len(),
+                "verify_gate",
+                format!(
+                    "post-execution verify failed with {} diagnostic(s)",
+                    verify.errors.len()
+                ),
+                verify_error,
+            )
+            .with_detail(verify_detail.clone())
+            .with_diagnostics(diagnostics);
+            attempt_summary.action_results.push(verify_result);
+            attempt_summary.action_count += 1;
+
+            let reason = StopReason::new(
+                StopReasonCode::VerifyFailed,
+                "post-execution verify gate failed",
+            )
+            .with_detail(verify_detail);
+            return gate_failure_resolution(
+                attempt_summary,
+                version,
+                reason,
+                GateFailure {
+                    event: AttemptEvent::VerifyFailed,
+                    gate: "verify",
+                    counts: serde_json::json!({
+                        "verify_error_count": verify.errors.len(),
+                        "verify_warning_count": verify.warnings.len(),
+                    }),
+                    run_status_note: "Autonomous run stopped after verify failures.",
+                },
+                attempt,
+                max_attempts,
+            );
+        }
 
-                            let reason = StopReason::new(
-                                StopReasonCode::VerifyFailed,
-                                "post-execution verify gate failed",
-                            )
-                            .with_detail(
-                                serde_json::to_value(&verify).unwrap_or(serde_json::Value::Null),
-                            );
-                            let decision = decide_transition(
-                                AttemptEvent::VerifyFailed,
-                                attempt,
-                                max_attempts,
-                            );
-
-                            match decision {
-                                TransitionDecision::Continue => {
-                                    attempt_summary.stop_reason = Some(reason);
-                                    let note = targeted_repair_retry_note(
-                                        &attempt_summary,
-                                        attempt,
-                                        max_attempts,
-                                    );
-                                    AttemptResolution {
-                                        attempt: attempt_summary,
-                                        version: Some(accepted.version),
-                                        note: Some(note),
-                                        transition: AttemptTransition::Continue,
-                                    }
-                                }
-                                TransitionDecision::Terminal { status, code } => {
-                                    let stop_reason = StopReason::new(
-                                        code,
-                                        format!(
-                                            "retry budget exhausted after verify gate failure (attempt {}/{})",
-                                            attempt, max_attempts
-                                        ),
-                                    )
-                                    .with_detail(serde_json::json!({
-                                        "attempt": attempt,
-                                        "max_attempts": max_attempts,
-                                        "verify_error_count": verify.errors.len(),
-                                        "verify_warning_count": verify.warnings.len(),
-                                    }));
-                                    attempt_summary.stop_reason = Some(stop_reason.clone());
-                                    AttemptResolution {
-                                        attempt: attempt_summary,
-                                        version: Some(accepted.version),
-                                        note: None,
-                                        transition: AttemptTransition::Terminal {
-                                            status,
-                                            stop_reason,
-                                            run_status_note:
-                                                "Autonomous run stopped after verify failures."
-                                                    .to_string(),
-                                        },
-                                    }
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            let stop_reason = stop_reason_from_api_error(
-                                StopReasonCode::RunnerInternalError,
-                                "post-execution verify gate failed to run",
-                                err,
-                            );
-                            attempt_summary.stop_reason = Some(stop_reason.clone());
-                            AttemptResolution {
-                                attempt: attempt_summary,
-                                version: Some(accepted.version),
-                                note: None,
-                                transition: AttemptTransition::Terminal {
-                                    status: AutonomyExecutionStatus::Failed,
-                                    stop_reason,
-                                    run_status_note:
-                                        "Autonomous run stopped due to verify gate error."
-                                            .to_string(),
-                                },
-                            }
-                        }
-                    }
-                }
+        let verify_result = AutonomyActionExecutionResult::succeeded(
+            attempt_summary.action_results.len(),
+            "verify_gate",
+            "post-execution verify passed",
+        )
+        .with_detail(verify_detail);
+        attempt_summary.action_results.push(verify_result);
+        attempt_summary.action_count += 1;
+        attempt_summary.succeeded_actions += 1;
+
+        let report = match self.run_test_gate(state, program_id).await {
+            Ok(report) => report,
+            Err(err) => return gate_error_resolution(attempt_summary, version, "test", err),
+        };
+        attempt_summary.action_count += 1;
+        match test_report_result(attempt_summary.action_results.len(), "test_gate", &report) {
+            Ok(test_result) => {
+                attempt_summary.action_results.push(test_result);
+                attempt_summary.succeeded_actions += 1;
             }
+            Err(test_result) => {
+                attempt_summary.action_results.push(test_result);
+                let reason = StopReason::new(
+                    StopReasonCode::TestsFailed,
+                    "post-execution test gate failed",
+                )
+                .with_detail(serde_json::to_value(&report).unwrap_or(serde_json::Value::Null));
+                return gate_failure_resolution(
+                    attempt_summary,
+                    version,
+                    reason,
+                    GateFailure {
+                        event: AttemptEvent::TestsFailed,
+                        gate: "test",
+                        counts: serde_json::json!({
+                            "test_passed_count": report.passed,
+                            "test_failed_count": report.failed,
+                        }),
+                        run_status_note: "Autonomous run stopped after test failures.",
+                    },
+                    attempt,
+                    max_attempts,
+                );
+            }
+        }
+
+        let decision = decide_transition(AttemptEvent::Success, attempt, max_attempts);
+        let (status, code) = match decision {
+            TransitionDecision::Continue => (
+                AutonomyExecutionStatus::Succeeded,
+                StopReasonCode::Completed,
+            ),
+            TransitionDecision::Terminal { status, code } => (status, code),
+        };
+        let stop_reason = StopReason::new(code, "autonomous execution completed");
+        attempt_summary.stop_reason = Some(stop_reason.clone());
+
+        AttemptResolution {
+            attempt: attempt_summary,
+            version: Some(version),
+            note: None,
+            transition: AttemptTransition::Terminal {
+                status,
+                stop_reason,
+                run_status_note: "Autonomous loop completed successfully.".to_string(),
+            },
         }
     }
 
+    async fn run_test_gate(
+        &self,
+        state: &AppState,
+        program_id: i64,
+    ) -> Result<TestSuiteReport, crate::error::ApiError> {
+        let mut service = state.service.lock().await;
+        service.load_program(ProgramId(program_id))?;
+        Ok(service.run_tests(&RunTestsRequest::default()))
+    }
+
     async fn run_verify_gate(
         &self,
         state: &AppState,
@@ -623,6 +627,7 @@ enum AttemptEvent {
     PlannerRejected { retryable: bool },
     ActionFailed { retryable: bool },
     VerifyFailed,
+    TestsFailed,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -678,7 +683,7 @@ fn decide_transition(event: AttemptEvent, attempt: u32, max_attempts: u32) -> Tr
                 }
             }
         }
-        AttemptEvent::VerifyFailed => {
+        AttemptEvent::VerifyFailed | AttemptEvent::TestsFailed => {
             if attempt < max_attempts {
                 TransitionDecision::Continue
             } else {
@@ -691,6 +696,92 @@ fn decide_transition(event: AttemptEvent, attempt: u32, max_attempts: u32) -> Tr
     }
 }
 
+/// How a failed post-execution gate is reported.
+struct GateFailure {
+    event: AttemptEvent,
+    gate: &'static str,
+    counts: serde_json::Value,
+    run_status_note: &'static str,
+}
+
+/// Retries after a failed gate, or stops once the retry budget is spent.
+fn gate_failure_resolution(
+    mut attempt_summary: AutonomyExecutionAttemptSummary,
+    version: String,
+    reason: StopReason,
+    failure: GateFailure,
+    attempt: u32,
+    max_attempts: u32,
+) -> AttemptResolution {
+    match decide_transition(failure.event, attempt, max_attempts) {
+        TransitionDecision::Continue => {
+            attempt_summary.stop_reason = Some(reason);
+            let note = targeted_repair_retry_note(&attempt_summary, attempt, max_attempts);
+            AttemptResolution {
+                attempt: attempt_summary,
+                version: Some(version),
+                note: Some(note),
+                transition: AttemptTransition::Continue,
+            }
+        }
+        TransitionDecision::Terminal { status, code } => {
+            let mut detail = serde_json::json!({
+                "attempt": attempt,
+                "max_attempts": max_attempts,
+            });
+            if let (Some(detail), serde_json::Value::Object(counts)) =
+                (detail.as_object_mut(), failure.counts)
+            {
+                detail.extend(counts);
+            }
+            let stop_reason = StopReason::new(
+                code,
+                format!(
+                    "retry budget exhausted after {} gate failure (attempt {}/{})",
+                    failure.gate, attempt, max_attempts
+                ),
+            )
+            .with_detail(detail);
+            attempt_summary.stop_reason = Some(stop_reason.clone());
+            AttemptResolution {
+                attempt: attempt_summary,
+                version: Some(version),
+                note: None,
+                transition: AttemptTransition::Terminal {
+                    status,
+                    stop_reason,
+                    run_status_note: failure.run_status_note.to_string(),
+                },
+            }
+        }
+    }
+}
+
+/// Stops the run when a post-execution gate could not run at all.
+fn gate_error_resolution(
+    mut attempt_summary: AutonomyExecutionAttemptSummary,
+    version: String,
+    gate: &str,
+    err: crate::error::ApiError,
+) -> AttemptResolution {
+    let stop_reason = stop_reason_from_api_error(
+        StopReasonCode::RunnerInternalError,
+        &format!("post-execution {} gate failed to run", gate),
+        err,
+    );
+    attempt_summary.stop_reason = Some(stop_reason.clone());
+    AttemptResolution {
+        attempt: attempt_summary,
+        version: Some(version),
+        note: None,
+        transition: AttemptTransition::Terminal {
+            status: AutonomyExecutionStatus::Failed,
+            stop_reason,
+            run_status_note: format!("Autonomous run stopped due to {} gate error.", gate),
+        },
+    }
+}
+
 fn stop_reason_from_api_error(
     code: StopReasonCode,
     context: &str,
@@ -950,6 +1041,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_gate_failure_retries_then_exhausts_budget_with_counts() {
+        let summary = AutonomyExecutionAttemptSummary {
+            attempt: 1,
+            max_attempts: 2,
+            planner_status: "accepted".to_string(),
+            action_count: 2,
+            succeeded_actions: 1,
+            action_results: Vec::new(),
+            stop_reason: None,
+        };
+        let failure = || GateFailure {
+            event: AttemptEvent::TestsFailed,
+            gate: "test",
+            counts: serde_json::json!({ "test_failed_count": 1 }),
+            run_status_note: "Autonomous run stopped after test failures.",
+        };
+        let reason = StopReason::new(
+            StopReasonCode::TestsFailed,
+            "post-execution test gate failed",
+        );
+
+        let retry = gate_failure_resolution(
+            summary.clone(),
+            "v1".to_string(),
+            reason.clone(),
+            failure(),
+            1,
+            2,
+        );
+        assert!(matches!(retry.transition, AttemptTransition::Continue));
+        assert_eq!(retry.attempt.stop_reason, Some(reason.clone()));
+
+        let terminal = gate_failure_resolution(summary, "v1".to_string(), reason, failure(), 2, 2);
+        let AttemptTransition::Terminal { stop_reason, .. } = terminal.transition else {
+            panic!("expected terminal transition");
+        };
+        assert_eq!(stop_reason.code, StopReasonCode::RetryBudgetExhausted);
+        assert!(stop_reason
+            .message
+            .contains("test gate failure (attempt 2/2)"));
+        let detail = stop_reason.detail.unwrap();
+        assert_eq!(detail["test_failed_count"], 1);
+        assert_eq!(detail["max_attempts"], 2);
+    }
+
     #[test]
     fn planner_repair_context_uses_latest_failed_attempt_diagnostics() {
         let compile_error = AutonomyExecutionError::new(
@@ -7,6 +7,7 @@
 
 use std::process::Command;
 
+use lmlang_check::test_runner::TestSuiteReport;
 use lmlang_core::id::{FunctionId, NodeId};
 use serde::Serialize;
 
@@ -18,12 +19,13 @@ use crate::schema::autonomy_execution::{
     StopReasonCode,
 };
 use crate::schema::autonomy_plan::{
-    AutonomyPlanAction, AutonomyPlanCompileRequest, AutonomyPlanEnvelope,
-    AutonomyPlanHistoryOperation, AutonomyPlanHistoryRequest, AutonomyPlanInspectRequest,
-    AutonomyPlanMutationRequest, AutonomyPlanRunRequest, AutonomyPlanSimulateRequest,
-    AutonomyPlanVerifyRequest,
+    AutonomyPlanAction, AutonomyPlanCompileRequest, AutonomyPlanDifferentialTestRequest,
+    AutonomyPlanEnvelope, AutonomyPlanHistoryOperation, AutonomyPlanHistoryRequest,
+    AutonomyPlanInspectRequest, AutonomyPlanMutationRequest, AutonomyPlanRunRequest,
+    AutonomyPlanSimulateRequest, AutonomyPlanTestRequest, AutonomyPlanVerifyRequest,
 };
-use crate::schema::compile::CompileRequest;
+use crate::schema::compile::{CompileRequest, DifferentialTestRequest};
+use crate::schema::contracts::RunTestsRequest;
 use crate::schema::mutations::Mutation;
 use crate::schema::queries::{DetailLevel, SearchRequest};
 use crate::schema::simulate::SimulateRequest;
@@ -129,6 +131,10 @@ fn execute_action(
         AutonomyPlanAction::Simulate { request, .. } => {
             execute_simulate(service, action_index, request)
         }
+        AutonomyPlanAction::Test { request, .. } => execute_test(service, action_index, request),
+        AutonomyPlanAction::DifferentialTest { request, .. } => {
+            execute_differential_test(service, action_index, request)
+        }
         AutonomyPlanAction::Inspect { request, .. } => {
             execute_inspect(service, action_index, request)
         }
@@ -254,6 +260,7 @@ fn execute_compile(
             debug_symbols: request.debug_symbols,
             entry_function: Some(entry_function.clone()),
             output_dir: request.output_dir.clone(),
+            contracts: lmlang_codegen::ContractMode::Off,
         })
         .map_err(|err| api_error_result(action_index, "compile", "compile action failed", err))?;
 
@@ -295,6 +302,7 @@ fn execute_run(
             debug_symbols: request.debug_symbols,
             entry_function: Some(entry_function.clone()),
             output_dir: request.output_dir.clone(),
+            contracts: lmlang_codegen::ContractMode::Off,
         })
         .map_err(|err| api_error_result(action_index, "run", "run action compile failed", err))?;
 
@@ -341,6 +349,7 @@ fn execute_run(
         })?;
 
     let exit_code = output.status.code().unwrap_or(-1);
+    let expected_exit_code = request.expected_exit_code.unwrap_or(0);
     let stdout = truncate_for_detail(String::from_utf8_lossy(&output.stdout).trim(), 4096);
     let stderr = truncate_for_detail(String::from_utf8_lossy(&output.stderr).trim(), 4096);
     let detail = serde_json::json!({
@@ -350,12 +359,13 @@ fn execute_run(
         "compilation": compile,
         "run": {
             "exit_code": exit_code,
+            "expected_exit_code": expected_exit_code,
             "stdout": stdout,
             "stderr": stderr,
         }
     });
 
-    if output.status.success() {
+    if output.status.code() == Some(expected_exit_code) {
         Ok(AutonomyActionExecutionResult::succeeded(
             action_index,
             "run",
@@ -366,8 +376,8 @@ fn execute_run(
         let error = AutonomyExecutionError::new(
             AutonomyExecutionErrorCode::ValidationFailed,
             format!(
-                "program execution failed (exit code {}): {}",
-                exit_code, stderr
+                "program execution failed (exit code {}, expected {}): {}",
+                exit_code, expected_exit_code, stderr
             ),
             true,
         );
@@ -404,6 +414,10 @@ fn execute_simulate(
             function_id,
             inputs: request.inputs.clone(),
             trace_enabled: request.trace_enabled,
+            random_seed: None,
+            clock: None,
+            limits: request.limits,
+            profile: false,
         })
         .map_err(|err| api_error_result(action_index, "simulate", "simulate action failed", err))?;
 
@@ -435,6 +449,150 @@ fn execute_simulate(
     )
 }
 
+fn execute_test(
+    service: &mut ProgramService,
+    action_index: usize,
+    request: &AutonomyPlanTestRequest,
+) -> Result<AutonomyActionExecutionResult, AutonomyActionExecutionResult> {
+    let report = service.run_tests(&RunTestsRequest {
+        test_ids: request.test_ids.clone(),
+        limits: request.limits,
+        ..RunTestsRequest::default()
+    });
+    test_report_result(action_index, "test", &report)
+}
+
+/// Converts a test suite report into a succeeded or failed action result.
+pub(crate) fn test_report_result(
+    action_index: usize,
+    kind: &str,
+    report: &TestSuiteReport,
+) -> Result<AutonomyActionExecutionResult, AutonomyActionExecutionResult> {
+    let detail = serde_json::to_value(report).unwrap_or(serde_json::Value::Null);
+    if report.all_passed() {
+        return Ok(AutonomyActionExecutionResult::succeeded(
+            action_index,
+            kind,
+            format!(
+                "{} test case(s) passed across {} test node(s)",
+                report.passed,
+                report.tests.len()
+            ),
+        )
+        .with_detail(detail));
+    }
+
+    let messages = report
+        .tests
+        .iter()
+        .flat_map(|test| {
+            test.cases.iter().filter(|case| !case.passed).map(|case| {
+                format!(
+                    "[{}] {}: {}",
+                    test.test_id,
+                    case.name,
+                    case.message.as_deref().unwrap_or("failed")
+                )
+            })
+        })
+        .take(3)
+        .collect::<Vec<_>>();
+    let summary = format!(
+        "{} of {} test case(s) failed",
+        report.failed,
+        report.passed + report.failed
+    );
+    let diagnostics =
+        AutonomyDiagnostics::new(AutonomyDiagnosticsClass::TestFailure, true, summary.clone())
+            .with_messages(messages)
+            .with_detail(serde_json::json!({
+                "passed": report.passed,
+                "failed": report.failed,
+            }));
+    let error = AutonomyExecutionError::new(
+        AutonomyExecutionErrorCode::ValidationFailed,
+        summary.clone(),
+        true,
+    )
+    .with_diagnostics(diagnostics.clone());
+    Err(
+        AutonomyActionExecutionResult::failed(action_index, kind, summary, error)
+            .with_detail(detail)
+            .with_diagnostics(diagnostics),
+    )
+}
+
+fn execute_differential_test(
+    service: &mut ProgramService,
+    action_index: usize,
+    request: &AutonomyPlanDifferentialTestRequest,
+) -> Result<AutonomyActionExecutionResult, AutonomyActionExecutionResult> {
+    let Some(function_id) = request.function_id else {
+        return Err(invalid_payload_result(
+            action_index,
+            "differential_test",
+            "differential_test request requires `function_id`",
+        ));
+    };
+
+    let report = service
+        .differential_test(&DifferentialTestRequest {
+            function_id,
+            iterations: request.iterations,
+            random_seed: request.random_seed,
+            opt_level: request.opt_level.clone(),
+            limits: request.limits,
+        })
+        .map_err(|err| {
+            api_error_result(
+                action_index,
+                "differential_test",
+                "differential_test action failed",
+                err,
+            )
+        })?;
+    let detail = serde_json::to_value(&report).unwrap_or(serde_json::Value::Null);
+
+    if report.agrees() {
+        return Ok(AutonomyActionExecutionResult::succeeded(
+            action_index,
+            "differential_test",
+            format!(
+                "interpreter and compiled code agreed on {} case(s) ({} skipped)",
+                report.compared, report.skipped
+            ),
+        )
+        .with_detail(detail));
+    }
+
+    let error = AutonomyExecutionError::new(
+        AutonomyExecutionErrorCode::ValidationFailed,
+        format!(
+            "interpreter and compiled code disagreed on {} of {} case(s)",
+            report.compared - report.agreed,
+            report.compared
+        ),
+        true,
+    )
+    .with_details(serde_json::to_value(&report.mismatches).unwrap_or(serde_json::Value::Null));
+    let diagnostics = diagnostics_from_error(
+        "differential_test",
+        "differential test found mismatches",
+        &error,
+    );
+    Err(AutonomyActionExecutionResult::failed(
+        action_index,
+        "differential_test",
+        format!(
+            "differential test found {} kind(s) of mismatch",
+            report.mismatches.len()
+        ),
+        error.with_diagnostics(diagnostics.clone()),
+    )
+    .with_detail(detail)
+    .with_diagnostics(diagnostics))
+}
+
 fn execute_inspect(
     service: &mut ProgramService,
     action_index: usize,
@@ -776,6 +934,13 @@ fn mutation_hint(mutation: &Mutation) -> String {
             None => format!("add_control_edge(#{}->#{})", from.0, to.0),
         },
         Mutation::RemoveEdge { edge_id } => format!("remove_edge(#{})", edge_id.0),
+        Mutation::SetFunctionEffects { function_id, .. } => {
+            format!("set_function_effects(fn#{})", function_id.0)
+        }
+        Mutation::AddTest { test_id, .. } => format!("add_test({})", test_id),
+        Mutation::RemoveTest { semantic_node_id } => {
+            format!("remove_test(#{})", semantic_node_id)
+        }
     }
 }
 
@@ -947,6 +1112,7 @@ fn diagnostics_class_for_action(kind: &str) -> AutonomyDiagnosticsClass {
     match kind {
         "verify" => AutonomyDiagnosticsClass::VerifyFailure,
         "compile" => AutonomyDiagnosticsClass::CompileFailure,
+        "test" => AutonomyDiagnosticsClass::TestFailure,
         _ => AutonomyDiagnosticsClass::ActionFailure,
     }
 }
@@ -1226,6 +1392,7 @@ mod tests {
                 entry_function: None,
                 output_dir: None,
                 args: Vec::new(),
+                expected_exit_code: None,
             },
             rationale: None,
         }]);
@@ -1292,6 +1459,7 @@ mod tests {
                 entry_function: Some("run_target".to_string()),
                 output_dir: None,
                 args: Vec::new(),
+                expected_exit_code: None,
             },
             rationale: None,
         }]);
@@ -1369,6 +1537,7 @@ mod tests {
                 function_id: None,
                 inputs: vec![serde_json::json!(1)],
                 trace_enabled: Some(false),
+                limits: Default::default(),
             },
             rationale: None,
         }]);
@@ -193,7 +193,7 @@ fn build_planner_prompt(
         "You are the lmlang autonomous planner.\n\
 Return only JSON with no markdown and no surrounding text.\n\
 Use planner contract version '{}'.\n\
-Allowed action types: mutate_batch, verify, compile, run, simulate, inspect, history.\n\
+Allowed action types: mutate_batch, verify, compile, run, simulate, test, differential_test, inspect, history.\n\
 Rules:\n\
 - Use an ordered actions array for executable plans.\n\
 - If no safe plan exists, return a structured failure object and empty actions.\n\
@@ -218,15 +218,39 @@ fn planner_programming_guide() -> &'static str {
     r#"Program-authoring guide (lmlang graph edits):
 - To write or change program logic, emit `mutate_batch` actions with concrete `request.mutations`.
 - Mutation `type` values must match exactly one of:
-  AddFunction, AddModule, InsertNode, ModifyNode, AddEdge, AddControlEdge, RemoveNode, RemoveEdge.
+  AddFunction, AddModule, InsertNode, ModifyNode, AddEdge, AddControlEdge, RemoveNode, RemoveEdge,
+  AddTest, RemoveTest.
+- `AddTest` records cases for a function: `{"type": "AddTest", "module": 0, "test_id": "T1",
+  "title": "...", "target_function": <fn_id>, "cases": [{"inputs": [{"I32": 2}],
+  "expect": {"kind": "returns", "value": {"I32": 4}}}]}`; expect `{"kind": "traps", "trap":
+  "divide_by_zero"}` for runtime errors and add `expected_io` to check printed values.
 - Built-in TypeId map: Bool=0, I8=1, I16=2, I32=3, I64=4, F32=5, F64=6, Unit=7, Never=8.
 - For new functions, default `module` is 0 and `visibility` is `Public` or `Private`.
+- For iteration, prefer structured loops over wiring `Loop` + `Phi` by hand:
+  `{"Core": {"ForRange": {"body": <fn_id>}}}` (ports 0-2 = start/end/step, port 3 = initial acc) or
+  `{"Core": {"ForEach": {"body": <fn_id>}}}` (port 0 = array, port 1 = initial acc). The body
+  function takes `(index_or_element, acc)` and returns the next acc; the loop outputs the final acc.
+- To transform arrays with a closure, use `{"Structured": "ArrayMap"}` (port 0 = array, port 1 =
+  fn(T) -> U), `{"Structured": "ArrayFilter"}` (port 1 = fn(T) -> Bool; outputs a struct
+  `{count: I32, items: [T; N]}`, read with `StructGet`) or
+  `{"Structured": "ArrayFold"}` (port 1 = initial acc, port 2 = fn(acc, T) -> acc).
+- Postconditions can compare against entry state with `{"Core": {"Old": {"index": <param>}}}` and
+  quantify with `{"Core": {"ForAll": {"predicate": <fn_id>}}}` or `{"Core": {"Exists": ...}}`
+  (port 0 = array, or ports 0-1 = integer range start..end; predicate is (item) -> Bool). These
+  values may only feed contract conditions.
 - Prefer this safe pipeline for build goals:
   1) mutate_batch
   2) verify (`scope`: `Full` or `Local`)
   3) optional compile (`entry_function`, `opt_level`: O0/O1/O2/O3)
   4) optional run (`entry_function`) to execute compiled program and capture stdout/stderr
   5) optional simulate/inspect/history for debugging
+  6) optional test to run the test node cases (add them with AddTest mutations); a successful run
+     must also pass every test case before it completes
+  7) optional differential_test (`function_id`, `iterations`) to check that the compiled code
+     matches the interpreter on generated inputs
+- Entry functions take no parameters or one integer array parameter (e.g. `[I64; 4]`) filled
+  from run `args` parsed as integers (missing slots are 0). An integer return value is the
+  process exit status; run succeeds when it equals `expected_exit_code` (default 0).
 - Inspect query shortcuts for graph/db context:
   `overview`, `semantic`, `search:<term>`, `function:<id>`, `node:<id>`, `neighborhood:<node_id>:<hops>`
 
@@ -321,6 +345,29 @@ fn summarize_actions(actions: &[AutonomyPlanAction]) -> Vec<PlannerActionSummary
                     request.inputs.len()
                 ),
             },
+            AutonomyPlanAction::Test { request, .. } => PlannerActionSummary {
+                kind: "test".to_string(),
+                summary: if request.test_ids.is_empty() {
+                    "all tests".to_string()
+                } else {
+                    format!("test_ids={}", request.test_ids.join(","))
+                },
+            },
+            AutonomyPlanAction::DifferentialTest { request, .. } => PlannerActionSummary {
+                kind: "differential_test".to_string(),
+                summary: format!(
+                    "function_id={}, iterations={}, opt_level={}",
+                    request
+                        .function_id
+                        .map(|id| id.0.to_string())
+                        .unwrap_or_else(|| "<none>".to_string()),
+                    request
+                        .iterations
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| "<default>".to_string()),
+                    request.opt_level
+                ),
+            },
             AutonomyPlanAction::Inspect { request, .. } => PlannerActionSummary {
                 kind: "inspect".to_string(),
                 summary: format!(
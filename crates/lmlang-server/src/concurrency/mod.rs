@@ -21,7 +21,6 @@ use std::collections::HashSet;
 
 use lmlang_core::graph::ProgramGraph;
 use lmlang_core::id::{FunctionId, NodeId};
-use lmlang_core::ops::{ComputeNodeOp, ComputeOp};
 use petgraph::graph::EdgeIndex;
 use uuid::Uuid;
 
@@ -69,9 +68,15 @@ pub fn affected_functions(mutations: &[Mutation], graph: &ProgramGraph) -> (Vec<
                     add_owner_for_node(graph, &mut affected, NodeId::from(to));
                 }
             }
-            Mutation::AddFunction { .. } | Mutation::AddModule { .. } => {
+            Mutation::AddFunction { .. }
+            | Mutation::AddModule { .. }
+            | Mutation::AddTest { .. }
+            | Mutation::RemoveTest { .. } => {
                 structure_change = true;
             }
+            Mutation::SetFunctionEffects { function_id, .. } => {
+                affected.insert(*function_id);
+            }
         }
     }
 
@@ -83,8 +88,8 @@ pub fn affected_functions(mutations: &[Mutation], graph: &ProgramGraph) -> (Vec<
 fn add_owner_for_node(graph: &ProgramGraph, affected: &mut HashSet<FunctionId>, node_id: NodeId) {
     if let Some(node) = graph.get_compute_node(node_id) {
         affected.insert(node.owner);
-        if let ComputeNodeOp::Core(ComputeOp::Call { target }) = &node.op {
-            affected.insert(*target);
+        if let Some(target) = node.op.call_target() {
+            affected.insert(target);
         }
     }
 }
@@ -84,7 +84,12 @@ fn error_function_id(err: &TypeError) -> Option<FunctionId> {
         | TypeError::MissingInput { function_id, .. }
         | TypeError::WrongInputCount { function_id, .. }
         | TypeError::NonNumericArithmetic { function_id, .. }
-        | TypeError::NonBooleanCondition { function_id, .. } => Some(*function_id),
+        | TypeError::NonBooleanCondition { function_id, .. }
+        | TypeError::InvalidLoopBody { function_id, .. }
+        | TypeError::NonArrayIteration { function_id, .. }
+        | TypeError::InvalidCallback { function_id, .. }
+        | TypeError::AmbiguousResultType { function_id, .. }
+        | TypeError::ContractValueEscapes { function_id, .. } => Some(*function_id),
         TypeError::UnknownType { .. } => None,
     }
 }
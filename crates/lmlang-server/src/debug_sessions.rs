@@ -0,0 +1,526 @@
+//! Stateful interactive debug sessions with TTL-based expiry.
+//!
+//! A [`DebugSession`] owns a snapshot of the program graph and a detached
+//! interpreter run, so it survives between requests without holding the
+//! [`ProgramService`](crate::service::ProgramService) lock. Sessions live in
+//! the [`DebugSessionManager`], which refreshes a session's expiry on every
+//! use and sweeps idle sessions in the background like the
+//! [`LockManager`](crate::concurrency::LockManager).
+//!
+//! A session can also be suspended to storage as a [`SuspendedSession`] and
+//! resumed later, possibly by another server process, as long as the
+//! program's functions are unchanged.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use lmlang_check::interpreter::debugger::{contract_nodes, evaluate_watch};
+use lmlang_check::interpreter::{
+    lineage, Debugger, DetachedInterpreter, ExecutionState, Interpreter, InterpreterConfig,
+    InterpreterSnapshot, SnapshotError, StepMode, StopReason, Value, WatchExpr, WriteTarget,
+};
+use lmlang_core::graph::ProgramGraph;
+use lmlang_core::id::{FunctionId, NodeId};
+use lmlang_storage::types::ProgramId;
+
+use crate::error::ApiError;
+use crate::schema::contracts::ContractViolationView;
+use crate::schema::debug::{
+    BreakpointSpec, DebugFrameView, DebugSessionResponse, DebugStatus, LineageEntryView,
+    LineageResponse, WatchResultView,
+};
+use crate::schema::diagnostics::DiagnosticError;
+
+/// Maximum nodes a single step command may evaluate before returning.
+pub const DEFAULT_STEP_LIMIT: u64 = 1_000_000;
+
+/// Steps between interpreter checkpoints; bounds the replay cost of stepping
+/// backward.
+pub const DEBUG_CHECKPOINT_INTERVAL: u64 = 64;
+
+/// A debug session suspended to storage: its interpreter snapshot and the
+/// debugger state layered on top.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedSession {
+    pub function_id: FunctionId,
+    pub snapshot: InterpreterSnapshot,
+    pub debugger: Debugger,
+    pub watches: Vec<WatchExpr>,
+    pub stop: StopReason,
+}
+
+/// One paused interpreter run and its debugger state.
+pub struct DebugSession {
+    program_id: ProgramId,
+    function_id: FunctionId,
+    /// Graph snapshot the run executes against; later edits to the program
+    /// do not affect the session.
+    graph: ProgramGraph,
+    /// Always `Some` outside [`Self::with_interpreter`].
+    run: Option<DetachedInterpreter>,
+    debugger: Debugger,
+    watches: Vec<WatchExpr>,
+    stop: StopReason,
+    expires_at: Instant,
+}
+
+impl DebugSession {
+    /// Starts `function_id` on `inputs`, stopped before its first node.
+    pub fn start(
+        program_id: ProgramId,
+        graph: ProgramGraph,
+        function_id: FunctionId,
+        inputs: Vec<Value>,
+        config: InterpreterConfig,
+    ) -> Self {
+        let mut interp = Interpreter::new(&graph, config);
+        interp.start(function_id, inputs);
+        let run = interp.detach();
+        DebugSession {
+            program_id,
+            function_id,
+            graph,
+            run: Some(run),
+            debugger: Debugger::new().with_step_limit(DEFAULT_STEP_LIMIT),
+            watches: Vec::new(),
+            stop: StopReason::Step,
+            expires_at: Instant::now(),
+        }
+    }
+
+    /// Continues a suspended session on `graph`, which must still hash the
+    /// same as the graph it was suspended from.
+    pub fn resume(
+        program_id: ProgramId,
+        graph: ProgramGraph,
+        suspended: SuspendedSession,
+    ) -> Result<Self, SnapshotError> {
+        let run = suspended.snapshot.resume(&graph)?.detach();
+        Ok(DebugSession {
+            program_id,
+            function_id: suspended.function_id,
+            graph,
+            run: Some(run),
+            debugger: suspended.debugger,
+            watches: suspended.watches,
+            stop: suspended.stop,
+            expires_at: Instant::now(),
+        })
+    }
+
+    /// Captures the session so it can be resumed later.
+    pub fn suspend(&mut self) -> SuspendedSession {
+        let snapshot = self.with_interpreter(|_, interp, _| interp.snapshot());
+        SuspendedSession {
+            function_id: self.function_id,
+            snapshot,
+            debugger: self.debugger.clone(),
+            watches: self.watches.clone(),
+            stop: self.stop.clone(),
+        }
+    }
+
+    /// Replaces the breakpoints, resolving contract breakpoints to nodes.
+    pub fn set_breakpoints(&mut self, specs: &[BreakpointSpec]) -> Result<(), ApiError> {
+        let mut nodes = Vec::new();
+        for spec in specs {
+            match spec {
+                BreakpointSpec::Node { node_id } => {
+                    if self.graph.get_compute_node(*node_id).is_none() {
+                        return Err(ApiError::NotFound(format!("node {} not found", node_id.0)));
+                    }
+                    nodes.push(*node_id);
+                }
+                BreakpointSpec::Contracts { function_id } => {
+                    if let Some(function_id) = function_id {
+                        if self.graph.get_function(*function_id).is_none() {
+                            return Err(ApiError::NotFound(format!(
+                                "function {} not found",
+                                function_id.0
+                            )));
+                        }
+                    }
+                    nodes.extend(contract_nodes(&self.graph, *function_id));
+                }
+            }
+        }
+        self.debugger.set_breakpoints(nodes);
+        Ok(())
+    }
+
+    /// Replaces the watch expressions reported with every response.
+    pub fn set_watches(&mut self, watches: Vec<WatchExpr>) {
+        self.watches = watches;
+    }
+
+    /// Advances execution by `mode`.
+    pub fn step(&mut self, mode: StepMode) {
+        self.stop = self.with_interpreter(|_, interp, debugger| debugger.step(interp, mode));
+    }
+
+    /// Rewinds to just before the last step that wrote `target`.
+    pub fn rewind(&mut self, target: &WriteTarget) -> Result<(), ApiError> {
+        let record =
+            self.with_interpreter(|_, interp, debugger| debugger.rewind_to_write(interp, target));
+        match record {
+            Some(_) => {
+                self.stop = StopReason::Step;
+                Ok(())
+            }
+            None => Err(ApiError::NotFound(format!(
+                "no recorded write to {}",
+                match target {
+                    WriteTarget::Node { node_id } => format!("node {}", node_id.0),
+                    WriteTarget::Memory { address } => format!("memory cell {}", address),
+                }
+            ))),
+        }
+    }
+
+    /// Trace entries that contributed to the most recent value of `node_id`.
+    pub fn lineage(&mut self, node_id: NodeId) -> Result<LineageResponse, ApiError> {
+        self.with_interpreter(|graph, interp, _| {
+            let trace = interp.trace().unwrap_or_default();
+            let entry = trace
+                .iter()
+                .rposition(|entry| entry.node_id == node_id)
+                .ok_or_else(|| {
+                    ApiError::NotFound(format!("node {} has not been evaluated", node_id.0))
+                })?;
+            let entries = lineage(graph, trace, entry)
+                .into_iter()
+                .map(|index| {
+                    let entry = &trace[index];
+                    LineageEntryView {
+                        index,
+                        depth: entry.depth,
+                        node_id: entry.node_id,
+                        op: entry.op_description.clone(),
+                        inputs: entry
+                            .inputs
+                            .iter()
+                            .filter_map(|(port, value)| {
+                                serde_json::to_value(value).ok().map(|json| (*port, json))
+                            })
+                            .collect(),
+                        output: entry
+                            .output
+                            .as_ref()
+                            .and_then(|value| serde_json::to_value(value).ok()),
+                    }
+                })
+                .collect();
+            Ok(LineageResponse { node_id, entries })
+        })
+    }
+
+    /// Evaluates `expressions` against the current state.
+    pub fn evaluate(&mut self, expressions: &[WatchExpr]) -> Vec<WatchResultView> {
+        self.with_interpreter(|graph, interp, _| {
+            expressions
+                .iter()
+                .map(
+                    |expression| match evaluate_watch(graph, interp, expression) {
+                        Ok(value) => WatchResultView {
+                            expression: expression.clone(),
+                            value: serde_json::to_value(value).ok(),
+                            error: None,
+                        },
+                        Err(err) => WatchResultView {
+                            expression: expression.clone(),
+                            value: None,
+                            error: Some(err.to_string()),
+                        },
+                    },
+                )
+                .collect()
+        })
+    }
+
+    /// Builds the API view of the session.
+    pub fn view(&mut self, session_id: Uuid) -> DebugSessionResponse {
+        let watches = self.evaluate(&self.watches.clone());
+        let expires_in_secs = self
+            .expires_at
+            .saturating_duration_since(Instant::now())
+            .as_secs();
+        let mut response = DebugSessionResponse {
+            session_id,
+            program_id: self.program_id.0,
+            function_id: self.function_id,
+            status: DebugStatus::Paused,
+            stop: self.stop.clone(),
+            next_node: None,
+            result: None,
+            error: None,
+            violation: None,
+            call_stack: Vec::new(),
+            memory: Vec::new(),
+            io_log: Vec::new(),
+            breakpoints: self.debugger.breakpoints(),
+            watches,
+            expires_in_secs,
+        };
+
+        self.with_interpreter(|graph, interp, _| {
+            match interp.state() {
+                ExecutionState::Completed { result } => {
+                    response.status = DebugStatus::Completed;
+                    response.result = serde_json::to_value(result).ok();
+                }
+                ExecutionState::Error { error, .. } => {
+                    response.status = DebugStatus::Error;
+                    response.error = Some(DiagnosticError {
+                        code: "RUNTIME_ERROR".to_string(),
+                        message: error.to_string(),
+                        details: None,
+                    });
+                }
+                ExecutionState::ContractViolation { violation } => {
+                    response.status = DebugStatus::ContractViolation;
+                    response.violation = Some(ContractViolationView::new(violation, &graph.types));
+                }
+                ExecutionState::Ready | ExecutionState::Running | ExecutionState::Paused { .. } => {
+                    response.next_node = interp.next_node();
+                }
+            }
+
+            response.call_stack = interp
+                .call_stack()
+                .iter()
+                .enumerate()
+                .map(|(depth, frame)| {
+                    let mut node_values: Vec<(NodeId, serde_json::Value)> = frame
+                        .node_values
+                        .iter()
+                        .filter_map(|(node_id, value)| {
+                            serde_json::to_value(value)
+                                .ok()
+                                .map(|json| (*node_id, json))
+                        })
+                        .collect();
+                    node_values.sort_by_key(|(node_id, _)| node_id.0);
+                    DebugFrameView {
+                        depth,
+                        function_id: frame.function_id,
+                        function_name: graph
+                            .get_function(frame.function_id)
+                            .map(|f| f.name.clone())
+                            .unwrap_or_default(),
+                        arguments: frame
+                            .arguments
+                            .iter()
+                            .filter_map(|v| serde_json::to_value(v).ok())
+                            .collect(),
+                        node_values,
+                    }
+                })
+                .collect();
+            response.memory = interp
+                .memory()
+                .iter()
+                .filter_map(|v| serde_json::to_value(v).ok())
+                .collect();
+            response.io_log = interp
+                .io_log()
+                .iter()
+                .filter_map(|v| serde_json::to_value(v).ok())
+                .collect();
+        });
+        response
+    }
+
+    /// Re-attaches the run to the session's graph for the duration of `f`.
+    fn with_interpreter<T>(
+        &mut self,
+        f: impl FnOnce(&ProgramGraph, &mut Interpreter<'_>, &mut Debugger) -> T,
+    ) -> T {
+        let run = self.run.take().expect("debug session run is detached");
+        let mut interp = run.attach(&self.graph);
+        let out = f(&self.graph, &mut interp, &mut self.debugger);
+        self.run = Some(interp.detach());
+        out
+    }
+}
+
+/// Registry of live debug sessions with TTL-based expiry.
+///
+/// Uses `DashMap` so commands on different sessions run concurrently.
+pub struct DebugSessionManager {
+    sessions: DashMap<Uuid, DebugSession>,
+    ttl: Duration,
+}
+
+impl DebugSessionManager {
+    /// Creates a manager whose sessions expire after `ttl` without use.
+    pub fn new(ttl: Duration) -> Self {
+        DebugSessionManager {
+            sessions: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// Creates a manager with the default 10-minute TTL.
+    pub fn with_default_ttl() -> Self {
+        Self::new(Duration::from_secs(10 * 60))
+    }
+
+    /// Registers `session` and returns its initial view.
+    pub fn insert(&self, mut session: DebugSession) -> DebugSessionResponse {
+        let session_id = Uuid::new_v4();
+        session.expires_at = Instant::now() + self.ttl;
+        let response = session.view(session_id);
+        self.sessions.insert(session_id, session);
+        response
+    }
+
+    /// Runs `f` on a live session of `program_id`, refreshing its expiry.
+    pub fn with_session<T>(
+        &self,
+        program_id: ProgramId,
+        session_id: Uuid,
+        f: impl FnOnce(&mut DebugSession) -> Result<T, ApiError>,
+    ) -> Result<T, ApiError> {
+        let now = Instant::now();
+        let not_found = || ApiError::NotFound(format!("debug session {} not found", session_id));
+        {
+            let mut session = self.sessions.get_mut(&session_id).ok_or_else(not_found)?;
+            if session.program_id != program_id {
+                return Err(not_found());
+            }
+            if now < session.expires_at {
+                session.expires_at = now + self.ttl;
+                return f(&mut session);
+            }
+        }
+        // Expired but not yet swept.
+        self.sessions.remove(&session_id);
+        Err(not_found())
+    }
+
+    /// Ends a session of `program_id`.
+    pub fn remove(&self, program_id: ProgramId, session_id: Uuid) -> Result<(), ApiError> {
+        self.sessions
+            .remove_if(&session_id, |_, session| session.program_id == program_id)
+            .map(|_| ())
+            .ok_or_else(|| ApiError::NotFound(format!("debug session {} not found", session_id)))
+    }
+
+    /// Number of live (possibly expired but unswept) sessions.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Whether there are no sessions.
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Removes expired sessions and returns their IDs.
+    pub fn sweep_expired_sessions(&self) -> Vec<Uuid> {
+        let now = Instant::now();
+        let expired: Vec<Uuid> = self
+            .sessions
+            .iter()
+            .filter(|entry| now >= entry.value().expires_at)
+            .map(|entry| *entry.key())
+            .collect();
+        for session_id in &expired {
+            self.sessions.remove(session_id);
+        }
+        expired
+    }
+
+    /// Spawns a background tokio task that periodically sweeps expired sessions.
+    pub fn start_expiry_sweep(self: &Arc<Self>, interval: Duration) {
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(interval);
+            loop {
+                tick.tick().await;
+                let expired = manager.sweep_expired_sessions();
+                if !expired.is_empty() {
+                    tracing::info!(
+                        "Swept {} expired debug session(s): {:?}",
+                        expired.len(),
+                        expired
+                    );
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lmlang_core::ops::ComputeOp;
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::Visibility;
+
+    /// Helper: a session over `id(x: i32) -> i32`.
+    fn identity_session(program_id: ProgramId) -> DebugSession {
+        let mut graph = ProgramGraph::new("test");
+        let root = graph.modules.root_id();
+        let func_id = graph
+            .add_function(
+                "id".into(),
+                root,
+                vec![("x".into(), TypeId::I32)],
+                TypeId::I32,
+                Visibility::Public,
+            )
+            .unwrap();
+        let param = graph
+            .add_core_op(ComputeOp::Parameter { index: 0 }, func_id)
+            .unwrap();
+        let ret = graph.add_core_op(ComputeOp::Return, func_id).unwrap();
+        graph.add_data_edge(param, ret, 0, 0, TypeId::I32).unwrap();
+        DebugSession::start(
+            program_id,
+            graph,
+            func_id,
+            vec![Value::I32(7)],
+            InterpreterConfig::default(),
+        )
+    }
+
+    #[test]
+    fn sessions_are_scoped_to_their_program() {
+        let manager = DebugSessionManager::with_default_ttl();
+        let session_id = manager.insert(identity_session(ProgramId(1))).session_id;
+
+        assert!(manager
+            .with_session(ProgramId(2), session_id, |_| Ok(()))
+            .is_err());
+        let response = manager
+            .with_session(ProgramId(1), session_id, |session| {
+                session.step(StepMode::Continue);
+                Ok(session.view(session_id))
+            })
+            .unwrap();
+        assert_eq!(response.status, DebugStatus::Completed);
+        assert_eq!(response.result, Some(serde_json::json!({"I32": 7})));
+
+        assert!(manager.remove(ProgramId(2), session_id).is_err());
+        manager.remove(ProgramId(1), session_id).unwrap();
+        assert!(manager.is_empty());
+    }
+
+    #[test]
+    fn expired_sessions_are_swept_and_rejected() {
+        let manager = DebugSessionManager::new(Duration::ZERO);
+        let swept = manager.insert(identity_session(ProgramId(1))).session_id;
+        let used = manager.insert(identity_session(ProgramId(1))).session_id;
+
+        assert!(manager
+            .with_session(ProgramId(1), used, |_| Ok(()))
+            .is_err());
+        assert_eq!(manager.sweep_expired_sessions(), vec![swept]);
+        assert!(manager.is_empty());
+    }
+}
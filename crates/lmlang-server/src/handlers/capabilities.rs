@@ -0,0 +1,49 @@
+//! Capability policy handlers.
+
+use axum::extract::{Path, State};
+use axum::Json;
+
+use crate::error::ApiError;
+use crate::schema::capabilities::{CapabilityPolicyResponse, UpdateCapabilityPolicyRequest};
+use crate::state::AppState;
+
+/// Returns the program's capability policy and per-function effects.
+///
+/// `GET /programs/{id}/capabilities`
+pub async fn get_capabilities(
+    State(state): State<AppState>,
+    Path(program_id): Path<i64>,
+) -> Result<Json<CapabilityPolicyResponse>, ApiError> {
+    let service = state.service.lock().await;
+
+    let active_id = service.program_id();
+    if active_id.0 != program_id {
+        return Err(ApiError::BadRequest(format!(
+            "program {} is not the active program (active: {})",
+            program_id, active_id.0
+        )));
+    }
+
+    Ok(Json(service.capability_policy()))
+}
+
+/// Sets (or resets) the capabilities forbidden for the program.
+///
+/// `POST /programs/{id}/capabilities`
+pub async fn update_capabilities(
+    State(state): State<AppState>,
+    Path(program_id): Path<i64>,
+    Json(req): Json<UpdateCapabilityPolicyRequest>,
+) -> Result<Json<CapabilityPolicyResponse>, ApiError> {
+    let mut service = state.service.lock().await;
+
+    let active_id = service.program_id();
+    if active_id.0 != program_id {
+        return Err(ApiError::BadRequest(format!(
+            "program {} is not the active program (active: {})",
+            program_id, active_id.0
+        )));
+    }
+
+    Ok(Json(service.set_capability_policy(req.forbidden)?))
+}
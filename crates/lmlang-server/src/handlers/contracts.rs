@@ -1,12 +1,19 @@
 //! Contract testing handlers.
 //!
-//! Implements the property-based testing endpoint for contract verification.
+//! Implements the property-based testing endpoint for contract verification
+//! the endpoint running the cases of the program's test nodes, and the
+//! mutation testing endpoint scoring both.
 
 use axum::extract::{Path, State};
 use axum::Json;
 
 use crate::error::ApiError;
-use crate::schema::contracts::{PropertyTestRequest, PropertyTestResponse};
+use lmlang_check::contracts::mutation::MutationReport;
+use lmlang_check::test_runner::TestSuiteReport;
+
+use crate::schema::contracts::{
+    MutationTestRequest, PropertyTestRequest, PropertyTestResponse, RunTestsRequest,
+};
 use crate::state::AppState;
 
 /// Runs property-based tests on a function's contracts.
@@ -17,7 +24,7 @@ pub async fn property_test(
     Path(program_id): Path<i64>,
     Json(req): Json<PropertyTestRequest>,
 ) -> Result<Json<PropertyTestResponse>, ApiError> {
-    let service = state.service.lock().await;
+    let mut service = state.service.lock().await;
 
     let active_id = service.program_id();
     if active_id.0 != program_id {
@@ -30,3 +37,48 @@ pub async fn property_test(
     let response = service.property_test(req)?;
     Ok(Json(response))
 }
+
+/// Runs the cases of the program's test nodes through the interpreter.
+///
+/// `POST /programs/{id}/test`
+pub async fn run_tests(
+    State(state): State<AppState>,
+    Path(program_id): Path<i64>,
+    Json(req): Json<RunTestsRequest>,
+) -> Result<Json<TestSuiteReport>, ApiError> {
+    let service = state.service.lock().await;
+
+    let active_id = service.program_id();
+    if active_id.0 != program_id {
+        return Err(ApiError::BadRequest(format!(
+            "program {} is not the active program (active: {})",
+            program_id, active_id.0
+        )));
+    }
+
+    let report = service.run_tests(&req);
+    Ok(Json(report))
+}
+
+/// Mutates a function and reports which mutants its contracts and test
+/// cases catch.
+///
+/// `POST /programs/{id}/mutation-test`
+pub async fn mutation_test(
+    State(state): State<AppState>,
+    Path(program_id): Path<i64>,
+    Json(req): Json<MutationTestRequest>,
+) -> Result<Json<MutationReport>, ApiError> {
+    let service = state.service.lock().await;
+
+    let active_id = service.program_id();
+    if active_id.0 != program_id {
+        return Err(ApiError::BadRequest(format!(
+            "program {} is not the active program (active: {})",
+            program_id, active_id.0
+        )));
+    }
+
+    let report = service.mutation_test(req)?;
+    Ok(Json(report))
+}
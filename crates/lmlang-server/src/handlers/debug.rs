@@ -0,0 +1,254 @@
+//! Interactive debug session handlers.
+//!
+//! Session creation snapshots the active program under the service lock;
+//! every later command works on the session alone, via the
+//! [`DebugSessionManager`](crate::debug_sessions::DebugSessionManager).
+//! Suspending and resuming go through the service again, which stores
+//! snapshots and checks them against the active program's graph.
+
+use axum::extract::{Path, State};
+use axum::Json;
+use uuid::Uuid;
+
+use lmlang_check::interpreter::WriteTarget;
+use lmlang_storage::ProgramId;
+
+use crate::error::ApiError;
+use crate::schema::debug::{
+    CreateDebugSessionRequest, DebugSessionResponse, DebugSnapshotView, DebugStepRequest,
+    EvaluateRequest, EvaluateResponse, LineageRequest, LineageResponse, ListDebugSnapshotsResponse,
+    SetBreakpointsRequest, SetWatchesRequest,
+};
+use crate::service::ProgramService;
+use crate::state::AppState;
+
+fn ensure_active(service: &ProgramService, program_id: i64) -> Result<(), ApiError> {
+    let active_id = service.program_id();
+    if active_id.0 != program_id {
+        return Err(ApiError::BadRequest(format!(
+            "program {} is not the active program (active: {})",
+            program_id, active_id.0
+        )));
+    }
+    Ok(())
+}
+
+/// Starts a debug session stopped before the function's first node.
+///
+/// `POST /programs/{id}/debug/sessions`
+pub async fn create_session(
+    State(state): State<AppState>,
+    Path(program_id): Path<i64>,
+    Json(req): Json<CreateDebugSessionRequest>,
+) -> Result<Json<DebugSessionResponse>, ApiError> {
+    let session = {
+        let service = state.service.lock().await;
+        ensure_active(&service, program_id)?;
+        service.debug_session(req)?
+    };
+
+    Ok(Json(state.debug_sessions.insert(session)))
+}
+
+/// Returns the state of a debug session.
+///
+/// `GET /programs/{id}/debug/sessions/{session_id}`
+pub async fn get_session(
+    State(state): State<AppState>,
+    Path((program_id, session_id)): Path<(i64, Uuid)>,
+) -> Result<Json<DebugSessionResponse>, ApiError> {
+    let response =
+        state
+            .debug_sessions
+            .with_session(ProgramId(program_id), session_id, |session| {
+                Ok(session.view(session_id))
+            })?;
+    Ok(Json(response))
+}
+
+/// Ends a debug session.
+///
+/// `DELETE /programs/{id}/debug/sessions/{session_id}`
+pub async fn delete_session(
+    State(state): State<AppState>,
+    Path((program_id, session_id)): Path<(i64, Uuid)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    state
+        .debug_sessions
+        .remove(ProgramId(program_id), session_id)?;
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+/// Steps into, over or out of the current node, back one node, or continues
+/// to the next breakpoint.
+///
+/// `POST /programs/{id}/debug/sessions/{session_id}/step`
+pub async fn step(
+    State(state): State<AppState>,
+    Path((program_id, session_id)): Path<(i64, Uuid)>,
+    Json(req): Json<DebugStepRequest>,
+) -> Result<Json<DebugSessionResponse>, ApiError> {
+    let response =
+        state
+            .debug_sessions
+            .with_session(ProgramId(program_id), session_id, |session| {
+                session.step(req.mode);
+                Ok(session.view(session_id))
+            })?;
+    Ok(Json(response))
+}
+
+/// Replaces the session's breakpoints.
+///
+/// `PUT /programs/{id}/debug/sessions/{session_id}/breakpoints`
+pub async fn set_breakpoints(
+    State(state): State<AppState>,
+    Path((program_id, session_id)): Path<(i64, Uuid)>,
+    Json(req): Json<SetBreakpointsRequest>,
+) -> Result<Json<DebugSessionResponse>, ApiError> {
+    let response =
+        state
+            .debug_sessions
+            .with_session(ProgramId(program_id), session_id, |session| {
+                session.set_breakpoints(&req.breakpoints)?;
+                Ok(session.view(session_id))
+            })?;
+    Ok(Json(response))
+}
+
+/// Replaces the session's watch expressions.
+///
+/// `PUT /programs/{id}/debug/sessions/{session_id}/watches`
+pub async fn set_watches(
+    State(state): State<AppState>,
+    Path((program_id, session_id)): Path<(i64, Uuid)>,
+    Json(req): Json<SetWatchesRequest>,
+) -> Result<Json<DebugSessionResponse>, ApiError> {
+    let response =
+        state
+            .debug_sessions
+            .with_session(ProgramId(program_id), session_id, |session| {
+                session.set_watches(req.watches);
+                Ok(session.view(session_id))
+            })?;
+    Ok(Json(response))
+}
+
+/// Evaluates expressions once against the paused state.
+///
+/// `POST /programs/{id}/debug/sessions/{session_id}/evaluate`
+pub async fn evaluate(
+    State(state): State<AppState>,
+    Path((program_id, session_id)): Path<(i64, Uuid)>,
+    Json(req): Json<EvaluateRequest>,
+) -> Result<Json<EvaluateResponse>, ApiError> {
+    let results =
+        state
+            .debug_sessions
+            .with_session(ProgramId(program_id), session_id, |session| {
+                Ok(session.evaluate(&req.expressions))
+            })?;
+    Ok(Json(EvaluateResponse { results }))
+}
+
+/// Rewinds to just before the last write of a node or memory cell.
+///
+/// `POST /programs/{id}/debug/sessions/{session_id}/rewind`
+pub async fn rewind(
+    State(state): State<AppState>,
+    Path((program_id, session_id)): Path<(i64, Uuid)>,
+    Json(target): Json<WriteTarget>,
+) -> Result<Json<DebugSessionResponse>, ApiError> {
+    let response =
+        state
+            .debug_sessions
+            .with_session(ProgramId(program_id), session_id, |session| {
+                session.rewind(&target)?;
+                Ok(session.view(session_id))
+            })?;
+    Ok(Json(response))
+}
+
+/// Lists the evaluations a node's most recent value was derived from.
+///
+/// `POST /programs/{id}/debug/sessions/{session_id}/lineage`
+pub async fn lineage(
+    State(state): State<AppState>,
+    Path((program_id, session_id)): Path<(i64, Uuid)>,
+    Json(req): Json<LineageRequest>,
+) -> Result<Json<LineageResponse>, ApiError> {
+    let response =
+        state
+            .debug_sessions
+            .with_session(ProgramId(program_id), session_id, |session| {
+                session.lineage(req.node_id)
+            })?;
+    Ok(Json(response))
+}
+
+/// Suspends a debug session to storage and ends it.
+///
+/// `POST /programs/{id}/debug/sessions/{session_id}/suspend`
+pub async fn suspend_session(
+    State(state): State<AppState>,
+    Path((program_id, session_id)): Path<(i64, Uuid)>,
+) -> Result<Json<DebugSnapshotView>, ApiError> {
+    let service = state.service.lock().await;
+    ensure_active(&service, program_id)?;
+
+    let suspended =
+        state
+            .debug_sessions
+            .with_session(ProgramId(program_id), session_id, |session| {
+                Ok(session.suspend())
+            })?;
+    let snapshot = service.save_debug_snapshot(&suspended)?;
+    state
+        .debug_sessions
+        .remove(ProgramId(program_id), session_id)?;
+    Ok(Json(snapshot))
+}
+
+/// Lists the program's suspended debug sessions.
+///
+/// `GET /programs/{id}/debug/snapshots`
+pub async fn list_snapshots(
+    State(state): State<AppState>,
+    Path(program_id): Path<i64>,
+) -> Result<Json<ListDebugSnapshotsResponse>, ApiError> {
+    let service = state.service.lock().await;
+    ensure_active(&service, program_id)?;
+    Ok(Json(service.list_debug_snapshots()?))
+}
+
+/// Resumes a suspended debug session as a new session.
+///
+/// Rejected with 409 Conflict if the program's functions changed since the
+/// session was suspended.
+///
+/// `POST /programs/{id}/debug/snapshots/{snapshot_id}/resume`
+pub async fn resume_snapshot(
+    State(state): State<AppState>,
+    Path((program_id, snapshot_id)): Path<(i64, Uuid)>,
+) -> Result<Json<DebugSessionResponse>, ApiError> {
+    let session = {
+        let service = state.service.lock().await;
+        ensure_active(&service, program_id)?;
+        service.resume_debug_snapshot(snapshot_id)?
+    };
+
+    Ok(Json(state.debug_sessions.insert(session)))
+}
+
+/// Deletes a suspended debug session.
+///
+/// `DELETE /programs/{id}/debug/snapshots/{snapshot_id}`
+pub async fn delete_snapshot(
+    State(state): State<AppState>,
+    Path((program_id, snapshot_id)): Path<(i64, Uuid)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let service = state.service.lock().await;
+    ensure_active(&service, program_id)?;
+    service.delete_debug_snapshot(snapshot_id)?;
+    Ok(Json(serde_json::json!({ "success": true })))
+}
@@ -6,9 +6,11 @@
 
 pub mod agent_control;
 pub mod agents;
+pub mod capabilities;
 pub mod compile;
 pub mod contracts;
 pub mod dashboard;
+pub mod debug;
 pub mod history;
 pub mod locks;
 pub mod mutations;
@@ -1,21 +1,22 @@
 //! Simulation handler for function interpretation.
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::Json;
 
 use crate::error::ApiError;
-use crate::schema::simulate::{SimulateRequest, SimulateResponse};
+use crate::schema::simulate::{SimulateQuery, SimulateRequest, SimulateResponse};
 use crate::state::AppState;
 
 /// Runs the interpreter on a function with provided inputs.
 ///
-/// `POST /programs/{id}/simulate`
+/// `POST /programs/{id}/simulate[?profile=true]`
 pub async fn simulate(
     State(state): State<AppState>,
     Path(program_id): Path<i64>,
-    Json(req): Json<SimulateRequest>,
+    Query(query): Query<SimulateQuery>,
+    Json(mut req): Json<SimulateRequest>,
 ) -> Result<Json<SimulateResponse>, ApiError> {
-    let service = state.service.lock().await;
+    let mut service = state.service.lock().await;
 
     let active_id = service.program_id();
     if active_id.0 != program_id {
@@ -25,6 +26,7 @@ pub async fn simulate(
         )));
     }
 
+    req.profile |= query.profile;
     let response = service.simulate(req)?;
     Ok(Json(response))
 }
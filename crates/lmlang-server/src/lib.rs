@@ -9,6 +9,7 @@ pub mod autonomous_runner;
 pub mod autonomy_executor;
 pub mod autonomy_planner;
 pub mod concurrency;
+pub mod debug_sessions;
 pub mod error;
 pub mod handlers;
 pub mod llm_provider;
@@ -16,6 +17,7 @@ pub mod project_agent;
 pub mod router;
 pub mod schema;
 pub mod service;
+pub mod snapshots;
 pub mod state;
 pub mod undo;
 
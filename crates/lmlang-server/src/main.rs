@@ -3,10 +3,32 @@
 //! Reads configuration from environment variables:
 //! - `LMLANG_DB_PATH`: SQLite database file path (default: "lmlang.db")
 //! - `LMLANG_PORT`: Server listen port (default: "3000")
+//! - `LMLANG_FORBIDDEN_CAPABILITIES`: Comma-separated capabilities forbidden
+//!   for programs without their own policy, e.g. "fs-write,random" (default: none)
+//! - `LMLANG_MAX_STEPS`, `LMLANG_TIMEOUT_MS`, `LMLANG_MAX_MEMORY_CELLS`:
+//!   interpreter budgets for simulate and property-test runs that do not set
+//!   their own (defaults: 10000000 steps, 10000 ms, 1000000 cells; "none"
+//!   removes a limit)
 
+use lmlang_core::capability::{Capability, EffectSet};
 use lmlang_server::router::build_router;
 use lmlang_server::state::AppState;
 
+/// Reads an optional limit from `var`, keeping `default` if unset or invalid.
+fn limit_from_env<T: std::str::FromStr>(var: &str, default: Option<T>) -> Option<T> {
+    match std::env::var(var) {
+        Ok(raw) if raw.trim().eq_ignore_ascii_case("none") => None,
+        Ok(raw) => match raw.trim().parse() {
+            Ok(limit) => Some(limit),
+            Err(_) => {
+                tracing::warn!("ignoring invalid {}='{}'", var, raw);
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt::init();
@@ -16,6 +38,33 @@ async fn main() {
 
     let state = AppState::new(&db_path).expect("Failed to initialize application state");
 
+    if let Ok(list) = std::env::var("LMLANG_FORBIDDEN_CAPABILITIES") {
+        let mut forbidden = EffectSet::new();
+        for name in list.split(',').filter(|n| !n.trim().is_empty()) {
+            match Capability::parse(name) {
+                Some(cap) => {
+                    forbidden.insert(cap);
+                }
+                None => tracing::warn!("ignoring unknown capability '{}'", name.trim()),
+            }
+        }
+        state
+            .service
+            .lock()
+            .await
+            .set_default_forbidden_capabilities(forbidden);
+    }
+
+    {
+        let mut service = state.service.lock().await;
+        let mut limits = service.default_execution_limits();
+        limits.max_steps = limit_from_env("LMLANG_MAX_STEPS", limits.max_steps);
+        limits.timeout_ms = limit_from_env("LMLANG_TIMEOUT_MS", limits.timeout_ms);
+        limits.max_memory_cells =
+            limit_from_env("LMLANG_MAX_MEMORY_CELLS", limits.max_memory_cells);
+        service.set_default_execution_limits(limits);
+    }
+
     let app = build_router(state);
 
     let addr = format!("0.0.0.0:{}", port);
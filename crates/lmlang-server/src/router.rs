@@ -3,7 +3,7 @@
 //! [`build_router`] wires all handler functions to their routes with
 //! CORS and tracing middleware layers.
 
-use axum::routing::{delete, get, post};
+use axum::routing::{delete, get, post, put};
 use axum::Router;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
@@ -164,11 +164,66 @@ pub fn build_router(state: AppState) -> Router {
         // Verify (TOOL-03)
         .route("/programs/{id}/verify", post(handlers::verify::verify))
         .route("/programs/{id}/verify/flush", post(handlers::verify::flush))
+        // Capability policy
+        .route(
+            "/programs/{id}/capabilities",
+            get(handlers::capabilities::get_capabilities)
+                .post(handlers::capabilities::update_capabilities),
+        )
         // Simulate (TOOL-04)
         .route(
             "/programs/{id}/simulate",
             post(handlers::simulate::simulate),
         )
+        // Interactive debug sessions
+        .route(
+            "/programs/{id}/debug/sessions",
+            post(handlers::debug::create_session),
+        )
+        .route(
+            "/programs/{id}/debug/sessions/{session_id}",
+            get(handlers::debug::get_session).delete(handlers::debug::delete_session),
+        )
+        .route(
+            "/programs/{id}/debug/sessions/{session_id}/step",
+            post(handlers::debug::step),
+        )
+        .route(
+            "/programs/{id}/debug/sessions/{session_id}/breakpoints",
+            put(handlers::debug::set_breakpoints),
+        )
+        .route(
+            "/programs/{id}/debug/sessions/{session_id}/watches",
+            put(handlers::debug::set_watches),
+        )
+        .route(
+            "/programs/{id}/debug/sessions/{session_id}/evaluate",
+            post(handlers::debug::evaluate),
+        )
+        .route(
+            "/programs/{id}/debug/sessions/{session_id}/rewind",
+            post(handlers::debug::rewind),
+        )
+        .route(
+            "/programs/{id}/debug/sessions/{session_id}/lineage",
+            post(handlers::debug::lineage),
+        )
+        .route(
+            "/programs/{id}/debug/sessions/{session_id}/suspend",
+            post(handlers::debug::suspend_session),
+        )
+        .route(
+            "/programs/{id}/debug/snapshots",
+            get(handlers::debug::list_snapshots),
+        )
+        .route(
+            "/programs/{id}/debug/snapshots/{snapshot_id}",
+            delete(handlers::debug::delete_snapshot),
+        )
+        .route(
+            "/programs/{id}/debug/snapshots/{snapshot_id}/resume",
+            post(handlers::debug::resume_snapshot),
+        )
         // Compile (EXEC-03/04)
         .route(
             "/programs/{id}/compile",
@@ -181,6 +236,13 @@ pub fn build_router(state: AppState) -> Router {
             "/programs/{id}/property-test",
             post(handlers::contracts::property_test),
         )
+        // Test node suites
+        .route("/programs/{id}/test", post(handlers::contracts::run_tests))
+        // Mutation testing of contracts and test cases
+        .route(
+            "/programs/{id}/mutation-test",
+            post(handlers::contracts::mutation_test),
+        )
         // History (STORE-03)
         .route(
             "/programs/{id}/history",
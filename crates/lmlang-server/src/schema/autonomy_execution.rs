@@ -80,6 +80,7 @@ impl AutonomyExecutionError {
 pub enum AutonomyDiagnosticsClass {
     VerifyFailure,
     CompileFailure,
+    TestFailure,
     ActionFailure,
 }
 
@@ -193,6 +194,7 @@ pub enum StopReasonCode {
     ActionFailedRetryable,
     ActionFailedNonRetryable,
     VerifyFailed,
+    TestsFailed,
     RetryBudgetExhausted,
     OperatorStopped,
     RunnerInternalError,
@@ -5,6 +5,7 @@
 
 use std::collections::HashMap;
 
+use lmlang_check::interpreter::ExecutionLimits;
 use lmlang_core::id::FunctionId;
 use serde::{Deserialize, Serialize};
 
@@ -123,6 +124,21 @@ pub enum AutonomyPlanAction {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         rationale: Option<String>,
     },
+    /// Compare one function's interpreted and compiled behavior.
+    #[serde(alias = "difftest", alias = "differentialTest")]
+    DifferentialTest {
+        request: AutonomyPlanDifferentialTestRequest,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rationale: Option<String>,
+    },
+    /// Run the cases of the program's test nodes.
+    #[serde(alias = "run_tests", alias = "runTests")]
+    Test {
+        #[serde(default)]
+        request: AutonomyPlanTestRequest,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rationale: Option<String>,
+    },
     /// Perform inspect/query operation against program context.
     #[serde(alias = "query", alias = "inspectProgram")]
     Inspect {
@@ -207,8 +223,14 @@ pub struct AutonomyPlanRunRequest {
     pub entry_function: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_dir: Option<String>,
+    /// Command-line arguments. They fill the entry function's optional
+    /// integer argument array (`[T; N]`) in order; missing slots are zero.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub args: Vec<String>,
+    /// Exit status the run must produce to succeed (default: 0). The entry
+    /// function's integer return value becomes the exit status.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_exit_code: Option<i32>,
 }
 
 /// Simulate action payload.
@@ -221,6 +243,40 @@ pub struct AutonomyPlanSimulateRequest {
     pub inputs: Vec<serde_json::Value>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trace_enabled: Option<bool>,
+    /// Interpreter budgets; unset limits use the server defaults.
+    #[serde(default, skip_serializing_if = "ExecutionLimits::is_unlimited")]
+    pub limits: ExecutionLimits,
+}
+
+/// Differential test action payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AutonomyPlanDifferentialTestRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_id: Option<FunctionId>,
+    /// Random inputs to compare (default: 100).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub random_seed: Option<u64>,
+    /// Optimization level of the compiled harness.
+    #[serde(default = "default_compile_opt_level")]
+    pub opt_level: String,
+    /// Interpreter budgets; unset limits use the server defaults.
+    #[serde(default, skip_serializing_if = "ExecutionLimits::is_unlimited")]
+    pub limits: ExecutionLimits,
+}
+
+/// Test action payload.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AutonomyPlanTestRequest {
+    /// Test IDs to run (default: all).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub test_ids: Vec<String>,
+    /// Interpreter budgets; unset limits use the server defaults.
+    #[serde(default, skip_serializing_if = "ExecutionLimits::is_unlimited")]
+    pub limits: ExecutionLimits,
 }
 
 /// Inspect/query action payload.
@@ -266,6 +322,7 @@ pub const AUTONOMY_PLAN_MAX_ACTIONS: usize = 32;
 const AUTONOMY_PLAN_MAX_MUTATIONS_PER_ACTION: usize = 128;
 const AUTONOMY_PLAN_MAX_SIM_INPUTS: usize = 32;
 const AUTONOMY_PLAN_MAX_INSPECT_RESULTS: usize = 200;
+const AUTONOMY_PLAN_MAX_DIFFERENTIAL_ITERATIONS: u32 = 1000;
 
 /// Structured semantic validation result for planner plans.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -516,6 +573,54 @@ fn validate_action(
                 );
             }
         }
+        AutonomyPlanAction::Test { request, .. } => {
+            if request.test_ids.iter().any(|id| id.trim().is_empty()) {
+                push_validation_error(
+                    errors,
+                    AutonomyPlanValidationCode::InvalidFieldValue,
+                    "test action test_ids must not contain empty IDs".to_string(),
+                    Some(action_index),
+                    Some("request.test_ids".to_string()),
+                );
+            }
+        }
+        AutonomyPlanAction::DifferentialTest { request, .. } => {
+            if request.function_id.is_none() {
+                push_validation_error(
+                    errors,
+                    AutonomyPlanValidationCode::MissingRequiredField,
+                    "differential_test action requires request.function_id".to_string(),
+                    Some(action_index),
+                    Some("request.function_id".to_string()),
+                );
+            }
+            if !matches!(request.opt_level.as_str(), "O0" | "O1" | "O2" | "O3") {
+                push_validation_error(
+                    errors,
+                    AutonomyPlanValidationCode::InvalidFieldValue,
+                    format!(
+                        "differential_test opt_level '{}' is invalid (expected O0/O1/O2/O3)",
+                        request.opt_level
+                    ),
+                    Some(action_index),
+                    Some("request.opt_level".to_string()),
+                );
+            }
+            if let Some(iterations) = request.iterations {
+                if iterations > AUTONOMY_PLAN_MAX_DIFFERENTIAL_ITERATIONS {
+                    push_validation_error(
+                        errors,
+                        AutonomyPlanValidationCode::InvalidActionPayload,
+                        format!(
+                            "differential_test iterations {} exceeds limit {}",
+                            iterations, AUTONOMY_PLAN_MAX_DIFFERENTIAL_ITERATIONS
+                        ),
+                        Some(action_index),
+                        Some("request.iterations".to_string()),
+                    );
+                }
+            }
+        }
         AutonomyPlanAction::Inspect { request, .. } => {
             if !option_is_present(&request.query) {
                 push_validation_error(
@@ -673,6 +778,7 @@ mod tests {
                         function_id: Some(lmlang_core::id::FunctionId(1)),
                         inputs: vec![serde_json::json!(7)],
                         trace_enabled: Some(false),
+                        limits: ExecutionLimits::default(),
                     },
                     rationale: None,
                 },
@@ -771,6 +877,7 @@ mod tests {
                         function_id: Some(lmlang_core::id::FunctionId(1)),
                         inputs: vec![serde_json::json!(1), serde_json::json!(2)],
                         trace_enabled: Some(true),
+                        limits: ExecutionLimits::default(),
                     },
                     rationale: Some("confirm runtime behavior".to_string()),
                 },
@@ -976,6 +1083,7 @@ mod tests {
                 entry_function: None,
                 output_dir: None,
                 args: Vec::new(),
+                expected_exit_code: None,
             },
             rationale: None,
         };
@@ -1030,6 +1138,74 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn differential_test_action_parses_aliases_and_validates() {
+        let action: AutonomyPlanAction = serde_json::from_value(serde_json::json!({
+            "type": "difftest",
+            "request": { "function_id": 0, "iterations": 20 }
+        }))
+        .expect("difftest alias should parse");
+        let AutonomyPlanAction::DifferentialTest { request, .. } = &action else {
+            panic!("expected differential_test action, got {:?}", action);
+        };
+        assert_eq!(request.opt_level, "O0");
+        assert!(request.limits.is_unlimited());
+
+        let mut plan = valid_minimal_plan();
+        plan.actions.push(action);
+        assert!(plan.validate().valid);
+
+        plan.actions.push(AutonomyPlanAction::DifferentialTest {
+            request: AutonomyPlanDifferentialTestRequest {
+                function_id: None,
+                iterations: Some(5000),
+                random_seed: None,
+                opt_level: "O2".to_string(),
+                limits: ExecutionLimits::default(),
+            },
+            rationale: None,
+        });
+        let result = plan.validate();
+        assert!(!result.valid);
+        assert!(result.errors.iter().any(|e| {
+            e.code == AutonomyPlanValidationCode::MissingRequiredField
+                && e.field.as_deref() == Some("request.function_id")
+        }));
+        assert!(result.errors.iter().any(|e| {
+            e.code == AutonomyPlanValidationCode::InvalidActionPayload
+                && e.field.as_deref() == Some("request.iterations")
+        }));
+    }
+
+    #[test]
+    fn test_action_parses_without_request_and_rejects_empty_ids() {
+        let action: AutonomyPlanAction = serde_json::from_value(serde_json::json!({
+            "type": "run_tests"
+        }))
+        .expect("run_tests alias should parse");
+        let AutonomyPlanAction::Test { request, .. } = &action else {
+            panic!("expected test action, got {:?}", action);
+        };
+        assert!(request.test_ids.is_empty());
+
+        let mut plan = valid_minimal_plan();
+        plan.actions.push(action);
+        assert!(plan.validate().valid);
+
+        plan.actions.push(AutonomyPlanAction::Test {
+            request: AutonomyPlanTestRequest {
+                test_ids: vec![" ".to_string()],
+                limits: ExecutionLimits::default(),
+            },
+            rationale: None,
+        });
+        let result = plan.validate();
+        assert!(result.errors.iter().any(|e| {
+            e.code == AutonomyPlanValidationCode::InvalidFieldValue
+                && e.field.as_deref() == Some("request.test_ids")
+        }));
+    }
+
     #[test]
     fn validation_requires_failure_only_when_no_actions_present() {
         let mut plan = valid_minimal_plan();
@@ -0,0 +1,45 @@
+//! API schema types for capability policies and function effects.
+//!
+//! Operators use `GET/POST /programs/{id}/capabilities` to inspect the
+//! effects each function uses and to forbid capabilities for a program.
+//! Forbidden capabilities are enforced by verify and compile, which gate
+//! agent-generated changes.
+
+use lmlang_core::capability::EffectSet;
+use lmlang_core::id::FunctionId;
+use serde::{Deserialize, Serialize};
+
+use super::diagnostics::DiagnosticError;
+
+/// Request body for `POST /programs/{id}/capabilities`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateCapabilityPolicyRequest {
+    /// Capabilities to forbid for this program. `null` (or omitted) resets
+    /// the program to the server default.
+    #[serde(default)]
+    pub forbidden: Option<EffectSet>,
+}
+
+/// Declared and inferred effects of one function.
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionEffectsView {
+    pub function_id: FunctionId,
+    pub name: String,
+    /// Declared capability set (`None` if undeclared).
+    pub declared: Option<EffectSet>,
+    /// Capabilities actually used, including through calls and closures.
+    pub inferred: EffectSet,
+}
+
+/// Response for the capability policy endpoints.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityPolicyResponse {
+    /// Capabilities forbidden for the program.
+    pub forbidden: EffectSet,
+    /// Server-wide default applied to programs without their own policy.
+    pub server_default: EffectSet,
+    /// Per-function effects, sorted by function ID.
+    pub functions: Vec<FunctionEffectsView>,
+    /// Undeclared or forbidden capability uses.
+    pub errors: Vec<DiagnosticError>,
+}
@@ -1,8 +1,11 @@
 //! API schema types for the compilation and dirty status endpoints.
 //!
 //! Defines the request and response types for `POST /programs/{id}/compile`
-//! and `GET /programs/{id}/dirty`.
+//! and `GET /programs/{id}/dirty`, and the request for differential tests of
+//! compiled code against the interpreter.
 
+use lmlang_check::interpreter::ExecutionLimits;
+use lmlang_core::id::FunctionId;
 use serde::{Deserialize, Serialize};
 
 /// Default optimization level for compilation requests.
@@ -29,6 +32,11 @@ pub struct CompileRequest {
 
     /// Output directory for the compiled binary (default: "./build/").
     pub output_dir: Option<String>,
+
+    /// Contracts compiled as runtime checks: "off", "pre-only" or "all"
+    /// (default: "off").
+    #[serde(default)]
+    pub contracts: lmlang_codegen::ContractMode,
 }
 
 /// Response body for `POST /programs/{id}/compile`.
@@ -47,6 +55,28 @@ pub struct CompileResponse {
     pub compilation_time_ms: u64,
 }
 
+/// Request for a differential test of one function, comparing the
+/// interpreter with a compiled harness binary.
+#[derive(Debug, Deserialize)]
+pub struct DifferentialTestRequest {
+    /// The function to test.
+    pub function_id: FunctionId,
+
+    /// Number of random inputs to compare (default: 100).
+    pub iterations: Option<u32>,
+
+    /// Seed for input generation (default: generated).
+    pub random_seed: Option<u64>,
+
+    /// Optimization level of the harness binary (default: "O0").
+    #[serde(default = "default_opt_level")]
+    pub opt_level: String,
+
+    /// Interpreter budgets per case; unset limits use the server defaults.
+    #[serde(default)]
+    pub limits: ExecutionLimits,
+}
+
 /// Response body for `GET /programs/{id}/dirty`.
 ///
 /// Shows which functions need recompilation, which are dirty dependents
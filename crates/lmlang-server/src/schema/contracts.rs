@@ -3,31 +3,124 @@
 //! Agents use [`PropertyTestRequest`] to trigger property-based testing
 //! of function contracts, and receive [`PropertyTestResponse`] with
 //! structured failure details including counterexample values.
+//! [`RunTestsRequest`] runs the cases of the program's test nodes, and
+//! [`MutationTestRequest`] measures how many seeded bugs they catch.
 
+use lmlang_check::contracts::{ContractKind, ContractViolation};
+use lmlang_check::interpreter::{CoverageSummary, Engine, ExecutionLimits};
 use lmlang_core::id::NodeId;
+use lmlang_core::type_id::TypeRegistry;
 use serde::{Deserialize, Serialize};
 
+/// How a property test explores a function's inputs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyTestMode {
+    /// Seeds plus randomly generated inputs.
+    #[default]
+    Random,
+    /// Every input in a bounded domain; proves the contracts or finds a
+    /// counterexample.
+    Exhaustive,
+}
+
 /// Request to run property-based tests on a function's contracts.
 #[derive(Debug, Deserialize)]
 pub struct PropertyTestRequest {
     /// Function to test.
     pub function_id: u32,
-    /// Agent-provided seed inputs (the "interesting" cases).
+    /// Random sampling (default) or bounded exhaustive enumeration.
+    #[serde(default)]
+    pub mode: PropertyTestMode,
+    /// Agent-provided seed inputs (the "interesting" cases). Random mode only.
+    #[serde(default)]
     pub seeds: Vec<Vec<serde_json::Value>>,
-    /// Number of randomized iterations (required, no default).
-    pub iterations: u32,
+    /// Number of randomized iterations (required in random mode, no default).
+    #[serde(default)]
+    pub iterations: Option<u32>,
     /// Random seed for reproducibility (optional -- system generates if absent).
     #[serde(default)]
     pub random_seed: Option<u64>,
     /// Whether to include execution traces for failures.
     #[serde(default)]
     pub trace_failures: bool,
+    /// Interpreter runs to spend shrinking each failure (default 500; 0 disables).
+    #[serde(default)]
+    pub max_shrink_runs: Option<u32>,
+    /// Maximum nesting depth for generated struct/enum/array inputs (default 8).
+    #[serde(default)]
+    pub max_depth: Option<u32>,
+    /// Maximum scalar leaves per generated input (default 256).
+    #[serde(default)]
+    pub max_leaves: Option<u32>,
+    /// Exhaustive mode: optional inclusive `[min, max]` box per integer parameter.
+    #[serde(default)]
+    pub bounds: Vec<Option<[i64; 2]>>,
+    /// Exhaustive mode: maximum domain size to enumerate (default 100000).
+    #[serde(default)]
+    pub max_cases: Option<u64>,
+    /// Budgets for each interpreter run; unset limits use the server
+    /// defaults.
+    #[serde(default)]
+    pub limits: ExecutionLimits,
+    /// Engine running each case: `bytecode` (default) or `reference`.
+    #[serde(default)]
+    pub engine: Engine,
+}
+
+/// Request to mutation-test a function against its contracts and tests.
+#[derive(Debug, Deserialize)]
+pub struct MutationTestRequest {
+    /// Function to mutate.
+    pub function_id: u32,
+    /// Seed inputs for the property tests run against each mutant.
+    #[serde(default)]
+    pub seeds: Vec<Vec<serde_json::Value>>,
+    /// Random property test iterations per mutant (default 100; 0 leaves
+    /// only seeds and test node cases).
+    #[serde(default)]
+    pub iterations: Option<u32>,
+    /// Random seed for reproducibility (optional -- system generates if absent).
+    #[serde(default)]
+    pub random_seed: Option<u64>,
+    /// Test IDs whose cases run against each mutant (default: all).
+    #[serde(default)]
+    pub test_ids: Vec<String>,
+    /// Maximum number of mutants to run (default 200).
+    #[serde(default)]
+    pub max_mutants: Option<u32>,
+    /// Budgets for each interpreter run; unset limits use the server
+    /// defaults.
+    #[serde(default)]
+    pub limits: ExecutionLimits,
+    /// Engine running each case: `bytecode` (default) or `reference`.
+    #[serde(default)]
+    pub engine: Engine,
+}
+
+/// Request to run the cases of the program's test nodes.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RunTestsRequest {
+    /// Test IDs to run (default: all).
+    #[serde(default)]
+    pub test_ids: Vec<String>,
+    /// Seed for `Random` ops inside each case (default 0).
+    #[serde(default)]
+    pub random_seed: u64,
+    /// Budgets for each case; unset limits use the server defaults.
+    #[serde(default)]
+    pub limits: ExecutionLimits,
+    /// Engine running each case: `bytecode` (default) or `reference`.
+    #[serde(default)]
+    pub engine: Engine,
 }
 
 /// Response from a property test run.
 #[derive(Debug, Serialize)]
 pub struct PropertyTestResponse {
-    /// Total tests run (seeds + random variations).
+    /// The mode that ran.
+    pub mode: PropertyTestMode,
+    /// Total tests run (seeds + random variations, or enumerated inputs).
     pub total_run: u32,
     /// Number of passing tests.
     pub passed: u32,
@@ -35,18 +128,61 @@ pub struct PropertyTestResponse {
     pub failed: u32,
     /// The random seed used (for reproducibility).
     pub random_seed: u64,
-    /// All failures with full details.
+    /// Inputs redrawn (random) or excluded (exhaustive) because they violated
+    /// the function's own preconditions.
+    pub rejected: u32,
+    /// Random mode: cases skipped, and not run, because every draw for them
+    /// violated the function's own preconditions. Not counted in `total_run`.
+    pub skipped: u32,
+    /// Runs stopped before their contracts were all checked: by an exceeded
+    /// limit (random), or by any runtime error (exhaustive). Counted in
+    /// `total_run`, not in `passed`.
+    pub unsettled: u32,
+    /// The unsettled runs reported (random mode: the first 10; exhaustive
+    /// mode: the first one).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unsettled_runs: Vec<UnsettledRunView>,
+    /// Exhaustive mode: whether every input in the domain satisfied the contracts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proved: Option<bool>,
+    /// Exhaustive mode: number of inputs in the enumerated domain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain_size: Option<u64>,
+    /// One failure per violated contract node, in order of first occurrence.
     pub failures: Vec<PropertyTestFailureView>,
+    /// Nodes and branch arms exercised by the cases run (not rejected inputs
+    /// or shrinking), over the function and its callees.
+    pub coverage: CoverageSummary,
 }
 
-/// A single property test failure for API responses.
+/// A run that did not finish, for API responses.
+#[derive(Debug, Serialize)]
+pub struct UnsettledRunView {
+    /// The inputs of the run.
+    pub inputs: Vec<serde_json::Value>,
+    /// Machine-readable error kind (e.g., "step_limit_exceeded").
+    pub kind: String,
+    /// Human-readable error description.
+    pub message: String,
+}
+
+/// A contract's property test failure for API responses.
 #[derive(Debug, Serialize)]
 pub struct PropertyTestFailureView {
-    /// The inputs that caused the failure.
+    /// The first inputs that caused the failure.
     pub inputs: Vec<serde_json::Value>,
-    /// The contract violation that occurred.
+    /// The minimal inputs found that still violate the same contract node.
+    pub shrunk_inputs: Vec<serde_json::Value>,
+    /// Number of shrink steps accepted.
+    pub shrink_steps: u32,
+    /// Number of test cases that violated this contract node.
+    pub occurrences: u32,
+    /// Seed of the failing case's `Random` ops; simulate the shrunk inputs
+    /// with it as `random_seed` to replay the failure.
+    pub case_seed: u64,
+    /// The contract violation produced by the shrunk inputs.
     pub violation: ContractViolationView,
-    /// Execution trace for this test case (None if trace_failures was false).
+    /// Execution trace for the shrunk inputs (None if trace_failures was false).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trace: Option<Vec<TraceEntryView>>,
 }
@@ -69,6 +205,43 @@ pub struct ContractViolationView {
     pub actual_return: Option<serde_json::Value>,
     /// Node values from the failing evaluation, sorted by NodeId for determinism.
     pub counterexample: Vec<(u32, serde_json::Value)>,
+    /// `counterexample` as JSON named by the program's types.
+    pub typed_counterexample: Vec<(u32, serde_json::Value)>,
+}
+
+impl ContractViolationView {
+    /// Builds the view, rendering typed values with `registry`.
+    pub fn new(violation: &ContractViolation, registry: &TypeRegistry) -> Self {
+        ContractViolationView {
+            kind: match violation.kind {
+                ContractKind::Precondition => "precondition".to_string(),
+                ContractKind::Postcondition => "postcondition".to_string(),
+                ContractKind::Invariant => "invariant".to_string(),
+            },
+            contract_node: violation.contract_node.0,
+            function_id: violation.function_id.0,
+            message: violation.message.clone(),
+            inputs: violation
+                .inputs
+                .iter()
+                .filter_map(|v| serde_json::to_value(v).ok())
+                .collect(),
+            actual_return: violation
+                .actual_return
+                .as_ref()
+                .and_then(|v| serde_json::to_value(v).ok()),
+            counterexample: violation
+                .counterexample
+                .iter()
+                .filter_map(|(nid, v)| serde_json::to_value(v).ok().map(|jv| (nid.0, jv)))
+                .collect(),
+            typed_counterexample: violation
+                .counterexample
+                .iter()
+                .map(|(nid, v)| (nid.0, v.to_typed_json(registry)))
+                .collect(),
+        }
+    }
 }
 
 /// A single trace entry for API responses (reuse pattern from simulate).
@@ -83,4 +256,7 @@ pub struct TraceEntryView {
     /// Output value produced (None for void ops).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<serde_json::Value>,
+    /// `output` as JSON named by the program's types.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typed_output: Option<serde_json::Value>,
 }
@@ -0,0 +1,197 @@
+//! Interactive debug session request/response types.
+//!
+//! A debug session runs a function step by step against a snapshot of the
+//! program graph taken when the session was created. Agents set breakpoints,
+//! step into/over/out of calls or back through history, rewind to the last
+//! write of a node or memory cell, and inspect call frames, memory, watch
+//! expressions and data lineage between steps. Idle sessions expire after a
+//! TTL, unless suspended to storage first; a stored snapshot resumes into a
+//! new session while the program's functions are unchanged.
+
+use lmlang_check::interpreter::{StepMode, StopReason, VirtualClock, WatchExpr};
+use lmlang_core::id::{FunctionId, NodeId};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::contracts::ContractViolationView;
+use super::diagnostics::DiagnosticError;
+
+/// Where to stop execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BreakpointSpec {
+    /// Before a specific node is evaluated.
+    Node { node_id: NodeId },
+    /// Before every contract node of a function, or of the whole program if
+    /// `function_id` is omitted.
+    Contracts {
+        #[serde(default)]
+        function_id: Option<FunctionId>,
+    },
+}
+
+/// Request to start a debug session.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateDebugSessionRequest {
+    /// The function to execute.
+    pub function_id: FunctionId,
+    /// Input values as JSON (converted like simulate inputs).
+    pub inputs: Vec<serde_json::Value>,
+    /// Initial breakpoints.
+    #[serde(default)]
+    pub breakpoints: Vec<BreakpointSpec>,
+    /// Watch expressions evaluated in every session response.
+    #[serde(default)]
+    pub watches: Vec<WatchExpr>,
+    /// Seed for `Random` ops (default 0).
+    #[serde(default)]
+    pub random_seed: Option<u64>,
+    /// Virtual clock for `Now` ops.
+    #[serde(default)]
+    pub clock: Option<VirtualClock>,
+}
+
+/// Request to advance a debug session.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DebugStepRequest {
+    pub mode: StepMode,
+}
+
+/// Request to replace a session's breakpoints.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetBreakpointsRequest {
+    pub breakpoints: Vec<BreakpointSpec>,
+}
+
+/// Request to replace a session's watch expressions.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetWatchesRequest {
+    pub watches: Vec<WatchExpr>,
+}
+
+/// Request to evaluate expressions once, without adding them as watches.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EvaluateRequest {
+    pub expressions: Vec<WatchExpr>,
+}
+
+/// Request for the data lineage of a node's most recent value.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LineageRequest {
+    pub node_id: NodeId,
+}
+
+/// Trace entries a node's value was derived from.
+#[derive(Debug, Clone, Serialize)]
+pub struct LineageResponse {
+    pub node_id: NodeId,
+    /// Contributing evaluations in execution order; the last is the node
+    /// itself.
+    pub entries: Vec<LineageEntryView>,
+}
+
+/// One evaluation in a lineage.
+#[derive(Debug, Clone, Serialize)]
+pub struct LineageEntryView {
+    /// Position in the session's execution trace.
+    pub index: usize,
+    /// Call stack depth of the evaluating frame (1 = the entry function).
+    pub depth: usize,
+    pub node_id: NodeId,
+    pub op: String,
+    pub inputs: Vec<(u16, serde_json::Value)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
+}
+
+/// Results of an [`EvaluateRequest`], in request order.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluateResponse {
+    pub results: Vec<WatchResultView>,
+}
+
+/// Where a debug session's execution is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugStatus {
+    /// Stopped between nodes; can be stepped further.
+    Paused,
+    Completed,
+    Error,
+    ContractViolation,
+}
+
+/// State of a debug session after a command.
+#[derive(Debug, Serialize)]
+pub struct DebugSessionResponse {
+    pub session_id: Uuid,
+    pub program_id: i64,
+    pub function_id: FunctionId,
+    pub status: DebugStatus,
+    /// Why the last command stopped (`step` for a fresh session).
+    pub stop: StopReason,
+    /// The node the next step evaluates (None once finished).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_node: Option<NodeId>,
+    /// Return value once completed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    /// Runtime error that halted execution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<DiagnosticError>,
+    /// Contract violation that halted execution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub violation: Option<ContractViolationView>,
+    /// Call frames, outermost first.
+    pub call_stack: Vec<DebugFrameView>,
+    /// Interpreter memory cells by address.
+    pub memory: Vec<serde_json::Value>,
+    /// Values printed so far.
+    pub io_log: Vec<serde_json::Value>,
+    /// Breakpoint nodes, sorted.
+    pub breakpoints: Vec<NodeId>,
+    /// The session's watch expressions, evaluated now.
+    pub watches: Vec<WatchResultView>,
+    /// Seconds until the session expires unless used again.
+    pub expires_in_secs: u64,
+}
+
+/// One call frame of a paused session.
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugFrameView {
+    /// Index in the call stack (0 = outermost).
+    pub depth: usize,
+    pub function_id: FunctionId,
+    pub function_name: String,
+    pub arguments: Vec<serde_json::Value>,
+    /// Values produced so far in this frame, sorted by node ID.
+    pub node_values: Vec<(NodeId, serde_json::Value)>,
+}
+
+/// A watch expression and its current value or evaluation error.
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchResultView {
+    pub expression: WatchExpr,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A suspended debug session stored for the program.
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugSnapshotView {
+    pub snapshot_id: Uuid,
+    pub program_id: i64,
+    pub function_id: FunctionId,
+    /// Steps the run had taken when suspended.
+    pub steps: u64,
+    /// When the session was suspended (ISO 8601).
+    pub timestamp: String,
+}
+
+/// Stored debug snapshots of a program, most recent first.
+#[derive(Debug, Clone, Serialize)]
+pub struct ListDebugSnapshotsResponse {
+    pub snapshots: Vec<DebugSnapshotView>,
+}
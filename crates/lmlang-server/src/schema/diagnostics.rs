@@ -4,6 +4,10 @@
 //! validation warnings. Per the CONTEXT.md locked decision, errors describe
 //! the problem only -- no fix suggestions are included in API output.
 
+use lmlang_check::borrow::BorrowError;
+use lmlang_check::effects::{EffectError, EffectOrigin};
+use lmlang_check::intervals::{RangeHazard, RangeWarning};
+use lmlang_check::termination::{TerminationHazard, TerminationWarning};
 use lmlang_check::typecheck::diagnostics::TypeError;
 use lmlang_core::graph::{ConflictPriorityClass, PropagationConflictDiagnostic};
 use lmlang_core::id::{EdgeId, FunctionId, NodeId};
@@ -35,6 +39,48 @@ pub struct DiagnosticWarning {
     pub code: String,
     /// Human-readable warning description.
     pub message: String,
+    /// Node the warning is about.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<NodeId>,
+    /// Function containing that node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function_id: Option<FunctionId>,
+    /// Other nodes involved, e.g. a loop's conditions.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_nodes: Vec<NodeId>,
+}
+
+impl From<RangeWarning> for DiagnosticWarning {
+    fn from(warning: RangeWarning) -> Self {
+        let code = match warning.hazard {
+            RangeHazard::Overflow => "POSSIBLE_OVERFLOW",
+            RangeHazard::DivisionByZero => "POSSIBLE_DIVISION_BY_ZERO",
+            RangeHazard::OutOfBounds => "POSSIBLE_OUT_OF_BOUNDS",
+        };
+        DiagnosticWarning {
+            code: code.to_string(),
+            message: warning.message,
+            node_id: Some(warning.node),
+            function_id: Some(warning.function_id),
+            related_nodes: Vec::new(),
+        }
+    }
+}
+
+impl From<TerminationWarning> for DiagnosticWarning {
+    fn from(warning: TerminationWarning) -> Self {
+        let code = match warning.hazard {
+            TerminationHazard::Loop => "POSSIBLE_NONTERMINATING_LOOP",
+            TerminationHazard::Recursion => "POSSIBLE_UNBOUNDED_RECURSION",
+        };
+        DiagnosticWarning {
+            code: code.to_string(),
+            message: warning.message,
+            node_id: Some(warning.node),
+            function_id: Some(warning.function_id),
+            related_nodes: warning.related,
+        }
+    }
 }
 
 /// Structured context for a diagnostic error.
@@ -202,6 +248,167 @@ impl From<TypeError> for DiagnosticError {
                     port: None,
                 }),
             },
+            TypeError::InvalidLoopBody {
+                node, function_id, ..
+            } => DiagnosticError {
+                code: "INVALID_LOOP_BODY".to_string(),
+                message: err.to_string(),
+                details: Some(DiagnosticDetails {
+                    source_node: None,
+                    target_node: Some(*node),
+                    edge_path: None,
+                    expected_type: None,
+                    actual_type: None,
+                    function_id: Some(*function_id),
+                    port: None,
+                }),
+            },
+            TypeError::ContractValueEscapes { node, function_id } => DiagnosticError {
+                code: "CONTRACT_VALUE_ESCAPES".to_string(),
+                message: err.to_string(),
+                details: Some(DiagnosticDetails {
+                    source_node: None,
+                    target_node: Some(*node),
+                    edge_path: None,
+                    expected_type: None,
+                    actual_type: None,
+                    function_id: Some(*function_id),
+                    port: None,
+                }),
+            },
+            TypeError::InvalidCallback {
+                node, function_id, ..
+            } => DiagnosticError {
+                code: "INVALID_CALLBACK".to_string(),
+                message: err.to_string(),
+                details: Some(DiagnosticDetails {
+                    source_node: None,
+                    target_node: Some(*node),
+                    edge_path: None,
+                    expected_type: None,
+                    actual_type: None,
+                    function_id: Some(*function_id),
+                    port: None,
+                }),
+            },
+            TypeError::AmbiguousResultType {
+                node, function_id, ..
+            } => DiagnosticError {
+                code: "AMBIGUOUS_RESULT_TYPE".to_string(),
+                message: err.to_string(),
+                details: Some(DiagnosticDetails {
+                    source_node: None,
+                    target_node: Some(*node),
+                    edge_path: None,
+                    expected_type: None,
+                    actual_type: None,
+                    function_id: Some(*function_id),
+                    port: None,
+                }),
+            },
+            TypeError::NonArrayIteration {
+                node,
+                actual,
+                function_id,
+            } => DiagnosticError {
+                code: "NON_ARRAY_ITERATION".to_string(),
+                message: err.to_string(),
+                details: Some(DiagnosticDetails {
+                    source_node: None,
+                    target_node: Some(*node),
+                    edge_path: None,
+                    expected_type: None,
+                    actual_type: Some(*actual),
+                    function_id: Some(*function_id),
+                    port: Some(0),
+                }),
+            },
+        }
+    }
+}
+
+impl From<EffectError> for DiagnosticError {
+    fn from(err: EffectError) -> Self {
+        let (code, function_id, origin) = match &err {
+            EffectError::UndeclaredCapability {
+                function_id,
+                origin,
+                ..
+            } => ("UNDECLARED_CAPABILITY", *function_id, *origin),
+            EffectError::ForbiddenCapability {
+                function_id,
+                origin,
+                ..
+            } => ("FORBIDDEN_CAPABILITY", *function_id, *origin),
+        };
+        let target_node = match origin {
+            EffectOrigin::Node(node) => Some(node),
+            EffectOrigin::Call(_) | EffectOrigin::Closure(_) => None,
+        };
+        DiagnosticError {
+            code: code.to_string(),
+            message: err.to_string(),
+            details: Some(DiagnosticDetails {
+                source_node: None,
+                target_node,
+                edge_path: None,
+                expected_type: None,
+                actual_type: None,
+                function_id: Some(function_id),
+                port: None,
+            }),
+        }
+    }
+}
+
+impl From<BorrowError> for DiagnosticError {
+    fn from(err: BorrowError) -> Self {
+        let (code, function_id, source_node, target_node, port) = match &err {
+            BorrowError::AliasedMutableBorrow {
+                function_id,
+                node,
+                origin,
+                mutable_port,
+                ..
+            } => (
+                "ALIASED_MUTABLE_BORROW",
+                *function_id,
+                *origin,
+                *node,
+                *mutable_port,
+            ),
+            BorrowError::StoreThroughImmutable {
+                function_id,
+                node,
+                pointer,
+                ..
+            } => ("STORE_THROUGH_IMMUTABLE", *function_id, *pointer, *node, 0),
+            BorrowError::DanglingCapture {
+                function_id,
+                node,
+                capture,
+                alloc,
+                ..
+            } => (
+                "DANGLING_CAPTURE",
+                *function_id,
+                *alloc,
+                *node,
+                *capture as u16,
+            ),
+        };
+        DiagnosticError {
+            code: code.to_string(),
+            message: err.to_string(),
+            details: Some(DiagnosticDetails {
+                source_node: Some(source_node),
+                target_node: Some(target_node),
+                edge_path: None,
+                expected_type: None,
+                actual_type: None,
+                function_id: Some(function_id),
+                port: Some(port),
+            }),
         }
     }
 }
@@ -7,10 +7,12 @@ pub mod agent_control;
 pub mod agents;
 pub mod autonomy_execution;
 pub mod autonomy_plan;
+pub mod capabilities;
 pub mod common;
 pub mod compile;
 pub mod contracts;
 pub mod dashboard;
+pub mod debug;
 pub mod diagnostics;
 pub mod history;
 pub mod locks;
@@ -4,7 +4,9 @@
 //! batch operations with all-or-nothing semantics. The `dry_run` flag
 //! previews validation results without committing.
 
+use lmlang_core::capability::EffectSet;
 use lmlang_core::id::{EdgeId, FunctionId, ModuleId, NodeId};
+use lmlang_core::node::TestCase;
 use lmlang_core::ops::ComputeNodeOp;
 use lmlang_core::type_id::TypeId;
 use lmlang_core::types::Visibility;
@@ -115,6 +117,36 @@ pub enum Mutation {
         /// Visibility.
         visibility: Visibility,
     },
+    /// Declare (or clear) a function's capability set.
+    #[serde(alias = "set_function_effects", alias = "setFunctionEffects")]
+    SetFunctionEffects {
+        /// The function to update.
+        function_id: FunctionId,
+        /// Declared capabilities; `[]` for pure, `null` to remove the declaration.
+        effects: Option<EffectSet>,
+    },
+    /// Add a test node with executable cases.
+    #[serde(alias = "add_test", alias = "addTest")]
+    AddTest {
+        /// Owning module.
+        module: ModuleId,
+        /// Stable test identifier.
+        test_id: String,
+        /// Human-readable title.
+        title: String,
+        /// Function the cases call.
+        #[serde(default)]
+        target_function: Option<FunctionId>,
+        /// Cases, with values in the interpreter's JSON value encoding.
+        #[serde(default)]
+        cases: Vec<TestCase>,
+    },
+    /// Remove a test node.
+    #[serde(alias = "remove_test", alias = "removeTest")]
+    RemoveTest {
+        /// Semantic node index of the test.
+        semantic_node_id: u32,
+    },
 }
 
 /// Response from a propose-edit operation.
@@ -144,4 +176,6 @@ pub enum CreatedEntity {
     Function { id: FunctionId },
     /// A new module was created.
     Module { id: ModuleId },
+    /// A new test node was created.
+    Test { semantic_node_id: u32 },
 }
@@ -4,6 +4,7 @@
 //! - dual-layer graph projection payloads (semantic + compute + cross-layer)
 //! - natural-language observability query request/response contracts
 
+use lmlang_check::interpreter::profile::NodeProfile;
 use lmlang_core::edge::SemanticEdge;
 use lmlang_core::id::{FunctionId, ModuleId, NodeId};
 use lmlang_core::type_id::TypeId;
@@ -75,6 +76,13 @@ pub struct ObservabilityNodeView {
     pub semantic_node_id: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>,
+    /// Evaluations recorded by simulate and property-test runs; absent for
+    /// nodes outside the functions those runs covered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage_hits: Option<u64>,
+    /// Position in `hot_nodes` (1 = hottest); absent for nodes not ranked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hot_rank: Option<usize>,
 }
 
 /// A projected edge for observability visualization.
@@ -97,6 +105,10 @@ pub struct ObservabilityEdgeView {
     pub branch_index: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub relationship: Option<SemanticEdge>,
+    /// Times the branch arm this control edge belongs to was taken; only set
+    /// on arms of covered `Branch`/`IfElse`/`Match` nodes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage_hits: Option<u64>,
 }
 
 /// Full graph payload consumed by the observability UI.
@@ -108,6 +120,23 @@ pub struct ObservabilityGraphResponse {
     pub nodes: Vec<ObservabilityNodeView>,
     pub edges: Vec<ObservabilityEdgeView>,
     pub groups: Vec<ObservabilityGroupView>,
+    /// Coverage totals over the functions simulate and property-test runs
+    /// covered since the program was loaded; absent before any run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<ObservabilityCoverageView>,
+    /// The most evaluated compute nodes over profiled simulate runs since
+    /// the program was loaded, hottest first; empty before any.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hot_nodes: Vec<NodeProfile>,
+}
+
+/// Accumulated node and branch-arm coverage totals.
+#[derive(Debug, Clone, Serialize)]
+pub struct ObservabilityCoverageView {
+    pub nodes_total: usize,
+    pub nodes_covered: usize,
+    pub arms_total: usize,
+    pub arms_covered: usize,
 }
 
 fn default_max_results() -> usize {
@@ -1,8 +1,9 @@
 //! Simulation request/response types for graph interpretation.
 //!
 //! Allows agents to execute functions with provided inputs and optionally
-//! record an execution trace for debugging.
+//! record an execution trace for debugging or a cost profile.
 
+use lmlang_check::interpreter::{CoverageSummary, ExecutionLimits, ProfileReport, VirtualClock};
 use lmlang_core::id::{FunctionId, NodeId};
 use serde::{Deserialize, Serialize};
 
@@ -18,6 +19,26 @@ pub struct SimulateRequest {
     /// Whether to record an execution trace.
     #[serde(default)]
     pub trace_enabled: Option<bool>,
+    /// Seed for `Random` ops (default 0).
+    #[serde(default)]
+    pub random_seed: Option<u64>,
+    /// Virtual clock for `Now` ops (default starts at 0 and advances 1ms per read).
+    #[serde(default)]
+    pub clock: Option<VirtualClock>,
+    /// Step, time and memory budgets; unset limits use the server defaults.
+    #[serde(default)]
+    pub limits: ExecutionLimits,
+    /// Whether to profile the run; also set by `?profile=true`.
+    #[serde(default)]
+    pub profile: bool,
+}
+
+/// Query parameters of a simulate request.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SimulateQuery {
+    /// Whether to profile the run.
+    #[serde(default)]
+    pub profile: bool,
 }
 
 /// Response from a simulation run.
@@ -28,14 +49,37 @@ pub struct SimulateResponse {
     /// The result value (None if simulation errored).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<serde_json::Value>,
+    /// The result as JSON named by the program's types: structs keyed by
+    /// field name, enums by variant name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typed_result: Option<serde_json::Value>,
     /// Execution trace (None if tracing was disabled).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trace: Option<Vec<TraceEntryView>>,
     /// Error if simulation failed.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<DiagnosticError>,
+    /// Values computed before a runtime error halted execution, sorted by
+    /// node ID (None on success).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial_results: Option<Vec<(NodeId, serde_json::Value)>>,
     /// I/O operations logged during execution (Print outputs, etc.).
     pub io_log: Vec<serde_json::Value>,
+    /// Nodes and branch arms exercised, over the function and its callees.
+    pub coverage: CoverageSummary,
+    /// Costs of the run (None unless profiling was requested).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ProfileView>,
+}
+
+/// Cost profile of a simulate run.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileView {
+    /// Ranked function, op kind and hot node costs.
+    #[serde(flatten)]
+    pub report: ProfileReport,
+    /// Steps per call stack in the folded format read by flamegraph tools.
+    pub folded_stacks: String,
 }
 
 /// A single trace entry for API responses.
@@ -53,4 +97,7 @@ pub struct TraceEntryView {
     /// Output value produced (None for void ops).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<serde_json::Value>,
+    /// `output` as JSON named by the program's types.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typed_output: Option<serde_json::Value>,
 }
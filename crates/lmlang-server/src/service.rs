@@ -11,9 +11,20 @@ use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
 use rusqlite::Connection;
-
-use lmlang_check::interpreter::{ExecutionState, Interpreter, InterpreterConfig, Value};
+use uuid::Uuid;
+
+use lmlang_check::borrow;
+use lmlang_check::effects;
+use lmlang_check::interpreter::coverage::{self, Coverage, CoverageSummary};
+use lmlang_check::interpreter::profile::{Profile, DEFAULT_HOT_NODES};
+use lmlang_check::interpreter::{
+    BytecodeCache, ExecutionLimits, ExecutionState, Interpreter, InterpreterConfig, SnapshotError,
+    Value,
+};
+use lmlang_check::intervals;
+use lmlang_check::termination;
 use lmlang_check::typecheck;
+use lmlang_core::capability::EffectSet;
 use lmlang_core::edge::{FlowEdge, SemanticEdge};
 use lmlang_core::graph::{
     ComputeEvent, ProgramGraph, PropagationEventKind, PropagationLayer, SemanticEvent,
@@ -21,13 +32,19 @@ use lmlang_core::graph::{
 use lmlang_core::id::{EdgeId, FunctionId, ModuleId, NodeId};
 use lmlang_core::node::{ComputeNode, SemanticNode};
 use lmlang_core::ops::{ComputeNodeOp, ComputeOp};
-use lmlang_core::type_id::TypeId;
+use lmlang_core::type_id::{TypeId, TypeRegistry};
 use lmlang_storage::traits::GraphStore;
 use lmlang_storage::types::ProgramId;
 use lmlang_storage::SqliteStore;
 
+use crate::debug_sessions::{DebugSession, SuspendedSession, DEBUG_CHECKPOINT_INTERVAL};
 use crate::error::ApiError;
+use crate::schema::capabilities::{CapabilityPolicyResponse, FunctionEffectsView};
+use crate::schema::debug::{
+    CreateDebugSessionRequest, DebugSnapshotView, ListDebugSnapshotsResponse,
+};
 use crate::schema::diagnostics::DiagnosticError;
+use crate::schema::diagnostics::DiagnosticWarning;
 use crate::schema::diagnostics::PropagationConflictDiagnosticView;
 use crate::schema::history::{
     CreateCheckpointResponse, DiffResponse, ListCheckpointsResponse, ListHistoryResponse,
@@ -35,12 +52,12 @@ use crate::schema::history::{
 };
 use crate::schema::mutations::{CreatedEntity, Mutation, ProposeEditRequest, ProposeEditResponse};
 use crate::schema::observability::{
-    ObservabilityEdgeView, ObservabilityGraphRequest, ObservabilityGraphResponse,
-    ObservabilityGroupView, ObservabilityLayer, ObservabilityNodeView, ObservabilityPreset,
-    ObservabilityQueryRequest, ObservabilityQueryResponse, ObservabilityQueryResultView,
-    QueryContractEntryView, QueryContractsTabView, QueryInterpretationView,
-    QueryRelationshipItemView, QueryRelationshipsTabView, QuerySummaryTabView,
-    SuggestedPromptChipView,
+    ObservabilityCoverageView, ObservabilityEdgeView, ObservabilityGraphRequest,
+    ObservabilityGraphResponse, ObservabilityGroupView, ObservabilityLayer, ObservabilityNodeView,
+    ObservabilityPreset, ObservabilityQueryRequest, ObservabilityQueryResponse,
+    ObservabilityQueryResultView, QueryContractEntryView, QueryContractsTabView,
+    QueryInterpretationView, QueryRelationshipItemView, QueryRelationshipsTabView,
+    QuerySummaryTabView, SuggestedPromptChipView,
 };
 use crate::schema::programs::ProgramSummaryView;
 use crate::schema::queries::{
@@ -48,10 +65,11 @@ use crate::schema::queries::{
     ProgramOverviewResponse, SearchRequest, SearchResponse, SemanticEdgeView, SemanticNodeView,
     SemanticOwnershipView, SemanticProvenanceView, SemanticQueryResponse,
 };
-use crate::schema::simulate::{SimulateRequest, SimulateResponse, TraceEntryView};
+use crate::schema::simulate::{ProfileView, SimulateRequest, SimulateResponse, TraceEntryView};
 use crate::schema::verify::{
     FlushPropagationResponse, PropagationEventSeed, VerifyResponse, VerifyScope,
 };
+use crate::snapshots::SnapshotStore;
 use crate::undo::{CheckpointManager, EditCommand, EditLog};
 
 /// The central service coordinating graph mutations, queries, verification,
@@ -70,8 +88,66 @@ pub struct ProgramService {
     conn: Connection,
     /// Incremental compilation state (lazily initialized on first compile).
     incremental_state: Option<lmlang_codegen::incremental::IncrementalState>,
+    /// Capabilities forbidden for programs without their own policy.
+    default_forbidden_capabilities: EffectSet,
+    /// Per-program forbidden capabilities (overrides the default), mirrored
+    /// in `programs.forbidden_capabilities_json`.
+    capability_policies: HashMap<ProgramId, EffectSet>,
+    /// Coverage accumulated over simulate and property-test runs since the
+    /// program was loaded or last changed, overlaid on the observability
+    /// graph.
+    coverage: Coverage,
+    /// Functions the accumulated coverage was measured over.
+    coverage_scope: HashSet<FunctionId>,
+    /// Costs accumulated over profiled simulate runs since the program was
+    /// loaded or last changed, ranked into the observability graph's hot
+    /// nodes.
+    profile: Profile,
+    /// Interpreter budgets for limits a request leaves unset.
+    default_execution_limits: ExecutionLimits,
+    /// Bytecode lowered for property tests, reused while functions are
+    /// unchanged.
+    bytecode_cache: BytecodeCache,
+}
+
+/// Loads every program's persisted forbidden capabilities; programs on the
+/// server default (`null`) are omitted.
+fn load_capability_policies(conn: &Connection) -> Result<HashMap<ProgramId, EffectSet>, ApiError> {
+    let load_err = |e: rusqlite::Error| {
+        ApiError::InternalError(format!("failed to load capability policies: {}", e))
+    };
+    let mut stmt = conn
+        .prepare("SELECT id, forbidden_capabilities_json FROM programs")
+        .map_err(load_err)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(load_err)?;
+    let mut policies = HashMap::new();
+    for row in rows {
+        let (id, json) = row.map_err(load_err)?;
+        let forbidden: Option<EffectSet> = serde_json::from_str(&json).map_err(|e| {
+            ApiError::InternalError(format!(
+                "corrupt capability policy for program {}: {}",
+                id, e
+            ))
+        })?;
+        if let Some(forbidden) = forbidden {
+            policies.insert(ProgramId(id), forbidden);
+        }
+    }
+    Ok(policies)
 }
 
+/// Server-wide interpreter budgets unless overridden at startup: ten million
+/// steps, ten seconds and one million memory cells per run.
+pub const DEFAULT_EXECUTION_LIMITS: ExecutionLimits = ExecutionLimits {
+    max_steps: Some(10_000_000),
+    timeout_ms: Some(10_000),
+    max_memory_cells: Some(1_000_000),
+};
+
 impl ProgramService {
     /// Creates a new ProgramService, opening a SQLite database at `db_path`.
     ///
@@ -103,12 +179,21 @@ impl ProgramService {
             (id, graph)
         };
 
+        let capability_policies = load_capability_policies(&conn)?;
+
         Ok(ProgramService {
             graph,
             store,
             program_id,
             conn,
             incremental_state: None,
+            default_forbidden_capabilities: EffectSet::new(),
+            capability_policies,
+            coverage: Coverage::default(),
+            coverage_scope: HashSet::new(),
+            profile: Profile::default(),
+            default_execution_limits: DEFAULT_EXECUTION_LIMITS,
+            bytecode_cache: BytecodeCache::new(),
         })
     }
 
@@ -142,6 +227,13 @@ impl ProgramService {
             program_id: id,
             conn,
             incremental_state: None,
+            default_forbidden_capabilities: EffectSet::new(),
+            capability_policies: HashMap::new(),
+            coverage: Coverage::default(),
+            coverage_scope: HashSet::new(),
+            profile: Profile::default(),
+            default_execution_limits: DEFAULT_EXECUTION_LIMITS,
+            bytecode_cache: BytecodeCache::new(),
         })
     }
 
@@ -172,9 +264,19 @@ impl ProgramService {
         let graph = self.store.load_program(id)?;
         self.graph = graph;
         self.program_id = id;
+        self.reset_run_statistics();
         Ok(())
     }
 
+    /// Drops the coverage and profile accumulated over earlier runs. Called
+    /// whenever the graph changes, since node IDs and branch arms recorded
+    /// against the old graph may no longer mean the same thing.
+    fn reset_run_statistics(&mut self) {
+        self.coverage = Coverage::default();
+        self.coverage_scope.clear();
+        self.profile = Profile::default();
+    }
+
     /// Lists all programs.
     pub fn list_programs(&self) -> Result<Vec<ProgramSummaryView>, ApiError> {
         let programs = self.store.list_programs()?;
@@ -201,6 +303,7 @@ impl ProgramService {
                 // Last program – reset to a blank graph; program_id will be
                 // stale but harmless since there are no programs left.
                 self.graph = ProgramGraph::new("main");
+                self.reset_run_statistics();
             }
         }
         self.store.delete_program(id)?;
@@ -303,6 +406,7 @@ impl ProgramService {
 
                     // Persist to store
                     self.store.save_program(self.program_id, &self.graph)?;
+                    self.reset_run_statistics();
                     self.enqueue_propagation_for_mutations(&request.mutations);
 
                     let mut created = Vec::new();
@@ -394,6 +498,7 @@ impl ProgramService {
 
             // Persist to store
             self.store.save_program(self.program_id, &self.graph)?;
+            self.reset_run_statistics();
             self.enqueue_propagation_for_mutations(&request.mutations);
 
             Ok(ProposeEditResponse {
@@ -532,6 +637,70 @@ impl ProgramService {
                 };
                 Ok((Some(CreatedEntity::Module { id: module_id }), cmd))
             }
+            Mutation::SetFunctionEffects {
+                function_id,
+                effects,
+            } => {
+                let func_def = graph.get_function_mut(*function_id).ok_or_else(|| {
+                    ApiError::NotFound(format!("function {} not found", function_id.0))
+                })?;
+                let old_effects = std::mem::replace(&mut func_def.effects, effects.clone());
+                let cmd = EditCommand::SetFunctionEffects {
+                    func_id: *function_id,
+                    old_effects,
+                    new_effects: effects.clone(),
+                };
+                Ok((None, cmd))
+            }
+            Mutation::AddTest {
+                module,
+                test_id,
+                title,
+                target_function,
+                cases,
+            } => {
+                let semantic_node_id = graph.add_test_suite(
+                    *module,
+                    test_id.clone(),
+                    title.clone(),
+                    *target_function,
+                    cases.clone(),
+                )?;
+                let test = match graph
+                    .semantic()
+                    .node_weight(NodeIndex::new(semantic_node_id as usize))
+                {
+                    Some(SemanticNode::Test(test)) => test.clone(),
+                    _ => {
+                        return Err(ApiError::InternalError(format!(
+                            "test node {} missing after insertion",
+                            semantic_node_id
+                        )))
+                    }
+                };
+                let cmd = EditCommand::AddTest {
+                    semantic_node_id,
+                    module: *module,
+                    test,
+                };
+                Ok((Some(CreatedEntity::Test { semantic_node_id }), cmd))
+            }
+            Mutation::RemoveTest { semantic_node_id } => {
+                let removed_test = graph.remove_test_node(*semantic_node_id).map_err(|_| {
+                    ApiError::NotFound(format!("test node {} not found", semantic_node_id))
+                })?;
+                let module = removed_test
+                    .metadata
+                    .ownership
+                    .module
+                    .unwrap_or_else(|| graph.modules.root_id());
+                let cmd = EditCommand::RemoveTest {
+                    semantic_node_id: *semantic_node_id,
+                    module,
+                    removed_test,
+                };
+                Ok((None, cmd))
+            }
         }
     }
 
@@ -602,6 +771,33 @@ impl ProgramService {
                 graph.add_module(name.clone(), *parent, *visibility)?;
                 Ok(())
             }
+            EditCommand::SetFunctionEffects {
+                func_id,
+                new_effects,
+                ..
+            } => {
+                let func_def = graph.get_function_mut(*func_id).ok_or_else(|| {
+                    ApiError::NotFound(format!("function {} not found", func_id.0))
+                })?;
+                func_def.effects = new_effects.clone();
+                Ok(())
+            }
+            EditCommand::AddTest { module, test, .. } => {
+                graph.add_test_suite(
+                    *module,
+                    test.test_id.clone(),
+                    test.title.clone(),
+                    test.target_function,
+                    test.cases.clone(),
+                )?;
+                Ok(())
+            }
+            EditCommand::RemoveTest {
+                semantic_node_id, ..
+            } => {
+                graph.remove_test_node(*semantic_node_id)?;
+                Ok(())
+            }
             EditCommand::Batch { commands, .. } => {
                 for sub_cmd in commands {
                     Self::apply_edit_command(graph, sub_cmd)?;
@@ -687,7 +883,11 @@ impl ProgramService {
                         );
                     }
                 }
-                Mutation::RemoveEdge { .. } | Mutation::AddModule { .. } => {}
+                Mutation::RemoveEdge { .. }
+                | Mutation::AddModule { .. }
+                | Mutation::SetFunctionEffects { .. }
+                | Mutation::AddTest { .. }
+                | Mutation::RemoveTest { .. } => {}
             }
         }
     }
@@ -830,7 +1030,9 @@ impl ProgramService {
     /// Runs type verification on the graph.
     ///
     /// - `VerifyScope::Local`: validates data edges touching the specified affected nodes.
-    /// - `VerifyScope::Full`: validates the entire graph.
+    /// - `VerifyScope::Full`: validates the entire graph, checks pointer and
+    ///   capture borrows, and warns about operations range analysis cannot
+    ///   prove free of runtime traps.
     pub fn verify(
         &self,
         scope: VerifyScope,
@@ -872,6 +1074,8 @@ impl ProgramService {
                     }
                 }
 
+                errors.extend(self.capability_errors());
+
                 Ok(VerifyResponse {
                     valid: errors.is_empty(),
                     errors,
@@ -880,18 +1084,132 @@ impl ProgramService {
             }
             VerifyScope::Full => {
                 let type_errors = typecheck::validate_graph(&self.graph);
-                let errors: Vec<DiagnosticError> =
+                let mut errors: Vec<DiagnosticError> =
                     type_errors.into_iter().map(DiagnosticError::from).collect();
+                errors.extend(self.capability_errors());
+                errors.extend(
+                    borrow::check_borrows(&self.graph)
+                        .into_iter()
+                        .map(DiagnosticError::from),
+                );
+                let ranges = intervals::analyze_ranges(&self.graph, true);
+                let mut warnings: Vec<DiagnosticWarning> = ranges
+                    .warnings
+                    .iter()
+                    .cloned()
+                    .map(DiagnosticWarning::from)
+                    .collect();
+                warnings.extend(
+                    termination::analyze_termination(&self.graph, &ranges)
+                        .warnings()
+                        .into_iter()
+                        .map(DiagnosticWarning::from),
+                );
 
                 Ok(VerifyResponse {
                     valid: errors.is_empty(),
                     errors,
-                    warnings: Vec::new(),
+                    warnings,
                 })
             }
         }
     }
 
+    // -----------------------------------------------------------------------
+    // Capability policy
+    // -----------------------------------------------------------------------
+
+    /// Sets the capabilities forbidden for programs without their own policy.
+    pub fn set_default_forbidden_capabilities(&mut self, forbidden: EffectSet) {
+        self.default_forbidden_capabilities = forbidden;
+    }
+
+    /// Sets the interpreter budgets used where a request leaves a limit unset.
+    pub fn set_default_execution_limits(&mut self, limits: ExecutionLimits) {
+        self.default_execution_limits = limits;
+    }
+
+    /// Returns the server-wide interpreter budgets.
+    pub fn default_execution_limits(&self) -> ExecutionLimits {
+        self.default_execution_limits
+    }
+
+    /// Returns the capabilities forbidden for the active program.
+    pub fn forbidden_capabilities(&self) -> &EffectSet {
+        self.capability_policies
+            .get(&self.program_id)
+            .unwrap_or(&self.default_forbidden_capabilities)
+    }
+
+    /// Sets the active program's forbidden capabilities, or resets it to the
+    /// server default when `forbidden` is `None`. The policy is persisted so
+    /// it survives a restart.
+    pub fn set_capability_policy(
+        &mut self,
+        forbidden: Option<EffectSet>,
+    ) -> Result<CapabilityPolicyResponse, ApiError> {
+        let json = serde_json::to_string(&forbidden).map_err(|e| {
+            ApiError::InternalError(format!("failed to serialize capability policy: {}", e))
+        })?;
+        self.conn
+            .execute(
+                "UPDATE programs SET forbidden_capabilities_json = ?1 WHERE id = ?2",
+                rusqlite::params![json, self.program_id.0],
+            )
+            .map_err(|e| {
+                ApiError::InternalError(format!("failed to save capability policy: {}", e))
+            })?;
+        match forbidden {
+            Some(forbidden) => {
+                self.capability_policies.insert(self.program_id, forbidden);
+            }
+            None => {
+                self.capability_policies.remove(&self.program_id);
+            }
+        }
+        Ok(self.capability_policy())
+    }
+
+    /// Returns the active program's capability policy with per-function effects.
+    pub fn capability_policy(&self) -> CapabilityPolicyResponse {
+        let call_graph = lmlang_codegen::incremental::build_call_graph(&self.graph);
+        let inferred = effects::infer_effects(&self.graph, &call_graph);
+
+        let mut functions: Vec<FunctionEffectsView> = self
+            .graph
+            .functions()
+            .values()
+            .map(|f| FunctionEffectsView {
+                function_id: f.id,
+                name: f.name.clone(),
+                declared: f.effects.clone(),
+                inferred: inferred.get(&f.id).cloned().unwrap_or_default(),
+            })
+            .collect();
+        functions.sort_by_key(|f| f.function_id.0);
+
+        CapabilityPolicyResponse {
+            forbidden: self.forbidden_capabilities().clone(),
+            server_default: self.default_forbidden_capabilities.clone(),
+            functions,
+            errors: self.capability_errors(),
+        }
+    }
+
+    /// Checks declared effects and the active policy over the whole program.
+    fn capability_errors(&self) -> Vec<DiagnosticError> {
+        let call_graph = lmlang_codegen::incremental::build_call_graph(&self.graph);
+        effects::check_effects(&self.graph, &call_graph)
+            .into_iter()
+            .chain(effects::check_forbidden(
+                &self.graph,
+                &call_graph,
+                self.forbidden_capabilities(),
+            ))
+            .map(DiagnosticError::from)
+            .collect()
+    }
+
     // -----------------------------------------------------------------------
     // Query methods (TOOL-02)
     // -----------------------------------------------------------------------
@@ -1201,7 +1519,7 @@ impl ProgramService {
                 compute_node_ids,
             });
         }
-        groups.sort_by(|a, b| a.function_id.0.cmp(&b.function_id.0));
+        groups.sort_by_key(|a| a.function_id.0);
 
         let mut nodes = Vec::new();
         let mut semantic_indices: Vec<_> = self.graph.semantic().node_indices().collect();
@@ -1227,10 +1545,25 @@ impl ProgramService {
                     compute_node_id: None,
                     semantic_node_id: Some(semantic_node_id),
                     summary: Some(node.metadata().summary.body.clone()),
+                    coverage_hits: None,
+                    hot_rank: None,
                 });
             }
         }
 
+        let mut hot_nodes = Vec::new();
+        if !self.profile.is_empty() {
+            hot_nodes = self.profile.report(&self.graph, usize::MAX).hot_nodes;
+            // Nodes removed since they were profiled cannot be shown
+            hot_nodes.retain(|n| n.function_id.is_some());
+            hot_nodes.truncate(DEFAULT_HOT_NODES);
+        }
+        let hot_ranks: HashMap<NodeId, usize> = hot_nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.node_id, i + 1))
+            .collect();
+
         let mut compute_indices: Vec<_> = self.graph.compute().node_indices().collect();
         compute_indices.sort_by_key(|idx| idx.index());
         if preset_includes_layer(request.preset, ObservabilityLayer::Compute) {
@@ -1252,6 +1585,11 @@ impl ProgramService {
                     compute_node_id: Some(node_id),
                     semantic_node_id: None,
                     summary: None,
+                    coverage_hits: self
+                        .coverage_scope
+                        .contains(&node.owner)
+                        .then(|| self.coverage.node_hits(node_id)),
+                    hot_rank: hot_ranks.get(&node_id).copied(),
                 });
             }
         }
@@ -1290,6 +1628,7 @@ impl ProgramService {
                     target_port: Some(*target_port),
                     branch_index: None,
                     relationship: None,
+                    coverage_hits: None,
                 },
                 FlowEdge::Control { branch_index } => ObservabilityEdgeView {
                     id: format!("compute-edge:{}", edge_idx.index()),
@@ -1304,6 +1643,7 @@ impl ProgramService {
                     target_port: None,
                     branch_index: *branch_index,
                     relationship: None,
+                    coverage_hits: self.arm_coverage_hits(NodeId::from(from_idx), *branch_index),
                 },
             });
         }
@@ -1330,6 +1670,7 @@ impl ProgramService {
                 target_port: None,
                 branch_index: None,
                 relationship: Some(*weight),
+                coverage_hits: None,
             });
         }
 
@@ -1352,6 +1693,7 @@ impl ProgramService {
                         target_port: None,
                         branch_index: None,
                         relationship: None,
+                        coverage_hits: None,
                     });
                 }
             }
@@ -1365,6 +1707,18 @@ impl ProgramService {
         });
         edges.sort_by(|a, b| a.id.cmp(&b.id));
 
+        let coverage = (!self.coverage_scope.is_empty()).then(|| {
+            let mut scope: Vec<FunctionId> = self.coverage_scope.iter().copied().collect();
+            scope.sort_by_key(|f| f.0);
+            let summary = self.coverage.summarize(&self.graph, &scope);
+            ObservabilityCoverageView {
+                nodes_total: summary.nodes_total,
+                nodes_covered: summary.nodes_covered,
+                arms_total: summary.arms_total,
+                arms_covered: summary.arms_covered,
+            }
+        });
+
         Ok(ObservabilityGraphResponse {
             preset: request.preset,
             node_count: nodes.len(),
@@ -1372,9 +1726,24 @@ impl ProgramService {
             nodes,
             edges,
             groups,
+            coverage,
+            hot_nodes,
         })
     }
 
+    /// Accumulated hits of the branch arm a control edge out of `node`
+    /// belongs to, if `node` is a branch in the coverage scope.
+    fn arm_coverage_hits(&self, node: NodeId, branch_index: Option<u16>) -> Option<u64> {
+        let arm = branch_index?;
+        let owner = self.graph.get_compute_node(node)?.owner;
+        if !self.coverage_scope.contains(&owner)
+            || !coverage::branch_arms(&self.graph, node).contains(&arm)
+        {
+            return None;
+        }
+        Some(self.coverage.arm_hits(node, arm))
+    }
+
     /// Executes a natural-language observability query with ranking,
     /// disambiguation, and low-confidence fallback.
     pub fn observability_query(
@@ -1846,8 +2215,26 @@ impl ProgramService {
     // Simulate method (TOOL-04)
     // -----------------------------------------------------------------------
 
+    /// Records a run's coverage over `function_id` and its callees for the
+    /// observability overlay, returning the run's own summary.
+    ///
+    /// Takes the service's fields separately so it can run while an
+    /// interpreter still borrows the graph.
+    fn record_coverage(
+        graph: &ProgramGraph,
+        total: &mut Coverage,
+        total_scope: &mut HashSet<FunctionId>,
+        function_id: FunctionId,
+        run: &Coverage,
+    ) -> CoverageSummary {
+        let scope = coverage::reachable_functions(graph, function_id);
+        total.merge(run);
+        total_scope.extend(scope.iter().copied());
+        run.summarize(graph, &scope)
+    }
+
     /// Runs the interpreter on a function with provided inputs.
-    pub fn simulate(&self, request: SimulateRequest) -> Result<SimulateResponse, ApiError> {
+    pub fn simulate(&mut self, request: SimulateRequest) -> Result<SimulateResponse, ApiError> {
         // Verify function exists
         let func_def = self
             .graph
@@ -1856,26 +2243,43 @@ impl ProgramService {
                 ApiError::NotFound(format!("function {} not found", request.function_id.0))
             })?;
 
-        // Convert JSON inputs to interpreter Values
-        let mut inputs = Vec::new();
-        for (i, json_val) in request.inputs.iter().enumerate() {
-            let value = json_to_value(json_val, func_def.params.get(i).map(|(_, t)| *t));
-            inputs.push(value);
-        }
+        let inputs = json_to_inputs(&request.inputs, &func_def.params, &self.graph.types)?;
 
         let trace_enabled = request.trace_enabled.unwrap_or(false);
         let config = InterpreterConfig {
             trace_enabled,
+            coverage_enabled: true,
+            profile_enabled: request.profile,
             max_recursion_depth: 256,
+            random_seed: request.random_seed.unwrap_or(0),
+            clock: request.clock.unwrap_or_default(),
+            checkpoint_interval: None,
+            limits: request.limits.or(self.default_execution_limits),
         };
 
         let mut interp = Interpreter::new(&self.graph, config);
         interp.start(request.function_id, inputs);
         interp.run();
+        let run_coverage = interp.coverage().cloned().unwrap_or_default();
+        let coverage = Self::record_coverage(
+            &self.graph,
+            &mut self.coverage,
+            &mut self.coverage_scope,
+            request.function_id,
+            &run_coverage,
+        );
+        let profile = interp.profile().map(|run_profile| {
+            self.profile.merge(run_profile);
+            ProfileView {
+                report: run_profile.report(&self.graph, DEFAULT_HOT_NODES),
+                folded_stacks: run_profile.folded_stacks(&self.graph),
+            }
+        });
 
         match interp.state() {
             ExecutionState::Completed { result } => {
                 let result_json = serde_json::to_value(result).ok();
+                let typed_result = Some(result.to_typed_json(&self.graph.types));
                 let trace = if trace_enabled {
                     interp.trace().map(|entries| {
                         entries
@@ -1894,6 +2298,10 @@ impl ProgramService {
                                     .output
                                     .as_ref()
                                     .and_then(|v| serde_json::to_value(v).ok()),
+                                typed_output: e
+                                    .output
+                                    .as_ref()
+                                    .map(|v| v.to_typed_json(&self.graph.types)),
                             })
                             .collect()
                     })
@@ -1910,28 +2318,48 @@ impl ProgramService {
                 Ok(SimulateResponse {
                     success: true,
                     result: result_json,
+                    typed_result,
                     trace,
                     error: None,
+                    partial_results: None,
                     io_log,
+                    coverage,
+                    profile,
                 })
             }
-            ExecutionState::Error { error, .. } => {
+            ExecutionState::Error {
+                error,
+                partial_results,
+            } => {
                 let io_log: Vec<serde_json::Value> = interp
                     .io_log()
                     .iter()
                     .filter_map(|v| serde_json::to_value(v).ok())
                     .collect();
+                let mut partial: Vec<(NodeId, serde_json::Value)> = partial_results
+                    .iter()
+                    .filter_map(|(node_id, value)| {
+                        serde_json::to_value(value)
+                            .ok()
+                            .map(|json| (*node_id, json))
+                    })
+                    .collect();
+                partial.sort_by_key(|(node_id, _)| node_id.0);
 
                 Ok(SimulateResponse {
                     success: false,
                     result: None,
+                    typed_result: None,
                     trace: None,
                     error: Some(DiagnosticError {
                         code: "RUNTIME_ERROR".to_string(),
                         message: format!("{}", error),
                         details: None,
                     }),
+                    partial_results: Some(partial),
                     io_log,
+                    coverage,
+                    profile,
                 })
             }
             _ => Err(ApiError::InternalError(
@@ -1940,6 +2368,89 @@ impl ProgramService {
         }
     }
 
+    // -----------------------------------------------------------------------
+    // Interactive debugging
+    // -----------------------------------------------------------------------
+
+    /// Starts a debug session for a function on a snapshot of the graph.
+    ///
+    /// The session is stopped before the function's first node; register it
+    /// with the [`DebugSessionManager`](crate::debug_sessions::DebugSessionManager)
+    /// to step it through later requests.
+    pub fn debug_session(
+        &self,
+        request: CreateDebugSessionRequest,
+    ) -> Result<DebugSession, ApiError> {
+        let func_def = self
+            .graph
+            .get_function(request.function_id)
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("function {} not found", request.function_id.0))
+            })?;
+
+        let inputs = json_to_inputs(&request.inputs, &func_def.params, &self.graph.types)?;
+        let config = InterpreterConfig {
+            trace_enabled: true,
+            random_seed: request.random_seed.unwrap_or(0),
+            clock: request.clock.unwrap_or_default(),
+            checkpoint_interval: Some(DEBUG_CHECKPOINT_INTERVAL),
+            // Sessions pause between requests, so only memory is budgeted;
+            // each command is bounded by the debugger's step limit.
+            limits: ExecutionLimits {
+                max_memory_cells: self.default_execution_limits.max_memory_cells,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut session = DebugSession::start(
+            self.program_id,
+            self.graph.clone(),
+            request.function_id,
+            inputs,
+            config,
+        );
+        session.set_breakpoints(&request.breakpoints)?;
+        session.set_watches(request.watches);
+        Ok(session)
+    }
+
+    /// Stores a suspended debug session of the active program.
+    pub fn save_debug_snapshot(
+        &self,
+        suspended: &SuspendedSession,
+    ) -> Result<DebugSnapshotView, ApiError> {
+        SnapshotStore::save(&self.conn, self.program_id, suspended)
+    }
+
+    /// Resumes a stored snapshot as a debug session on the current graph.
+    ///
+    /// Fails with a conflict listing the changed functions if any function
+    /// was added, removed or edited since the session was suspended.
+    pub fn resume_debug_snapshot(&self, snapshot_id: Uuid) -> Result<DebugSession, ApiError> {
+        let suspended = SnapshotStore::load(&self.conn, self.program_id, snapshot_id)?;
+        DebugSession::resume(self.program_id, self.graph.clone(), suspended).map_err(
+            |err| match err {
+                SnapshotError::GraphChanged { ref functions } => ApiError::ConflictWithDetails {
+                    message: err.to_string(),
+                    details: serde_json::json!({ "changed_functions": functions }),
+                },
+                SnapshotError::UnsupportedVersion { .. } => ApiError::Conflict(err.to_string()),
+            },
+        )
+    }
+
+    /// Lists the stored debug snapshots of the active program.
+    pub fn list_debug_snapshots(&self) -> Result<ListDebugSnapshotsResponse, ApiError> {
+        let snapshots = SnapshotStore::list(&self.conn, self.program_id)?;
+        Ok(ListDebugSnapshotsResponse { snapshots })
+    }
+
+    /// Deletes a stored debug snapshot of the active program.
+    pub fn delete_debug_snapshot(&self, snapshot_id: Uuid) -> Result<(), ApiError> {
+        SnapshotStore::delete(&self.conn, self.program_id, snapshot_id)
+    }
+
     // -----------------------------------------------------------------------
     // Property testing method (CNTR-05)
     // -----------------------------------------------------------------------
@@ -1949,13 +2460,20 @@ impl ProgramService {
     /// Converts JSON seed inputs to interpreter Values, builds a PropertyTestConfig,
     /// runs the harness, and converts results to the API response format.
     pub fn property_test(
-        &self,
+        &mut self,
         request: crate::schema::contracts::PropertyTestRequest,
     ) -> Result<crate::schema::contracts::PropertyTestResponse, ApiError> {
         use crate::schema::contracts::{
-            ContractViolationView, PropertyTestFailureView, PropertyTestResponse, TraceEntryView,
+            ContractViolationView, PropertyTestFailureView, PropertyTestMode, PropertyTestResponse,
+            TraceEntryView, UnsettledRunView,
+        };
+        use lmlang_check::contracts::exhaustive::{
+            verify_exhaustively, ExhaustiveConfig, ExhaustiveError, DEFAULT_MAX_CASES,
+        };
+        use lmlang_check::contracts::property::{
+            run_property_tests, GeneratorLimits, PropertyTestConfig, PropertyTestFailure,
+            UnsettledRun, DEFAULT_MAX_SHRINK_RUNS,
         };
-        use lmlang_check::contracts::property::{run_property_tests, PropertyTestConfig};
 
         let func_id = FunctionId(request.function_id);
 
@@ -1964,20 +2482,11 @@ impl ProgramService {
             ApiError::NotFound(format!("function {} not found", request.function_id))
         })?;
 
-        let param_types: Vec<Option<TypeId>> =
-            func_def.params.iter().map(|(_, t)| Some(*t)).collect();
-
-        // Convert JSON seeds to interpreter Values
-        let mut seeds = Vec::new();
-        for seed_json in &request.seeds {
-            let mut seed_values = Vec::new();
-            for (i, json_val) in seed_json.iter().enumerate() {
-                let type_hint = param_types.get(i).copied().flatten();
-                let value = json_to_value(json_val, type_hint);
-                seed_values.push(value);
-            }
-            seeds.push(seed_values);
-        }
+        let seeds = request
+            .seeds
+            .iter()
+            .map(|seed| json_to_inputs(seed, &func_def.params, &self.graph.types))
+            .collect::<Result<Vec<_>, _>>()?;
 
         // Generate random seed if not provided
         let random_seed = request.random_seed.unwrap_or_else(|| {
@@ -1988,95 +2497,152 @@ impl ProgramService {
                 .unwrap_or(42)
         });
 
-        let config = PropertyTestConfig {
-            seeds,
-            iterations: request.iterations,
-            random_seed,
+        let to_json = |values: &[Value]| {
+            values
+                .iter()
+                .filter_map(|v| serde_json::to_value(v).ok())
+                .collect()
+        };
+        let unsettled_view = |run: &UnsettledRun| UnsettledRunView {
+            inputs: to_json(&run.inputs),
+            kind: run.error.kind().to_string(),
+            message: run.error.to_string(),
         };
 
-        let result = run_property_tests(&self.graph, func_id, config)
-            .map_err(|e| ApiError::InternalError(format!("property test failed: {}", e)))?;
+        let failure_view = |f: &PropertyTestFailure| {
+            let violation_view = ContractViolationView::new(&f.violation, &self.graph.types);
 
-        // Convert to API response
-        let failures: Vec<PropertyTestFailureView> = result
-            .failures
-            .iter()
-            .map(|f| {
-                let violation = &f.violation;
-                let violation_view = ContractViolationView {
-                    kind: match violation.kind {
-                        lmlang_check::contracts::ContractKind::Precondition => {
-                            "precondition".to_string()
-                        }
-                        lmlang_check::contracts::ContractKind::Postcondition => {
-                            "postcondition".to_string()
-                        }
-                        lmlang_check::contracts::ContractKind::Invariant => "invariant".to_string(),
-                    },
-                    contract_node: violation.contract_node.0,
-                    function_id: violation.function_id.0,
-                    message: violation.message.clone(),
-                    inputs: violation
-                        .inputs
-                        .iter()
-                        .filter_map(|v| serde_json::to_value(v).ok())
-                        .collect(),
-                    actual_return: violation
-                        .actual_return
-                        .as_ref()
-                        .and_then(|v| serde_json::to_value(v).ok()),
-                    counterexample: violation
-                        .counterexample
+            let trace = if request.trace_failures {
+                Some(
+                    f.trace
                         .iter()
-                        .filter_map(|(nid, v)| serde_json::to_value(v).ok().map(|jv| (nid.0, jv)))
+                        .map(|t| TraceEntryView {
+                            node_id: t.node_id,
+                            op: t.op_description.clone(),
+                            inputs: t
+                                .inputs
+                                .iter()
+                                .map(|(port, val)| {
+                                    (*port, serde_json::to_value(val).unwrap_or_default())
+                                })
+                                .collect(),
+                            output: t.output.as_ref().and_then(|v| serde_json::to_value(v).ok()),
+                            typed_output: t
+                                .output
+                                .as_ref()
+                                .map(|v| v.to_typed_json(&self.graph.types)),
+                        })
                         .collect(),
-                };
+                )
+            } else {
+                None
+            };
 
-                let trace = if request.trace_failures {
-                    Some(
-                        f.trace
-                            .iter()
-                            .map(|t| TraceEntryView {
-                                node_id: t.node_id,
-                                op: t.op_description.clone(),
-                                inputs: t
-                                    .inputs
-                                    .iter()
-                                    .map(|(port, val)| {
-                                        (*port, serde_json::to_value(val).unwrap_or_default())
-                                    })
-                                    .collect(),
-                                output: t
-                                    .output
-                                    .as_ref()
-                                    .and_then(|v| serde_json::to_value(v).ok()),
-                            })
-                            .collect(),
-                    )
-                } else {
-                    None
-                };
+            PropertyTestFailureView {
+                inputs: to_json(&f.inputs),
+                shrunk_inputs: to_json(&f.shrunk_inputs),
+                shrink_steps: f.shrink_steps,
+                occurrences: f.occurrences,
+                case_seed: f.case_seed,
+                violation: violation_view,
+                trace,
+            }
+        };
 
-                PropertyTestFailureView {
-                    inputs: f
-                        .inputs
-                        .iter()
-                        .filter_map(|v| serde_json::to_value(v).ok())
-                        .collect(),
-                    violation: violation_view,
-                    trace,
-                }
-            })
-            .collect();
+        if request.mode == PropertyTestMode::Exhaustive {
+            let config = ExhaustiveConfig {
+                bounds: request
+                    .bounds
+                    .iter()
+                    .map(|b| b.map(|[min, max]| (min, max)))
+                    .collect(),
+                max_cases: request.max_cases.unwrap_or(DEFAULT_MAX_CASES),
+                random_seed,
+                limits: request.limits.or(self.default_execution_limits),
+                engine: request.engine,
+                bytecode_cache: self.bytecode_cache.clone(),
+            };
+            let result =
+                verify_exhaustively(&self.graph, func_id, &config).map_err(|e| match e {
+                    ExhaustiveError::Runtime(e) => {
+                        ApiError::InternalError(format!("exhaustive verification failed: {}", e))
+                    }
+                    other => ApiError::BadRequest(other.to_string()),
+                })?;
+            let count = |n: u64| u32::try_from(n).unwrap_or(u32::MAX);
+            let failed = u32::from(result.counterexample.is_some());
+            let failures = result.counterexample.iter().map(failure_view).collect();
+            let coverage = Self::record_coverage(
+                &self.graph,
+                &mut self.coverage,
+                &mut self.coverage_scope,
+                func_id,
+                &result.coverage,
+            );
+            return Ok(PropertyTestResponse {
+                mode: PropertyTestMode::Exhaustive,
+                total_run: count(result.checked + result.unsettled),
+                passed: count(result.checked) - failed,
+                failed,
+                random_seed,
+                rejected: count(result.excluded),
+                skipped: 0,
+                unsettled: count(result.unsettled),
+                unsettled_runs: result.first_unsettled.iter().map(unsettled_view).collect(),
+                proved: Some(result.proved()),
+                domain_size: Some(result.domain_size),
+                failures,
+                coverage,
+            });
+        }
 
-        let failed = failures.len() as u32;
+        let iterations = request.iterations.ok_or_else(|| {
+            ApiError::BadRequest("iterations is required in random mode".to_string())
+        })?;
+        let defaults = GeneratorLimits::default();
+        let config = PropertyTestConfig {
+            seeds,
+            iterations,
+            random_seed,
+            max_shrink_runs: request.max_shrink_runs.unwrap_or(DEFAULT_MAX_SHRINK_RUNS),
+            generator: GeneratorLimits {
+                max_depth: request.max_depth.unwrap_or(defaults.max_depth),
+                max_leaves: request.max_leaves.unwrap_or(defaults.max_leaves),
+            },
+            limits: request.limits.or(self.default_execution_limits),
+            stop_on_unsettled: false,
+            engine: request.engine,
+            bytecode_cache: self.bytecode_cache.clone(),
+        };
+
+        let result = run_property_tests(&self.graph, func_id, config)
+            .map_err(|e| ApiError::InternalError(format!("property test failed: {}", e)))?;
+
+        // Failures are deduplicated per contract, so count failing cases directly
+        let failed = result.total_run - result.passed - result.unsettled;
+        let failures = result.failures.iter().map(failure_view).collect();
+        let coverage = Self::record_coverage(
+            &self.graph,
+            &mut self.coverage,
+            &mut self.coverage_scope,
+            func_id,
+            &result.coverage,
+        );
 
         Ok(PropertyTestResponse {
+            mode: PropertyTestMode::Random,
             total_run: result.total_run,
             passed: result.passed,
             failed,
             random_seed: result.random_seed,
+            rejected: result.rejected,
+            skipped: result.skipped,
+            unsettled: result.unsettled,
+            unsettled_runs: result.unsettled_runs.iter().map(unsettled_view).collect(),
+            proved: None,
+            domain_size: None,
             failures,
+            coverage,
         })
     }
 
@@ -2105,8 +2671,14 @@ impl ProgramService {
             target_triple: request.target_triple.clone(),
             debug_symbols: request.debug_symbols,
             entry_function: request.entry_function.clone(),
+            contracts: request.contracts,
         };
 
+        let capability_errors = self.capability_errors();
+        if !capability_errors.is_empty() {
+            return Err(ApiError::ValidationFailed(capability_errors));
+        }
+
         let result = lmlang_codegen::compile(&self.graph, &options).map_err(|e| match e {
             lmlang_codegen::error::CodegenError::TypeCheckFailed(errors) => {
                 let diags: Vec<crate::schema::diagnostics::DiagnosticError> = errors
@@ -2126,6 +2698,126 @@ impl ProgramService {
         })
     }
 
+    /// Runs the cases of the program's test nodes through the interpreter.
+    pub fn run_tests(
+        &self,
+        request: &crate::schema::contracts::RunTestsRequest,
+    ) -> lmlang_check::test_runner::TestSuiteReport {
+        use lmlang_check::test_runner::{run_test_suites, TestRunConfig};
+
+        let config = TestRunConfig {
+            test_ids: request.test_ids.clone(),
+            random_seed: request.random_seed,
+            limits: request.limits.or(self.default_execution_limits),
+            engine: request.engine,
+            bytecode_cache: self.bytecode_cache.clone(),
+        };
+        run_test_suites(&self.graph, &config)
+    }
+
+    /// Mutation-tests one function against its contracts and test cases.
+    ///
+    /// Unknown functions map to 404 and interpreter failures on the original
+    /// function to 500. Surviving mutants are reported, not errors.
+    pub fn mutation_test(
+        &self,
+        request: crate::schema::contracts::MutationTestRequest,
+    ) -> Result<lmlang_check::contracts::mutation::MutationReport, ApiError> {
+        use lmlang_check::contracts::mutation::{
+            run_mutation_tests, MutationConfig, MutationError, DEFAULT_MAX_MUTANTS,
+            DEFAULT_MUTATION_ITERATIONS,
+        };
+
+        let func_id = FunctionId(request.function_id);
+        let func_def = self.graph.get_function(func_id).ok_or_else(|| {
+            ApiError::NotFound(format!("function {} not found", request.function_id))
+        })?;
+
+        let seeds = request
+            .seeds
+            .iter()
+            .map(|seed| json_to_inputs(seed, &func_def.params, &self.graph.types))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let random_seed = request.random_seed.unwrap_or_else(|| {
+            use std::time::SystemTime;
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(42)
+        });
+
+        let config = MutationConfig {
+            seeds,
+            iterations: request.iterations.unwrap_or(DEFAULT_MUTATION_ITERATIONS),
+            random_seed,
+            test_ids: request.test_ids,
+            max_mutants: request.max_mutants.unwrap_or(DEFAULT_MAX_MUTANTS),
+            limits: request.limits.or(self.default_execution_limits),
+            engine: request.engine,
+            bytecode_cache: self.bytecode_cache.clone(),
+        };
+
+        run_mutation_tests(&self.graph, func_id, &config).map_err(|e| match e {
+            MutationError::FunctionNotFound(_) => ApiError::NotFound(e.to_string()),
+            MutationError::Runtime(e) => {
+                ApiError::InternalError(format!("mutation test failed: {}", e))
+            }
+        })
+    }
+
+    /// Runs a differential test of one function against its compiled code.
+    ///
+    /// Unsupported functions (environment-dependent or closures) map to 400,
+    /// type check failures to 422, and harness failures to 500. Mismatches
+    /// are reported in the returned report, not as errors.
+    pub fn differential_test(
+        &self,
+        request: &crate::schema::compile::DifferentialTestRequest,
+    ) -> Result<lmlang_codegen::differential::DifferentialReport, ApiError> {
+        use lmlang_codegen::differential::{
+            run_differential_tests, DifferentialConfig, DifferentialError,
+        };
+
+        let opt_level = parse_opt_level(&request.opt_level)?;
+        let capability_errors = self.capability_errors();
+        if !capability_errors.is_empty() {
+            return Err(ApiError::ValidationFailed(capability_errors));
+        }
+
+        let defaults = DifferentialConfig::default();
+        let config = DifferentialConfig {
+            iterations: request.iterations.unwrap_or(defaults.iterations),
+            random_seed: request.random_seed.unwrap_or_else(|| {
+                use std::time::SystemTime;
+                SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map(|d| d.as_nanos() as u64)
+                    .unwrap_or(42)
+            }),
+            limits: request.limits.or(self.default_execution_limits),
+            bytecode_cache: self.bytecode_cache.clone(),
+            opt_level,
+            ..defaults
+        };
+
+        run_differential_tests(&self.graph, request.function_id, &config).map_err(|e| match e {
+            DifferentialError::FunctionNotFound(id) => {
+                ApiError::NotFound(format!("function {} not found", id.0))
+            }
+            DifferentialError::Unsupported { .. } => ApiError::BadRequest(e.to_string()),
+            DifferentialError::Codegen(lmlang_codegen::error::CodegenError::TypeCheckFailed(
+                errors,
+            )) => ApiError::ValidationFailed(
+                errors
+                    .into_iter()
+                    .map(crate::schema::diagnostics::DiagnosticError::from)
+                    .collect(),
+            ),
+            other => ApiError::InternalError(other.to_string()),
+        })
+    }
+
     // -----------------------------------------------------------------------
     // Dirty status query (STORE-05)
     // -----------------------------------------------------------------------
@@ -2138,7 +2830,7 @@ impl ProgramService {
     pub fn dirty_status(&self) -> Result<crate::schema::compile::DirtyStatusResponse, ApiError> {
         use crate::schema::compile::{CachedFunctionView, DirtyFunctionView, DirtyStatusResponse};
         use lmlang_codegen::incremental::build_call_graph;
-        use lmlang_storage::hash::hash_all_functions_for_compilation;
+        use lmlang_codegen::incremental::compilation_hashes;
 
         let functions = self.graph.functions();
 
@@ -2163,11 +2855,7 @@ impl ProgramService {
             }
             Some(state) => {
                 // Compute current hashes and call graph
-                let blake_hashes = hash_all_functions_for_compilation(&self.graph);
-                let current_hashes: std::collections::HashMap<FunctionId, [u8; 32]> = blake_hashes
-                    .iter()
-                    .map(|(&fid, h)| (fid, *h.as_bytes()))
-                    .collect();
+                let current_hashes = compilation_hashes(&self.graph, state.contracts());
 
                 let call_graph = build_call_graph(&self.graph);
                 let plan = state.compute_dirty(&current_hashes, &call_graph);
@@ -2244,8 +2932,14 @@ impl ProgramService {
             target_triple: request.target_triple.clone(),
             debug_symbols: request.debug_symbols,
             entry_function: request.entry_function.clone(),
+            contracts: request.contracts,
         };
 
+        let capability_errors = self.capability_errors();
+        if !capability_errors.is_empty() {
+            return Err(ApiError::ValidationFailed(capability_errors));
+        }
+
         // Initialize or reuse incremental state
         let state = self
             .incremental_state
@@ -2285,6 +2979,7 @@ impl ProgramService {
             Some((inverse_cmd, entry)) => {
                 Self::apply_edit_command(&mut self.graph, &inverse_cmd)?;
                 self.store.save_program(self.program_id, &self.graph)?;
+                self.reset_run_statistics();
                 Ok(UndoResponse {
                     success: true,
                     restored_edit: Some(entry),
@@ -2303,6 +2998,7 @@ impl ProgramService {
             Some((cmd, entry)) => {
                 Self::apply_edit_command(&mut self.graph, &cmd)?;
                 self.store.save_program(self.program_id, &self.graph)?;
+                self.reset_run_statistics();
                 Ok(RedoResponse {
                     success: true,
                     reapplied_edit: Some(entry),
@@ -2333,6 +3029,7 @@ impl ProgramService {
         let graph = CheckpointManager::restore(&self.conn, self.program_id, name)?;
         self.graph = graph;
         self.store.save_program(self.program_id, &self.graph)?;
+        self.reset_run_statistics();
         Ok(RestoreCheckpointResponse {
             success: true,
             name: name.to_string(),
@@ -2495,6 +3192,8 @@ fn op_kind_name(op: &ComputeNodeOp) -> String {
             ComputeOp::Shift { .. } => "Shift".to_string(),
             ComputeOp::IfElse => "IfElse".to_string(),
             ComputeOp::Loop => "Loop".to_string(),
+            ComputeOp::ForRange { .. } => "ForRange".to_string(),
+            ComputeOp::ForEach { .. } => "ForEach".to_string(),
             ComputeOp::Match => "Match".to_string(),
             ComputeOp::Branch => "Branch".to_string(),
             ComputeOp::Jump => "Jump".to_string(),
@@ -2513,11 +3212,16 @@ fn op_kind_name(op: &ComputeNodeOp) -> String {
             ComputeOp::FileRead => "FileRead".to_string(),
             ComputeOp::FileWrite => "FileWrite".to_string(),
             ComputeOp::FileClose => "FileClose".to_string(),
+            ComputeOp::Now => "Now".to_string(),
+            ComputeOp::Random => "Random".to_string(),
             ComputeOp::MakeClosure { .. } => "MakeClosure".to_string(),
             ComputeOp::CaptureAccess { .. } => "CaptureAccess".to_string(),
             ComputeOp::Precondition { .. } => "Precondition".to_string(),
             ComputeOp::Postcondition { .. } => "Postcondition".to_string(),
             ComputeOp::Invariant { .. } => "Invariant".to_string(),
+            ComputeOp::Old { .. } => "Old".to_string(),
+            ComputeOp::ForAll { .. } => "ForAll".to_string(),
+            ComputeOp::Exists { .. } => "Exists".to_string(),
         },
         ComputeNodeOp::Structured(inner) => format!("{:?}", inner),
     }
@@ -2614,46 +3318,31 @@ fn default_prompt_chips() -> Vec<SuggestedPromptChipView> {
     ]
 }
 
-/// Converts a serde_json::Value to an interpreter Value.
+/// Marshals JSON `inputs` into values of the `params` types.
 ///
-/// Uses the function parameter type hint to disambiguate numeric types.
-fn json_to_value(json: &serde_json::Value, type_hint: Option<TypeId>) -> Value {
-    match json {
-        serde_json::Value::Bool(b) => Value::Bool(*b),
-        serde_json::Value::Number(n) => {
-            match type_hint {
-                Some(TypeId::I8) => Value::I8(n.as_i64().unwrap_or(0) as i8),
-                Some(TypeId::I16) => Value::I16(n.as_i64().unwrap_or(0) as i16),
-                Some(TypeId::I32) => Value::I32(n.as_i64().unwrap_or(0) as i32),
-                Some(TypeId::I64) => Value::I64(n.as_i64().unwrap_or(0)),
-                Some(TypeId::F32) => Value::F32(n.as_f64().unwrap_or(0.0) as f32),
-                Some(TypeId::F64) => Value::F64(n.as_f64().unwrap_or(0.0)),
-                _ => {
-                    // Best effort: if it has a decimal point use F64, else I32
-                    if n.is_f64() && n.as_i64().is_none() {
-                        Value::F64(n.as_f64().unwrap_or(0.0))
-                    } else {
-                        Value::I32(n.as_i64().unwrap_or(0) as i32)
-                    }
-                }
-            }
-        }
-        serde_json::Value::Null => Value::Unit,
-        serde_json::Value::String(s) => {
-            // Try to parse as a number if type hint suggests numeric
-            match type_hint {
-                Some(TypeId::I32) => Value::I32(s.parse().unwrap_or(0)),
-                Some(TypeId::I64) => Value::I64(s.parse().unwrap_or(0)),
-                Some(TypeId::F64) => Value::F64(s.parse().unwrap_or(0.0)),
-                _ => Value::Unit, // Strings don't have a direct Value equivalent
-            }
-        }
-        serde_json::Value::Array(arr) => {
-            let values: Vec<Value> = arr.iter().map(|v| json_to_value(v, None)).collect();
-            Value::Array(values)
-        }
-        serde_json::Value::Object(_) => Value::Unit, // Objects not directly supported
+/// Inputs use the typed schema of [`Value::from_typed_json`]; a missing,
+/// extra or ill-typed input is rejected naming the parameter and the path
+/// to the offending value.
+fn json_to_inputs(
+    inputs: &[serde_json::Value],
+    params: &[(String, TypeId)],
+    registry: &TypeRegistry,
+) -> Result<Vec<Value>, ApiError> {
+    if inputs.len() != params.len() {
+        return Err(ApiError::BadRequest(format!(
+            "expected {} inputs, got {}",
+            params.len(),
+            inputs.len()
+        )));
     }
+    inputs
+        .iter()
+        .zip(params)
+        .map(|(json, (name, ty))| {
+            Value::from_typed_json(json, *ty, registry)
+                .map_err(|e| ApiError::BadRequest(format!("input `{}`: {}", name, e)))
+        })
+        .collect()
 }
 
 /// Parse an optimization level string to `lmlang_codegen::OptLevel`.
@@ -2697,6 +3386,27 @@ fn describe_mutation(mutation: &Mutation) -> String {
         Mutation::AddModule { name, .. } => {
             format!("add module '{}'", name)
         }
+        Mutation::SetFunctionEffects {
+            function_id,
+            effects,
+        } => match effects {
+            Some(effects) => format!(
+                "declare effects [{}] on function {}",
+                effects
+                    .iter()
+                    .map(|c| c.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                function_id.0
+            ),
+            None => format!("clear declared effects on function {}", function_id.0),
+        },
+        Mutation::AddTest { test_id, cases, .. } => {
+            format!("add test '{}' with {} case(s)", test_id, cases.len())
+        }
+        Mutation::RemoveTest { semantic_node_id } => {
+            format!("remove test node {}", semantic_node_id)
+        }
     }
 }
 
@@ -0,0 +1,135 @@
+//! Persistent storage for suspended debug sessions.
+//!
+//! [`SnapshotStore`] keeps [`SuspendedSession`]s in the
+//! `interpreter_snapshots` table, keyed by program and snapshot ID, so a run
+//! survives the session TTL and server restarts.
+
+use rusqlite::{Connection, OptionalExtension};
+use uuid::Uuid;
+
+use lmlang_core::id::FunctionId;
+use lmlang_storage::ProgramId;
+
+use crate::debug_sessions::SuspendedSession;
+use crate::error::ApiError;
+use crate::schema::debug::DebugSnapshotView;
+use crate::undo::chrono_now;
+
+/// SQL-backed store of suspended debug sessions.
+pub struct SnapshotStore;
+
+impl SnapshotStore {
+    /// Stores `suspended` under a new snapshot ID.
+    pub fn save(
+        conn: &Connection,
+        program_id: ProgramId,
+        suspended: &SuspendedSession,
+    ) -> Result<DebugSnapshotView, ApiError> {
+        let snapshot_id = Uuid::new_v4();
+        let timestamp = chrono_now();
+        let steps = suspended.snapshot.steps();
+        let snapshot_json = serde_json::to_string(suspended)
+            .map_err(|e| ApiError::InternalError(format!("failed to serialize snapshot: {}", e)))?;
+
+        conn.execute(
+            "INSERT INTO interpreter_snapshots (program_id, snapshot_id, timestamp, function_id, steps, snapshot_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                program_id.0,
+                snapshot_id.to_string(),
+                timestamp,
+                suspended.function_id.0,
+                steps as i64,
+                snapshot_json
+            ],
+        )
+        .map_err(|e| ApiError::InternalError(format!("failed to store snapshot: {}", e)))?;
+
+        Ok(DebugSnapshotView {
+            snapshot_id,
+            program_id: program_id.0,
+            function_id: suspended.function_id,
+            steps,
+            timestamp,
+        })
+    }
+
+    /// Loads a stored snapshot.
+    pub fn load(
+        conn: &Connection,
+        program_id: ProgramId,
+        snapshot_id: Uuid,
+    ) -> Result<SuspendedSession, ApiError> {
+        let snapshot_json: String = conn
+            .query_row(
+                "SELECT snapshot_json FROM interpreter_snapshots WHERE program_id = ?1 AND snapshot_id = ?2",
+                rusqlite::params![program_id.0, snapshot_id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| ApiError::InternalError(format!("failed to query snapshot: {}", e)))?
+            .ok_or_else(|| ApiError::NotFound(format!("snapshot {} not found", snapshot_id)))?;
+
+        serde_json::from_str(&snapshot_json)
+            .map_err(|e| ApiError::InternalError(format!("failed to deserialize snapshot: {}", e)))
+    }
+
+    /// Lists the stored snapshots of a program, most recent first.
+    pub fn list(
+        conn: &Connection,
+        program_id: ProgramId,
+    ) -> Result<Vec<DebugSnapshotView>, ApiError> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT snapshot_id, function_id, steps, timestamp FROM interpreter_snapshots WHERE program_id = ?1 ORDER BY id DESC",
+            )
+            .map_err(|e| ApiError::InternalError(format!("failed to prepare list query: {}", e)))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params![program_id.0], |row| {
+                let snapshot_id: String = row.get(0)?;
+                let function_id: u32 = row.get(1)?;
+                let steps: i64 = row.get(2)?;
+                let timestamp: String = row.get(3)?;
+                Ok((snapshot_id, function_id, steps, timestamp))
+            })
+            .map_err(|e| ApiError::InternalError(format!("failed to query snapshots: {}", e)))?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let (snapshot_id, function_id, steps, timestamp) =
+                row.map_err(|e| ApiError::InternalError(format!("failed to read row: {}", e)))?;
+            entries.push(DebugSnapshotView {
+                snapshot_id: snapshot_id.parse().map_err(|e| {
+                    ApiError::InternalError(format!("invalid snapshot id '{}': {}", snapshot_id, e))
+                })?,
+                program_id: program_id.0,
+                function_id: FunctionId(function_id),
+                steps: steps as u64,
+                timestamp,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Deletes a stored snapshot.
+    pub fn delete(
+        conn: &Connection,
+        program_id: ProgramId,
+        snapshot_id: Uuid,
+    ) -> Result<(), ApiError> {
+        let rows = conn
+            .execute(
+                "DELETE FROM interpreter_snapshots WHERE program_id = ?1 AND snapshot_id = ?2",
+                rusqlite::params![program_id.0, snapshot_id.to_string()],
+            )
+            .map_err(|e| ApiError::InternalError(format!("failed to delete snapshot: {}", e)))?;
+
+        if rows == 0 {
+            return Err(ApiError::NotFound(format!(
+                "snapshot {} not found",
+                snapshot_id
+            )));
+        }
+        Ok(())
+    }
+}
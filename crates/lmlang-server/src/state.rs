@@ -17,6 +17,7 @@ use std::time::Duration;
 use crate::agent_config_store::AgentConfigStore;
 use crate::autonomous_runner::AutonomousRunner;
 use crate::concurrency::{AgentRegistry, LockManager};
+use crate::debug_sessions::DebugSessionManager;
 use crate::error::ApiError;
 use crate::project_agent::ProjectAgentManager;
 use crate::service::ProgramService;
@@ -43,6 +44,8 @@ pub struct AppState {
     pub project_agent_manager: Arc<ProjectAgentManager>,
     /// Background autonomous run-loop manager.
     pub autonomous_runner: Arc<AutonomousRunner>,
+    /// Interactive debug sessions, expired by TTL.
+    pub debug_sessions: Arc<DebugSessionManager>,
 }
 
 impl AppState {
@@ -55,9 +58,11 @@ impl AppState {
         let agent_config_store = Arc::new(AgentConfigStore::new(db_path)?);
         let project_agent_manager = Arc::new(ProjectAgentManager::new());
         let autonomous_runner = Arc::new(AutonomousRunner::new());
+        let debug_sessions = Arc::new(DebugSessionManager::with_default_ttl());
 
-        // Start the lock expiry sweep task (every 60 seconds)
+        // Start the lock and debug session expiry sweep tasks (every 60 seconds)
         lock_manager.start_expiry_sweep(Duration::from_secs(60));
+        debug_sessions.start_expiry_sweep(Duration::from_secs(60));
 
         for persisted in agent_config_store.list()? {
             agent_registry.restore(persisted.id, persisted.name, persisted.llm);
@@ -70,6 +75,7 @@ impl AppState {
             agent_config_store,
             project_agent_manager,
             autonomous_runner,
+            debug_sessions,
         })
     }
 
@@ -81,9 +87,11 @@ impl AppState {
         let agent_config_store = Arc::new(AgentConfigStore::in_memory()?);
         let project_agent_manager = Arc::new(ProjectAgentManager::new());
         let autonomous_runner = Arc::new(AutonomousRunner::new());
+        let debug_sessions = Arc::new(DebugSessionManager::with_default_ttl());
 
-        // Start the lock expiry sweep task (every 60 seconds)
+        // Start the lock and debug session expiry sweep tasks (every 60 seconds)
         lock_manager.start_expiry_sweep(Duration::from_secs(60));
+        debug_sessions.start_expiry_sweep(Duration::from_secs(60));
 
         Ok(AppState {
             service: Arc::new(tokio::sync::Mutex::new(service)),
@@ -92,6 +100,7 @@ impl AppState {
             agent_config_store,
             project_agent_manager,
             autonomous_runner,
+            debug_sessions,
         })
     }
 }
@@ -8,10 +8,11 @@ use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use lmlang_core::capability::EffectSet;
 use lmlang_core::edge::FlowEdge;
 use lmlang_core::graph::ProgramGraph;
 use lmlang_core::id::{EdgeId, FunctionId, ModuleId, NodeId};
-use lmlang_core::node::ComputeNode;
+use lmlang_core::node::{ComputeNode, TestNode};
 use lmlang_core::ops::ComputeNodeOp;
 use lmlang_core::type_id::TypeId;
 use lmlang_core::types::Visibility;
@@ -85,6 +86,24 @@ pub enum EditCommand {
         parent: ModuleId,
         visibility: Visibility,
     },
+    /// A function's declared effects were changed.
+    SetFunctionEffects {
+        func_id: FunctionId,
+        old_effects: Option<EffectSet>,
+        new_effects: Option<EffectSet>,
+    },
+    /// A test node was added.
+    AddTest {
+        semantic_node_id: u32,
+        module: ModuleId,
+        test: TestNode,
+    },
+    /// A test node was removed (captures the full node for undo re-insertion).
+    RemoveTest {
+        semantic_node_id: u32,
+        module: ModuleId,
+        removed_test: TestNode,
+    },
     /// A batch of commands applied atomically (all-or-nothing).
     Batch {
         commands: Vec<EditCommand>,
@@ -211,6 +230,33 @@ impl EditCommand {
                 parent: *parent,
                 visibility: *visibility,
             },
+            EditCommand::SetFunctionEffects {
+                func_id,
+                old_effects,
+                new_effects,
+            } => EditCommand::SetFunctionEffects {
+                func_id: *func_id,
+                old_effects: new_effects.clone(),
+                new_effects: old_effects.clone(),
+            },
+            EditCommand::AddTest {
+                semantic_node_id,
+                module,
+                test,
+            } => EditCommand::RemoveTest {
+                semantic_node_id: *semantic_node_id,
+                module: *module,
+                removed_test: test.clone(),
+            },
+            EditCommand::RemoveTest {
+                semantic_node_id,
+                module,
+                removed_test,
+            } => EditCommand::AddTest {
+                semantic_node_id: *semantic_node_id,
+                module: *module,
+                test: removed_test.clone(),
+            },
             EditCommand::Batch {
                 commands,
                 description,
@@ -514,7 +560,7 @@ impl CheckpointManager {
 use rusqlite::OptionalExtension;
 
 /// Returns the current UTC timestamp in ISO 8601 format.
-fn chrono_now() -> String {
+pub(crate) fn chrono_now() -> String {
     // Use a simple approach without chrono dependency: format current time.
     // We'll use std::time::SystemTime for a basic ISO 8601 timestamp.
     use std::time::SystemTime;
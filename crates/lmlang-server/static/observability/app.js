@@ -295,7 +295,9 @@
       return "graph-edge edge-data";
     }
     if (edge.edge_kind === "control") {
-      return "graph-edge edge-control";
+      return edge.coverage_hits === 0
+        ? "graph-edge edge-control uncovered"
+        : "graph-edge edge-control";
     }
     return "graph-edge edge-semantic";
   }
@@ -334,7 +336,9 @@
       }
 
       const group = createSvg("g", {
-        class: `graph-node layer-${node.layer}`,
+        class: `graph-node layer-${node.layer}${node.coverage_hits === 0 ? " uncovered" : ""}${
+          typeof node.hot_rank === "number" ? " hot" : ""
+        }`,
         transform: `translate(${point.x} ${point.y})`,
         "data-node-id": node.id,
       });
@@ -397,6 +401,12 @@
     if (node.summary) {
       parts.push(`<div class="detail-row"><strong>Summary:</strong> ${sanitize(node.summary)}</div>`);
     }
+    if (typeof node.coverage_hits === "number") {
+      parts.push(`<div class="detail-row"><strong>Coverage:</strong> ${node.coverage_hits} hit(s)</div>`);
+    }
+    if (typeof node.hot_rank === "number") {
+      parts.push(`<div class="detail-row"><strong>Hot rank:</strong> #${node.hot_rank}</div>`);
+    }
     return parts.join("");
   }
 
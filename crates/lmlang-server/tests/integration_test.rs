@@ -140,12 +140,22 @@ async fn post_json(
     app: &Router,
     path: &str,
     body: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    send_json(app, "POST", path, body).await
+}
+
+/// Sends a request with the given method and JSON body and returns (status, json).
+async fn send_json(
+    app: &Router,
+    method: &str,
+    path: &str,
+    body: serde_json::Value,
 ) -> (StatusCode, serde_json::Value) {
     let response = app
         .clone()
         .oneshot(
             Request::builder()
-                .method("POST")
+                .method(method)
                 .uri(path)
                 .header("content-type", "application/json")
                 .body(Body::from(serde_json::to_vec(&body).unwrap()))
@@ -786,6 +796,267 @@ async fn tool03_type_mismatch_detected() {
     }
 }
 
+/// Full verification reports operations range analysis cannot prove safe.
+#[tokio::test]
+async fn tool03_full_verify_warns_possible_division_by_zero() {
+    let app = test_app();
+    let pid = setup_program(&app).await;
+
+    let func_id = add_typed_function(&app, pid, "div", json!([["a", 3], ["b", 3]]), 3).await;
+    let param_a = insert_param(&app, pid, func_id, 0).await;
+    let param_b = insert_param(&app, pid, func_id, 1).await;
+
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {
+                "type": "InsertNode",
+                "op": {"Core": {"BinaryArith": {"op": "Div"}}},
+                "owner": func_id
+            },
+            {
+                "type": "AddEdge",
+                "from": param_a, "to": param_a + 2,
+                "source_port": 0, "target_port": 0,
+                "value_type": 3
+            },
+            {
+                "type": "AddEdge",
+                "from": param_b, "to": param_a + 2,
+                "source_port": 0, "target_port": 1,
+                "value_type": 3
+            }
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{}", body);
+
+    let (status, verify_body) = post_json(
+        &app,
+        &format!("/programs/{}/verify", pid),
+        json!({ "scope": "full" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    // Warnings do not make the graph invalid
+    assert!(verify_body["valid"].as_bool().unwrap(), "{}", verify_body);
+
+    let warnings = verify_body["warnings"].as_array().unwrap();
+    let codes: Vec<&str> = warnings
+        .iter()
+        .map(|w| w["code"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        codes,
+        vec!["POSSIBLE_DIVISION_BY_ZERO", "POSSIBLE_OVERFLOW"],
+        "{}",
+        verify_body
+    );
+    assert_eq!(warnings[0]["node_id"].as_u64().unwrap(), param_a as u64 + 2);
+    assert_eq!(warnings[0]["function_id"].as_u64().unwrap(), func_id as u64);
+}
+
+/// Full verification warns about recursion with no argument moving toward a guarded bound.
+#[tokio::test]
+async fn tool03_full_verify_warns_possible_unbounded_recursion() {
+    let app = test_app();
+    let pid = setup_program(&app).await;
+
+    // f(n) = f(n - 1), with no base case
+    let func_id = add_typed_function(&app, pid, "f", json!([["n", 3]]), 3).await;
+    let param_n = insert_param(&app, pid, func_id, 0).await;
+    let one = insert_const(&app, pid, func_id, json!({"I32": 1})).await;
+
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {
+                "type": "InsertNode",
+                "op": {"Core": {"BinaryArith": {"op": "Sub"}}},
+                "owner": func_id
+            },
+            {
+                "type": "InsertNode",
+                "op": {"Core": {"Call": {"target": func_id}}},
+                "owner": func_id
+            },
+            {
+                "type": "InsertNode",
+                "op": {"Core": "Return"},
+                "owner": func_id
+            },
+            {
+                "type": "AddEdge",
+                "from": param_n, "to": one + 1,
+                "source_port": 0, "target_port": 0,
+                "value_type": 3
+            },
+            {
+                "type": "AddEdge",
+                "from": one, "to": one + 1,
+                "source_port": 0, "target_port": 1,
+                "value_type": 3
+            },
+            {
+                "type": "AddEdge",
+                "from": one + 1, "to": one + 2,
+                "source_port": 0, "target_port": 0,
+                "value_type": 3
+            },
+            {
+                "type": "AddEdge",
+                "from": one + 2, "to": one + 3,
+                "source_port": 0, "target_port": 0,
+                "value_type": 3
+            }
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{}", body);
+
+    let (status, verify_body) = post_json(
+        &app,
+        &format!("/programs/{}/verify", pid),
+        json!({ "scope": "full" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(verify_body["valid"].as_bool().unwrap(), "{}", verify_body);
+
+    let warnings = verify_body["warnings"].as_array().unwrap();
+    let recursion = warnings
+        .iter()
+        .find(|w| w["code"] == "POSSIBLE_UNBOUNDED_RECURSION")
+        .unwrap_or_else(|| panic!("no recursion warning: {}", verify_body));
+    assert_eq!(recursion["function_id"].as_u64().unwrap(), func_id as u64);
+    assert_eq!(
+        recursion["related_nodes"],
+        json!([one + 2]),
+        "{}",
+        verify_body
+    );
+}
+
+/// Full verification reports borrow violations as typed errors.
+#[tokio::test]
+async fn tool03_full_verify_reports_borrow_errors() {
+    use lmlang_core::graph::ProgramGraph;
+    use lmlang_core::ops::ComputeOp;
+    use lmlang_core::type_id::TypeId;
+    use lmlang_core::types::{ConstValue, LmType, Visibility};
+    use lmlang_storage::{GraphStore, ProgramId, SqliteStore};
+
+    let db_path = temp_db_path("lmlang_borrow_verify");
+    let app = test_app_with_db(&db_path);
+    let pid = setup_program(&app).await;
+
+    // Mutations cannot register pointer types, so the graph is stored directly:
+    // set(p: *i32) -> i32 { *p = 1; 1 }
+    // swap(a: *mut i32, b: *i32) -> i32, declared without a body
+    // main() -> i32 { let cell = alloc; swap(cell, cell) }
+    let mut graph = ProgramGraph::new("main");
+    let root = graph.modules.root_id();
+    let read_only = graph.types.register(LmType::Pointer {
+        pointee: TypeId::I32,
+        mutable: false,
+    });
+    let mutable = graph.types.register(LmType::Pointer {
+        pointee: TypeId::I32,
+        mutable: true,
+    });
+
+    let set = graph
+        .add_function(
+            "set".into(),
+            root,
+            vec![("p".into(), read_only)],
+            TypeId::I32,
+            Visibility::Public,
+        )
+        .unwrap();
+    let p = graph
+        .add_core_op(ComputeOp::Parameter { index: 0 }, set)
+        .unwrap();
+    let one = graph
+        .add_core_op(
+            ComputeOp::Const {
+                value: ConstValue::I32(1),
+            },
+            set,
+        )
+        .unwrap();
+    let store = graph.add_core_op(ComputeOp::Store, set).unwrap();
+    let set_ret = graph.add_core_op(ComputeOp::Return, set).unwrap();
+    graph.add_data_edge(p, store, 0, 0, read_only).unwrap();
+    graph.add_data_edge(one, store, 0, 1, TypeId::I32).unwrap();
+    graph
+        .add_data_edge(one, set_ret, 0, 0, TypeId::I32)
+        .unwrap();
+    graph.add_control_edge(store, set_ret, None).unwrap();
+
+    let swap = graph
+        .add_function(
+            "swap".into(),
+            root,
+            vec![("a".into(), mutable), ("b".into(), read_only)],
+            TypeId::I32,
+            Visibility::Public,
+        )
+        .unwrap();
+    let main = graph
+        .add_function("main".into(), root, vec![], TypeId::I32, Visibility::Public)
+        .unwrap();
+    let cell = graph.add_core_op(ComputeOp::Alloc, main).unwrap();
+    let call = graph
+        .add_core_op(ComputeOp::Call { target: swap }, main)
+        .unwrap();
+    let main_ret = graph.add_core_op(ComputeOp::Return, main).unwrap();
+    graph.add_data_edge(cell, call, 0, 0, mutable).unwrap();
+    graph.add_data_edge(cell, call, 0, 1, read_only).unwrap();
+    graph
+        .add_data_edge(call, main_ret, 0, 0, TypeId::I32)
+        .unwrap();
+
+    let mut store_db = SqliteStore::new(&db_path).unwrap();
+    store_db.save_program(ProgramId(pid), &graph).unwrap();
+    drop(store_db);
+    let (status, _) = post_json(&app, &format!("/programs/{}/load", pid), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = post_json(
+        &app,
+        &format!("/programs/{}/verify", pid),
+        json!({ "scope": "full" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!body["valid"].as_bool().unwrap(), "{}", body);
+
+    let errors = body["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 2, "{}", body);
+    let error = |code: &str| {
+        errors
+            .iter()
+            .find(|e| e["code"] == code)
+            .unwrap_or_else(|| panic!("no {} error: {}", code, body))
+    };
+
+    let aliased = error("ALIASED_MUTABLE_BORROW");
+    assert_eq!(aliased["details"]["function_id"], json!(main.0));
+    assert_eq!(aliased["details"]["source_node"], json!(cell.0));
+    assert_eq!(aliased["details"]["target_node"], json!(call.0));
+    assert_eq!(aliased["details"]["port"], json!(0));
+
+    let immutable = error("STORE_THROUGH_IMMUTABLE");
+    assert_eq!(immutable["details"]["function_id"], json!(set.0));
+    assert_eq!(immutable["details"]["source_node"], json!(p.0));
+    assert_eq!(immutable["details"]["target_node"], json!(store.0));
+
+    let _ = std::fs::remove_file(&db_path);
+}
+
 // ===========================================================================
 // TOOL-04: simulate function execution
 // ===========================================================================
@@ -875,6 +1146,7 @@ async fn tool04_simulate_add_function() {
     // Check result = 8 (Value::I32(8) serializes as {"I32": 8})
     let result = &body["result"];
     assert_eq!(result["I32"].as_i64().unwrap(), 8, "3 + 5 should equal 8");
+    assert_eq!(body["typed_result"], json!(8));
 
     // Check trace is present when trace_enabled=true
     assert!(
@@ -883,6 +1155,597 @@ async fn tool04_simulate_add_function() {
     );
     let trace = body["trace"].as_array().unwrap();
     assert!(!trace.is_empty(), "trace should have entries");
+    let last = trace.last().unwrap();
+    assert_eq!(last["output"], json!({"I32": 8}));
+    assert_eq!(last["typed_output"], json!(8));
+
+    // A step budget halts the run and reports the values computed so far
+    let (status, body) = post_json(
+        &app,
+        &format!("/programs/{}/simulate", pid),
+        json!({
+            "function_id": func_id,
+            "inputs": [3, 5],
+            "limits": {"max_steps": 2}
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!body["success"].as_bool().unwrap(), "{:?}", body);
+    let message = body["error"]["message"].as_str().unwrap();
+    assert!(message.contains("step limit (2)"), "{}", message);
+    assert_eq!(
+        body["partial_results"],
+        json!([[param_a, {"I32": 3}], [param_b, {"I32": 5}]])
+    );
+
+    // Inputs are validated against the parameter types
+    for (inputs, expected) in [
+        (
+            json!([3, 2147483648i64]),
+            "input `b`: $: 2147483648 is out of range for i32 (-2147483648 to 2147483647)",
+        ),
+        (json!([3, 1.5]), "input `b`: $: expected i32, found 1.5"),
+        (
+            json!(["3", 5]),
+            "input `a`: $: expected i32, found string \"3\"",
+        ),
+        (json!([3]), "expected 2 inputs, got 1"),
+    ] {
+        let (status, body) = post_json(
+            &app,
+            &format!("/programs/{}/simulate", pid),
+            json!({"function_id": func_id, "inputs": inputs}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{:?}", body);
+        assert_eq!(body["error"]["message"], expected);
+    }
+}
+
+/// Simulating one arm of a branch reports the other arm uncovered, and the
+/// observability graph overlays the accumulated hit counts.
+#[tokio::test]
+async fn tool04_simulate_reports_branch_coverage() {
+    let app = test_app();
+    let pid = setup_program(&app).await;
+
+    // pick(flag: bool) -> i32 { if flag { 1 } else { 2 } }
+    let func_id = add_typed_function(&app, pid, "pick", json!([["flag", 0]]), 3).await;
+    let flag = insert_param(&app, pid, func_id, 0).await;
+    let branch = flag + 1;
+    let (then_const, then_ret) = (flag + 2, flag + 3);
+    let (else_const, else_ret) = (flag + 4, flag + 5);
+
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {"type": "InsertNode", "op": {"Core": "Branch"}, "owner": func_id},
+            {"type": "InsertNode", "op": {"Core": {"Const": {"value": {"I32": 1}}}}, "owner": func_id},
+            {"type": "InsertNode", "op": {"Core": "Return"}, "owner": func_id},
+            {"type": "InsertNode", "op": {"Core": {"Const": {"value": {"I32": 2}}}}, "owner": func_id},
+            {"type": "InsertNode", "op": {"Core": "Return"}, "owner": func_id},
+            {"type": "AddEdge", "from": flag, "to": branch, "source_port": 0, "target_port": 0, "value_type": 0},
+            {"type": "AddControlEdge", "from": branch, "to": then_const, "branch_index": 0},
+            {"type": "AddControlEdge", "from": branch, "to": else_const, "branch_index": 1},
+            {"type": "AddEdge", "from": then_const, "to": then_ret, "source_port": 0, "target_port": 0, "value_type": 3},
+            {"type": "AddEdge", "from": else_const, "to": else_ret, "source_port": 0, "target_port": 0, "value_type": 3}
+        ]),
+    )
+    .await;
+    assert!(
+        body["committed"].as_bool().unwrap(),
+        "should commit: {:?}",
+        body
+    );
+
+    let (status, body) = post_json(
+        &app,
+        &format!("/programs/{}/simulate", pid),
+        json!({"function_id": func_id, "inputs": [true]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["success"].as_bool().unwrap(), "{:?}", body);
+    assert_eq!(body["result"]["I32"].as_i64().unwrap(), 1);
+
+    let coverage = &body["coverage"];
+    assert_eq!(coverage["nodes_total"].as_u64().unwrap(), 6);
+    assert_eq!(coverage["nodes_covered"].as_u64().unwrap(), 4);
+    assert_eq!(coverage["arms_total"].as_u64().unwrap(), 2);
+    assert_eq!(coverage["arms_covered"].as_u64().unwrap(), 1);
+    let uncovered: Vec<u64> = coverage["uncovered_nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n.as_u64().unwrap())
+        .collect();
+    assert_eq!(uncovered, vec![else_const as u64, else_ret as u64]);
+
+    let (status, graph) = get_json(&app, &format!("/programs/{}/observability/graph", pid)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(graph["coverage"]["arms_covered"].as_u64().unwrap(), 1);
+    let hits = |id: u32| {
+        graph["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|n| n["id"] == format!("compute:{}", id))
+            .map(|n| n["coverage_hits"].clone())
+            .unwrap()
+    };
+    assert_eq!(hits(then_ret), json!(1));
+    assert_eq!(hits(else_ret), json!(0));
+    let else_edge = graph["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["edge_kind"] == "control" && e["branch_index"] == json!(1))
+        .expect("else control edge");
+    assert_eq!(else_edge["coverage_hits"], json!(0));
+
+    // Editing the graph drops the coverage measured against the old one
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {"type": "InsertNode", "op": {"Core": {"Const": {"value": {"I32": 3}}}}, "owner": func_id}
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+    let (_, graph) = get_json(&app, &format!("/programs/{}/observability/graph", pid)).await;
+    assert!(graph["coverage"].is_null(), "{:?}", graph["coverage"]);
+    assert!(graph["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|n| n["coverage_hits"].is_null()));
+
+    // ...and so does undoing an edit
+    post_json(
+        &app,
+        &format!("/programs/{}/simulate", pid),
+        json!({"function_id": func_id, "inputs": [false]}),
+    )
+    .await;
+    let (status, _) = post_json(&app, &format!("/programs/{}/undo", pid), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, graph) = get_json(&app, &format!("/programs/{}/observability/graph", pid)).await;
+    assert!(graph["coverage"].is_null(), "{:?}", graph["coverage"]);
+}
+
+/// `?profile=true` adds a cost profile to the simulate response, and the
+/// observability graph ranks the nodes profiled runs evaluated.
+#[tokio::test]
+async fn tool04_simulate_profile_ranks_hot_nodes() {
+    let app = test_app();
+    let pid = setup_program(&app).await;
+
+    // pick(flag: bool) -> i32 { if flag { 1 } else { 2 } }
+    let func_id = add_typed_function(&app, pid, "pick", json!([["flag", 0]]), 3).await;
+    let flag = insert_param(&app, pid, func_id, 0).await;
+    let branch = flag + 1;
+    let (then_const, then_ret) = (flag + 2, flag + 3);
+    let (else_const, else_ret) = (flag + 4, flag + 5);
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {"type": "InsertNode", "op": {"Core": "Branch"}, "owner": func_id},
+            {"type": "InsertNode", "op": {"Core": {"Const": {"value": {"I32": 1}}}}, "owner": func_id},
+            {"type": "InsertNode", "op": {"Core": "Return"}, "owner": func_id},
+            {"type": "InsertNode", "op": {"Core": {"Const": {"value": {"I32": 2}}}}, "owner": func_id},
+            {"type": "InsertNode", "op": {"Core": "Return"}, "owner": func_id},
+            {"type": "AddEdge", "from": flag, "to": branch, "source_port": 0, "target_port": 0, "value_type": 0},
+            {"type": "AddControlEdge", "from": branch, "to": then_const, "branch_index": 0},
+            {"type": "AddControlEdge", "from": branch, "to": else_const, "branch_index": 1},
+            {"type": "AddEdge", "from": then_const, "to": then_ret, "source_port": 0, "target_port": 0, "value_type": 3},
+            {"type": "AddEdge", "from": else_const, "to": else_ret, "source_port": 0, "target_port": 0, "value_type": 3}
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+
+    let request = json!({"function_id": func_id, "inputs": [true]});
+    let (status, body) = post_json(
+        &app,
+        &format!("/programs/{}/simulate", pid),
+        request.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.get("profile").is_none(), "{:?}", body);
+    let (_, graph) = get_json(&app, &format!("/programs/{}/observability/graph", pid)).await;
+    assert!(graph.get("hot_nodes").is_none());
+
+    let url = format!("/programs/{}/simulate?profile=true", pid);
+    for _ in 0..2 {
+        let (status, body) = post_json(&app, &url, request.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        let profile = &body["profile"];
+        assert_eq!(profile["total_steps"], 4, "{:?}", profile);
+        assert_eq!(
+            profile["functions"],
+            json!([{
+                "function_id": func_id,
+                "name": "pick",
+                "calls": 1,
+                "inclusive_steps": 4,
+                "exclusive_steps": 4
+            }])
+        );
+        assert_eq!(profile["folded_stacks"], "pick 4\n");
+        assert_eq!(profile["op_kinds"].as_array().unwrap().len(), 4);
+    }
+
+    let (_, graph) = get_json(&app, &format!("/programs/{}/observability/graph", pid)).await;
+    let hot_nodes = graph["hot_nodes"].as_array().unwrap();
+    assert_eq!(hot_nodes.len(), 4);
+    assert!(hot_nodes.iter().all(|n| n["evaluations"] == 2));
+    let rank = |id: u32| {
+        graph["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|n| n["id"] == format!("compute:{}", id))
+            .map(|n| n["hot_rank"].clone())
+            .unwrap()
+    };
+    let mut ranks: Vec<u64> = [flag, branch, then_const, then_ret]
+        .iter()
+        .map(|id| rank(*id).as_u64().unwrap())
+        .collect();
+    ranks.sort_unstable();
+    assert_eq!(ranks, vec![1, 2, 3, 4]);
+    assert!(rank(else_const).is_null());
+    assert!(rank(else_ret).is_null());
+}
+
+// ===========================================================================
+// Interactive debug sessions
+// ===========================================================================
+
+/// Breakpoint in a callee, watches, step out and continue to completion,
+/// then session teardown.
+#[tokio::test]
+async fn debug_session_breakpoints_watches_and_stepping() {
+    let app = test_app();
+    let pid = setup_program(&app).await;
+
+    // inc(x: i32) -> i32 { x + 1 }
+    let inc = add_typed_function(&app, pid, "inc", json!([["x", 3]]), 3).await;
+    let inc_param = insert_param(&app, pid, inc, 0).await;
+    let one = insert_const(&app, pid, inc, json!({"I32": 1})).await;
+    let (inc_add, inc_ret) = (one + 1, one + 2);
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {"type": "InsertNode", "op": {"Core": {"BinaryArith": {"op": "Add"}}}, "owner": inc},
+            {"type": "InsertNode", "op": {"Core": "Return"}, "owner": inc},
+            {"type": "AddEdge", "from": inc_param, "to": inc_add, "source_port": 0, "target_port": 0, "value_type": 3},
+            {"type": "AddEdge", "from": one, "to": inc_add, "source_port": 0, "target_port": 1, "value_type": 3},
+            {"type": "AddEdge", "from": inc_add, "to": inc_ret, "source_port": 0, "target_port": 0, "value_type": 3}
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+
+    // main(x: i32) -> i32 { inc(x) }
+    let main = add_typed_function(&app, pid, "main", json!([["x", 3]]), 3).await;
+    let main_param = insert_param(&app, pid, main, 0).await;
+    let (call, main_ret) = (main_param + 1, main_param + 2);
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {"type": "InsertNode", "op": {"Core": {"Call": {"target": inc}}}, "owner": main},
+            {"type": "InsertNode", "op": {"Core": "Return"}, "owner": main},
+            {"type": "AddEdge", "from": main_param, "to": call, "source_port": 0, "target_port": 0, "value_type": 3},
+            {"type": "AddEdge", "from": call, "to": main_ret, "source_port": 0, "target_port": 0, "value_type": 3}
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+
+    let (status, session) = post_json(
+        &app,
+        &format!("/programs/{}/debug/sessions", pid),
+        json!({
+            "function_id": main,
+            "inputs": [41],
+            "breakpoints": [{"kind": "node", "node_id": inc_add}],
+            "watches": [{"kind": "argument", "index": 0}]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", session);
+    assert_eq!(session["status"], "paused");
+    assert_eq!(session["next_node"], json!(main_param));
+    assert_eq!(session["call_stack"].as_array().unwrap().len(), 1);
+    assert_eq!(session["breakpoints"], json!([inc_add]));
+    let base = format!(
+        "/programs/{}/debug/sessions/{}",
+        pid,
+        session["session_id"].as_str().unwrap()
+    );
+
+    let (status, body) =
+        post_json(&app, &format!("{}/step", base), json!({"mode": "continue"})).await;
+    assert_eq!(status, StatusCode::OK, "{:?}", body);
+    assert_eq!(
+        body["stop"],
+        json!({"reason": "breakpoint", "node_id": inc_add})
+    );
+    let frames = body["call_stack"].as_array().unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[1]["function_name"], "inc");
+    assert_eq!(body["watches"][0]["value"], json!({"I32": 41}));
+
+    let (status, body) = post_json(
+        &app,
+        &format!("{}/evaluate", base),
+        json!({"expressions": [
+            {"kind": "node", "node_id": one},
+            {"kind": "node", "node_id": inc_add}
+        ]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", body);
+    assert_eq!(body["results"][0]["value"], json!({"I32": 1}));
+    assert!(body["results"][1]["error"].is_string());
+
+    let (status, body) = send_json(
+        &app,
+        "PUT",
+        &format!("{}/watches", base),
+        json!({"watches": [{"kind": "node", "node_id": call}]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", body);
+    assert!(body["watches"][0]["error"].is_string());
+
+    let (_, body) = post_json(&app, &format!("{}/step", base), json!({"mode": "out"})).await;
+    assert_eq!(body["stop"]["reason"], "step");
+    assert_eq!(body["call_stack"].as_array().unwrap().len(), 1);
+    assert_eq!(body["next_node"], json!(main_ret));
+    assert_eq!(body["watches"][0]["value"], json!({"I32": 42}));
+
+    let (_, body) = post_json(&app, &format!("{}/step", base), json!({"mode": "continue"})).await;
+    assert_eq!(body["stop"]["reason"], "finished");
+    assert_eq!(body["status"], "completed");
+    assert_eq!(body["result"], json!({"I32": 42}));
+
+    // The result derives from main's argument through inc's Add.
+    let (status, body) = post_json(
+        &app,
+        &format!("{}/lineage", base),
+        json!({"node_id": main_ret}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", body);
+    let nodes: Vec<serde_json::Value> = body["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["node_id"].clone())
+        .collect();
+    for node in [main_param, call, inc_param, one, inc_add, inc_ret, main_ret] {
+        assert!(
+            nodes.contains(&json!(node)),
+            "{} missing from {:?}",
+            node,
+            nodes
+        );
+    }
+    assert_eq!(nodes.last(), Some(&json!(main_ret)));
+
+    // Step back from the finished run, then rewind to where `call` was written.
+    let (_, body) = post_json(&app, &format!("{}/step", base), json!({"mode": "back"})).await;
+    assert_eq!(body["status"], "paused");
+    assert_eq!(body["next_node"], json!(main_ret));
+    let (status, body) = post_json(
+        &app,
+        &format!("{}/rewind", base),
+        json!({"kind": "node", "node_id": call}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", body);
+    assert_eq!(body["next_node"], json!(inc_ret));
+    assert_eq!(body["call_stack"].as_array().unwrap().len(), 2);
+    let (status, _) = post_json(
+        &app,
+        &format!("{}/rewind", base),
+        json!({"kind": "memory", "address": 0}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send_json(&app, "DELETE", &base, json!(null)).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = get_json(&app, &base).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+/// A suspended session survives a server restart and resumes where it
+/// stopped; once the program is edited, resuming is rejected.
+#[tokio::test]
+async fn debug_session_suspends_and_resumes_across_restart() {
+    let db_path = temp_db_path("lmlang_debug_snapshots");
+    let app = test_app_with_db(&db_path);
+    let pid = setup_program(&app).await;
+
+    // inc(x: i32) -> i32 { x + 1 }
+    let inc = add_typed_function(&app, pid, "inc", json!([["x", 3]]), 3).await;
+    let inc_param = insert_param(&app, pid, inc, 0).await;
+    let one = insert_const(&app, pid, inc, json!({"I32": 1})).await;
+    let (inc_add, inc_ret) = (one + 1, one + 2);
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {"type": "InsertNode", "op": {"Core": {"BinaryArith": {"op": "Add"}}}, "owner": inc},
+            {"type": "InsertNode", "op": {"Core": "Return"}, "owner": inc},
+            {"type": "AddEdge", "from": inc_param, "to": inc_add, "source_port": 0, "target_port": 0, "value_type": 3},
+            {"type": "AddEdge", "from": one, "to": inc_add, "source_port": 0, "target_port": 1, "value_type": 3},
+            {"type": "AddEdge", "from": inc_add, "to": inc_ret, "source_port": 0, "target_port": 0, "value_type": 3}
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+
+    let (status, session) = post_json(
+        &app,
+        &format!("/programs/{}/debug/sessions", pid),
+        json!({
+            "function_id": inc,
+            "inputs": [41],
+            "breakpoints": [{"kind": "node", "node_id": inc_add}],
+            "watches": [{"kind": "argument", "index": 0}]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", session);
+    let base = format!(
+        "/programs/{}/debug/sessions/{}",
+        pid,
+        session["session_id"].as_str().unwrap()
+    );
+    let (_, paused) = post_json(&app, &format!("{}/step", base), json!({"mode": "continue"})).await;
+    assert_eq!(paused["next_node"], json!(inc_add));
+
+    let (status, snapshot) = post_json(&app, &format!("{}/suspend", base), json!(null)).await;
+    assert_eq!(status, StatusCode::OK, "{:?}", snapshot);
+    assert_eq!(snapshot["function_id"], json!(inc));
+    let snapshot_id = snapshot["snapshot_id"].as_str().unwrap().to_string();
+    let (status, _) = get_json(&app, &base).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    drop(app);
+
+    let app = test_app_with_db(&db_path);
+    let (status, _) = post_json(&app, &format!("/programs/{}/load", pid), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, listing) = get_json(&app, &format!("/programs/{}/debug/snapshots", pid)).await;
+    assert_eq!(status, StatusCode::OK, "{:?}", listing);
+    assert_eq!(listing["snapshots"][0]["snapshot_id"], json!(snapshot_id));
+    assert_eq!(listing["snapshots"][0]["steps"], snapshot["steps"]);
+
+    let resume = format!("/programs/{}/debug/snapshots/{}/resume", pid, snapshot_id);
+    let (status, resumed) = post_json(&app, &resume, json!(null)).await;
+    assert_eq!(status, StatusCode::OK, "{:?}", resumed);
+    assert_eq!(resumed["next_node"], json!(inc_add));
+    assert_eq!(resumed["stop"], paused["stop"]);
+    assert_eq!(resumed["breakpoints"], json!([inc_add]));
+    assert_eq!(resumed["watches"][0]["value"], json!({"I32": 41}));
+    let (_, body) = post_json(
+        &app,
+        &format!(
+            "/programs/{}/debug/sessions/{}/step",
+            pid,
+            resumed["session_id"].as_str().unwrap()
+        ),
+        json!({"mode": "continue"}),
+    )
+    .await;
+    assert_eq!(body["status"], "completed");
+    assert_eq!(body["result"], json!({"I32": 42}));
+
+    batch_mutate(
+        &app,
+        pid,
+        json!([{"type": "ModifyNode", "node_id": inc_add, "new_op": {"Core": {"BinaryArith": {"op": "Sub"}}}}]),
+    )
+    .await;
+    let (status, body) = post_json(&app, &resume, json!(null)).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{:?}", body);
+    assert_eq!(body["error"]["details"]["changed_functions"], json!([inc]));
+
+    let (status, _) = send_json(
+        &app,
+        "DELETE",
+        &format!("/programs/{}/debug/snapshots/{}", pid, snapshot_id),
+        json!(null),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = post_json(&app, &resume, json!(null)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    drop(app);
+    let _ = std::fs::remove_file(&db_path);
+}
+
+/// Contract breakpoints stop before each contract node of the function.
+#[tokio::test]
+async fn debug_session_breaks_on_contract_nodes() {
+    let app = test_app();
+    let pid = setup_program(&app).await;
+
+    // pos(x: i32) -> i32 with precondition x > 0
+    let func = add_typed_function(&app, pid, "pos", json!([["x", 3]]), 3).await;
+    let param = insert_param(&app, pid, func, 0).await;
+    let zero = insert_const(&app, pid, func, json!({"I32": 0})).await;
+    let (cmp, pre, ret) = (zero + 1, zero + 2, zero + 3);
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {"type": "InsertNode", "op": {"Core": {"Compare": {"op": "Gt"}}}, "owner": func},
+            {"type": "InsertNode", "op": {"Core": {"Precondition": {"message": "x must be positive"}}}, "owner": func},
+            {"type": "InsertNode", "op": {"Core": "Return"}, "owner": func},
+            {"type": "AddEdge", "from": param, "to": cmp, "source_port": 0, "target_port": 0, "value_type": 3},
+            {"type": "AddEdge", "from": zero, "to": cmp, "source_port": 0, "target_port": 1, "value_type": 3},
+            {"type": "AddEdge", "from": cmp, "to": pre, "source_port": 0, "target_port": 0, "value_type": 0},
+            {"type": "AddEdge", "from": param, "to": ret, "source_port": 0, "target_port": 0, "value_type": 3},
+            {"type": "AddControlEdge", "from": pre, "to": ret, "branch_index": null}
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+
+    let (status, session) = post_json(
+        &app,
+        &format!("/programs/{}/debug/sessions", pid),
+        json!({
+            "function_id": func,
+            "inputs": [-1],
+            "breakpoints": [{"kind": "contracts", "function_id": func}]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", session);
+    assert_eq!(session["breakpoints"], json!([pre]));
+    let base = format!(
+        "/programs/{}/debug/sessions/{}",
+        pid,
+        session["session_id"].as_str().unwrap()
+    );
+
+    let (_, body) = post_json(&app, &format!("{}/step", base), json!({"mode": "continue"})).await;
+    assert_eq!(
+        body["stop"],
+        json!({"reason": "breakpoint", "node_id": pre})
+    );
+    let values = &body["call_stack"][0]["node_values"];
+    assert!(values
+        .as_array()
+        .unwrap()
+        .contains(&json!([cmp, {"Bool": false}])));
+
+    let (_, body) = post_json(&app, &format!("{}/step", base), json!({"mode": "into"})).await;
+    assert_eq!(body["status"], "contract_violation");
+    assert_eq!(body["violation"]["contract_node"], json!(pre));
+
+    let (status, _) = send_json(
+        &app,
+        "PUT",
+        &format!("{}/breakpoints", base),
+        json!({"breakpoints": [{"kind": "node", "node_id": 9999}]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
 }
 
 // ===========================================================================
@@ -1292,11 +2155,9 @@ async fn cntr05_property_test_finds_violations() {
     // Should have total_run = 3 seeds + 50 random = 53
     assert_eq!(test_body["total_run"].as_u64().unwrap(), 53);
 
-    // Should find at least the seed(-1) failure
-    assert!(
-        test_body["failed"].as_u64().unwrap() >= 1,
-        "expected at least 1 failure"
-    );
+    // Only the seed(-1) fails: negative random draws are redrawn instead
+    assert_eq!(test_body["failed"].as_u64().unwrap(), 1);
+    assert!(test_body["rejected"].as_u64().unwrap() > 0);
 
     let failures = test_body["failures"].as_array().unwrap();
     assert!(!failures.is_empty(), "should have failure details");
@@ -1309,6 +2170,14 @@ async fn cntr05_property_test_finds_violations() {
         -1,
         "first failure input should be -1"
     );
+    // -1 is already minimal, and every negative input violates the same
+    // precondition, so it is the only reported failure
+    assert_eq!(first_failure["shrunk_inputs"], json!([{"I32": -1}]));
+    assert_eq!(failures.len(), 1, "failures dedupe by contract node");
+    assert_eq!(
+        first_failure["occurrences"].as_u64(),
+        test_body["failed"].as_u64()
+    );
 
     // Violation details
     let violation = &first_failure["violation"];
@@ -1339,66 +2208,237 @@ async fn cntr05_property_test_finds_violations() {
 
     // Random seed should be returned for reproducibility
     assert_eq!(test_body["random_seed"].as_u64().unwrap(), 42);
+    assert_eq!(test_body["unsettled"].as_u64().unwrap(), 0);
+
+    // Runs cut short by a budget are unsettled, neither passed nor failed
+    let (status, test_body) = post_json(
+        &app,
+        &format!("/programs/{}/property-test", pid),
+        json!({
+            "function_id": func_id,
+            "seeds": [[5]],
+            "iterations": 2,
+            "random_seed": 42,
+            "limits": {"max_steps": 1}
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", test_body);
+    assert_eq!(test_body["total_run"].as_u64().unwrap(), 3);
+    assert_eq!(test_body["passed"].as_u64().unwrap(), 0);
+    assert_eq!(test_body["failed"].as_u64().unwrap(), 0);
+    assert_eq!(test_body["unsettled"].as_u64().unwrap(), 3);
+    let unsettled = test_body["unsettled_runs"].as_array().unwrap();
+    assert_eq!(unsettled.len(), 3);
+    assert_eq!(unsettled[0]["inputs"], json!([{"I32": 5}]));
+    assert_eq!(unsettled[0]["kind"], "step_limit_exceeded");
+}
+
+/// Test 15: Property test with all valid inputs reports zero failures.
+#[tokio::test]
+async fn cntr05_property_test_all_pass() {
+    let app = test_app();
+    let pid = setup_program(&app).await;
+
+    // Create simple_fn(a: i32) -> i32 with NO contracts
+    let func_id = add_typed_function(&app, pid, "simple_fn", json!([["a", 3]]), 3).await;
+
+    let param_a = insert_param(&app, pid, func_id, 0).await;
+
+    let ret_id = param_a + 1;
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {
+                "type": "InsertNode",
+                "op": {"Core": "Return"},
+                "owner": func_id
+            },
+            {
+                "type": "AddEdge",
+                "from": param_a, "to": ret_id,
+                "source_port": 0, "target_port": 0,
+                "value_type": 3
+            }
+        ]),
+    )
+    .await;
+    assert!(
+        body["valid"].as_bool().unwrap(),
+        "function build should be valid: {:?}",
+        body
+    );
+
+    let (status, test_body) = post_json(
+        &app,
+        &format!("/programs/{}/property-test", pid),
+        json!({
+            "function_id": func_id,
+            "seeds": [[0], [42], [-1]],
+            "iterations": 20,
+            "random_seed": 99
+        }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::OK,
+        "property test request failed: {:?}",
+        test_body
+    );
+
+    assert_eq!(test_body["total_run"].as_u64().unwrap(), 23);
+    assert_eq!(test_body["passed"].as_u64().unwrap(), 23);
+    assert_eq!(test_body["failed"].as_u64().unwrap(), 0);
+    assert!(test_body["failures"].as_array().unwrap().is_empty());
 }
 
-/// Test 15: Property test with all valid inputs reports zero failures.
+/// Exhaustive mode enumerates a bounded box and returns the smallest counterexample.
 #[tokio::test]
-async fn cntr05_property_test_all_pass() {
+async fn cntr05_property_test_exhaustive_mode() {
     let app = test_app();
     let pid = setup_program(&app).await;
 
-    // Create simple_fn(a: i32) -> i32 with NO contracts
-    let func_id = add_typed_function(&app, pid, "simple_fn", json!([["a", 3]]), 3).await;
-
+    // Create below_three(a: i32) -> i32 with postcondition a < 3
+    let func_id = add_typed_function(&app, pid, "below_three", json!([["a", 3]]), 3).await;
     let param_a = insert_param(&app, pid, func_id, 0).await;
 
-    let ret_id = param_a + 1;
+    let const_three_id = param_a + 1;
+    let cmp_node_id = param_a + 2;
+    let postcond_id = param_a + 3;
+    let ret_id = param_a + 4;
     let body = batch_mutate(
         &app,
         pid,
         json!([
+            {
+                "type": "InsertNode",
+                "op": {"Core": {"Const": {"value": {"I32": 3}}}},
+                "owner": func_id
+            },
+            {
+                "type": "InsertNode",
+                "op": {"Core": {"Compare": {"op": "Lt"}}},
+                "owner": func_id
+            },
+            {
+                "type": "InsertNode",
+                "op": {"Core": {"Postcondition": {"message": "a must be below 3"}}},
+                "owner": func_id
+            },
             {
                 "type": "InsertNode",
                 "op": {"Core": "Return"},
                 "owner": func_id
             },
+            {
+                "type": "AddEdge",
+                "from": param_a, "to": cmp_node_id,
+                "source_port": 0, "target_port": 0,
+                "value_type": 3
+            },
+            {
+                "type": "AddEdge",
+                "from": const_three_id, "to": cmp_node_id,
+                "source_port": 0, "target_port": 1,
+                "value_type": 3
+            },
+            {
+                "type": "AddEdge",
+                "from": cmp_node_id, "to": postcond_id,
+                "source_port": 0, "target_port": 0,
+                "value_type": 0
+            },
             {
                 "type": "AddEdge",
                 "from": param_a, "to": ret_id,
                 "source_port": 0, "target_port": 0,
                 "value_type": 3
+            },
+            {
+                "type": "AddControlEdge",
+                "from": postcond_id, "to": ret_id,
+                "branch_index": null
             }
         ]),
     )
     .await;
     assert!(
-        body["valid"].as_bool().unwrap(),
-        "function build should be valid: {:?}",
+        body["committed"].as_bool().unwrap(),
+        "should commit: {:?}",
         body
     );
 
+    let url = format!("/programs/{}/property-test", pid);
+
+    // An unbounded i32 domain exceeds the default budget
+    let (status, _) = post_json(
+        &app,
+        &url,
+        json!({"function_id": func_id, "mode": "exhaustive"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Random mode still requires an iteration count
+    let (status, _) = post_json(&app, &url, json!({"function_id": func_id})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
     let (status, test_body) = post_json(
         &app,
-        &format!("/programs/{}/property-test", pid),
+        &url,
         json!({
             "function_id": func_id,
-            "seeds": [[0], [42], [-1]],
-            "iterations": 20,
-            "random_seed": 99
+            "mode": "exhaustive",
+            "bounds": [[-2, 5]]
         }),
     )
     .await;
-    assert_eq!(
-        status,
-        StatusCode::OK,
-        "property test request failed: {:?}",
-        test_body
-    );
+    assert_eq!(status, StatusCode::OK, "{:?}", test_body);
+    assert_eq!(test_body["mode"], "exhaustive");
+    assert_eq!(test_body["domain_size"].as_u64().unwrap(), 8);
+    assert_eq!(test_body["proved"], false);
+    assert_eq!(test_body["failed"].as_u64().unwrap(), 1);
+    let failures = test_body["failures"].as_array().unwrap();
+    assert_eq!(failures[0]["inputs"], json!([{"I32": 3}]));
 
-    assert_eq!(test_body["total_run"].as_u64().unwrap(), 23);
-    assert_eq!(test_body["passed"].as_u64().unwrap(), 23);
+    let (status, test_body) = post_json(
+        &app,
+        &url,
+        json!({
+            "function_id": func_id,
+            "mode": "exhaustive",
+            "bounds": [[-2, 2]]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", test_body);
+    assert_eq!(test_body["proved"], true);
+    assert_eq!(test_body["total_run"].as_u64().unwrap(), 5);
+    assert_eq!(test_body["passed"].as_u64().unwrap(), 5);
+    assert_eq!(test_body["unsettled"].as_u64().unwrap(), 0);
+
+    // Runs cut short by a budget leave the domain unproved
+    let (status, test_body) = post_json(
+        &app,
+        &url,
+        json!({
+            "function_id": func_id,
+            "mode": "exhaustive",
+            "bounds": [[-2, 2]],
+            "limits": {"max_steps": 1}
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", test_body);
+    assert_eq!(test_body["proved"], false);
     assert_eq!(test_body["failed"].as_u64().unwrap(), 0);
-    assert!(test_body["failures"].as_array().unwrap().is_empty());
+    assert_eq!(test_body["passed"].as_u64().unwrap(), 0);
+    assert_eq!(test_body["unsettled"].as_u64().unwrap(), 5);
+    let unsettled = test_body["unsettled_runs"].as_array().unwrap();
+    assert_eq!(unsettled[0]["inputs"], json!([{"I32": 0}]));
+    assert_eq!(unsettled[0]["kind"], "step_limit_exceeded");
 }
 
 // ===========================================================================
@@ -3186,7 +4226,7 @@ async fn phase16_successful_repair_keeps_attempt_history_and_completes() {
         .unwrap()
         .iter()
         .any(|row| row["content"]
-            == json!("Execution attempt 2/3 recorded (2 action(s), 2 succeeded).")));
+            == json!("Execution attempt 2/3 recorded (3 action(s), 3 succeeded).")));
 
     let requests_guard = requests.lock().unwrap();
     assert!(
@@ -3552,3 +4592,337 @@ async fn phase17_state_machine_benchmark_runs_generic_pipeline_and_persists_atte
 
     server.abort();
 }
+
+// ===========================================================================
+// Capabilities: declared effects and per-program policies
+// ===========================================================================
+
+/// Forbidden capabilities fail verify and compile; undeclared effects are
+/// reported once a function declares its effect set.
+#[tokio::test]
+async fn capabilities_policy_and_declared_effects() {
+    let app = test_app();
+    let pid = setup_program(&app).await;
+    let func_id = add_function(&app, pid, "greet").await;
+    let value = insert_const(&app, pid, func_id, json!({"I32": 7})).await;
+
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {
+                "type": "InsertNode",
+                "op": {"Core": "Print"},
+                "owner": func_id
+            },
+            {
+                "type": "AddEdge",
+                "from": value, "to": value + 1,
+                "source_port": 0, "target_port": 0,
+                "value_type": 3
+            }
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+
+    // Forbid console output for this program.
+    let (status, policy) = post_json(
+        &app,
+        &format!("/programs/{}/capabilities", pid),
+        json!({ "forbidden": ["console"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", policy);
+    assert_eq!(policy["forbidden"], json!(["console"]));
+    assert_eq!(policy["errors"][0]["code"], "FORBIDDEN_CAPABILITY");
+
+    let (status, verify) = post_json(
+        &app,
+        &format!("/programs/{}/verify", pid),
+        json!({ "scope": "full" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!verify["valid"].as_bool().unwrap());
+    assert_eq!(verify["errors"][0]["code"], "FORBIDDEN_CAPABILITY");
+    assert_eq!(
+        verify["errors"][0]["details"]["target_node"],
+        json!(value + 1)
+    );
+
+    let (status, compile) = post_json(
+        &app,
+        &format!("/programs/{}/compile", pid),
+        json!({ "entry_function": "greet" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{:?}", compile);
+
+    // Reset to the server default (nothing forbidden).
+    let (status, policy) = post_json(
+        &app,
+        &format!("/programs/{}/capabilities", pid),
+        json!({ "forbidden": null }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(policy["forbidden"], json!([]));
+    assert!(policy["errors"].as_array().unwrap().is_empty());
+
+    // Declaring the function pure makes its Print an undeclared effect.
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([{ "type": "SetFunctionEffects", "function_id": func_id, "effects": [] }]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+
+    let (_, verify) = post_json(
+        &app,
+        &format!("/programs/{}/verify", pid),
+        json!({ "scope": "full" }),
+    )
+    .await;
+    assert!(!verify["valid"].as_bool().unwrap());
+    assert_eq!(verify["errors"][0]["code"], "UNDECLARED_CAPABILITY");
+
+    let (status, policy) = get_json(&app, &format!("/programs/{}/capabilities", pid)).await;
+    assert_eq!(status, StatusCode::OK);
+    let function = policy["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["name"] == "greet")
+        .unwrap();
+    assert_eq!(function["declared"], json!([]));
+    assert_eq!(function["inferred"], json!(["console"]));
+}
+
+/// A program's capability policy survives a server restart.
+#[tokio::test]
+async fn capability_policy_persists_across_restart() {
+    let db_path = temp_db_path("lmlang_capability_policy");
+    let app = test_app_with_db(&db_path);
+    let pid = setup_program(&app).await;
+
+    let (status, policy) = post_json(
+        &app,
+        &format!("/programs/{}/capabilities", pid),
+        json!({ "forbidden": ["fs-write", "random"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{:?}", policy);
+    drop(app);
+
+    let app = test_app_with_db(&db_path);
+    let (status, _) = post_json(&app, &format!("/programs/{}/load", pid), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, policy) = get_json(&app, &format!("/programs/{}/capabilities", pid)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(policy["forbidden"], json!(["fs-write", "random"]));
+
+    // Resetting to the default is persisted too.
+    let (status, _) = post_json(
+        &app,
+        &format!("/programs/{}/capabilities", pid),
+        json!({ "forbidden": null }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    drop(app);
+
+    let app = test_app_with_db(&db_path);
+    let (status, _) = post_json(&app, &format!("/programs/{}/load", pid), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, policy) = get_json(&app, &format!("/programs/{}/capabilities", pid)).await;
+    assert_eq!(policy["forbidden"], json!([]));
+    let _ = std::fs::remove_file(&db_path);
+}
+
+// ===========================================================================
+// Test node suites
+// ===========================================================================
+
+/// Test nodes added by mutation run their cases through `/test`, report the
+/// failing ones, and removal is undoable.
+#[tokio::test]
+async fn test_suites_run_cases_and_removal_undoes() {
+    let app = test_app();
+    let pid = setup_program(&app).await;
+
+    let func_id = add_typed_function(&app, pid, "add", json!([["a", 3], ["b", 3]]), 3).await;
+    let param_a = insert_param(&app, pid, func_id, 0).await;
+    let param_b = insert_param(&app, pid, func_id, 1).await;
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            { "type": "InsertNode", "op": {"Core": {"BinaryArith": {"op": "Add"}}}, "owner": func_id },
+            { "type": "InsertNode", "op": {"Core": "Return"}, "owner": func_id },
+            { "type": "AddEdge", "from": param_a, "to": param_b + 1,
+              "source_port": 0, "target_port": 0, "value_type": 3 },
+            { "type": "AddEdge", "from": param_b, "to": param_b + 1,
+              "source_port": 0, "target_port": 1, "value_type": 3 },
+            { "type": "AddEdge", "from": param_b + 1, "to": param_b + 2,
+              "source_port": 0, "target_port": 0, "value_type": 3 }
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            {
+                "type": "AddTest",
+                "module": 0,
+                "test_id": "T-ADD",
+                "title": "addition",
+                "target_function": func_id,
+                "cases": [
+                    { "name": "small", "inputs": [{"I32": 3}, {"I32": 5}],
+                      "expect": { "kind": "returns", "value": {"I32": 8} } },
+                    { "name": "overflow", "inputs": [{"I32": 2147483647}, {"I32": 1}],
+                      "expect": { "kind": "traps", "trap": "integer_overflow" } }
+                ]
+            },
+            {
+                "type": "add_test",
+                "module": 0,
+                "test_id": "T-WRONG",
+                "title": "wrong expectation",
+                "target_function": func_id,
+                "cases": [
+                    { "inputs": [{"I32": 1}, {"I32": 1}],
+                      "expect": { "kind": "returns", "value": {"I32": 3} } }
+                ]
+            }
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+    assert_eq!(body["created"][0]["type"], "Test");
+    let wrong_idx = body["created"][1]["semantic_node_id"].as_u64().unwrap();
+
+    let (status, report) = post_json(&app, &format!("/programs/{}/test", pid), json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{:?}", report);
+    assert_eq!(report["passed"], 2);
+    assert_eq!(report["failed"], 1);
+    let wrong = &report["tests"][1]["cases"][0];
+    assert_eq!(wrong["name"], "case 0");
+    assert_eq!(
+        wrong["actual"],
+        json!({ "kind": "returned", "value": {"I32": 2} })
+    );
+
+    let (_, report) = post_json(
+        &app,
+        &format!("/programs/{}/test", pid),
+        json!({ "test_ids": ["T-ADD"] }),
+    )
+    .await;
+    assert_eq!(report["tests"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        (report["passed"].as_u64(), report["failed"].as_u64()),
+        (Some(2), Some(0))
+    );
+
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([{ "type": "RemoveTest", "semantic_node_id": wrong_idx }]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+    let (_, report) = post_json(&app, &format!("/programs/{}/test", pid), json!({})).await;
+    assert_eq!(report["failed"], 0);
+
+    let (status, _) = post_json(&app, &format!("/programs/{}/undo", pid), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, report) = post_json(&app, &format!("/programs/{}/test", pid), json!({})).await;
+    assert_eq!(report["failed"], 1, "{:?}", report);
+    assert_eq!(
+        report["tests"][1]["semantic_node_id"].as_u64(),
+        Some(wrong_idx)
+    );
+}
+
+/// `/mutation-test` swaps operators in the target function and reports the
+/// mutants the test cases kill.
+#[tokio::test]
+async fn mutation_test_scores_test_cases() {
+    let app = test_app();
+    let pid = setup_program(&app).await;
+
+    let func_id = add_typed_function(&app, pid, "add", json!([["a", 3], ["b", 3]]), 3).await;
+    let param_a = insert_param(&app, pid, func_id, 0).await;
+    let param_b = insert_param(&app, pid, func_id, 1).await;
+    let add_id = param_b + 1;
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([
+            { "type": "InsertNode", "op": {"Core": {"BinaryArith": {"op": "Add"}}}, "owner": func_id },
+            { "type": "InsertNode", "op": {"Core": "Return"}, "owner": func_id },
+            { "type": "AddEdge", "from": param_a, "to": add_id,
+              "source_port": 0, "target_port": 0, "value_type": 3 },
+            { "type": "AddEdge", "from": param_b, "to": add_id,
+              "source_port": 0, "target_port": 1, "value_type": 3 },
+            { "type": "AddEdge", "from": add_id, "to": add_id + 1,
+              "source_port": 0, "target_port": 0, "value_type": 3 }
+        ]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+
+    // No contracts and no tests: the mutant survives
+    let url = format!("/programs/{}/mutation-test", pid);
+    let request = json!({ "function_id": func_id, "iterations": 0 });
+    let (status, report) = post_json(&app, &url, request.clone()).await;
+    assert_eq!(status, StatusCode::OK, "{:?}", report);
+    assert_eq!(report["total"], 1);
+    assert_eq!(report["survived"], 1);
+    assert_eq!(report["score"], 0.0);
+    let survivor = &report["survivors"][0];
+    assert_eq!(survivor["node_id"], add_id);
+    assert_eq!(
+        survivor["mutation"],
+        json!({ "kind": "arith_swap", "from": "Add", "to": "Sub" })
+    );
+    assert_eq!(
+        survivor["description"],
+        format!("node {}: Add -> Sub", add_id)
+    );
+
+    let body = batch_mutate(
+        &app,
+        pid,
+        json!([{
+            "type": "AddTest",
+            "module": 0,
+            "test_id": "T-ADD",
+            "title": "addition",
+            "target_function": func_id,
+            "cases": [
+                { "name": "small", "inputs": [{"I32": 3}, {"I32": 5}],
+                  "expect": { "kind": "returns", "value": {"I32": 8} } }
+            ]
+        }]),
+    )
+    .await;
+    assert!(body["committed"].as_bool().unwrap(), "{:?}", body);
+
+    let (_, report) = post_json(&app, &url, request).await;
+    assert_eq!(report["killed"], 1, "{:?}", report);
+    assert_eq!(report["score"], 1.0);
+    assert_eq!(
+        report["killed_mutants"][0]["killed_by"],
+        json!(["test 'T-ADD' small"])
+    );
+
+    let (status, _) = post_json(&app, &url, json!({ "function_id": 999 })).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -165,19 +165,16 @@ pub fn hash_function(graph: &ProgramGraph, func_id: FunctionId) -> blake3::Hash
 ///
 /// Used for incremental compilation dirty detection.
 /// Contract changes do NOT mark functions dirty (contracts are dev-only).
-/// Same logic as [`hash_function`] but filters out nodes where `op.is_contract()`.
+/// Same logic as [`hash_function`] but filters out the nodes the compiler
+/// skips: contract checks and everything fed by contract expressions (see
+/// [`ProgramGraph::contract_only_nodes`]).
 pub fn hash_function_for_compilation(graph: &ProgramGraph, func_id: FunctionId) -> blake3::Hash {
     // Get all non-contract nodes owned by this function, sorted by NodeId
+    let contract_only = graph.contract_only_nodes(func_id);
     let mut func_nodes: Vec<NodeId> = graph
         .function_nodes(func_id)
         .into_iter()
-        .filter(|node_id| {
-            if let Some(node) = graph.get_compute_node(*node_id) {
-                !node.op.is_contract()
-            } else {
-                true
-            }
-        })
+        .filter(|node_id| !contract_only.contains(node_id))
         .collect();
     func_nodes.sort_by_key(|n| n.0);
 
@@ -204,10 +201,8 @@ pub fn hash_function_for_compilation(graph: &ProgramGraph, func_id: FunctionId)
             let target_id = NodeId::from(edge_ref.target());
 
             // Skip edges to contract nodes
-            if let Some(target_node) = graph.get_compute_node(target_id) {
-                if target_node.op.is_contract() {
-                    continue;
-                }
+            if contract_only.contains(&target_id) {
+                continue;
             }
 
             let edge = edge_ref.weight().clone();
@@ -479,7 +474,7 @@ mod tests {
         let full_hash_before = hash_function(&graph, func_id);
 
         // Add a precondition (contract node)
-        let _precond = graph
+        let precond = graph
             .add_core_op(
                 ComputeOp::Precondition {
                     message: "x > 0".into(),
@@ -505,6 +500,29 @@ mod tests {
             full_hash_before, full_hash_after,
             "Full hash must change when any node is added"
         );
+
+        // A condition built on a contract expression is contract-only too
+        let old = graph
+            .add_core_op(ComputeOp::Old { index: 0 }, func_id)
+            .unwrap();
+        let cmp = graph
+            .add_core_op(
+                ComputeOp::Compare {
+                    op: lmlang_core::ops::CmpOp::Eq,
+                },
+                func_id,
+            )
+            .unwrap();
+        graph.add_data_edge(param, cmp, 0, 0, TypeId::I32).unwrap();
+        graph.add_data_edge(old, cmp, 0, 1, TypeId::I32).unwrap();
+        graph
+            .add_data_edge(cmp, precond, 0, 0, TypeId::BOOL)
+            .unwrap();
+        assert_eq!(
+            hash_before,
+            hash_function_for_compilation(&graph, func_id),
+            "Compilation hash must ignore nodes fed by contract expressions"
+        );
     }
 
     #[test]
@@ -0,0 +1,5 @@
+-- Declared capability sets for functions.
+-- JSON `null` means the function declares no effects (unchecked);
+-- `[]` declares it pure.
+
+ALTER TABLE functions ADD COLUMN effects_json TEXT NOT NULL DEFAULT 'null';
@@ -0,0 +1,17 @@
+-- Suspended debug sessions.
+-- Each row holds a serialized interpreter snapshot bound to the hashes of
+-- the program's functions when it was taken, plus the session's debugger
+-- state, so the run can be resumed by a later server process.
+
+CREATE TABLE IF NOT EXISTS interpreter_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    program_id INTEGER NOT NULL REFERENCES programs(id),
+    snapshot_id TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    function_id INTEGER NOT NULL,
+    steps INTEGER NOT NULL,
+    snapshot_json TEXT NOT NULL,
+    UNIQUE(program_id, snapshot_id)
+);
+
+CREATE INDEX idx_interpreter_snapshots_program ON interpreter_snapshots(program_id, id);
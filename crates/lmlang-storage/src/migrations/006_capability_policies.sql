@@ -0,0 +1,4 @@
+-- Per-program forbidden capability sets.
+-- JSON `null` means the program uses the server's default policy.
+
+ALTER TABLE programs ADD COLUMN forbidden_capabilities_json TEXT NOT NULL DEFAULT 'null';
@@ -15,6 +15,9 @@ fn migrations() -> Migrations<'static> {
         M::up(include_str!("migrations/001_initial_schema.sql")),
         M::up(include_str!("migrations/002_edit_history.sql")),
         M::up(include_str!("migrations/003_agent_config_store.sql")),
+        M::up(include_str!("migrations/004_function_effects.sql")),
+        M::up(include_str!("migrations/005_interpreter_snapshots.sql")),
+        M::up(include_str!("migrations/006_capability_policies.sql")),
     ])
 }
 
@@ -9,6 +9,7 @@ use std::collections::HashMap;
 use petgraph::graph::NodeIndex;
 use rusqlite::{params, Connection, OptionalExtension};
 
+use lmlang_core::capability::EffectSet;
 use lmlang_core::edge::{FlowEdge, SemanticEdge};
 use lmlang_core::function::{Capture, FunctionDef};
 use lmlang_core::graph::ProgramGraph;
@@ -190,13 +191,14 @@ impl SqliteStore {
         // Insert functions
         {
             let mut stmt = tx.prepare_cached(
-                "INSERT INTO functions (program_id, function_id, name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                "INSERT INTO functions (program_id, function_id, name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json, effects_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             )?;
             for (func_id, func) in &decomposed.functions {
                 let params_json = serde_json::to_string(&func.params)?;
                 let entry_node_id: Option<u32> = func.entry_node.map(|n| n.0);
                 let parent_fn: Option<u32> = func.parent_function.map(|f| f.0);
                 let captures_json = serde_json::to_string(&func.captures)?;
+                let effects_json = serde_json::to_string(&func.effects)?;
                 stmt.execute(params![
                     program_id,
                     func_id.0,
@@ -209,6 +211,7 @@ impl SqliteStore {
                     func.is_closure as i32,
                     parent_fn,
                     captures_json,
+                    effects_json,
                 ])?;
             }
         }
@@ -341,7 +344,7 @@ impl SqliteStore {
         // Load functions
         let functions: Vec<(FunctionId, FunctionDef)> = {
             let mut stmt = self.conn.prepare_cached(
-                "SELECT function_id, name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json FROM functions WHERE program_id = ?1 ORDER BY function_id",
+                "SELECT function_id, name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json, effects_json FROM functions WHERE program_id = ?1 ORDER BY function_id",
             )?;
             let rows = stmt.query_map(params![program_id], |row| {
                 let function_id: u32 = row.get(0)?;
@@ -354,6 +357,7 @@ impl SqliteStore {
                 let is_closure: i32 = row.get(7)?;
                 let parent_function: Option<u32> = row.get(8)?;
                 let captures_json: String = row.get(9)?;
+                let effects_json: String = row.get(10)?;
                 Ok((
                     function_id,
                     name,
@@ -365,6 +369,7 @@ impl SqliteStore {
                     is_closure,
                     parent_function,
                     captures_json,
+                    effects_json,
                 ))
             })?;
             let mut result = Vec::new();
@@ -380,9 +385,11 @@ impl SqliteStore {
                     is_closure,
                     parent_function,
                     captures_json,
+                    effects_json,
                 ) = row?;
                 let params: Vec<(String, TypeId)> = serde_json::from_str(&params_json)?;
                 let captures: Vec<Capture> = serde_json::from_str(&captures_json)?;
+                let effects: Option<EffectSet> = serde_json::from_str(&effects_json)?;
                 let func_def = FunctionDef {
                     id: FunctionId(function_id),
                     name,
@@ -394,6 +401,7 @@ impl SqliteStore {
                     captures,
                     is_closure: is_closure != 0,
                     parent_function: parent_function.map(FunctionId),
+                    effects,
                 };
                 result.push((FunctionId(function_id), func_def));
             }
@@ -780,10 +788,11 @@ impl GraphStore for SqliteStore {
         if let Some(func_def) = graph.get_function(func_id) {
             let params_json = serde_json::to_string(&func_def.params)?;
             let captures_json = serde_json::to_string(&func_def.captures)?;
+            let effects_json = serde_json::to_string(&func_def.effects)?;
             let entry_node_id: Option<u32> = func_def.entry_node.map(|n| n.0);
             let parent_fn: Option<u32> = func_def.parent_function.map(|f| f.0);
             tx.execute(
-                "INSERT INTO functions (program_id, function_id, name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                "INSERT INTO functions (program_id, function_id, name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json, effects_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
                 params![
                     id.0,
                     func_id.0,
@@ -796,6 +805,7 @@ impl GraphStore for SqliteStore {
                     func_def.is_closure as i32,
                     parent_fn,
                     captures_json,
+                    effects_json,
                 ],
             )?;
         }
@@ -1015,10 +1025,11 @@ impl GraphStore for SqliteStore {
         let tx = self.conn.transaction()?;
         let params_json = serde_json::to_string(&func.params)?;
         let captures_json = serde_json::to_string(&func.captures)?;
+        let effects_json = serde_json::to_string(&func.effects)?;
         let entry_node_id: Option<u32> = func.entry_node.map(|n| n.0);
         let parent_fn: Option<u32> = func.parent_function.map(|f| f.0);
         tx.execute(
-            "INSERT INTO functions (program_id, function_id, name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            "INSERT INTO functions (program_id, function_id, name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json, effects_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             params![
                 program.0,
                 func_id.0,
@@ -1031,6 +1042,7 @@ impl GraphStore for SqliteStore {
                 func.is_closure as i32,
                 parent_fn,
                 captures_json,
+                effects_json,
             ],
         )?;
         tx.commit()?;
@@ -1045,7 +1057,7 @@ impl GraphStore for SqliteStore {
         let row = self
             .conn
             .query_row(
-                "SELECT name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json FROM functions WHERE program_id = ?1 AND function_id = ?2",
+                "SELECT name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json, effects_json FROM functions WHERE program_id = ?1 AND function_id = ?2",
                 params![program.0, func_id.0],
                 |row| {
                     let name: String = row.get(0)?;
@@ -1057,7 +1069,8 @@ impl GraphStore for SqliteStore {
                     let is_closure: i32 = row.get(6)?;
                     let parent_function: Option<u32> = row.get(7)?;
                     let captures_json: String = row.get(8)?;
-                    Ok((name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json))
+                    let effects_json: String = row.get(9)?;
+                    Ok((name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json, effects_json))
                 },
             )
             .optional()?;
@@ -1073,9 +1086,11 @@ impl GraphStore for SqliteStore {
                 is_closure,
                 parent_function,
                 captures_json,
+                effects_json,
             )) => {
                 let params: Vec<(String, TypeId)> = serde_json::from_str(&params_json)?;
                 let captures: Vec<Capture> = serde_json::from_str(&captures_json)?;
+                let effects: Option<EffectSet> = serde_json::from_str(&effects_json)?;
                 Ok(FunctionDef {
                     id: func_id,
                     name,
@@ -1087,6 +1102,7 @@ impl GraphStore for SqliteStore {
                     captures,
                     is_closure: is_closure != 0,
                     parent_function: parent_function.map(FunctionId),
+                    effects,
                 })
             }
             None => Err(StorageError::FunctionNotFound {
@@ -1105,10 +1121,11 @@ impl GraphStore for SqliteStore {
         let tx = self.conn.transaction()?;
         let params_json = serde_json::to_string(&func.params)?;
         let captures_json = serde_json::to_string(&func.captures)?;
+        let effects_json = serde_json::to_string(&func.effects)?;
         let entry_node_id: Option<u32> = func.entry_node.map(|n| n.0);
         let parent_fn: Option<u32> = func.parent_function.map(|f| f.0);
         let rows = tx.execute(
-            "UPDATE functions SET name = ?3, module_id = ?4, visibility = ?5, params_json = ?6, return_type_id = ?7, entry_node_id = ?8, is_closure = ?9, parent_function = ?10, captures_json = ?11 WHERE program_id = ?1 AND function_id = ?2",
+            "UPDATE functions SET name = ?3, module_id = ?4, visibility = ?5, params_json = ?6, return_type_id = ?7, entry_node_id = ?8, is_closure = ?9, parent_function = ?10, captures_json = ?11, effects_json = ?12 WHERE program_id = ?1 AND function_id = ?2",
             params![
                 program.0,
                 func_id.0,
@@ -1121,6 +1138,7 @@ impl GraphStore for SqliteStore {
                 func.is_closure as i32,
                 parent_fn,
                 captures_json,
+                effects_json,
             ],
         )?;
         tx.commit()?;
@@ -1367,7 +1385,7 @@ impl GraphStore for SqliteStore {
         module: ModuleId,
     ) -> Result<Vec<(FunctionId, FunctionDef)>, StorageError> {
         let mut stmt = self.conn.prepare_cached(
-            "SELECT function_id, name, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json FROM functions WHERE program_id = ?1 AND module_id = ?2 ORDER BY function_id",
+            "SELECT function_id, name, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json, effects_json FROM functions WHERE program_id = ?1 AND module_id = ?2 ORDER BY function_id",
         )?;
         let rows = stmt.query_map(params![program.0, module.0], |row| {
             let function_id: u32 = row.get(0)?;
@@ -1379,6 +1397,7 @@ impl GraphStore for SqliteStore {
             let is_closure: i32 = row.get(6)?;
             let parent_function: Option<u32> = row.get(7)?;
             let captures_json: String = row.get(8)?;
+            let effects_json: String = row.get(9)?;
             Ok((
                 function_id,
                 name,
@@ -1389,6 +1408,7 @@ impl GraphStore for SqliteStore {
                 is_closure,
                 parent_function,
                 captures_json,
+                effects_json,
             ))
         })?;
         let mut result = Vec::new();
@@ -1403,9 +1423,11 @@ impl GraphStore for SqliteStore {
                 is_closure,
                 parent_function,
                 captures_json,
+                effects_json,
             ) = row?;
             let params: Vec<(String, TypeId)> = serde_json::from_str(&params_json)?;
             let captures: Vec<Capture> = serde_json::from_str(&captures_json)?;
+            let effects: Option<EffectSet> = serde_json::from_str(&effects_json)?;
             result.push((
                 FunctionId(function_id),
                 FunctionDef {
@@ -1419,6 +1441,7 @@ impl GraphStore for SqliteStore {
                     captures,
                     is_closure: is_closure != 0,
                     parent_function: parent_function.map(FunctionId),
+                    effects,
                 },
             ));
         }
@@ -1494,7 +1517,7 @@ impl GraphStore for SqliteStore {
         program: ProgramId,
     ) -> Result<Vec<(FunctionId, FunctionDef)>, StorageError> {
         let mut stmt = self.conn.prepare_cached(
-            "SELECT function_id, name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json FROM functions WHERE program_id = ?1 ORDER BY function_id",
+            "SELECT function_id, name, module_id, visibility, params_json, return_type_id, entry_node_id, is_closure, parent_function, captures_json, effects_json FROM functions WHERE program_id = ?1 ORDER BY function_id",
         )?;
         let rows = stmt.query_map(params![program.0], |row| {
             let function_id: u32 = row.get(0)?;
@@ -1507,6 +1530,7 @@ impl GraphStore for SqliteStore {
             let is_closure: i32 = row.get(7)?;
             let parent_function: Option<u32> = row.get(8)?;
             let captures_json: String = row.get(9)?;
+            let effects_json: String = row.get(10)?;
             Ok((
                 function_id,
                 name,
@@ -1518,6 +1542,7 @@ impl GraphStore for SqliteStore {
                 is_closure,
                 parent_function,
                 captures_json,
+                effects_json,
             ))
         })?;
         let mut result = Vec::new();
@@ -1533,9 +1558,11 @@ impl GraphStore for SqliteStore {
                 is_closure,
                 parent_function,
                 captures_json,
+                effects_json,
             ) = row?;
             let params: Vec<(String, TypeId)> = serde_json::from_str(&params_json)?;
             let captures: Vec<Capture> = serde_json::from_str(&captures_json)?;
+            let effects: Option<EffectSet> = serde_json::from_str(&effects_json)?;
             result.push((
                 FunctionId(function_id),
                 FunctionDef {
@@ -1549,6 +1576,7 @@ impl GraphStore for SqliteStore {
                     captures,
                     is_closure: is_closure != 0,
                     parent_function: parent_function.map(FunctionId),
+                    effects,
                 },
             ));
         }
@@ -1774,6 +1802,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_function_effects_roundtrip() {
+        use lmlang_core::capability::Capability;
+
+        let mut store = SqliteStore::in_memory().unwrap();
+        let mut graph = build_full_program();
+        graph.get_function_mut(FunctionId(0)).unwrap().effects = Some(EffectSet::new());
+        graph.get_function_mut(FunctionId(1)).unwrap().effects =
+            Some([Capability::Console, Capability::FsWrite].into());
+
+        let id = store.create_program("effects").unwrap();
+        store.save_program(id, &graph).unwrap();
+        let loaded = store.load_program(id).unwrap();
+
+        assert!(loaded.get_function(FunctionId(0)).unwrap().is_pure());
+        assert_eq!(
+            loaded.get_function(FunctionId(1)).unwrap().effects,
+            Some([Capability::Console, Capability::FsWrite].into())
+        );
+        assert_eq!(loaded.get_function(FunctionId(2)).unwrap().effects, None);
+    }
+
     #[test]
     fn test_delete_program() {
         let mut store = SqliteStore::in_memory().unwrap();